@@ -1,18 +1,22 @@
-//! Cat printer control library supporting GB and MXW01 series.
+//! Cat printer control library supporting GB and MXW01 series, plus
+//! ESC/POS receipt printers.
 //!
 //! Provides BLE connection management, printer protocol implementation,
-//! and KeepAlive functionality for thermal printers.
+//! byte transports (BLE, USB/serial device files), and KeepAlive
+//! functionality for thermal printers.
 
 pub mod ble;
 pub mod keepalive;
 pub mod options;
 pub mod protocol;
+pub mod transport;
 
 // Re-exports for convenience
 pub use ble::BleConnection;
 pub use keepalive::KeepAliveManager;
 pub use options::PrinterOptions;
 pub use protocol::PrinterProtocol;
+pub use transport::PrinterTransport;
 
 /// Print width in pixels (standard for GB/MXW01 series thermal printers).
 pub const PRINT_WIDTH: u16 = 384;
@@ -55,6 +59,9 @@ pub enum CatPrinterError {
 
     #[error("Protocol error: {0}")]
     Protocol(String),
+
+    #[error("Transport error: {0}")]
+    Transport(String),
 }
 
 /// Result type alias for catprinter operations.
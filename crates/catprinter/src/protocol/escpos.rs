@@ -0,0 +1,151 @@
+//! ESC/POS thermal printer protocol implementation.
+//!
+//! Used by receipt printers connected over USB or serial rather than BLE.
+//! Rows are sent as `GS v 0` raster images one dot line high, so the same
+//! row-by-row pipeline as the cat printers applies. The BLE UUIDs are nil
+//! because these printers are reached through a device transport.
+
+use super::PrinterProtocol;
+
+const ESC: u8 = 0x1b;
+const GS: u8 = 0x1d;
+
+/// Lines fed before cutting so the last row clears the cutter.
+const FEED_BEFORE_CUT: u8 = 4;
+
+/// Pack 8 pixels into one byte (MSB first, ESC/POS raster convention).
+fn byte_encode_msb(row: &[u8]) -> Vec<u8> {
+    row.chunks(8)
+        .map(|chunk| {
+            chunk.iter().enumerate().fold(
+                0u8,
+                |acc, (i, &px)| if px != 0 { acc | (1 << (7 - i)) } else { acc },
+            )
+        })
+        .collect()
+}
+
+/// Build a `GS v 0` raster command for one dot line of packed bytes.
+fn build_raster_line(packed: &[u8]) -> Vec<u8> {
+    let width_bytes = packed.len() as u16;
+    let mut buf = Vec::with_capacity(8 + packed.len());
+    buf.extend_from_slice(&[GS, b'v', b'0', 0x00]);
+    buf.push((width_bytes & 0xff) as u8);
+    buf.push((width_bytes >> 8) as u8);
+    buf.extend_from_slice(&[0x01, 0x00]); // one line high
+    buf.extend_from_slice(packed);
+    buf
+}
+
+/// ESC/POS protocol implementation.
+#[derive(Debug, Clone)]
+pub struct EscPosProtocol {
+    /// Send a partial cut after each print.
+    cut: bool,
+}
+
+impl Default for EscPosProtocol {
+    fn default() -> Self {
+        Self { cut: true }
+    }
+}
+
+impl EscPosProtocol {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: set whether the paper is cut after printing.
+    pub fn with_cut(mut self, cut: bool) -> Self {
+        self.cut = cut;
+        self
+    }
+}
+
+impl PrinterProtocol for EscPosProtocol {
+    fn name(&self) -> &str {
+        "ESC/POS"
+    }
+
+    fn service_uuid(&self) -> uuid::Uuid {
+        uuid::Uuid::nil()
+    }
+
+    fn tx_characteristic(&self) -> uuid::Uuid {
+        uuid::Uuid::nil()
+    }
+
+    fn build_init_sequence(&self) -> Vec<Vec<u8>> {
+        vec![
+            vec![ESC, b'@'],       // initialize
+            vec![ESC, b'3', 0x00], // zero line spacing so raster rows touch
+        ]
+    }
+
+    fn build_print_command(&self, row_data: &[u8]) -> Vec<u8> {
+        build_raster_line(row_data)
+    }
+
+    fn build_feed_command(&self, lines: u16) -> Vec<u8> {
+        vec![ESC, b'd', lines.min(255) as u8]
+    }
+
+    fn build_finish_sequence(&self) -> Vec<Vec<u8>> {
+        let mut seq = vec![vec![ESC, b'2']]; // restore default line spacing
+        if self.cut {
+            seq.push(vec![GS, b'V', 0x42, FEED_BEFORE_CUT]); // feed and partial cut
+        }
+        seq
+    }
+
+    fn encode_row(&self, row: &[u8], _width: u16) -> Vec<u8> {
+        build_raster_line(&byte_encode_msb(row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_row_raster_header() {
+        let proto = EscPosProtocol::new();
+        let mut row = vec![0u8; 16];
+        row[0] = 1;
+        row[15] = 1;
+        let cmd = proto.encode_row(&row, 16);
+        assert_eq!(&cmd[..4], &[GS, b'v', b'0', 0x00]);
+        assert_eq!(&cmd[4..8], &[2, 0, 1, 0]); // 2 bytes wide, 1 line
+        assert_eq!(&cmd[8..], &[0x80, 0x01]);
+    }
+
+    #[test]
+    fn test_encode_row_pads_partial_byte() {
+        let proto = EscPosProtocol::new();
+        let cmd = proto.encode_row(&[1, 1, 1], 3);
+        assert_eq!(cmd[4], 1);
+        assert_eq!(cmd[8], 0xe0);
+    }
+
+    #[test]
+    fn test_feed_command_clamps() {
+        let proto = EscPosProtocol::new();
+        assert_eq!(proto.build_feed_command(4), vec![ESC, b'd', 4]);
+        assert_eq!(proto.build_feed_command(1000), vec![ESC, b'd', 255]);
+    }
+
+    #[test]
+    fn test_finish_sequence_cut() {
+        let with_cut = EscPosProtocol::new().build_finish_sequence();
+        assert_eq!(with_cut.last().unwrap()[..2], [GS, b'V']);
+        let without_cut = EscPosProtocol::new()
+            .with_cut(false)
+            .build_finish_sequence();
+        assert!(without_cut.iter().all(|cmd| cmd[0] != GS));
+    }
+
+    #[test]
+    fn test_escpos_protocol_name() {
+        assert_eq!(EscPosProtocol::new().name(), "ESC/POS");
+    }
+}
@@ -1,19 +1,23 @@
 //! Printer protocol definitions.
 //!
-//! Supports two protocol variants:
+//! Supports three protocol variants:
 //! - GB series (magic bytes: 0x51, 0x78) - most common cat printers
 //! - MXW01 series (magic bytes: 0x22, 0x21) - newer model variant
+//! - ESC/POS - USB/serial receipt printers
 
+pub mod escpos;
 pub mod gb;
 pub mod mxw01;
 
+pub use escpos::EscPosProtocol;
 pub use gb::GbProtocol;
 pub use mxw01::Mxw01Protocol;
 
 /// Trait defining the interface for printer protocol implementations.
 ///
-/// Each protocol variant (GB, MXW01) implements this trait to provide
-/// device-specific BLE service UUIDs, command encoding, and print sequences.
+/// Each protocol variant (GB, MXW01, ESC/POS) implements this trait to
+/// provide device-specific BLE service UUIDs, command encoding, and print
+/// sequences. Protocols not used over BLE return nil UUIDs.
 pub trait PrinterProtocol: Send + Sync {
     /// Human-readable protocol name (e.g. "GB", "MXW01").
     fn name(&self) -> &str;
@@ -0,0 +1,179 @@
+//! Byte transports that carry printer commands.
+//!
+//! BLE cat printers are reached through [`BleConnection`]. ESC/POS printers
+//! are written to a device file: `/dev/usb/lp0` for USB printer-class
+//! devices, `/dev/ttyUSB0` or `COM3` for serial ports (the baud rate is set
+//! at the OS level). [`send_bitmap`] runs any protocol over any transport.
+
+use std::future::Future;
+
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+use crate::ble::BleConnection;
+use crate::protocol::PrinterProtocol;
+use crate::{CatPrinterError, Result};
+
+/// A connection that printer commands can be written to.
+pub trait PrinterTransport: Send {
+    /// Write raw command bytes.
+    fn write_data(&mut self, data: &[u8]) -> impl Future<Output = Result<()>> + Send;
+
+    /// Push out anything buffered. Called once after a print.
+    fn flush(&mut self) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+}
+
+impl PrinterTransport for BleConnection {
+    async fn write_data(&mut self, data: &[u8]) -> Result<()> {
+        BleConnection::write_data(self, data).await
+    }
+}
+
+/// A printer device opened as a file (USB printer class or serial port).
+pub struct DeviceTransport {
+    path: String,
+    writer: BufWriter<tokio::fs::File>,
+}
+
+impl DeviceTransport {
+    /// Open the device for writing.
+    pub async fn open(path: &str) -> Result<Self> {
+        let file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .await
+            .map_err(|e| CatPrinterError::Transport(format!("{path}: {e}")))?;
+        Ok(Self {
+            path: path.to_string(),
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Device path this transport writes to.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl PrinterTransport for DeviceTransport {
+    async fn write_data(&mut self, data: &[u8]) -> Result<()> {
+        self.writer
+            .write_all(data)
+            .await
+            .map_err(|e| CatPrinterError::Transport(format!("{}: {e}", self.path)))
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.writer
+            .flush()
+            .await
+            .map_err(|e| CatPrinterError::Transport(format!("{}: {e}", self.path)))
+    }
+}
+
+/// Split a bitmap (1 byte per pixel, 0=white) into rows of `width`.
+fn rows(bitmap: &[u8], width: u16) -> Result<std::slice::Chunks<'_, u8>> {
+    let row_width = width as usize;
+    if row_width == 0 || !bitmap.len().is_multiple_of(row_width) {
+        return Err(CatPrinterError::Protocol(
+            "bitmap dimensions are invalid".into(),
+        ));
+    }
+    Ok(bitmap.chunks(row_width))
+}
+
+/// Every command of a print job, in order: init, one per row, feed, finish.
+fn commands(
+    protocol: &dyn PrinterProtocol,
+    bitmap: &[u8],
+    width: u16,
+    feed_lines: u16,
+) -> Result<Vec<Vec<u8>>> {
+    let mut cmds = protocol.build_init_sequence();
+    cmds.extend(rows(bitmap, width)?.map(|row| protocol.encode_row(row, width)));
+    cmds.push(protocol.build_feed_command(feed_lines));
+    cmds.extend(protocol.build_finish_sequence());
+    Ok(cmds)
+}
+
+/// Encode a whole print job into one byte stream (e.g. for spooling).
+pub fn build_payload(
+    protocol: &dyn PrinterProtocol,
+    bitmap: &[u8],
+    width: u16,
+    feed_lines: u16,
+) -> Result<Vec<u8>> {
+    Ok(commands(protocol, bitmap, width, feed_lines)?.concat())
+}
+
+/// Print a bitmap over `transport`, one command per write.
+pub async fn send_bitmap<T: PrinterTransport>(
+    transport: &mut T,
+    protocol: &dyn PrinterProtocol,
+    bitmap: &[u8],
+    width: u16,
+    feed_lines: u16,
+) -> Result<()> {
+    for cmd in commands(protocol, bitmap, width, feed_lines)? {
+        transport.write_data(&cmd).await?;
+    }
+    transport.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{EscPosProtocol, GbProtocol};
+
+    struct RecordingTransport {
+        writes: Vec<Vec<u8>>,
+        flushed: bool,
+    }
+
+    impl PrinterTransport for RecordingTransport {
+        async fn write_data(&mut self, data: &[u8]) -> Result<()> {
+            self.writes.push(data.to_vec());
+            Ok(())
+        }
+
+        async fn flush(&mut self) -> Result<()> {
+            self.flushed = true;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_build_payload_matches_commands() {
+        let proto = GbProtocol::new();
+        let bitmap = vec![0u8, 1, 1, 0, 1, 0, 0, 1];
+        let payload = build_payload(&proto, &bitmap, 4, 4).unwrap();
+        let expected: Vec<u8> = commands(&proto, &bitmap, 4, 4).unwrap().concat();
+        assert_eq!(payload, expected);
+        assert!(payload.starts_with(&proto.build_init_sequence().concat()));
+    }
+
+    #[test]
+    fn test_build_payload_rejects_bad_dimensions() {
+        let proto = EscPosProtocol::new();
+        assert!(build_payload(&proto, &[0, 1, 0], 2, 4).is_err());
+        assert!(build_payload(&proto, &[0, 1], 0, 4).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_bitmap_writes_each_command() {
+        let proto = EscPosProtocol::new();
+        let mut transport = RecordingTransport {
+            writes: Vec::new(),
+            flushed: false,
+        };
+        send_bitmap(&mut transport, &proto, &[1u8; 16], 8, 2)
+            .await
+            .unwrap();
+        let rows = 2;
+        let expected =
+            proto.build_init_sequence().len() + rows + 1 + proto.build_finish_sequence().len();
+        assert_eq!(transport.writes.len(), expected);
+        assert!(transport.flushed);
+    }
+}
@@ -0,0 +1,212 @@
+//! Chat excerpt rendering for sharing.
+//!
+//! Lays out several chat messages — time, badge icons, colored name and
+//! the message with emotes — on a white canvas. Wrapping and emote drawing
+//! are shared with [`crate::message`]; the canvas width and font size are
+//! chosen by the caller so excerpts can be rendered above printer
+//! resolution.
+
+use ab_glyph::{FontRef, PxScale};
+use image::{DynamicImage, Rgba, RgbaImage};
+use imageproc::drawing::draw_text_mut;
+
+use crate::PAPER_WIDTH;
+use crate::compose;
+use crate::message::{draw_wrapped_lines, wrap_fragments, wrapped_height};
+use crate::text::{self, Fragment};
+
+const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const GRAY: Rgba<u8> = Rgba([128, 128, 128, 255]);
+/// Name color when the chatter has none.
+const DEFAULT_NAME_COLOR: Rgba<u8> = Rgba([80, 80, 80, 255]);
+
+/// A chat badge; `label` is drawn when the image is missing.
+#[derive(Debug, Clone)]
+pub struct Badge {
+    pub label: String,
+    pub image: Option<DynamicImage>,
+}
+
+/// How [`draw_badges`] draws badges.
+pub(crate) struct BadgeStyle {
+    /// Icon edge length.
+    pub size: u32,
+    /// Space after each badge.
+    pub gap: u32,
+    pub label_scale: PxScale,
+    pub label_color: Rgba<u8>,
+}
+
+/// Draw badges left to right from `x`, vertically centered on a line of
+/// height `lh` starting at `y`. Badges without an image are drawn as a
+/// `[label]` tag. Returns the x position after the last badge.
+pub(crate) fn draw_badges(
+    img: &mut RgbaImage,
+    badges: &[Badge],
+    (mut x, y): (i32, i32),
+    lh: u32,
+    style: &BadgeStyle,
+    font: &FontRef<'_>,
+) -> i32 {
+    let label_lh = text::line_height(font, style.label_scale);
+    let label_y = y + (lh as i32 - label_lh as i32) / 2;
+    for badge in badges {
+        match &badge.image {
+            Some(badge_img) => {
+                let resized = badge_img.resize_exact(
+                    style.size,
+                    style.size,
+                    image::imageops::FilterType::Lanczos3,
+                );
+                let by = y + (lh as i32 - style.size as i32) / 2;
+                compose::overlay(img, &resized, x.max(0) as u32, by.max(0) as u32);
+                x += (style.size + style.gap) as i32;
+            }
+            None => {
+                let tag = format!("[{}]", badge.label);
+                draw_text_mut(
+                    img,
+                    style.label_color,
+                    x,
+                    label_y,
+                    style.label_scale,
+                    font,
+                    &tag,
+                );
+                x += (text::measure_text_width(font, style.label_scale, &tag) + style.gap) as i32;
+            }
+        }
+    }
+    x
+}
+
+/// One chat message to render.
+#[derive(Debug, Clone)]
+pub struct ChatLine {
+    /// Preformatted time shown before the name.
+    pub time: String,
+    pub username: String,
+    pub color: Option<Rgba<u8>>,
+    pub badges: Vec<Badge>,
+    pub fragments: Vec<Fragment>,
+}
+
+/// Canvas width and font size.
+#[derive(Debug, Clone, Copy)]
+pub struct ChatRenderOptions {
+    pub width: u32,
+    pub font_size: f32,
+}
+
+impl ChatRenderOptions {
+    /// `scale` times the printer paper width with a matching font size.
+    pub fn scaled(scale: u32) -> Self {
+        let scale = scale.max(1);
+        Self {
+            width: PAPER_WIDTH * scale,
+            font_size: 20.0 * scale as f32,
+        }
+    }
+}
+
+impl Default for ChatRenderOptions {
+    fn default() -> Self {
+        Self::scaled(2)
+    }
+}
+
+/// Parse a `#RRGGBB` name color, darkening colors too light to read on
+/// white.
+pub fn parse_name_color(hex: &str) -> Option<Rgba<u8>> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+    let luminance = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    if luminance > 180.0 {
+        let k = 180.0 / luminance;
+        let dim = |c: u8| (c as f32 * k) as u8;
+        return Some(Rgba([dim(r), dim(g), dim(b), 255]));
+    }
+    Some(Rgba([r, g, b, 255]))
+}
+
+/// Render chat messages top to bottom into one RGBA image.
+pub fn chat_to_image(
+    lines: &[ChatLine],
+    font: &FontRef<'_>,
+    options: ChatRenderOptions,
+) -> DynamicImage {
+    let scale = PxScale::from(options.font_size);
+    let small_scale = PxScale::from(options.font_size * 0.75);
+    let lh = text::line_height(font, scale);
+    let padding = (options.font_size / 2.0).ceil() as u32;
+    let badge_style = BadgeStyle {
+        size: lh * 4 / 5,
+        gap: padding / 4,
+        label_scale: small_scale,
+        label_color: GRAY,
+    };
+    let max_width = options.width.saturating_sub(padding * 2).max(lh);
+
+    let wrapped: Vec<_> = lines
+        .iter()
+        .map(|line| wrap_fragments(&line.fragments, font, scale, max_width, lh))
+        .collect();
+    let content_height: u32 = wrapped
+        .iter()
+        .map(|w| lh + 2 + wrapped_height(w, lh) + padding)
+        .sum();
+    let height = (padding + content_height).max(1);
+    let mut img = RgbaImage::from_pixel(options.width, height, Rgba([255, 255, 255, 255]));
+
+    let mut y = padding as i32;
+    for (line, wrapped) in lines.iter().zip(&wrapped) {
+        // Header: time, badges, name
+        let mut x = padding as i32;
+        let small_lh = text::line_height(font, small_scale);
+        let small_y = y + (lh as i32 - small_lh as i32) / 2;
+        if !line.time.is_empty() {
+            draw_text_mut(&mut img, GRAY, x, small_y, small_scale, font, &line.time);
+            x += (text::measure_text_width(font, small_scale, &line.time) + padding / 2) as i32;
+        }
+        x = draw_badges(&mut img, &line.badges, (x, y), lh, &badge_style, font);
+        let color = line.color.unwrap_or(DEFAULT_NAME_COLOR);
+        draw_text_mut(&mut img, color, x, y, scale, font, &line.username);
+        y += lh as i32 + 2;
+
+        y = draw_wrapped_lines(&mut img, wrapped, padding as i32, y, scale, font, BLACK);
+        y += padding as i32;
+    }
+
+    DynamicImage::ImageRgba8(img)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name_color() {
+        assert_eq!(
+            parse_name_color("#9146FF"),
+            Some(Rgba([0x91, 0x46, 0xFF, 255]))
+        );
+        assert_eq!(parse_name_color("9146FF"), None);
+        assert_eq!(parse_name_color("#12345"), None);
+        assert_eq!(parse_name_color("#GG0000"), None);
+        // Yellow is darkened to stay readable on white.
+        let Rgba([r, g, b, _]) = parse_name_color("#FFFF00").unwrap();
+        assert!(r < 255 && g < 255 && b == 0);
+    }
+
+    #[test]
+    fn test_scaled_options() {
+        let options = ChatRenderOptions::scaled(3);
+        assert_eq!(options.width, PAPER_WIDTH * 3);
+        assert_eq!(options.font_size, 60.0);
+        assert_eq!(ChatRenderOptions::scaled(0).width, PAPER_WIDTH);
+    }
+}
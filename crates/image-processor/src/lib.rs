@@ -1,20 +1,26 @@
 //! Image processing utilities for thermal printer output.
 //!
 //! Provides dithering, resizing, rotation, text rendering,
-//! QR code generation, image composition, and message-to-image
-//! conversion for thermal printer output.
+//! QR code generation, image composition, message-to-image
+//! conversion and print previews for thermal printer output.
 
+pub mod chat_render;
 pub mod clock;
 pub mod compose;
 pub mod dither;
+pub mod locale;
 pub mod message;
+pub mod poster;
+pub mod preview;
 pub mod qr;
 pub mod resize;
 pub mod rotate;
 pub mod text;
+pub mod wordcloud;
 
 // Re-exports for convenience
 pub use dither::{floyd_steinberg_dither, threshold_convert};
+pub use locale::PrintLocale;
 pub use resize::{resize_to_height, resize_to_width};
 pub use rotate::{auto_rotate_portrait, rotate_180};
 
@@ -0,0 +1,202 @@
+//! Locale-aware date, time and number formatting for printed content.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+/// Locale used to render dates, times and numbers on prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrintLocale {
+    /// `2026-10-16 21:05`
+    #[default]
+    Ja,
+    /// Japanese era: `令和8年10月16日 21:05`
+    JaEra,
+    /// `10/16/2026 9:05 PM`
+    EnUs,
+    /// `16/10/2026 21:05`
+    EnGb,
+    /// `16.10.2026 21:05`
+    De,
+    /// `16/10/2026 21:05`
+    Fr,
+    /// `16/10/2026 21:05`
+    Es,
+}
+
+/// Japanese eras (newest first) with the date each began.
+const ERAS: &[(&str, i32, u32, u32)] = &[
+    ("令和", 2019, 5, 1),
+    ("平成", 1989, 1, 8),
+    ("昭和", 1926, 12, 25),
+];
+
+impl PrintLocale {
+    /// Accepted locale codes.
+    pub const CODES: &'static [&'static str] = &[
+        "ja-JP",
+        "ja-JP-era",
+        "en-US",
+        "en-GB",
+        "de-DE",
+        "fr-FR",
+        "es-ES",
+    ];
+
+    pub fn parse(code: &str) -> Option<Self> {
+        match code {
+            "ja-JP" => Some(Self::Ja),
+            "ja-JP-era" => Some(Self::JaEra),
+            "en-US" => Some(Self::EnUs),
+            "en-GB" => Some(Self::EnGb),
+            "de-DE" => Some(Self::De),
+            "fr-FR" => Some(Self::Fr),
+            "es-ES" => Some(Self::Es),
+            _ => None,
+        }
+    }
+
+    /// Language part of the locale (`ja`, `en`, `de`, `fr`, `es`).
+    pub fn lang(self) -> &'static str {
+        match self {
+            Self::Ja | Self::JaEra => "ja",
+            Self::EnUs | Self::EnGb => "en",
+            Self::De => "de",
+            Self::Fr => "fr",
+            Self::Es => "es",
+        }
+    }
+
+    pub fn uses_12h_clock(self) -> bool {
+        self == Self::EnUs
+    }
+
+    pub fn format_date(self, date: NaiveDate) -> String {
+        match self {
+            Self::Ja => date.format("%Y-%m-%d").to_string(),
+            Self::JaEra => match japanese_era(date) {
+                Some((era, year)) => format!(
+                    "{era}{}年{}月{}日",
+                    era_year(year),
+                    date.month(),
+                    date.day()
+                ),
+                None => date.format("%Y年%-m月%-d日").to_string(),
+            },
+            Self::EnUs => date.format("%m/%d/%Y").to_string(),
+            Self::De => date.format("%d.%m.%Y").to_string(),
+            Self::EnGb | Self::Fr | Self::Es => date.format("%d/%m/%Y").to_string(),
+        }
+    }
+
+    pub fn format_time(self, time: NaiveTime) -> String {
+        if !self.uses_12h_clock() {
+            return time.format("%H:%M").to_string();
+        }
+        let (pm, hour) = time.hour12();
+        format!(
+            "{hour}:{:02} {}",
+            time.minute(),
+            if pm { "PM" } else { "AM" }
+        )
+    }
+
+    pub fn format_datetime(self, dt: NaiveDateTime) -> String {
+        format!(
+            "{} {}",
+            self.format_date(dt.date()),
+            self.format_time(dt.time())
+        )
+    }
+
+    /// Integer with the locale's thousands separator.
+    pub fn format_number(self, n: i64) -> String {
+        let sep = match self {
+            Self::De | Self::Es => '.',
+            Self::Fr => ' ',
+            _ => ',',
+        };
+        let digits = n.unsigned_abs().to_string();
+        let mut out = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+        if n < 0 {
+            out.push('-');
+        }
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(sep);
+            }
+            out.push(c);
+        }
+        out
+    }
+}
+
+/// Era name and year within the era (1 = first year).
+fn japanese_era(date: NaiveDate) -> Option<(&'static str, i32)> {
+    ERAS.iter().find_map(|&(name, y, m, d)| {
+        let start = NaiveDate::from_ymd_opt(y, m, d)?;
+        (date >= start).then(|| (name, date.year() - y + 1))
+    })
+}
+
+/// The first year of an era is written 元年.
+fn era_year(year: i32) -> String {
+    if year == 1 {
+        "元".to_string()
+    } else {
+        year.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dt(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_format_datetime() {
+        let t = dt(2026, 10, 16, 21, 5);
+        assert_eq!(PrintLocale::Ja.format_datetime(t), "2026-10-16 21:05");
+        assert_eq!(
+            PrintLocale::JaEra.format_datetime(t),
+            "令和8年10月16日 21:05"
+        );
+        assert_eq!(PrintLocale::EnUs.format_datetime(t), "10/16/2026 9:05 PM");
+        assert_eq!(PrintLocale::EnGb.format_datetime(t), "16/10/2026 21:05");
+        assert_eq!(PrintLocale::De.format_datetime(t), "16.10.2026 21:05");
+        assert_eq!(
+            PrintLocale::EnUs.format_time(dt(2026, 1, 1, 0, 30).time()),
+            "12:30 AM"
+        );
+    }
+
+    #[test]
+    fn test_japanese_era() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(
+            PrintLocale::JaEra.format_date(date(2019, 5, 1)),
+            "令和元年5月1日"
+        );
+        assert_eq!(
+            PrintLocale::JaEra.format_date(date(2019, 4, 30)),
+            "平成31年4月30日"
+        );
+        assert_eq!(
+            PrintLocale::JaEra.format_date(date(1900, 1, 1)),
+            "1900年1月1日"
+        );
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(PrintLocale::Ja.format_number(1234567), "1,234,567");
+        assert_eq!(PrintLocale::De.format_number(-1234), "-1.234");
+        assert_eq!(PrintLocale::Fr.format_number(999), "999");
+        assert_eq!(PrintLocale::parse("en-GB"), Some(PrintLocale::EnGb));
+        assert_eq!(PrintLocale::parse("xx"), None);
+    }
+}
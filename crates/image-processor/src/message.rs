@@ -4,17 +4,19 @@
 //! printer-compatible image with word wrapping and emote embedding.
 
 use ab_glyph::{FontRef, PxScale};
-use image::{DynamicImage, Rgba};
+use image::{DynamicImage, Rgba, RgbaImage};
 use imageproc::drawing::draw_text_mut;
 
 use crate::PAPER_WIDTH;
+use crate::chat_render::{Badge, BadgeStyle, draw_badges};
 use crate::compose;
+use crate::locale::PrintLocale;
 use crate::text::{self, DEFAULT_FONT_SIZE, Fragment, UNDERLINE_HEIGHT, UNDERLINE_MARGIN};
 
 const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
 
 /// Layout result for wrapped fragments.
-struct WrappedLine {
+pub(crate) struct WrappedLine {
     fragments: Vec<Fragment>,
     is_emote_only: bool,
 }
@@ -30,6 +32,18 @@ pub fn message_to_image(
     fragments: &[Fragment],
     font: &FontRef<'_>,
     use_color: bool,
+) -> DynamicImage {
+    message_to_image_with_badges(username, &[], fragments, font, use_color)
+}
+
+/// [`message_to_image`] with badge icons (or `[label]` tags when a badge
+/// has no image) before the username.
+pub fn message_to_image_with_badges(
+    username: &str,
+    badges: &[Badge],
+    fragments: &[Fragment],
+    font: &FontRef<'_>,
+    use_color: bool,
 ) -> DynamicImage {
     let scale = PxScale::from(DEFAULT_FONT_SIZE);
     let lh = text::line_height(font, scale);
@@ -40,49 +54,30 @@ pub fn message_to_image(
 
     // Calculate total image height
     let header_height = lh + 4; // username line + small padding
-    let mut content_height = 0u32;
-    for line in &lines {
-        if line.is_emote_only {
-            content_height += lh + 4; // emote cells
-        } else {
-            content_height += lh + 2;
-        }
-    }
+    let content_height = wrapped_height(&lines, lh);
     let footer_height = UNDERLINE_MARGIN * 2 + UNDERLINE_HEIGHT;
     let total_height = header_height + content_height + footer_height;
 
     let mut img = text::blank_image(total_height);
 
-    // Draw username
-    draw_text_mut(&mut img, BLACK, 0, 0, scale, font, username);
-    let mut y = header_height as i32;
-
-    // Draw each line
-    for line in &lines {
-        let mut x = 0i32;
-        for frag in &line.fragments {
-            if frag.is_emote {
-                if let Some(ref emote_img) = frag.emote_image {
-                    let resized =
-                        emote_img.resize_exact(lh, lh, image::imageops::FilterType::Lanczos3);
-                    compose::overlay(&mut img, &resized, x as u32, y as u32);
-                    x += lh as i32;
-                } else {
-                    // Fallback: draw emote name as text
-                    draw_text_mut(&mut img, BLACK, x, y, scale, font, &frag.text);
-                    x += text::measure_text_width(font, scale, &frag.text) as i32;
-                }
-            } else {
-                draw_text_mut(&mut img, BLACK, x, y, scale, font, &frag.text);
-                x += text::measure_text_width(font, scale, &frag.text) as i32;
-            }
-        }
-        y += if line.is_emote_only {
-            lh as i32 + 4
-        } else {
-            lh as i32 + 2
-        };
-    }
+    // Draw badges and username
+    let badge_style = BadgeStyle {
+        size: lh * 4 / 5,
+        gap: 4,
+        label_scale: PxScale::from(DEFAULT_FONT_SIZE * 0.75),
+        label_color: BLACK,
+    };
+    let name_x = draw_badges(&mut img, badges, (0, 0), lh, &badge_style, font);
+    draw_text_mut(&mut img, BLACK, name_x, 0, scale, font, username);
+    draw_wrapped_lines(
+        &mut img,
+        &lines,
+        0,
+        header_height as i32,
+        scale,
+        font,
+        BLACK,
+    );
 
     // Draw underline separator
     let underline_y = (total_height - footer_height + UNDERLINE_MARGIN) as u32;
@@ -113,6 +108,7 @@ pub fn message_to_image_with_title(
     avatar: Option<&DynamicImage>,
     font: &FontRef<'_>,
     use_color: bool,
+    locale: PrintLocale,
 ) -> DynamicImage {
     let scale = PxScale::from(DEFAULT_FONT_SIZE);
     let small_scale = PxScale::from(DEFAULT_FONT_SIZE * 0.75);
@@ -171,7 +167,7 @@ pub fn message_to_image_with_title(
     // Footer separator + timestamp
     let sep_y = header_height + UNDERLINE_HEIGHT + details_height;
     text::draw_dashed_line(&mut img, sep_y, UNDERLINE_HEIGHT, 8, 4);
-    let now = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let now = locale.format_datetime(chrono::Local::now().naive_local());
     text::draw_centered_text(
         &mut img,
         font,
@@ -188,8 +184,59 @@ pub fn message_to_image_with_title(
     }
 }
 
+/// Total height of wrapped lines drawn by [`draw_wrapped_lines`].
+pub(crate) fn wrapped_height(lines: &[WrappedLine], line_height: u32) -> u32 {
+    lines
+        .iter()
+        .map(|line| {
+            if line.is_emote_only {
+                line_height + 4 // emote cells
+            } else {
+                line_height + 2
+            }
+        })
+        .sum()
+}
+
+/// Draw wrapped lines starting at `(x0, y)`; emotes are scaled to the line
+/// height. Returns the y below the last line.
+pub(crate) fn draw_wrapped_lines(
+    img: &mut RgbaImage,
+    lines: &[WrappedLine],
+    x0: i32,
+    mut y: i32,
+    scale: PxScale,
+    font: &FontRef<'_>,
+    color: Rgba<u8>,
+) -> i32 {
+    let lh = text::line_height(font, scale);
+    for line in lines {
+        let mut x = x0;
+        for frag in &line.fragments {
+            if let (true, Some(emote_img)) = (frag.is_emote, &frag.emote_image) {
+                let resized = emote_img.resize_exact(lh, lh, image::imageops::FilterType::Lanczos3);
+                compose::overlay(img, &resized, x as u32, y as u32);
+                x += lh as i32;
+            } else {
+                // Text, or the emote name when its image is missing
+                draw_text_mut(img, color, x, y, scale, font, &frag.text);
+                x += text::measure_text_width(font, scale, &frag.text) as i32;
+            }
+        }
+        y += if line.is_emote_only {
+            lh as i32 + 4
+        } else {
+            lh as i32 + 2
+        };
+    }
+    y
+}
+
 /// Wrap fragments into lines that fit within max_width.
-fn wrap_fragments(
+///
+/// Text breaks at whitespace; a word wider than a whole line (e.g.
+/// Japanese without spaces) breaks between characters.
+pub(crate) fn wrap_fragments(
     fragments: &[Fragment],
     font: &FontRef<'_>,
     scale: PxScale,
@@ -216,8 +263,13 @@ fn wrap_fragments(
             current_width += emote_width;
         } else {
             all_emotes = false;
-            // Split text by words
-            for word in frag.text.split_inclusive(|c: char| c.is_whitespace()) {
+            // Split text by words, and over-long words by characters
+            for word in frag
+                .text
+                .split_inclusive(|c: char| c.is_whitespace())
+                .flat_map(|word| split_long_word(word, font, scale, max_width))
+            {
+                let word = word.as_str();
                 let w = text::measure_text_width(font, scale, word);
                 if current_width + w > max_width && !current_frags.is_empty() {
                     lines.push(WrappedLine {
@@ -253,3 +305,26 @@ fn wrap_fragments(
 
     lines
 }
+
+/// Split `word` into pieces no wider than `max_width`.
+fn split_long_word(word: &str, font: &FontRef<'_>, scale: PxScale, max_width: u32) -> Vec<String> {
+    if text::measure_text_width(font, scale, word) <= max_width {
+        return vec![word.to_string()];
+    }
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for ch in word.chars() {
+        current.push(ch);
+        if text::measure_text_width(font, scale, &current) > max_width
+            && current.chars().count() > 1
+        {
+            current.pop();
+            pieces.push(std::mem::take(&mut current));
+            current.push(ch);
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
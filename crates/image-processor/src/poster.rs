@@ -0,0 +1,122 @@
+//! Multi-strip posters.
+//!
+//! A poster is laid out several paper widths wide and printed as one strip
+//! per column, to be placed side by side. Cards (pre-rendered at paper
+//! width) are packed masonry-style into the shortest column under a title
+//! spanning the whole poster. Each strip ends with its position (`1/3`) so
+//! the pieces can be put back in order.
+
+use ab_glyph::{FontRef, PxScale};
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+use crate::PAPER_WIDTH;
+use crate::compose;
+use crate::text::{self, DEFAULT_FONT_SIZE};
+
+const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const GRAY: Rgba<u8> = Rgba([128, 128, 128, 255]);
+const GAP: u32 = 12;
+
+/// Lay out `cards` under `title` in `columns` paper-width columns.
+pub fn masonry(
+    title: &str,
+    cards: &[DynamicImage],
+    columns: u32,
+    font: &FontRef<'_>,
+) -> DynamicImage {
+    let columns = columns.max(1);
+    let width = PAPER_WIDTH * columns;
+    let title_scale = PxScale::from(DEFAULT_FONT_SIZE * 2.0);
+    let title_lh = text::line_height(font, title_scale);
+    let title_lines = if title.is_empty() {
+        Vec::new()
+    } else {
+        text::wrap_text(font, title_scale, title, width - GAP * 2)
+    };
+    let title_height = if title_lines.is_empty() {
+        0
+    } else {
+        title_lines.len() as u32 * title_lh + GAP * 2
+    };
+
+    let card_heights: Vec<u32> = cards.iter().map(|c| c.height()).collect();
+    let (placed, height) = pack(&card_heights, columns, title_height);
+
+    let mut img = RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]));
+    let mut y = GAP as i32;
+    for line in &title_lines {
+        text::draw_centered_text(&mut img, font, title_scale, y, line, BLACK);
+        y += title_lh as i32;
+    }
+    if title_height > 0 {
+        text::draw_dashed_line(&mut img, title_height - GAP / 2, 2, 8, 4);
+    }
+    for (card, (col, y)) in cards.iter().zip(placed) {
+        compose::overlay(&mut img, card, col * PAPER_WIDTH, y);
+    }
+    DynamicImage::ImageRgba8(img)
+}
+
+/// Place cards of the given heights, each in the currently shortest
+/// column below `top`. Returns `(column, y)` per card and the total height.
+fn pack(card_heights: &[u32], columns: u32, top: u32) -> (Vec<(u32, u32)>, u32) {
+    let mut heights = vec![top; columns.max(1) as usize];
+    let mut placed = Vec::with_capacity(card_heights.len());
+    for &card_height in card_heights {
+        let (col, y) = heights
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|&(_, h)| h)
+            .unwrap_or((0, top));
+        placed.push((col as u32, y));
+        heights[col] = y + card_height + GAP;
+    }
+    let height = heights.into_iter().max().unwrap_or(top).max(1);
+    (placed, height)
+}
+
+/// Cut a poster into paper-width strips, each ending with its position.
+pub fn split_strips(poster: &DynamicImage, font: &FontRef<'_>) -> Vec<DynamicImage> {
+    let count = poster.width().div_ceil(PAPER_WIDTH).max(1);
+    let label_scale = PxScale::from(DEFAULT_FONT_SIZE * 0.75);
+    let label_height = text::line_height(font, label_scale) + GAP;
+    (0..count)
+        .map(|i| {
+            let x = i * PAPER_WIDTH;
+            let w = PAPER_WIDTH.min(poster.width() - x);
+            let mut strip = text::blank_image(poster.height() + label_height);
+            compose::overlay(
+                &mut strip,
+                &DynamicImage::ImageRgba8(poster.view(x, 0, w, poster.height()).to_image()),
+                0,
+                0,
+            );
+            text::draw_centered_text(
+                &mut strip,
+                font,
+                label_scale,
+                (poster.height() + GAP / 2) as i32,
+                &format!("{}/{count}", i + 1),
+                GRAY,
+            );
+            DynamicImage::ImageRgba8(strip)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_fills_shortest_column() {
+        let (placed, height) = pack(&[100, 40, 30, 50], 2, 10);
+        assert_eq!(
+            placed,
+            vec![(0, 10), (1, 10), (1, 10 + 40 + GAP), (1, 10 + 70 + GAP * 2)]
+        );
+        assert_eq!(height, 10 + 120 + GAP * 3);
+        assert_eq!(pack(&[], 3, 0).1, 1);
+    }
+}
@@ -0,0 +1,57 @@
+//! Print preview rendering.
+//!
+//! Turns the 0/1-per-pixel bitmap sent to the printer back into an image,
+//! so layout can be checked without feeding paper.
+
+use image::{DynamicImage, GrayImage, Luma};
+
+use crate::rotate::rotate_180;
+
+/// Convert a monochrome bitmap (1 = black) into a grayscale image.
+///
+/// Returns `None` when `width` is zero or the bitmap is not a whole number
+/// of rows.
+pub fn bitmap_to_image(bitmap: &[u8], width: u32) -> Option<GrayImage> {
+    let row = width as usize;
+    if row == 0 || !bitmap.len().is_multiple_of(row) {
+        return None;
+    }
+    let height = (bitmap.len() / row) as u32;
+    Some(GrayImage::from_fn(width, height, |x, y| {
+        let black = bitmap[y as usize * row + x as usize] != 0;
+        Luma([if black { 0 } else { 255 }])
+    }))
+}
+
+/// Render a bitmap the way it leaves the printer. With `rotate_print` the
+/// preview is turned 180 degrees, matching the printer's rotated output.
+pub fn render_preview(bitmap: &[u8], width: u32, rotate_print: bool) -> Option<DynamicImage> {
+    let img = DynamicImage::ImageLuma8(bitmap_to_image(bitmap, width)?);
+    Some(if rotate_print { rotate_180(&img) } else { img })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitmap_to_image() {
+        let img = bitmap_to_image(&[1, 0, 0, 0, 0, 1], 3).unwrap();
+        assert_eq!(img.dimensions(), (3, 2));
+        assert_eq!(img.get_pixel(0, 0).0[0], 0);
+        assert_eq!(img.get_pixel(1, 0).0[0], 255);
+        assert_eq!(img.get_pixel(2, 1).0[0], 0);
+        assert!(bitmap_to_image(&[1, 0, 0, 0], 3).is_none());
+        assert!(bitmap_to_image(&[], 0).is_none());
+    }
+
+    #[test]
+    fn test_render_preview_rotation() {
+        let bitmap = [1, 0, 0, 0, 0, 0];
+        let plain = render_preview(&bitmap, 3, false).unwrap().to_luma8();
+        assert_eq!(plain.get_pixel(0, 0).0[0], 0);
+        let rotated = render_preview(&bitmap, 3, true).unwrap().to_luma8();
+        assert_eq!(rotated.get_pixel(0, 0).0[0], 255);
+        assert_eq!(rotated.get_pixel(2, 1).0[0], 0);
+    }
+}
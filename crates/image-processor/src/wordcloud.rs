@@ -0,0 +1,109 @@
+//! Word cloud image generation (end-of-stream display or print).
+
+use ab_glyph::{FontRef, PxScale};
+use image::{DynamicImage, Rgba};
+use imageproc::drawing::draw_text_mut;
+
+use crate::PAPER_WIDTH;
+use crate::text::{self, DEFAULT_FONT_SIZE};
+
+const MIN_FONT_SIZE: f32 = 18.0;
+const MAX_FONT_SIZE: f32 = 64.0;
+const MARGIN: u32 = 12;
+const WORD_GAP: u32 = 12;
+const LINE_GAP: u32 = 4;
+
+/// A placed word: text, scale and measured size.
+struct Placed<'a> {
+    text: &'a str,
+    scale: PxScale,
+    width: u32,
+    height: u32,
+}
+
+/// Render weighted terms (`weight` 0.0-1.0, heaviest first) as rows of
+/// centered words sized by weight, under an optional title.
+pub fn generate_word_cloud_image(
+    title: &str,
+    terms: &[(String, f32)],
+    font: &FontRef<'_>,
+) -> DynamicImage {
+    let max_width = PAPER_WIDTH - MARGIN * 2;
+
+    let mut rows: Vec<Vec<Placed<'_>>> = Vec::new();
+    let mut row_width = 0u32;
+    for (term, weight) in terms {
+        let size = MIN_FONT_SIZE + weight.clamp(0.0, 1.0) * (MAX_FONT_SIZE - MIN_FONT_SIZE);
+        let mut scale = PxScale::from(size);
+        let mut width = text::measure_text_width(font, scale, term);
+        if width > max_width {
+            // Shrink overly long words to fit the paper.
+            scale = PxScale::from(size * max_width as f32 / width as f32);
+            width = text::measure_text_width(font, scale, term).min(max_width);
+        }
+        let placed = Placed {
+            text: term,
+            scale,
+            width,
+            height: text::line_height(font, scale),
+        };
+        let needed = if row_width == 0 {
+            width
+        } else {
+            row_width + WORD_GAP + width
+        };
+        match rows.last_mut() {
+            Some(row) if needed <= max_width => {
+                row.push(placed);
+                row_width = needed;
+            }
+            _ => {
+                rows.push(vec![placed]);
+                row_width = width;
+            }
+        }
+    }
+
+    let title_scale = PxScale::from(DEFAULT_FONT_SIZE * 1.2);
+    let title_height = if title.is_empty() {
+        0
+    } else {
+        text::line_height(font, title_scale) + MARGIN
+    };
+    let rows_height: u32 = rows
+        .iter()
+        .map(|row| row.iter().map(|p| p.height).max().unwrap_or(0) + LINE_GAP)
+        .sum();
+    let height = (MARGIN + title_height + rows_height + MARGIN).max(MARGIN * 2 + 1);
+
+    let mut img = text::blank_image(height);
+    let black = Rgba([0, 0, 0, 255]);
+    let mut y = MARGIN;
+    if !title.is_empty() {
+        text::draw_centered_text(&mut img, font, title_scale, y as i32, title, black);
+        y += title_height;
+    }
+    for row in &rows {
+        let row_height = row.iter().map(|p| p.height).max().unwrap_or(0);
+        let total: u32 =
+            row.iter().map(|p| p.width).sum::<u32>() + WORD_GAP * (row.len() as u32 - 1);
+        let mut x = (PAPER_WIDTH - total.min(PAPER_WIDTH)) / 2;
+        for p in row {
+            // Bottom-align words of different sizes on the row.
+            let word_y = y + row_height - p.height;
+            draw_text_mut(
+                &mut img,
+                black,
+                x as i32,
+                word_y as i32,
+                p.scale,
+                font,
+                p.text,
+            );
+            x += p.width + WORD_GAP;
+        }
+        y += row_height + LINE_GAP;
+    }
+
+    DynamicImage::ImageRgba8(img)
+}
@@ -1,6 +1,6 @@
 //! Chat message history storage.
 
-use crate::{Database, DbError};
+use crate::{Database, DbError, like_contains};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +16,83 @@ pub struct ChatMessage {
     pub translation_status: String,
     pub translation_lang: String,
     pub created_at: i64,
+    /// EventSub badge list as JSON.
+    #[serde(default)]
+    pub badges_json: String,
+    /// Name color (`#RRGGBB`), empty when unset.
+    #[serde(default)]
+    pub color: String,
+}
+
+const SELECT: &str =
+    "SELECT id, message_id, user_id, username, message, fragments_json, avatar_url,
+        translation_text, translation_status, translation_lang, created_at, badges_json, color
+    FROM chat_messages";
+
+/// [`SELECT`]'s columns qualified for joins with the full-text index.
+pub(crate) const SEARCH_COLUMNS: &str =
+    "SELECT m.id, m.message_id, m.user_id, m.username, m.message, m.fragments_json,
+        m.avatar_url, m.translation_text, m.translation_status, m.translation_lang,
+        m.created_at, m.badges_json, m.color";
+
+/// Shortest term the trigram index can match; shorter terms use `LIKE`.
+const MIN_FTS_CHARS: usize = 3;
+
+/// Filters of [`Database::full_text_search_chat`].
+#[derive(Debug, Clone, Default)]
+pub struct ChatSearch {
+    /// Whitespace-separated terms; a message must contain every one of them
+    /// in its text or author name.
+    pub query: String,
+    /// User ID or name (case-insensitive).
+    pub user: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub offset: i64,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatSearchHit {
+    #[serde(flatten)]
+    pub message: ChatMessage,
+    /// bm25 relevance, lower is better; 0 when every term was too short for
+    /// the index.
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatSearchPage {
+    pub hits: Vec<ChatSearchHit>,
+    /// Matches across all pages.
+    pub total: i64,
+}
+
+/// FTS5 query matching each term as a literal substring.
+fn fts_query<'a>(terms: impl IntoIterator<Item = &'a str>) -> String {
+    terms
+        .into_iter()
+        .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub(crate) fn map_message(row: &rusqlite::Row<'_>) -> rusqlite::Result<ChatMessage> {
+    Ok(ChatMessage {
+        id: row.get(0)?,
+        message_id: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+        user_id: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+        username: row.get(3)?,
+        message: row.get(4)?,
+        fragments_json: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+        avatar_url: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+        translation_text: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+        translation_status: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+        translation_lang: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+        created_at: row.get(10)?,
+        badges_json: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
+        color: row.get::<_, Option<String>>(12)?.unwrap_or_default(),
+    })
 }
 
 impl Database {
@@ -24,8 +101,9 @@ impl Database {
             let changed = conn.execute(
                 "INSERT OR IGNORE INTO chat_messages
                     (message_id, user_id, username, message, fragments_json, avatar_url,
-                     translation_text, translation_status, translation_lang, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                     translation_text, translation_status, translation_lang, created_at,
+                     badges_json, color)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
                 rusqlite::params![
                     msg.message_id,
                     msg.user_id,
@@ -37,6 +115,8 @@ impl Database {
                     msg.translation_status,
                     msg.translation_lang,
                     msg.created_at,
+                    non_empty_json(&msg.badges_json),
+                    msg.color,
                 ],
             )?;
             Ok(changed > 0)
@@ -51,38 +131,165 @@ impl Database {
         self.with_conn(|conn| {
             let (sql, params): (String, Vec<Box<dyn rusqlite::types::ToSql>>) = match limit {
                 Some(l) => (
-                    "SELECT id, message_id, user_id, username, message, fragments_json, avatar_url,
-                            translation_text, translation_status, translation_lang, created_at
-                     FROM chat_messages WHERE created_at >= ?1 ORDER BY created_at ASC LIMIT ?2"
-                        .to_string(),
+                    format!("{SELECT} WHERE created_at >= ?1 ORDER BY created_at ASC LIMIT ?2"),
                     vec![Box::new(since_unix), Box::new(l)],
                 ),
                 None => (
-                    "SELECT id, message_id, user_id, username, message, fragments_json, avatar_url,
-                            translation_text, translation_status, translation_lang, created_at
-                     FROM chat_messages WHERE created_at >= ?1 ORDER BY created_at ASC"
-                        .to_string(),
+                    format!("{SELECT} WHERE created_at >= ?1 ORDER BY created_at ASC"),
                     vec![Box::new(since_unix)],
                 ),
             };
 
             let mut stmt = conn.prepare(&sql)?;
-            let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
-                Ok(ChatMessage {
-                    id: row.get(0)?,
-                    message_id: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
-                    user_id: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
-                    username: row.get(3)?,
-                    message: row.get(4)?,
-                    fragments_json: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
-                    avatar_url: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
-                    translation_text: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
-                    translation_status: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
-                    translation_lang: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
-                    created_at: row.get(10)?,
+            let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), map_message)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Messages with `from <= created_at <= to`, oldest first, at most `limit`.
+    pub fn get_chat_messages_between(
+        &self,
+        from_unix: i64,
+        to_unix: i64,
+        limit: i64,
+    ) -> Result<Vec<ChatMessage>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT} WHERE created_at >= ?1 AND created_at <= ?2
+                 ORDER BY created_at ASC, id ASC LIMIT ?3"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params![from_unix, to_unix, limit], map_message)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Messages with the given EventSub message IDs, oldest first.
+    pub fn get_chat_messages_by_ids(
+        &self,
+        message_ids: &[String],
+    ) -> Result<Vec<ChatMessage>, DbError> {
+        if message_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.with_conn(|conn| {
+            let placeholders = vec!["?"; message_ids.len()].join(", ");
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT} WHERE message_id IN ({placeholders}) ORDER BY created_at ASC, id ASC"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(message_ids), map_message)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Messages whose text or author contains `query`, newest first.
+    pub fn search_chat_messages(
+        &self,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<ChatMessage>, DbError> {
+        self.with_conn(|conn| {
+            let (sql, pattern) = if query.chars().count() >= MIN_FTS_CHARS {
+                (
+                    format!(
+                        "{SEARCH_COLUMNS} FROM chat_messages_fts
+                         JOIN chat_messages m ON m.id = chat_messages_fts.rowid
+                         WHERE chat_messages_fts MATCH ?1
+                         ORDER BY m.created_at DESC, m.id DESC LIMIT ?2"
+                    ),
+                    fts_query([query]),
+                )
+            } else {
+                (
+                    format!(
+                        "{SELECT} WHERE message LIKE ?1 ESCAPE '\\' OR username LIKE ?1 ESCAPE '\\'
+                         ORDER BY created_at DESC, id DESC LIMIT ?2"
+                    ),
+                    like_contains(query),
+                )
+            };
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params![pattern, limit], map_message)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Messages matching `search`, most relevant first. Terms of three or
+    /// more characters go through the full-text index; shorter ones are
+    /// matched with `LIKE`, and with only short terms the newest come first.
+    pub fn full_text_search_chat(&self, search: &ChatSearch) -> Result<ChatSearchPage, DbError> {
+        let (long, short): (Vec<&str>, Vec<&str>) = search
+            .query
+            .split_whitespace()
+            .partition(|t| t.chars().count() >= MIN_FTS_CHARS);
+        let indexed = !long.is_empty();
+
+        let mut conditions = Vec::new();
+        let mut params: Vec<rusqlite::types::Value> = Vec::new();
+        if indexed {
+            conditions.push("chat_messages_fts MATCH ?");
+            params.push(fts_query(long).into());
+        }
+        for term in short {
+            conditions.push("(m.message LIKE ? ESCAPE '\\' OR m.username LIKE ? ESCAPE '\\')");
+            let pattern = like_contains(term);
+            params.push(pattern.clone().into());
+            params.push(pattern.into());
+        }
+        if let Some(user) = &search.user {
+            conditions.push("(m.user_id = ? OR m.username = ? COLLATE NOCASE)");
+            params.push(user.clone().into());
+            params.push(user.clone().into());
+        }
+        if let Some(from) = search.from {
+            conditions.push("m.created_at >= ?");
+            params.push(from.into());
+        }
+        if let Some(to) = search.to {
+            conditions.push("m.created_at <= ?");
+            params.push(to.into());
+        }
+
+        let source = if indexed {
+            "chat_messages_fts JOIN chat_messages m ON m.id = chat_messages_fts.rowid"
+        } else {
+            "chat_messages m"
+        };
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        let (score, order) = if indexed {
+            (
+                "bm25(chat_messages_fts)",
+                "score, m.created_at DESC, m.id DESC",
+            )
+        } else {
+            ("0.0", "m.created_at DESC, m.id DESC")
+        };
+
+        self.with_conn(|conn| {
+            let total = conn.query_row(
+                &format!("SELECT COUNT(*) FROM {source}{filter}"),
+                rusqlite::params_from_iter(&params),
+                |row| row.get(0),
+            )?;
+            let mut stmt = conn.prepare(&format!(
+                "{SEARCH_COLUMNS}, {score} AS score FROM {source}{filter}
+                 ORDER BY {order} LIMIT ? OFFSET ?"
+            ))?;
+            params.push(search.limit.into());
+            params.push(search.offset.into());
+            let rows = stmt.query_map(rusqlite::params_from_iter(&params), |row| {
+                Ok(ChatSearchHit {
+                    message: map_message(row)?,
+                    score: row.get(13)?,
                 })
             })?;
-            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+            Ok(ChatSearchPage {
+                hits: rows.collect::<Result<Vec<_>, _>>()?,
+                total,
+            })
         })
     }
 
@@ -96,6 +303,24 @@ impl Database {
         })
     }
 
+    /// Messages stored since `since_unix`, not counting those from
+    /// `exclude_user_id`.
+    pub fn count_chat_messages_since(
+        &self,
+        since_unix: i64,
+        exclude_user_id: &str,
+    ) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT COUNT(*) FROM chat_messages
+                 WHERE created_at >= ?1 AND COALESCE(user_id, '') != ?2",
+                rusqlite::params![since_unix, exclude_user_id],
+                |row| row.get(0),
+            )
+            .map_err(Into::into)
+        })
+    }
+
     pub fn get_latest_chat_avatar(&self, user_id: &str) -> Result<Option<String>, DbError> {
         self.with_conn(|conn| {
             let mut stmt = conn.prepare(
@@ -136,6 +361,10 @@ impl Database {
     }
 }
 
+fn non_empty_json(json: &str) -> &str {
+    if json.is_empty() { "[]" } else { json }
+}
+
 trait OptionalExt<T> {
     fn optional(self) -> Result<Option<T>, rusqlite::Error>;
 }
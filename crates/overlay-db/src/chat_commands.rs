@@ -0,0 +1,152 @@
+//! Custom chat commands answered with a response template.
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatCommand {
+    pub id: i64,
+    /// Lowercase, including the prefix (`!discord`).
+    pub trigger: String,
+    pub response: String,
+    /// Seconds before the command answers again.
+    pub cooldown_secs: i64,
+    /// Lowest role allowed to use it (`everyone`, `subscriber`, `vip`,
+    /// `moderator`, `broadcaster`).
+    pub permission: String,
+    pub enabled: bool,
+    pub use_count: i64,
+    pub last_used_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Editable fields of a command.
+#[derive(Debug, Clone)]
+pub struct ChatCommandInput<'a> {
+    pub trigger: &'a str,
+    pub response: &'a str,
+    pub cooldown_secs: i64,
+    pub permission: &'a str,
+    pub enabled: bool,
+}
+
+const SELECT: &str = "SELECT id, trigger, response, cooldown_secs, permission, enabled,
+        use_count, last_used_at, created_at, updated_at
+    FROM chat_commands";
+
+fn map_command(row: &rusqlite::Row<'_>) -> rusqlite::Result<ChatCommand> {
+    Ok(ChatCommand {
+        id: row.get(0)?,
+        trigger: row.get(1)?,
+        response: row.get(2)?,
+        cooldown_secs: row.get(3)?,
+        permission: row.get(4)?,
+        enabled: row.get(5)?,
+        use_count: row.get(6)?,
+        last_used_at: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    pub fn add_chat_command(&self, input: &ChatCommandInput<'_>, now: i64) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO chat_commands
+                    (trigger, response, cooldown_secs, permission, enabled, created_at,
+                     updated_at)
+                 VALUES (LOWER(?1), ?2, ?3, ?4, ?5, ?6, ?6)",
+                rusqlite::params![
+                    input.trigger,
+                    input.response,
+                    input.cooldown_secs,
+                    input.permission,
+                    input.enabled,
+                    now,
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    pub fn get_chat_command(&self, id: i64) -> Result<Option<ChatCommand>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(&format!("{SELECT} WHERE id = ?1"), [id], map_command)
+                .optional()
+                .map_err(Into::into)
+        })
+    }
+
+    /// The command for `trigger` (case-insensitive).
+    pub fn get_chat_command_by_trigger(
+        &self,
+        trigger: &str,
+    ) -> Result<Option<ChatCommand>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                &format!("{SELECT} WHERE trigger = LOWER(?1)"),
+                [trigger],
+                map_command,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    pub fn get_chat_commands(&self) -> Result<Vec<ChatCommand>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("{SELECT} ORDER BY trigger ASC"))?;
+            let rows = stmt.query_map([], map_command)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Replace a command's settings, keeping its usage counters. Returns
+    /// false if it does not exist.
+    pub fn update_chat_command(
+        &self,
+        id: i64,
+        input: &ChatCommandInput<'_>,
+        now: i64,
+    ) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE chat_commands SET trigger = LOWER(?2), response = ?3,
+                    cooldown_secs = ?4, permission = ?5, enabled = ?6, updated_at = ?7
+                 WHERE id = ?1",
+                rusqlite::params![
+                    id,
+                    input.trigger,
+                    input.response,
+                    input.cooldown_secs,
+                    input.permission,
+                    input.enabled,
+                    now,
+                ],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    pub fn delete_chat_command(&self, id: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM chat_commands WHERE id = ?1", [id])?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Record a use at `at`.
+    pub fn mark_chat_command_used(&self, id: i64, at: i64) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE chat_commands SET last_used_at = ?2, use_count = use_count + 1
+                 WHERE id = ?1",
+                rusqlite::params![id, at],
+            )?;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,130 @@
+//! Aggregates over stored chat: top chatters, activity per hour and emote
+//! usage.
+//!
+//! Ranges are `from <= created_at < to` in unix seconds. A chatter is
+//! identified by user ID, or by name for messages stored without one.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+/// Expression identifying the author of a `chat_messages` row.
+const CHATTER: &str = "COALESCE(NULLIF(user_id, ''), username)";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatterStat {
+    pub user_id: String,
+    /// Name on the chatter's latest message in the range.
+    pub username: String,
+    pub messages: i64,
+    pub last_message_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HourlyChat {
+    /// Start of the UTC hour, unix seconds.
+    pub hour: i64,
+    pub messages: i64,
+    pub chatters: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmoteUsage {
+    pub emote_id: String,
+    pub name: String,
+    /// Times the emote appeared, counting repeats within a message.
+    pub uses: i64,
+    pub chatters: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatStats {
+    pub messages: i64,
+    pub unique_chatters: i64,
+    /// Most active chatters first, at most `limit`.
+    pub top_chatters: Vec<ChatterStat>,
+    /// Hours with at least one message, oldest first.
+    pub hourly: Vec<HourlyChat>,
+    /// Most used emotes first, at most `limit`.
+    pub emotes: Vec<EmoteUsage>,
+}
+
+impl Database {
+    /// Chat statistics for `[from, to)`, leaving out messages from
+    /// `exclude_user_id` (e.g. the broadcaster; empty excludes nobody).
+    pub fn get_chat_stats(
+        &self,
+        from: i64,
+        to: i64,
+        exclude_user_id: &str,
+        limit: i64,
+    ) -> Result<ChatStats, DbError> {
+        let range = "created_at >= ?1 AND created_at < ?2 AND COALESCE(user_id, '') != ?3";
+        self.with_conn(|conn| {
+            let (messages, unique_chatters) = conn.query_row(
+                &format!("SELECT COUNT(*), COUNT(DISTINCT {CHATTER}) FROM chat_messages WHERE {range}"),
+                rusqlite::params![from, to, exclude_user_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+
+            // The bare columns come from the row holding MAX(created_at).
+            let mut stmt = conn.prepare(&format!(
+                "SELECT COALESCE(user_id, ''), username, COUNT(*), MAX(created_at)
+                 FROM chat_messages WHERE {range}
+                 GROUP BY {CHATTER} ORDER BY COUNT(*) DESC, MAX(created_at) DESC LIMIT ?4"
+            ))?;
+            let top_chatters = stmt
+                .query_map(rusqlite::params![from, to, exclude_user_id, limit], |row| {
+                    Ok(ChatterStat {
+                        user_id: row.get(0)?,
+                        username: row.get(1)?,
+                        messages: row.get(2)?,
+                        last_message_at: row.get(3)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut stmt = conn.prepare(&format!(
+                "SELECT created_at / 3600 * 3600 AS hour, COUNT(*), COUNT(DISTINCT {CHATTER})
+                 FROM chat_messages WHERE {range}
+                 GROUP BY hour ORDER BY hour ASC"
+            ))?;
+            let hourly = stmt
+                .query_map(rusqlite::params![from, to, exclude_user_id], |row| {
+                    Ok(HourlyChat {
+                        hour: row.get(0)?,
+                        messages: row.get(1)?,
+                        chatters: row.get(2)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // Emotes are the `emote` fragments of the stored EventSub message.
+            let mut stmt = conn.prepare(&format!(
+                "SELECT json_extract(f.value, '$.emote.id') AS emote_id,
+                        MAX(json_extract(f.value, '$.text')), COUNT(*), COUNT(DISTINCT {CHATTER})
+                 FROM chat_messages,
+                      json_each(CASE WHEN json_valid(fragments_json) THEN fragments_json ELSE '[]' END) f
+                 WHERE {range} AND json_extract(f.value, '$.type') = 'emote' AND emote_id IS NOT NULL
+                 GROUP BY emote_id ORDER BY COUNT(*) DESC, emote_id ASC LIMIT ?4"
+            ))?;
+            let emotes = stmt
+                .query_map(rusqlite::params![from, to, exclude_user_id, limit], |row| {
+                    Ok(EmoteUsage {
+                        emote_id: row.get(0)?,
+                        name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                        uses: row.get(2)?,
+                        chatters: row.get(3)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(ChatStats {
+                messages,
+                unique_chatters,
+                top_chatters,
+                hourly,
+                emotes,
+            })
+        })
+    }
+}
@@ -0,0 +1,164 @@
+//! Timed chat messages (e.g. a Discord link every 20 minutes while live).
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatTimer {
+    pub id: i64,
+    pub name: String,
+    /// Posted in turn, one per interval.
+    pub messages: Vec<String>,
+    pub interval_minutes: i64,
+    /// Chat messages from viewers required since the last post.
+    pub min_chat_messages: i64,
+    pub enabled: bool,
+    /// Post as an announcement rather than a plain message.
+    #[serde(default)]
+    pub announce: bool,
+    /// Announcement color (`primary`, `blue`, `green`, `orange`, `purple`).
+    #[serde(default)]
+    pub announce_color: String,
+    /// Index into `messages` of the next post.
+    pub next_index: i64,
+    pub last_sent_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl ChatTimer {
+    /// The message posted next, if the timer has any.
+    pub fn next_message(&self) -> Option<&str> {
+        if self.messages.is_empty() {
+            return None;
+        }
+        let index = self.next_index.rem_euclid(self.messages.len() as i64) as usize;
+        Some(&self.messages[index])
+    }
+}
+
+/// Editable fields of a timer.
+#[derive(Debug, Clone)]
+pub struct ChatTimerInput<'a> {
+    pub name: &'a str,
+    pub messages: &'a [String],
+    pub interval_minutes: i64,
+    pub min_chat_messages: i64,
+    pub enabled: bool,
+    pub announce: bool,
+    pub announce_color: &'a str,
+}
+
+const SELECT: &str = "SELECT id, name, messages_json, interval_minutes, min_chat_messages,
+        enabled, next_index, last_sent_at, created_at, updated_at, announce, announce_color
+    FROM chat_timers";
+
+fn map_timer(row: &rusqlite::Row<'_>) -> rusqlite::Result<ChatTimer> {
+    let messages: String = row.get(2)?;
+    Ok(ChatTimer {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        messages: serde_json::from_str(&messages).unwrap_or_default(),
+        interval_minutes: row.get(3)?,
+        min_chat_messages: row.get(4)?,
+        enabled: row.get(5)?,
+        next_index: row.get(6)?,
+        last_sent_at: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+        announce: row.get(10)?,
+        announce_color: row.get(11)?,
+    })
+}
+
+impl Database {
+    pub fn add_chat_timer(&self, input: &ChatTimerInput<'_>, now: i64) -> Result<i64, DbError> {
+        let messages = serde_json::to_string(input.messages).unwrap_or_else(|_| "[]".into());
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO chat_timers
+                    (name, messages_json, interval_minutes, min_chat_messages, enabled,
+                     announce, announce_color, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+                rusqlite::params![
+                    input.name,
+                    messages,
+                    input.interval_minutes,
+                    input.min_chat_messages,
+                    input.enabled,
+                    input.announce,
+                    input.announce_color,
+                    now,
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    pub fn get_chat_timer(&self, id: i64) -> Result<Option<ChatTimer>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(&format!("{SELECT} WHERE id = ?1"), [id], map_timer)
+                .optional()
+                .map_err(Into::into)
+        })
+    }
+
+    pub fn get_chat_timers(&self) -> Result<Vec<ChatTimer>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("{SELECT} ORDER BY name ASC, id ASC"))?;
+            let rows = stmt.query_map([], map_timer)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Replace a timer's settings, keeping its rotation position. Returns
+    /// false if it does not exist.
+    pub fn update_chat_timer(
+        &self,
+        id: i64,
+        input: &ChatTimerInput<'_>,
+        now: i64,
+    ) -> Result<bool, DbError> {
+        let messages = serde_json::to_string(input.messages).unwrap_or_else(|_| "[]".into());
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE chat_timers SET name = ?2, messages_json = ?3, interval_minutes = ?4,
+                    min_chat_messages = ?5, enabled = ?6, announce = ?7, announce_color = ?8,
+                    updated_at = ?9
+                 WHERE id = ?1",
+                rusqlite::params![
+                    id,
+                    input.name,
+                    messages,
+                    input.interval_minutes,
+                    input.min_chat_messages,
+                    input.enabled,
+                    input.announce,
+                    input.announce_color,
+                    now,
+                ],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    pub fn delete_chat_timer(&self, id: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM chat_timers WHERE id = ?1", [id])?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Record a post at `at` and advance the rotation.
+    pub fn mark_chat_timer_sent(&self, id: i64, at: i64) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE chat_timers SET last_sent_at = ?2, next_index = next_index + 1
+                 WHERE id = ?1",
+                rusqlite::params![id, at],
+            )?;
+            Ok(())
+        })
+    }
+}
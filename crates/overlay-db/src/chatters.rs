@@ -0,0 +1,67 @@
+//! First-message tracking: whether a chatter has ever chatted before, and
+//! whether they have chatted in the open stream session.
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+/// What a message was a first of.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirstChat {
+    /// The chatter's first message ever seen.
+    pub first_ever: bool,
+    /// Their first message in the open stream session; always false while
+    /// no session is open.
+    pub first_this_stream: bool,
+    pub session_id: Option<i64>,
+}
+
+impl FirstChat {
+    pub fn any(&self) -> bool {
+        self.first_ever || self.first_this_stream
+    }
+}
+
+impl Database {
+    /// Record a message from `user_id` at `now` and report which firsts it is.
+    pub fn record_chatter(
+        &self,
+        user_id: &str,
+        username: &str,
+        now: i64,
+    ) -> Result<FirstChat, DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let first_ever = tx.execute(
+                "INSERT INTO chatters (user_id, username, first_seen_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(user_id) DO NOTHING",
+                rusqlite::params![user_id, username, now],
+            )? > 0;
+            let session_id: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM stream_sessions WHERE ended_at IS NULL
+                     ORDER BY started_at DESC LIMIT 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let first_this_stream = match session_id {
+                Some(session_id) => {
+                    tx.execute(
+                        "INSERT INTO session_chatters (session_id, user_id, first_message_at)
+                         VALUES (?1, ?2, ?3)
+                         ON CONFLICT(session_id, user_id) DO NOTHING",
+                        rusqlite::params![session_id, user_id, now],
+                    )? > 0
+                }
+                None => false,
+            };
+            tx.commit()?;
+            Ok(FirstChat {
+                first_ever,
+                first_this_stream,
+                session_id,
+            })
+        })
+    }
+}
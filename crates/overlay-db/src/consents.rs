@@ -0,0 +1,134 @@
+//! Viewer consent registry: whether a viewer's messages may be printed,
+//! read out by TTS or shown in credits.
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+/// What a viewer consents to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsentScope {
+    Print,
+    Tts,
+    Credits,
+}
+
+impl ConsentScope {
+    pub const ALL: [ConsentScope; 3] = [Self::Print, Self::Tts, Self::Credits];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Print => "print",
+            Self::Tts => "tts",
+            Self::Credits => "credits",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|scope| scope.as_str().eq_ignore_ascii_case(s))
+    }
+}
+
+/// A viewer's choices; `None` means the default policy applies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewerConsent {
+    pub user_id: String,
+    pub username: String,
+    pub print: Option<bool>,
+    pub tts: Option<bool>,
+    pub credits: Option<bool>,
+    pub source: String,
+    pub updated_at: i64,
+}
+
+impl ViewerConsent {
+    pub fn get(&self, scope: ConsentScope) -> Option<bool> {
+        match scope {
+            ConsentScope::Print => self.print,
+            ConsentScope::Tts => self.tts,
+            ConsentScope::Credits => self.credits,
+        }
+    }
+}
+
+const SELECT: &str =
+    "SELECT user_id, username, print, tts, credits, source, updated_at FROM viewer_consents";
+
+fn map_consent(row: &rusqlite::Row<'_>) -> rusqlite::Result<ViewerConsent> {
+    Ok(ViewerConsent {
+        user_id: row.get(0)?,
+        username: row.get(1)?,
+        print: row.get(2)?,
+        tts: row.get(3)?,
+        credits: row.get(4)?,
+        source: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+impl Database {
+    /// Set (or with `None`, clear) one scope for a viewer. An empty
+    /// `username` keeps the stored one.
+    pub fn set_consent(
+        &self,
+        user_id: &str,
+        username: &str,
+        scope: ConsentScope,
+        allowed: Option<bool>,
+        source: &str,
+        now: i64,
+    ) -> Result<(), DbError> {
+        let column = scope.as_str();
+        self.with_conn(|conn| {
+            conn.execute(
+                &format!(
+                    "INSERT INTO viewer_consents (user_id, username, {column}, source, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT(user_id) DO UPDATE SET
+                         username = CASE WHEN excluded.username = '' THEN username
+                                         ELSE excluded.username END,
+                         {column} = excluded.{column},
+                         source = excluded.source,
+                         updated_at = excluded.updated_at"
+                ),
+                rusqlite::params![user_id, username, allowed, source, now],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_consent(&self, user_id: &str) -> Result<Option<ViewerConsent>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                &format!("{SELECT} WHERE user_id = ?1"),
+                [user_id],
+                map_consent,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    /// All recorded viewers, most recently changed first; `search` filters
+    /// on the name.
+    pub fn get_consents(&self, search: Option<&str>) -> Result<Vec<ViewerConsent>, DbError> {
+        self.with_conn(|conn| {
+            let pattern = crate::like_contains(search.unwrap_or_default());
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT} WHERE username LIKE ?1 ESCAPE '\\' ORDER BY updated_at DESC, user_id ASC"
+            ))?;
+            let rows = stmt.query_map([pattern], map_consent)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    pub fn delete_consent(&self, user_id: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM viewer_consents WHERE user_id = ?1", [user_id])?;
+            Ok(n > 0)
+        })
+    }
+}
@@ -0,0 +1,85 @@
+//! Customized Discord notification embeds, one per event.
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscordTemplate {
+    pub event: String,
+    pub enabled: bool,
+    pub title: String,
+    pub description: String,
+    /// Embed color as `0xRRGGBB`.
+    pub color: i64,
+    pub updated_at: i64,
+}
+
+const SELECT_TEMPLATE: &str =
+    "SELECT event, enabled, title, description, color, updated_at FROM discord_templates";
+
+fn map_template(row: &rusqlite::Row<'_>) -> rusqlite::Result<DiscordTemplate> {
+    Ok(DiscordTemplate {
+        event: row.get(0)?,
+        enabled: row.get(1)?,
+        title: row.get(2)?,
+        description: row.get(3)?,
+        color: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+impl Database {
+    pub fn get_discord_templates(&self) -> Result<Vec<DiscordTemplate>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("{SELECT_TEMPLATE} ORDER BY event"))?;
+            let rows = stmt.query_map([], map_template)?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+    }
+
+    pub fn get_discord_template(&self, event: &str) -> Result<Option<DiscordTemplate>, DbError> {
+        self.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    &format!("{SELECT_TEMPLATE} WHERE event = ?1"),
+                    [event],
+                    map_template,
+                )
+                .optional()?)
+        })
+    }
+
+    /// Insert or replace the template of `template.event`.
+    pub fn upsert_discord_template(&self, template: &DiscordTemplate) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO discord_templates
+                    (event, enabled, title, description, color, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(event) DO UPDATE SET
+                    enabled = excluded.enabled,
+                    title = excluded.title,
+                    description = excluded.description,
+                    color = excluded.color,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![
+                    template.event,
+                    template.enabled,
+                    template.title,
+                    template.description,
+                    template.color,
+                    template.updated_at,
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Drop the customization of `event`; returns false if there was none.
+    pub fn delete_discord_template(&self, event: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            Ok(conn.execute("DELETE FROM discord_templates WHERE event = ?1", [event])? > 0)
+        })
+    }
+}
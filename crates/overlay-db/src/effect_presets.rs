@@ -0,0 +1,84 @@
+//! Named overlay visual effect presets.
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectPreset {
+    pub name: String,
+    pub effect: String,
+    pub params: Value,
+    pub duration_ms: i64,
+    pub updated_at: i64,
+}
+
+fn row_to_preset(row: &rusqlite::Row<'_>) -> rusqlite::Result<EffectPreset> {
+    let params: String = row.get(2)?;
+    Ok(EffectPreset {
+        name: row.get(0)?,
+        effect: row.get(1)?,
+        params: serde_json::from_str(&params).unwrap_or(Value::Null),
+        duration_ms: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+impl Database {
+    pub fn get_effect_presets(&self) -> Result<Vec<EffectPreset>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT name, effect, params, duration_ms, updated_at FROM effect_presets
+                 ORDER BY name ASC",
+            )?;
+            let rows = stmt.query_map([], row_to_preset)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    pub fn get_effect_preset(&self, name: &str) -> Result<Option<EffectPreset>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT name, effect, params, duration_ms, updated_at FROM effect_presets
+                 WHERE name = ?1",
+                [name],
+                row_to_preset,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    /// Create or replace a preset.
+    pub fn upsert_effect_preset(
+        &self,
+        name: &str,
+        effect: &str,
+        params: &Value,
+        duration_ms: i64,
+        now: i64,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO effect_presets (name, effect, params, duration_ms, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(name) DO UPDATE SET
+                    effect = excluded.effect,
+                    params = excluded.params,
+                    duration_ms = excluded.duration_ms,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![name, effect, params.to_string(), duration_ms, now],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Delete a preset. Returns false if it does not exist.
+    pub fn delete_effect_preset(&self, name: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM effect_presets WHERE name = ?1", [name])?;
+            Ok(n > 0)
+        })
+    }
+}
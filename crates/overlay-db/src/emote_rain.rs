@@ -0,0 +1,97 @@
+//! Emote rain cooldowns: last trigger time and optional per-emote cooldown.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmoteRainCooldown {
+    pub emote_id: String,
+    pub emote_name: String,
+    /// Per-emote cooldown; `None` uses the global setting.
+    pub cooldown_seconds: Option<i64>,
+    pub last_fired_at: i64,
+    pub fire_count: i64,
+}
+
+impl Database {
+    /// Record an emote rain if the emote is off cooldown. Returns false
+    /// (and changes nothing) while it is still cooling down.
+    pub fn try_fire_emote_rain(
+        &self,
+        emote_id: &str,
+        emote_name: &str,
+        default_cooldown: i64,
+        now: i64,
+    ) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO emote_rain_cooldowns (emote_id, emote_name) VALUES (?1, ?2)
+                 ON CONFLICT(emote_id) DO UPDATE SET
+                    emote_name = CASE WHEN excluded.emote_name != '' THEN excluded.emote_name
+                                      ELSE emote_rain_cooldowns.emote_name END",
+                rusqlite::params![emote_id, emote_name],
+            )?;
+            let n = conn.execute(
+                "UPDATE emote_rain_cooldowns
+                 SET last_fired_at = ?3, fire_count = fire_count + 1
+                 WHERE emote_id = ?1
+                   AND (fire_count = 0 OR last_fired_at + COALESCE(cooldown_seconds, ?2) <= ?3)",
+                rusqlite::params![emote_id, default_cooldown, now],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    /// All emotes that have rained or have a custom cooldown, most recent first.
+    pub fn get_emote_rain_cooldowns(&self) -> Result<Vec<EmoteRainCooldown>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT emote_id, emote_name, cooldown_seconds, last_fired_at, fire_count
+                 FROM emote_rain_cooldowns
+                 ORDER BY last_fired_at DESC, emote_name ASC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(EmoteRainCooldown {
+                    emote_id: row.get(0)?,
+                    emote_name: row.get(1)?,
+                    cooldown_seconds: row.get(2)?,
+                    last_fired_at: row.get(3)?,
+                    fire_count: row.get(4)?,
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Set (or clear with `None`) the per-emote cooldown.
+    pub fn set_emote_rain_cooldown(
+        &self,
+        emote_id: &str,
+        emote_name: &str,
+        cooldown_seconds: Option<i64>,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO emote_rain_cooldowns (emote_id, emote_name, cooldown_seconds)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(emote_id) DO UPDATE SET
+                    cooldown_seconds = excluded.cooldown_seconds,
+                    emote_name = CASE WHEN excluded.emote_name != '' THEN excluded.emote_name
+                                      ELSE emote_rain_cooldowns.emote_name END",
+                rusqlite::params![emote_id, emote_name, cooldown_seconds],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Forget an emote (its cooldown and history).
+    pub fn delete_emote_rain_cooldown(&self, emote_id: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "DELETE FROM emote_rain_cooldowns WHERE emote_id = ?1",
+                [emote_id],
+            )?;
+            Ok(n > 0)
+        })
+    }
+}
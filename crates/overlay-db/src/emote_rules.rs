@@ -0,0 +1,93 @@
+//! Per-emote print rules (allow/deny) and the approval queue.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_ALLOWED: &str = "allowed";
+pub const STATUS_DENIED: &str = "denied";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmotePrintRule {
+    pub emote_id: String,
+    pub emote_name: String,
+    pub status: String,
+    pub first_seen_at: i64,
+    pub updated_at: i64,
+}
+
+impl Database {
+    /// Register an emote seen in printed content and return its status.
+    /// Unknown emotes are added to the approval queue as `pending`.
+    pub fn observe_emote(&self, emote_id: &str, name: &str, now: i64) -> Result<String, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO emote_print_rules (emote_id, emote_name, status, first_seen_at, updated_at)
+                 VALUES (?1, ?2, 'pending', ?3, ?3)
+                 ON CONFLICT(emote_id) DO UPDATE SET
+                    emote_name = CASE WHEN excluded.emote_name != '' THEN excluded.emote_name
+                                      ELSE emote_print_rules.emote_name END",
+                rusqlite::params![emote_id, name, now],
+            )?;
+            let status = conn.query_row(
+                "SELECT status FROM emote_print_rules WHERE emote_id = ?1",
+                [emote_id],
+                |row| row.get::<_, String>(0),
+            )?;
+            Ok(status)
+        })
+    }
+
+    /// List rules, optionally filtered by status, newest first.
+    pub fn get_emote_print_rules(
+        &self,
+        status: Option<&str>,
+    ) -> Result<Vec<EmotePrintRule>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT emote_id, emote_name, status, first_seen_at, updated_at
+                 FROM emote_print_rules
+                 WHERE ?1 IS NULL OR status = ?1
+                 ORDER BY first_seen_at DESC",
+            )?;
+            let rows = stmt.query_map([status], |row| {
+                Ok(EmotePrintRule {
+                    emote_id: row.get(0)?,
+                    emote_name: row.get(1)?,
+                    status: row.get(2)?,
+                    first_seen_at: row.get(3)?,
+                    updated_at: row.get(4)?,
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Set the status of an emote (creating the rule if missing).
+    pub fn set_emote_print_status(
+        &self,
+        emote_id: &str,
+        name: &str,
+        status: &str,
+        now: i64,
+    ) -> Result<(), DbError> {
+        if ![STATUS_PENDING, STATUS_ALLOWED, STATUS_DENIED].contains(&status) {
+            return Err(DbError::InvalidData(format!(
+                "invalid emote status: {status}"
+            )));
+        }
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO emote_print_rules (emote_id, emote_name, status, first_seen_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?4)
+                 ON CONFLICT(emote_id) DO UPDATE SET
+                    status = excluded.status,
+                    emote_name = CASE WHEN excluded.emote_name != '' THEN excluded.emote_name
+                                      ELSE emote_print_rules.emote_name END,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![emote_id, name, status, now],
+            )?;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,116 @@
+//! Raw EventSub notification archive.
+
+use crate::{Database, DbError, like_contains};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedEvent {
+    pub id: i64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub received_at: i64,
+}
+
+/// Row count and approximate payload size of the archive.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EventArchiveStats {
+    pub events: i64,
+    pub bytes: i64,
+}
+
+fn map_event(row: &rusqlite::Row<'_>) -> rusqlite::Result<ArchivedEvent> {
+    let payload: String = row.get(2)?;
+    Ok(ArchivedEvent {
+        id: row.get(0)?,
+        event_type: row.get(1)?,
+        payload: serde_json::from_str(&payload).unwrap_or_default(),
+        received_at: row.get(3)?,
+    })
+}
+
+impl Database {
+    pub fn archive_event(
+        &self,
+        event_type: &str,
+        payload: &serde_json::Value,
+        received_at: i64,
+    ) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO event_archive (event_type, payload, received_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![event_type, payload.to_string(), received_at],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    /// Archived events at or after `since`, oldest first, optionally of one
+    /// type.
+    pub fn get_archived_events(
+        &self,
+        event_type: Option<&str>,
+        since: i64,
+        limit: i64,
+    ) -> Result<Vec<ArchivedEvent>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, event_type, payload, received_at FROM event_archive
+                 WHERE received_at >= ?1 AND (?2 IS NULL OR event_type = ?2)
+                 ORDER BY received_at ASC, id ASC LIMIT ?3",
+            )?;
+            let rows = stmt.query_map(rusqlite::params![since, event_type, limit], map_event)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Archived events whose type or payload contains `query`, newest first.
+    pub fn search_archived_events(
+        &self,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<ArchivedEvent>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, event_type, payload, received_at FROM event_archive
+                 WHERE event_type LIKE ?1 ESCAPE '\\' OR payload LIKE ?1 ESCAPE '\\'
+                 ORDER BY received_at DESC, id DESC LIMIT ?2",
+            )?;
+            let rows = stmt.query_map(rusqlite::params![like_contains(query), limit], map_event)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    pub fn get_event_archive_stats(&self) -> Result<EventArchiveStats, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT COUNT(*), COALESCE(SUM(LENGTH(payload)), 0) FROM event_archive",
+                [],
+                |row| {
+                    Ok(EventArchiveStats {
+                        events: row.get(0)?,
+                        bytes: row.get(1)?,
+                    })
+                },
+            )
+            .map_err(Into::into)
+        })
+    }
+
+    /// Delete the oldest events until the payloads fit in `max_bytes`.
+    /// Returns the number of deleted rows.
+    pub fn prune_event_archive(&self, max_bytes: i64) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            // Keep the newest rows whose running payload total fits.
+            let n = conn.execute(
+                "DELETE FROM event_archive WHERE id IN (
+                     SELECT id FROM (
+                         SELECT id, SUM(LENGTH(payload)) OVER (ORDER BY id DESC) AS total
+                         FROM event_archive
+                     ) WHERE total > ?1
+                 )",
+                [max_bytes],
+            )?;
+            Ok(n)
+        })
+    }
+}
@@ -0,0 +1,132 @@
+//! Event-to-output mappings for external integrations (OSC, MIDI, lights).
+//!
+//! Each trigger maps a channel event (optionally above an amount threshold,
+//! e.g. cheer bits) to an integration-specific JSON payload. When several
+//! thresholds match, only the triggers of the highest one fire, so "100+
+//! bits" and "1000+ bits" can map to different reactions.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventTrigger {
+    pub id: i64,
+    pub integration: String,
+    pub event: String,
+    pub min_amount: i64,
+    pub params: Value,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, integration, event, min_amount, params, enabled, created_at FROM event_triggers";
+
+fn row_to_trigger(row: &rusqlite::Row<'_>) -> rusqlite::Result<EventTrigger> {
+    let params: String = row.get(4)?;
+    Ok(EventTrigger {
+        id: row.get(0)?,
+        integration: row.get(1)?,
+        event: row.get(2)?,
+        min_amount: row.get(3)?,
+        params: serde_json::from_str(&params).unwrap_or(Value::Null),
+        enabled: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+impl Database {
+    /// List all triggers of an integration, grouped by event and threshold.
+    pub fn get_event_triggers(&self, integration: &str) -> Result<Vec<EventTrigger>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_COLUMNS} WHERE integration = ?1 ORDER BY event ASC, min_amount ASC, id ASC"
+            ))?;
+            let rows = stmt.query_map([integration], row_to_trigger)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Enabled triggers for an event at the highest threshold `amount` reaches.
+    pub fn get_matching_event_triggers(
+        &self,
+        integration: &str,
+        event: &str,
+        amount: i64,
+    ) -> Result<Vec<EventTrigger>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_COLUMNS}
+                 WHERE integration = ?1 AND event = ?2 AND enabled = 1
+                   AND min_amount = (
+                       SELECT MAX(min_amount) FROM event_triggers
+                       WHERE integration = ?1 AND event = ?2 AND enabled = 1 AND min_amount <= ?3
+                   )
+                 ORDER BY id ASC"
+            ))?;
+            let rows = stmt.query_map(
+                rusqlite::params![integration, event, amount],
+                row_to_trigger,
+            )?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Add a trigger and return its ID.
+    pub fn add_event_trigger(
+        &self,
+        integration: &str,
+        event: &str,
+        min_amount: i64,
+        params: &Value,
+        now: i64,
+    ) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO event_triggers (integration, event, min_amount, params, enabled, created_at)
+                 VALUES (?1, ?2, ?3, ?4, 1, ?5)",
+                rusqlite::params![integration, event, min_amount, params.to_string(), now],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    /// Update a trigger. Returns false if it does not exist.
+    pub fn update_event_trigger(
+        &self,
+        id: i64,
+        integration: &str,
+        event: &str,
+        min_amount: i64,
+        params: &Value,
+        enabled: bool,
+    ) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE event_triggers SET event = ?2, min_amount = ?3, params = ?4, enabled = ?5
+                 WHERE id = ?1 AND integration = ?6",
+                rusqlite::params![
+                    id,
+                    event,
+                    min_amount,
+                    params.to_string(),
+                    enabled,
+                    integration
+                ],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Delete a trigger. Returns false if it does not exist.
+    pub fn delete_event_trigger(&self, id: i64, integration: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "DELETE FROM event_triggers WHERE id = ?1 AND integration = ?2",
+                rusqlite::params![id, integration],
+            )?;
+            Ok(n > 0)
+        })
+    }
+}
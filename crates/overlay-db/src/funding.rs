@@ -0,0 +1,122 @@
+//! Funding goal contributions (bits, gifted subs, donations, adjustments).
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+pub const SOURCE_BITS: &str = "bits";
+pub const SOURCE_GIFT_SUBS: &str = "gift_subs";
+pub const SOURCE_DONATION: &str = "donation";
+pub const SOURCE_ADJUSTMENT: &str = "adjustment";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingEntry {
+    pub id: i64,
+    pub source: String,
+    pub quantity: f64,
+    pub value: f64,
+    pub username: String,
+    pub note: String,
+    pub created_at: i64,
+}
+
+/// Summed contribution value per source.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FundingTotals {
+    pub bits: f64,
+    pub gift_subs: f64,
+    pub donations: f64,
+    pub adjustments: f64,
+}
+
+impl FundingTotals {
+    pub fn total(&self) -> f64 {
+        self.bits + self.gift_subs + self.donations + self.adjustments
+    }
+}
+
+const SELECT: &str =
+    "SELECT id, source, quantity, value, username, note, created_at FROM funding_entries";
+
+fn map_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<FundingEntry> {
+    Ok(FundingEntry {
+        id: row.get(0)?,
+        source: row.get(1)?,
+        quantity: row.get(2)?,
+        value: row.get(3)?,
+        username: row.get(4)?,
+        note: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+impl Database {
+    pub fn add_funding_entry(
+        &self,
+        source: &str,
+        quantity: f64,
+        value: f64,
+        username: &str,
+        note: &str,
+        now: i64,
+    ) -> Result<FundingEntry, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO funding_entries (source, quantity, value, username, note, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![source, quantity, value, username, note, now],
+            )?;
+            Ok(FundingEntry {
+                id: conn.last_insert_rowid(),
+                source: source.to_string(),
+                quantity,
+                value,
+                username: username.to_string(),
+                note: note.to_string(),
+                created_at: now,
+            })
+        })
+    }
+
+    /// Totals of entries recorded at or after `since`.
+    pub fn get_funding_totals(&self, since: i64) -> Result<FundingTotals, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT source, SUM(value) FROM funding_entries
+                 WHERE created_at >= ?1 GROUP BY source",
+            )?;
+            let rows = stmt.query_map([since], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+            })?;
+            let mut totals = FundingTotals::default();
+            for row in rows {
+                let (source, sum) = row?;
+                match source.as_str() {
+                    SOURCE_BITS => totals.bits = sum,
+                    SOURCE_GIFT_SUBS => totals.gift_subs = sum,
+                    SOURCE_DONATION => totals.donations = sum,
+                    SOURCE_ADJUSTMENT => totals.adjustments = sum,
+                    _ => {}
+                }
+            }
+            Ok(totals)
+        })
+    }
+
+    /// Entries recorded at or after `since`, newest first, optionally of
+    /// one source.
+    pub fn get_funding_entries(
+        &self,
+        since: i64,
+        source: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<FundingEntry>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT} WHERE created_at >= ?1 AND (?2 IS NULL OR source = ?2)
+                 ORDER BY created_at DESC, id DESC LIMIT ?3"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params![since, source, limit], map_entry)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+}
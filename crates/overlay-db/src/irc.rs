@@ -0,0 +1,97 @@
+//! Chat of other channels received through the IRC relay.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IrcChatMessage {
+    pub id: i64,
+    /// Channel login, without `#`.
+    pub channel: String,
+    pub message_id: String,
+    pub user_id: String,
+    pub login: String,
+    pub display_name: String,
+    pub message: String,
+    /// Name color (`#RRGGBB`), empty when unset.
+    pub color: String,
+    /// IRC `badges` tag (`moderator/1,subscriber/12`).
+    pub badges: String,
+    pub created_at: i64,
+}
+
+fn map_message(row: &rusqlite::Row<'_>) -> rusqlite::Result<IrcChatMessage> {
+    Ok(IrcChatMessage {
+        id: row.get(0)?,
+        channel: row.get(1)?,
+        message_id: row.get(2)?,
+        user_id: row.get(3)?,
+        login: row.get(4)?,
+        display_name: row.get(5)?,
+        message: row.get(6)?,
+        color: row.get(7)?,
+        badges: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    /// Store `msg`; its `id` is ignored. Returns the new row ID, or `None`
+    /// for a message ID that is already stored.
+    pub fn add_irc_chat_message(&self, msg: &IrcChatMessage) -> Result<Option<i64>, DbError> {
+        self.with_conn(|conn| {
+            let changed = conn.execute(
+                "INSERT OR IGNORE INTO irc_chat_messages
+                    (channel, message_id, user_id, login, display_name, message, color,
+                     badges, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    msg.channel,
+                    msg.message_id,
+                    msg.user_id,
+                    msg.login,
+                    msg.display_name,
+                    msg.message,
+                    msg.color,
+                    msg.badges,
+                    msg.created_at,
+                ],
+            )?;
+            Ok((changed > 0).then(|| conn.last_insert_rowid()))
+        })
+    }
+
+    /// The latest `limit` messages since `since_unix`, oldest first; all
+    /// channels when `channel` is `None`.
+    pub fn get_irc_chat_messages(
+        &self,
+        channel: Option<&str>,
+        since_unix: i64,
+        limit: i64,
+    ) -> Result<Vec<IrcChatMessage>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT * FROM (
+                     SELECT id, channel, message_id, user_id, login, display_name, message,
+                            color, badges, created_at
+                     FROM irc_chat_messages
+                     WHERE (?1 IS NULL OR channel = LOWER(?1)) AND created_at >= ?2
+                     ORDER BY created_at DESC, id DESC LIMIT ?3
+                 ) ORDER BY created_at ASC, id ASC",
+            )?;
+            let rows =
+                stmt.query_map(rusqlite::params![channel, since_unix, limit], map_message)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    pub fn cleanup_irc_chat_messages_before(&self, cutoff_unix: i64) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM irc_chat_messages WHERE created_at < ?1",
+                [cutoff_unix],
+            )
+            .map_err(Into::into)
+        })
+    }
+}
@@ -0,0 +1,228 @@
+//! Import data from the legacy Go implementation's SQLite database.
+//!
+//! The legacy file is attached read-only and copied table by table in one
+//! transaction, so a failed import leaves nothing behind. Columns are
+//! matched by name, so older layouts with missing or extra columns still
+//! import whatever overlaps. Existing rows are never overwritten except for
+//! settings, where the legacy value wins unless it is empty; a setting keeps
+//! its `setting_type` (secret or normal) unless the legacy row has one.
+//! Chat messages are de-duplicated by `message_id`, also within the legacy
+//! database; rows without one (NULL or empty) are always appended.
+
+use std::io::Read;
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::{Database, DbError};
+
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Per-table result of a legacy import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyTableImport {
+    pub table: String,
+    /// Table name found in the legacy database, if any.
+    pub source_table: Option<String>,
+    pub source_rows: usize,
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+/// Summary of a legacy import, one entry per target table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyImportSummary {
+    pub tables: Vec<LegacyTableImport>,
+}
+
+/// How rows are merged into a target table.
+#[derive(Clone, Copy)]
+enum Merge {
+    /// Keep existing rows, add missing ones.
+    InsertOrIgnore,
+    /// Legacy rows update existing ones (skipping empty values); columns
+    /// the legacy table lacks keep their current values.
+    Settings,
+    /// Only import when the target table is empty.
+    IfEmpty,
+    /// Append rows whose `dedup_key` is not present yet.
+    Append { dedup_key: &'static str },
+}
+
+struct TableSpec {
+    target: &'static str,
+    /// Candidate table names in the legacy database, most likely first.
+    sources: &'static [&'static str],
+    merge: Merge,
+}
+
+const TABLES: &[TableSpec] = &[
+    TableSpec {
+        target: "settings",
+        sources: &["settings"],
+        merge: Merge::Settings,
+    },
+    TableSpec {
+        target: "tokens",
+        sources: &["tokens", "token"],
+        merge: Merge::IfEmpty,
+    },
+    TableSpec {
+        target: "reward_redemption_counts",
+        sources: &["reward_redemption_counts", "reward_counts"],
+        merge: Merge::InsertOrIgnore,
+    },
+    TableSpec {
+        target: "chat_messages",
+        sources: &["chat_messages", "chat_history"],
+        merge: Merge::Append {
+            dedup_key: "message_id",
+        },
+    },
+];
+
+impl Database {
+    /// Import tokens, settings, reward counts and chat history from a legacy
+    /// (Go version) SQLite database.
+    pub fn import_legacy(&self, path: impl AsRef<Path>) -> Result<LegacyImportSummary, DbError> {
+        let path = path.as_ref();
+        let mut header = [0u8; 16];
+        std::fs::File::open(path)
+            .and_then(|mut f| f.read_exact(&mut header))
+            .map_err(|e| DbError::InvalidData(format!("{}: {e}", path.display())))?;
+        if &header != SQLITE_HEADER {
+            return Err(DbError::InvalidData(
+                "not a SQLite database (BoltDB files are not supported)".into(),
+            ));
+        }
+
+        let escaped = path
+            .to_string_lossy()
+            .replace('%', "%25")
+            .replace('?', "%3f")
+            .replace('#', "%23");
+        let uri = format!("file:{escaped}?mode=ro");
+        self.with_conn_mut(|conn| {
+            // ATTACH is not allowed inside a transaction.
+            conn.execute("ATTACH DATABASE ?1 AS legacy", [uri])?;
+            let result = conn.transaction().map_err(DbError::from).and_then(|tx| {
+                let summary = import_tables(&tx)?;
+                tx.commit()?;
+                Ok(summary)
+            });
+            // Keep the import's own error if detaching fails too.
+            if let Err(e) = conn.execute("DETACH DATABASE legacy", []) {
+                tracing::warn!("Failed to detach legacy database: {e}");
+            }
+            result
+        })
+    }
+}
+
+fn import_tables(conn: &Connection) -> Result<LegacyImportSummary, DbError> {
+    let mut tables = Vec::new();
+    for spec in TABLES {
+        tables.push(import_table(conn, spec)?);
+    }
+    Ok(LegacyImportSummary { tables })
+}
+
+fn import_table(conn: &Connection, spec: &TableSpec) -> Result<LegacyTableImport, DbError> {
+    let mut summary = LegacyTableImport {
+        table: spec.target.to_string(),
+        source_table: None,
+        source_rows: 0,
+        imported: 0,
+        skipped: 0,
+    };
+
+    let Some(source) = find_source_table(conn, spec.sources)? else {
+        return Ok(summary);
+    };
+    summary.source_rows = conn.query_row(
+        &format!("SELECT COUNT(*) FROM legacy.\"{source}\""),
+        [],
+        |r| r.get::<_, i64>(0),
+    )? as usize;
+    summary.source_table = Some(source.clone());
+
+    let target_cols = columns(conn, "main", spec.target)?;
+    let source_cols = columns(conn, "legacy", &source)?;
+    let shared: Vec<String> = target_cols
+        .into_iter()
+        // Surrogate keys would collide with existing rows.
+        .filter(|c| !(c == "id" && matches!(spec.merge, Merge::Append { .. })))
+        .filter(|c| source_cols.contains(c))
+        .collect();
+    if shared.is_empty() {
+        summary.skipped = summary.source_rows;
+        return Ok(summary);
+    }
+    let cols = shared
+        .iter()
+        .map(|c| format!("\"{c}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let sql = match spec.merge {
+        Merge::InsertOrIgnore => format!(
+            "INSERT OR IGNORE INTO main.{t} ({cols}) SELECT {cols} FROM legacy.\"{source}\"",
+            t = spec.target
+        ),
+        Merge::Settings => {
+            let updates = shared
+                .iter()
+                .filter(|c| *c != "key")
+                .map(|c| format!("\"{c}\" = excluded.\"{c}\""))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "INSERT INTO main.{t} ({cols}) SELECT {cols} FROM legacy.\"{source}\"
+                 WHERE value IS NOT NULL AND value != ''
+                 ON CONFLICT(key) DO UPDATE SET {updates}",
+                t = spec.target
+            )
+        }
+        Merge::IfEmpty => format!(
+            "INSERT INTO main.{t} ({cols}) SELECT {cols} FROM legacy.\"{source}\"
+             WHERE NOT EXISTS (SELECT 1 FROM main.{t})",
+            t = spec.target
+        ),
+        Merge::Append { dedup_key } if shared.iter().any(|c| c == dedup_key) => format!(
+            "INSERT OR IGNORE INTO main.{t} ({cols}) SELECT {cols} FROM legacy.\"{source}\" AS l
+             WHERE l.{dedup_key} IS NULL OR l.{dedup_key} = ''
+                OR NOT EXISTS (SELECT 1 FROM main.{t} m WHERE m.{dedup_key} = l.{dedup_key})",
+            t = spec.target
+        ),
+        Merge::Append { .. } => format!(
+            "INSERT INTO main.{t} ({cols}) SELECT {cols} FROM legacy.\"{source}\"",
+            t = spec.target
+        ),
+    };
+    summary.imported = conn.execute(&sql, [])?;
+    summary.skipped = summary.source_rows.saturating_sub(summary.imported);
+    Ok(summary)
+}
+
+fn find_source_table(conn: &Connection, candidates: &[&str]) -> Result<Option<String>, DbError> {
+    for name in candidates {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM legacy.sqlite_master WHERE type = 'table' AND name = ?1)",
+            [name],
+            |r| r.get(0),
+        )?;
+        if exists {
+            return Ok(Some((*name).to_string()));
+        }
+    }
+    Ok(None)
+}
+
+fn columns(conn: &Connection, schema: &str, table: &str) -> Result<Vec<String>, DbError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {schema}.table_info(\"{table}\")"))?;
+    let cols = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(cols)
+}
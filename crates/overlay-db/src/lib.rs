@@ -2,43 +2,97 @@
 
 pub mod cache;
 pub mod chat;
+pub mod chat_commands;
+pub mod chat_stats;
+pub mod chat_timers;
+pub mod chatters;
+pub mod consents;
+pub mod discord;
+pub mod effect_presets;
+pub mod emote_rain;
+pub mod emote_rules;
+pub mod event_archive;
+pub mod event_triggers;
+pub mod funding;
+pub mod irc;
+pub mod legacy_import;
 pub mod lottery;
+pub mod lottery_engine;
+pub mod lottery_presets;
+pub mod macros;
+pub mod milestones;
+pub mod moderation;
 pub mod music;
+pub mod polls;
+pub mod print_jobs;
+pub mod print_votes;
+pub mod profiles;
+pub mod projections;
+pub mod quotes;
+pub mod raids;
+pub mod recent_emotes;
 pub mod rewards;
+pub mod rundowns;
 pub mod schema;
+pub mod segments;
 pub mod settings;
+pub mod stream_sessions;
 pub mod tokens;
+pub mod webhooks;
 pub mod word_filter;
 
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
+
+/// Source of [`Database::id`].
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
 
 /// Thread-safe database handle wrapping a single SQLite connection.
 #[derive(Clone)]
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
+    id: u64,
 }
 
 impl Database {
+    fn wrap(conn: Connection) -> Self {
+        Self {
+            conn: Arc::new(Mutex::new(conn)),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
     /// Open or create database at the given path.
+    ///
+    /// If migrations are pending on an existing database, a copy is written
+    /// to `<path>.v<version>.bak` first (see [`schema`] for the downgrade path).
     pub fn open(path: impl AsRef<Path>) -> Result<Self, DbError> {
-        let conn = Connection::open(path)?;
-        let db = Self {
-            conn: Arc::new(Mutex::new(conn)),
-        };
+        let path = path.as_ref();
+        let db = Self::wrap(Connection::open(path)?);
         db.configure()?;
+        db.backup_before_migrate(path)?;
         db.migrate()?;
         Ok(db)
     }
 
+    /// Copy the database at `path` to `scratch` and open the copy without
+    /// migrating it, leaving the original untouched (for dry runs).
+    pub fn open_copy(path: impl AsRef<Path>, scratch: impl AsRef<Path>) -> Result<Self, DbError> {
+        let scratch = scratch.as_ref();
+        let source = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        source.execute("VACUUM INTO ?1", [scratch.to_string_lossy()])?;
+        drop(source);
+        let db = Self::wrap(Connection::open(scratch)?);
+        db.configure()?;
+        Ok(db)
+    }
+
     /// Create an in-memory database (for testing).
     pub fn open_in_memory() -> Result<Self, DbError> {
-        let conn = Connection::open_in_memory()?;
-        let db = Self {
-            conn: Arc::new(Mutex::new(conn)),
-        };
+        let db = Self::wrap(Connection::open_in_memory()?);
         db.configure()?;
         db.migrate()?;
         Ok(db)
@@ -62,6 +116,17 @@ impl Database {
         f(&mut conn)
     }
 
+    /// Returns true if both handles share the same underlying connection.
+    pub fn same_connection(&self, other: &Database) -> bool {
+        Arc::ptr_eq(&self.conn, &other.conn)
+    }
+
+    /// Identity shared by the clones of this handle and never reused by
+    /// another database in the process (for per-database caches).
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
     fn configure(&self) -> Result<(), DbError> {
         self.with_conn(|conn| {
             conn.execute_batch(
@@ -73,6 +138,63 @@ impl Database {
         })
     }
 
+    /// Current schema version recorded in `schema_version`.
+    pub fn schema_version(&self) -> Result<u32, DbError> {
+        self.with_conn(schema::current_version)
+    }
+
+    /// Versions that would be applied by the next migration run (dry run).
+    pub fn pending_migrations(&self) -> Result<Vec<u32>, DbError> {
+        self.with_conn(|conn| schema::apply_migrations(conn, true))
+    }
+
+    /// Apply pending migrations, returning the versions applied.
+    pub fn apply_pending_migrations(&self) -> Result<Vec<u32>, DbError> {
+        self.with_conn(|conn| schema::apply_migrations(conn, false))
+    }
+
+    /// Write a consistent copy of the database to `dest`.
+    pub fn backup_to(&self, dest: impl AsRef<Path>) -> Result<(), DbError> {
+        let dest = dest.as_ref().to_string_lossy().to_string();
+        self.with_conn(|conn| {
+            conn.execute("VACUUM INTO ?1", [dest])?;
+            Ok(())
+        })
+    }
+
+    /// Verify the database accepts writes. Nothing is kept.
+    pub fn check_writable(&self) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            tx.execute_batch("CREATE TABLE write_check (x INTEGER); DROP TABLE write_check;")?;
+            tx.rollback()?;
+            Ok(())
+        })
+    }
+
+    fn backup_before_migrate(&self, path: &Path) -> Result<(), DbError> {
+        let (version, pending, has_data) = self.with_conn(|conn| {
+            Ok((
+                schema::current_version(conn)?,
+                schema::pending_migrations(conn)?.len(),
+                schema::has_user_tables(conn)?,
+            ))
+        })?;
+        if pending == 0 || !has_data {
+            return Ok(());
+        }
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(format!(".v{version}.bak"));
+        let backup = std::path::PathBuf::from(backup);
+        if backup.exists() {
+            std::fs::remove_file(&backup)
+                .map_err(|e| DbError::InvalidData(format!("remove old backup: {e}")))?;
+        }
+        self.backup_to(&backup)?;
+        tracing::info!("Database backed up before migration: {}", backup.display());
+        Ok(())
+    }
+
     fn migrate(&self) -> Result<(), DbError> {
         self.with_conn(|conn| {
             schema::run_migrations(conn)?;
@@ -81,6 +203,21 @@ impl Database {
     }
 }
 
+/// `%query%` for a `LIKE ?1 ESCAPE '\'` clause, so that `%`, `_` and `\`
+/// in the query match literally.
+pub(crate) fn like_contains(query: &str) -> String {
+    let mut pattern = String::with_capacity(query.len() + 2);
+    pattern.push('%');
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
 /// Database error type.
 #[derive(Debug, thiserror::Error)]
 pub enum DbError {
@@ -111,6 +248,9 @@ mod tests {
         // Verify tables exist by querying settings
         let settings = db.get_all_settings().unwrap();
         assert!(settings.is_empty());
+
+        assert_eq!(db.clone().id(), db.id());
+        assert_ne!(test_db().id(), db.id());
     }
 
     #[test]
@@ -161,6 +301,8 @@ mod tests {
             translation_status: String::new(),
             translation_lang: String::new(),
             created_at: 1000,
+            badges_json: r#"[{"set_id":"moderator","id":"1","info":""}]"#.into(),
+            color: "#FF0000".into(),
         };
         assert!(db.add_chat_message(&msg).unwrap());
         // Duplicate should be ignored
@@ -169,12 +311,36 @@ mod tests {
         let msgs = db.get_chat_messages_since(0, None).unwrap();
         assert_eq!(msgs.len(), 1);
         assert_eq!(msgs[0].username, "alice");
+        assert_eq!(msgs[0].color, "#FF0000");
+        assert!(msgs[0].badges_json.contains("moderator"));
+        assert_eq!(
+            db.get_chat_messages_between(900, 1000, 10).unwrap().len(),
+            1
+        );
+        assert!(
+            db.get_chat_messages_between(1001, 2000, 10)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(db.count_chat_messages_since(900, "broadcaster").unwrap(), 1);
+        assert_eq!(db.count_chat_messages_since(900, "user1").unwrap(), 0);
+        assert_eq!(db.count_chat_messages_since(1001, "").unwrap(), 0);
+
+        let by_id = db
+            .get_chat_messages_by_ids(&["msg1".into(), "missing".into()])
+            .unwrap();
+        assert_eq!(by_id.len(), 1);
+        assert!(db.get_chat_messages_by_ids(&[]).unwrap().is_empty());
 
         assert!(db.chat_message_exists("msg1").unwrap());
         assert!(!db.chat_message_exists("msg2").unwrap());
 
         let avatar = db.get_latest_chat_avatar("user1").unwrap();
         assert_eq!(avatar, Some("https://example.com/avatar.png".into()));
+
+        assert_eq!(db.search_chat_messages("HELLO", 10).unwrap().len(), 1);
+        assert_eq!(db.search_chat_messages("ali", 10).unwrap().len(), 1);
+        assert!(db.search_chat_messages("goodbye", 10).unwrap().is_empty());
     }
 
     #[test]
@@ -188,6 +354,7 @@ mod tests {
             redeemed_at: "2024-01-01".into(),
             is_subscriber: false,
             subscriber_tier: String::new(),
+            subscribed_months: 0,
             entry_count: 1,
             assigned_color: "#ff0000".into(),
         };
@@ -199,6 +366,129 @@ mod tests {
 
         db.clear_all_lottery_participants().unwrap();
         assert!(db.get_all_lottery_participants().unwrap().is_empty());
+
+        db.add_lottery_draw(&p, 5, 100, None).unwrap();
+        let second = lottery::LotteryParticipant {
+            user_id: "u2".into(),
+            username: "carol_100".into(),
+            display_name: "Carol".into(),
+            ..p
+        };
+        let proof = lottery::DrawProof {
+            algorithm: "test".into(),
+            commitment: "c".into(),
+            committed_at: None,
+            seed: "s".into(),
+            entries_digest: "d".into(),
+            entries: vec![],
+            total_tickets: 3,
+            winning_ticket: 1,
+        };
+        db.add_lottery_draw(&second, 3, 200, Some(&proof)).unwrap();
+        let draws = db.get_lottery_draws(10).unwrap();
+        assert_eq!(draws.len(), 2);
+        assert_eq!(draws[0].display_name, "Carol");
+        assert_eq!(draws[0].proof.as_ref(), Some(&proof));
+        assert_eq!(draws[1].participant_count, 5);
+        assert!(draws[1].proof.is_none());
+
+        let delivery = lottery::DrawDelivery {
+            claim_deadline: Some(800),
+            whisper_status: lottery::DELIVERY_FAILED.into(),
+            announce_status: lottery::DELIVERY_SENT.into(),
+            error: "whisper: 403".into(),
+        };
+        let id = draws[0].id;
+        db.set_lottery_draw_delivery(id, &delivery).unwrap();
+        let draw = db.get_lottery_draw(id).unwrap().unwrap();
+        assert_eq!(draw.delivery, delivery);
+        assert_eq!(draws[1].delivery, lottery::DrawDelivery::default());
+
+        // Draw `id` (Carol, deadline 800) is overdue from 800 on.
+        assert_eq!(draw.claim.status, lottery::CLAIM_PENDING);
+        assert!(db.get_overdue_lottery_draws(799).unwrap().is_empty());
+        assert_eq!(db.get_overdue_lottery_draws(800).unwrap()[0].id, id);
+        assert_eq!(db.get_pending_lottery_draw("u2").unwrap().unwrap().id, id);
+        let source = lottery::CLAIM_SOURCE_COMMAND;
+        assert!(db.claim_lottery_draw(id, source, 700).unwrap());
+        assert!(!db.expire_lottery_draw(id).unwrap());
+        assert!(db.get_pending_lottery_draw("u2").unwrap().is_none());
+        let claimed = db.get_lottery_draw(id).unwrap().unwrap().claim;
+        assert_eq!(claimed.status, lottery::CLAIM_CLAIMED);
+        assert_eq!(claimed.claimed_at, Some(700));
+
+        assert!(db.expire_lottery_draw(draws[1].id).unwrap());
+        assert!(!db.claim_lottery_draw(draws[1].id, "manual", 900).unwrap());
+        let expired = draws[1].id;
+        let redraw = db.add_lottery_draw(&second, 4, 300, None).unwrap();
+        db.set_lottery_draw_redraw_of(redraw.id, expired).unwrap();
+        let redraw = db.get_lottery_draw(redraw.id).unwrap().unwrap();
+        assert_eq!(redraw.redraw_of, Some(expired));
+        assert_eq!(db.expire_pending_lottery_draws().unwrap(), 1);
+        assert!(db.get_overdue_lottery_draws(i64::MAX).unwrap().is_empty());
+        assert_eq!(db.expire_pending_lottery_draws().unwrap(), 0);
+        assert_eq!(db.search_lottery_draws("BOB", 10).unwrap().len(), 1);
+        // Wildcards in the query are literal.
+        assert_eq!(db.search_lottery_draws("l_1", 10).unwrap()[0].user_id, "u2");
+        assert!(db.search_lottery_draws("b_b", 10).unwrap().is_empty());
+        assert!(db.search_lottery_draws("%", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_print_jobs() {
+        use print_jobs::{NewPrintJob, STATUS_FAILED, STATUS_PENDING, STATUS_PRINTING};
+
+        let db = test_db();
+        let job = |description| NewPrintJob {
+            description,
+            category: "chat",
+            force: false,
+            width: 2,
+            height: 1,
+            payload: &[0, 1],
+            color_payload: None,
+        };
+        let a = db.add_print_job(&job("a"), 100).unwrap();
+        let b = db.add_print_job(&job("b"), 100).unwrap();
+        let c = db.add_print_job(&job("c"), 100).unwrap();
+        db.reorder_print_jobs(&[c, 999]).unwrap();
+        let order: Vec<_> = db
+            .list_print_jobs(STATUS_PENDING)
+            .unwrap()
+            .into_iter()
+            .map(|j| j.id)
+            .collect();
+        assert_eq!(order, vec![c, a, b]);
+
+        let claimed = db.claim_next_print_job(100).unwrap().unwrap();
+        assert_eq!(claimed.record.id, c);
+        assert_eq!(claimed.payload, vec![0, 1]);
+        assert_eq!(db.count_print_jobs(STATUS_PRINTING).unwrap(), 1);
+
+        // Backoff: not due until 160.
+        db.fail_print_job(c, "offline", Some(160), 100).unwrap();
+        db.delete_print_job(a).unwrap();
+        db.delete_print_job(b).unwrap();
+        assert!(db.claim_next_print_job(150).unwrap().is_none());
+        assert_eq!(db.next_print_job_due().unwrap(), Some(160));
+        assert_eq!(db.claim_next_print_job(160).unwrap().unwrap().record.id, c);
+
+        // Dead letter, then manual retry.
+        db.fail_print_job(c, "still offline", None, 170).unwrap();
+        let failed = db.get_print_job(c).unwrap().unwrap();
+        assert_eq!(failed.status, STATUS_FAILED);
+        assert_eq!(failed.attempts, 2);
+        assert_eq!(failed.last_error.as_deref(), Some("still offline"));
+        assert_eq!(db.next_print_job_due().unwrap(), None);
+        assert!(db.retry_print_job(c, 200).unwrap());
+        assert_eq!(db.get_print_job(c).unwrap().unwrap().attempts, 0);
+
+        // A crash mid-print leaves the job pending on the next start.
+        db.claim_next_print_job(200).unwrap().unwrap();
+        assert!(!db.retry_print_job(c, 200).unwrap());
+        assert_eq!(db.requeue_interrupted_print_jobs(300).unwrap(), 1);
+        assert_eq!(db.clear_print_jobs(STATUS_PENDING).unwrap(), 1);
+        assert!(db.get_print_job(c).unwrap().is_none());
     }
 
     #[test]
@@ -222,8 +512,41 @@ mod tests {
         assert!(rc.user_names.is_empty());
     }
 
+    #[test]
+    fn test_reward_count_goals() {
+        let db = test_db();
+        let effect = serde_json::json!({ "preset": "hydrate" });
+        db.set_reward_count_goal("r1", 10, "hydrate", Some(&effect), 100)
+            .unwrap();
+        db.set_reward_count_goal("r2", 5, "", None, 100).unwrap();
+
+        let goal = db.get_reward_count_goal("r1").unwrap().unwrap();
+        assert_eq!(goal.goal, 10);
+        assert_eq!(goal.effect, Some(effect));
+        assert_eq!(
+            db.get_reward_count_goal("r2").unwrap().unwrap().effect,
+            None
+        );
+
+        db.set_reward_count_goal("r1", 20, "hydrate x2", None, 200)
+            .unwrap();
+        let goals = db.get_reward_count_goals().unwrap();
+        assert_eq!(goals.len(), 2);
+        assert_eq!(goals[0].goal, 20);
+        assert_eq!(goals[0].label, "hydrate x2");
+
+        assert!(db.delete_reward_count_goal("r1").unwrap());
+        assert!(!db.delete_reward_count_goal("r1").unwrap());
+        assert!(db.get_reward_count_goal("r1").unwrap().is_none());
+    }
+
     #[test]
     fn test_reward_groups() {
+        use rewards::{
+            POLICY_LIVE, PolicyWindow, RewardGroupPolicy, TRIGGER_STREAM_OFFLINE,
+            TRIGGER_STREAM_ONLINE,
+        };
+
         let db = test_db();
         let g = db.create_reward_group("test-group").unwrap();
         assert_eq!(g.name, "test-group");
@@ -242,18 +565,71 @@ mod tests {
         db.remove_reward_from_group(g.id, "reward1").unwrap();
         assert_eq!(db.get_group_rewards(g.id).unwrap().len(), 1);
 
+        db.set_reward_group_schedule(g.id, TRIGGER_STREAM_ONLINE, Some(true))
+            .unwrap();
+        db.set_reward_group_schedule(g.id, TRIGGER_STREAM_OFFLINE, Some(false))
+            .unwrap();
+        assert_eq!(db.get_reward_group_schedules(g.id).unwrap().len(), 2);
+        let online = db
+            .get_scheduled_reward_groups(TRIGGER_STREAM_ONLINE)
+            .unwrap();
+        assert_eq!(online.len(), 1);
+        assert!(online[0].enabled);
+        db.set_reward_group_schedule(g.id, TRIGGER_STREAM_ONLINE, None)
+            .unwrap();
+        assert!(
+            db.get_scheduled_reward_groups(TRIGGER_STREAM_ONLINE)
+                .unwrap()
+                .is_empty()
+        );
+
+        assert_eq!(
+            db.get_reward_group_policy(g.id).unwrap(),
+            RewardGroupPolicy::default()
+        );
+        assert!(db.get_automatic_reward_groups().unwrap().is_empty());
+        let policy = RewardGroupPolicy {
+            mode: POLICY_LIVE.into(),
+            windows: vec![PolicyWindow {
+                days: vec![5, 6],
+                start: "20:00".into(),
+                end: "02:00".into(),
+            }],
+        };
+        db.set_reward_group_policy(g.id, &policy).unwrap();
+        assert_eq!(db.get_reward_group_policy(g.id).unwrap(), policy);
+        let automatic = db.get_automatic_reward_groups().unwrap();
+        assert_eq!(automatic.len(), 1);
+        assert_eq!(automatic[0].0.id, g.id);
+
         db.delete_reward_group(g.id).unwrap();
         assert!(db.get_reward_groups().unwrap().is_empty());
     }
 
     #[test]
     fn test_word_filter() {
+        use crate::word_filter::WordFilterWord;
+
         let db = test_db();
-        let w = db.add_word_filter_word("en", "badword", "bad").unwrap();
+        let w = db
+            .add_word_filter_word(&WordFilterWord::new("en", "badword", "bad"))
+            .unwrap();
         assert_eq!(w.word, "badword");
+        let pattern = db
+            .add_word_filter_word(&WordFilterWord {
+                match_type: "wildcard".into(),
+                severity: "high".into(),
+                ..WordFilterWord::new("en", "b*d", "bad")
+            })
+            .unwrap();
+        assert_eq!(db.get_word_filter_revision().unwrap(), 2);
+        db.delete_word_filter_word(pattern.id).unwrap();
+        assert_eq!(db.get_word_filter_revision().unwrap(), 3);
 
         let words = db.get_word_filter_words("en").unwrap();
         assert_eq!(words.len(), 1);
+        assert_eq!(words[0].match_type, "substring");
+        assert_eq!(words[0].severity, "medium");
 
         let langs = db.get_word_filter_languages().unwrap();
         assert_eq!(langs, vec!["en"]);
@@ -268,6 +644,37 @@ mod tests {
         assert!(db.get_word_filter_words("en").unwrap().is_empty());
     }
 
+    #[test]
+    fn test_word_filter_import() {
+        use crate::word_filter::WordFilterWord;
+
+        let db = test_db();
+        let word = |language: &str, word: &str| WordFilterWord::new(language, word, "bad");
+        db.add_word_filter_word(&word("en", "old")).unwrap();
+        db.add_word_filter_word(&word("ja", "keep")).unwrap();
+
+        let merged = db
+            .import_word_filter_words(&[word("en", "old"), word("en", "new")], false)
+            .unwrap();
+        assert_eq!(merged, 1);
+        assert_eq!(db.get_word_filter_words("en").unwrap().len(), 2);
+
+        let replaced = db
+            .import_word_filter_words(&[word("en", "only")], true)
+            .unwrap();
+        assert_eq!(replaced, 1);
+        let all = db.get_all_word_filter_words().unwrap();
+        let listed: Vec<&str> = all.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(listed, vec!["only", "keep"]);
+
+        let ids: Vec<i64> = all.iter().map(|w| w.id).collect();
+        assert_eq!(
+            db.delete_word_filter_words(&[ids[0], ids[1], 999]).unwrap(),
+            2
+        );
+        assert!(db.get_all_word_filter_words().unwrap().is_empty());
+    }
+
     #[test]
     fn test_music() {
         let db = test_db();
@@ -318,4 +725,1375 @@ mod tests {
         db.delete_cache_entry("hash1").unwrap();
         assert!(db.get_cache_entry("hash1").unwrap().is_none());
     }
+
+    #[test]
+    fn test_schema_versioning() {
+        let db = test_db();
+        assert_eq!(db.schema_version().unwrap(), schema::latest_version());
+        assert!(db.pending_migrations().unwrap().is_empty());
+
+        // Re-running is a no-op.
+        db.migrate().unwrap();
+        assert_eq!(db.schema_version().unwrap(), schema::latest_version());
+
+        // A database from a newer build is refused.
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO schema_version (version, name) VALUES (?1, 'future')",
+                [schema::latest_version() + 1],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        assert!(matches!(
+            db.pending_migrations(),
+            Err(DbError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_legacy_database_is_adopted() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL,
+             setting_type TEXT NOT NULL DEFAULT 'normal');
+             INSERT INTO settings (key, value) VALUES ('k', 'v');",
+        )
+        .unwrap();
+        assert_eq!(schema::current_version(&conn).unwrap(), 0);
+        assert_eq!(
+            schema::apply_migrations(&conn, true).unwrap(),
+            vec![
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+                24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42
+            ]
+        );
+        schema::run_migrations(&conn).unwrap();
+        assert_eq!(
+            schema::current_version(&conn).unwrap(),
+            schema::latest_version()
+        );
+        let db = Database::wrap(conn);
+        assert_eq!(db.get_setting("k").unwrap(), Some("v".into()));
+    }
+
+    #[test]
+    fn test_segments() {
+        let db = test_db();
+        assert!(db.get_current_segment().unwrap().is_none());
+
+        let first = db.start_segment("Just Chatting", 100).unwrap();
+        let second = db.start_segment("Ranked", 200).unwrap();
+        assert_eq!(db.get_current_segment().unwrap().unwrap().id, second.id);
+
+        let all = db.get_segments_since(0).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].id, first.id);
+        assert_eq!(all[0].ended_at, Some(200));
+
+        let msg = chat::ChatMessage {
+            id: 0,
+            message_id: "m1".into(),
+            user_id: "u1".into(),
+            username: "alice".into(),
+            message: "gg".into(),
+            fragments_json: "[]".into(),
+            avatar_url: String::new(),
+            translation_text: String::new(),
+            translation_status: String::new(),
+            translation_lang: String::new(),
+            created_at: 250,
+            badges_json: String::new(),
+            color: String::new(),
+        };
+        db.add_chat_message(&msg).unwrap();
+
+        let closed = db.end_current_segment(300).unwrap().unwrap();
+        let stats = db.get_segment_stats(&closed, 999).unwrap();
+        assert_eq!(stats.duration_secs, 100);
+        assert_eq!(stats.chat_messages, 1);
+        assert_eq!(stats.unique_chatters, 1);
+        assert!(db.end_current_segment(400).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_milestones_fire_once() {
+        let db = test_db();
+        assert!(
+            db.mark_milestone_fired("followers", 1000, 1003, 10)
+                .unwrap()
+        );
+        assert!(
+            !db.mark_milestone_fired("followers", 1000, 1010, 20)
+                .unwrap()
+        );
+        assert!(db.mark_milestone_fired("viewers", 1000, 1000, 30).unwrap());
+
+        let fired = db.get_fired_milestones().unwrap();
+        assert_eq!(fired.len(), 2);
+        assert_eq!(fired[0].value, 1003);
+
+        db.reset_milestone("followers", 1000).unwrap();
+        assert!(
+            db.mark_milestone_fired("followers", 1000, 1020, 40)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_emote_print_rules() {
+        let db = test_db();
+        assert_eq!(db.observe_emote("e1", "Kappa", 1).unwrap(), "pending");
+        db.set_emote_print_status("e1", "", "denied", 2).unwrap();
+        assert_eq!(db.observe_emote("e1", "Kappa", 3).unwrap(), "denied");
+        assert!(db.set_emote_print_status("e1", "", "bogus", 4).is_err());
+
+        db.observe_emote("e2", "PogChamp", 5).unwrap();
+        let pending = db.get_emote_print_rules(Some("pending")).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].emote_name, "PogChamp");
+        assert_eq!(db.get_emote_print_rules(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_event_triggers_highest_threshold() {
+        let db = test_db();
+        let small = serde_json::json!({ "address": "/small" });
+        let big = serde_json::json!({ "address": "/big" });
+        db.add_event_trigger("osc", "cheer", 100, &small, 1)
+            .unwrap();
+        let big_id = db.add_event_trigger("osc", "cheer", 1000, &big, 2).unwrap();
+        db.add_event_trigger("midi", "cheer", 0, &small, 3).unwrap();
+
+        assert!(
+            db.get_matching_event_triggers("osc", "cheer", 50)
+                .unwrap()
+                .is_empty()
+        );
+        let hit = db.get_matching_event_triggers("osc", "cheer", 500).unwrap();
+        assert_eq!(hit.len(), 1);
+        assert_eq!(hit[0].params, small);
+        let hit = db
+            .get_matching_event_triggers("osc", "cheer", 5000)
+            .unwrap();
+        assert_eq!(hit[0].params, big);
+
+        db.update_event_trigger(big_id, "osc", "cheer", 1000, &big, false)
+            .unwrap();
+        let hit = db
+            .get_matching_event_triggers("osc", "cheer", 5000)
+            .unwrap();
+        assert_eq!(hit[0].params, small);
+
+        assert!(!db.delete_event_trigger(big_id, "midi").unwrap());
+        assert!(db.delete_event_trigger(big_id, "osc").unwrap());
+        assert_eq!(db.get_event_triggers("osc").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_effect_presets() {
+        let db = test_db();
+        let params = serde_json::json!({ "particles": 300 });
+        db.upsert_effect_preset("big", "confetti", &params, 3000, 1)
+            .unwrap();
+        db.upsert_effect_preset("big", "fireworks", &params, 5000, 2)
+            .unwrap();
+        let preset = db.get_effect_preset("big").unwrap().unwrap();
+        assert_eq!(preset.effect, "fireworks");
+        assert_eq!(preset.duration_ms, 5000);
+        assert_eq!(preset.params, params);
+        assert_eq!(db.get_effect_presets().unwrap().len(), 1);
+        assert!(db.delete_effect_preset("big").unwrap());
+        assert!(db.get_effect_preset("big").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_ticket_formula() {
+        use lottery_engine::TicketFormula;
+
+        let formula = TicketFormula::default();
+        assert_eq!(formula.tickets(5, false, "", 0).total, 3);
+        let tier3 = formula.tickets(1, true, "3000", 24);
+        assert_eq!((tier3.base, tier3.bonus, tier3.total), (1, 12, 13));
+        assert_eq!(formula.tickets(0, true, "", 0).total, 4);
+
+        let formula = TicketFormula::parse(
+            r#"{"tier_bonus":{},"tier_coefficients":{"1000":1.0,"3000":1.5},"max_bonus":10}"#,
+        )
+        .unwrap();
+        assert_eq!(formula.max_entries, 3);
+        assert_eq!(formula.tickets(1, true, "1000", 7).bonus, 2);
+        assert_eq!(formula.tickets(1, true, "3000", 40).bonus, 10);
+        assert_eq!(formula.tickets(1, true, "2000", 40).bonus, 0);
+        assert_eq!(TicketFormula::parse("").unwrap(), Default::default());
+        assert!(TicketFormula::parse(r#"{"max_entries":0}"#).is_err());
+        assert!(TicketFormula::parse(r#"{"months_divisor":0}"#).is_err());
+    }
+
+    #[test]
+    fn test_lottery_presets() {
+        let db = test_db();
+        let settings = std::collections::BTreeMap::from([(
+            "LOTTERY_DISPLAY_DURATION".to_string(),
+            "8".to_string(),
+        )]);
+        db.upsert_lottery_preset("fair", "", &settings, 1).unwrap();
+        db.upsert_lottery_preset("fair", "subs x2", &settings, 2)
+            .unwrap();
+        let preset = db.get_lottery_preset("fair").unwrap().unwrap();
+        assert_eq!(preset.description, "subs x2");
+        assert_eq!(preset.settings, settings);
+        assert_eq!(preset.updated_at, 2);
+        assert_eq!(db.get_lottery_presets().unwrap().len(), 1);
+        assert!(db.delete_lottery_preset("fair").unwrap());
+        assert!(!db.delete_lottery_preset("fair").unwrap());
+    }
+
+    #[test]
+    fn test_emote_rain_cooldown() {
+        let db = test_db();
+        assert!(db.try_fire_emote_rain("e1", "Kappa", 60, 1000).unwrap());
+        assert!(!db.try_fire_emote_rain("e1", "Kappa", 60, 1030).unwrap());
+        assert!(db.try_fire_emote_rain("e1", "Kappa", 60, 1060).unwrap());
+
+        db.set_emote_rain_cooldown("e1", "", Some(300)).unwrap();
+        assert!(!db.try_fire_emote_rain("e1", "Kappa", 60, 1200).unwrap());
+        assert!(db.try_fire_emote_rain("e1", "Kappa", 60, 1360).unwrap());
+
+        let rows = db.get_emote_rain_cooldowns().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].emote_name, "Kappa");
+        assert_eq!(rows[0].fire_count, 3);
+        assert_eq!(rows[0].cooldown_seconds, Some(300));
+    }
+
+    #[test]
+    fn test_quotes() {
+        let db = test_db();
+        assert!(db.get_random_quote().unwrap().is_none());
+        let q = db
+            .add_quote("It's fine.", "streamer", "mod1", 1000)
+            .unwrap();
+        db.add_quote("GG", "", "dashboard", 1001).unwrap();
+
+        assert_eq!(db.get_quote(q.id).unwrap(), Some(q.clone()));
+        assert!(db.get_random_quote().unwrap().is_some());
+        assert_eq!(db.get_quotes(Some("fine")).unwrap(), vec![q.clone()]);
+        assert_eq!(db.get_quotes(None).unwrap().len(), 2);
+        // Wildcards in the search are literal.
+        assert!(db.get_quotes(Some("G_")).unwrap().is_empty());
+        assert!(db.get_quotes(Some("%")).unwrap().is_empty());
+
+        assert!(db.update_quote(q.id, "It's fine!", "streamer").unwrap());
+        assert_eq!(db.get_quote(q.id).unwrap().unwrap().text, "It's fine!");
+        assert!(db.delete_quote(q.id).unwrap());
+        assert!(!db.delete_quote(q.id).unwrap());
+    }
+
+    #[test]
+    fn test_viewer_consents() {
+        use consents::ConsentScope;
+
+        let db = test_db();
+        assert!(db.get_consent("u1").unwrap().is_none());
+        db.set_consent(
+            "u1",
+            "alice",
+            ConsentScope::Print,
+            Some(false),
+            "command",
+            100,
+        )
+        .unwrap();
+        db.set_consent("u1", "", ConsentScope::Tts, Some(true), "dashboard", 200)
+            .unwrap();
+        let c = db.get_consent("u1").unwrap().unwrap();
+        assert_eq!(c.username, "alice");
+        assert_eq!((c.print, c.tts, c.credits), (Some(false), Some(true), None));
+        assert_eq!((c.source.as_str(), c.updated_at), ("dashboard", 200));
+        assert_eq!(c.get(ConsentScope::Print), Some(false));
+
+        db.set_consent("u1", "alice", ConsentScope::Print, None, "dashboard", 300)
+            .unwrap();
+        assert_eq!(db.get_consent("u1").unwrap().unwrap().print, None);
+
+        db.set_consent(
+            "u2",
+            "bob",
+            ConsentScope::Credits,
+            Some(true),
+            "reward",
+            250,
+        )
+        .unwrap();
+        let all = db.get_consents(None).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].user_id, "u1");
+        assert_eq!(db.get_consents(Some("bo")).unwrap().len(), 1);
+
+        db.set_consent("u3", "c_d", ConsentScope::Tts, Some(false), "command", 260)
+            .unwrap();
+        let found = db.get_consents(Some("_")).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].user_id, "u3");
+        assert!(db.get_consents(Some("%")).unwrap().is_empty());
+
+        assert!(db.delete_consent("u1").unwrap());
+        assert!(!db.delete_consent("u1").unwrap());
+    }
+
+    #[test]
+    fn test_funding_entries() {
+        use funding::{SOURCE_ADJUSTMENT, SOURCE_BITS, SOURCE_DONATION, SOURCE_GIFT_SUBS};
+
+        let db = test_db();
+        assert_eq!(db.get_funding_totals(0).unwrap().total(), 0.0);
+        db.add_funding_entry(SOURCE_BITS, 500.0, 700.0, "alice", "", 100)
+            .unwrap();
+        db.add_funding_entry(SOURCE_GIFT_SUBS, 5.0, 3500.0, "bob", "tier 1000", 200)
+            .unwrap();
+        db.add_funding_entry(SOURCE_DONATION, 1000.0, 1000.0, "carol", "Ko-fi", 300)
+            .unwrap();
+        let adj = db
+            .add_funding_entry(
+                SOURCE_ADJUSTMENT,
+                -200.0,
+                -200.0,
+                "dashboard",
+                "refund",
+                400,
+            )
+            .unwrap();
+
+        let totals = db.get_funding_totals(0).unwrap();
+        assert_eq!(
+            (
+                totals.bits,
+                totals.gift_subs,
+                totals.donations,
+                totals.adjustments
+            ),
+            (700.0, 3500.0, 1000.0, -200.0)
+        );
+        assert_eq!(totals.total(), 5000.0);
+        assert_eq!(db.get_funding_totals(250).unwrap().total(), 800.0);
+
+        let entries = db.get_funding_entries(0, None, 10).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0], adj);
+        let adjustments = db
+            .get_funding_entries(0, Some(SOURCE_ADJUSTMENT), 10)
+            .unwrap();
+        assert_eq!(adjustments, vec![adj]);
+        assert_eq!(db.get_funding_entries(150, None, 2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_macros() {
+        let db = test_db();
+        let steps = serde_json::json!([{ "type": "chat", "message": "hello" }]);
+        let m = db.add_macro("Start stream", &steps, 100).unwrap();
+        db.add_macro("End stream", &serde_json::json!([]), 110)
+            .unwrap();
+        assert_eq!(db.get_macro(m.id).unwrap(), Some(m.clone()));
+        let names: Vec<_> = db
+            .get_macros()
+            .unwrap()
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(names, vec!["End stream", "Start stream"]);
+
+        let new_steps = serde_json::json!([{ "type": "print_rundown" }]);
+        assert!(db.update_macro(m.id, "Go live", &new_steps, 200).unwrap());
+        let updated = db.get_macro(m.id).unwrap().unwrap();
+        assert_eq!(
+            (
+                updated.name.as_str(),
+                &updated.steps,
+                updated.created_at,
+                updated.updated_at
+            ),
+            ("Go live", &new_steps, 100, 200)
+        );
+        assert!(db.delete_macro(m.id).unwrap());
+        assert!(!db.update_macro(m.id, "x", &new_steps, 300).unwrap());
+        assert!(db.get_macro(m.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_raid_partners() {
+        let db = test_db();
+        db.add_raid("out", "10", "alice", "Alice", 20, 100).unwrap();
+        db.add_raid("out", "10", "alice", "Alice_", 25, 200)
+            .unwrap();
+        db.add_raid("in", "10", "alice", "Alice_", 40, 300).unwrap();
+        db.add_raid("out", "20", "bob", "Bob", 15, 400).unwrap();
+
+        let partners = db.get_raid_partners(0).unwrap();
+        assert_eq!(partners.len(), 2);
+        let alice = &partners[0];
+        assert_eq!(alice.channel_name, "Alice_");
+        assert_eq!((alice.raids_in, alice.raids_out), (1, 2));
+        assert_eq!((alice.viewers_in, alice.viewers_out), (40, 45));
+        assert_eq!(
+            (alice.last_in_at, alice.last_out_at),
+            (Some(300), Some(200))
+        );
+        assert_eq!(partners[1].last_in_at, None);
+
+        assert_eq!(db.get_raids_since(250, 10).unwrap().len(), 2);
+        assert_eq!(db.get_raid_partners(350).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rebuild_projections() {
+        let db = test_db();
+        // Nothing to replay without an archive.
+        db.increment_reward_count("r1", "alice").unwrap();
+        assert_eq!(db.rebuild_reward_counts().unwrap(), 0);
+
+        let now = chrono::Utc::now().timestamp();
+        let redemption = |user_name: &str, user_login: &str, reward_id: &str| {
+            serde_json::json!({
+                "user_name": user_name,
+                "user_login": user_login,
+                "reward": { "id": reward_id },
+            })
+        };
+        let event = "channel.channel_points_custom_reward_redemption.add";
+        // Before the archive reaches back to the count's start, it is kept.
+        db.archive_event(event, &redemption("alice", "alice", "r1"), now)
+            .unwrap();
+        assert_eq!(db.rebuild_reward_counts().unwrap(), 0);
+        db.archive_event("channel.follow", &serde_json::json!({}), now - 60)
+            .unwrap();
+        db.archive_event(event, &redemption("", "bob", "r1"), now)
+            .unwrap();
+        db.archive_event(event, &redemption("carol", "carol", "r2"), now)
+            .unwrap();
+        assert_eq!(db.rebuild_reward_counts().unwrap(), 1);
+        let count = db.get_reward_count("r1").unwrap().unwrap();
+        assert_eq!(count.count, 2);
+        assert_eq!(count.user_names, vec!["alice", "bob"]);
+        assert_eq!(db.rebuild_reward_counts().unwrap(), 0);
+
+        db.start_segment("A", 100).unwrap();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO stream_segments (name, started_at) VALUES ('B', 200)",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(db.rebuild_segments().unwrap(), 1);
+        let segments = db.get_segments_since(0).unwrap();
+        assert_eq!(segments[0].ended_at, Some(200));
+        assert_eq!(segments[1].ended_at, None);
+        assert_eq!(db.rebuild_segments().unwrap(), 0);
+
+        db.observe_emote("25", "Kappa", 500).unwrap();
+        db.add_chat_message(&chat::ChatMessage {
+            id: 0,
+            message_id: "m1".into(),
+            user_id: "u1".into(),
+            username: "viewer".into(),
+            message: "Kappa".into(),
+            fragments_json: r#"[{"type":"emote","text":"Kappa","emote":{"id":"25"}}]"#.into(),
+            avatar_url: String::new(),
+            translation_text: String::new(),
+            translation_status: String::new(),
+            translation_lang: String::new(),
+            created_at: 300,
+            badges_json: String::new(),
+            color: String::new(),
+        })
+        .unwrap();
+        assert_eq!(db.rebuild_projection("emote_first_seen").unwrap(), 1);
+        assert_eq!(db.rebuild_emote_first_seen().unwrap(), 0);
+        assert!(db.rebuild_projection("nope").is_err());
+    }
+
+    #[test]
+    fn test_check_writable() {
+        let db = test_db();
+        db.check_writable().unwrap();
+        let exists: i64 = db
+            .with_conn(|conn| {
+                Ok(conn.query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE name = 'write_check'",
+                    [],
+                    |row| row.get(0),
+                )?)
+            })
+            .unwrap();
+        assert_eq!(exists, 0);
+    }
+
+    #[test]
+    fn test_event_archive() {
+        let db = test_db();
+        let raid = serde_json::json!({
+            "from_broadcaster_user_id": "42",
+            "from_broadcaster_user_login": "friend",
+            "from_broadcaster_user_name": "Friend",
+            "viewers": 12,
+        });
+        db.archive_event("channel.raid", &raid, 100).unwrap();
+        db.archive_event(
+            "channel.follow",
+            &serde_json::json!({ "user_id": "1" }),
+            200,
+        )
+        .unwrap();
+
+        let all = db.get_archived_events(None, 0, 10).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].payload["viewers"], 12);
+        let follows = db
+            .get_archived_events(Some("channel.follow"), 0, 10)
+            .unwrap();
+        assert_eq!(follows.len(), 1);
+        assert!(db.get_archived_events(None, 150, 10).unwrap()[0].event_type == "channel.follow");
+        let found = db.search_archived_events("friend", 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].event_type, "channel.raid");
+        let by_type = db.search_archived_events("channel.", 10).unwrap();
+        assert_eq!(by_type[0].event_type, "channel.follow");
+
+        // Raids missing from the history are restored once.
+        assert_eq!(db.rebuild_projection("raids").unwrap(), 1);
+        assert_eq!(
+            db.get_raids_since(0, 10).unwrap()[0].channel_login,
+            "friend"
+        );
+        assert_eq!(db.rebuild_raids().unwrap(), 0);
+
+        let stats = db.get_event_archive_stats().unwrap();
+        assert_eq!(stats.events, 2);
+        // Only the newest payload fits.
+        let newest = all[1].payload.to_string().len() as i64;
+        assert_eq!(db.prune_event_archive(newest).unwrap(), 1);
+        assert_eq!(db.get_event_archive_stats().unwrap().events, 1);
+        assert_eq!(db.prune_event_archive(newest).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_rundowns() {
+        let db = test_db();
+        let day = "2026-10-16";
+        let a = db.add_rundown_item(day, "Intro", 1000).unwrap();
+        let b = db.add_rundown_item(day, "Game", 1001).unwrap();
+        let c = db.add_rundown_item(day, "Raid out", 1002).unwrap();
+        db.add_rundown_item("2026-10-17", "Other day", 1003)
+            .unwrap();
+        assert_eq!((a.position, c.position), (0, 2));
+
+        db.reorder_rundown(day, &[c.id, a.id]).unwrap();
+        let texts: Vec<String> = db
+            .get_rundown(day)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.text)
+            .collect();
+        assert_eq!(texts, ["Raid out", "Intro", "Game"]);
+
+        let checked = db.set_rundown_item_checked(b.id, true, 2000).unwrap();
+        assert_eq!(
+            checked.map(|i| (i.checked, i.checked_at)),
+            Some((true, Some(2000)))
+        );
+        let unchecked = db.set_rundown_item_checked(b.id, false, 2001).unwrap();
+        assert_eq!(unchecked.map(|i| i.checked_at), Some(None));
+
+        assert!(db.update_rundown_item_text(a.id, "Opening").unwrap());
+        assert_eq!(db.get_rundown_item(a.id).unwrap().unwrap().text, "Opening");
+        assert!(db.delete_rundown_item(a.id).unwrap());
+        assert!(!db.delete_rundown_item(a.id).unwrap());
+        assert_eq!(db.get_rundown(day).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_legacy() {
+        let path = std::env::temp_dir().join(format!(
+            "overlay-legacy-{}-{}.db",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        {
+            let legacy = Connection::open(&path).unwrap();
+            legacy
+                .execute_batch(
+                    "CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+                     INSERT INTO settings VALUES ('PRINTER_ADDRESS', 'AA:BB'), ('EMPTY', ''),
+                                                 ('CLIENT_SECRET', 's3');
+                     CREATE TABLE tokens (id INTEGER PRIMARY KEY, access_token TEXT,
+                         refresh_token TEXT, scope TEXT, expires_at INTEGER);
+                     INSERT INTO tokens VALUES (1, 'at', 'rt', 'chat:read', 123);
+                     CREATE TABLE reward_counts (reward_id TEXT PRIMARY KEY, count INTEGER);
+                     INSERT INTO reward_counts VALUES ('r1', 5);
+                     CREATE TABLE chat_messages (id INTEGER PRIMARY KEY, message_id TEXT,
+                         username TEXT NOT NULL, message TEXT NOT NULL, created_at INTEGER NOT NULL,
+                         legacy_only TEXT);
+                     INSERT INTO chat_messages VALUES (1, 'm1', 'alice', 'hi', 10, 'x'),
+                                                      (2, 'm2', 'bob', 'yo', 11, 'y');",
+                )
+                .unwrap();
+        }
+
+        let db = test_db();
+        db.set_setting("CLIENT_SECRET", "old", "secret").unwrap();
+        let summary = db.import_legacy(&path).unwrap();
+        let by_table = |t: &str| {
+            summary
+                .tables
+                .iter()
+                .find(|s| s.table == t)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(by_table("settings").imported, 2);
+        assert_eq!(by_table("settings").skipped, 1);
+        // The legacy table has no setting_type, so the secret stays secret.
+        let secrets = db.get_settings_by_type("secret").unwrap();
+        assert_eq!(secrets.get("CLIENT_SECRET").map(String::as_str), Some("s3"));
+        assert_eq!(
+            db.get_setting("PRINTER_ADDRESS").unwrap(),
+            Some("AA:BB".into())
+        );
+        assert_eq!(by_table("tokens").imported, 1);
+        assert_eq!(
+            by_table("reward_redemption_counts").source_table.as_deref(),
+            Some("reward_counts")
+        );
+        assert_eq!(by_table("chat_messages").imported, 2);
+
+        // Re-importing does not duplicate chat history or tokens.
+        let again = db.import_legacy(&path).unwrap();
+        assert!(
+            again
+                .tables
+                .iter()
+                .filter(|t| t.table != "settings")
+                .all(|t| t.imported == 0)
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(db.import_legacy(&path).is_err());
+    }
+
+    #[test]
+    fn test_import_legacy_chat_dedup() {
+        let path = std::env::temp_dir().join(format!(
+            "overlay-legacy-chat-{}-{}.db",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        {
+            let legacy = Connection::open(&path).unwrap();
+            legacy
+                .execute_batch(
+                    "CREATE TABLE chat_messages (id INTEGER PRIMARY KEY, message_id TEXT,
+                         username TEXT NOT NULL, message TEXT NOT NULL, created_at INTEGER NOT NULL);
+                     INSERT INTO chat_messages VALUES (1, 'm1', 'alice', 'hi', 10),
+                                                      (2, 'm1', 'alice', 'hi', 10),
+                                                      (3, '', 'bob', 'yo', 11),
+                                                      (4, '', 'carol', 'hey', 12);",
+                )
+                .unwrap();
+        }
+
+        let db = test_db();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO chat_messages (message_id, username, message, created_at)
+                 VALUES ('', 'dave', 'old', 1)",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        // The duplicate m1 is skipped instead of failing the import, and rows
+        // with an empty message_id are appended even though main has one.
+        let summary = db.import_legacy(&path).unwrap();
+        let chat = summary
+            .tables
+            .iter()
+            .find(|s| s.table == "chat_messages")
+            .unwrap();
+        assert_eq!((chat.imported, chat.skipped), (3, 1));
+        let count: i64 = db
+            .with_conn(|conn| {
+                conn.query_row("SELECT COUNT(*) FROM chat_messages", [], |r| r.get(0))
+                    .map_err(Into::into)
+            })
+            .unwrap();
+        assert_eq!(count, 4);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_poll_and_prediction_upsert() {
+        use polls::{Poll, PollChoice, Prediction, PredictionOutcome};
+        let db = test_db();
+        let choice = |id: &str, votes| PollChoice {
+            id: id.into(),
+            title: id.into(),
+            votes,
+            channel_points_votes: 0,
+        };
+        let mut poll = Poll {
+            id: "p1".into(),
+            title: "Which?".into(),
+            status: polls::STATUS_ACTIVE.into(),
+            choices: vec![choice("a", 0), choice("b", 0)],
+            started_at: 100,
+            ends_at: Some(160),
+            ended_at: None,
+            updated_at: 100,
+        };
+        db.upsert_poll(&poll).unwrap();
+
+        // The end notification carries no `ends_at`; the stored one stays.
+        poll.status = "completed".into();
+        poll.choices = vec![choice("a", 3), choice("b", 5)];
+        poll.started_at = 150;
+        poll.ends_at = None;
+        poll.ended_at = Some(155);
+        poll.updated_at = 155;
+        let stored = db.upsert_poll(&poll).unwrap();
+        assert_eq!(stored.started_at, 100);
+        assert_eq!(stored.ends_at, Some(160));
+        assert_eq!(stored.ended_at, Some(155));
+        assert_eq!(stored.choices[1].votes, 5);
+        assert_eq!(db.recent_polls(10).unwrap(), vec![stored]);
+
+        let mut prediction = Prediction {
+            id: "r1".into(),
+            title: "Win?".into(),
+            status: polls::STATUS_LOCKED.into(),
+            outcomes: vec![PredictionOutcome {
+                id: "o1".into(),
+                title: "Yes".into(),
+                color: "blue".into(),
+                users: 2,
+                channel_points: 500,
+            }],
+            winning_outcome_id: None,
+            started_at: 200,
+            locks_at: None,
+            locked_at: Some(230),
+            ended_at: None,
+            updated_at: 230,
+        };
+        db.upsert_prediction(&prediction).unwrap();
+        prediction.status = "resolved".into();
+        prediction.winning_outcome_id = Some("o1".into());
+        prediction.locked_at = None;
+        prediction.ended_at = Some(300);
+        let stored = db.upsert_prediction(&prediction).unwrap();
+        assert_eq!(stored.locked_at, Some(230));
+        assert_eq!(stored.winning_outcome_id.as_deref(), Some("o1"));
+        assert_eq!(stored.outcomes[0].channel_points, 500);
+        assert_eq!(db.recent_predictions(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_webhook_deliveries() {
+        use webhooks::{STATUS_FAILED, STATUS_PENDING, WebhookInput};
+        let db = test_db();
+        let types = vec!["channel.follow".to_string()];
+        let id = db
+            .add_webhook(
+                &WebhookInput {
+                    name: "automation",
+                    url: "http://localhost:9000/hook",
+                    secret: "s3cret",
+                    event_types: &types,
+                    enabled: true,
+                },
+                100,
+            )
+            .unwrap();
+        let hook = db.get_webhook(id).unwrap().unwrap();
+        assert!(hook.accepts("channel.follow"));
+        assert!(!hook.accepts("channel.cheer"));
+
+        let d1 = db
+            .add_webhook_delivery(id, "channel.follow", "{}", 100)
+            .unwrap();
+        let d2 = db
+            .add_webhook_delivery(id, "channel.follow", "{}", 100)
+            .unwrap();
+        db.fail_webhook_delivery(d1, "timeout", Some(200), 100)
+            .unwrap();
+        let due: Vec<_> = db
+            .due_webhook_deliveries(150, 10)
+            .unwrap()
+            .into_iter()
+            .map(|d| d.id)
+            .collect();
+        assert_eq!(due, vec![d2]);
+        assert_eq!(db.next_webhook_delivery_due().unwrap(), Some(100));
+
+        db.fail_webhook_delivery(d1, "timeout", None, 200).unwrap();
+        let failed = db.list_webhook_deliveries(STATUS_FAILED, 10).unwrap();
+        assert_eq!(failed[0].attempts, 2);
+        assert!(db.retry_webhook_delivery(d1, 300).unwrap());
+        assert_eq!(db.count_webhook_deliveries(STATUS_PENDING).unwrap(), 2);
+
+        // Deleting the webhook drops its queued deliveries.
+        assert!(db.delete_webhook(id).unwrap());
+        assert_eq!(db.count_webhook_deliveries(STATUS_PENDING).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_recent_emotes() {
+        let db = test_db();
+        db.record_emote_uses(&[("1", "Kappa"), ("2", "LUL")], 100, 10)
+            .unwrap();
+        db.record_emote_uses(&[("1", "Kappa")], 200, 10).unwrap();
+        let recent = db.recent_emotes(10).unwrap();
+        let ids: Vec<_> = recent.iter().map(|e| e.emote_id.as_str()).collect();
+        assert_eq!(ids, ["1", "2"]);
+        assert_eq!(recent[0].use_count, 2);
+        assert_eq!(recent[0].last_used_at, 200);
+
+        // Only the newest `keep` emotes survive.
+        db.record_emote_uses(&[("3", "PogChamp")], 300, 2).unwrap();
+        let ids: Vec<_> = db
+            .recent_emotes(10)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.emote_id)
+            .collect();
+        assert_eq!(ids, ["3", "1"]);
+    }
+
+    #[test]
+    fn test_discord_templates() {
+        use discord::DiscordTemplate;
+        let db = test_db();
+        assert!(db.get_discord_template("raid").unwrap().is_none());
+        let mut template = DiscordTemplate {
+            event: "raid".into(),
+            enabled: true,
+            title: "{user} raided".into(),
+            description: String::new(),
+            color: 0x9146ff,
+            updated_at: 100,
+        };
+        db.upsert_discord_template(&template).unwrap();
+        template.enabled = false;
+        template.updated_at = 200;
+        db.upsert_discord_template(&template).unwrap();
+        assert_eq!(db.get_discord_template("raid").unwrap(), Some(template));
+        assert_eq!(db.get_discord_templates().unwrap().len(), 1);
+        assert!(db.delete_discord_template("raid").unwrap());
+        assert!(!db.delete_discord_template("raid").unwrap());
+    }
+
+    #[test]
+    fn test_chat_timers() {
+        use chat_timers::ChatTimerInput;
+        let db = test_db();
+        let messages = vec![
+            "Discord: https://example.com".to_string(),
+            "Follow!".to_string(),
+        ];
+        let input = ChatTimerInput {
+            name: "discord",
+            messages: &messages,
+            interval_minutes: 20,
+            min_chat_messages: 5,
+            enabled: true,
+            announce: false,
+            announce_color: "primary",
+        };
+        let id = db.add_chat_timer(&input, 100).unwrap();
+        let timer = db.get_chat_timer(id).unwrap().unwrap();
+        assert_eq!(timer.next_message(), Some("Discord: https://example.com"));
+        assert_eq!(timer.last_sent_at, None);
+
+        db.mark_chat_timer_sent(id, 200).unwrap();
+        let timer = db.get_chat_timer(id).unwrap().unwrap();
+        assert_eq!(timer.next_message(), Some("Follow!"));
+        db.mark_chat_timer_sent(id, 300).unwrap();
+        let timer = db.get_chat_timer(id).unwrap().unwrap();
+        assert_eq!(timer.next_message(), Some("Discord: https://example.com"));
+        assert_eq!(timer.last_sent_at, Some(300));
+
+        let input = ChatTimerInput {
+            enabled: false,
+            announce: true,
+            announce_color: "purple",
+            ..input
+        };
+        assert!(db.update_chat_timer(id, &input, 400).unwrap());
+        let timer = &db.get_chat_timers().unwrap()[0];
+        assert!(!timer.enabled);
+        assert!(timer.announce);
+        assert_eq!(timer.announce_color, "purple");
+        assert!(db.delete_chat_timer(id).unwrap());
+        assert!(!db.update_chat_timer(id, &input, 500).unwrap());
+    }
+
+    #[test]
+    fn test_chat_commands() {
+        use chat_commands::ChatCommandInput;
+        let db = test_db();
+        let input = ChatCommandInput {
+            trigger: "!Discord",
+            response: "Join us: https://example.com",
+            cooldown_secs: 30,
+            permission: "everyone",
+            enabled: true,
+        };
+        let id = db.add_chat_command(&input, 100).unwrap();
+        assert!(db.add_chat_command(&input, 100).is_err());
+        let command = db.get_chat_command_by_trigger("!DISCORD").unwrap().unwrap();
+        assert_eq!(command.id, id);
+        assert_eq!(command.trigger, "!discord");
+        assert_eq!(command.last_used_at, None);
+
+        db.mark_chat_command_used(id, 200).unwrap();
+        let input = ChatCommandInput {
+            permission: "moderator",
+            ..input
+        };
+        assert!(db.update_chat_command(id, &input, 300).unwrap());
+        let command = db.get_chat_command(id).unwrap().unwrap();
+        assert_eq!(command.use_count, 1);
+        assert_eq!(command.last_used_at, Some(200));
+        assert_eq!(command.permission, "moderator");
+
+        assert!(db.delete_chat_command(id).unwrap());
+        assert!(db.get_chat_commands().unwrap().is_empty());
+        assert!(!db.update_chat_command(id, &input, 400).unwrap());
+    }
+
+    #[test]
+    fn test_chat_full_text_search() {
+        use chat::ChatSearch;
+        let db = test_db();
+        let add = |id: &str, user: &str, text: &str, at: i64| {
+            let msg = chat::ChatMessage {
+                id: 0,
+                message_id: id.into(),
+                user_id: format!("{user}-id"),
+                username: user.into(),
+                message: text.into(),
+                fragments_json: "[]".into(),
+                avatar_url: String::new(),
+                translation_text: String::new(),
+                translation_status: String::new(),
+                translation_lang: String::new(),
+                created_at: at,
+                badges_json: String::new(),
+                color: String::new(),
+            };
+            db.add_chat_message(&msg).unwrap();
+        };
+        add("m1", "alice", "今日の配信たのしかった", 100);
+        add("m2", "bob", "Speedrun world record attempt", 200);
+        add("m3", "alice", "world record おめでとう", 300);
+
+        let search = |query: &str| ChatSearch {
+            query: query.into(),
+            limit: 10,
+            ..Default::default()
+        };
+        let page = db.full_text_search_chat(&search("WORLD record")).unwrap();
+        assert_eq!(page.total, 2);
+        assert!(page.hits.iter().all(|h| h.score < 0.0));
+
+        // Japanese substrings and terms too short for the trigram index.
+        let page = db.full_text_search_chat(&search("配信た")).unwrap();
+        assert_eq!(page.hits[0].message.message_id, "m1");
+        let page = db.full_text_search_chat(&search("配信")).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.hits[0].score, 0.0);
+
+        let page = db
+            .full_text_search_chat(&ChatSearch {
+                user: Some("ALICE".into()),
+                from: Some(150),
+                ..search("record")
+            })
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.hits[0].message.message_id, "m3");
+
+        let page = db
+            .full_text_search_chat(&ChatSearch {
+                offset: 1,
+                limit: 1,
+                ..search("record")
+            })
+            .unwrap();
+        assert_eq!((page.total, page.hits.len()), (2, 1));
+
+        // The index follows deletes.
+        db.cleanup_chat_messages_before(250).unwrap();
+        assert_eq!(
+            db.full_text_search_chat(&search("record")).unwrap().total,
+            1
+        );
+        assert_eq!(db.search_chat_messages("world rec", 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_chat_stats() {
+        use serde_json::json;
+        let db = test_db();
+        let emote =
+            |id: &str, name: &str| json!({ "type": "emote", "text": name, "emote": { "id": id } });
+        let text = json!({ "type": "text", "text": " " });
+        let add = |id: &str, user: &str, fragments: serde_json::Value, at: i64| {
+            let msg = chat::ChatMessage {
+                id: 0,
+                message_id: id.into(),
+                user_id: format!("{user}-id"),
+                username: user.into(),
+                message: String::new(),
+                fragments_json: fragments.to_string(),
+                avatar_url: String::new(),
+                translation_text: String::new(),
+                translation_status: String::new(),
+                translation_lang: String::new(),
+                created_at: at,
+                badges_json: String::new(),
+                color: String::new(),
+            };
+            db.add_chat_message(&msg).unwrap();
+        };
+        let kappa = emote("25", "Kappa");
+        add("m1", "alice", json!([kappa, text, kappa]), 3600);
+        add("m2", "bob", json!([kappa]), 3700);
+        add("m3", "alice", json!([emote("1", "PogChamp")]), 7300);
+        add("m4", "streamer", json!([kappa]), 7400);
+        add("m5", "carol", json!([text]), 99_999);
+
+        let stats = db.get_chat_stats(0, 10_000, "streamer-id", 10).unwrap();
+        assert_eq!((stats.messages, stats.unique_chatters), (3, 2));
+        assert_eq!(stats.top_chatters[0].username, "alice");
+        assert_eq!(stats.top_chatters[0].messages, 2);
+        assert_eq!(stats.top_chatters[0].last_message_at, 7300);
+        let hours: Vec<_> = stats.hourly.iter().map(|h| (h.hour, h.messages)).collect();
+        assert_eq!(hours, vec![(3600, 2), (7200, 1)]);
+        assert_eq!(stats.emotes[0].name, "Kappa");
+        assert_eq!((stats.emotes[0].uses, stats.emotes[0].chatters), (3, 2));
+        assert_eq!(stats.emotes.len(), 2);
+
+        let stats = db.get_chat_stats(0, 10_000, "", 1).unwrap();
+        assert_eq!(stats.unique_chatters, 3);
+        assert_eq!(stats.top_chatters.len(), 1);
+        assert_eq!(stats.emotes[0].uses, 4);
+    }
+
+    #[test]
+    fn test_print_votes() {
+        use print_votes::{SOURCE_REPLY, SOURCE_REWARD};
+        let db = test_db();
+        for (id, user, at) in [("m1", "alice", 100), ("m2", "bob", 200)] {
+            let msg = chat::ChatMessage {
+                id: 0,
+                message_id: id.into(),
+                user_id: format!("{user}-id"),
+                username: user.into(),
+                message: format!("hello from {user}"),
+                fragments_json: "[]".into(),
+                avatar_url: String::new(),
+                translation_text: String::new(),
+                translation_status: String::new(),
+                translation_lang: String::new(),
+                created_at: at,
+                badges_json: String::new(),
+                color: String::new(),
+            };
+            db.add_chat_message(&msg).unwrap();
+        }
+
+        let vote = |id: &str, voter: &str, source: &str, at: i64| {
+            db.add_print_vote(id, voter, source, at).unwrap()
+        };
+        assert!(vote("m1", "carol-id", SOURCE_REPLY, 300));
+        // One vote per viewer and message, none for your own or unknown messages.
+        assert!(!vote("m1", "carol-id", SOURCE_REWARD, 310));
+        assert!(!vote("m1", "alice-id", SOURCE_REPLY, 320));
+        assert!(!vote("missing", "carol-id", SOURCE_REPLY, 330));
+        assert!(vote("m2", "carol-id", SOURCE_REPLY, 340));
+        assert!(vote("m2", "dave-id", SOURCE_REWARD, 350));
+
+        let tally = db.get_print_vote_tally(10).unwrap();
+        assert_eq!(tally.len(), 2);
+        assert_eq!(tally[0].message.message_id, "m2");
+        assert_eq!((tally[0].votes, tally[0].first_vote_at), (2, 340));
+
+        db.record_print_vote_winner(&tally[0].message, tally[0].votes, 400)
+            .unwrap();
+        assert!(db.get_print_vote_tally(10).unwrap().is_empty());
+        assert!(!vote("m2", "erin-id", SOURCE_REPLY, 410));
+        let winners = db.get_print_vote_winners(10).unwrap();
+        assert_eq!(winners[0].username, "bob");
+        assert_eq!(winners[0].votes, 2);
+
+        assert!(vote("m1", "erin-id", SOURCE_REPLY, 420));
+        assert_eq!(db.clear_print_votes().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_stream_sessions() {
+        use stream_sessions::StreamSample;
+        let db = test_db();
+        let first = db.start_stream_session("s1", 1000).unwrap();
+        // A redelivered stream.online keeps the session.
+        assert_eq!(db.start_stream_session("s1", 1005).unwrap().id, first.id);
+
+        let sample = |viewers, followers, subs| StreamSample {
+            title: Some("Speedrun".into()),
+            category: None,
+            viewers: Some(viewers),
+            followers: Some(followers),
+            subs,
+        };
+        db.record_stream_sample(first.id, &sample(10, 500, Some(40)), 1060)
+            .unwrap();
+        db.record_stream_sample(first.id, &sample(25, 510, None), 1120)
+            .unwrap();
+        db.record_stream_sample(first.id, &sample(20, 507, Some(43)), 1180)
+            .unwrap();
+
+        let open = db.get_open_stream_session().unwrap().unwrap();
+        assert_eq!(open.title, "Speedrun");
+        assert_eq!((open.peak_viewers, open.peak_viewers_at), (25, Some(1120)));
+        assert_eq!(open.follower_delta(), Some(7));
+        assert_eq!(open.sub_delta(), Some(3));
+        let viewers: Vec<i64> = db
+            .get_viewer_samples(first.id)
+            .unwrap()
+            .iter()
+            .map(|v| v.viewers)
+            .collect();
+        assert_eq!(viewers, vec![10, 25, 20]);
+
+        // A new stream closes one left open at its last sample.
+        let second = db.start_stream_session("s2", 5000).unwrap();
+        let first = db.get_stream_session(first.id).unwrap().unwrap();
+        assert_eq!(first.ended_at, Some(1180));
+        assert_eq!(second.follower_delta(), None);
+
+        let ended = db.end_stream_session(6000).unwrap().unwrap();
+        assert_eq!((ended.id, ended.ended_at), (second.id, Some(6000)));
+        assert!(db.end_stream_session(6001).unwrap().is_none());
+        let all = db.get_stream_sessions(10, 0).unwrap();
+        let ids: Vec<i64> = all.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![second.id, first.id]);
+    }
+
+    #[test]
+    fn test_first_chat() {
+        let db = test_db();
+        let first = db.record_chatter("u1", "alice", 100).unwrap();
+        assert!(first.first_ever && !first.first_this_stream);
+        assert!(!db.record_chatter("u1", "alice", 110).unwrap().any());
+
+        let session = db.start_stream_session("s1", 200).unwrap();
+        let again = db.record_chatter("u1", "alice", 210).unwrap();
+        assert!(!again.first_ever && again.first_this_stream);
+        assert_eq!(again.session_id, Some(session.id));
+        assert!(!db.record_chatter("u1", "alice", 220).unwrap().any());
+
+        db.end_stream_session(300).unwrap();
+        db.start_stream_session("s2", 400).unwrap();
+        let next_stream = db.record_chatter("u1", "alice", 410).unwrap();
+        assert!(next_stream.first_this_stream);
+    }
+
+    #[test]
+    fn test_viewer_profiles() {
+        let db = test_db();
+        assert!(db.get_viewer_profile("u1").unwrap().is_none());
+        let mut profile = profiles::ViewerProfile {
+            user_id: "u1".into(),
+            login: "alice".into(),
+            display_name: "Alice".into(),
+            source: "helix".into(),
+            fetched_at: 100,
+            ..Default::default()
+        };
+        db.save_viewer_profile(&profile).unwrap();
+        profile.source = "ivr".into();
+        profile.fetched_at = 200;
+        db.save_viewer_profile(&profile).unwrap();
+        assert_eq!(db.get_viewer_profile("u1").unwrap(), Some(profile));
+
+        assert_eq!(db.prune_viewer_profiles(200).unwrap(), 0);
+        assert_eq!(db.prune_viewer_profiles(201).unwrap(), 1);
+        assert!(db.get_viewer_profile("u1").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_open_copy() {
+        let dir = std::env::temp_dir();
+        let stamp = format!(
+            "{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        );
+        let path = dir.join(format!("overlay-copy-src-{stamp}.db"));
+        let scratch = dir.join(format!("overlay-copy-dst-{stamp}.db"));
+        let db = Database::open(&path).unwrap();
+        db.set_setting("PRINTER_ADDRESS", "AA:BB", "normal")
+            .unwrap();
+
+        let copy = Database::open_copy(&path, &scratch).unwrap();
+        assert_eq!(copy.schema_version().unwrap(), schema::latest_version());
+        assert!(copy.apply_pending_migrations().unwrap().is_empty());
+        assert_eq!(
+            copy.get_setting("PRINTER_ADDRESS").unwrap(),
+            Some("AA:BB".into())
+        );
+        copy.set_setting("PRINTER_ADDRESS", "CC:DD", "normal")
+            .unwrap();
+        assert_eq!(
+            db.get_setting("PRINTER_ADDRESS").unwrap(),
+            Some("AA:BB".into())
+        );
+
+        drop((db, copy));
+        for p in [path, scratch] {
+            let _ = std::fs::remove_file(p);
+        }
+    }
+
+    #[test]
+    fn test_moderation_log() {
+        use crate::moderation::{ModerationAction, ModerationLogQuery};
+
+        let db = test_db();
+        let entry = |action: &str, moderator: &str, at: i64| ModerationAction {
+            action: action.into(),
+            moderator_id: format!("{moderator}-id"),
+            moderator_login: moderator.into(),
+            moderator_name: moderator.to_uppercase(),
+            target_login: "troll".into(),
+            created_at: at,
+            ..Default::default()
+        };
+        db.add_moderation_action(&entry("ban", "alice", 100))
+            .unwrap();
+        db.add_moderation_action(&ModerationAction {
+            duration_secs: Some(600),
+            details: serde_json::json!({ "reason": "spam" }),
+            ..entry("timeout", "bob", 200)
+        })
+        .unwrap();
+        db.add_moderation_action(&entry("timeout", "alice", 300))
+            .unwrap();
+
+        let all = |q: ModerationLogQuery| {
+            db.get_moderation_log(&ModerationLogQuery { limit: 10, ..q })
+                .unwrap()
+        };
+        let log = all(ModerationLogQuery::default());
+        assert_eq!(log.len(), 3);
+        assert_eq!(log[0].created_at, 300);
+        assert_eq!(log[1].duration_secs, Some(600));
+        assert_eq!(log[1].details["reason"], "spam");
+
+        let by_alice = all(ModerationLogQuery {
+            moderator: Some("ALICE".into()),
+            ..Default::default()
+        });
+        assert_eq!(by_alice.len(), 2);
+        let by_id = all(ModerationLogQuery {
+            moderator: Some("bob-id".into()),
+            ..Default::default()
+        });
+        assert_eq!(by_id.len(), 1);
+        let timeouts = all(ModerationLogQuery {
+            action: Some("timeout".into()),
+            from: Some(250),
+            ..Default::default()
+        });
+        assert_eq!(timeouts.len(), 1);
+        assert_eq!(timeouts[0].moderator_login, "alice");
+    }
+
+    #[test]
+    fn test_moderation_events() {
+        use crate::moderation::ModerationEvent;
+
+        let db = test_db();
+        let event = |login: &str, action: &str, at: i64| ModerationEvent {
+            user_id: format!("{login}-id"),
+            user_login: login.into(),
+            message: "some badword".into(),
+            matched: vec!["badword".into()],
+            action: action.into(),
+            created_at: at,
+            ..Default::default()
+        };
+        db.add_moderation_event(&event("troll", "redact", 100))
+            .unwrap();
+        db.add_moderation_event(&ModerationEvent {
+            duration_secs: Some(60),
+            ..event("troll", "timeout", 200)
+        })
+        .unwrap();
+        db.add_moderation_event(&event("alice", "flag", 300))
+            .unwrap();
+
+        let all = db.get_moderation_events(None, 10).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].action, "flag");
+        assert_eq!(all[1].duration_secs, Some(60));
+        assert_eq!(all[2].matched, vec!["badword"]);
+
+        let troll = db.get_moderation_events(Some("TROLL"), 10).unwrap();
+        assert_eq!(troll.len(), 2);
+        assert_eq!(
+            db.get_moderation_events(Some("alice-id"), 1).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_irc_chat_messages() {
+        use crate::irc::IrcChatMessage;
+
+        let db = test_db();
+        let msg = |id: &str, channel: &str, at: i64| IrcChatMessage {
+            channel: channel.into(),
+            message_id: id.into(),
+            login: "alice".into(),
+            message: format!("hi from {id}"),
+            created_at: at,
+            ..Default::default()
+        };
+        assert!(
+            db.add_irc_chat_message(&msg("a", "bob", 100))
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            db.add_irc_chat_message(&msg("a", "bob", 100)).unwrap(),
+            None
+        );
+        db.add_irc_chat_message(&msg("b", "bob", 200)).unwrap();
+        db.add_irc_chat_message(&msg("c", "carol", 300)).unwrap();
+
+        let bob = db.get_irc_chat_messages(Some("Bob"), 0, 10).unwrap();
+        assert_eq!(bob.len(), 2);
+        assert_eq!(bob[0].message_id, "a");
+        let latest = db.get_irc_chat_messages(None, 0, 2).unwrap();
+        let ids: Vec<_> = latest.iter().map(|m| m.message_id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+
+        assert_eq!(db.cleanup_irc_chat_messages_before(250).unwrap(), 2);
+        assert_eq!(db.get_irc_chat_messages(None, 0, 10).unwrap().len(), 1);
+    }
 }
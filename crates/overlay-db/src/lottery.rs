@@ -1,6 +1,8 @@
-//! Lottery/present participant storage.
+//! Lottery/present participant storage and draw history.
 
-use crate::{Database, DbError};
+use crate::lottery_engine::ENTRY_LIMIT;
+use crate::{Database, DbError, like_contains};
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,18 +14,130 @@ pub struct LotteryParticipant {
     pub redeemed_at: String,
     pub is_subscriber: bool,
     pub subscriber_tier: String,
+    /// Cumulative subscription months, 0 when unknown.
+    #[serde(default)]
+    pub subscribed_months: i32,
+    /// Redemptions; tickets are worked out by `lottery_engine`.
     pub entry_count: i32,
     pub assigned_color: String,
 }
 
+/// One drawn winner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LotteryDraw {
+    pub id: i64,
+    pub user_id: String,
+    pub username: String,
+    pub display_name: String,
+    pub participant_count: i64,
+    pub drawn_at: i64,
+    /// `None` for draws recorded without a proof.
+    #[serde(default)]
+    pub proof: Option<DrawProof>,
+    #[serde(default)]
+    pub delivery: DrawDelivery,
+    #[serde(default)]
+    pub claim: DrawClaim,
+    /// The draw whose winner this one replaced after they did not claim.
+    #[serde(default)]
+    pub redraw_of: Option<i64>,
+}
+
+pub const CLAIM_PENDING: &str = "pending";
+pub const CLAIM_CLAIMED: &str = "claimed";
+pub const CLAIM_EXPIRED: &str = "expired";
+
+pub const CLAIM_SOURCE_COMMAND: &str = "command";
+pub const CLAIM_SOURCE_REWARD: &str = "reward";
+pub const CLAIM_SOURCE_MANUAL: &str = "manual";
+
+/// Whether the winner claimed their prize.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DrawClaim {
+    /// `CLAIM_*`; empty for draws made before claims were tracked.
+    pub status: String,
+    pub claimed_at: Option<i64>,
+    /// `CLAIM_SOURCE_*` of a claimed draw.
+    pub source: String,
+}
+
+pub const DELIVERY_SENT: &str = "sent";
+pub const DELIVERY_FAILED: &str = "failed";
+
+/// How the winner was told about a draw.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DrawDelivery {
+    pub claim_deadline: Option<i64>,
+    /// `DELIVERY_*`; empty when not attempted.
+    pub whisper_status: String,
+    pub announce_status: String,
+    /// Why a delivery failed; empty when none did.
+    pub error: String,
+}
+
+/// What a viewer needs to recompute a draw.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrawProof {
+    pub algorithm: String,
+    /// Hash of `seed`, published before the draw.
+    pub commitment: String,
+    /// When the commitment was published; `None` when the seed was made at
+    /// the draw itself.
+    pub committed_at: Option<i64>,
+    pub seed: String,
+    pub entries_digest: String,
+    /// Entries in ticket order.
+    pub entries: Vec<DrawEntry>,
+    pub total_tickets: i64,
+    /// Zero-based ticket number that won.
+    pub winning_ticket: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrawEntry {
+    pub user_id: String,
+    pub name: String,
+    pub tickets: i64,
+}
+
+const SELECT_DRAW: &str =
+    "SELECT id, user_id, username, display_name, participant_count, drawn_at, proof,
+        claim_deadline, whisper_status, announce_status, delivery_error,
+        claim_status, claimed_at, claim_source, redraw_of
+    FROM lottery_draws";
+
+fn map_draw(row: &rusqlite::Row<'_>) -> rusqlite::Result<LotteryDraw> {
+    Ok(LotteryDraw {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        username: row.get(2)?,
+        display_name: row.get(3)?,
+        participant_count: row.get(4)?,
+        drawn_at: row.get(5)?,
+        proof: serde_json::from_str(&row.get::<_, String>(6)?).ok(),
+        delivery: DrawDelivery {
+            claim_deadline: row.get(7)?,
+            whisper_status: row.get(8)?,
+            announce_status: row.get(9)?,
+            error: row.get(10)?,
+        },
+        claim: DrawClaim {
+            status: row.get(11)?,
+            claimed_at: row.get(12)?,
+            source: row.get(13)?,
+        },
+        redraw_of: row.get(14)?,
+    })
+}
+
 impl Database {
     pub fn add_lottery_participant(&self, p: &LotteryParticipant) -> Result<(), DbError> {
         self.with_conn(|conn| {
             conn.execute(
                 "INSERT INTO lottery_participants
                     (user_id, username, display_name, avatar_url, redeemed_at, is_subscriber,
-                     subscriber_tier, entry_count, assigned_color, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, CURRENT_TIMESTAMP)
+                     subscriber_tier, subscribed_months, entry_count, assigned_color, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, CURRENT_TIMESTAMP)
                  ON CONFLICT(user_id) DO UPDATE SET
                     username = excluded.username,
                     display_name = excluded.display_name,
@@ -31,7 +145,8 @@ impl Database {
                     redeemed_at = excluded.redeemed_at,
                     is_subscriber = excluded.is_subscriber,
                     subscriber_tier = excluded.subscriber_tier,
-                    entry_count = MIN(lottery_participants.entry_count + excluded.entry_count, 3),
+                    subscribed_months = excluded.subscribed_months,
+                    entry_count = MIN(lottery_participants.entry_count + excluded.entry_count, ?11),
                     assigned_color = excluded.assigned_color,
                     updated_at = CURRENT_TIMESTAMP",
                 rusqlite::params![
@@ -42,8 +157,10 @@ impl Database {
                     p.redeemed_at,
                     p.is_subscriber,
                     p.subscriber_tier,
+                    p.subscribed_months,
                     p.entry_count,
                     p.assigned_color,
+                    ENTRY_LIMIT,
                 ],
             )?;
             Ok(())
@@ -54,7 +171,7 @@ impl Database {
         self.with_conn(|conn| {
             let mut stmt = conn.prepare(
                 "SELECT user_id, username, display_name, avatar_url, redeemed_at, is_subscriber,
-                        subscriber_tier, entry_count, assigned_color
+                        subscriber_tier, entry_count, assigned_color, subscribed_months
                  FROM lottery_participants ORDER BY redeemed_at ASC",
             )?;
             let rows = stmt.query_map([], |row| {
@@ -68,6 +185,7 @@ impl Database {
                     subscriber_tier: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
                     entry_count: row.get(7)?,
                     assigned_color: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+                    subscribed_months: row.get(9)?,
                 })
             })?;
             rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
@@ -92,13 +210,179 @@ impl Database {
         })
     }
 
-    pub fn fix_entry_counts_over_3(&self) -> Result<(), DbError> {
+    /// Record a drawn winner; their claim starts out pending.
+    pub fn add_lottery_draw(
+        &self,
+        winner: &LotteryParticipant,
+        participant_count: i64,
+        drawn_at: i64,
+        proof: Option<&DrawProof>,
+    ) -> Result<LotteryDraw, DbError> {
+        let proof_json = proof
+            .and_then(|p| serde_json::to_string(p).ok())
+            .unwrap_or_default();
         self.with_conn(|conn| {
             conn.execute(
-                "UPDATE lottery_participants SET entry_count = 3 WHERE entry_count > 3",
-                [],
+                "INSERT INTO lottery_draws
+                    (user_id, username, display_name, participant_count, drawn_at, proof,
+                     claim_status)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    winner.user_id,
+                    winner.username,
+                    winner.display_name,
+                    participant_count,
+                    drawn_at,
+                    proof_json,
+                    CLAIM_PENDING,
+                ],
+            )?;
+            Ok(LotteryDraw {
+                id: conn.last_insert_rowid(),
+                user_id: winner.user_id.clone(),
+                username: winner.username.clone(),
+                display_name: winner.display_name.clone(),
+                participant_count,
+                drawn_at,
+                proof: proof.cloned(),
+                delivery: DrawDelivery::default(),
+                claim: DrawClaim {
+                    status: CLAIM_PENDING.to_string(),
+                    ..Default::default()
+                },
+                redraw_of: None,
+            })
+        })
+    }
+
+    pub fn get_lottery_draw(&self, id: i64) -> Result<Option<LotteryDraw>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(&format!("{SELECT_DRAW} WHERE id = ?1"), [id], map_draw)
+                .optional()
+                .map_err(Into::into)
+        })
+    }
+
+    /// Record how the winner of draw `id` was told.
+    pub fn set_lottery_draw_delivery(
+        &self,
+        id: i64,
+        delivery: &DrawDelivery,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE lottery_draws SET claim_deadline = ?2, whisper_status = ?3,
+                    announce_status = ?4, delivery_error = ?5
+                 WHERE id = ?1",
+                rusqlite::params![
+                    id,
+                    delivery.claim_deadline,
+                    delivery.whisper_status,
+                    delivery.announce_status,
+                    delivery.error,
+                ],
             )?;
             Ok(())
         })
     }
+
+    pub fn set_lottery_draw_redraw_of(&self, id: i64, redraw_of: i64) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE lottery_draws SET redraw_of = ?2 WHERE id = ?1",
+                [id, redraw_of],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Mark draw `id` claimed; `false` when it was not pending.
+    pub fn claim_lottery_draw(&self, id: i64, source: &str, now: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let changed = conn.execute(
+                "UPDATE lottery_draws SET claim_status = ?2, claimed_at = ?3, claim_source = ?4
+                 WHERE id = ?1 AND claim_status = ?5",
+                rusqlite::params![id, CLAIM_CLAIMED, now, source, CLAIM_PENDING],
+            )?;
+            Ok(changed > 0)
+        })
+    }
+
+    /// Mark draw `id` expired; `false` when it was not pending.
+    pub fn expire_lottery_draw(&self, id: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let changed = conn.execute(
+                "UPDATE lottery_draws SET claim_status = ?2 WHERE id = ?1 AND claim_status = ?3",
+                rusqlite::params![id, CLAIM_EXPIRED, CLAIM_PENDING],
+            )?;
+            Ok(changed > 0)
+        })
+    }
+
+    /// Expire every draw still waiting for a claim; returns how many were.
+    pub fn expire_pending_lottery_draws(&self) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE lottery_draws SET claim_status = ?1 WHERE claim_status = ?2",
+                rusqlite::params![CLAIM_EXPIRED, CLAIM_PENDING],
+            )
+            .map_err(Into::into)
+        })
+    }
+
+    /// `user_id`'s latest draw still waiting for a claim.
+    pub fn get_pending_lottery_draw(&self, user_id: &str) -> Result<Option<LotteryDraw>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                &format!(
+                    "{SELECT_DRAW} WHERE user_id = ?1 AND claim_status = ?2
+                     ORDER BY drawn_at DESC, id DESC LIMIT 1"
+                ),
+                rusqlite::params![user_id, CLAIM_PENDING],
+                map_draw,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    /// Pending draws whose claim deadline is at or before `now`, oldest first.
+    pub fn get_overdue_lottery_draws(&self, now: i64) -> Result<Vec<LotteryDraw>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_DRAW} WHERE claim_status = ?1 AND claim_deadline <= ?2
+                 ORDER BY drawn_at ASC, id ASC"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params![CLAIM_PENDING, now], map_draw)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Past draws, newest first.
+    pub fn get_lottery_draws(&self, limit: i64) -> Result<Vec<LotteryDraw>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_DRAW} ORDER BY drawn_at DESC, id DESC LIMIT ?1"
+            ))?;
+            let rows = stmt.query_map([limit], map_draw)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Past draws whose winner name contains `query`, newest first.
+    pub fn search_lottery_draws(
+        &self,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<LotteryDraw>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_DRAW}
+                 WHERE username LIKE ?1 ESCAPE '\\' OR display_name LIKE ?1 ESCAPE '\\'
+                 ORDER BY drawn_at DESC, id DESC LIMIT ?2"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params![like_contains(query), limit], map_draw)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
 }
@@ -0,0 +1,143 @@
+//! Lottery ticket calculation.
+//!
+//! A participant holds `base + bonus` tickets:
+//!
+//! - `base` is their redemption count, capped at `max_entries`.
+//! - `bonus` is for subscribers only: the flat `tier_bonus` of their tier
+//!   plus `tier_coefficients[tier] × months ÷ months_divisor` (rounded down),
+//!   capped at `max_bonus` when set.
+//!
+//! The parameters are a [`TicketFormula`], stored as JSON in the
+//! `LOTTERY_TICKET_FORMULA` setting; an empty setting or a missing field
+//! takes the default. The defaults give Tier 1/2/3 subscribers 3/6/12 bonus
+//! tickets and no month bonus.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::lottery::LotteryParticipant;
+
+/// Upper bound for stored entry counts and for `max_entries`/`max_bonus`.
+pub const ENTRY_LIMIT: i32 = 100;
+
+/// Tier assumed for subscribers whose tier is unknown.
+const DEFAULT_TIER: &str = "1000";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TicketFormula {
+    /// Most redemptions counted per participant.
+    pub max_entries: i32,
+    /// Flat bonus per subscription tier (`"1000"`, `"2000"`, `"3000"`).
+    pub tier_bonus: BTreeMap<String, i32>,
+    /// Month bonus coefficient per subscription tier.
+    pub tier_coefficients: BTreeMap<String, f64>,
+    pub months_divisor: i32,
+    pub max_bonus: Option<i32>,
+}
+
+impl Default for TicketFormula {
+    fn default() -> Self {
+        Self {
+            max_entries: 3,
+            tier_bonus: BTreeMap::from([
+                ("1000".to_string(), 3),
+                ("2000".to_string(), 6),
+                ("3000".to_string(), 12),
+            ]),
+            tier_coefficients: BTreeMap::new(),
+            months_divisor: 3,
+            max_bonus: None,
+        }
+    }
+}
+
+/// A participant's tickets and how they add up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TicketBreakdown {
+    pub base: i32,
+    pub bonus: i32,
+    pub total: i32,
+}
+
+impl TicketFormula {
+    /// Parse and check a formula; empty input is the default formula.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        if json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let formula: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        formula.validate()?;
+        Ok(formula)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=ENTRY_LIMIT).contains(&self.max_entries) {
+            return Err(format!("max_entries must be between 1 and {ENTRY_LIMIT}"));
+        }
+        if self.months_divisor < 1 {
+            return Err("months_divisor must be at least 1".to_string());
+        }
+        if self
+            .max_bonus
+            .is_some_and(|max| !(0..=ENTRY_LIMIT).contains(&max))
+        {
+            return Err(format!("max_bonus must be between 0 and {ENTRY_LIMIT}"));
+        }
+        if let Some((tier, _)) = self
+            .tier_bonus
+            .iter()
+            .find(|(_, bonus)| !(0..=ENTRY_LIMIT).contains(*bonus))
+        {
+            return Err(format!(
+                "tier_bonus for {tier} must be between 0 and {ENTRY_LIMIT}"
+            ));
+        }
+        if let Some((tier, _)) = self
+            .tier_coefficients
+            .iter()
+            .find(|(_, c)| !c.is_finite() || **c < 0.0)
+        {
+            return Err(format!("tier_coefficients for {tier} must be 0 or more"));
+        }
+        Ok(())
+    }
+
+    /// Tickets for `entries` redemptions by a viewer with the given
+    /// subscription (`tier` empty when unknown).
+    pub fn tickets(
+        &self,
+        entries: i32,
+        is_subscriber: bool,
+        tier: &str,
+        months: i32,
+    ) -> TicketBreakdown {
+        let base = entries.clamp(1, self.max_entries);
+        let bonus = if is_subscriber {
+            let tier = if tier.is_empty() { DEFAULT_TIER } else { tier };
+            let flat = self.tier_bonus.get(tier).copied().unwrap_or(0);
+            let coefficient = self.tier_coefficients.get(tier).copied().unwrap_or(0.0);
+            let by_months =
+                (coefficient * f64::from(months.max(0)) / f64::from(self.months_divisor)).floor();
+            let bonus = flat.saturating_add(by_months.min(f64::from(ENTRY_LIMIT)) as i32);
+            self.max_bonus.map_or(bonus, |max| bonus.min(max))
+        } else {
+            0
+        };
+        TicketBreakdown {
+            base,
+            bonus,
+            total: base + bonus,
+        }
+    }
+
+    pub fn for_participant(&self, p: &LotteryParticipant) -> TicketBreakdown {
+        self.tickets(
+            p.entry_count,
+            p.is_subscriber,
+            &p.subscriber_tier,
+            p.subscribed_months,
+        )
+    }
+}
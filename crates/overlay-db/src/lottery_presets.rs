@@ -0,0 +1,83 @@
+//! Named lottery setting presets.
+
+use std::collections::BTreeMap;
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LotteryPreset {
+    pub name: String,
+    pub description: String,
+    /// Setting key → value.
+    pub settings: BTreeMap<String, String>,
+    pub updated_at: i64,
+}
+
+fn row_to_preset(row: &rusqlite::Row<'_>) -> rusqlite::Result<LotteryPreset> {
+    let settings: String = row.get(2)?;
+    Ok(LotteryPreset {
+        name: row.get(0)?,
+        description: row.get(1)?,
+        settings: serde_json::from_str(&settings).unwrap_or_default(),
+        updated_at: row.get(3)?,
+    })
+}
+
+impl Database {
+    pub fn get_lottery_presets(&self) -> Result<Vec<LotteryPreset>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT name, description, settings, updated_at FROM lottery_presets
+                 ORDER BY name ASC",
+            )?;
+            let rows = stmt.query_map([], row_to_preset)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    pub fn get_lottery_preset(&self, name: &str) -> Result<Option<LotteryPreset>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT name, description, settings, updated_at FROM lottery_presets
+                 WHERE name = ?1",
+                [name],
+                row_to_preset,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    /// Create or replace a preset.
+    pub fn upsert_lottery_preset(
+        &self,
+        name: &str,
+        description: &str,
+        settings: &BTreeMap<String, String>,
+        now: i64,
+    ) -> Result<(), DbError> {
+        let settings = serde_json::to_string(settings).unwrap_or_else(|_| "{}".to_string());
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO lottery_presets (name, description, settings, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(name) DO UPDATE SET
+                    description = excluded.description,
+                    settings = excluded.settings,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![name, description, settings, now],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Delete a preset. Returns false if it does not exist.
+    pub fn delete_lottery_preset(&self, name: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM lottery_presets WHERE name = ?1", [name])?;
+            Ok(n > 0)
+        })
+    }
+}
@@ -0,0 +1,86 @@
+//! Action macros (ordered step sequences run by one trigger).
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Macro {
+    pub id: i64,
+    pub name: String,
+    pub steps: Value,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+const SELECT: &str = "SELECT id, name, steps, created_at, updated_at FROM macros";
+
+fn map_macro(row: &rusqlite::Row<'_>) -> rusqlite::Result<Macro> {
+    let steps: String = row.get(2)?;
+    Ok(Macro {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        steps: serde_json::from_str(&steps).unwrap_or(Value::Null),
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+impl Database {
+    pub fn add_macro(&self, name: &str, steps: &Value, now: i64) -> Result<Macro, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO macros (name, steps, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+                rusqlite::params![name, steps.to_string(), now],
+            )?;
+            Ok(Macro {
+                id: conn.last_insert_rowid(),
+                name: name.to_string(),
+                steps: steps.clone(),
+                created_at: now,
+                updated_at: now,
+            })
+        })
+    }
+
+    pub fn get_macro(&self, id: i64) -> Result<Option<Macro>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(&format!("{SELECT} WHERE id = ?1"), [id], map_macro)
+                .optional()
+                .map_err(Into::into)
+        })
+    }
+
+    pub fn get_macros(&self) -> Result<Vec<Macro>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("{SELECT} ORDER BY name ASC, id ASC"))?;
+            let rows = stmt.query_map([], map_macro)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Replace a macro's name and steps. Returns false if it does not exist.
+    pub fn update_macro(
+        &self,
+        id: i64,
+        name: &str,
+        steps: &Value,
+        now: i64,
+    ) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE macros SET name = ?2, steps = ?3, updated_at = ?4 WHERE id = ?1",
+                rusqlite::params![id, name, steps.to_string(), now],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    pub fn delete_macro(&self, id: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM macros WHERE id = ?1", [id])?;
+            Ok(n > 0)
+        })
+    }
+}
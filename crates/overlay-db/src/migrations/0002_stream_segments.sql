@@ -0,0 +1,11 @@
+-- Stream segments ("Just Chatting", "Ranked", ...) switched during a broadcast.
+
+CREATE TABLE IF NOT EXISTS stream_segments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    ended_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_stream_segments_started_at
+    ON stream_segments(started_at);
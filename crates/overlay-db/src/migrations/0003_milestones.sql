@@ -0,0 +1,9 @@
+-- Milestones (follower/viewer round numbers) that have already been celebrated.
+
+CREATE TABLE IF NOT EXISTS milestones_fired (
+    kind TEXT NOT NULL,
+    threshold INTEGER NOT NULL,
+    value INTEGER NOT NULL,
+    fired_at INTEGER NOT NULL,
+    PRIMARY KEY (kind, threshold)
+);
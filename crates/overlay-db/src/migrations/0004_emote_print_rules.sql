@@ -0,0 +1,12 @@
+-- Per-emote print allow/deny list with an approval queue for new emotes.
+
+CREATE TABLE IF NOT EXISTS emote_print_rules (
+    emote_id TEXT PRIMARY KEY,
+    emote_name TEXT NOT NULL DEFAULT '',
+    status TEXT NOT NULL DEFAULT 'pending' CHECK(status IN ('pending', 'allowed', 'denied')),
+    first_seen_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_emote_print_rules_status
+    ON emote_print_rules(status);
@@ -0,0 +1,15 @@
+-- Event-to-output mappings for external integrations (OSC, MIDI, lights).
+-- `params` holds the integration-specific payload as JSON.
+
+CREATE TABLE IF NOT EXISTS event_triggers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    integration TEXT NOT NULL,
+    event TEXT NOT NULL,
+    min_amount INTEGER NOT NULL DEFAULT 0,
+    params TEXT NOT NULL DEFAULT '{}',
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_triggers_lookup
+    ON event_triggers(integration, event, min_amount);
@@ -0,0 +1,9 @@
+-- User-defined overlay visual effect presets (confetti, fireworks, screen shake).
+
+CREATE TABLE IF NOT EXISTS effect_presets (
+    name TEXT PRIMARY KEY,
+    effect TEXT NOT NULL,
+    params TEXT NOT NULL DEFAULT '{}',
+    duration_ms INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,10 @@
+-- Emote rain: when each emote last rained, with optional per-emote cooldowns.
+
+CREATE TABLE IF NOT EXISTS emote_rain_cooldowns (
+    emote_id TEXT PRIMARY KEY,
+    emote_name TEXT NOT NULL DEFAULT '',
+    -- NULL uses the global EMOTE_RAIN_COOLDOWN_SECONDS setting.
+    cooldown_seconds INTEGER,
+    last_fired_at INTEGER NOT NULL DEFAULT 0,
+    fire_count INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,11 @@
+-- Quote database (`!quote` chat commands).
+
+CREATE TABLE IF NOT EXISTS quotes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    text TEXT NOT NULL,
+    -- Who said it (usually the broadcaster).
+    author TEXT NOT NULL DEFAULT '',
+    -- Who added it (chat login or "dashboard").
+    added_by TEXT NOT NULL DEFAULT '',
+    created_at INTEGER NOT NULL
+);
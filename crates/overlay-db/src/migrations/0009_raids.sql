@@ -0,0 +1,15 @@
+-- Raid history: inbound (`in`) and outbound (`out`) raids.
+
+CREATE TABLE IF NOT EXISTS raids (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    direction TEXT NOT NULL CHECK (direction IN ('in', 'out')),
+    -- The other channel (raider for `in`, target for `out`).
+    channel_id TEXT NOT NULL,
+    channel_login TEXT NOT NULL DEFAULT '',
+    channel_name TEXT NOT NULL DEFAULT '',
+    viewers INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_raids_channel ON raids (channel_id);
+CREATE INDEX IF NOT EXISTS idx_raids_created_at ON raids (created_at);
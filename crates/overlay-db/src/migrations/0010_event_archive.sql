@@ -0,0 +1,14 @@
+-- Raw EventSub notifications, kept for debugging and projection rebuilds.
+-- Size-bounded: the oldest rows are pruned first.
+
+CREATE TABLE IF NOT EXISTS event_archive (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    received_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_archive_type_received
+    ON event_archive (event_type, received_at);
+CREATE INDEX IF NOT EXISTS idx_event_archive_received_at
+    ON event_archive (received_at);
@@ -0,0 +1,15 @@
+-- Stream rundown: an ordered checklist per stream day.
+
+CREATE TABLE IF NOT EXISTS rundowns (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Stream day (YYYY-MM-DD in the configured time zone).
+    stream_date TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    checked INTEGER NOT NULL DEFAULT 0,
+    checked_at INTEGER,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rundowns_date_position
+    ON rundowns (stream_date, position);
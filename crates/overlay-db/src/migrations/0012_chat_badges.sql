@@ -0,0 +1,6 @@
+-- Chat badges and name color, for rendering chat excerpts.
+
+-- EventSub badge list: [{"set_id", "id", "info"}].
+ALTER TABLE chat_messages ADD COLUMN badges_json TEXT NOT NULL DEFAULT '[]';
+-- Chatter name color (#RRGGBB), empty when unset.
+ALTER TABLE chat_messages ADD COLUMN color TEXT NOT NULL DEFAULT '';
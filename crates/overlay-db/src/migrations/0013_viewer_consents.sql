@@ -0,0 +1,12 @@
+-- Viewer consent registry. A NULL scope falls back to the default policy.
+
+CREATE TABLE IF NOT EXISTS viewer_consents (
+    user_id TEXT PRIMARY KEY,
+    username TEXT NOT NULL DEFAULT '',
+    print INTEGER,
+    tts INTEGER,
+    credits INTEGER,
+    -- Where the last change came from ("command", "reward" or "dashboard").
+    source TEXT NOT NULL DEFAULT '',
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,17 @@
+-- Contributions to the funding goal: bits, gifted subs, external donations
+-- and manual adjustments (which double as the audit log).
+
+CREATE TABLE IF NOT EXISTS funding_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL CHECK (source IN ('bits', 'gift_subs', 'donation', 'adjustment')),
+    -- Raw amount: bits, gifted subs, or the donated / adjusted amount.
+    quantity REAL NOT NULL DEFAULT 0,
+    -- Contribution in the goal currency, converted when recorded.
+    value REAL NOT NULL,
+    username TEXT NOT NULL DEFAULT '',
+    -- Donation platform or message, or the reason for an adjustment.
+    note TEXT NOT NULL DEFAULT '',
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_funding_entries_created_at ON funding_entries (created_at);
@@ -0,0 +1,10 @@
+-- Action macros: an ordered list of steps run by one trigger.
+
+CREATE TABLE IF NOT EXISTS macros (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    -- JSON array of steps, validated by the app.
+    steps TEXT NOT NULL DEFAULT '[]',
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
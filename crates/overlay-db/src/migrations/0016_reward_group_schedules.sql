@@ -0,0 +1,10 @@
+-- Reward groups switched automatically when the stream goes online/offline.
+
+CREATE TABLE IF NOT EXISTS reward_group_schedules (
+    group_id INTEGER NOT NULL,
+    -- 'stream_online' or 'stream_offline'
+    trigger TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    PRIMARY KEY (group_id, trigger),
+    FOREIGN KEY (group_id) REFERENCES reward_groups(id) ON DELETE CASCADE
+);
@@ -0,0 +1,10 @@
+-- Per-reward redemption count goals for the reward count widget.
+
+CREATE TABLE IF NOT EXISTS reward_count_goals (
+    reward_id TEXT PRIMARY KEY,
+    goal INTEGER NOT NULL,
+    label TEXT NOT NULL DEFAULT '',
+    -- Overlay effect request (JSON) fired when the goal is reached; '' for none.
+    effect TEXT NOT NULL DEFAULT '',
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,12 @@
+-- Lottery draw history: one row per winner drawn.
+
+CREATE TABLE IF NOT EXISTS lottery_draws (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL,
+    username TEXT NOT NULL,
+    display_name TEXT NOT NULL DEFAULT '',
+    participant_count INTEGER NOT NULL,
+    drawn_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_lottery_draws_drawn_at ON lottery_draws(drawn_at);
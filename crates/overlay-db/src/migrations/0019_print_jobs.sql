@@ -0,0 +1,22 @@
+-- Persistent print queue. Jobs are deleted once printed; jobs that used up
+-- their retries stay as 'failed' (dead letter) until retried or deleted.
+
+CREATE TABLE IF NOT EXISTS print_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    status TEXT NOT NULL DEFAULT 'pending',
+    description TEXT NOT NULL DEFAULT '',
+    category TEXT NOT NULL DEFAULT 'other',
+    force INTEGER NOT NULL DEFAULT 0,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    payload BLOB NOT NULL,
+    color_payload BLOB,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    position INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_print_jobs_status ON print_jobs(status, position);
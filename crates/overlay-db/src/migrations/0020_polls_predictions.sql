@@ -0,0 +1,30 @@
+-- Channel polls and predictions received over EventSub. One row each,
+-- updated as progress / lock / end notifications arrive.
+
+CREATE TABLE IF NOT EXISTS polls (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    status TEXT NOT NULL,
+    choices_json TEXT NOT NULL DEFAULT '[]',
+    started_at INTEGER NOT NULL,
+    ends_at INTEGER,
+    ended_at INTEGER,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_polls_started_at ON polls(started_at);
+
+CREATE TABLE IF NOT EXISTS predictions (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    status TEXT NOT NULL,
+    outcomes_json TEXT NOT NULL DEFAULT '[]',
+    winning_outcome_id TEXT,
+    started_at INTEGER NOT NULL,
+    locks_at INTEGER,
+    locked_at INTEGER,
+    ended_at INTEGER,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_predictions_started_at ON predictions(started_at);
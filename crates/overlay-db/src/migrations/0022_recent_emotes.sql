@@ -0,0 +1,11 @@
+-- Emotes the broadcaster sent in chat, newest first, for the "recent" group
+-- of the emote picker.
+
+CREATE TABLE IF NOT EXISTS recent_emotes (
+    emote_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    use_count INTEGER NOT NULL DEFAULT 0,
+    last_used_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_recent_emotes_last_used_at ON recent_emotes(last_used_at);
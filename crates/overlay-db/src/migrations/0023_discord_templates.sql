@@ -0,0 +1,11 @@
+-- Customized Discord embeds per notification event. Events without a row
+-- use the built-in template.
+
+CREATE TABLE IF NOT EXISTS discord_templates (
+    event TEXT PRIMARY KEY,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    color INTEGER NOT NULL DEFAULT 0,
+    updated_at INTEGER NOT NULL
+);
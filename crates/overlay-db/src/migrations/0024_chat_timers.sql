@@ -0,0 +1,15 @@
+-- Timed chat messages posted while live. Each timer rotates through its
+-- messages; next_index is the one posted next.
+
+CREATE TABLE IF NOT EXISTS chat_timers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    messages_json TEXT NOT NULL DEFAULT '[]',
+    interval_minutes INTEGER NOT NULL,
+    min_chat_messages INTEGER NOT NULL DEFAULT 0,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    next_index INTEGER NOT NULL DEFAULT 0,
+    last_sent_at INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
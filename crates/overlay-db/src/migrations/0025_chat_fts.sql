@@ -0,0 +1,35 @@
+-- Full-text index over chat message text and author name. The trigram
+-- tokenizer matches any substring of three or more characters, which also
+-- works for Japanese text without spaces. Triggers keep it in sync with
+-- chat_messages.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS chat_messages_fts USING fts5(
+    message,
+    username,
+    content = 'chat_messages',
+    content_rowid = 'id',
+    tokenize = 'trigram'
+);
+
+INSERT INTO chat_messages_fts(chat_messages_fts) VALUES ('rebuild');
+
+CREATE TRIGGER IF NOT EXISTS chat_messages_fts_insert AFTER INSERT ON chat_messages
+BEGIN
+    INSERT INTO chat_messages_fts(rowid, message, username)
+    VALUES (new.id, new.message, new.username);
+END;
+
+CREATE TRIGGER IF NOT EXISTS chat_messages_fts_delete AFTER DELETE ON chat_messages
+BEGIN
+    INSERT INTO chat_messages_fts(chat_messages_fts, rowid, message, username)
+    VALUES ('delete', old.id, old.message, old.username);
+END;
+
+CREATE TRIGGER IF NOT EXISTS chat_messages_fts_update
+AFTER UPDATE OF message, username ON chat_messages
+BEGIN
+    INSERT INTO chat_messages_fts(chat_messages_fts, rowid, message, username)
+    VALUES ('delete', old.id, old.message, old.username);
+    INSERT INTO chat_messages_fts(rowid, message, username)
+    VALUES (new.id, new.message, new.username);
+END;
@@ -0,0 +1,23 @@
+-- Chat-to-print voting. Viewers nominate chat messages; each round the
+-- most-voted message is printed, recorded in print_vote_winners, and the
+-- votes are cleared.
+
+CREATE TABLE IF NOT EXISTS print_votes (
+    message_id TEXT NOT NULL,
+    voter_id TEXT NOT NULL,
+    -- How the vote was cast ("reply" or "reward").
+    source TEXT NOT NULL DEFAULT '',
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (message_id, voter_id)
+);
+
+CREATE TABLE IF NOT EXISTS print_vote_winners (
+    message_id TEXT PRIMARY KEY,
+    username TEXT NOT NULL DEFAULT '',
+    message TEXT NOT NULL DEFAULT '',
+    votes INTEGER NOT NULL,
+    printed_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_print_vote_winners_printed_at
+    ON print_vote_winners(printed_at);
@@ -0,0 +1,27 @@
+-- One row per broadcast, opened by stream.online and closed by
+-- stream.offline. Viewer, follower and subscriber counts are sampled while
+-- live; *_start is the first sample and *_end the latest.
+
+CREATE TABLE IF NOT EXISTS stream_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Twitch stream ID; empty when unknown.
+    stream_id TEXT NOT NULL DEFAULT '',
+    title TEXT NOT NULL DEFAULT '',
+    category TEXT NOT NULL DEFAULT '',
+    started_at INTEGER NOT NULL,
+    ended_at INTEGER,
+    peak_viewers INTEGER NOT NULL DEFAULT 0,
+    peak_viewers_at INTEGER,
+    followers_start INTEGER,
+    followers_end INTEGER,
+    subs_start INTEGER,
+    subs_end INTEGER,
+    last_sampled_at INTEGER
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_stream_sessions_stream_id
+    ON stream_sessions(stream_id)
+    WHERE stream_id != '';
+
+CREATE INDEX IF NOT EXISTS idx_stream_sessions_started_at
+    ON stream_sessions(started_at);
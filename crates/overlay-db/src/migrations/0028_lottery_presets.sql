@@ -0,0 +1,8 @@
+-- Saved lottery setting presets, importable from and exportable to JSON.
+
+CREATE TABLE IF NOT EXISTS lottery_presets (
+    name TEXT PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT '',
+    settings TEXT NOT NULL DEFAULT '{}',
+    updated_at INTEGER NOT NULL
+);
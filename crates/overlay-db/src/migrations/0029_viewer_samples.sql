@@ -0,0 +1,8 @@
+-- Viewer counts sampled during a stream session, for the dashboard graph.
+
+CREATE TABLE IF NOT EXISTS viewer_samples (
+    session_id INTEGER NOT NULL,
+    sampled_at INTEGER NOT NULL,
+    viewers INTEGER NOT NULL,
+    PRIMARY KEY (session_id, sampled_at)
+);
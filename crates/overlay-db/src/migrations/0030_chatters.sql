@@ -0,0 +1,21 @@
+-- First messages per chatter: ever (chatters) and per stream session
+-- (session_chatters). Chatters already in the chat history count as seen.
+
+CREATE TABLE IF NOT EXISTS chatters (
+    user_id TEXT PRIMARY KEY,
+    username TEXT NOT NULL DEFAULT '',
+    first_seen_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS session_chatters (
+    session_id INTEGER NOT NULL,
+    user_id TEXT NOT NULL,
+    first_message_at INTEGER NOT NULL,
+    PRIMARY KEY (session_id, user_id)
+);
+
+INSERT OR IGNORE INTO chatters (user_id, username, first_seen_at)
+SELECT user_id, username, MIN(created_at)
+FROM chat_messages
+WHERE user_id IS NOT NULL AND user_id != ''
+GROUP BY user_id;
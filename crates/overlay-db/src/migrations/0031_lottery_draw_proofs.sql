@@ -0,0 +1,4 @@
+-- Fairness proof of each draw (JSON, see lottery::DrawProof); empty for
+-- draws made before proofs were recorded.
+
+ALTER TABLE lottery_draws ADD COLUMN proof TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,7 @@
+-- How the winner of each draw was told: claim deadline and whether the
+-- whisper and chat announcement went out ('' when not attempted).
+
+ALTER TABLE lottery_draws ADD COLUMN claim_deadline INTEGER;
+ALTER TABLE lottery_draws ADD COLUMN whisper_status TEXT NOT NULL DEFAULT '';
+ALTER TABLE lottery_draws ADD COLUMN announce_status TEXT NOT NULL DEFAULT '';
+ALTER TABLE lottery_draws ADD COLUMN delivery_error TEXT NOT NULL DEFAULT '';
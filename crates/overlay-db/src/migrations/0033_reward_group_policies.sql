@@ -0,0 +1,5 @@
+-- Automatic on/off policy of each reward group (see rewards::RewardGroupPolicy):
+-- 'manual', 'live' or 'offline', plus JSON time windows.
+
+ALTER TABLE reward_groups ADD COLUMN policy TEXT NOT NULL DEFAULT 'manual';
+ALTER TABLE reward_groups ADD COLUMN policy_windows TEXT NOT NULL DEFAULT '[]';
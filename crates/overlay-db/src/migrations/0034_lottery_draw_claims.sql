@@ -0,0 +1,8 @@
+-- Whether each winner claimed their prize before the deadline, and which
+-- draw a re-draw replaced. Draws made before claims were tracked keep an
+-- empty claim_status.
+
+ALTER TABLE lottery_draws ADD COLUMN claim_status TEXT NOT NULL DEFAULT '';
+ALTER TABLE lottery_draws ADD COLUMN claimed_at INTEGER;
+ALTER TABLE lottery_draws ADD COLUMN claim_source TEXT NOT NULL DEFAULT '';
+ALTER TABLE lottery_draws ADD COLUMN redraw_of INTEGER;
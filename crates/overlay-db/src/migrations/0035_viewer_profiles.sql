@@ -0,0 +1,13 @@
+-- Viewer profile details looked up from Twitch or third-party providers,
+-- kept for a while so repeated lookups do not hit the providers.
+
+CREATE TABLE IF NOT EXISTS viewer_profiles (
+    user_id TEXT PRIMARY KEY,
+    login TEXT NOT NULL DEFAULT '',
+    display_name TEXT NOT NULL DEFAULT '',
+    avatar_url TEXT NOT NULL DEFAULT '',
+    description TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL DEFAULT '',
+    source TEXT NOT NULL DEFAULT '',
+    fetched_at INTEGER NOT NULL
+);
@@ -0,0 +1,22 @@
+-- Moderator actions reported by EventSub `channel.moderate`, kept for
+-- auditing after streams.
+
+CREATE TABLE IF NOT EXISTS moderation_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    action TEXT NOT NULL,
+    moderator_id TEXT NOT NULL DEFAULT '',
+    moderator_login TEXT NOT NULL DEFAULT '',
+    moderator_name TEXT NOT NULL DEFAULT '',
+    target_id TEXT NOT NULL DEFAULT '',
+    target_login TEXT NOT NULL DEFAULT '',
+    target_name TEXT NOT NULL DEFAULT '',
+    reason TEXT NOT NULL DEFAULT '',
+    -- Timeout length in seconds.
+    duration_secs INTEGER,
+    -- The action's own payload object.
+    details TEXT NOT NULL DEFAULT '{}',
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_moderation_log_created ON moderation_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_moderation_log_moderator ON moderation_log(moderator_login);
@@ -0,0 +1,20 @@
+-- Chat of other channels joined through the IRC relay. The broadcaster's own
+-- chat arrives through EventSub and stays in chat_messages.
+
+CREATE TABLE IF NOT EXISTS irc_chat_messages (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Channel login, without '#'.
+    channel TEXT NOT NULL,
+    message_id TEXT NOT NULL UNIQUE,
+    user_id TEXT NOT NULL DEFAULT '',
+    login TEXT NOT NULL DEFAULT '',
+    display_name TEXT NOT NULL DEFAULT '',
+    message TEXT NOT NULL,
+    color TEXT NOT NULL DEFAULT '',
+    -- IRC `badges` tag, e.g. 'moderator/1,subscriber/12'.
+    badges TEXT NOT NULL DEFAULT '',
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_irc_chat_messages_channel ON irc_chat_messages(channel, created_at);
+CREATE INDEX IF NOT EXISTS idx_irc_chat_messages_created ON irc_chat_messages(created_at);
@@ -0,0 +1,16 @@
+-- Custom chat commands (e.g. !discord) answered with a response template.
+-- trigger is stored lowercase including its prefix.
+
+CREATE TABLE IF NOT EXISTS chat_commands (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    trigger TEXT NOT NULL UNIQUE,
+    response TEXT NOT NULL,
+    cooldown_secs INTEGER NOT NULL DEFAULT 0,
+    -- everyone, subscriber, vip, moderator or broadcaster.
+    permission TEXT NOT NULL DEFAULT 'everyone',
+    enabled INTEGER NOT NULL DEFAULT 1,
+    use_count INTEGER NOT NULL DEFAULT 0,
+    last_used_at INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,5 @@
+-- Chat timers can post as highlighted announcements instead of plain
+-- messages.
+
+ALTER TABLE chat_timers ADD COLUMN announce INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE chat_timers ADD COLUMN announce_color TEXT NOT NULL DEFAULT 'primary';
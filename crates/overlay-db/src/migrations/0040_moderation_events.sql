@@ -0,0 +1,20 @@
+-- Chat messages caught by the word filter and what was done about them.
+
+CREATE TABLE IF NOT EXISTS moderation_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    message_id TEXT NOT NULL DEFAULT '',
+    user_id TEXT NOT NULL DEFAULT '',
+    user_login TEXT NOT NULL DEFAULT '',
+    user_name TEXT NOT NULL DEFAULT '',
+    message TEXT NOT NULL DEFAULT '',
+    -- JSON array of the matched terms.
+    matched_json TEXT NOT NULL DEFAULT '[]',
+    -- flag, redact or timeout.
+    action TEXT NOT NULL,
+    -- Timeout length in seconds.
+    duration_secs INTEGER,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_moderation_events_created ON moderation_events(created_at);
+CREATE INDEX IF NOT EXISTS idx_moderation_events_user ON moderation_events(user_login);
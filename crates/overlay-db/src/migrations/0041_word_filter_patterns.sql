@@ -0,0 +1,5 @@
+-- Word filter entries can be exact terms, substrings (the old behavior),
+-- wildcards (`*`, `?`) or regular expressions, each with a severity.
+
+ALTER TABLE word_filter_words ADD COLUMN match_type TEXT NOT NULL DEFAULT 'substring';
+ALTER TABLE word_filter_words ADD COLUMN severity TEXT NOT NULL DEFAULT 'medium';
@@ -0,0 +1,24 @@
+-- Revision of the word filter lists, bumped by triggers on every change so
+-- that compiled matchers can tell when they are stale.
+
+CREATE TABLE IF NOT EXISTS word_filter_revision (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    revision INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT OR IGNORE INTO word_filter_revision (id, revision) VALUES (1, 0);
+
+CREATE TRIGGER IF NOT EXISTS word_filter_words_revision_insert AFTER INSERT ON word_filter_words
+BEGIN
+    UPDATE word_filter_revision SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS word_filter_words_revision_update AFTER UPDATE ON word_filter_words
+BEGIN
+    UPDATE word_filter_revision SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS word_filter_words_revision_delete AFTER DELETE ON word_filter_words
+BEGIN
+    UPDATE word_filter_revision SET revision = revision + 1 WHERE id = 1;
+END;
@@ -0,0 +1,61 @@
+//! Persisted record of celebrated milestones, so each fires exactly once.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiredMilestone {
+    pub kind: String,
+    pub threshold: i64,
+    pub value: i64,
+    pub fired_at: i64,
+}
+
+impl Database {
+    /// Record a milestone. Returns false if it had already been recorded.
+    pub fn mark_milestone_fired(
+        &self,
+        kind: &str,
+        threshold: i64,
+        value: i64,
+        now: i64,
+    ) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "INSERT OR IGNORE INTO milestones_fired (kind, threshold, value, fired_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![kind, threshold, value, now],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    pub fn get_fired_milestones(&self) -> Result<Vec<FiredMilestone>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT kind, threshold, value, fired_at FROM milestones_fired
+                 ORDER BY kind ASC, threshold ASC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(FiredMilestone {
+                    kind: row.get(0)?,
+                    threshold: row.get(1)?,
+                    value: row.get(2)?,
+                    fired_at: row.get(3)?,
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Forget a fired milestone so it can be celebrated again.
+    pub fn reset_milestone(&self, kind: &str, threshold: i64) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM milestones_fired WHERE kind = ?1 AND threshold = ?2",
+                rusqlite::params![kind, threshold],
+            )?;
+            Ok(())
+        })
+    }
+}
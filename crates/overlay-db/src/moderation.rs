@@ -0,0 +1,187 @@
+//! Moderator action log (EventSub `channel.moderate`) and the word filter's
+//! own actions on chat messages.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModerationAction {
+    pub id: i64,
+    /// Twitch action name (`ban`, `timeout`, `delete`, `slow`, ...).
+    pub action: String,
+    pub moderator_id: String,
+    pub moderator_login: String,
+    pub moderator_name: String,
+    /// The user acted on; empty for chat-wide actions.
+    pub target_id: String,
+    pub target_login: String,
+    pub target_name: String,
+    pub reason: String,
+    pub duration_secs: Option<i64>,
+    pub details: serde_json::Value,
+    pub created_at: i64,
+}
+
+/// Filters of [`Database::get_moderation_log`].
+#[derive(Debug, Clone, Default)]
+pub struct ModerationLogQuery {
+    /// Moderator ID or login (case-insensitive).
+    pub moderator: Option<String>,
+    pub action: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub offset: i64,
+    pub limit: i64,
+}
+
+/// A chat message the word filter acted on.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModerationEvent {
+    pub id: i64,
+    pub message_id: String,
+    pub user_id: String,
+    pub user_login: String,
+    pub user_name: String,
+    pub message: String,
+    /// Filtered terms found in the message.
+    pub matched: Vec<String>,
+    /// `flag`, `redact` or `timeout`.
+    pub action: String,
+    pub duration_secs: Option<i64>,
+    pub created_at: i64,
+}
+
+fn map_action(row: &rusqlite::Row<'_>) -> rusqlite::Result<ModerationAction> {
+    let details: String = row.get(10)?;
+    Ok(ModerationAction {
+        id: row.get(0)?,
+        action: row.get(1)?,
+        moderator_id: row.get(2)?,
+        moderator_login: row.get(3)?,
+        moderator_name: row.get(4)?,
+        target_id: row.get(5)?,
+        target_login: row.get(6)?,
+        target_name: row.get(7)?,
+        reason: row.get(8)?,
+        duration_secs: row.get(9)?,
+        details: serde_json::from_str(&details).unwrap_or_default(),
+        created_at: row.get(11)?,
+    })
+}
+
+impl Database {
+    /// Record `entry`; its `id` is ignored. Returns the new row ID.
+    pub fn add_moderation_action(&self, entry: &ModerationAction) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO moderation_log
+                    (action, moderator_id, moderator_login, moderator_name, target_id,
+                     target_login, target_name, reason, duration_secs, details, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                rusqlite::params![
+                    entry.action,
+                    entry.moderator_id,
+                    entry.moderator_login,
+                    entry.moderator_name,
+                    entry.target_id,
+                    entry.target_login,
+                    entry.target_name,
+                    entry.reason,
+                    entry.duration_secs,
+                    entry.details.to_string(),
+                    entry.created_at,
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    /// Logged actions matching `query`, newest first.
+    pub fn get_moderation_log(
+        &self,
+        query: &ModerationLogQuery,
+    ) -> Result<Vec<ModerationAction>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, action, moderator_id, moderator_login, moderator_name, target_id,
+                        target_login, target_name, reason, duration_secs, details, created_at
+                 FROM moderation_log
+                 WHERE (?1 IS NULL OR moderator_id = ?1 OR moderator_login = LOWER(?1))
+                   AND (?2 IS NULL OR action = ?2)
+                   AND (?3 IS NULL OR created_at >= ?3)
+                   AND (?4 IS NULL OR created_at <= ?4)
+                 ORDER BY created_at DESC, id DESC LIMIT ?5 OFFSET ?6",
+            )?;
+            let rows = stmt.query_map(
+                rusqlite::params![
+                    query.moderator,
+                    query.action,
+                    query.from,
+                    query.to,
+                    query.limit,
+                    query.offset
+                ],
+                map_action,
+            )?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Record `event`; its `id` is ignored. Returns the new row ID.
+    pub fn add_moderation_event(&self, event: &ModerationEvent) -> Result<i64, DbError> {
+        let matched = serde_json::to_string(&event.matched).unwrap_or_else(|_| "[]".into());
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO moderation_events
+                    (message_id, user_id, user_login, user_name, message, matched_json,
+                     action, duration_secs, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    event.message_id,
+                    event.user_id,
+                    event.user_login,
+                    event.user_name,
+                    event.message,
+                    matched,
+                    event.action,
+                    event.duration_secs,
+                    event.created_at,
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    /// Word filter events, newest first; `user` is a user ID or login.
+    pub fn get_moderation_events(
+        &self,
+        user: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ModerationEvent>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, message_id, user_id, user_login, user_name, message, matched_json,
+                        action, duration_secs, created_at
+                 FROM moderation_events
+                 WHERE (?1 IS NULL OR user_id = ?1 OR user_login = LOWER(?1))
+                 ORDER BY created_at DESC, id DESC LIMIT ?2",
+            )?;
+            let rows = stmt.query_map(rusqlite::params![user, limit], |row| {
+                let matched: String = row.get(6)?;
+                Ok(ModerationEvent {
+                    id: row.get(0)?,
+                    message_id: row.get(1)?,
+                    user_id: row.get(2)?,
+                    user_login: row.get(3)?,
+                    user_name: row.get(4)?,
+                    message: row.get(5)?,
+                    matched: serde_json::from_str(&matched).unwrap_or_default(),
+                    action: row.get(7)?,
+                    duration_secs: row.get(8)?,
+                    created_at: row.get(9)?,
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+}
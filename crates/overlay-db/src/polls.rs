@@ -0,0 +1,196 @@
+//! Channel polls and predictions.
+//!
+//! Rows are keyed by the Twitch poll / prediction ID and overwritten by
+//! each update; `started_at` keeps the value of the first one and a set
+//! `ended_at` / `locked_at` is never cleared again.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+pub const STATUS_ACTIVE: &str = "active";
+pub const STATUS_LOCKED: &str = "locked";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PollChoice {
+    pub id: String,
+    pub title: String,
+    /// All votes, including those bought with channel points.
+    #[serde(default)]
+    pub votes: i64,
+    #[serde(default)]
+    pub channel_points_votes: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Poll {
+    pub id: String,
+    pub title: String,
+    /// `active`, or the end status from Twitch (`completed`, `terminated`,
+    /// `archived`).
+    pub status: String,
+    pub choices: Vec<PollChoice>,
+    pub started_at: i64,
+    pub ends_at: Option<i64>,
+    pub ended_at: Option<i64>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PredictionOutcome {
+    pub id: String,
+    pub title: String,
+    /// `blue` or `pink`.
+    pub color: String,
+    #[serde(default)]
+    pub users: i64,
+    #[serde(default)]
+    pub channel_points: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Prediction {
+    pub id: String,
+    pub title: String,
+    /// `active`, `locked`, or the end status from Twitch (`resolved`,
+    /// `canceled`).
+    pub status: String,
+    pub outcomes: Vec<PredictionOutcome>,
+    pub winning_outcome_id: Option<String>,
+    pub started_at: i64,
+    pub locks_at: Option<i64>,
+    pub locked_at: Option<i64>,
+    pub ended_at: Option<i64>,
+    pub updated_at: i64,
+}
+
+fn map_poll(row: &rusqlite::Row<'_>) -> rusqlite::Result<Poll> {
+    let choices: String = row.get(3)?;
+    Ok(Poll {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        status: row.get(2)?,
+        choices: serde_json::from_str(&choices).unwrap_or_default(),
+        started_at: row.get(4)?,
+        ends_at: row.get(5)?,
+        ended_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn map_prediction(row: &rusqlite::Row<'_>) -> rusqlite::Result<Prediction> {
+    let outcomes: String = row.get(3)?;
+    Ok(Prediction {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        status: row.get(2)?,
+        outcomes: serde_json::from_str(&outcomes).unwrap_or_default(),
+        winning_outcome_id: row.get(4)?,
+        started_at: row.get(5)?,
+        locks_at: row.get(6)?,
+        locked_at: row.get(7)?,
+        ended_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+const SELECT_POLL: &str =
+    "SELECT id, title, status, choices_json, started_at, ends_at, ended_at, updated_at FROM polls";
+const SELECT_PREDICTION: &str = "SELECT id, title, status, outcomes_json, winning_outcome_id,
+        started_at, locks_at, locked_at, ended_at, updated_at
+    FROM predictions";
+
+impl Database {
+    /// Insert or update a poll; returns the stored row.
+    pub fn upsert_poll(&self, poll: &Poll) -> Result<Poll, DbError> {
+        let choices = serde_json::to_string(&poll.choices).unwrap_or_else(|_| "[]".into());
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO polls
+                    (id, title, status, choices_json, started_at, ends_at, ended_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT(id) DO UPDATE SET
+                    title = excluded.title,
+                    status = excluded.status,
+                    choices_json = excluded.choices_json,
+                    ends_at = COALESCE(excluded.ends_at, ends_at),
+                    ended_at = COALESCE(excluded.ended_at, ended_at),
+                    updated_at = excluded.updated_at",
+                rusqlite::params![
+                    poll.id,
+                    poll.title,
+                    poll.status,
+                    choices,
+                    poll.started_at,
+                    poll.ends_at,
+                    poll.ended_at,
+                    poll.updated_at,
+                ],
+            )?;
+            Ok(conn.query_row(
+                &format!("{SELECT_POLL} WHERE id = ?1"),
+                [&poll.id],
+                map_poll,
+            )?)
+        })
+    }
+
+    /// Most recently started polls first.
+    pub fn recent_polls(&self, limit: i64) -> Result<Vec<Poll>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt =
+                conn.prepare(&format!("{SELECT_POLL} ORDER BY started_at DESC LIMIT ?1"))?;
+            let rows = stmt.query_map([limit], map_poll)?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+    }
+
+    /// Insert or update a prediction; returns the stored row.
+    pub fn upsert_prediction(&self, prediction: &Prediction) -> Result<Prediction, DbError> {
+        let outcomes = serde_json::to_string(&prediction.outcomes).unwrap_or_else(|_| "[]".into());
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO predictions
+                    (id, title, status, outcomes_json, winning_outcome_id, started_at,
+                     locks_at, locked_at, ended_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(id) DO UPDATE SET
+                    title = excluded.title,
+                    status = excluded.status,
+                    outcomes_json = excluded.outcomes_json,
+                    winning_outcome_id = COALESCE(excluded.winning_outcome_id, winning_outcome_id),
+                    locks_at = COALESCE(excluded.locks_at, locks_at),
+                    locked_at = COALESCE(excluded.locked_at, locked_at),
+                    ended_at = COALESCE(excluded.ended_at, ended_at),
+                    updated_at = excluded.updated_at",
+                rusqlite::params![
+                    prediction.id,
+                    prediction.title,
+                    prediction.status,
+                    outcomes,
+                    prediction.winning_outcome_id,
+                    prediction.started_at,
+                    prediction.locks_at,
+                    prediction.locked_at,
+                    prediction.ended_at,
+                    prediction.updated_at,
+                ],
+            )?;
+            Ok(conn.query_row(
+                &format!("{SELECT_PREDICTION} WHERE id = ?1"),
+                [&prediction.id],
+                map_prediction,
+            )?)
+        })
+    }
+
+    /// Most recently started predictions first.
+    pub fn recent_predictions(&self, limit: i64) -> Result<Vec<Prediction>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_PREDICTION} ORDER BY started_at DESC LIMIT ?1"
+            ))?;
+            let rows = stmt.query_map([limit], map_prediction)?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+    }
+}
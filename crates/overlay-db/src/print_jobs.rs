@@ -0,0 +1,263 @@
+//! Persistent print queue.
+//!
+//! Jobs move `pending` → `printing` → deleted on success. A failed attempt
+//! puts the job back to `pending` with a later `next_attempt_at`, or to
+//! `failed` (dead letter) once the caller gives up on it.
+
+use crate::{Database, DbError};
+use serde::Serialize;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_PRINTING: &str = "printing";
+pub const STATUS_FAILED: &str = "failed";
+
+/// A job to store.
+#[derive(Debug, Clone)]
+pub struct NewPrintJob<'a> {
+    pub description: &'a str,
+    pub category: &'a str,
+    pub force: bool,
+    pub width: i64,
+    pub height: i64,
+    pub payload: &'a [u8],
+    pub color_payload: Option<&'a [u8]>,
+}
+
+/// Job metadata (without the image data).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrintJobRecord {
+    pub id: i64,
+    pub status: String,
+    pub description: String,
+    pub category: String,
+    pub force: bool,
+    pub width: i64,
+    pub height: i64,
+    pub has_color: bool,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// A claimed job with its image data.
+#[derive(Debug, Clone)]
+pub struct ClaimedPrintJob {
+    pub record: PrintJobRecord,
+    pub payload: Vec<u8>,
+    pub color_payload: Option<Vec<u8>>,
+}
+
+const SELECT_JOB: &str = "SELECT id, status, description, category, force, width, height,
+        color_payload IS NOT NULL, attempts, last_error, next_attempt_at, created_at, updated_at
+    FROM print_jobs";
+
+fn map_job(row: &rusqlite::Row<'_>) -> rusqlite::Result<PrintJobRecord> {
+    Ok(PrintJobRecord {
+        id: row.get(0)?,
+        status: row.get(1)?,
+        description: row.get(2)?,
+        category: row.get(3)?,
+        force: row.get(4)?,
+        width: row.get(5)?,
+        height: row.get(6)?,
+        has_color: row.get(7)?,
+        attempts: row.get(8)?,
+        last_error: row.get(9)?,
+        next_attempt_at: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+impl Database {
+    /// Queue a job at the end of the pending list.
+    pub fn add_print_job(&self, job: &NewPrintJob<'_>, now: i64) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO print_jobs
+                    (status, description, category, force, width, height, payload,
+                     color_payload, position, next_attempt_at, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
+                     (SELECT COALESCE(MAX(position), -1) + 1 FROM print_jobs), ?9, ?9, ?9)",
+                rusqlite::params![
+                    STATUS_PENDING,
+                    job.description,
+                    job.category,
+                    job.force,
+                    job.width,
+                    job.height,
+                    job.payload,
+                    job.color_payload,
+                    now,
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    pub fn get_print_job(&self, id: i64) -> Result<Option<PrintJobRecord>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("{SELECT_JOB} WHERE id = ?1"))?;
+            let mut rows = stmt.query_map([id], map_job)?;
+            Ok(rows.next().transpose()?)
+        })
+    }
+
+    /// Jobs with the given status in queue order.
+    pub fn list_print_jobs(&self, status: &str) -> Result<Vec<PrintJobRecord>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_JOB} WHERE status = ?1 ORDER BY position, id"
+            ))?;
+            let rows = stmt.query_map([status], map_job)?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+    }
+
+    pub fn count_print_jobs(&self, status: &str) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            Ok(conn.query_row(
+                "SELECT COUNT(*) FROM print_jobs WHERE status = ?1",
+                [status],
+                |row| row.get(0),
+            )?)
+        })
+    }
+
+    /// Mark the first pending job due at `now` as printing and return it.
+    pub fn claim_next_print_job(&self, now: i64) -> Result<Option<ClaimedPrintJob>, DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let id: Option<i64> = {
+                let mut stmt = tx.prepare(
+                    "SELECT id FROM print_jobs
+                     WHERE status = ?1 AND next_attempt_at <= ?2
+                     ORDER BY position, id LIMIT 1",
+                )?;
+                let mut rows =
+                    stmt.query_map(rusqlite::params![STATUS_PENDING, now], |r| r.get(0))?;
+                rows.next().transpose()?
+            };
+            let Some(id) = id else {
+                return Ok(None);
+            };
+            tx.execute(
+                "UPDATE print_jobs SET status = ?2, updated_at = ?3 WHERE id = ?1",
+                rusqlite::params![id, STATUS_PRINTING, now],
+            )?;
+            let record = tx.query_row(&format!("{SELECT_JOB} WHERE id = ?1"), [id], map_job)?;
+            let (payload, color_payload) = tx.query_row(
+                "SELECT payload, color_payload FROM print_jobs WHERE id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            tx.commit()?;
+            Ok(Some(ClaimedPrintJob {
+                record,
+                payload,
+                color_payload,
+            }))
+        })
+    }
+
+    /// Earliest `next_attempt_at` among pending jobs.
+    pub fn next_print_job_due(&self) -> Result<Option<i64>, DbError> {
+        self.with_conn(|conn| {
+            Ok(conn.query_row(
+                "SELECT MIN(next_attempt_at) FROM print_jobs WHERE status = ?1",
+                [STATUS_PENDING],
+                |row| row.get(0),
+            )?)
+        })
+    }
+
+    /// Record a failed attempt. With `retry_at` the job is pending again
+    /// from then on; without it the job becomes a dead letter.
+    pub fn fail_print_job(
+        &self,
+        id: i64,
+        error: &str,
+        retry_at: Option<i64>,
+        now: i64,
+    ) -> Result<(), DbError> {
+        let status = if retry_at.is_some() {
+            STATUS_PENDING
+        } else {
+            STATUS_FAILED
+        };
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE print_jobs
+                 SET status = ?2, attempts = attempts + 1, last_error = ?3,
+                     next_attempt_at = ?4, updated_at = ?5
+                 WHERE id = ?1",
+                rusqlite::params![id, status, error, retry_at.unwrap_or(0), now],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Queue a failed job again with a fresh attempt count. Returns false
+    /// when the job does not exist or is already printing.
+    pub fn retry_print_job(&self, id: i64, now: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE print_jobs
+                 SET status = ?2, attempts = 0, next_attempt_at = ?3, updated_at = ?3
+                 WHERE id = ?1 AND status != ?4",
+                rusqlite::params![id, STATUS_PENDING, now, STATUS_PRINTING],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Put jobs left `printing` by a crash back to pending.
+    pub fn requeue_interrupted_print_jobs(&self, now: i64) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            Ok(conn.execute(
+                "UPDATE print_jobs SET status = ?1, updated_at = ?2 WHERE status = ?3",
+                rusqlite::params![STATUS_PENDING, now, STATUS_PRINTING],
+            )?)
+        })
+    }
+
+    pub fn delete_print_job(&self, id: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM print_jobs WHERE id = ?1", [id])?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Delete every job with the given status; returns how many.
+    pub fn clear_print_jobs(&self, status: &str) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            Ok(conn.execute("DELETE FROM print_jobs WHERE status = ?1", [status])?)
+        })
+    }
+
+    /// Put the listed pending jobs first in the given order; the rest keep
+    /// their relative order after them.
+    pub fn reorder_print_jobs(&self, ids: &[i64]) -> Result<(), DbError> {
+        let mut jobs = self.list_print_jobs(STATUS_PENDING)?;
+        let mut ordered = Vec::with_capacity(jobs.len());
+        for id in ids {
+            if let Some(i) = jobs.iter().position(|job| job.id == *id) {
+                ordered.push(jobs.remove(i));
+            }
+        }
+        ordered.extend(jobs);
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            for (position, job) in ordered.iter().enumerate() {
+                tx.execute(
+                    "UPDATE print_jobs SET position = ?2 WHERE id = ?1",
+                    rusqlite::params![job.id, position as i64],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+}
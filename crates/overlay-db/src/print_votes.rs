@@ -0,0 +1,129 @@
+//! Chat-to-print voting: votes per chat message and the printed winners.
+//!
+//! A viewer has one vote per message and cannot vote for their own
+//! messages or for one that already won. Recording a winner clears all
+//! votes, starting the next round.
+
+use crate::chat::{ChatMessage, SEARCH_COLUMNS, map_message};
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+pub const SOURCE_REPLY: &str = "reply";
+pub const SOURCE_REWARD: &str = "reward";
+
+/// A nominated message and its votes in the current round.
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintVoteTally {
+    pub message: ChatMessage,
+    pub votes: i64,
+    pub first_vote_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrintVoteWinner {
+    pub message_id: String,
+    pub username: String,
+    pub message: String,
+    pub votes: i64,
+    pub printed_at: i64,
+}
+
+impl Database {
+    /// Vote for a stored chat message. Returns false when the vote was not
+    /// counted: a repeat vote, the voter's own message, a message that
+    /// already won or one that is not stored.
+    pub fn add_print_vote(
+        &self,
+        message_id: &str,
+        voter_id: &str,
+        source: &str,
+        now: i64,
+    ) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let changed = conn.execute(
+                "INSERT OR IGNORE INTO print_votes (message_id, voter_id, source, created_at)
+                 SELECT ?1, ?2, ?3, ?4
+                 WHERE EXISTS (
+                     SELECT 1 FROM chat_messages
+                     WHERE message_id = ?1 AND COALESCE(user_id, '') != ?2
+                 )
+                 AND NOT EXISTS (SELECT 1 FROM print_vote_winners WHERE message_id = ?1)",
+                rusqlite::params![message_id, voter_id, source, now],
+            )?;
+            Ok(changed > 0)
+        })
+    }
+
+    /// Nominated messages, most votes first; ties go to the message voted
+    /// for first.
+    pub fn get_print_vote_tally(&self, limit: i64) -> Result<Vec<PrintVoteTally>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SEARCH_COLUMNS}, COUNT(*) AS votes, MIN(v.created_at) AS first_vote_at
+                 FROM print_votes v JOIN chat_messages m ON m.message_id = v.message_id
+                 GROUP BY v.message_id
+                 ORDER BY votes DESC, first_vote_at ASC LIMIT ?1"
+            ))?;
+            let rows = stmt.query_map([limit], |row| {
+                Ok(PrintVoteTally {
+                    message: map_message(row)?,
+                    votes: row.get(13)?,
+                    first_vote_at: row.get(14)?,
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Record `message` as printed with `votes` and clear every vote.
+    pub fn record_print_vote_winner(
+        &self,
+        message: &ChatMessage,
+        votes: i64,
+        now: i64,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "INSERT OR REPLACE INTO print_vote_winners
+                    (message_id, username, message, votes, printed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![
+                    message.message_id,
+                    message.username,
+                    message.message,
+                    votes,
+                    now
+                ],
+            )?;
+            tx.execute("DELETE FROM print_votes", [])?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Drop every vote of the current round; returns how many were dropped.
+    pub fn clear_print_votes(&self) -> Result<usize, DbError> {
+        self.with_conn(|conn| Ok(conn.execute("DELETE FROM print_votes", [])?))
+    }
+
+    /// Printed winners, newest first.
+    pub fn get_print_vote_winners(&self, limit: i64) -> Result<Vec<PrintVoteWinner>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT message_id, username, message, votes, printed_at
+                 FROM print_vote_winners ORDER BY printed_at DESC LIMIT ?1",
+            )?;
+            let rows = stmt.query_map([limit], |row| {
+                Ok(PrintVoteWinner {
+                    message_id: row.get(0)?,
+                    username: row.get(1)?,
+                    message: row.get(2)?,
+                    votes: row.get(3)?,
+                    printed_at: row.get(4)?,
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+}
@@ -0,0 +1,95 @@
+//! Cached viewer profile details (see `services::profiles` in the app).
+//!
+//! Entries are replaced on each lookup; callers decide how old an entry may
+//! be. Timestamps are unix seconds.
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ViewerProfile {
+    pub user_id: String,
+    pub login: String,
+    pub display_name: String,
+    pub avatar_url: String,
+    pub description: String,
+    /// Account creation time as reported by the provider; empty if unknown.
+    pub created_at: String,
+    /// Provider the details came from.
+    pub source: String,
+    pub fetched_at: i64,
+}
+
+const SELECT: &str = "SELECT user_id, login, display_name, avatar_url, description, created_at,
+        source, fetched_at
+    FROM viewer_profiles";
+
+fn map_profile(row: &rusqlite::Row<'_>) -> rusqlite::Result<ViewerProfile> {
+    Ok(ViewerProfile {
+        user_id: row.get(0)?,
+        login: row.get(1)?,
+        display_name: row.get(2)?,
+        avatar_url: row.get(3)?,
+        description: row.get(4)?,
+        created_at: row.get(5)?,
+        source: row.get(6)?,
+        fetched_at: row.get(7)?,
+    })
+}
+
+impl Database {
+    pub fn get_viewer_profile(&self, user_id: &str) -> Result<Option<ViewerProfile>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                &format!("{SELECT} WHERE user_id = ?1"),
+                [user_id],
+                map_profile,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    pub fn save_viewer_profile(&self, profile: &ViewerProfile) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO viewer_profiles
+                    (user_id, login, display_name, avatar_url, description, created_at,
+                     source, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                rusqlite::params![
+                    profile.user_id,
+                    profile.login,
+                    profile.display_name,
+                    profile.avatar_url,
+                    profile.description,
+                    profile.created_at,
+                    profile.source,
+                    profile.fetched_at,
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Drop entries fetched before `before`; returns how many were removed.
+    pub fn prune_viewer_profiles(&self, before: i64) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM viewer_profiles WHERE fetched_at < ?1",
+                [before],
+            )
+            .map_err(Into::into)
+        })
+    }
+
+    /// Drop every cached profile (e.g. when third-party lookups are turned
+    /// off).
+    pub fn clear_viewer_profiles(&self) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            conn.execute("DELETE FROM viewer_profiles", [])
+                .map_err(Into::into)
+        })
+    }
+}
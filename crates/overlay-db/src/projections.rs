@@ -0,0 +1,259 @@
+//! Rebuilds of derived data from the rows they are derived from.
+//!
+//! Each rebuild is idempotent: running it on consistent data changes
+//! nothing. They return the number of rows corrected.
+
+use std::collections::HashMap;
+
+use crate::lottery_engine::ENTRY_LIMIT;
+use crate::{Database, DbError};
+
+/// Names accepted by [`Database::rebuild_projection`].
+pub const PROJECTIONS: &[&str] = &[
+    "reward_counts",
+    "lottery_entries",
+    "emote_first_seen",
+    "segments",
+    "raids",
+];
+
+/// Archived EventSub types that carry raids, with their direction and the
+/// payload prefix of the other channel.
+const RAID_EVENTS: &[(&str, &str, &str)] = &[
+    ("channel.raid", crate::raids::DIRECTION_IN, "from"),
+    ("channel.raid.outgoing", crate::raids::DIRECTION_OUT, "to"),
+];
+
+/// Seconds between archiving an event and recording its raid that still
+/// count as the same raid.
+const RAID_MATCH_SECONDS: i64 = 5;
+
+/// Archived EventSub type of channel point redemptions.
+const REWARD_REDEMPTION_EVENT: &str = "channel.channel_points_custom_reward_redemption.add";
+
+/// Seconds before a reward's last reset from which archived redemptions
+/// still count: a count row is created by its first redemption, just after
+/// the event was archived.
+const REDEMPTION_MATCH_SECONDS: i64 = 5;
+
+impl Database {
+    /// Run one rebuild by name.
+    pub fn rebuild_projection(&self, name: &str) -> Result<usize, DbError> {
+        match name {
+            "reward_counts" => self.rebuild_reward_counts(),
+            "lottery_entries" => self.rebuild_lottery_entries(),
+            "emote_first_seen" => self.rebuild_emote_first_seen(),
+            "segments" => self.rebuild_segments(),
+            "raids" => self.rebuild_raids(),
+            other => Err(DbError::NotFound(format!("projection {other}"))),
+        }
+    }
+
+    /// Recount each reward's redemptions since its last reset by replaying
+    /// the archived redemption events. Rewards whose last reset is older
+    /// than the archive (pruned or turned off) are left alone.
+    pub fn rebuild_reward_counts(&self) -> Result<usize, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let oldest: Option<i64> =
+                tx.query_row("SELECT MIN(received_at) FROM event_archive", [], |row| {
+                    row.get(0)
+                })?;
+            let Some(oldest) = oldest else {
+                return Ok(0);
+            };
+            let rows: Vec<(String, i64, String, i64)> = {
+                let mut stmt = tx.prepare(
+                    "SELECT reward_id, count, COALESCE(user_names, '[]'),
+                            COALESCE(CAST(strftime('%s', last_reset_at) AS INTEGER), 0)
+                     FROM reward_redemption_counts",
+                )?;
+                stmt.query_map([], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                })?
+                .collect::<Result<_, _>>()?
+            };
+            let mut fixed = 0;
+            for (reward_id, count, names_json, reset_at) in rows {
+                let since = reset_at - REDEMPTION_MATCH_SECONDS;
+                if since < oldest {
+                    continue;
+                }
+                let names: Vec<String> = {
+                    let mut stmt = tx.prepare(
+                        "SELECT payload FROM event_archive
+                         WHERE event_type = ?1 AND received_at >= ?2
+                           AND json_extract(payload, '$.reward.id') = ?3
+                         ORDER BY received_at, id",
+                    )?;
+                    let payloads = stmt.query_map(
+                        rusqlite::params![REWARD_REDEMPTION_EVENT, since, reward_id],
+                        |row| row.get::<_, String>(0),
+                    )?;
+                    payloads
+                        .map(|json| {
+                            let payload: serde_json::Value =
+                                serde_json::from_str(&json?).unwrap_or_default();
+                            let field = |name: &str| payload[name].as_str().unwrap_or_default();
+                            let name = match field("user_name") {
+                                "" => field("user_login"),
+                                name => name,
+                            };
+                            Ok(name.to_string())
+                        })
+                        .collect::<Result<_, rusqlite::Error>>()?
+                };
+                let current: Vec<String> = serde_json::from_str(&names_json).unwrap_or_default();
+                if names.len() as i64 != count || names != current {
+                    tx.execute(
+                        "UPDATE reward_redemption_counts SET count = ?2, user_names = ?3
+                         WHERE reward_id = ?1",
+                        rusqlite::params![
+                            reward_id,
+                            names.len() as i64,
+                            serde_json::to_string(&names).unwrap_or_else(|_| "[]".into()),
+                        ],
+                    )?;
+                    fixed += 1;
+                }
+            }
+            tx.commit()?;
+            Ok(fixed)
+        })
+    }
+
+    /// Clamp lottery entry counts to `1..=ENTRY_LIMIT`.
+    pub fn rebuild_lottery_entries(&self) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE lottery_participants
+                 SET entry_count = MIN(MAX(entry_count, 1), ?1)
+                 WHERE entry_count < 1 OR entry_count > ?1",
+                [ENTRY_LIMIT],
+            )?;
+            Ok(n)
+        })
+    }
+
+    /// Move each known emote's `first_seen_at` back to its earliest use in
+    /// stored chat.
+    pub fn rebuild_emote_first_seen(&self) -> Result<usize, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let mut earliest: HashMap<String, i64> = HashMap::new();
+            {
+                let mut stmt = tx.prepare(
+                    "SELECT created_at, fragments_json FROM chat_messages
+                     WHERE fragments_json LIKE '%\"emote\"%'",
+                )?;
+                let mut rows = stmt.query([])?;
+                while let Some(row) = rows.next()? {
+                    let created_at: i64 = row.get(0)?;
+                    let json: String = row.get(1)?;
+                    let fragments: serde_json::Value =
+                        serde_json::from_str(&json).unwrap_or_default();
+                    let ids = fragments
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter(|f| f["type"] == "emote")
+                        .filter_map(|f| f["emote"]["id"].as_str());
+                    for id in ids {
+                        earliest
+                            .entry(id.to_string())
+                            .and_modify(|t| *t = (*t).min(created_at))
+                            .or_insert(created_at);
+                    }
+                }
+            }
+            let mut fixed = 0;
+            for (emote_id, seen_at) in earliest {
+                fixed += tx.execute(
+                    "UPDATE emote_print_rules SET first_seen_at = ?2
+                     WHERE emote_id = ?1 AND first_seen_at > ?2",
+                    rusqlite::params![emote_id, seen_at],
+                )?;
+            }
+            tx.commit()?;
+            Ok(fixed)
+        })
+    }
+
+    /// Close segments left open when a newer one started (only the newest
+    /// segment may be open); they end where the next segment starts.
+    pub fn rebuild_segments(&self) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE stream_segments
+                 SET ended_at = (
+                     SELECT MIN(n.started_at) FROM stream_segments n
+                     WHERE n.started_at > stream_segments.started_at
+                        OR (n.started_at = stream_segments.started_at
+                            AND n.id > stream_segments.id)
+                 )
+                 WHERE ended_at IS NULL
+                   AND EXISTS (
+                     SELECT 1 FROM stream_segments n
+                     WHERE n.started_at > stream_segments.started_at
+                        OR (n.started_at = stream_segments.started_at
+                            AND n.id > stream_segments.id)
+                 )",
+                [],
+            )?;
+            Ok(n)
+        })
+    }
+
+    /// Restore raids that are in the event archive but missing from the
+    /// raid history.
+    pub fn rebuild_raids(&self) -> Result<usize, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let mut fixed = 0;
+            for (event_type, direction, side) in RAID_EVENTS {
+                let events: Vec<(String, i64)> = {
+                    let mut stmt = tx.prepare(
+                        "SELECT payload, received_at FROM event_archive WHERE event_type = ?1",
+                    )?;
+                    stmt.query_map([event_type], |row| Ok((row.get(0)?, row.get(1)?)))?
+                        .collect::<Result<_, _>>()?
+                };
+                for (json, received_at) in events {
+                    let payload: serde_json::Value =
+                        serde_json::from_str(&json).unwrap_or_default();
+                    let field = |name: &str| {
+                        payload[format!("{side}_broadcaster_user_{name}")]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string()
+                    };
+                    let channel_id = field("id");
+                    if channel_id.is_empty() {
+                        continue;
+                    }
+                    fixed += tx.execute(
+                        "INSERT INTO raids
+                             (direction, channel_id, channel_login, channel_name, viewers, created_at)
+                         SELECT ?1, ?2, ?3, ?4, ?5, ?6
+                         WHERE NOT EXISTS (
+                             SELECT 1 FROM raids
+                             WHERE direction = ?1 AND channel_id = ?2
+                               AND ABS(created_at - ?6) <= ?7
+                         )",
+                        rusqlite::params![
+                            direction,
+                            channel_id,
+                            field("login"),
+                            field("name"),
+                            payload["viewers"].as_i64().unwrap_or(0),
+                            received_at,
+                            RAID_MATCH_SECONDS,
+                        ],
+                    )?;
+                }
+            }
+            tx.commit()?;
+            Ok(fixed)
+        })
+    }
+}
@@ -0,0 +1,100 @@
+//! Quote database for the `!quote` chat commands.
+
+use crate::{Database, DbError, like_contains};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub id: i64,
+    pub text: String,
+    pub author: String,
+    pub added_by: String,
+    pub created_at: i64,
+}
+
+const SELECT: &str = "SELECT id, text, author, added_by, created_at FROM quotes";
+
+fn map_quote(row: &rusqlite::Row<'_>) -> rusqlite::Result<Quote> {
+    Ok(Quote {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        author: row.get(2)?,
+        added_by: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+impl Database {
+    pub fn add_quote(
+        &self,
+        text: &str,
+        author: &str,
+        added_by: &str,
+        now: i64,
+    ) -> Result<Quote, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO quotes (text, author, added_by, created_at) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![text, author, added_by, now],
+            )?;
+            Ok(Quote {
+                id: conn.last_insert_rowid(),
+                text: text.to_string(),
+                author: author.to_string(),
+                added_by: added_by.to_string(),
+                created_at: now,
+            })
+        })
+    }
+
+    pub fn get_quote(&self, id: i64) -> Result<Option<Quote>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(&format!("{SELECT} WHERE id = ?1"), [id], map_quote)
+                .optional()
+                .map_err(Into::into)
+        })
+    }
+
+    pub fn get_random_quote(&self) -> Result<Option<Quote>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                &format!("{SELECT} ORDER BY RANDOM() LIMIT 1"),
+                [],
+                map_quote,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    /// All quotes, oldest first; `search` filters on text and author.
+    pub fn get_quotes(&self, search: Option<&str>) -> Result<Vec<Quote>, DbError> {
+        self.with_conn(|conn| {
+            let pattern = like_contains(search.unwrap_or_default());
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT} WHERE text LIKE ?1 ESCAPE '\\' OR author LIKE ?1 ESCAPE '\\'
+                 ORDER BY id ASC"
+            ))?;
+            let rows = stmt.query_map([pattern], map_quote)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    pub fn update_quote(&self, id: i64, text: &str, author: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE quotes SET text = ?2, author = ?3 WHERE id = ?1",
+                rusqlite::params![id, text, author],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    pub fn delete_quote(&self, id: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM quotes WHERE id = ?1", [id])?;
+            Ok(n > 0)
+        })
+    }
+}
@@ -0,0 +1,120 @@
+//! Raid history (inbound and outbound) and per-channel reciprocity.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+pub const DIRECTION_IN: &str = "in";
+pub const DIRECTION_OUT: &str = "out";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Raid {
+    pub id: i64,
+    pub direction: String,
+    pub channel_id: String,
+    pub channel_login: String,
+    pub channel_name: String,
+    pub viewers: i64,
+    pub created_at: i64,
+}
+
+/// Raids exchanged with one channel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaidPartner {
+    pub channel_id: String,
+    pub channel_login: String,
+    pub channel_name: String,
+    pub raids_in: i64,
+    pub raids_out: i64,
+    pub viewers_in: i64,
+    pub viewers_out: i64,
+    pub last_in_at: Option<i64>,
+    pub last_out_at: Option<i64>,
+}
+
+impl Database {
+    pub fn add_raid(
+        &self,
+        direction: &str,
+        channel_id: &str,
+        channel_login: &str,
+        channel_name: &str,
+        viewers: i64,
+        now: i64,
+    ) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO raids
+                    (direction, channel_id, channel_login, channel_name, viewers, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    direction,
+                    channel_id,
+                    channel_login,
+                    channel_name,
+                    viewers,
+                    now
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    /// Raids at or after `since`, newest first.
+    pub fn get_raids_since(&self, since: i64, limit: i64) -> Result<Vec<Raid>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, direction, channel_id, channel_login, channel_name, viewers, created_at
+                 FROM raids WHERE created_at >= ?1
+                 ORDER BY created_at DESC, id DESC LIMIT ?2",
+            )?;
+            let rows = stmt.query_map([since, limit], |row| {
+                Ok(Raid {
+                    id: row.get(0)?,
+                    direction: row.get(1)?,
+                    channel_id: row.get(2)?,
+                    channel_login: row.get(3)?,
+                    channel_name: row.get(4)?,
+                    viewers: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Per-channel raid totals since `since`, most raids first. Names are
+    /// taken from the most recent raid with that channel.
+    pub fn get_raid_partners(&self, since: i64) -> Result<Vec<RaidPartner>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT r.channel_id,
+                        (SELECT channel_login FROM raids WHERE channel_id = r.channel_id
+                         ORDER BY created_at DESC, id DESC LIMIT 1),
+                        (SELECT channel_name FROM raids WHERE channel_id = r.channel_id
+                         ORDER BY created_at DESC, id DESC LIMIT 1),
+                        SUM(r.direction = 'in'), SUM(r.direction = 'out'),
+                        COALESCE(SUM(CASE WHEN r.direction = 'in' THEN r.viewers END), 0),
+                        COALESCE(SUM(CASE WHEN r.direction = 'out' THEN r.viewers END), 0),
+                        MAX(CASE WHEN r.direction = 'in' THEN r.created_at END),
+                        MAX(CASE WHEN r.direction = 'out' THEN r.created_at END)
+                 FROM raids r WHERE r.created_at >= ?1
+                 GROUP BY r.channel_id
+                 ORDER BY COUNT(*) DESC, MAX(r.created_at) DESC",
+            )?;
+            let rows = stmt.query_map([since], |row| {
+                Ok(RaidPartner {
+                    channel_id: row.get(0)?,
+                    channel_login: row.get(1)?,
+                    channel_name: row.get(2)?,
+                    raids_in: row.get(3)?,
+                    raids_out: row.get(4)?,
+                    viewers_in: row.get(5)?,
+                    viewers_out: row.get(6)?,
+                    last_in_at: row.get(7)?,
+                    last_out_at: row.get(8)?,
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+}
@@ -0,0 +1,72 @@
+//! Emotes the broadcaster recently sent in chat.
+//!
+//! One row per emote with its last use and a use count; only the newest
+//! rows up to the limit passed to [`Database::record_emote_uses`] are kept.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentEmote {
+    pub emote_id: String,
+    /// Emote name as it appeared in the message.
+    pub name: String,
+    pub use_count: i64,
+    pub last_used_at: i64,
+}
+
+impl Database {
+    /// Record one use of each `(emote_id, name)` at `at` and drop all but the
+    /// `keep` most recently used emotes.
+    pub fn record_emote_uses(
+        &self,
+        emotes: &[(&str, &str)],
+        at: i64,
+        keep: i64,
+    ) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO recent_emotes (emote_id, name, use_count, last_used_at)
+                     VALUES (?1, ?2, 1, ?3)
+                     ON CONFLICT(emote_id) DO UPDATE SET
+                        name = excluded.name,
+                        use_count = use_count + 1,
+                        last_used_at = MAX(last_used_at, excluded.last_used_at)",
+                )?;
+                for (id, name) in emotes {
+                    stmt.execute(rusqlite::params![id, name, at])?;
+                }
+            }
+            tx.execute(
+                "DELETE FROM recent_emotes WHERE emote_id NOT IN (
+                    SELECT emote_id FROM recent_emotes
+                    ORDER BY last_used_at DESC, use_count DESC LIMIT ?1
+                 )",
+                [keep],
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Most recently used emotes first.
+    pub fn recent_emotes(&self, limit: i64) -> Result<Vec<RecentEmote>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT emote_id, name, use_count, last_used_at FROM recent_emotes
+                 ORDER BY last_used_at DESC, use_count DESC LIMIT ?1",
+            )?;
+            let rows = stmt.query_map([limit], |row| {
+                Ok(RecentEmote {
+                    emote_id: row.get(0)?,
+                    name: row.get(1)?,
+                    use_count: row.get(2)?,
+                    last_used_at: row.get(3)?,
+                })
+            })?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+    }
+}
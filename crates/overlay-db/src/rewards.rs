@@ -205,6 +205,96 @@ impl Database {
     }
 }
 
+// --- Reward Count Goals ---
+
+/// A redemption count that fires a celebration when reached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RewardCountGoal {
+    pub reward_id: String,
+    pub goal: i32,
+    pub label: String,
+    /// Overlay effect request fired when the goal is reached.
+    pub effect: Option<serde_json::Value>,
+    pub updated_at: i64,
+}
+
+const SELECT_GOAL: &str =
+    "SELECT reward_id, goal, label, effect, updated_at FROM reward_count_goals";
+
+fn map_goal(row: &rusqlite::Row<'_>) -> rusqlite::Result<RewardCountGoal> {
+    let effect: String = row.get(3)?;
+    Ok(RewardCountGoal {
+        reward_id: row.get(0)?,
+        goal: row.get(1)?,
+        label: row.get(2)?,
+        effect: serde_json::from_str(&effect).ok(),
+        updated_at: row.get(4)?,
+    })
+}
+
+impl Database {
+    pub fn set_reward_count_goal(
+        &self,
+        reward_id: &str,
+        goal: i32,
+        label: &str,
+        effect: Option<&serde_json::Value>,
+        now: i64,
+    ) -> Result<RewardCountGoal, DbError> {
+        let effect_json = effect.map(|e| e.to_string()).unwrap_or_default();
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO reward_count_goals (reward_id, goal, label, effect, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(reward_id) DO UPDATE SET
+                   goal = excluded.goal, label = excluded.label,
+                   effect = excluded.effect, updated_at = excluded.updated_at",
+                rusqlite::params![reward_id, goal, label, effect_json, now],
+            )?;
+            Ok(RewardCountGoal {
+                reward_id: reward_id.to_string(),
+                goal,
+                label: label.to_string(),
+                effect: effect.cloned(),
+                updated_at: now,
+            })
+        })
+    }
+
+    pub fn get_reward_count_goal(
+        &self,
+        reward_id: &str,
+    ) -> Result<Option<RewardCountGoal>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                &format!("{SELECT_GOAL} WHERE reward_id = ?1"),
+                [reward_id],
+                map_goal,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    pub fn get_reward_count_goals(&self) -> Result<Vec<RewardCountGoal>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("{SELECT_GOAL} ORDER BY reward_id"))?;
+            let rows = stmt.query_map([], map_goal)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    pub fn delete_reward_count_goal(&self, reward_id: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "DELETE FROM reward_count_goals WHERE reward_id = ?1",
+                [reward_id],
+            )?;
+            Ok(n > 0)
+        })
+    }
+}
+
 // --- Reward Groups ---
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -357,6 +447,188 @@ impl Database {
     }
 }
 
+// --- Reward Group Schedules ---
+
+pub const TRIGGER_STREAM_ONLINE: &str = "stream_online";
+pub const TRIGGER_STREAM_OFFLINE: &str = "stream_offline";
+
+/// A group switched on or off automatically at `trigger`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RewardGroupSchedule {
+    pub group_id: i64,
+    pub trigger: String,
+    pub enabled: bool,
+}
+
+fn map_schedule(row: &rusqlite::Row<'_>) -> rusqlite::Result<RewardGroupSchedule> {
+    Ok(RewardGroupSchedule {
+        group_id: row.get(0)?,
+        trigger: row.get(1)?,
+        enabled: row.get(2)?,
+    })
+}
+
+impl Database {
+    /// Bind `trigger` to enable/disable the group; `None` removes the binding.
+    pub fn set_reward_group_schedule(
+        &self,
+        group_id: i64,
+        trigger: &str,
+        enabled: Option<bool>,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            match enabled {
+                Some(enabled) => conn.execute(
+                    "INSERT INTO reward_group_schedules (group_id, trigger, enabled)
+                     VALUES (?1, ?2, ?3)
+                     ON CONFLICT(group_id, trigger) DO UPDATE SET enabled = excluded.enabled",
+                    rusqlite::params![group_id, trigger, enabled],
+                )?,
+                None => conn.execute(
+                    "DELETE FROM reward_group_schedules WHERE group_id = ?1 AND trigger = ?2",
+                    rusqlite::params![group_id, trigger],
+                )?,
+            };
+            Ok(())
+        })
+    }
+
+    pub fn get_reward_group_schedules(
+        &self,
+        group_id: i64,
+    ) -> Result<Vec<RewardGroupSchedule>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT group_id, trigger, enabled FROM reward_group_schedules
+                 WHERE group_id = ?1 ORDER BY trigger",
+            )?;
+            let rows = stmt.query_map([group_id], map_schedule)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Every group bound to `trigger`.
+    pub fn get_scheduled_reward_groups(
+        &self,
+        trigger: &str,
+    ) -> Result<Vec<RewardGroupSchedule>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT group_id, trigger, enabled FROM reward_group_schedules
+                 WHERE trigger = ?1 ORDER BY group_id",
+            )?;
+            let rows = stmt.query_map([trigger], map_schedule)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+}
+
+// --- Reward Group Policies ---
+
+/// Switched by hand (and by schedules) only.
+pub const POLICY_MANUAL: &str = "manual";
+/// On while the stream is live, off otherwise.
+pub const POLICY_LIVE: &str = "live";
+/// On while the stream is offline, off otherwise.
+pub const POLICY_OFFLINE: &str = "offline";
+
+/// When a group should be on, kept in line by the stream status worker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RewardGroupPolicy {
+    /// `POLICY_*`.
+    pub mode: String,
+    /// Local-time windows the group is on in; empty means any time. A
+    /// `manual` group with windows follows the windows alone.
+    #[serde(default)]
+    pub windows: Vec<PolicyWindow>,
+}
+
+impl Default for RewardGroupPolicy {
+    fn default() -> Self {
+        Self {
+            mode: POLICY_MANUAL.to_string(),
+            windows: Vec::new(),
+        }
+    }
+}
+
+impl RewardGroupPolicy {
+    /// Whether the policy switches the group at all.
+    pub fn is_automatic(&self) -> bool {
+        self.mode != POLICY_MANUAL || !self.windows.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyWindow {
+    /// Weekdays the window starts on, 0 = Sunday; empty means every day.
+    #[serde(default)]
+    pub days: Vec<u32>,
+    /// `HH:MM`; a window ending before it starts runs past midnight.
+    pub start: String,
+    pub end: String,
+}
+
+fn map_policy(mode: String, windows: &str) -> RewardGroupPolicy {
+    RewardGroupPolicy {
+        mode,
+        windows: serde_json::from_str(windows).unwrap_or_default(),
+    }
+}
+
+impl Database {
+    pub fn set_reward_group_policy(
+        &self,
+        group_id: i64,
+        policy: &RewardGroupPolicy,
+    ) -> Result<(), DbError> {
+        let windows = serde_json::to_string(&policy.windows).unwrap_or_else(|_| "[]".into());
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE reward_groups SET policy = ?2, policy_windows = ?3 WHERE id = ?1",
+                rusqlite::params![group_id, policy.mode, windows],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_reward_group_policy(&self, group_id: i64) -> Result<RewardGroupPolicy, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT policy, policy_windows FROM reward_groups WHERE id = ?1",
+                [group_id],
+                |row| Ok(map_policy(row.get(0)?, &row.get::<_, String>(1)?)),
+            )
+            .map_err(Into::into)
+        })
+    }
+
+    /// Groups with an automatic policy, with their policies.
+    pub fn get_automatic_reward_groups(
+        &self,
+    ) -> Result<Vec<(RewardGroup, RewardGroupPolicy)>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, is_enabled, created_at, updated_at, policy, policy_windows
+                 FROM reward_groups
+                 WHERE policy != 'manual' OR policy_windows NOT IN ('', '[]')
+                 ORDER BY id",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                let group = RewardGroup {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    is_enabled: row.get(2)?,
+                    created_at: row.get(3)?,
+                    updated_at: row.get(4)?,
+                };
+                Ok((group, map_policy(row.get(5)?, &row.get::<_, String>(6)?)))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+}
+
 trait OptionalExt<T> {
     fn optional(self) -> Result<Option<T>, rusqlite::Error>;
 }
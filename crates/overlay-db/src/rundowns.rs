@@ -0,0 +1,142 @@
+//! Stream rundown: ordered checklist items per stream day.
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RundownItem {
+    pub id: i64,
+    /// Stream day, `YYYY-MM-DD`.
+    pub stream_date: String,
+    pub position: i64,
+    pub text: String,
+    pub checked: bool,
+    pub checked_at: Option<i64>,
+    pub created_at: i64,
+}
+
+const SELECT: &str =
+    "SELECT id, stream_date, position, text, checked, checked_at, created_at FROM rundowns";
+
+fn map_item(row: &rusqlite::Row<'_>) -> rusqlite::Result<RundownItem> {
+    Ok(RundownItem {
+        id: row.get(0)?,
+        stream_date: row.get(1)?,
+        position: row.get(2)?,
+        text: row.get(3)?,
+        checked: row.get::<_, i64>(4)? != 0,
+        checked_at: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+impl Database {
+    /// Items of one stream day in order.
+    pub fn get_rundown(&self, stream_date: &str) -> Result<Vec<RundownItem>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT} WHERE stream_date = ?1 ORDER BY position ASC, id ASC"
+            ))?;
+            let rows = stmt.query_map([stream_date], map_item)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    pub fn get_rundown_item(&self, id: i64) -> Result<Option<RundownItem>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(&format!("{SELECT} WHERE id = ?1"), [id], map_item)
+                .optional()
+                .map_err(Into::into)
+        })
+    }
+
+    /// Append an item to the end of the day's rundown.
+    pub fn add_rundown_item(
+        &self,
+        stream_date: &str,
+        text: &str,
+        now: i64,
+    ) -> Result<RundownItem, DbError> {
+        self.with_conn(|conn| {
+            let position: i64 = conn.query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM rundowns WHERE stream_date = ?1",
+                [stream_date],
+                |row| row.get(0),
+            )?;
+            conn.execute(
+                "INSERT INTO rundowns (stream_date, position, text, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![stream_date, position, text, now],
+            )?;
+            Ok(RundownItem {
+                id: conn.last_insert_rowid(),
+                stream_date: stream_date.to_string(),
+                position,
+                text: text.to_string(),
+                checked: false,
+                checked_at: None,
+                created_at: now,
+            })
+        })
+    }
+
+    pub fn update_rundown_item_text(&self, id: i64, text: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE rundowns SET text = ?2 WHERE id = ?1",
+                rusqlite::params![id, text],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Check or uncheck an item; returns the updated item.
+    pub fn set_rundown_item_checked(
+        &self,
+        id: i64,
+        checked: bool,
+        now: i64,
+    ) -> Result<Option<RundownItem>, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE rundowns SET checked = ?2, checked_at = ?3 WHERE id = ?1",
+                rusqlite::params![id, checked, checked.then_some(now)],
+            )?;
+            conn.query_row(&format!("{SELECT} WHERE id = ?1"), [id], map_item)
+                .optional()
+                .map_err(Into::into)
+        })
+    }
+
+    /// Put the listed items first in the given order; the rest keep their
+    /// relative order after them.
+    pub fn reorder_rundown(&self, stream_date: &str, ids: &[i64]) -> Result<(), DbError> {
+        let mut items = self.get_rundown(stream_date)?;
+        let mut ordered = Vec::with_capacity(items.len());
+        for id in ids {
+            if let Some(i) = items.iter().position(|item| item.id == *id) {
+                ordered.push(items.remove(i));
+            }
+        }
+        ordered.extend(items);
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            for (position, item) in ordered.iter().enumerate() {
+                tx.execute(
+                    "UPDATE rundowns SET position = ?2 WHERE id = ?1",
+                    rusqlite::params![item.id, position as i64],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    pub fn delete_rundown_item(&self, id: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM rundowns WHERE id = ?1", [id])?;
+            Ok(n > 0)
+        })
+    }
+}
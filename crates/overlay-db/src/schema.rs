@@ -1,157 +1,334 @@
 //! Database schema definitions and migrations.
+//!
+//! Schema changes are numbered SQL files under `migrations/` and are applied
+//! forward-only. Each applied version is recorded in `schema_version`, so a
+//! migration runs at most once per database.
+//!
+//! Adding a change:
+//! 1. Create `migrations/NNNN_description.sql` with the next version number.
+//! 2. Append it to [`MIGRATIONS`].
+//!
+//! Downgrades are not supported in SQL. Before pending migrations are applied
+//! to an on-disk database, [`crate::Database::open`] copies the file to
+//! `<db>.v<current-version>.bak`. To roll back to an older build, stop the
+//! app and replace the database file with that backup. Opening a database
+//! whose version is newer than this build knows fails with
+//! [`DbError::InvalidData`] instead of touching it.
 
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 
 use crate::DbError;
 
-pub fn run_migrations(conn: &Connection) -> Result<(), DbError> {
-    conn.execute_batch(SCHEMA)?;
-    Ok(())
+/// A single numbered schema migration.
+#[derive(Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
 }
 
-const SCHEMA: &str = r#"
-CREATE TABLE IF NOT EXISTS tokens (
-    id INTEGER PRIMARY KEY,
-    access_token TEXT,
-    refresh_token TEXT,
-    scope TEXT,
-    expires_at INTEGER
-);
-
-CREATE TABLE IF NOT EXISTS settings (
-    key TEXT PRIMARY KEY,
-    value TEXT NOT NULL,
-    setting_type TEXT NOT NULL DEFAULT 'normal',
-    is_required BOOLEAN NOT NULL DEFAULT false,
-    description TEXT,
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-);
-
-CREATE TABLE IF NOT EXISTS playback_state (
-    id INTEGER PRIMARY KEY,
-    track_id TEXT NOT NULL,
-    position REAL NOT NULL DEFAULT 0,
-    duration REAL NOT NULL DEFAULT 0,
-    playback_status TEXT NOT NULL DEFAULT 'stopped',
-    is_playing BOOLEAN NOT NULL DEFAULT false,
-    volume INTEGER NOT NULL DEFAULT 70,
-    playlist_name TEXT,
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-);
-
-CREATE TABLE IF NOT EXISTS playlists (
-    id TEXT PRIMARY KEY,
-    name TEXT NOT NULL UNIQUE,
-    description TEXT,
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-);
-
-CREATE TABLE IF NOT EXISTS playlist_tracks (
-    playlist_id TEXT NOT NULL,
-    track_id TEXT NOT NULL,
-    position INTEGER NOT NULL,
-    added_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    PRIMARY KEY (playlist_id, track_id),
-    FOREIGN KEY (playlist_id) REFERENCES playlists(id) ON DELETE CASCADE
-);
-
-CREATE TABLE IF NOT EXISTS tracks (
-    id TEXT PRIMARY KEY,
-    file_path TEXT NOT NULL UNIQUE,
-    title TEXT,
-    artist TEXT,
-    album TEXT,
-    duration REAL,
-    added_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-);
-
-CREATE TABLE IF NOT EXISTS cache_entries (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    url_hash TEXT UNIQUE NOT NULL,
-    original_url TEXT NOT NULL,
-    file_path TEXT NOT NULL,
-    file_size INTEGER DEFAULT 0,
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    last_accessed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-);
-
-CREATE TABLE IF NOT EXISTS reward_groups (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    name TEXT NOT NULL UNIQUE,
-    is_enabled BOOLEAN NOT NULL DEFAULT true,
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-);
-
-CREATE TABLE IF NOT EXISTS reward_group_members (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    group_id INTEGER NOT NULL,
-    reward_id TEXT NOT NULL,
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    UNIQUE(group_id, reward_id),
-    FOREIGN KEY (group_id) REFERENCES reward_groups(id) ON DELETE CASCADE
-);
+/// All migrations, in ascending version order.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial",
+        sql: include_str!("migrations/0001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "stream_segments",
+        sql: include_str!("migrations/0002_stream_segments.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "milestones",
+        sql: include_str!("migrations/0003_milestones.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "emote_print_rules",
+        sql: include_str!("migrations/0004_emote_print_rules.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "event_triggers",
+        sql: include_str!("migrations/0005_event_triggers.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "effect_presets",
+        sql: include_str!("migrations/0006_effect_presets.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "emote_rain",
+        sql: include_str!("migrations/0007_emote_rain.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "quotes",
+        sql: include_str!("migrations/0008_quotes.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "raids",
+        sql: include_str!("migrations/0009_raids.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "event_archive",
+        sql: include_str!("migrations/0010_event_archive.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "rundowns",
+        sql: include_str!("migrations/0011_rundowns.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "chat_badges",
+        sql: include_str!("migrations/0012_chat_badges.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "viewer_consents",
+        sql: include_str!("migrations/0013_viewer_consents.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "funding_entries",
+        sql: include_str!("migrations/0014_funding_entries.sql"),
+    },
+    Migration {
+        version: 15,
+        name: "macros",
+        sql: include_str!("migrations/0015_macros.sql"),
+    },
+    Migration {
+        version: 16,
+        name: "reward_group_schedules",
+        sql: include_str!("migrations/0016_reward_group_schedules.sql"),
+    },
+    Migration {
+        version: 17,
+        name: "reward_count_goals",
+        sql: include_str!("migrations/0017_reward_count_goals.sql"),
+    },
+    Migration {
+        version: 18,
+        name: "lottery_draws",
+        sql: include_str!("migrations/0018_lottery_draws.sql"),
+    },
+    Migration {
+        version: 19,
+        name: "print_jobs",
+        sql: include_str!("migrations/0019_print_jobs.sql"),
+    },
+    Migration {
+        version: 20,
+        name: "polls_predictions",
+        sql: include_str!("migrations/0020_polls_predictions.sql"),
+    },
+    Migration {
+        version: 21,
+        name: "webhooks",
+        sql: include_str!("migrations/0021_webhooks.sql"),
+    },
+    Migration {
+        version: 22,
+        name: "recent_emotes",
+        sql: include_str!("migrations/0022_recent_emotes.sql"),
+    },
+    Migration {
+        version: 23,
+        name: "discord_templates",
+        sql: include_str!("migrations/0023_discord_templates.sql"),
+    },
+    Migration {
+        version: 24,
+        name: "chat_timers",
+        sql: include_str!("migrations/0024_chat_timers.sql"),
+    },
+    Migration {
+        version: 25,
+        name: "chat_fts",
+        sql: include_str!("migrations/0025_chat_fts.sql"),
+    },
+    Migration {
+        version: 26,
+        name: "print_votes",
+        sql: include_str!("migrations/0026_print_votes.sql"),
+    },
+    Migration {
+        version: 27,
+        name: "stream_sessions",
+        sql: include_str!("migrations/0027_stream_sessions.sql"),
+    },
+    Migration {
+        version: 28,
+        name: "lottery_presets",
+        sql: include_str!("migrations/0028_lottery_presets.sql"),
+    },
+    Migration {
+        version: 29,
+        name: "viewer_samples",
+        sql: include_str!("migrations/0029_viewer_samples.sql"),
+    },
+    Migration {
+        version: 30,
+        name: "chatters",
+        sql: include_str!("migrations/0030_chatters.sql"),
+    },
+    Migration {
+        version: 31,
+        name: "lottery_draw_proofs",
+        sql: include_str!("migrations/0031_lottery_draw_proofs.sql"),
+    },
+    Migration {
+        version: 32,
+        name: "lottery_draw_delivery",
+        sql: include_str!("migrations/0032_lottery_draw_delivery.sql"),
+    },
+    Migration {
+        version: 33,
+        name: "reward_group_policies",
+        sql: include_str!("migrations/0033_reward_group_policies.sql"),
+    },
+    Migration {
+        version: 34,
+        name: "lottery_draw_claims",
+        sql: include_str!("migrations/0034_lottery_draw_claims.sql"),
+    },
+    Migration {
+        version: 35,
+        name: "viewer_profiles",
+        sql: include_str!("migrations/0035_viewer_profiles.sql"),
+    },
+    Migration {
+        version: 36,
+        name: "moderation_log",
+        sql: include_str!("migrations/0036_moderation_log.sql"),
+    },
+    Migration {
+        version: 37,
+        name: "irc_chat_messages",
+        sql: include_str!("migrations/0037_irc_chat_messages.sql"),
+    },
+    Migration {
+        version: 38,
+        name: "chat_commands",
+        sql: include_str!("migrations/0038_chat_commands.sql"),
+    },
+    Migration {
+        version: 39,
+        name: "chat_timer_announcements",
+        sql: include_str!("migrations/0039_chat_timer_announcements.sql"),
+    },
+    Migration {
+        version: 40,
+        name: "moderation_events",
+        sql: include_str!("migrations/0040_moderation_events.sql"),
+    },
+    Migration {
+        version: 41,
+        name: "word_filter_patterns",
+        sql: include_str!("migrations/0041_word_filter_patterns.sql"),
+    },
+    Migration {
+        version: 42,
+        name: "word_filter_revision",
+        sql: include_str!("migrations/0042_word_filter_revision.sql"),
+    },
+];
 
-CREATE TABLE IF NOT EXISTS app_created_rewards (
-    reward_id TEXT PRIMARY KEY,
-    title TEXT NOT NULL,
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-);
+/// Latest schema version known to this build.
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
 
-CREATE TABLE IF NOT EXISTS reward_redemption_counts (
-    reward_id TEXT PRIMARY KEY,
-    count INTEGER NOT NULL DEFAULT 0,
-    user_names TEXT DEFAULT '[]',
-    display_name TEXT DEFAULT '',
-    is_enabled BOOLEAN DEFAULT NULL,
-    last_reset_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-);
+pub fn run_migrations(conn: &Connection) -> Result<(), DbError> {
+    apply_migrations(conn, false)?;
+    Ok(())
+}
 
-CREATE TABLE IF NOT EXISTS word_filter_words (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    language TEXT NOT NULL,
-    word TEXT NOT NULL,
-    type TEXT NOT NULL CHECK(type IN ('bad', 'good')),
-    UNIQUE(language, word, type)
-);
+/// Apply pending migrations, returning the versions that were (or, with
+/// `dry_run`, would be) applied.
+pub fn apply_migrations(conn: &Connection, dry_run: bool) -> Result<Vec<u32>, DbError> {
+    let pending = pending_migrations(conn)?;
+    if dry_run {
+        return Ok(pending.iter().map(|m| m.version).collect());
+    }
+    ensure_version_table(conn)?;
 
-CREATE TABLE IF NOT EXISTS chat_messages (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    message_id TEXT,
-    user_id TEXT,
-    username TEXT NOT NULL,
-    message TEXT NOT NULL,
-    fragments_json TEXT,
-    avatar_url TEXT DEFAULT '',
-    translation_text TEXT DEFAULT '',
-    translation_status TEXT DEFAULT '',
-    translation_lang TEXT DEFAULT '',
-    created_at INTEGER NOT NULL
-);
+    let mut applied = Vec::with_capacity(pending.len());
+    for migration in pending {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(migration.sql)?;
+        tx.execute(
+            "INSERT INTO schema_version (version, name, applied_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+            rusqlite::params![migration.version, migration.name],
+        )?;
+        tx.commit()?;
+        tracing::info!(
+            "Applied schema migration {:04}_{}",
+            migration.version,
+            migration.name
+        );
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
 
-CREATE UNIQUE INDEX IF NOT EXISTS idx_chat_messages_message_id
-    ON chat_messages(message_id)
-    WHERE message_id IS NOT NULL AND message_id != '';
+/// Migrations not yet recorded in `schema_version`.
+pub fn pending_migrations(conn: &Connection) -> Result<Vec<&'static Migration>, DbError> {
+    let current = current_version(conn)?;
+    let latest = latest_version();
+    if current > latest {
+        return Err(DbError::InvalidData(format!(
+            "database schema version {current} is newer than supported version {latest}; \
+             restore a backup taken before the upgrade"
+        )));
+    }
+    Ok(MIGRATIONS.iter().filter(|m| m.version > current).collect())
+}
 
-CREATE INDEX IF NOT EXISTS idx_chat_messages_created_at
-    ON chat_messages(created_at);
+/// Highest applied schema version (0 for a fresh or pre-versioned database).
+pub fn current_version(conn: &Connection) -> Result<u32, DbError> {
+    let has_table = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !has_table {
+        return Ok(0);
+    }
+    let version = conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+        row.get::<_, Option<u32>>(0)
+    })?;
+    Ok(version.unwrap_or(0))
+}
 
-CREATE INDEX IF NOT EXISTS idx_chat_messages_user_id
-    ON chat_messages(user_id);
+/// True if the database already holds application tables.
+pub fn has_user_tables(conn: &Connection) -> Result<bool, DbError> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != 'schema_version'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
 
-CREATE TABLE IF NOT EXISTS lottery_participants (
-    user_id TEXT PRIMARY KEY,
-    username TEXT NOT NULL,
-    display_name TEXT NOT NULL,
-    avatar_url TEXT DEFAULT '',
-    redeemed_at TIMESTAMP NOT NULL,
-    is_subscriber BOOLEAN NOT NULL DEFAULT false,
-    subscribed_months INTEGER NOT NULL DEFAULT 0,
-    subscriber_tier TEXT DEFAULT '',
-    entry_count INTEGER NOT NULL DEFAULT 1,
-    assigned_color TEXT DEFAULT '',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-);
-"#;
+fn ensure_version_table(conn: &Connection) -> Result<(), DbError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );",
+    )?;
+    Ok(())
+}
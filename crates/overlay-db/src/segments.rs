@@ -0,0 +1,158 @@
+//! Stream segment storage ("Just Chatting", "Ranked", "Art", ...).
+//!
+//! Only one segment is open at a time; starting a new one closes the
+//! previous. Timestamps are unix seconds, matching `chat_messages.created_at`.
+
+use crate::{Database, DbError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamSegment {
+    pub id: i64,
+    pub name: String,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+}
+
+/// Per-segment statistics derived from stored chat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentStats {
+    pub segment: StreamSegment,
+    pub duration_secs: i64,
+    pub chat_messages: i64,
+    pub unique_chatters: i64,
+}
+
+impl Database {
+    /// Close the open segment (if any) and start a new one.
+    pub fn start_segment(&self, name: &str, now: i64) -> Result<StreamSegment, DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "UPDATE stream_segments SET ended_at = ?1 WHERE ended_at IS NULL",
+                [now],
+            )?;
+            tx.execute(
+                "INSERT INTO stream_segments (name, started_at) VALUES (?1, ?2)",
+                rusqlite::params![name, now],
+            )?;
+            let id = tx.last_insert_rowid();
+            tx.commit()?;
+            Ok(StreamSegment {
+                id,
+                name: name.to_string(),
+                started_at: now,
+                ended_at: None,
+            })
+        })
+    }
+
+    /// Close the open segment. Returns the closed segment, if one was open.
+    pub fn end_current_segment(&self, now: i64) -> Result<Option<StreamSegment>, DbError> {
+        let Some(mut current) = self.get_current_segment()? else {
+            return Ok(None);
+        };
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE stream_segments SET ended_at = ?1 WHERE id = ?2",
+                rusqlite::params![now, current.id],
+            )?;
+            Ok(())
+        })?;
+        current.ended_at = Some(now);
+        Ok(Some(current))
+    }
+
+    pub fn get_current_segment(&self) -> Result<Option<StreamSegment>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT id, name, started_at, ended_at FROM stream_segments
+                 WHERE ended_at IS NULL ORDER BY started_at DESC, id DESC LIMIT 1",
+                [],
+                map_segment,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    pub fn get_segment(&self, id: i64) -> Result<Option<StreamSegment>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT id, name, started_at, ended_at FROM stream_segments WHERE id = ?1",
+                [id],
+                map_segment,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    /// Segments that started at or after `since`, oldest first.
+    pub fn get_segments_since(&self, since: i64) -> Result<Vec<StreamSegment>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, started_at, ended_at FROM stream_segments
+                 WHERE started_at >= ?1 ORDER BY started_at ASC, id ASC",
+            )?;
+            let rows = stmt.query_map([since], map_segment)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    pub fn delete_segment(&self, id: i64) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM stream_segments WHERE id = ?1", [id])?;
+            if n == 0 {
+                return Err(DbError::NotFound(format!("segment {id}")));
+            }
+            Ok(())
+        })
+    }
+
+    /// Compute stats for a segment. An open segment is measured up to `now`.
+    pub fn get_segment_stats(
+        &self,
+        segment: &StreamSegment,
+        now: i64,
+    ) -> Result<SegmentStats, DbError> {
+        let end = segment.ended_at.unwrap_or(now);
+        self.with_conn(|conn| {
+            let (chat_messages, unique_chatters) = conn.query_row(
+                "SELECT COUNT(*), COUNT(DISTINCT COALESCE(NULLIF(user_id, ''), username))
+                 FROM chat_messages WHERE created_at >= ?1 AND created_at < ?2",
+                [segment.started_at, end],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )?;
+            Ok(SegmentStats {
+                segment: segment.clone(),
+                duration_secs: (end - segment.started_at).max(0),
+                chat_messages,
+                unique_chatters,
+            })
+        })
+    }
+}
+
+fn map_segment(row: &rusqlite::Row<'_>) -> rusqlite::Result<StreamSegment> {
+    Ok(StreamSegment {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        started_at: row.get(2)?,
+        ended_at: row.get(3)?,
+    })
+}
+
+trait OptionalExt<T> {
+    fn optional(self) -> Result<Option<T>, rusqlite::Error>;
+}
+
+impl<T> OptionalExt<T> for Result<T, rusqlite::Error> {
+    fn optional(self) -> Result<Option<T>, rusqlite::Error> {
+        match self {
+            Ok(v) => Ok(Some(v)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
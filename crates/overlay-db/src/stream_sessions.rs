@@ -0,0 +1,231 @@
+//! Broadcast sessions: start/end, peak viewers and follower/subscriber
+//! changes per stream.
+//!
+//! Only one session is open at a time; opening a new one closes the
+//! previous. Each sample with a viewer count is also kept in
+//! `viewer_samples` for the viewer graph. Timestamps are unix seconds,
+//! matching `chat_messages.created_at`.
+
+use crate::{Database, DbError};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamSession {
+    pub id: i64,
+    pub stream_id: String,
+    pub title: String,
+    pub category: String,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub peak_viewers: i64,
+    pub peak_viewers_at: Option<i64>,
+    pub followers_start: Option<i64>,
+    pub followers_end: Option<i64>,
+    pub subs_start: Option<i64>,
+    pub subs_end: Option<i64>,
+    pub last_sampled_at: Option<i64>,
+}
+
+impl StreamSession {
+    /// Followers gained (negative when lost) between the first and the
+    /// latest sample.
+    pub fn follower_delta(&self) -> Option<i64> {
+        Some(self.followers_end? - self.followers_start?)
+    }
+
+    pub fn sub_delta(&self) -> Option<i64> {
+        Some(self.subs_end? - self.subs_start?)
+    }
+}
+
+/// One point of a session's viewer graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewerSample {
+    pub sampled_at: i64,
+    pub viewers: i64,
+}
+
+/// Counts taken while live; `None` for counts that could not be fetched.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamSample {
+    pub title: Option<String>,
+    pub category: Option<String>,
+    pub viewers: Option<i64>,
+    pub followers: Option<i64>,
+    pub subs: Option<i64>,
+}
+
+const SELECT: &str = "SELECT id, stream_id, title, category, started_at, ended_at, peak_viewers,
+        peak_viewers_at, followers_start, followers_end, subs_start, subs_end, last_sampled_at
+    FROM stream_sessions";
+
+fn map_session(row: &rusqlite::Row<'_>) -> rusqlite::Result<StreamSession> {
+    Ok(StreamSession {
+        id: row.get(0)?,
+        stream_id: row.get(1)?,
+        title: row.get(2)?,
+        category: row.get(3)?,
+        started_at: row.get(4)?,
+        ended_at: row.get(5)?,
+        peak_viewers: row.get(6)?,
+        peak_viewers_at: row.get(7)?,
+        followers_start: row.get(8)?,
+        followers_end: row.get(9)?,
+        subs_start: row.get(10)?,
+        subs_end: row.get(11)?,
+        last_sampled_at: row.get(12)?,
+    })
+}
+
+impl Database {
+    /// Open a session for `stream_id`, closing any other open one. A stream
+    /// already recorded (a redelivered `stream.online`, or a restart while
+    /// live) returns its existing session.
+    pub fn start_stream_session(
+        &self,
+        stream_id: &str,
+        started_at: i64,
+    ) -> Result<StreamSession, DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            if !stream_id.is_empty()
+                && let Some(existing) = tx
+                    .query_row(
+                        &format!("{SELECT} WHERE stream_id = ?1"),
+                        [stream_id],
+                        map_session,
+                    )
+                    .optional()?
+            {
+                return Ok(existing);
+            }
+            tx.execute(
+                "UPDATE stream_sessions SET ended_at = COALESCE(last_sampled_at, ?1)
+                 WHERE ended_at IS NULL",
+                [started_at],
+            )?;
+            tx.execute(
+                "INSERT INTO stream_sessions (stream_id, started_at) VALUES (?1, ?2)",
+                rusqlite::params![stream_id, started_at],
+            )?;
+            let id = tx.last_insert_rowid();
+            let session = tx.query_row(&format!("{SELECT} WHERE id = ?1"), [id], map_session)?;
+            tx.commit()?;
+            Ok(session)
+        })
+    }
+
+    /// Close the open session at `ended_at`; returns it, or `None` when no
+    /// session was open.
+    pub fn end_stream_session(&self, ended_at: i64) -> Result<Option<StreamSession>, DbError> {
+        let Some(open) = self.get_open_stream_session()? else {
+            return Ok(None);
+        };
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE stream_sessions SET ended_at = ?1 WHERE id = ?2",
+                rusqlite::params![ended_at.max(open.started_at), open.id],
+            )?;
+            Ok(())
+        })?;
+        self.get_stream_session(open.id)
+    }
+
+    pub fn get_open_stream_session(&self) -> Result<Option<StreamSession>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                &format!("{SELECT} WHERE ended_at IS NULL ORDER BY started_at DESC LIMIT 1"),
+                [],
+                map_session,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+    }
+
+    pub fn get_stream_session(&self, id: i64) -> Result<Option<StreamSession>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(&format!("{SELECT} WHERE id = ?1"), [id], map_session)
+                .optional()
+                .map_err(Into::into)
+        })
+    }
+
+    /// Sessions, newest first.
+    pub fn get_stream_sessions(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<StreamSession>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT} ORDER BY started_at DESC, id DESC LIMIT ?1 OFFSET ?2"
+            ))?;
+            let rows = stmt.query_map([limit, offset], map_session)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+
+    /// Fold a sample into session `id`: raise the peak, keep the first
+    /// follower/sub counts, replace the latest ones and add the viewer count
+    /// to the graph.
+    pub fn record_stream_sample(
+        &self,
+        id: i64,
+        sample: &StreamSample,
+        now: i64,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "UPDATE stream_sessions SET
+                    title = COALESCE(?2, title),
+                    category = COALESCE(?3, category),
+                    peak_viewers_at = CASE WHEN ?4 > peak_viewers THEN ?7 ELSE peak_viewers_at END,
+                    peak_viewers = MAX(peak_viewers, COALESCE(?4, 0)),
+                    followers_start = COALESCE(followers_start, ?5),
+                    followers_end = COALESCE(?5, followers_end),
+                    subs_start = COALESCE(subs_start, ?6),
+                    subs_end = COALESCE(?6, subs_end),
+                    last_sampled_at = ?7
+                 WHERE id = ?1",
+                rusqlite::params![
+                    id,
+                    sample.title,
+                    sample.category,
+                    sample.viewers,
+                    sample.followers,
+                    sample.subs,
+                    now
+                ],
+            )?;
+            if let Some(viewers) = sample.viewers {
+                tx.execute(
+                    "INSERT OR REPLACE INTO viewer_samples (session_id, sampled_at, viewers)
+                     VALUES (?1, ?2, ?3)",
+                    [id, now, viewers],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Session `id`'s viewer counts, oldest first.
+    pub fn get_viewer_samples(&self, id: i64) -> Result<Vec<ViewerSample>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT sampled_at, viewers FROM viewer_samples
+                 WHERE session_id = ?1 ORDER BY sampled_at ASC",
+            )?;
+            let rows = stmt.query_map([id], |row| {
+                Ok(ViewerSample {
+                    sampled_at: row.get(0)?,
+                    viewers: row.get(1)?,
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        })
+    }
+}
@@ -0,0 +1,293 @@
+//! Outbound webhooks and their delivery queue.
+//!
+//! Deliveries are deleted once delivered. A failed attempt puts the
+//! delivery back to `pending` with a later `next_attempt_at`, or to
+//! `failed` (dead letter) once the caller gives up on it. Deleting a
+//! webhook deletes its deliveries.
+
+use crate::{Database, DbError};
+use serde::Serialize;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_FAILED: &str = "failed";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Webhook {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub secret: String,
+    /// EventSub types to forward; empty means every event.
+    pub event_types: Vec<String>,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Webhook {
+    /// Whether an event of `event_type` should be sent to this webhook.
+    pub fn accepts(&self, event_type: &str) -> bool {
+        self.enabled
+            && (self.event_types.is_empty() || self.event_types.iter().any(|t| t == event_type))
+    }
+}
+
+/// Webhook fields to store.
+#[derive(Debug, Clone)]
+pub struct WebhookInput<'a> {
+    pub name: &'a str,
+    pub url: &'a str,
+    pub secret: &'a str,
+    pub event_types: &'a [String],
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_type: String,
+    /// JSON request body.
+    pub body: String,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+const SELECT_WEBHOOK: &str =
+    "SELECT id, name, url, secret, event_types, enabled, created_at, updated_at FROM webhooks";
+const SELECT_DELIVERY: &str = "SELECT id, webhook_id, event_type, body, status, attempts,
+        last_error, next_attempt_at, created_at, updated_at
+    FROM webhook_deliveries";
+
+fn map_webhook(row: &rusqlite::Row<'_>) -> rusqlite::Result<Webhook> {
+    let event_types: String = row.get(4)?;
+    Ok(Webhook {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        secret: row.get(3)?,
+        event_types: serde_json::from_str(&event_types).unwrap_or_default(),
+        enabled: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn map_delivery(row: &rusqlite::Row<'_>) -> rusqlite::Result<WebhookDelivery> {
+    Ok(WebhookDelivery {
+        id: row.get(0)?,
+        webhook_id: row.get(1)?,
+        event_type: row.get(2)?,
+        body: row.get(3)?,
+        status: row.get(4)?,
+        attempts: row.get(5)?,
+        last_error: row.get(6)?,
+        next_attempt_at: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    pub fn get_webhooks(&self) -> Result<Vec<Webhook>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("{SELECT_WEBHOOK} ORDER BY id"))?;
+            let rows = stmt.query_map([], map_webhook)?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+    }
+
+    pub fn get_webhook(&self, id: i64) -> Result<Option<Webhook>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("{SELECT_WEBHOOK} WHERE id = ?1"))?;
+            let mut rows = stmt.query_map([id], map_webhook)?;
+            Ok(rows.next().transpose()?)
+        })
+    }
+
+    /// Add a webhook and return its ID.
+    pub fn add_webhook(&self, hook: &WebhookInput<'_>, now: i64) -> Result<i64, DbError> {
+        let event_types = serde_json::to_string(hook.event_types).unwrap_or_else(|_| "[]".into());
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO webhooks
+                    (name, url, secret, event_types, enabled, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
+                rusqlite::params![
+                    hook.name,
+                    hook.url,
+                    hook.secret,
+                    event_types,
+                    hook.enabled,
+                    now
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    /// Replace a webhook's fields. Returns false if it does not exist.
+    pub fn update_webhook(
+        &self,
+        id: i64,
+        hook: &WebhookInput<'_>,
+        now: i64,
+    ) -> Result<bool, DbError> {
+        let event_types = serde_json::to_string(hook.event_types).unwrap_or_else(|_| "[]".into());
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE webhooks
+                 SET name = ?2, url = ?3, secret = ?4, event_types = ?5, enabled = ?6,
+                     updated_at = ?7
+                 WHERE id = ?1",
+                rusqlite::params![
+                    id,
+                    hook.name,
+                    hook.url,
+                    hook.secret,
+                    event_types,
+                    hook.enabled,
+                    now
+                ],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    pub fn delete_webhook(&self, id: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM webhooks WHERE id = ?1", [id])?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Queue a delivery due immediately and return its ID.
+    pub fn add_webhook_delivery(
+        &self,
+        webhook_id: i64,
+        event_type: &str,
+        body: &str,
+        now: i64,
+    ) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO webhook_deliveries
+                    (webhook_id, event_type, body, status, next_attempt_at, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5, ?5)",
+                rusqlite::params![webhook_id, event_type, body, STATUS_PENDING, now],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    pub fn count_webhook_deliveries(&self, status: &str) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            Ok(conn.query_row(
+                "SELECT COUNT(*) FROM webhook_deliveries WHERE status = ?1",
+                [status],
+                |row| row.get(0),
+            )?)
+        })
+    }
+
+    /// Deliveries with the given status, newest first.
+    pub fn list_webhook_deliveries(
+        &self,
+        status: &str,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_DELIVERY} WHERE status = ?1 ORDER BY id DESC LIMIT ?2"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params![status, limit], map_delivery)?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+    }
+
+    /// Pending deliveries due at `now`, oldest first.
+    pub fn due_webhook_deliveries(
+        &self,
+        now: i64,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SELECT_DELIVERY} WHERE status = ?1 AND next_attempt_at <= ?2
+                 ORDER BY id LIMIT ?3"
+            ))?;
+            let rows =
+                stmt.query_map(rusqlite::params![STATUS_PENDING, now, limit], map_delivery)?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+    }
+
+    /// Earliest `next_attempt_at` among pending deliveries.
+    pub fn next_webhook_delivery_due(&self) -> Result<Option<i64>, DbError> {
+        self.with_conn(|conn| {
+            Ok(conn.query_row(
+                "SELECT MIN(next_attempt_at) FROM webhook_deliveries WHERE status = ?1",
+                [STATUS_PENDING],
+                |row| row.get(0),
+            )?)
+        })
+    }
+
+    pub fn delete_webhook_delivery(&self, id: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute("DELETE FROM webhook_deliveries WHERE id = ?1", [id])?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Record a failed attempt. With `retry_at` the delivery is pending
+    /// again from then on; without it the delivery becomes a dead letter.
+    pub fn fail_webhook_delivery(
+        &self,
+        id: i64,
+        error: &str,
+        retry_at: Option<i64>,
+        now: i64,
+    ) -> Result<(), DbError> {
+        let status = if retry_at.is_some() {
+            STATUS_PENDING
+        } else {
+            STATUS_FAILED
+        };
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE webhook_deliveries
+                 SET status = ?2, attempts = attempts + 1, last_error = ?3,
+                     next_attempt_at = ?4, updated_at = ?5
+                 WHERE id = ?1",
+                rusqlite::params![id, status, error, retry_at.unwrap_or(0), now],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Queue a failed delivery again with a fresh attempt count. Returns
+    /// false when it does not exist.
+    pub fn retry_webhook_delivery(&self, id: i64, now: i64) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE webhook_deliveries
+                 SET status = ?2, attempts = 0, next_attempt_at = ?3, updated_at = ?3
+                 WHERE id = ?1",
+                rusqlite::params![id, STATUS_PENDING, now],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Delete every delivery with the given status; returns how many.
+    pub fn clear_webhook_deliveries(&self, status: &str) -> Result<usize, DbError> {
+        self.with_conn(|conn| {
+            Ok(conn.execute("DELETE FROM webhook_deliveries WHERE status = ?1", [status])?)
+        })
+    }
+}
@@ -3,6 +3,12 @@
 use crate::{Database, DbError};
 use serde::{Deserialize, Serialize};
 
+/// How [`WordFilterWord::word`] is matched against a chat term.
+pub const MATCH_TYPES: &[&str] = &["exact", "substring", "wildcard", "regex"];
+
+/// Severity levels, lowest first.
+pub const SEVERITIES: &[&str] = &["low", "medium", "high"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WordFilterWord {
     pub id: i64,
@@ -10,43 +16,75 @@ pub struct WordFilterWord {
     pub word: String,
     #[serde(rename = "type")]
     pub word_type: String,
+    /// One of [`MATCH_TYPES`].
+    #[serde(default = "default_match_type")]
+    pub match_type: String,
+    /// One of [`SEVERITIES`].
+    #[serde(default = "default_severity")]
+    pub severity: String,
+}
+
+fn default_match_type() -> String {
+    "substring".into()
+}
+
+fn default_severity() -> String {
+    "medium".into()
+}
+
+impl WordFilterWord {
+    /// A substring entry of medium severity, as in the default lists.
+    pub fn new(language: &str, word: &str, word_type: &str) -> Self {
+        Self {
+            id: 0,
+            language: language.to_string(),
+            word: word.to_string(),
+            word_type: word_type.to_string(),
+            match_type: default_match_type(),
+            severity: default_severity(),
+        }
+    }
+}
+
+const SELECT: &str = "SELECT id, language, word, type, match_type, severity FROM word_filter_words";
+
+fn map_word(row: &rusqlite::Row<'_>) -> rusqlite::Result<WordFilterWord> {
+    Ok(WordFilterWord {
+        id: row.get(0)?,
+        language: row.get(1)?,
+        word: row.get(2)?,
+        word_type: row.get(3)?,
+        match_type: row.get(4)?,
+        severity: row.get(5)?,
+    })
 }
 
 impl Database {
     pub fn get_word_filter_words(&self, language: &str) -> Result<Vec<WordFilterWord>, DbError> {
         self.with_conn(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, language, word, type FROM word_filter_words WHERE language = ?1 ORDER BY word",
-            )?;
-            let rows = stmt.query_map([language], |row| {
-                Ok(WordFilterWord {
-                    id: row.get(0)?,
-                    language: row.get(1)?,
-                    word: row.get(2)?,
-                    word_type: row.get(3)?,
-                })
-            })?;
+            let mut stmt = conn.prepare(&format!("{SELECT} WHERE language = ?1 ORDER BY word"))?;
+            let rows = stmt.query_map([language], map_word)?;
             rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
         })
     }
 
-    pub fn add_word_filter_word(
-        &self,
-        language: &str,
-        word: &str,
-        word_type: &str,
-    ) -> Result<WordFilterWord, DbError> {
+    /// Store `word`; its `id` is ignored. Returns the stored entry.
+    pub fn add_word_filter_word(&self, word: &WordFilterWord) -> Result<WordFilterWord, DbError> {
         self.with_conn(|conn| {
             conn.execute(
-                "INSERT INTO word_filter_words (language, word, type) VALUES (?1, ?2, ?3)",
-                rusqlite::params![language, word, word_type],
+                "INSERT INTO word_filter_words (language, word, type, match_type, severity)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![
+                    word.language,
+                    word.word,
+                    word.word_type,
+                    word.match_type,
+                    word.severity,
+                ],
             )?;
-            let id = conn.last_insert_rowid();
             Ok(WordFilterWord {
-                id,
-                language: language.to_string(),
-                word: word.to_string(),
-                word_type: word_type.to_string(),
+                id: conn.last_insert_rowid(),
+                ..word.clone()
             })
         })
     }
@@ -59,18 +97,73 @@ impl Database {
     }
 
     pub fn bulk_insert_word_filter_words(&self, words: &[WordFilterWord]) -> Result<(), DbError> {
+        self.import_word_filter_words(words, false).map(|_| ())
+    }
+
+    /// Insert `words` in one transaction, skipping ones already listed. With
+    /// `replace`, the lists of every language in `words` are emptied first.
+    /// Returns the number of words inserted.
+    pub fn import_word_filter_words(
+        &self,
+        words: &[WordFilterWord],
+        replace: bool,
+    ) -> Result<usize, DbError> {
         self.with_conn_mut(|conn| {
             let tx = conn.transaction()?;
+            let mut inserted = 0;
             {
+                if replace {
+                    let mut languages: Vec<&str> =
+                        words.iter().map(|w| w.language.as_str()).collect();
+                    languages.sort_unstable();
+                    languages.dedup();
+                    let mut stmt = tx.prepare("DELETE FROM word_filter_words WHERE language = ?1")?;
+                    for language in languages {
+                        stmt.execute([language])?;
+                    }
+                }
                 let mut stmt = tx.prepare(
-                    "INSERT OR IGNORE INTO word_filter_words (language, word, type) VALUES (?1, ?2, ?3)",
+                    "INSERT OR IGNORE INTO word_filter_words (language, word, type, match_type, severity)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
                 )?;
                 for w in words {
-                    stmt.execute(rusqlite::params![w.language, w.word, w.word_type])?;
+                    inserted += stmt.execute(rusqlite::params![
+                        w.language,
+                        w.word,
+                        w.word_type,
+                        w.match_type,
+                        w.severity,
+                    ])?;
                 }
             }
             tx.commit()?;
-            Ok(())
+            Ok(inserted)
+        })
+    }
+
+    /// Delete the words with `ids` in one transaction; returns how many
+    /// existed.
+    pub fn delete_word_filter_words(&self, ids: &[i64]) -> Result<usize, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let mut deleted = 0;
+            {
+                let mut stmt = tx.prepare("DELETE FROM word_filter_words WHERE id = ?1")?;
+                for id in ids {
+                    deleted += stmt.execute([id])?;
+                }
+            }
+            tx.commit()?;
+            Ok(deleted)
+        })
+    }
+
+    /// Every language's words, for export.
+    pub fn get_all_word_filter_words(&self) -> Result<Vec<WordFilterWord>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("{SELECT} ORDER BY language, type, word"))?;
+            let rows = stmt.query_map([], map_word)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
         })
     }
 
@@ -92,6 +185,19 @@ impl Database {
         })
     }
 
+    /// Revision of the word lists; triggers bump it on every insert,
+    /// update and delete.
+    pub fn get_word_filter_revision(&self) -> Result<i64, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT revision FROM word_filter_revision WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(Into::into)
+        })
+    }
+
     pub fn get_word_filter_seed_version(&self) -> Result<Option<String>, DbError> {
         self.get_setting("word_filter_seed_version")
     }
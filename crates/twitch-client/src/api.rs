@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{Token, TwitchError};
 
+pub mod moderation;
+
 const HELIX_BASE: &str = "https://api.twitch.tv/helix";
 
 // ---------------------------------------------------------------------------
@@ -19,6 +21,15 @@ const HELIX_BASE: &str = "https://api.twitch.tv/helix";
 #[derive(Debug, Deserialize)]
 pub struct HelixResponse<T> {
     pub data: Vec<T>,
+    #[serde(default)]
+    pub pagination: Option<Pagination>,
+}
+
+/// Cursor for the next page; empty on the last page.
+#[derive(Debug, Default, Deserialize)]
+pub struct Pagination {
+    #[serde(default)]
+    pub cursor: Option<String>,
 }
 
 /// Stream information from GET /helix/streams.
@@ -32,6 +43,23 @@ pub struct StreamInfo {
     pub viewer_count: u64,
     #[serde(rename = "type")]
     pub stream_type: String,
+    /// RFC 3339.
+    #[serde(default)]
+    pub started_at: String,
+}
+
+/// Channel information from GET /helix/channels.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelInformation {
+    pub broadcaster_id: String,
+    pub broadcaster_login: String,
+    pub broadcaster_name: String,
+    #[serde(default)]
+    pub game_id: String,
+    #[serde(default)]
+    pub game_name: String,
+    #[serde(default)]
+    pub title: String,
 }
 
 /// Convenience wrapper returned by [`TwitchApiClient::get_stream_info`].
@@ -49,6 +77,22 @@ pub struct TwitchUser {
     pub login: String,
     pub display_name: String,
     pub profile_image_url: String,
+    #[serde(default)]
+    pub description: String,
+    /// "partner", "affiliate" or empty.
+    #[serde(default)]
+    pub broadcaster_type: String,
+    /// Account creation time (RFC 3339).
+    #[serde(default)]
+    pub created_at: String,
+}
+
+/// A channel the user follows, from GET /helix/channels/followed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowedChannel {
+    pub broadcaster_id: String,
+    pub broadcaster_login: String,
+    pub broadcaster_name: String,
 }
 
 /// Custom channel point reward.
@@ -98,6 +142,36 @@ pub struct UpdateRewardRequest {
     pub background_color: Option<String>,
 }
 
+/// Redemption statuses of GET/PATCH /helix/channel_points/custom_rewards/redemptions.
+pub const REDEMPTION_UNFULFILLED: &str = "UNFULFILLED";
+pub const REDEMPTION_FULFILLED: &str = "FULFILLED";
+/// Cancelling a redemption refunds its channel points.
+pub const REDEMPTION_CANCELED: &str = "CANCELED";
+
+/// A channel point reward redemption.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Redemption {
+    pub id: String,
+    pub user_id: String,
+    pub user_login: String,
+    pub user_name: String,
+    #[serde(default)]
+    pub user_input: String,
+    pub status: String,
+    pub redeemed_at: String,
+    pub reward: RedemptionReward,
+}
+
+/// The reward a [`Redemption`] is for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedemptionReward {
+    pub id: String,
+    pub title: String,
+    pub cost: u64,
+    #[serde(default)]
+    pub prompt: String,
+}
+
 /// User subscription info from GET /helix/subscriptions.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSubscription {
@@ -109,6 +183,103 @@ pub struct UserSubscription {
     pub user_login: String,
 }
 
+/// Paginated response carrying a `total` count (e.g. GET /helix/channels/followers).
+#[derive(Debug, Deserialize)]
+pub struct HelixTotalResponse {
+    pub total: u64,
+}
+
+/// Result of POST /helix/chat/messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentChatMessage {
+    pub message_id: String,
+    pub is_sent: bool,
+    #[serde(default)]
+    pub drop_reason: Option<serde_json::Value>,
+}
+
+/// Colors accepted by POST /helix/chat/announcements.
+pub const ANNOUNCEMENT_COLORS: &[&str] = &["primary", "blue", "green", "orange", "purple"];
+
+/// The broadcaster's ad schedule from GET /helix/channels/ads. Times are
+/// unix seconds; `None` when there is nothing scheduled (e.g. offline).
+/// The snooze endpoint only returns the snooze fields and `next_ad_at`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdSchedule {
+    #[serde(default, deserialize_with = "de_timestamp")]
+    pub next_ad_at: Option<i64>,
+    #[serde(default, deserialize_with = "de_timestamp")]
+    pub last_ad_at: Option<i64>,
+    /// Length of the next ad break in seconds.
+    #[serde(default)]
+    pub duration: u64,
+    /// Seconds of pre-roll free time left.
+    #[serde(default)]
+    pub preroll_free_time: u64,
+    /// Snoozes left.
+    #[serde(default)]
+    pub snooze_count: u32,
+    /// When another snooze becomes available.
+    #[serde(default, deserialize_with = "de_timestamp")]
+    pub snooze_refresh_at: Option<i64>,
+}
+
+/// Helix documents RFC 3339 strings for ad times but sends unix seconds,
+/// as a number or a string, and `""`/`0` for none; accept all of them.
+fn de_timestamp<'de, D: serde::Deserializer<'de>>(de: D) -> Result<Option<i64>, D::Error> {
+    let value = serde_json::Value::deserialize(de)?;
+    let secs = match &value {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.parse::<i64>().ok().or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|t| t.timestamp())
+        }),
+        _ => None,
+    };
+    Ok(secs.filter(|&s| s > 0))
+}
+
+/// Result of POST /helix/clips. The clip is processed in the background;
+/// it can be looked up with [`TwitchApiClient::get_clip`] after ~15 seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedClip {
+    pub id: String,
+    pub edit_url: String,
+}
+
+/// A clip from GET /helix/clips.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clip {
+    pub id: String,
+    pub url: String,
+    pub embed_url: String,
+    pub creator_name: String,
+    pub title: String,
+    pub thumbnail_url: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub duration: f64,
+}
+
+/// A chat badge set from GET /helix/chat/badges(/global).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatBadgeSet {
+    pub set_id: String,
+    pub versions: Vec<ChatBadgeVersion>,
+}
+
+/// One version of a badge set (e.g. `subscriber` / `24`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatBadgeVersion {
+    pub id: String,
+    pub image_url_1x: String,
+    pub image_url_2x: String,
+    pub image_url_4x: String,
+    #[serde(default)]
+    pub title: String,
+}
+
 // ---------------------------------------------------------------------------
 // Client
 // ---------------------------------------------------------------------------
@@ -121,10 +292,13 @@ pub struct TwitchApiClient {
 
 impl TwitchApiClient {
     pub fn new(client_id: String) -> Self {
-        Self {
-            http: reqwest::Client::new(),
-            client_id,
-        }
+        Self::with_http(client_id, reqwest::Client::new())
+    }
+
+    /// Create a client that sends requests with `http` (e.g. a shared,
+    /// pooled client).
+    pub fn with_http(client_id: String, http: reqwest::Client) -> Self {
+        Self { http, client_id }
     }
 
     /// Build auth headers from the given token.
@@ -220,6 +394,35 @@ impl TwitchApiClient {
         Ok(resp_body)
     }
 
+    /// Execute a PUT request with auth headers and JSON body.
+    async fn authenticated_put(
+        &self,
+        url: &str,
+        token: &Token,
+        body: &impl Serialize,
+    ) -> Result<String, TwitchError> {
+        let headers = self.auth_headers(token);
+        let resp = self
+            .http
+            .put(url)
+            .headers(headers)
+            .json(body)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let resp_body = resp.text().await?;
+
+        if !status.is_success() {
+            return Err(TwitchError::ApiError {
+                status: status.as_u16(),
+                message: resp_body,
+            });
+        }
+
+        Ok(resp_body)
+    }
+
     /// Execute a DELETE request with auth headers.
     async fn authenticated_delete(&self, url: &str, token: &Token) -> Result<(), TwitchError> {
         let headers = self.auth_headers(token);
@@ -285,6 +488,70 @@ impl TwitchApiClient {
             })
     }
 
+    /// Look up a user by login name.
+    pub async fn get_user_by_login(
+        &self,
+        token: &Token,
+        login: &str,
+    ) -> Result<Option<TwitchUser>, TwitchError> {
+        let url = format!("{HELIX_BASE}/users?login={login}");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixResponse<TwitchUser> = serde_json::from_str(&body)?;
+        Ok(resp.data.into_iter().next())
+    }
+
+    pub async fn get_user_by_id(
+        &self,
+        token: &Token,
+        user_id: &str,
+    ) -> Result<Option<TwitchUser>, TwitchError> {
+        let url = format!("{HELIX_BASE}/users?id={user_id}");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixResponse<TwitchUser> = serde_json::from_str(&body)?;
+        Ok(resp.data.into_iter().next())
+    }
+
+    /// Current title and category of `broadcaster_id`'s channel, live or
+    /// not.
+    pub async fn get_channel_information(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+    ) -> Result<Option<ChannelInformation>, TwitchError> {
+        let url = format!("{HELIX_BASE}/channels?broadcaster_id={broadcaster_id}");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixResponse<ChannelInformation> = serde_json::from_str(&body)?;
+        Ok(resp.data.into_iter().next())
+    }
+
+    /// Channels `user_id` follows, most recently followed first, up to
+    /// `limit`. Requires `user:read:follows`.
+    pub async fn get_followed_channels(
+        &self,
+        token: &Token,
+        user_id: &str,
+        limit: usize,
+    ) -> Result<Vec<FollowedChannel>, TwitchError> {
+        let mut channels = Vec::new();
+        let mut cursor: Option<String> = None;
+        while channels.len() < limit {
+            let mut url = format!("{HELIX_BASE}/channels/followed?user_id={user_id}&first=100");
+            if let Some(after) = &cursor {
+                url.push_str(&format!("&after={after}"));
+            }
+            let body = self.authenticated_get(&url, token).await?;
+            let resp: HelixResponse<FollowedChannel> = serde_json::from_str(&body)?;
+            let last_page = resp.data.is_empty();
+            channels.extend(resp.data);
+            cursor = resp.pagination.and_then(|p| p.cursor);
+            if last_page || cursor.is_none() {
+                break;
+            }
+        }
+        channels.truncate(limit);
+        Ok(channels)
+    }
+
     /// Get all custom channel point rewards for a broadcaster.
     pub async fn get_custom_rewards(
         &self,
@@ -384,6 +651,63 @@ impl TwitchApiClient {
         self.authenticated_delete(&url, token).await
     }
 
+    /// Redemptions of `reward_id` with `status` (`REDEMPTION_*`), oldest
+    /// first, up to `limit`. Only rewards created by this app's client ID
+    /// can be read.
+    pub async fn get_redemptions(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        reward_id: &str,
+        status: &str,
+        limit: usize,
+    ) -> Result<Vec<Redemption>, TwitchError> {
+        let mut redemptions = Vec::new();
+        let mut cursor: Option<String> = None;
+        while redemptions.len() < limit {
+            let mut url = format!(
+                "{HELIX_BASE}/channel_points/custom_rewards/redemptions?broadcaster_id={broadcaster_id}&reward_id={reward_id}&status={status}&sort=OLDEST&first=50"
+            );
+            if let Some(after) = &cursor {
+                url.push_str(&format!("&after={after}"));
+            }
+            let body = self.authenticated_get(&url, token).await?;
+            let resp: HelixResponse<Redemption> = serde_json::from_str(&body)?;
+            let last_page = resp.data.is_empty();
+            redemptions.extend(resp.data);
+            cursor = resp.pagination.and_then(|p| p.cursor);
+            if last_page || cursor.is_none() {
+                break;
+            }
+        }
+        redemptions.truncate(limit);
+        Ok(redemptions)
+    }
+
+    /// Mark up to 50 unfulfilled redemptions of `reward_id` as `status`
+    /// (`REDEMPTION_FULFILLED`, or `REDEMPTION_CANCELED` to refund them).
+    pub async fn update_redemption_status(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        reward_id: &str,
+        redemption_ids: &[String],
+        status: &str,
+    ) -> Result<Vec<Redemption>, TwitchError> {
+        let ids: String = redemption_ids
+            .iter()
+            .map(|id| format!("&id={id}"))
+            .collect();
+        let url = format!(
+            "{HELIX_BASE}/channel_points/custom_rewards/redemptions?broadcaster_id={broadcaster_id}&reward_id={reward_id}{ids}"
+        );
+        let body = self
+            .authenticated_patch(&url, token, &serde_json::json!({ "status": status }))
+            .await?;
+        let resp: HelixResponse<Redemption> = serde_json::from_str(&body)?;
+        Ok(resp.data)
+    }
+
     /// Check if a user is subscribed to a broadcaster.
     pub async fn get_user_subscription(
         &self,
@@ -397,4 +721,230 @@ impl TwitchApiClient {
         let resp: HelixResponse<UserSubscription> = serde_json::from_str(&body)?;
         Ok(resp.data.into_iter().next())
     }
+
+    /// Get the total follower count of a broadcaster.
+    pub async fn get_follower_count(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+    ) -> Result<u64, TwitchError> {
+        let url =
+            format!("{HELIX_BASE}/channels/followers?broadcaster_id={broadcaster_id}&first=1");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixTotalResponse = serde_json::from_str(&body)?;
+        Ok(resp.total)
+    }
+
+    /// Get the total subscriber count of a broadcaster.
+    pub async fn get_subscriber_count(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+    ) -> Result<u64, TwitchError> {
+        let url = format!("{HELIX_BASE}/subscriptions?broadcaster_id={broadcaster_id}&first=1");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixTotalResponse = serde_json::from_str(&body)?;
+        Ok(resp.total)
+    }
+
+    /// Get Twitch's global chat badges.
+    pub async fn get_global_chat_badges(
+        &self,
+        token: &Token,
+    ) -> Result<Vec<ChatBadgeSet>, TwitchError> {
+        let url = format!("{HELIX_BASE}/chat/badges/global");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixResponse<ChatBadgeSet> = serde_json::from_str(&body)?;
+        Ok(resp.data)
+    }
+
+    /// Get a broadcaster's custom chat badges (subscriber, bits).
+    pub async fn get_channel_chat_badges(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+    ) -> Result<Vec<ChatBadgeSet>, TwitchError> {
+        let url = format!("{HELIX_BASE}/chat/badges?broadcaster_id={broadcaster_id}");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixResponse<ChatBadgeSet> = serde_json::from_str(&body)?;
+        Ok(resp.data)
+    }
+
+    /// Send a chat message to a broadcaster's channel as `sender_id`.
+    pub async fn send_chat_message(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        sender_id: &str,
+        message: &str,
+    ) -> Result<SentChatMessage, TwitchError> {
+        let url = format!("{HELIX_BASE}/chat/messages");
+
+        #[derive(Serialize)]
+        struct Body<'a> {
+            broadcaster_id: &'a str,
+            sender_id: &'a str,
+            message: &'a str,
+        }
+
+        let body = self
+            .authenticated_post(
+                &url,
+                token,
+                &Body {
+                    broadcaster_id,
+                    sender_id,
+                    message,
+                },
+            )
+            .await?;
+        let resp: HelixResponse<SentChatMessage> = serde_json::from_str(&body)?;
+        resp.data
+            .into_iter()
+            .next()
+            .ok_or_else(|| TwitchError::ApiError {
+                status: 500,
+                message: "Empty chat message response".into(),
+            })
+    }
+
+    /// Post `message` as a highlighted announcement in `broadcaster_id`'s
+    /// chat. `color` is one of [`ANNOUNCEMENT_COLORS`]; `None` uses the
+    /// channel's accent color.
+    pub async fn send_announcement(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        moderator_id: &str,
+        message: &str,
+        color: Option<&str>,
+    ) -> Result<(), TwitchError> {
+        let url = format!(
+            "{HELIX_BASE}/chat/announcements?broadcaster_id={broadcaster_id}&moderator_id={moderator_id}"
+        );
+        let body = serde_json::json!({
+            "message": message,
+            "color": color.unwrap_or("primary"),
+        });
+        self.authenticated_post(&url, token, &body).await?;
+        Ok(())
+    }
+
+    /// Whisper `message` from `from_user_id` to `to_user_id`.
+    ///
+    /// The sender needs a verified phone number, and Twitch may refuse
+    /// whispers to users who have not whispered them before.
+    pub async fn send_whisper(
+        &self,
+        token: &Token,
+        from_user_id: &str,
+        to_user_id: &str,
+        message: &str,
+    ) -> Result<(), TwitchError> {
+        let url =
+            format!("{HELIX_BASE}/whispers?from_user_id={from_user_id}&to_user_id={to_user_id}");
+        self.authenticated_post(&url, token, &serde_json::json!({ "message": message }))
+            .await?;
+        Ok(())
+    }
+
+    /// Send a shoutout from `from_broadcaster_id` to `to_broadcaster_id`.
+    ///
+    /// Twitch rate-limits shoutouts (2 minutes globally, 1 hour per target).
+    pub async fn send_shoutout(
+        &self,
+        token: &Token,
+        from_broadcaster_id: &str,
+        to_broadcaster_id: &str,
+        moderator_id: &str,
+    ) -> Result<(), TwitchError> {
+        let url = format!(
+            "{HELIX_BASE}/chat/shoutouts?from_broadcaster_id={from_broadcaster_id}&to_broadcaster_id={to_broadcaster_id}&moderator_id={moderator_id}"
+        );
+        self.authenticated_post(&url, token, &serde_json::json!({}))
+            .await?;
+        Ok(())
+    }
+
+    /// The ad schedule of `broadcaster_id`'s channel.
+    pub async fn get_ad_schedule(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+    ) -> Result<AdSchedule, TwitchError> {
+        let url = format!("{HELIX_BASE}/channels/ads?broadcaster_id={broadcaster_id}");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixResponse<AdSchedule> = serde_json::from_str(&body)?;
+        Ok(resp.data.into_iter().next().unwrap_or_default())
+    }
+
+    /// Push the next scheduled ad break back by 5 minutes, using one
+    /// snooze. Only the snooze fields and `next_ad_at` are filled in.
+    pub async fn snooze_next_ad(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+    ) -> Result<AdSchedule, TwitchError> {
+        let url =
+            format!("{HELIX_BASE}/channels/ads/schedule/snooze?broadcaster_id={broadcaster_id}");
+        let body = self
+            .authenticated_post(&url, token, &serde_json::json!({}))
+            .await?;
+        let resp: HelixResponse<AdSchedule> = serde_json::from_str(&body)?;
+        Ok(resp.data.into_iter().next().unwrap_or_default())
+    }
+
+    /// Clip the last seconds of `broadcaster_id`'s live stream. With
+    /// `has_delay` the clip accounts for the stream delay.
+    pub async fn create_clip(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        has_delay: bool,
+    ) -> Result<CreatedClip, TwitchError> {
+        let url =
+            format!("{HELIX_BASE}/clips?broadcaster_id={broadcaster_id}&has_delay={has_delay}");
+        let body = self
+            .authenticated_post(&url, token, &serde_json::json!({}))
+            .await?;
+        let resp: HelixResponse<CreatedClip> = serde_json::from_str(&body)?;
+        resp.data
+            .into_iter()
+            .next()
+            .ok_or_else(|| TwitchError::ApiError {
+                status: 500,
+                message: "Empty clip response".into(),
+            })
+    }
+
+    /// Look up clip `id`; `None` while it is still being processed.
+    pub async fn get_clip(&self, token: &Token, id: &str) -> Result<Option<Clip>, TwitchError> {
+        let url = format!("{HELIX_BASE}/clips?id={id}");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixResponse<Clip> = serde_json::from_str(&body)?;
+        Ok(resp.data.into_iter().next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ad_schedule_timestamps() {
+        let body = r#"{"data":[{"next_ad_at":"1700000000","last_ad_at":1699990000,
+            "duration":60,"preroll_free_time":90,"snooze_count":1,
+            "snooze_refresh_at":"2023-11-14T22:13:20Z"}]}"#;
+        let resp: HelixResponse<AdSchedule> = serde_json::from_str(body).unwrap();
+        let schedule = &resp.data[0];
+        assert_eq!(schedule.next_ad_at, Some(1_700_000_000));
+        assert_eq!(schedule.last_ad_at, Some(1_699_990_000));
+        assert_eq!(schedule.snooze_refresh_at, Some(1_700_000_000));
+
+        let offline = r#"{"data":[{"next_ad_at":"","last_ad_at":0,"snooze_count":3}]}"#;
+        let resp: HelixResponse<AdSchedule> = serde_json::from_str(offline).unwrap();
+        assert_eq!(resp.data[0].next_ad_at, None);
+        assert_eq!(resp.data[0].last_ad_at, None);
+        assert_eq!(resp.data[0].snooze_count, 3);
+    }
 }
@@ -0,0 +1,195 @@
+//! Moderation endpoints: Shield Mode and chat settings.
+
+use serde::{Deserialize, Serialize};
+
+use super::{HELIX_BASE, HelixResponse, TwitchApiClient};
+use crate::{Token, TwitchError};
+
+/// Chat settings from GET /helix/chat/settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatSettings {
+    pub follower_mode: bool,
+    /// Minutes a viewer must have followed to chat.
+    #[serde(default)]
+    pub follower_mode_duration: Option<u32>,
+    pub emote_mode: bool,
+    pub subscriber_mode: bool,
+    pub slow_mode: bool,
+    /// Seconds between a viewer's messages.
+    #[serde(default)]
+    pub slow_mode_wait_time: Option<u32>,
+    pub unique_chat_mode: bool,
+}
+
+/// Fields of PATCH /helix/chat/settings; `None` leaves a setting as is.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatSettingsUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follower_mode: Option<bool>,
+    /// Minutes a viewer must have followed to chat (0-129600).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follower_mode_duration: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emote_mode: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscriber_mode: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_mode: Option<bool>,
+    /// Seconds between a viewer's messages (3-120).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_mode_wait_time: Option<u32>,
+}
+
+impl ChatSettingsUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.follower_mode.is_none()
+            && self.follower_mode_duration.is_none()
+            && self.emote_mode.is_none()
+            && self.subscriber_mode.is_none()
+            && self.slow_mode.is_none()
+            && self.slow_mode_wait_time.is_none()
+    }
+}
+
+/// Shield Mode state from GET /helix/moderation/shield_mode.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShieldModeStatus {
+    pub is_active: bool,
+    /// Moderator who last turned it on; empty if never.
+    #[serde(default)]
+    pub moderator_login: String,
+    #[serde(default)]
+    pub moderator_name: String,
+    /// RFC 3339; empty if never activated.
+    #[serde(default)]
+    pub last_activated_at: String,
+}
+
+fn moderation_query(broadcaster_id: &str, moderator_id: &str) -> String {
+    format!("broadcaster_id={broadcaster_id}&moderator_id={moderator_id}")
+}
+
+impl TwitchApiClient {
+    pub async fn get_shield_mode(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        moderator_id: &str,
+    ) -> Result<ShieldModeStatus, TwitchError> {
+        let query = moderation_query(broadcaster_id, moderator_id);
+        let url = format!("{HELIX_BASE}/moderation/shield_mode?{query}");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixResponse<ShieldModeStatus> = serde_json::from_str(&body)?;
+        Ok(resp.data.into_iter().next().unwrap_or_default())
+    }
+
+    /// Turn Shield Mode on or off in `broadcaster_id`'s channel.
+    pub async fn update_shield_mode(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        moderator_id: &str,
+        is_active: bool,
+    ) -> Result<(), TwitchError> {
+        let query = moderation_query(broadcaster_id, moderator_id);
+        let url = format!("{HELIX_BASE}/moderation/shield_mode?{query}");
+        self.authenticated_put(&url, token, &serde_json::json!({ "is_active": is_active }))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_chat_settings(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        moderator_id: &str,
+    ) -> Result<ChatSettings, TwitchError> {
+        let query = moderation_query(broadcaster_id, moderator_id);
+        let url = format!("{HELIX_BASE}/chat/settings?{query}");
+        let body = self.authenticated_get(&url, token).await?;
+        let resp: HelixResponse<ChatSettings> = serde_json::from_str(&body)?;
+        resp.data
+            .into_iter()
+            .next()
+            .ok_or_else(|| TwitchError::ApiError {
+                status: 404,
+                message: "Empty chat settings response".into(),
+            })
+    }
+
+    /// Change chat settings (follower-only, slow mode, ...) of
+    /// `broadcaster_id`'s channel.
+    pub async fn update_chat_settings(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        moderator_id: &str,
+        settings: &ChatSettingsUpdate,
+    ) -> Result<(), TwitchError> {
+        let query = moderation_query(broadcaster_id, moderator_id);
+        let url = format!("{HELIX_BASE}/chat/settings?{query}");
+        self.authenticated_patch(&url, token, settings).await?;
+        Ok(())
+    }
+
+    /// Ban `user_id` from `broadcaster_id`'s chat, or time them out when
+    /// `duration_secs` (1-1209600) is given.
+    pub async fn ban_user(
+        &self,
+        token: &Token,
+        broadcaster_id: &str,
+        moderator_id: &str,
+        user_id: &str,
+        duration_secs: Option<u32>,
+        reason: &str,
+    ) -> Result<(), TwitchError> {
+        let query = moderation_query(broadcaster_id, moderator_id);
+        let url = format!("{HELIX_BASE}/moderation/bans?{query}");
+        self.authenticated_post(&url, token, &ban_body(user_id, duration_secs, reason))
+            .await?;
+        Ok(())
+    }
+}
+
+fn ban_body(user_id: &str, duration_secs: Option<u32>, reason: &str) -> serde_json::Value {
+    let mut data = serde_json::json!({ "user_id": user_id, "reason": reason });
+    if let Some(duration) = duration_secs {
+        data["duration"] = duration.into();
+    }
+    serde_json::json!({ "data": data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_settings_update_body() {
+        let update = ChatSettingsUpdate {
+            subscriber_mode: Some(true),
+            ..Default::default()
+        };
+        assert!(!update.is_empty());
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            serde_json::json!({ "subscriber_mode": true })
+        );
+        assert!(ChatSettingsUpdate::default().is_empty());
+
+        let body = r#"{"data":[{"broadcaster_id":"1","slow_mode":true,"slow_mode_wait_time":30,
+            "follower_mode":false,"follower_mode_duration":null,"subscriber_mode":false,
+            "emote_mode":false,"unique_chat_mode":false}]}"#;
+        let resp: HelixResponse<ChatSettings> = serde_json::from_str(body).unwrap();
+        assert_eq!(resp.data[0].slow_mode_wait_time, Some(30));
+        assert_eq!(resp.data[0].follower_mode_duration, None);
+    }
+
+    #[test]
+    fn test_ban_body() {
+        assert_eq!(
+            ban_body("42", Some(600), "spam"),
+            serde_json::json!({ "data": { "user_id": "42", "reason": "spam", "duration": 600 } })
+        );
+        assert!(ban_body("42", None, "")["data"].get("duration").is_none());
+    }
+}
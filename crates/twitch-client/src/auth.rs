@@ -25,6 +25,51 @@ struct ErrorResponse {
     error_description: Option<String>,
 }
 
+/// Result of validating an access token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenValidation {
+    pub client_id: String,
+    #[serde(default)]
+    pub login: String,
+    #[serde(default)]
+    pub user_id: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub expires_in: i64,
+}
+
+impl TokenValidation {
+    /// Required scopes (see [`SCOPES`]) the token was not granted.
+    pub fn missing_scopes(&self) -> Vec<&'static str> {
+        crate::missing_scopes(self.scopes.iter().map(String::as_str))
+    }
+}
+
+/// Check an access token against the OAuth validate endpoint.
+///
+/// An invalid or expired token yields `TwitchError::AuthRequired`.
+pub async fn validate_token(
+    http: &reqwest::Client,
+    access_token: &str,
+) -> Result<TokenValidation, TwitchError> {
+    let resp = http
+        .get("https://id.twitch.tv/oauth2/validate")
+        .header("Authorization", format!("OAuth {access_token}"))
+        .send()
+        .await?;
+    let status = resp.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(TwitchError::AuthRequired);
+    }
+    if !status.is_success() {
+        return Err(TwitchError::ApiError {
+            status: status.as_u16(),
+            message: resp.text().await.unwrap_or_default(),
+        });
+    }
+    Ok(resp.json().await?)
+}
+
 /// Manages Twitch OAuth authentication.
 ///
 /// The caller is responsible for persisting tokens via the provided callbacks.
@@ -39,11 +84,27 @@ pub struct TwitchAuth {
 impl TwitchAuth {
     /// Create a new auth manager.
     pub fn new(client_id: String, client_secret: String, redirect_uri: String) -> Self {
+        Self::with_http(
+            client_id,
+            client_secret,
+            redirect_uri,
+            reqwest::Client::new(),
+        )
+    }
+
+    /// Create an auth manager that sends requests with `http` (e.g. a
+    /// shared, pooled client).
+    pub fn with_http(
+        client_id: String,
+        client_secret: String,
+        redirect_uri: String,
+        http: reqwest::Client,
+    ) -> Self {
         Self {
             client_id,
             client_secret,
             redirect_uri,
-            http: reqwest::Client::new(),
+            http,
         }
     }
 
@@ -188,6 +249,30 @@ mod tests {
         assert!(url.contains("user%3Aread%3Achat"));
     }
 
+    #[test]
+    fn test_missing_scopes() {
+        let validation = TokenValidation {
+            client_id: "id".into(),
+            login: String::new(),
+            user_id: String::new(),
+            scopes: SCOPES
+                .iter()
+                .filter(|s| **s != "bits:read")
+                .map(|s| s.to_string())
+                .collect(),
+            expires_in: 3600,
+        };
+        assert_eq!(validation.missing_scopes(), vec!["bits:read"]);
+
+        let token = Token {
+            access_token: String::new(),
+            refresh_token: String::new(),
+            scope: validation.scopes.join(" "),
+            expires_at: 0,
+        };
+        assert_eq!(token.missing_scopes(), vec!["bits:read"]);
+    }
+
     #[test]
     fn test_get_or_refresh_still_valid() {
         let auth = TwitchAuth::new("id".into(), "secret".into(), "http://localhost".into());
@@ -0,0 +1,368 @@
+//! Third-party emotes from 7TV, BetterTTV and FrankerFaceZ.
+//!
+//! The providers' public APIs need no authentication. Their emotes are
+//! returned as [`Emote`]s whose ID is prefixed with the provider (`7tv:…`,
+//! `bttv:…`, `ffz:…`) so they never collide with Twitch emote IDs, and
+//! whose `source` names the provider. Viewers without the matching browser
+//! extension see these emotes as plain words, so chat fragments have to be
+//! resolved by name.
+
+use serde_json::Value;
+
+use crate::TwitchError;
+use crate::emotes::{Emote, EmoteImages};
+
+const SEVENTV_BASE: &str = "https://7tv.io/v3";
+const BTTV_BASE: &str = "https://api.betterttv.net/3/cached";
+const BTTV_CDN: &str = "https://cdn.betterttv.net/emote";
+const FFZ_BASE: &str = "https://api.frankerfacez.com/v1";
+
+/// A third-party emote provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    SevenTv,
+    BetterTtv,
+    FrankerFaceZ,
+}
+
+impl Provider {
+    pub const ALL: [Provider; 3] = [Self::SevenTv, Self::BetterTtv, Self::FrankerFaceZ];
+
+    /// Short name used as emote ID prefix and `source` (`7tv`, `bttv`,
+    /// `ffz`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::SevenTv => "7tv",
+            Self::BetterTtv => "bttv",
+            Self::FrankerFaceZ => "ffz",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.as_str().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Display name (`7TV`, `BetterTTV`, `FrankerFaceZ`).
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::SevenTv => "7TV",
+            Self::BetterTtv => "BetterTTV",
+            Self::FrankerFaceZ => "FrankerFaceZ",
+        }
+    }
+
+    fn emote(self, id: &str, name: &str, urls: [String; 3], animated: bool) -> Emote {
+        let [url_1x, url_2x, url_4x] = urls;
+        Emote {
+            id: format!("{}:{id}", self.as_str()),
+            name: name.to_string(),
+            images: EmoteImages {
+                url_1x,
+                url_2x,
+                url_4x,
+            },
+            format: vec![if animated { "animated" } else { "static" }.to_string()],
+            scale: vec!["1.0".into(), "2.0".into(), "4.0".into()],
+            theme_mode: Vec::new(),
+            emote_type: String::new(),
+            tier: String::new(),
+            emote_set_id: String::new(),
+            source: self.as_str().to_string(),
+        }
+    }
+}
+
+fn str_of<'a>(value: &'a Value, key: &str) -> &'a str {
+    value[key].as_str().unwrap_or_default()
+}
+
+/// IDs are strings on 7TV and BTTV but numbers on FFZ.
+fn id_of(value: &Value) -> String {
+    match &value["id"] {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// `//cdn…` → `https://cdn…`.
+fn absolute(url: &str) -> String {
+    match url.strip_prefix("//") {
+        Some(rest) => format!("https://{rest}"),
+        None => url.to_string(),
+    }
+}
+
+/// Emotes of a 7TV emote set (`emotes` array). The set's alias of an emote
+/// is its `name`; GIF and PNG files are preferred over WebP so printing
+/// can decode them.
+pub fn parse_7tv_emotes(set: &Value) -> Vec<Emote> {
+    let mut out = Vec::new();
+    for item in set["emotes"].as_array().into_iter().flatten() {
+        let data = &item["data"];
+        let id = id_of(item);
+        let name = str_of(item, "name");
+        let host = absolute(str_of(&data["host"], "url"));
+        if id.is_empty() || name.is_empty() || host.is_empty() {
+            continue;
+        }
+        let files: Vec<&str> = data["host"]["files"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|f| f["name"].as_str())
+            .collect();
+        let url = |scale: u8| {
+            let file = ["gif", "png", "webp"]
+                .iter()
+                .map(|ext| format!("{scale}x.{ext}"))
+                .find(|name| files.contains(&name.as_str()))
+                .unwrap_or_else(|| format!("{scale}x.webp"));
+            format!("{host}/{file}")
+        };
+        let animated = data["animated"].as_bool().unwrap_or(false);
+        out.push(Provider::SevenTv.emote(&id, name, [url(1), url(2), url(4)], animated));
+    }
+    out
+}
+
+/// Emotes of a BTTV emote list.
+pub fn parse_bttv_emotes(list: &Value) -> Vec<Emote> {
+    let mut out = Vec::new();
+    for item in list.as_array().into_iter().flatten() {
+        let id = id_of(item);
+        let name = str_of(item, "code");
+        if id.is_empty() || name.is_empty() {
+            continue;
+        }
+        let ext = match str_of(item, "imageType") {
+            "" => String::new(),
+            t => format!(".{t}"),
+        };
+        let url = |scale: u8| format!("{BTTV_CDN}/{id}/{scale}x{ext}");
+        let animated = item["animated"].as_bool().unwrap_or(false);
+        out.push(Provider::BetterTtv.emote(&id, name, [url(1), url(2), url(3)], animated));
+    }
+    out
+}
+
+/// Emotes of the FFZ `sets` map, limited to `set_ids` when given. Missing
+/// scales fall back to the nearest smaller one.
+pub fn parse_ffz_sets(sets: &Value, set_ids: Option<&[String]>) -> Vec<Emote> {
+    let mut out = Vec::new();
+    let Some(sets) = sets.as_object() else {
+        return out;
+    };
+    for (set_id, set) in sets {
+        if set_ids.is_some_and(|ids| !ids.contains(set_id)) {
+            continue;
+        }
+        for item in set["emoticons"].as_array().into_iter().flatten() {
+            let id = id_of(item);
+            let name = str_of(item, "name");
+            let animated = item["animated"].is_object();
+            let urls = if animated {
+                &item["animated"]
+            } else {
+                &item["urls"]
+            };
+            let Some(base) = urls["1"].as_str() else {
+                continue;
+            };
+            if id.is_empty() || name.is_empty() {
+                continue;
+            }
+            let x2 = urls["2"].as_str().unwrap_or(base);
+            let x4 = urls["4"].as_str().unwrap_or(x2);
+            out.push(Provider::FrankerFaceZ.emote(
+                &id,
+                name,
+                [absolute(base), absolute(x2), absolute(x4)],
+                animated,
+            ));
+        }
+    }
+    out
+}
+
+/// Client for the providers' public emote APIs.
+#[derive(Clone, Default)]
+pub struct EmoteProviders {
+    http: reqwest::Client,
+}
+
+impl EmoteProviders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a client that sends requests with `http` (e.g. a shared,
+    /// pooled client).
+    pub fn with_http(http: reqwest::Client) -> Self {
+        Self { http }
+    }
+
+    /// Global emotes of `provider`.
+    pub async fn get_global_emotes(&self, provider: Provider) -> Result<Vec<Emote>, TwitchError> {
+        let emotes = match provider {
+            Provider::SevenTv => self
+                .fetch(&format!("{SEVENTV_BASE}/emote-sets/global"))
+                .await?
+                .map(|set| parse_7tv_emotes(&set)),
+            Provider::BetterTtv => self
+                .fetch(&format!("{BTTV_BASE}/emotes/global"))
+                .await?
+                .map(|list| parse_bttv_emotes(&list)),
+            Provider::FrankerFaceZ => {
+                self.fetch(&format!("{FFZ_BASE}/set/global"))
+                    .await?
+                    .map(|body| {
+                        let defaults: Option<Vec<String>> =
+                            body["default_sets"].as_array().map(|ids| {
+                                ids.iter()
+                                    .map(|id| match id {
+                                        Value::String(s) => s.clone(),
+                                        other => other.to_string(),
+                                    })
+                                    .collect()
+                            });
+                        parse_ffz_sets(&body["sets"], defaults.as_deref())
+                    })
+            }
+        }
+        .unwrap_or_default();
+        tracing::debug!(
+            provider = provider.as_str(),
+            count = emotes.len(),
+            "Fetched third-party global emotes"
+        );
+        Ok(emotes)
+    }
+
+    /// Channel emotes of the Twitch user `user_id` on `provider`; empty when
+    /// the channel has no account there.
+    pub async fn get_channel_emotes(
+        &self,
+        provider: Provider,
+        user_id: &str,
+    ) -> Result<Vec<Emote>, TwitchError> {
+        let emotes = match provider {
+            Provider::SevenTv => self
+                .fetch(&format!("{SEVENTV_BASE}/users/twitch/{user_id}"))
+                .await?
+                .map(|user| parse_7tv_emotes(&user["emote_set"])),
+            Provider::BetterTtv => self
+                .fetch(&format!("{BTTV_BASE}/users/twitch/{user_id}"))
+                .await?
+                .map(|user| {
+                    let mut emotes = parse_bttv_emotes(&user["channelEmotes"]);
+                    emotes.extend(parse_bttv_emotes(&user["sharedEmotes"]));
+                    emotes
+                }),
+            Provider::FrankerFaceZ => self
+                .fetch(&format!("{FFZ_BASE}/room/id/{user_id}"))
+                .await?
+                .map(|room| parse_ffz_sets(&room["sets"], None)),
+        }
+        .unwrap_or_default();
+        tracing::debug!(
+            provider = provider.as_str(),
+            user_id,
+            count = emotes.len(),
+            "Fetched third-party channel emotes"
+        );
+        Ok(emotes)
+    }
+
+    /// GET a JSON document; `None` on 404.
+    async fn fetch(&self, url: &str) -> Result<Option<Value>, TwitchError> {
+        let resp = self.http.get(url).send().await?;
+        let status = resp.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body = resp.text().await?;
+        if !status.is_success() {
+            return Err(TwitchError::ApiError {
+                status: status.as_u16(),
+                message: body,
+            });
+        }
+        Ok(Some(serde_json::from_str(&body)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_provider_names() {
+        assert_eq!(Provider::parse(" BTTV "), Some(Provider::BetterTtv));
+        assert_eq!(Provider::parse("twitch"), None);
+        assert_eq!(Provider::FrankerFaceZ.label(), "FrankerFaceZ");
+    }
+
+    #[test]
+    fn test_parse_7tv() {
+        let emotes = parse_7tv_emotes(&json!({
+            "emotes": [
+                {
+                    "id": "01A",
+                    "name": "catJAM",
+                    "data": {
+                        "animated": true,
+                        "host": {
+                            "url": "//cdn.7tv.app/emote/01A",
+                            "files": [{ "name": "1x.webp" }, { "name": "2x.gif" }]
+                        }
+                    }
+                },
+                { "id": "01B", "name": "broken", "data": {} }
+            ]
+        }));
+        assert_eq!(emotes.len(), 1);
+        assert_eq!(emotes[0].id, "7tv:01A");
+        assert_eq!(emotes[0].source, "7tv");
+        assert!(emotes[0].is_animated());
+        assert_eq!(
+            emotes[0].images.url_1x,
+            "https://cdn.7tv.app/emote/01A/1x.webp"
+        );
+        assert_eq!(
+            emotes[0].images.url_2x,
+            "https://cdn.7tv.app/emote/01A/2x.gif"
+        );
+    }
+
+    #[test]
+    fn test_parse_bttv_and_ffz() {
+        let bttv = parse_bttv_emotes(&json!([
+            { "id": "5e76", "code": "monkaS", "imageType": "png", "animated": false }
+        ]));
+        assert_eq!(bttv[0].id, "bttv:5e76");
+        assert_eq!(bttv[0].name, "monkaS");
+        assert_eq!(
+            bttv[0].images.url_2x,
+            "https://cdn.betterttv.net/emote/5e76/2x.png"
+        );
+
+        let sets = json!({
+            "3": { "emoticons": [
+                { "id": 25927, "name": "CatBag", "urls": { "1": "https://cdn.frankerfacez.com/emote/25927/1" } }
+            ] },
+            "9": { "emoticons": [{ "id": 1, "name": "Other", "urls": { "1": "x" } }] }
+        });
+        let ffz = parse_ffz_sets(&sets, Some(&["3".to_string()]));
+        assert_eq!(ffz.len(), 1);
+        assert_eq!(ffz[0].id, "ffz:25927");
+        assert_eq!(
+            ffz[0].images.url_4x,
+            "https://cdn.frankerfacez.com/emote/25927/1"
+        );
+        assert_eq!(parse_ffz_sets(&sets, None).len(), 2);
+    }
+}
@@ -1,7 +1,8 @@
 //! Twitch emote cache.
 //!
 //! Fetches and caches global and per-channel emotes from the
-//! Twitch Helix API so they can be resolved by emote ID.
+//! Twitch Helix API so they can be resolved by emote ID or listed per
+//! channel.
 
 use std::collections::HashMap;
 
@@ -27,6 +28,31 @@ pub struct Emote {
     pub scale: Vec<String>,
     #[serde(default)]
     pub theme_mode: Vec<String>,
+    /// `subscriptions`, `bitstier`, `follower`, ... (channel emotes only).
+    #[serde(default)]
+    pub emote_type: String,
+    /// Subscription tier (`1000`, `2000`, `3000`) for subscriber emotes.
+    #[serde(default)]
+    pub tier: String,
+    #[serde(default)]
+    pub emote_set_id: String,
+    /// `twitch`, or the third-party provider (see
+    /// [`crate::emote_providers`]).
+    #[serde(default = "twitch_source")]
+    pub source: String,
+}
+
+/// `source` of Twitch emotes.
+pub const SOURCE_TWITCH: &str = "twitch";
+
+fn twitch_source() -> String {
+    SOURCE_TWITCH.to_string()
+}
+
+impl Emote {
+    pub fn is_animated(&self) -> bool {
+        self.format.iter().any(|f| f == "animated")
+    }
 }
 
 /// Image URLs at different scales.
@@ -47,26 +73,63 @@ struct EmoteResponse {
 // Cache
 // ---------------------------------------------------------------------------
 
+/// Owner key of the global emote set in [`EmoteCache`].
+pub const GLOBAL_OWNER: &str = "global";
+
 /// In-memory cache for global and channel emotes.
 ///
-/// Emotes are indexed by emote ID for fast lookup.
+/// Emotes are indexed by emote ID for fast lookup and remembered per owner
+/// (a broadcaster ID or [`GLOBAL_OWNER`]) for listing.
 pub struct EmoteCache {
     client_id: String,
     http: reqwest::Client,
     /// Emote ID -> Emote mapping.
     emotes: HashMap<String, Emote>,
+    /// Owner -> emote IDs in Helix order.
+    owners: HashMap<String, Vec<String>>,
 }
 
 impl EmoteCache {
     /// Create a new empty emote cache.
     pub fn new(client_id: String) -> Self {
+        Self::with_http(client_id, reqwest::Client::new())
+    }
+
+    /// Create an empty emote cache that sends requests with `http` (e.g. a
+    /// shared, pooled client).
+    pub fn with_http(client_id: String, http: reqwest::Client) -> Self {
         Self {
             client_id,
-            http: reqwest::Client::new(),
+            http,
             emotes: HashMap::new(),
+            owners: HashMap::new(),
         }
     }
 
+    /// Replace the emote set of `owner`.
+    pub fn insert_channel(&mut self, owner: &str, emotes: Vec<Emote>) {
+        if let Some(old) = self.owners.remove(owner) {
+            for id in old {
+                self.emotes.remove(&id);
+            }
+        }
+        let ids = emotes.iter().map(|e| e.id.clone()).collect();
+        for emote in emotes {
+            self.emotes.insert(emote.id.clone(), emote);
+        }
+        self.owners.insert(owner.to_string(), ids);
+    }
+
+    /// Emotes of `owner` in the order Helix returned them.
+    pub fn channel_emotes(&self, owner: &str) -> Vec<&Emote> {
+        self.owners
+            .get(owner)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.emotes.get(id))
+            .collect()
+    }
+
     /// Look up an emote by ID.
     pub fn get(&self, emote_id: &str) -> Option<&Emote> {
         self.emotes.get(emote_id)
@@ -116,15 +179,12 @@ impl EmoteCache {
         token: &Token,
         broadcaster_id: &str,
     ) -> Result<(), TwitchError> {
-        let mut new_emotes = HashMap::new();
+        self.emotes.clear();
+        self.owners.clear();
 
         // Fetch global emotes
         match self.get_global_emotes(token).await {
-            Ok(emotes) => {
-                for emote in emotes {
-                    new_emotes.insert(emote.id.clone(), emote);
-                }
-            }
+            Ok(emotes) => self.insert_channel(GLOBAL_OWNER, emotes),
             Err(e) => {
                 tracing::warn!(error = %e, "Failed to fetch global emotes");
             }
@@ -132,18 +192,13 @@ impl EmoteCache {
 
         // Fetch channel emotes
         match self.get_channel_emotes(token, broadcaster_id).await {
-            Ok(emotes) => {
-                for emote in emotes {
-                    new_emotes.insert(emote.id.clone(), emote);
-                }
-            }
+            Ok(emotes) => self.insert_channel(broadcaster_id, emotes),
             Err(e) => {
                 tracing::warn!(error = %e, "Failed to fetch channel emotes");
             }
         }
 
-        let count = new_emotes.len();
-        self.emotes = new_emotes;
+        let count = self.emotes.len();
         tracing::info!(count, "Emote cache refreshed");
         Ok(())
     }
@@ -176,30 +231,70 @@ impl EmoteCache {
 mod tests {
     use super::*;
 
+    fn emote(id: &str, name: &str) -> Emote {
+        Emote {
+            id: id.into(),
+            name: name.into(),
+            images: EmoteImages {
+                url_1x: "https://example.com/1x".into(),
+                url_2x: "https://example.com/2x".into(),
+                url_4x: "https://example.com/4x".into(),
+            },
+            format: vec![],
+            scale: vec![],
+            theme_mode: vec![],
+            emote_type: String::new(),
+            tier: String::new(),
+            emote_set_id: String::new(),
+            source: SOURCE_TWITCH.into(),
+        }
+    }
+
     #[test]
     fn test_emote_cache_lookup() {
         let mut cache = EmoteCache::new("test".into());
         assert!(cache.is_empty());
 
-        cache.emotes.insert(
-            "123".into(),
-            Emote {
-                id: "123".into(),
-                name: "Kappa".into(),
-                images: EmoteImages {
-                    url_1x: "https://example.com/1x".into(),
-                    url_2x: "https://example.com/2x".into(),
-                    url_4x: "https://example.com/4x".into(),
-                },
-                format: vec![],
-                scale: vec![],
-                theme_mode: vec![],
-            },
-        );
+        cache.emotes.insert("123".into(), emote("123", "Kappa"));
 
         assert_eq!(cache.len(), 1);
         let emote = cache.get("123").unwrap();
         assert_eq!(emote.name, "Kappa");
         assert!(cache.get("999").is_none());
     }
+
+    #[test]
+    fn test_channel_sets() {
+        let mut cache = EmoteCache::new("test".into());
+        cache.insert_channel(GLOBAL_OWNER, vec![emote("1", "Kappa")]);
+        cache.insert_channel("42", vec![emote("3", "chanHype"), emote("2", "chanWave")]);
+        let names: Vec<_> = cache
+            .channel_emotes("42")
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, ["chanHype", "chanWave"]);
+
+        cache.insert_channel("42", vec![emote("4", "chanNew")]);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("3").is_none());
+        assert!(cache.channel_emotes("7").is_empty());
+    }
+
+    #[test]
+    fn test_emote_deserialize() {
+        let emote: Emote = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "name": "chanHype",
+            "images": { "url_1x": "a", "url_2x": "b", "url_4x": "c" },
+            "format": ["static", "animated"],
+            "tier": "1000",
+            "emote_type": "subscriptions",
+            "emote_set_id": "99"
+        }))
+        .unwrap();
+        assert!(emote.is_animated());
+        assert_eq!(emote.tier, "1000");
+        assert_eq!(emote.source, SOURCE_TWITCH);
+    }
 }
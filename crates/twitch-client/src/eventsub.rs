@@ -3,12 +3,17 @@
 //! Connects to wss://eventsub.wss.twitch.tv/ws, handles welcome/keepalive/
 //! notification messages, and manages automatic reconnection with
 //! exponential backoff.
+//!
+//! An [`EventSubMonitor`] tracks the state of each subscription (enabled,
+//! failed, revoked, last event) and lets the caller re-subscribe without
+//! restarting the connection.
 
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
+use tokio::sync::{Notify, mpsc};
 use tokio_tungstenite::connect_async;
 
 use crate::TwitchError;
@@ -30,6 +35,21 @@ pub const EVENT_CHAT_MESSAGE: &str = "channel.chat.message";
 pub const EVENT_SUBSCRIPTION_GIFT: &str = "channel.subscription.gift";
 pub const EVENT_SUBSCRIPTION_MESSAGE: &str = "channel.subscription.message";
 pub const EVENT_SHOUTOUT_RECEIVE: &str = "channel.shoutout.receive";
+pub const EVENT_AD_BREAK_BEGIN: &str = "channel.ad_break.begin";
+pub const EVENT_POLL_BEGIN: &str = "channel.poll.begin";
+pub const EVENT_POLL_PROGRESS: &str = "channel.poll.progress";
+pub const EVENT_POLL_END: &str = "channel.poll.end";
+pub const EVENT_PREDICTION_BEGIN: &str = "channel.prediction.begin";
+pub const EVENT_PREDICTION_PROGRESS: &str = "channel.prediction.progress";
+pub const EVENT_PREDICTION_LOCK: &str = "channel.prediction.lock";
+pub const EVENT_PREDICTION_END: &str = "channel.prediction.end";
+pub const EVENT_HYPE_TRAIN_BEGIN: &str = "channel.hype_train.begin";
+pub const EVENT_HYPE_TRAIN_PROGRESS: &str = "channel.hype_train.progress";
+pub const EVENT_HYPE_TRAIN_END: &str = "channel.hype_train.end";
+pub const EVENT_CHANNEL_MODERATE: &str = "channel.moderate";
+/// Raids *from* the broadcaster. Local name for a second `channel.raid`
+/// subscription; notifications for it are reported under this type.
+pub const EVENT_CHANNEL_RAID_OUTGOING: &str = "channel.raid.outgoing";
 
 #[derive(Debug, Deserialize)]
 struct WsMessage {
@@ -78,20 +98,229 @@ pub struct EventSubEvent {
     pub payload: serde_json::Value,
 }
 
+/// State of one EventSub subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionState {
+    /// Not subscribed yet (connecting, or waiting to re-subscribe).
+    Pending,
+    Enabled,
+    /// The subscribe request was rejected.
+    Failed,
+    /// Twitch revoked the subscription (e.g. a scope was removed).
+    Revoked,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubscriptionStatus {
+    /// Local event type (see [`EVENT_CHANNEL_RAID_OUTGOING`]).
+    pub event_type: String,
+    pub state: SubscriptionState,
+    pub subscription_id: Option<String>,
+    /// Subscribe error or revocation reason.
+    pub error: Option<String>,
+    /// Unix seconds of the last notification; kept across reconnects.
+    pub last_event_at: Option<i64>,
+    pub event_count: u64,
+    /// Unix seconds of the last state change.
+    pub updated_at: i64,
+}
+
+/// Snapshot returned by [`EventSubMonitor::status`].
+#[derive(Debug, Clone, Serialize)]
+pub struct EventSubStatus {
+    pub connected: bool,
+    pub session_id: Option<String>,
+    pub subscriptions: Vec<SubscriptionStatus>,
+}
+
+#[derive(Debug, Default)]
+struct MonitorState {
+    session_id: Option<String>,
+    subscriptions: Vec<SubscriptionStatus>,
+    requested: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct MonitorInner {
+    state: Mutex<MonitorState>,
+    resubscribe: Notify,
+}
+
+/// Shared view of subscription health. Cheap to clone; hand the same
+/// monitor to every connection so history survives reconnects.
+#[derive(Debug, Clone, Default)]
+pub struct EventSubMonitor {
+    inner: Arc<MonitorInner>,
+}
+
+impl EventSubMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MonitorState> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn status(&self) -> EventSubStatus {
+        let state = self.lock();
+        EventSubStatus {
+            connected: state.session_id.is_some(),
+            session_id: state.session_id.clone(),
+            subscriptions: state.subscriptions.clone(),
+        }
+    }
+
+    /// Ask the live connection to subscribe to `event_types` again. An
+    /// empty list means every subscription that is not enabled. Returns the
+    /// types queued.
+    pub fn request_resubscribe(&self, event_types: &[String]) -> Result<Vec<String>, String> {
+        let mut state = self.lock();
+        if state.session_id.is_none() {
+            return Err("EventSub is not connected".into());
+        }
+        let types: Vec<String> = if event_types.is_empty() {
+            state
+                .subscriptions
+                .iter()
+                .filter(|s| s.state != SubscriptionState::Enabled)
+                .map(|s| s.event_type.clone())
+                .collect()
+        } else {
+            for event_type in event_types {
+                if !state
+                    .subscriptions
+                    .iter()
+                    .any(|s| &s.event_type == event_type)
+                {
+                    return Err(format!("Unknown subscription: {event_type}"));
+                }
+            }
+            event_types.to_vec()
+        };
+        for event_type in &types {
+            if !state.requested.contains(event_type) {
+                state.requested.push(event_type.clone());
+            }
+        }
+        drop(state);
+        if !types.is_empty() {
+            self.inner.resubscribe.notify_one();
+        }
+        Ok(types)
+    }
+
+    /// Start tracking `event_types` as pending, keeping event history.
+    fn reset(&self, event_types: &[String]) {
+        let now = now_secs();
+        let mut state = self.lock();
+        let previous = std::mem::take(&mut state.subscriptions);
+        state.subscriptions = event_types
+            .iter()
+            .map(|event_type| {
+                let old = previous.iter().find(|s| &s.event_type == event_type);
+                SubscriptionStatus {
+                    event_type: event_type.clone(),
+                    state: SubscriptionState::Pending,
+                    subscription_id: None,
+                    error: None,
+                    last_event_at: old.and_then(|s| s.last_event_at),
+                    event_count: old.map_or(0, |s| s.event_count),
+                    updated_at: now,
+                }
+            })
+            .collect();
+        state.requested.clear();
+    }
+
+    fn set_session(&self, session_id: Option<String>) {
+        let mut state = self.lock();
+        if session_id.is_none() {
+            state.requested.clear();
+        }
+        state.session_id = session_id;
+    }
+
+    fn update(&self, event_type: &str, f: impl FnOnce(&mut SubscriptionStatus)) {
+        let mut state = self.lock();
+        if let Some(sub) = state
+            .subscriptions
+            .iter_mut()
+            .find(|s| s.event_type == event_type)
+        {
+            f(sub);
+        }
+    }
+
+    fn record_subscribed(&self, event_type: &str, result: Result<Option<String>, String>) {
+        let now = now_secs();
+        self.update(event_type, |sub| {
+            match result {
+                Ok(id) => {
+                    sub.state = SubscriptionState::Enabled;
+                    sub.subscription_id = id;
+                    sub.error = None;
+                }
+                Err(error) => {
+                    sub.state = SubscriptionState::Failed;
+                    sub.subscription_id = None;
+                    sub.error = Some(error);
+                }
+            }
+            sub.updated_at = now;
+        });
+    }
+
+    fn record_event(&self, event_type: &str) {
+        let now = now_secs();
+        self.update(event_type, |sub| {
+            sub.last_event_at = Some(now);
+            sub.event_count += 1;
+        });
+    }
+
+    fn record_revoked(&self, event_type: &str, reason: &str) {
+        let now = now_secs();
+        self.update(event_type, |sub| {
+            sub.state = SubscriptionState::Revoked;
+            sub.error = Some(reason.to_string());
+            sub.updated_at = now;
+        });
+    }
+
+    fn take_requested(&self) -> Vec<String> {
+        std::mem::take(&mut self.lock().requested)
+    }
+}
+
+fn now_secs() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
 /// EventSub WebSocket client configuration.
 pub struct EventSubConfig {
     pub client_id: String,
     pub access_token: String,
     pub broadcaster_user_id: String,
     pub subscriptions: Vec<String>,
+    pub monitor: EventSubMonitor,
+    /// Client for subscription requests.
+    pub http: reqwest::Client,
 }
 
 impl EventSubConfig {
-    /// Create a config with all 11 default event subscriptions.
+    /// Create a config with all 24 default event subscriptions whose
+    /// subscription requests are sent with `http` (e.g. a shared, pooled
+    /// client).
     pub fn with_all_events(
         client_id: String,
         access_token: String,
         broadcaster_user_id: String,
+        http: reqwest::Client,
     ) -> Self {
         Self {
             client_id,
@@ -102,6 +331,7 @@ impl EventSubConfig {
                 EVENT_CHANNEL_SUBSCRIBE.into(),
                 EVENT_CHANNEL_CHEER.into(),
                 EVENT_CHANNEL_RAID.into(),
+                EVENT_CHANNEL_RAID_OUTGOING.into(),
                 EVENT_STREAM_ONLINE.into(),
                 EVENT_STREAM_OFFLINE.into(),
                 EVENT_REWARD_REDEMPTION.into(),
@@ -109,9 +339,29 @@ impl EventSubConfig {
                 EVENT_SUBSCRIPTION_GIFT.into(),
                 EVENT_SUBSCRIPTION_MESSAGE.into(),
                 EVENT_SHOUTOUT_RECEIVE.into(),
+                EVENT_AD_BREAK_BEGIN.into(),
+                EVENT_POLL_BEGIN.into(),
+                EVENT_POLL_PROGRESS.into(),
+                EVENT_POLL_END.into(),
+                EVENT_PREDICTION_BEGIN.into(),
+                EVENT_PREDICTION_PROGRESS.into(),
+                EVENT_PREDICTION_LOCK.into(),
+                EVENT_PREDICTION_END.into(),
+                EVENT_HYPE_TRAIN_BEGIN.into(),
+                EVENT_HYPE_TRAIN_PROGRESS.into(),
+                EVENT_HYPE_TRAIN_END.into(),
+                EVENT_CHANNEL_MODERATE.into(),
             ],
+            monitor: EventSubMonitor::default(),
+            http,
         }
     }
+
+    /// Report subscription health to `monitor`.
+    pub fn with_monitor(mut self, monitor: EventSubMonitor) -> Self {
+        self.monitor = monitor;
+        self
+    }
 }
 
 /// EventSub WebSocket client with auto-reconnect.
@@ -126,10 +376,20 @@ impl EventSubClient {
     ) -> Result<(mpsc::Receiver<EventSubEvent>, mpsc::Sender<()>), TwitchError> {
         let (event_tx, event_rx) = mpsc::channel::<EventSubEvent>(256);
         let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
+        config.monitor.reset(&config.subscriptions);
         tokio::spawn(Self::run_loop(config, event_tx, shutdown_rx));
         Ok((event_rx, shutdown_tx))
     }
 
+    /// Open a connection, wait for the welcome message and close again.
+    /// Returns the session id; used to check that EventSub is reachable.
+    pub async fn probe() -> Result<String, TwitchError> {
+        let (mut ws, _) = connect_async(EVENTSUB_URL).await?;
+        let session_id = Self::wait_for_welcome(&mut ws).await;
+        let _ = ws.close(None).await;
+        session_id
+    }
+
     async fn run_loop(
         config: EventSubConfig,
         event_tx: mpsc::Sender<EventSubEvent>,
@@ -141,7 +401,10 @@ impl EventSubClient {
                 tracing::info!("EventSub shutdown requested");
                 return;
             }
-            match Self::connect_once(&config, &event_tx, &mut shutdown_rx).await {
+            let result = Self::connect_once(&config, &event_tx, &mut shutdown_rx).await;
+            config.monitor.set_session(None);
+            config.monitor.reset(&config.subscriptions);
+            match result {
                 Ok(()) => {
                     tracing::info!("EventSub connection closed cleanly");
                     return;
@@ -170,7 +433,8 @@ impl EventSubClient {
         tracing::info!("Connecting to EventSub WebSocket");
         let (mut ws, _) = connect_async(EVENTSUB_URL).await?;
         let session_id = Self::wait_for_welcome(&mut ws).await?;
-        Self::subscribe_events(config, &session_id).await?;
+        config.monitor.set_session(Some(session_id.clone()));
+        Self::subscribe_events(config, &session_id, &config.subscriptions).await?;
 
         let timeout = KEEPALIVE_TIMEOUT * 2;
         loop {
@@ -180,10 +444,15 @@ impl EventSubClient {
                     let _ = ws.close(None).await;
                     return Ok(());
                 }
+                _ = config.monitor.inner.resubscribe.notified() => {
+                    let event_types = config.monitor.take_requested();
+                    tracing::info!(?event_types, "Re-subscribing to EventSub events");
+                    Self::subscribe_events(config, &session_id, &event_types).await?;
+                }
                 result = tokio::time::timeout(timeout, ws.next()) => {
                     match result {
                         Ok(Some(Ok(Msg::Text(text)))) => {
-                            Self::handle_message(&text, event_tx).await?;
+                            Self::handle_message(&text, event_tx, &config.monitor).await?;
                         }
                         Ok(Some(Ok(Msg::Ping(data)))) => {
                             let _ = ws.send(Msg::Pong(data)).await;
@@ -231,6 +500,7 @@ impl EventSubClient {
     async fn handle_message(
         text: &str,
         event_tx: &mpsc::Sender<EventSubEvent>,
+        monitor: &EventSubMonitor,
     ) -> Result<(), TwitchError> {
         let ws_msg: WsMessage = serde_json::from_str(text)?;
         match ws_msg.metadata.message_type.as_str() {
@@ -238,33 +508,27 @@ impl EventSubClient {
                 tracing::trace!("EventSub keepalive received");
             }
             "notification" => {
-                if let Some(sub_type) = ws_msg
-                    .payload
-                    .get("subscription")
-                    .and_then(|s| s.get("type"))
-                    .and_then(|t| t.as_str())
-                {
+                if let Some(subscription) = ws_msg.payload.get("subscription") {
                     let payload = ws_msg
                         .payload
                         .get("event")
                         .cloned()
                         .unwrap_or(serde_json::Value::Null);
                     let event = EventSubEvent {
-                        event_type: sub_type.to_string(),
+                        event_type: Self::local_event_type(subscription),
                         payload,
                     };
                     tracing::debug!(event_type = %event.event_type, "EventSub notification");
+                    monitor.record_event(&event.event_type);
                     let _ = event_tx.send(event).await;
                 }
             }
             "revocation" => {
-                let sub_type = ws_msg
-                    .payload
-                    .get("subscription")
-                    .and_then(|s| s.get("type"))
-                    .and_then(|t| t.as_str())
-                    .unwrap_or("unknown");
-                tracing::warn!(sub_type, "EventSub subscription revoked");
+                let subscription = &ws_msg.payload["subscription"];
+                let sub_type = Self::local_event_type(subscription);
+                let reason = subscription["status"].as_str().unwrap_or("revoked");
+                tracing::warn!(sub_type, reason, "EventSub subscription revoked");
+                monitor.record_revoked(&sub_type, reason);
             }
             other => tracing::debug!(msg_type = other, "Unhandled EventSub message"),
         }
@@ -274,11 +538,12 @@ impl EventSubClient {
     async fn subscribe_events(
         config: &EventSubConfig,
         session_id: &str,
+        event_types: &[String],
     ) -> Result<(), TwitchError> {
-        let http = reqwest::Client::new();
-        for event_type in &config.subscriptions {
+        let http = &config.http;
+        for event_type in event_types {
             let req = SubscribeRequest {
-                event_type: event_type.clone(),
+                event_type: Self::subscription_type(event_type).into(),
                 version: Self::event_version(event_type).into(),
                 condition: Self::build_condition(event_type, &config.broadcaster_user_id),
                 transport: SubscribeTransport {
@@ -293,20 +558,67 @@ impl EventSubClient {
                 .json(&req)
                 .send()
                 .await?;
-            if resp.status().is_success() {
-                tracing::info!(event_type, "Subscribed to EventSub event");
-            } else {
-                let status = resp.status().as_u16();
-                let body = resp.text().await.unwrap_or_default();
-                tracing::error!(event_type, status, body, "Failed to subscribe");
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            let result = Self::subscribe_result(status.as_u16(), &body);
+            match &result {
+                Ok(_) => tracing::info!(event_type, "Subscribed to EventSub event"),
+                Err(_) => {
+                    tracing::error!(
+                        event_type,
+                        status = status.as_u16(),
+                        body,
+                        "Failed to subscribe"
+                    )
+                }
             }
+            config.monitor.record_subscribed(event_type, result);
         }
         Ok(())
     }
 
+    /// Subscription id from a subscribe response, or the error to report.
+    /// `409 Conflict` means the subscription already exists on this session.
+    fn subscribe_result(status: u16, body: &str) -> Result<Option<String>, String> {
+        let json: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+        match status {
+            200..=299 => Ok(json["data"][0]["id"].as_str().map(String::from)),
+            409 => Ok(None),
+            _ => Err(format!(
+                "{status}: {}",
+                json["message"].as_str().unwrap_or(body)
+            )),
+        }
+    }
+
+    /// Event type reported for a notification's `subscription` object.
+    fn local_event_type(subscription: &serde_json::Value) -> String {
+        let sub_type = subscription["type"].as_str().unwrap_or_default();
+        let outgoing = subscription["condition"]["from_broadcaster_user_id"]
+            .as_str()
+            .is_some_and(|id| !id.is_empty());
+        if sub_type == EVENT_CHANNEL_RAID && outgoing {
+            EVENT_CHANNEL_RAID_OUTGOING.to_string()
+        } else {
+            sub_type.to_string()
+        }
+    }
+
+    /// Twitch subscription type for a (possibly local) event type.
+    fn subscription_type(event_type: &str) -> &str {
+        match event_type {
+            EVENT_CHANNEL_RAID_OUTGOING => EVENT_CHANNEL_RAID,
+            other => other,
+        }
+    }
+
     fn event_version(event_type: &str) -> &'static str {
         match event_type {
-            EVENT_CHANNEL_FOLLOW => "2",
+            EVENT_CHANNEL_FOLLOW
+            | EVENT_HYPE_TRAIN_BEGIN
+            | EVENT_HYPE_TRAIN_PROGRESS
+            | EVENT_HYPE_TRAIN_END
+            | EVENT_CHANNEL_MODERATE => "2",
             _ => "1",
         }
     }
@@ -324,7 +636,10 @@ impl EventSubClient {
             EVENT_CHANNEL_RAID => serde_json::json!({
                 "to_broadcaster_user_id": broadcaster_id,
             }),
-            EVENT_SHOUTOUT_RECEIVE => serde_json::json!({
+            EVENT_CHANNEL_RAID_OUTGOING => serde_json::json!({
+                "from_broadcaster_user_id": broadcaster_id,
+            }),
+            EVENT_SHOUTOUT_RECEIVE | EVENT_CHANNEL_MODERATE => serde_json::json!({
                 "broadcaster_user_id": broadcaster_id,
                 "moderator_user_id": broadcaster_id,
             }),
@@ -338,3 +653,89 @@ impl EventSubClient {
         d.min(MAX_BACKOFF)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outgoing_raid_event_type() {
+        let inbound = serde_json::json!({
+            "type": "channel.raid",
+            "condition": { "from_broadcaster_user_id": "", "to_broadcaster_user_id": "1" },
+        });
+        let outbound = serde_json::json!({
+            "type": "channel.raid",
+            "condition": { "from_broadcaster_user_id": "1", "to_broadcaster_user_id": "" },
+        });
+        assert_eq!(
+            EventSubClient::local_event_type(&inbound),
+            EVENT_CHANNEL_RAID
+        );
+        assert_eq!(
+            EventSubClient::local_event_type(&outbound),
+            EVENT_CHANNEL_RAID_OUTGOING
+        );
+        assert_eq!(
+            EventSubClient::subscription_type(EVENT_CHANNEL_RAID_OUTGOING),
+            EVENT_CHANNEL_RAID
+        );
+    }
+
+    #[test]
+    fn test_subscribe_result() {
+        assert_eq!(
+            EventSubClient::subscribe_result(202, r#"{"data":[{"id":"abc"}]}"#),
+            Ok(Some("abc".into()))
+        );
+        assert_eq!(EventSubClient::subscribe_result(409, ""), Ok(None));
+        assert_eq!(
+            EventSubClient::subscribe_result(403, r#"{"message":"missing scope"}"#),
+            Err("403: missing scope".into())
+        );
+    }
+
+    #[test]
+    fn test_monitor_tracks_subscriptions() {
+        let monitor = EventSubMonitor::new();
+        let types = vec![
+            EVENT_CHANNEL_FOLLOW.to_string(),
+            EVENT_CHANNEL_CHEER.to_string(),
+        ];
+        monitor.reset(&types);
+        assert!(monitor.request_resubscribe(&[]).is_err());
+
+        monitor.set_session(Some("session".into()));
+        monitor.record_subscribed(EVENT_CHANNEL_FOLLOW, Ok(Some("id1".into())));
+        monitor.record_subscribed(EVENT_CHANNEL_CHEER, Err("403: missing scope".into()));
+        monitor.record_event(EVENT_CHANNEL_FOLLOW);
+
+        let status = monitor.status();
+        assert!(status.connected);
+        assert_eq!(status.subscriptions[0].state, SubscriptionState::Enabled);
+        assert_eq!(status.subscriptions[0].event_count, 1);
+        assert!(status.subscriptions[0].last_event_at.is_some());
+        assert_eq!(status.subscriptions[1].state, SubscriptionState::Failed);
+
+        monitor.record_revoked(EVENT_CHANNEL_FOLLOW, "authorization_revoked");
+        assert_eq!(
+            monitor.request_resubscribe(&[]).unwrap(),
+            vec![
+                EVENT_CHANNEL_FOLLOW.to_string(),
+                EVENT_CHANNEL_CHEER.to_string()
+            ]
+        );
+        assert!(
+            monitor
+                .request_resubscribe(&["channel.unknown".into()])
+                .is_err()
+        );
+        assert_eq!(monitor.take_requested().len(), 2);
+
+        // A reconnect starts over as pending but keeps the event history.
+        monitor.reset(&types);
+        let follow = &monitor.status().subscriptions[0];
+        assert_eq!(follow.state, SubscriptionState::Pending);
+        assert_eq!(follow.event_count, 1);
+    }
+}
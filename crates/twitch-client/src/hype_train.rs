@@ -0,0 +1,99 @@
+//! Typed payloads of the `channel.hype_train.*` EventSub events.
+//!
+//! Begin and progress notifications carry `progress`, `goal` and
+//! `expires_at`; the end notification carries `ended_at` instead. Fields a
+//! notification does not include are left at their defaults.
+
+use serde::{Deserialize, Serialize};
+
+/// One entry of `top_contributions`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HypeTrainContribution {
+    pub user_id: String,
+    pub user_login: String,
+    pub user_name: String,
+    /// `bits`, `subscription` or `other`.
+    #[serde(rename = "type")]
+    pub contribution_type: String,
+    /// Bits, or subscription points (500 / 1000 / 2500 per tier).
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HypeTrainEvent {
+    pub id: String,
+    pub broadcaster_user_id: String,
+    pub broadcaster_user_login: String,
+    pub broadcaster_user_name: String,
+    pub level: u32,
+    /// Points contributed over the whole train.
+    pub total: u64,
+    /// Points towards the next level.
+    pub progress: u64,
+    /// Points needed for the next level.
+    pub goal: u64,
+    pub top_contributions: Vec<HypeTrainContribution>,
+    /// `regular`, `golden_kappa` or `treasure`.
+    #[serde(rename = "type")]
+    pub train_type: String,
+    pub started_at: String,
+    pub expires_at: Option<String>,
+    pub ended_at: Option<String>,
+}
+
+impl HypeTrainEvent {
+    pub fn from_payload(payload: &serde_json::Value) -> Result<Self, serde_json::Error> {
+        Self::deserialize(payload)
+    }
+
+    /// Progress towards the next level in percent (0–100).
+    pub fn percent(&self) -> u32 {
+        if self.goal == 0 {
+            return 0;
+        }
+        (self.progress.saturating_mul(100) / self.goal).min(100) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress_payload() {
+        let payload = serde_json::json!({
+            "id": "1b0AsbInCHZW2SQFQkCzqN07Ib2",
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User",
+            "level": 2,
+            "total": 700,
+            "progress": 200,
+            "goal": 1000,
+            "top_contributions": [
+                { "user_id": "123", "user_login": "pogchamp", "user_name": "PogChamp",
+                  "type": "bits", "total": 50 }
+            ],
+            "started_at": "2020-07-15T17:16:03.17106713Z",
+            "expires_at": "2020-07-15T17:16:11.17106713Z",
+            "type": "regular",
+        });
+        let event = HypeTrainEvent::from_payload(&payload).unwrap();
+        assert_eq!(event.level, 2);
+        assert_eq!(event.percent(), 20);
+        assert_eq!(event.top_contributions[0].contribution_type, "bits");
+        assert_eq!(event.train_type, "regular");
+        assert!(event.ended_at.is_none());
+
+        let ended = HypeTrainEvent::from_payload(&serde_json::json!({
+            "id": "x",
+            "level": 3,
+            "ended_at": "2020-07-15T17:20:00Z",
+        }))
+        .unwrap();
+        assert_eq!(ended.percent(), 0);
+        assert!(ended.top_contributions.is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+//! Twitch chat over IRC (WebSocket transport).
+//!
+//! Only message parsing and formatting live here; the connection itself is
+//! kept by the application. Lines follow IRCv3 with Twitch's tags, e.g.
+//!
+//! ```text
+//! @badges=moderator/1;color=#1E90FF;display-name=Alice;id=abc;tmi-sent-ts=1700000000000;user-id=42 :alice!alice@alice.tmi.twitch.tv PRIVMSG #bob :hello
+//! ```
+
+use std::collections::HashMap;
+
+/// Twitch IRC over WebSocket (TLS).
+pub const IRC_WS_URL: &str = "wss://irc-ws.chat.twitch.tv:443";
+
+/// Capabilities requested after connecting.
+pub const CAPABILITIES: &str = "twitch.tv/tags twitch.tv/commands";
+
+/// One parsed IRC line.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IrcMessage {
+    pub tags: HashMap<String, String>,
+    /// `nick!user@host` or a server name, without the leading `:`.
+    pub prefix: Option<String>,
+    pub command: String,
+    /// Middle parameters followed by the trailing one.
+    pub params: Vec<String>,
+}
+
+/// Undo IRCv3 tag value escaping (`\s`, `\:`, `\\`, `\r`, `\n`).
+fn unescape_tag_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => out.push(' '),
+            Some(':') => out.push(';'),
+            Some('r') => out.push('\r'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+impl IrcMessage {
+    /// Parse one line (without the trailing CRLF). `None` for an empty line
+    /// or one without a command.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut rest = line.trim_end_matches(['\r', '\n']);
+        let mut msg = Self::default();
+
+        if let Some(tagged) = rest.strip_prefix('@') {
+            let (tags, after) = tagged.split_once(' ')?;
+            for tag in tags.split(';') {
+                let (key, value) = tag.split_once('=').unwrap_or((tag, ""));
+                msg.tags.insert(key.to_string(), unescape_tag_value(value));
+            }
+            rest = after.trim_start();
+        }
+        if let Some(prefixed) = rest.strip_prefix(':') {
+            let (prefix, after) = prefixed.split_once(' ')?;
+            msg.prefix = Some(prefix.to_string());
+            rest = after.trim_start();
+        }
+
+        let (command, mut rest) = rest.split_once(' ').unwrap_or((rest, ""));
+        if command.is_empty() {
+            return None;
+        }
+        msg.command = command.to_string();
+        while !rest.is_empty() {
+            if let Some(trailing) = rest.strip_prefix(':') {
+                msg.params.push(trailing.to_string());
+                break;
+            }
+            let (param, after) = rest.split_once(' ').unwrap_or((rest, ""));
+            if !param.is_empty() {
+                msg.params.push(param.to_string());
+            }
+            rest = after;
+        }
+        Some(msg)
+    }
+
+    pub fn tag(&self, key: &str) -> &str {
+        self.tags.get(key).map(String::as_str).unwrap_or_default()
+    }
+
+    /// Nick of the sender, from the prefix.
+    pub fn nick(&self) -> &str {
+        let prefix = self.prefix.as_deref().unwrap_or_default();
+        prefix.split('!').next().unwrap_or_default()
+    }
+
+    /// Channel login of a channel command, without the `#`.
+    pub fn channel(&self) -> &str {
+        self.params
+            .first()
+            .and_then(|p| p.strip_prefix('#'))
+            .unwrap_or_default()
+    }
+
+    /// The trailing parameter (message text of `PRIVMSG` and `NOTICE`).
+    pub fn text(&self) -> &str {
+        self.params.last().map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// Channel login as used in IRC: trimmed, lowercase, without `#`.
+pub fn normalize_channel(channel: &str) -> String {
+    channel.trim().trim_start_matches('#').to_lowercase()
+}
+
+/// `PRIVMSG` line sending `text` to `channel`. Line breaks become spaces
+/// since they would end the command.
+pub fn privmsg(channel: &str, text: &str) -> String {
+    let text: String = text
+        .chars()
+        .map(|c| if c == '\r' || c == '\n' { ' ' } else { c })
+        .collect();
+    format!("PRIVMSG #{} :{}", normalize_channel(channel), text.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_privmsg() {
+        let line = "@badges=moderator/1;color=#1E90FF;display-name=Alice\\sA;id=abc;user-id=42 \
+                    :alice!alice@alice.tmi.twitch.tv PRIVMSG #bob :hello : world\r\n";
+        let msg = IrcMessage::parse(line).unwrap();
+        assert_eq!(msg.command, "PRIVMSG");
+        assert_eq!(msg.nick(), "alice");
+        assert_eq!(msg.channel(), "bob");
+        assert_eq!(msg.text(), "hello : world");
+        assert_eq!(msg.tag("display-name"), "Alice A");
+        assert_eq!(msg.tag("user-id"), "42");
+        assert_eq!(msg.tag("missing"), "");
+    }
+
+    #[test]
+    fn test_parse_other_lines() {
+        let ping = IrcMessage::parse("PING :tmi.twitch.tv").unwrap();
+        assert_eq!(ping.command, "PING");
+        assert_eq!(ping.text(), "tmi.twitch.tv");
+        assert!(ping.prefix.is_none());
+
+        let welcome = IrcMessage::parse(":tmi.twitch.tv 001 bob :Welcome, GLHF!").unwrap();
+        assert_eq!(welcome.command, "001");
+        assert_eq!(welcome.params, vec!["bob", "Welcome, GLHF!"]);
+
+        assert!(IrcMessage::parse("").is_none());
+    }
+
+    #[test]
+    fn test_privmsg() {
+        assert_eq!(privmsg("#Bob ", "hi\nthere "), "PRIVMSG #bob :hi there");
+    }
+}
@@ -1,12 +1,16 @@
 //! Twitch integration client library.
 //!
 //! Provides OAuth authentication, EventSub WebSocket client,
-//! REST API client, and emote handling.
+//! REST API client, IRC chat parsing and emote handling (including
+//! 7TV, BetterTTV and FrankerFaceZ emotes).
 
 pub mod api;
 pub mod auth;
+pub mod emote_providers;
 pub mod emotes;
 pub mod eventsub;
+pub mod hype_train;
+pub mod irc;
 
 use serde::{Deserialize, Serialize};
 
@@ -21,6 +25,23 @@ pub struct Token {
     pub expires_at: i64,
 }
 
+impl Token {
+    /// Required scopes (see [`SCOPES`]) the token was not granted.
+    pub fn missing_scopes(&self) -> Vec<&'static str> {
+        missing_scopes(self.scope.split_whitespace())
+    }
+}
+
+/// Scopes in [`SCOPES`] that are not in `granted`.
+pub fn missing_scopes<'a>(granted: impl IntoIterator<Item = &'a str>) -> Vec<&'static str> {
+    let granted: Vec<&str> = granted.into_iter().collect();
+    SCOPES
+        .iter()
+        .copied()
+        .filter(|s| !granted.contains(s))
+        .collect()
+}
+
 /// Unified error type for the twitch-client crate.
 #[derive(Debug, thiserror::Error)]
 pub enum TwitchError {
@@ -59,7 +80,28 @@ pub const SCOPES: &[&str] = &[
     "bits:read",
     "chat:read",
     "chat:edit",
+    "user:write:chat",
+    "user:manage:whispers",
+    "clips:edit",
     "moderator:read:followers",
     "channel:manage:redemptions",
     "moderator:manage:shoutouts",
+    "moderator:manage:shield_mode",
+    "moderator:manage:chat_settings",
+    "moderator:manage:announcements",
+    "moderator:manage:banned_users",
+    // channel.moderate
+    "moderator:read:blocked_terms",
+    "moderator:read:banned_users",
+    "moderator:read:chat_messages",
+    "moderator:read:unban_requests",
+    "moderator:read:warnings",
+    "moderator:read:moderators",
+    "moderator:read:vips",
+    "channel:read:ads",
+    "channel:manage:ads",
+    "user:read:follows",
+    "channel:read:polls",
+    "channel:read:predictions",
+    "channel:read:hype_train",
 ];
@@ -4,6 +4,10 @@
 //! NL, PL, PT, RU, SV, TH, TR, UK, VI, ZH
 
 pub mod defaults;
+pub mod matcher;
 pub mod seed;
+pub mod stopwords;
+pub mod transfer;
 
+pub use matcher::WordMatcher;
 pub use seed::{SeedError, seed_default_words};
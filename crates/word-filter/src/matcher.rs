@@ -0,0 +1,328 @@
+//! Compiled matcher over the stored word filter lists.
+//!
+//! Entries match a chat term exactly, as a substring, as a wildcard
+//! (`*` any run of characters, `?` one character) or as a regular
+//! expression over the whole message. Terms are also checked with common
+//! character substitutions undone (`b@dw0rd` → `badword`). Compiled
+//! matchers are cached per database and language set and rebuilt when the
+//! lists' revision changes.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use overlay_db::Database;
+use overlay_db::word_filter::{SEVERITIES, WordFilterWord};
+use regex::{Regex, RegexBuilder};
+
+/// Rank of a severity level; unknown levels rank as medium.
+pub fn severity_rank(severity: &str) -> usize {
+    SEVERITIES.iter().position(|s| *s == severity).unwrap_or(1)
+}
+
+#[derive(Debug, Clone)]
+enum Rule {
+    Exact(String),
+    Substring(String),
+    /// Wildcards are anchored to the whole term.
+    Term(Regex),
+    /// Regular expressions search the whole message.
+    Text(Regex),
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    rule: Rule,
+    severity: usize,
+}
+
+/// A filtered part of a message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    /// Byte range in the checked text.
+    pub start: usize,
+    pub end: usize,
+    pub severity: usize,
+}
+
+/// Blocks terms matching a bad entry unless the term is whitelisted
+/// (the `good` list holds false positives such as "classic").
+#[derive(Debug, Default, Clone)]
+pub struct WordMatcher {
+    bad: Vec<Entry>,
+    good: Vec<String>,
+}
+
+/// Compiled matchers by database ([`Database::id`]) and language set, with
+/// the lists' revision when they were built.
+type Cache = HashMap<(u64, Vec<String>), (i64, Arc<WordMatcher>)>;
+
+static CACHE: LazyLock<Mutex<Cache>> = LazyLock::new(Default::default);
+
+/// Undo common look-alike substitutions.
+fn deobfuscate(term: &str) -> String {
+    term.chars()
+        .map(|c| match c {
+            '@' | '4' => 'a',
+            '3' => 'e',
+            '1' | '!' | '|' => 'i',
+            '0' => 'o',
+            '$' | '5' => 's',
+            '7' | '+' => 't',
+            other => other,
+        })
+        .collect()
+}
+
+fn wildcard_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut re = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            other => re.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    re.push('$');
+    RegexBuilder::new(&re).case_insensitive(true).build()
+}
+
+fn compile(word: &WordFilterWord) -> Result<Rule, regex::Error> {
+    let text = word.word.to_lowercase();
+    Ok(match word.match_type.as_str() {
+        "exact" => Rule::Exact(text),
+        "wildcard" => Rule::Term(wildcard_regex(&text)?),
+        "regex" => Rule::Text(
+            RegexBuilder::new(&word.word)
+                .case_insensitive(true)
+                .build()?,
+        ),
+        _ => Rule::Substring(text),
+    })
+}
+
+/// Check that `word` compiles, for validating entries before they are
+/// stored.
+pub fn validate(word: &WordFilterWord) -> Result<(), String> {
+    compile(word).map(|_| ()).map_err(|e| e.to_string())
+}
+
+impl Rule {
+    fn matches_term(&self, term: &str) -> bool {
+        match self {
+            Rule::Exact(word) => term == word,
+            Rule::Substring(word) => term.contains(word.as_str()),
+            Rule::Term(re) => re.is_match(term),
+            Rule::Text(_) => false,
+        }
+    }
+}
+
+/// Byte ranges of the whitespace-separated terms of `text`.
+fn terms(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+impl WordMatcher {
+    /// Substring entries of medium severity.
+    pub fn new(bad: Vec<String>, good: Vec<String>) -> Self {
+        let words: Vec<WordFilterWord> = bad
+            .iter()
+            .map(|w| WordFilterWord::new("", w, "bad"))
+            .chain(good.iter().map(|w| WordFilterWord::new("", w, "good")))
+            .collect();
+        Self::from_words(&words)
+    }
+
+    /// Compile `words`; entries that fail to compile are skipped with a
+    /// warning.
+    pub fn from_words(words: &[WordFilterWord]) -> Self {
+        let mut matcher = Self::default();
+        for w in words.iter().filter(|w| !w.word.is_empty()) {
+            if w.word_type == "good" {
+                matcher.good.push(w.word.to_lowercase());
+                continue;
+            }
+            match compile(w) {
+                Ok(rule) => matcher.bad.push(Entry {
+                    rule,
+                    severity: severity_rank(&w.severity),
+                }),
+                Err(e) => tracing::warn!(word = %w.word, "Skipping word filter entry: {e}"),
+            }
+        }
+        matcher
+    }
+
+    /// Load the lists of the given languages from the database.
+    pub fn load(db: &Database, languages: &[&str]) -> Result<Self, overlay_db::DbError> {
+        let mut words = Vec::new();
+        for lang in languages {
+            words.extend(db.get_word_filter_words(lang)?);
+        }
+        Ok(Self::from_words(&words))
+    }
+
+    /// Like [`WordMatcher::load`], reusing the compiled matcher until the
+    /// stored lists change.
+    pub fn cached(db: &Database, languages: &[&str]) -> Result<Arc<Self>, overlay_db::DbError> {
+        let revision = db.get_word_filter_revision()?;
+        let key = (db.id(), languages.iter().map(|l| l.to_string()).collect());
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, matcher)) = cache.get(&key).filter(|(built, _)| *built == revision) {
+            return Ok(matcher.clone());
+        }
+        let matcher = Arc::new(Self::load(db, languages)?);
+        cache.insert(key, (revision, matcher.clone()));
+        Ok(matcher)
+    }
+
+    /// Highest severity of the bad entries matching `term`, if any.
+    fn term_severity(&self, term: &str) -> Option<usize> {
+        let term = term.to_lowercase();
+        if self.good.contains(&term) {
+            return None;
+        }
+        let plain = deobfuscate(&term);
+        self.bad
+            .iter()
+            .filter(|e| e.rule.matches_term(&term) || e.rule.matches_term(&plain))
+            .map(|e| e.severity)
+            .max()
+    }
+
+    /// Whether `term` should be hidden.
+    pub fn is_blocked(&self, term: &str) -> bool {
+        !self.find(term).is_empty()
+    }
+
+    /// Filtered parts of `text`: blocked whitespace-separated terms and
+    /// regular expression matches, sorted by start.
+    pub fn find(&self, text: &str) -> Vec<Match> {
+        let mut found: Vec<Match> = terms(text)
+            .into_iter()
+            .filter_map(|(start, end)| {
+                let severity = self.term_severity(&text[start..end])?;
+                Some(Match {
+                    start,
+                    end,
+                    severity,
+                })
+            })
+            .collect();
+        for entry in &self.bad {
+            let Rule::Text(re) = &entry.rule else {
+                continue;
+            };
+            found.extend(re.find_iter(text).filter(|m| !m.is_empty()).map(|m| Match {
+                start: m.start(),
+                end: m.end(),
+                severity: entry.severity,
+            }));
+        }
+        found.sort_by_key(|m| (m.start, m.end));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str, match_type: &str, severity: &str) -> WordFilterWord {
+        WordFilterWord {
+            match_type: match_type.into(),
+            severity: severity.into(),
+            ..WordFilterWord::new("en", word, "bad")
+        }
+    }
+
+    #[test]
+    fn good_list_overrides_substring_match() {
+        let m = WordMatcher::new(vec!["ass".into()], vec!["classic".into()]);
+        assert!(m.is_blocked("ASS"));
+        assert!(m.is_blocked("badass"));
+        assert!(!m.is_blocked("Classic"));
+        assert!(!m.is_blocked("hello"));
+    }
+
+    #[test]
+    fn match_types_and_obfuscation() {
+        let m = WordMatcher::from_words(&[
+            entry("badword", "substring", "low"),
+            entry("spam", "exact", "medium"),
+            entry("h?ck*", "wildcard", "high"),
+            entry(r"free\s+v-?bucks", "regex", "high"),
+        ]);
+        assert!(m.is_blocked("b@dw0rd!"));
+        assert!(m.is_blocked("SPAM"));
+        assert!(!m.is_blocked("spammer"));
+        assert!(m.is_blocked("H4CKERMAN"));
+        assert!(!m.is_blocked("shack"));
+
+        let text = "FREE  vbucks for spam";
+        let found: Vec<(&str, usize)> = m
+            .find(text)
+            .iter()
+            .map(|f| (&text[f.start..f.end], f.severity))
+            .collect();
+        assert_eq!(found, vec![("FREE  vbucks", 2), ("spam", 1)]);
+        assert_eq!(severity_rank("unknown"), 1);
+        assert!(validate(&entry("(", "regex", "low")).is_err());
+    }
+
+    #[test]
+    fn cache_rebuilds_on_list_changes() {
+        let db = Database::open_in_memory().unwrap();
+        let first = WordMatcher::cached(&db, &["xx"]).unwrap();
+        assert!(Arc::ptr_eq(
+            &first,
+            &WordMatcher::cached(&db, &["xx"]).unwrap()
+        ));
+        assert!(!first.is_blocked("frob"));
+
+        db.add_word_filter_word(&WordFilterWord::new("xx", "frob", "bad"))
+            .unwrap();
+        assert!(
+            WordMatcher::cached(&db, &["xx"])
+                .unwrap()
+                .is_blocked("frob")
+        );
+
+        let entries = db.get_word_filter_words("xx").unwrap();
+        db.delete_word_filter_word(entries[0].id).unwrap();
+        db.add_word_filter_word(&WordFilterWord::new("xx", "grok", "bad"))
+            .unwrap();
+        let matcher = WordMatcher::cached(&db, &["xx"]).unwrap();
+        assert!(!matcher.is_blocked("frob"));
+        assert!(matcher.is_blocked("grok"));
+    }
+
+    #[test]
+    fn cache_is_per_database() {
+        let a = Database::open_in_memory().unwrap();
+        let b = Database::open_in_memory().unwrap();
+        a.add_word_filter_word(&WordFilterWord::new("yy", "frob", "bad"))
+            .unwrap();
+        b.add_word_filter_word(&WordFilterWord::new("yy", "zap", "bad"))
+            .unwrap();
+        assert!(WordMatcher::cached(&a, &["yy"]).unwrap().is_blocked("frob"));
+        let matcher = WordMatcher::cached(&b, &["yy"]).unwrap();
+        assert!(matcher.is_blocked("zap"));
+        assert!(!matcher.is_blocked("frob"));
+    }
+}
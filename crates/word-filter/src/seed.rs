@@ -58,12 +58,7 @@ fn parse_lines(content: &str, language: &str, word_type: &str, out: &mut Vec<Wor
         if w.is_empty() {
             continue;
         }
-        out.push(WordFilterWord {
-            id: 0,
-            language: language.to_string(),
-            word: w.to_string(),
-            word_type: word_type.to_string(),
-        });
+        out.push(WordFilterWord::new(language, w, word_type));
     }
 }
 
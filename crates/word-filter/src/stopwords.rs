@@ -0,0 +1,106 @@
+//! Per-language stopwords for chat statistics (word clouds etc.).
+//!
+//! Lists are short and lowercase; languages without a list have no
+//! stopwords.
+
+const EN: &[&str] = &[
+    "a", "about", "all", "also", "am", "an", "and", "any", "are", "as", "at", "be", "been", "but",
+    "by", "can", "could", "did", "do", "does", "dont", "for", "from", "get", "got", "had", "has",
+    "have", "he", "her", "him", "his", "how", "i", "if", "im", "in", "into", "is", "it", "its",
+    "just", "like", "me", "my", "no", "not", "now", "of", "oh", "ok", "on", "one", "or", "our",
+    "out", "so", "some", "that", "the", "their", "them", "then", "there", "they", "this", "to",
+    "too", "up", "us", "was", "we", "were", "what", "when", "who", "why", "will", "with", "would",
+    "yeah", "yes", "you", "your",
+];
+
+const JA: &[&str] = &[
+    "これ",
+    "それ",
+    "あれ",
+    "どれ",
+    "ここ",
+    "そこ",
+    "あそこ",
+    "こと",
+    "もの",
+    "ため",
+    "よう",
+    "する",
+    "した",
+    "して",
+    "ある",
+    "いる",
+    "なる",
+    "です",
+    "ます",
+    "でした",
+    "ない",
+    "さん",
+    "ちゃん",
+    "くん",
+    "私",
+    "僕",
+    "俺",
+    "自分",
+    "今日",
+];
+
+const ES: &[&str] = &[
+    "a", "al", "como", "con", "de", "del", "el", "en", "es", "esta", "la", "las", "lo", "los",
+    "me", "mi", "muy", "no", "para", "pero", "por", "que", "se", "si", "su", "te", "tu", "un",
+    "una", "y", "ya", "yo",
+];
+
+const FR: &[&str] = &[
+    "au", "avec", "ce", "c'est", "dans", "de", "des", "du", "elle", "en", "est", "et", "il", "je",
+    "la", "le", "les", "mais", "me", "mon", "ne", "on", "pas", "pour", "que", "qui", "se", "sur",
+    "ta", "te", "tu", "un", "une", "vous",
+];
+
+const DE: &[&str] = &[
+    "aber", "auch", "auf", "das", "dass", "dem", "den", "der", "die", "du", "ein", "eine", "es",
+    "für", "hat", "ich", "ist", "ja", "mit", "nicht", "noch", "sich", "sie", "so", "und", "von",
+    "war", "was", "wie", "wir", "zu",
+];
+
+const PT: &[&str] = &[
+    "a", "as", "com", "da", "de", "do", "e", "ele", "em", "eu", "isso", "mais", "mas", "me", "na",
+    "no", "não", "o", "os", "para", "por", "que", "se", "um", "uma", "você",
+];
+
+const IT: &[&str] = &[
+    "a", "che", "con", "da", "di", "e", "è", "il", "in", "io", "la", "le", "lo", "ma", "mi", "non",
+    "per", "si", "sono", "ti", "tu", "un", "una",
+];
+
+/// Stopwords for a language code (`en`, `ja`, ...).
+pub fn stopwords(language: &str) -> &'static [&'static str] {
+    match language {
+        "en" => EN,
+        "ja" => JA,
+        "es" => ES,
+        "fr" => FR,
+        "de" => DE,
+        "pt" => PT,
+        "it" => IT,
+        _ => &[],
+    }
+}
+
+/// Whether `word` (already lowercase) is a stopword in any of `languages`.
+pub fn is_stopword(languages: &[&str], word: &str) -> bool {
+    languages.iter().any(|lang| stopwords(lang).contains(&word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stopwords_by_language() {
+        assert!(is_stopword(&["en"], "the"));
+        assert!(!is_stopword(&["ja"], "the"));
+        assert!(is_stopword(&["en", "ja"], "これ"));
+        assert!(!is_stopword(&["xx"], "the"));
+    }
+}
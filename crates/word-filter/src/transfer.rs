@@ -0,0 +1,194 @@
+//! Word list import/export (JSON and CSV) for sharing curated lists.
+//!
+//! CSV rows are `language,word,type,match_type,severity` with an optional
+//! header row of the same names; the last two columns may be left out.
+//! Fields containing `,`, `"` or line breaks are quoted.
+
+use overlay_db::word_filter::{MATCH_TYPES, SEVERITIES, WordFilterWord};
+
+pub const CSV_HEADER: &str = "language,word,type,match_type,severity";
+
+/// Trim and lowercase the language, trim the word and check the type,
+/// match type, severity and that patterns compile.
+pub fn normalize(word: &WordFilterWord) -> Result<WordFilterWord, String> {
+    let language = word.language.trim().to_lowercase();
+    if language.is_empty() || language.len() > 16 {
+        return Err(format!("invalid language '{}'", word.language));
+    }
+    let text = word.word.trim();
+    if text.is_empty() {
+        return Err("word is empty".into());
+    }
+    if word.word_type != "bad" && word.word_type != "good" {
+        return Err(format!(
+            "type must be 'bad' or 'good', got '{}'",
+            word.word_type
+        ));
+    }
+    if !MATCH_TYPES.contains(&word.match_type.as_str()) {
+        return Err(format!(
+            "match_type must be one of {}, got '{}'",
+            MATCH_TYPES.join(", "),
+            word.match_type
+        ));
+    }
+    if !SEVERITIES.contains(&word.severity.as_str()) {
+        return Err(format!(
+            "severity must be one of {}, got '{}'",
+            SEVERITIES.join(", "),
+            word.severity
+        ));
+    }
+    let word = WordFilterWord {
+        id: 0,
+        language,
+        word: text.to_string(),
+        ..word.clone()
+    };
+    crate::matcher::validate(&word).map_err(|e| format!("invalid pattern '{text}': {e}"))?;
+    Ok(word)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `words` as CSV with a header row.
+pub fn to_csv(words: &[WordFilterWord]) -> String {
+    let mut out = format!("{CSV_HEADER}\n");
+    for w in words {
+        out.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&w.language),
+            csv_field(&w.word),
+            csv_field(&w.word_type),
+            csv_field(&w.match_type),
+            csv_field(&w.severity)
+        ));
+    }
+    out
+}
+
+/// Split CSV text into records of fields, honoring quoted fields.
+fn csv_records(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".into());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Parse CSV rows into normalized words; blank lines and the header row
+/// are skipped. Errors name the 1-based row.
+pub fn from_csv(text: &str) -> Result<Vec<WordFilterWord>, String> {
+    let mut words = Vec::new();
+    for (i, record) in csv_records(text)?.into_iter().enumerate() {
+        if record.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        if i == 0 && record[0].trim().eq_ignore_ascii_case("language") {
+            continue;
+        }
+        let word = match record.as_slice() {
+            [language, word, word_type] => WordFilterWord::new(language, word, word_type.trim()),
+            [language, word, word_type, match_type, severity] => WordFilterWord {
+                match_type: match_type.trim().to_string(),
+                severity: severity.trim().to_string(),
+                ..WordFilterWord::new(language, word, word_type.trim())
+            },
+            _ => return Err(format!("row {}: expected 3 or 5 fields", i + 1)),
+        };
+        words.push(normalize(&word).map_err(|e| format!("row {}: {e}", i + 1))?);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(language: &str, word: &str, word_type: &str) -> WordFilterWord {
+        WordFilterWord::new(language, word, word_type)
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let words = vec![
+            word("en", "badword", "bad"),
+            word("en", "a, \"quoted\" one", "good"),
+            WordFilterWord {
+                match_type: "regex".into(),
+                severity: "high".into(),
+                ..word("en", "b[a@]d", "bad")
+            },
+        ];
+        let csv = to_csv(&words);
+        assert!(csv.starts_with(
+            "language,word,type,match_type,severity\nen,badword,bad,substring,medium\n"
+        ));
+        let parsed = from_csv(&csv).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[1].word, "a, \"quoted\" one");
+        assert_eq!(parsed[2].match_type, "regex");
+        assert_eq!(parsed[2].severity, "high");
+    }
+
+    #[test]
+    fn csv_errors_and_normalization() {
+        let parsed = from_csv(" EN , hello ,bad\r\n\r\nja,x,good").unwrap();
+        assert_eq!(parsed[0].language, "en");
+        assert_eq!(parsed[0].word, "hello");
+        assert_eq!(parsed[1].language, "ja");
+
+        assert_eq!(
+            from_csv("en,ok,bad\nen,oops").unwrap_err(),
+            "row 2: expected 3 or 5 fields"
+        );
+        assert!(
+            from_csv("en,x,ugly")
+                .unwrap_err()
+                .starts_with("row 1: type")
+        );
+        assert!(from_csv("en,\"open,bad").is_err());
+        assert!(
+            from_csv("en,(,bad,regex,low")
+                .unwrap_err()
+                .starts_with("row 1: invalid pattern")
+        );
+    }
+}
@@ -1,6 +1,7 @@
 import { Routes, Route } from 'react-router-dom';
 import { SettingsPage } from './components/SettingsPage';
 import { NotificationWindow } from './components/notification/NotificationWindow';
+import { StreamMonitorWindow } from './components/monitor/StreamMonitorWindow';
 import { Toaster } from 'sonner';
 import { SettingsProvider } from './contexts/SettingsContext';
 import { MicCaptionStatusProvider } from './contexts/MicCaptionStatusContext';
@@ -25,6 +26,9 @@ function App() {
 
       {/* Notification Window Route */}
       <Route path="/notification" element={<NotificationWindow />} />
+
+      {/* Stream Monitor Window Route */}
+      <Route path="/monitor" element={<StreamMonitorWindow />} />
     </Routes>
   );
 }
@@ -2,7 +2,15 @@ import React from 'react';
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
 import { RefreshCw, Wifi, Radio } from "lucide-react";
-import { FeatureStatus, AuthStatus, StreamStatus, TwitchUserInfo, PrinterStatusInfo } from '@/types';
+import { FeatureStatus, FeatureFlags, AuthStatus, StreamStatus, TwitchUserInfo, PrinterStatusInfo } from '@/types';
+
+const FEATURE_LABELS: Record<keyof FeatureFlags, string> = {
+  printer: 'プリンター',
+  music: '音楽',
+  lottery: '抽選',
+  translations: '翻訳',
+  tts: '読み上げ',
+};
 
 interface SystemStatusCardProps {
   featureStatus: FeatureStatus | null;
@@ -264,6 +272,19 @@ export const SystemStatusCard: React.FC<SystemStatusCardProps> = ({
             )}
           </div>
         </div>
+
+        {/* 機能フラグ */}
+        {featureStatus.features && (
+          <div className="mt-4 flex flex-wrap items-center gap-3 text-sm">
+            <span className="font-medium dark:text-gray-200">機能</span>
+            {(Object.keys(FEATURE_LABELS) as (keyof FeatureFlags)[]).map((key) => (
+              <span key={key} className="flex items-center space-x-1">
+                <span className={`w-2 h-2 rounded-full ${featureStatus.features?.[key] ? 'bg-green-500' : 'bg-gray-400'}`} />
+                <span className="text-gray-600 dark:text-gray-300">{FEATURE_LABELS[key]}</span>
+              </span>
+            ))}
+          </div>
+        )}
       </CardContent>
     </Card>
   );
@@ -0,0 +1,169 @@
+import { useEffect, useState } from 'react';
+import { buildApiUrl } from '../../utils/api';
+import { initWebSocket } from '../../utils/websocket';
+
+interface MonitorEvent {
+  id: number;
+  label: string;
+  at: number;
+}
+
+const STATUS_POLL_INTERVAL_MS = 30_000;
+const ACTIVITY_WINDOW_MS = 60_000;
+const ACTIVITY_FULL_SCALE = 30; // 1分あたりのメッセージ数でメーター満タン
+const MAX_EVENTS = 8;
+
+/**
+ * EventSub イベントを一行の表示文字列に変換（チャット等は null）
+ */
+function describeEvent(eventType: string, payload: any): string | null {
+  const user = payload?.user_name || payload?.from_broadcaster_user_name || '';
+  switch (eventType) {
+    case 'channel.follow':
+      return `フォロー: ${user}`;
+    case 'channel.subscribe':
+      return `サブスク: ${user}`;
+    case 'channel.subscription.gift':
+      return `ギフト: ${user} ×${payload?.total ?? 1}`;
+    case 'channel.subscription.message':
+      return `再サブスク: ${user}`;
+    case 'channel.cheer':
+      return `チア: ${user} ${payload?.bits ?? 0} bits`;
+    case 'channel.raid':
+      return `レイド: ${user} (${payload?.viewers ?? 0}人)`;
+    case 'channel.channel_points_custom_reward_redemption.add':
+      return `リワード: ${user} - ${payload?.reward?.title ?? ''}`;
+    default:
+      return null;
+  }
+}
+
+/**
+ * StreamMonitorWindow component
+ * 常に最前面に表示する小型ダッシュボード（視聴者数・最近のイベント・チャット量・プリンター状態）
+ */
+export function StreamMonitorWindow() {
+  const [isLive, setIsLive] = useState(false);
+  const [viewers, setViewers] = useState(0);
+  const [printerConnected, setPrinterConnected] = useState(false);
+  const [events, setEvents] = useState<MonitorEvent[]>([]);
+  const [chatTimestamps, setChatTimestamps] = useState<number[]>([]);
+  const [now, setNow] = useState(Date.now());
+
+  // 視聴者数・プリンター状態は定期的に REST から取得
+  useEffect(() => {
+    const refresh = async () => {
+      try {
+        const [stream, printer] = await Promise.all([
+          fetch(buildApiUrl('/api/stream/status')).then((r) => r.json()),
+          fetch(buildApiUrl('/api/printer/status')).then((r) => r.json()),
+        ]);
+        setIsLive(Boolean(stream.is_live));
+        setViewers(Number(stream.viewer_count) || 0);
+        setPrinterConnected(Boolean(printer.connected));
+      } catch (error) {
+        console.error('[StreamMonitor] Failed to refresh status:', error);
+      }
+    };
+
+    refresh();
+    const timer = setInterval(refresh, STATUS_POLL_INTERVAL_MS);
+    return () => clearInterval(timer);
+  }, []);
+
+  // チャット量メーターの減衰用
+  useEffect(() => {
+    const timer = setInterval(() => setNow(Date.now()), 5_000);
+    return () => clearInterval(timer);
+  }, []);
+
+  useEffect(() => {
+    const unsubscribers: (() => void)[] = [];
+    let nextId = 0;
+
+    const setupWebSocket = async () => {
+      try {
+        const ws = await initWebSocket();
+
+        unsubscribers.push(
+          ws.on('stream_status', (data: any) => {
+            setIsLive(Boolean(data?.is_live));
+            if (!data?.is_live) setViewers(0);
+          }),
+          ws.on('printer_status', (data: any) => setPrinterConnected(Boolean(data?.connected))),
+          ws.on('printer_connected', () => setPrinterConnected(true)),
+          ws.on('printer_disconnected', () => setPrinterConnected(false)),
+          ws.on('eventsub_event', (data: any) => {
+            const eventType = String(data?.event_type ?? '');
+            const at = Date.now();
+
+            if (eventType === 'channel.chat.message') {
+              setChatTimestamps((prev) => [...prev.filter((t) => at - t < ACTIVITY_WINDOW_MS), at]);
+              return;
+            }
+
+            const label = describeEvent(eventType, data?.payload);
+            if (label) {
+              setEvents((prev) => [{ id: nextId++, label, at }, ...prev].slice(0, MAX_EVENTS));
+            }
+          }),
+        );
+      } catch (error) {
+        console.error('[StreamMonitor] Failed to setup WebSocket:', error);
+      }
+    };
+
+    setupWebSocket();
+    return () => unsubscribers.forEach((unsubscribe) => unsubscribe());
+  }, []);
+
+  const chatPerMinute = chatTimestamps.filter((t) => now - t < ACTIVITY_WINDOW_MS).length;
+  const activityPercent = Math.min(100, (chatPerMinute / ACTIVITY_FULL_SCALE) * 100);
+
+  return (
+    <div className="w-full min-h-screen bg-gray-900 text-gray-100 p-3 text-sm space-y-3">
+      <div className="flex items-center justify-between">
+        <span className={`font-bold ${isLive ? 'text-red-400' : 'text-gray-500'}`}>
+          {isLive ? '● LIVE' : 'OFFLINE'}
+        </span>
+        <span className="text-lg font-bold">{viewers.toLocaleString()} 人</span>
+      </div>
+
+      <div>
+        <div className="flex justify-between text-xs text-gray-400 mb-1">
+          <span>チャット</span>
+          <span>{chatPerMinute} / 分</span>
+        </div>
+        <div className="h-2 rounded bg-gray-700 overflow-hidden">
+          <div
+            className="h-full bg-[#9147ff] transition-all duration-500"
+            style={{ width: `${activityPercent}%` }}
+          />
+        </div>
+      </div>
+
+      <div className="flex items-center gap-2 text-xs">
+        <span className={`w-2 h-2 rounded-full ${printerConnected ? 'bg-green-400' : 'bg-gray-500'}`} />
+        <span>プリンター: {printerConnected ? '接続中' : '未接続'}</span>
+      </div>
+
+      <div>
+        <div className="text-xs text-gray-400 mb-1">最近のイベント</div>
+        {events.length === 0 ? (
+          <div className="text-xs text-gray-500">まだイベントはありません</div>
+        ) : (
+          <ul className="space-y-1">
+            {events.map((event) => (
+              <li key={event.id} className="flex justify-between gap-2 text-xs">
+                <span className="truncate">{event.label}</span>
+                <span className="text-gray-500 flex-shrink-0">
+                  {new Date(event.at).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' })}
+                </span>
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -1,5 +1,6 @@
-import { useEffect, useState } from 'react';
-import { ChatNotification } from '../../types/notification';
+import { useEffect, useState, type MouseEvent } from 'react';
+import { ChatNotification, NotificationAction } from '../../types/notification';
+import { buildApiUrl } from '../../utils/api';
 import { initWebSocket } from '../../utils/websocket';
 import { MessageContent } from './MessageContent';
 
@@ -46,6 +47,8 @@ export function NotificationWindow() {
               fragments: data.fragments, // フラグメントデータを含める
               fontSize: data.fontSize || 14, // フォントサイズ（デフォルト14px）
               avatarUrl: data.avatarUrl, // アバターURL
+              interactionMode: data.interactionMode,
+              actions: Array.isArray(data.actions) ? data.actions : [],
             });
             console.log('[NotificationWindow] Notification state updated', {
               username: data.username,
@@ -80,11 +83,38 @@ export function NotificationWindow() {
     };
   }, []);
 
+  const dismissOnClick = notification?.interactionMode === 'dismiss_on_click';
+
+  // クリックで閉じる（バックエンドのキューを次へ進める）
+  const handleDismiss = () => {
+    if (!dismissOnClick) return;
+    fetch(buildApiUrl('/api/notification/dismiss'), { method: 'POST' }).catch((error) => {
+      console.error('[NotificationWindow] Failed to dismiss notification', error);
+    });
+  };
+
+  const handleAction = async (event: MouseEvent, action: NotificationAction) => {
+    event.stopPropagation();
+    try {
+      const res = await fetch(buildApiUrl('/api/notification/action'), {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ action: action.action, targetUserId: action.targetUserId }),
+      });
+      if (!res.ok) {
+        console.error('[NotificationWindow] Action failed', await res.text());
+      }
+    } catch (error) {
+      console.error('[NotificationWindow] Failed to run action', error);
+    }
+  };
+
   return (
     <div
+      onClick={handleDismiss}
       className={`w-full min-h-screen overflow-hidden transition-colors duration-300 ${
         isFlashing ? 'bg-[rgba(220,220,220,0.95)]' : 'bg-[rgba(30,30,30,0.95)]'
-      }`}
+      } ${dismissOnClick ? 'cursor-pointer' : ''}`}
       style={{
         fontFamily: '"Nunito", -apple-system, BlinkMacSystemFont, "Segoe UI", "Roboto", "Oxygen", "Ubuntu", "Cantarell", "Fira Sans", "Droid Sans", "Helvetica Neue", sans-serif'
       }}
@@ -130,6 +160,21 @@ export function NotificationWindow() {
                     fontSize={notification.fontSize}
                   />
                 </div>
+
+                {/* アクションボタン */}
+                {notification.actions && notification.actions.length > 0 && (
+                  <div className="flex gap-2 mt-2">
+                    {notification.actions.map((action) => (
+                      <button
+                        key={`${action.action}-${action.targetUserId}`}
+                        onClick={(event) => handleAction(event, action)}
+                        className="px-3 py-1 rounded bg-[#9147ff] text-white text-xs font-bold hover:bg-[#772ce8]"
+                      >
+                        {action.label}
+                      </button>
+                    ))}
+                  </div>
+                )}
               </div>
             </div>
           ) : (
@@ -0,0 +1,148 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import { RefreshCw, RotateCcw } from 'lucide-react';
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '../ui/card';
+import { Button } from '../ui/button';
+import { buildApiUrl } from '../../utils/api';
+
+type SubscriptionState = 'pending' | 'enabled' | 'failed' | 'revoked';
+
+interface SubscriptionStatus {
+  event_type: string;
+  state: SubscriptionState;
+  subscription_id: string | null;
+  error: string | null;
+  last_event_at: number | null;
+  event_count: number;
+  updated_at: number;
+}
+
+interface EventSubStatus {
+  connected: boolean;
+  session_id: string | null;
+  subscriptions: SubscriptionStatus[];
+}
+
+const STATE_LABELS: Record<SubscriptionState, { label: string; className: string }> = {
+  pending: { label: '待機中', className: 'text-gray-500 dark:text-gray-400' },
+  enabled: { label: '有効', className: 'text-green-600' },
+  failed: { label: '失敗', className: 'text-red-600' },
+  revoked: { label: '取り消し', className: 'text-orange-600' },
+};
+
+const POLL_INTERVAL_MS = 10000;
+
+export const EventSubStatusCard: React.FC = () => {
+  const [status, setStatus] = useState<EventSubStatus | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchStatus = useCallback(async () => {
+    try {
+      const response = await fetch(buildApiUrl('/api/twitch/eventsub/status'));
+      if (response.ok) {
+        setStatus(await response.json());
+      }
+    } catch (err) {
+      console.error('Failed to fetch EventSub status:', err);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchStatus();
+    const timer = setInterval(fetchStatus, POLL_INTERVAL_MS);
+    return () => clearInterval(timer);
+  }, [fetchStatus]);
+
+  const resubscribe = async (eventTypes: string[]) => {
+    setLoading(true);
+    setError(null);
+    try {
+      const response = await fetch(buildApiUrl('/api/twitch/eventsub/resubscribe'), {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ event_types: eventTypes }),
+      });
+      if (!response.ok) {
+        const data = await response.json().catch(() => ({}));
+        setError(data.error || '再購読に失敗しました');
+      }
+      setTimeout(fetchStatus, 1000);
+    } catch (err) {
+      console.error('Failed to resubscribe EventSub:', err);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const unhealthy = status?.subscriptions.filter((s) => s.state !== 'enabled') ?? [];
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>EventSub購読状態</CardTitle>
+        <CardDescription>
+          取り消された購読や失敗した購読はアプリを再起動せずに再購読できます
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="flex items-center justify-between">
+          <p className="text-sm dark:text-gray-200">
+            接続:{' '}
+            {status?.connected ? (
+              <span className="text-green-600">接続中</span>
+            ) : (
+              <span className="text-orange-600">未接続</span>
+            )}
+          </p>
+          <div className="flex items-center space-x-2">
+            <Button onClick={fetchStatus} variant="outline" size="sm">
+              <RefreshCw className="w-4 h-4" />
+            </Button>
+            <Button
+              onClick={() => resubscribe([])}
+              variant="outline"
+              size="sm"
+              disabled={loading || !status?.connected || unhealthy.length === 0}
+              className="flex items-center space-x-2"
+            >
+              <RotateCcw className="w-4 h-4" />
+              <span>問題のある購読を再購読</span>
+            </Button>
+          </div>
+        </div>
+        {error && <p className="text-sm text-red-600">{error}</p>}
+        <div className="divide-y dark:divide-gray-700 border rounded dark:border-gray-600">
+          {(status?.subscriptions ?? []).map((sub) => {
+            const label = STATE_LABELS[sub.state];
+            return (
+              <div key={sub.event_type} className="flex items-center justify-between px-3 py-2 text-sm">
+                <div>
+                  <p className="font-mono dark:text-gray-200">{sub.event_type}</p>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    最終イベント:{' '}
+                    {sub.last_event_at ? new Date(sub.last_event_at * 1000).toLocaleString() : 'なし'}
+                    {sub.event_count > 0 && ` (${sub.event_count}件)`}
+                  </p>
+                  {sub.error && <p className="text-xs text-red-600">{sub.error}</p>}
+                </div>
+                <div className="flex items-center space-x-2">
+                  <span className={label.className}>{label.label}</span>
+                  {sub.state !== 'enabled' && sub.state !== 'pending' && (
+                    <Button
+                      onClick={() => resubscribe([sub.event_type])}
+                      variant="ghost"
+                      size="sm"
+                      disabled={loading || !status?.connected}
+                    >
+                      <RotateCcw className="w-4 h-4" />
+                    </Button>
+                  )}
+                </div>
+              </div>
+            );
+          })}
+        </div>
+      </CardContent>
+    </Card>
+  );
+};
@@ -104,6 +104,53 @@ export const GeneralSettings: React.FC<GeneralSettingsProps> = ({
             )}
           </div>
 
+          <div className="space-y-4">
+            <div className="space-y-2">
+              <Label htmlFor="server_bind_address">公開範囲</Label>
+              <Select
+                value={getSettingValue('SERVER_BIND_ADDRESS')}
+                onValueChange={(value) => handleSettingChange('SERVER_BIND_ADDRESS', value)}
+              >
+                <SelectTrigger id="server_bind_address" className="w-64">
+                  <SelectValue placeholder="待ち受けアドレスを選択" />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="127.0.0.1">このPCのみ (127.0.0.1)</SelectItem>
+                  <SelectItem value="0.0.0.0">LAN に公開 (0.0.0.0)</SelectItem>
+                </SelectContent>
+              </Select>
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="server_ip_allowlist">許可する IP アドレス</Label>
+              <Input
+                id="server_ip_allowlist"
+                placeholder="192.168.1.0/24, 192.168.1.20"
+                value={getSettingValue('SERVER_IP_ALLOWLIST')}
+                onChange={(e) => handleSettingChange('SERVER_IP_ALLOWLIST', e.target.value)}
+              />
+              <p className="text-sm text-gray-500 dark:text-gray-400">
+                カンマ区切りの IP / CIDR。空欄ならすべて許可（このPCからの接続は常に許可）
+              </p>
+            </div>
+            <div className="flex items-center justify-between">
+              <div className="space-y-0.5">
+                <Label>オーバーレイ・FAX は許可リスト外にも公開</Label>
+                <p className="text-sm text-gray-500 dark:text-gray-400">
+                  オフにすると管理画面・API と同じく許可リストで制限します
+                </p>
+              </div>
+              <Switch
+                checked={getSettingValue('SERVER_PUBLIC_ROUTES') !== 'allowlist'}
+                onCheckedChange={(checked) =>
+                  handleSettingChange('SERVER_PUBLIC_ROUTES', checked ? 'open' : 'allowlist')
+                }
+              />
+            </div>
+            <p className="text-sm text-gray-500 dark:text-gray-400">
+              保存するとWebサーバーが再起動して反映されます
+            </p>
+          </div>
+
           <div className="grid grid-cols-1 md:grid-cols-2 gap-6">
             <div className="flex items-center justify-between">
               <div className="space-y-0.5">
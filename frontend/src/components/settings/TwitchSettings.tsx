@@ -7,6 +7,7 @@ import { Button } from '../ui/button';
 import { SettingsPageContext } from '../../hooks/useSettingsPage';
 import { CustomRewardsList } from './CustomRewardsList';
 import { RewardGroupsManager } from './RewardGroupsManager';
+import { EventSubStatusCard } from './EventSubStatusCard';
 import { buildApiUrl } from '../../utils/api';
 
 interface CustomReward {
@@ -92,6 +93,11 @@ export const TwitchSettings: React.FC = () => {
                       有効期限: {new Date(authStatus.expiresAt * 1000).toLocaleString()}
                     </p>
                   )}
+                  {authStatus.reauthRequired && (
+                    <p className="text-sm text-orange-600 mt-1">
+                      権限が不足しています。再認証してください: {authStatus.missingScopes?.join(', ')}
+                    </p>
+                  )}
                 </div>
                 {!authStatus.authenticated && (
                   <Button
@@ -200,6 +206,19 @@ export const TwitchSettings: React.FC = () => {
               </div>
             </div>
 
+            <div className="space-y-2">
+              <Label htmlFor="emote_priority_channel">優先エモートチャンネル</Label>
+              <Input
+                id="emote_priority_channel"
+                placeholder="チャンネルのログイン名（空欄で無効）"
+                value={unsavedChanges['EMOTE_PRIORITY_CHANNEL'] !== undefined ? unsavedChanges['EMOTE_PRIORITY_CHANNEL'] : getSettingValue('EMOTE_PRIORITY_CHANNEL')}
+                onChange={(e) => handleSettingChange('EMOTE_PRIORITY_CHANNEL', e.target.value)}
+              />
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                このチャンネルのエモートを一覧の先頭に表示します
+              </p>
+            </div>
+
             <div className="space-y-2">
               <Label htmlFor="fax_trigger_reward">FAXトリガーリワード</Label>
               <div className="flex items-center space-x-2">
@@ -241,6 +260,9 @@ export const TwitchSettings: React.FC = () => {
         </CardContent>
       </Card>
 
+      {/* EventSub購読状態 */}
+      {authStatus?.authenticated && <EventSubStatusCard />}
+
       {/* リワードグループ管理 */}
       <RewardGroupsManager
         onGroupsChanged={() => setRefreshTrigger(prev => prev + 1)}
@@ -122,6 +122,16 @@ export interface FeatureStatus {
   webserver_running: boolean;
   webserver_port: number;
   font_configured: boolean;
+  features?: FeatureFlags;
+}
+
+// 機能フラグ（無効な機能はバックグラウンド処理を起動しない）
+export interface FeatureFlags {
+  printer: boolean;
+  music: boolean;
+  lottery: boolean;
+  translations: boolean;
+  tts: boolean;
 }
 
 // Twitchユーザー情報
@@ -139,6 +149,9 @@ export interface AuthStatus {
   authenticated: boolean;
   authUrl: string;
   expiresAt?: number | null;
+  // 現在のトークンに足りないスコープ（あれば再認証が必要）
+  missingScopes?: string[];
+  reauthRequired?: boolean;
   error?: string | null;
 }
 
@@ -8,6 +8,20 @@ export interface Fragment {
   emoteUrl?: string;
 }
 
+/**
+ * Mouse interaction mode of the notification window
+ */
+export type NotificationInteractionMode = 'normal' | 'click_through' | 'dismiss_on_click';
+
+/**
+ * Action button shown on a notification (e.g. shoutout on a raid)
+ */
+export interface NotificationAction {
+  action: 'shoutout';
+  label: string;
+  targetUserId: string;
+}
+
 /**
  * Chat notification data
  */
@@ -17,4 +31,6 @@ export interface ChatNotification {
   fragments?: Fragment[];
   fontSize?: number;
   avatarUrl?: string;
+  interactionMode?: NotificationInteractionMode;
+  actions?: NotificationAction[];
 }
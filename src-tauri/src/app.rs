@@ -7,6 +7,7 @@ use tauri::Emitter;
 use tokio::sync::{RwLock, broadcast};
 
 use crate::config::{AppConfig, SettingsManager};
+use crate::services::http::{self, HttpSettings};
 
 /// Application shared state accessible from both Tauri commands and axum handlers.
 #[derive(Clone)]
@@ -25,12 +26,15 @@ struct SharedStateInner {
     data_dir: PathBuf,
     /// Tauri AppHandle (set during setup, used for emit)
     app_handle: OnceLock<tauri::AppHandle>,
+    /// Pooled outgoing HTTP client (rebuilt on config reload)
+    http: std::sync::RwLock<reqwest::Client>,
 }
 
 impl SharedState {
     /// Create shared state from an already-opened database and loaded config.
     pub fn new(db: Database, config: AppConfig, data_dir: PathBuf) -> Self {
         let (ws_tx, _) = broadcast::channel(2048);
+        let http = http::build(&HttpSettings::load(&db));
 
         Self {
             inner: Arc::new(SharedStateInner {
@@ -39,6 +43,7 @@ impl SharedState {
                 db,
                 data_dir,
                 app_handle: OnceLock::new(),
+                http: std::sync::RwLock::new(http),
             }),
         }
     }
@@ -87,6 +92,15 @@ impl SharedState {
         &self.inner.data_dir
     }
 
+    /// The shared HTTP client. Clones share its connection pool.
+    pub fn http(&self) -> reqwest::Client {
+        self.inner
+            .http
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
     /// Get a read lock on the current config.
     pub async fn config(&self) -> tokio::sync::RwLockReadGuard<'_, AppConfig> {
         self.inner.config.read().await
@@ -97,6 +111,8 @@ impl SharedState {
         let sm = SettingsManager::new(self.inner.db.clone());
         let mut config = self.inner.config.write().await;
         config.reload(&sm)?;
+        let http = http::build(&HttpSettings::load(&self.inner.db));
+        *self.inner.http.write().unwrap_or_else(|e| e.into_inner()) = http;
         Ok(())
     }
 }
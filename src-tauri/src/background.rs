@@ -1,43 +1,139 @@
-//! Background task loops: token refresh, printer keepalive.
+//! Background task loops: token refresh, printer keepalive, and the
+//! service workers shared by the desktop app and the headless server.
 
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use tokio::time::sleep;
 
 use crate::app::SharedState;
-use crate::services::printer;
+use crate::services::print_queue::{self, PrinterRegistry, PrinterTarget};
+use crate::services::{self, printer};
+
+/// How often the keepalive loop checks which printers are due.
+const KEEPALIVE_TICK: Duration = Duration::from_secs(10);
+
+/// Bluetooth printers (name, address) the keepalive loop looks after.
+fn keepalive_targets(registry: &PrinterRegistry) -> Vec<(String, String)> {
+    registry
+        .printers
+        .iter()
+        .filter_map(|p| match &p.target {
+            PrinterTarget::Bluetooth { address } if !address.is_empty() => {
+                Some((p.name.clone(), address.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
 
 /// Periodic BLE printer KeepAlive reconnection.
+///
+/// Every Bluetooth printer in the registry has its own schedule and
+/// connection state, so one unreachable printer does not delay or mark
+/// the others.
 pub async fn printer_keepalive_loop(state: SharedState) {
     // Wait for initial startup
     sleep(Duration::from_secs(30)).await;
 
+    let mut last_refresh: HashMap<String, Instant> = HashMap::new();
     loop {
-        let (enabled, interval, printer_type, address) = {
+        let (enabled, interval) = {
             let config = state.config().await;
             (
                 config.keep_alive_enabled,
-                config.keep_alive_interval.max(10) as u64,
-                config.printer_type.clone(),
-                config.printer_address.clone(),
+                Duration::from_secs(config.keep_alive_interval.max(10) as u64),
             )
         };
+        let targets = if enabled {
+            keepalive_targets(&print_queue::registry(&state).await)
+        } else {
+            Vec::new()
+        };
 
-        if !enabled || printer_type != "bluetooth" || address.is_empty() {
+        if targets.is_empty() {
+            last_refresh.clear();
             sleep(Duration::from_secs(60)).await;
             continue;
         }
+        last_refresh.retain(|name, _| targets.iter().any(|(n, _)| n == name));
+
+        for (name, address) in targets {
+            // A newly seen printer waits a full interval first.
+            let last = *last_refresh
+                .entry(name.clone())
+                .or_insert_with(Instant::now);
+            if last.elapsed() < interval {
+                continue;
+            }
+            last_refresh.insert(name.clone(), Instant::now());
+
+            tracing::debug!(printer = %name, "Printer KeepAlive: reconnecting to {address}");
+            if let Err(e) = printer::reconnect_bluetooth(&address).await {
+                tracing::warn!(printer = %name, "Printer KeepAlive failed: {e}");
+                printer::mark_printer_error(&name, e).await;
+            } else {
+                printer::mark_printer_connected(&name, "bluetooth", &address).await;
+                tracing::debug!(printer = %name, "Printer KeepAlive: reconnected successfully");
+            }
+        }
 
-        sleep(Duration::from_secs(interval)).await;
+        sleep(KEEPALIVE_TICK).await;
+    }
+}
 
-        tracing::debug!("Printer KeepAlive: reconnecting to {address}");
-        if let Err(e) = printer::reconnect_bluetooth(&address).await {
-            tracing::warn!("Printer KeepAlive failed: {e}");
-            printer::mark_error(e).await;
-        } else {
-            printer::mark_connected("bluetooth", &address).await;
-            tracing::debug!("Printer KeepAlive: reconnected successfully");
-        }
+/// Start the service workers. Called by both entry points from within the
+/// Tokio runtime, so a worker added here runs in headless mode too.
+pub fn spawn_workers(state: &SharedState) {
+    // Step 10: Print queue and feature-gated subsystems (printer KeepAlive,
+    // music library check, lottery claims, chat translations, TTS)
+    let s = state.clone();
+    tokio::spawn(async move { services::features::start_enabled_tasks(&s).await });
+
+    // AFK detection
+    let s = state.clone();
+    tokio::spawn(async move { services::afk::run_monitor(s).await });
+
+    // Viewer milestones
+    let s = state.clone();
+    tokio::spawn(async move { services::milestones::run_monitor(s).await });
+
+    // Shoutout queue
+    let s = state.clone();
+    tokio::spawn(async move { services::shoutouts::run_worker(s).await });
+
+    // Emote cache warm-up
+    let s = state.clone();
+    tokio::spawn(async move { services::emotes::run(s).await });
+
+    // Outbound webhook deliveries
+    let s = state.clone();
+    tokio::spawn(async move { services::webhooks::run(s).await });
+
+    // Discord notifications
+    let s = state.clone();
+    tokio::spawn(async move { services::discord::run(s).await });
+
+    // Chat timers
+    let s = state.clone();
+    tokio::spawn(async move { services::chat_timers::run(s).await });
+
+    // Stream session sampling and reward group schedules
+    let s = state.clone();
+    tokio::spawn(async move { services::stream_sessions::run(s).await });
+
+    // Ad schedule
+    let s = state.clone();
+    tokio::spawn(async move { services::ad_break::run(s).await });
+
+    // IRC relay for other channels' chat
+    let s = state.clone();
+    tokio::spawn(async move { services::irc_relay::run(s).await });
+
+    // Demo mode: seed data and synthetic events
+    if services::demo::is_enabled() {
+        let s = state.clone();
+        tokio::spawn(async move { services::demo::run(s).await });
     }
 }
 
@@ -71,10 +167,11 @@ pub async fn token_refresh_loop(state: SharedState) {
             }
 
             let redirect_uri = format!("http://127.0.0.1:{}/callback", config.server_port);
-            let auth = twitch_client::auth::TwitchAuth::new(
+            let auth = twitch_client::auth::TwitchAuth::with_http(
                 config.client_id.clone(),
                 config.client_secret.clone(),
                 redirect_uri,
+                state.http(),
             );
             drop(config);
 
@@ -8,7 +8,6 @@ use tracing_subscriber::EnvFilter;
 use cairo_overlay_lib::app::SharedState;
 use cairo_overlay_lib::background;
 use cairo_overlay_lib::server;
-use cairo_overlay_lib::services;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -35,13 +34,8 @@ async fn main() -> anyhow::Result<()> {
     let s = state.clone();
     tokio::spawn(async move { background::token_refresh_loop(s).await });
 
-    // Step 10: Printer KeepAlive
-    let s = state.clone();
-    tokio::spawn(async move { background::printer_keepalive_loop(s).await });
-
-    // Print queue worker
-    let s = state.clone();
-    tokio::spawn(async move { services::print_queue::start_worker(s).await });
+    // Service workers
+    background::spawn_workers(&state);
 
     tracing::info!(
         port = state.server_port(),
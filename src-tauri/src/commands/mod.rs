@@ -5,3 +5,7 @@
 // - twitch.rs (Phase 4)
 // - music.rs (Phase 6)
 // - window.rs (Phase 7)
+
+pub mod notification;
+pub mod system;
+pub mod window;
@@ -0,0 +1,20 @@
+//! Notification window commands (same operations as `/api/notification/*`).
+
+use tauri::State;
+
+use crate::app::SharedState;
+use crate::notification::types::NotificationAction;
+use crate::notification::{actions, queue};
+
+#[tauri::command]
+pub fn dismiss_notification() {
+    queue::dismiss_current();
+}
+
+#[tauri::command]
+pub async fn run_notification_action(
+    state: State<'_, SharedState>,
+    action: NotificationAction,
+) -> Result<(), String> {
+    actions::run(&state, &action).await
+}
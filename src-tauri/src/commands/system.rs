@@ -0,0 +1,13 @@
+//! Diagnostics commands (see `services::preflight`).
+
+use tauri::State;
+
+use crate::app::SharedState;
+use crate::services::preflight::{self, PreflightReport};
+
+/// Pre-flight checks against the running app, as `--check` runs them
+/// before launch.
+#[tauri::command]
+pub async fn validate(state: State<'_, SharedState>) -> Result<PreflightReport, String> {
+    preflight::validate(&state).await
+}
@@ -0,0 +1,56 @@
+//! Window control commands (same operations as `/api/window/*`).
+//!
+//! Commands that may create a window are async: building a webview from a
+//! synchronous command deadlocks on Windows.
+
+use tauri::State;
+
+use crate::app::SharedState;
+use crate::notification::window::NotificationPosition;
+use crate::window::control::{self, WindowStatus};
+use crate::window::stream_monitor;
+
+#[tauri::command]
+pub fn window_status(state: State<'_, SharedState>) -> WindowStatus {
+    control::status(&state)
+}
+
+#[tauri::command]
+pub async fn show_main_window(state: State<'_, SharedState>) -> Result<(), String> {
+    control::show_main(&state)
+}
+
+#[tauri::command]
+pub fn hide_main_window(state: State<'_, SharedState>) -> Result<(), String> {
+    control::hide_main(&state)
+}
+
+#[tauri::command]
+pub async fn open_settings_tab(state: State<'_, SharedState>, tab: String) -> Result<(), String> {
+    control::open_settings_tab(&state, &tab)
+}
+
+#[tauri::command]
+pub async fn open_overlay_preview(state: State<'_, SharedState>) -> Result<(), String> {
+    control::open_overlay_preview(&state)
+}
+
+#[tauri::command]
+pub fn close_overlay_preview(state: State<'_, SharedState>) -> Result<(), String> {
+    control::close_overlay_preview(&state)
+}
+
+#[tauri::command]
+pub async fn move_notification_window(
+    state: State<'_, SharedState>,
+    x: i32,
+    y: i32,
+    screen_index: Option<usize>,
+) -> Result<NotificationPosition, String> {
+    control::move_notification(&state, x, y, screen_index)
+}
+
+#[tauri::command]
+pub async fn toggle_stream_monitor(state: State<'_, SharedState>) -> Result<bool, String> {
+    stream_monitor::toggle(&state)
+}
@@ -13,6 +13,10 @@ pub struct AppConfig {
     pub printer_address: String,
     pub printer_type: String,
     pub usb_printer_name: String,
+    /// `catprinter` (`printer_type` applies) or `escpos`.
+    pub printer_backend: String,
+    pub escpos_device: String,
+    pub escpos_cut: bool,
     pub best_quality: bool,
     pub dither: bool,
     pub black_point: f32,
@@ -38,6 +42,9 @@ impl Default for AppConfig {
             printer_address: String::new(),
             printer_type: "bluetooth".into(),
             usb_printer_name: String::new(),
+            printer_backend: "catprinter".into(),
+            escpos_device: String::new(),
+            escpos_cut: true,
             best_quality: true,
             dither: true,
             black_point: 0.5,
@@ -91,6 +98,12 @@ impl AppConfig {
                 if t.is_empty() { "bluetooth".into() } else { t }
             },
             usb_printer_name: g("USB_PRINTER_NAME"),
+            printer_backend: {
+                let b = g("PRINTER_BACKEND");
+                if b.is_empty() { "catprinter".into() } else { b }
+            },
+            escpos_device: g("ESCPOS_DEVICE"),
+            escpos_cut: g("ESCPOS_CUT") != "false",
             best_quality: g("BEST_QUALITY") == "true",
             dither: g("DITHER") == "true",
             black_point: parse_f32(&g("BLACK_POINT"), 0.5),
@@ -114,6 +127,11 @@ impl AppConfig {
         })
     }
 
+    /// Whether prints go to an ESC/POS printer instead of a cat printer.
+    pub fn uses_escpos(&self) -> bool {
+        self.printer_backend == "escpos"
+    }
+
     /// Reload config from the settings manager.
     pub fn reload(&mut self, sm: &SettingsManager) -> Result<(), anyhow::Error> {
         *self = Self::load(sm)?;
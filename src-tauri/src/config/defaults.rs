@@ -33,7 +33,28 @@ const DEFS: &[(&str, &str, bool, bool, &str)] = &[
         true,
         "Custom Reward ID for triggering FAX",
     ),
+    (
+        "EMOTE_PRIORITY_CHANNEL",
+        "",
+        false,
+        false,
+        "Channel login whose emotes are listed first in the emote picker",
+    ),
+    (
+        "THIRD_PARTY_EMOTE_PROVIDERS",
+        "7tv,bttv,ffz",
+        false,
+        false,
+        "Third-party emote providers shown in chat and the emote picker (comma-separated: 7tv, bttv, ffz)",
+    ),
     // --- Printer ---
+    (
+        "PRINTER_BACKEND",
+        "catprinter",
+        false,
+        false,
+        "Printer protocol (catprinter or escpos)",
+    ),
     (
         "PRINTER_TYPE",
         "bluetooth",
@@ -55,6 +76,41 @@ const DEFS: &[(&str, &str, bool, bool, &str)] = &[
         false,
         "System printer name for USB printing",
     ),
+    (
+        "ESCPOS_DEVICE",
+        "",
+        false,
+        false,
+        "ESC/POS printer device (e.g. /dev/usb/lp0, /dev/ttyUSB0, COM3)",
+    ),
+    (
+        "ESCPOS_CUT",
+        "true",
+        false,
+        false,
+        "Cut the paper after each ESC/POS print",
+    ),
+    (
+        "PRINTERS",
+        "[]",
+        false,
+        false,
+        "Additional named printers (JSON array)",
+    ),
+    (
+        "PRINTER_ROUTES",
+        "{}",
+        false,
+        false,
+        "Printer name per print category: chat, follow, reward, clock (JSON object)",
+    ),
+    (
+        "PRINT_LOCALE",
+        "ja-JP",
+        false,
+        false,
+        "Locale for printed text, dates and numbers (ja-JP, ja-JP-era, en-US, en-GB, de-DE, fr-FR, es-ES)",
+    ),
     (
         "DRY_RUN_MODE",
         "true",
@@ -85,6 +141,91 @@ const DEFS: &[(&str, &str, bool, bool, &str)] = &[
         false,
         "Rotate print output 180 degrees",
     ),
+    (
+        "EMOTE_PRINT_PENDING_POLICY",
+        "allow",
+        false,
+        false,
+        "How to print emotes awaiting approval (allow or deny)",
+    ),
+    (
+        "PRINT_QUOTA_PER_STREAM",
+        "0",
+        false,
+        false,
+        "Max redemption prints per user per stream (0 = unlimited)",
+    ),
+    (
+        "PRINT_COOLDOWN_MINUTES",
+        "0",
+        false,
+        false,
+        "Per-user cooldown between redemption prints (min)",
+    ),
+    (
+        "PRINT_LIMIT_REPLY_ENABLED",
+        "true",
+        false,
+        false,
+        "Reply in chat when a print is refused",
+    ),
+    (
+        "PRINT_QUOTA_MESSAGE",
+        "@{user} この配信での印刷は{limit}回までです。ご協力ありがとうございます！",
+        false,
+        false,
+        "Chat reply when the quota is reached ({user}, {limit})",
+    ),
+    (
+        "PRINT_COOLDOWN_MESSAGE",
+        "@{user} 次の印刷まであと{remaining}分お待ちください🙏",
+        false,
+        false,
+        "Chat reply during cooldown ({user}, {remaining})",
+    ),
+    // --- Printer power (smart plug) ---
+    (
+        "SMART_PLUG_ENABLED",
+        "false",
+        false,
+        false,
+        "Control printer power via a smart plug",
+    ),
+    (
+        "SMART_PLUG_TYPE",
+        "tasmota",
+        false,
+        false,
+        "Smart plug type (tasmota or homeassistant)",
+    ),
+    (
+        "SMART_PLUG_URL",
+        "",
+        false,
+        false,
+        "Smart plug / Home Assistant base URL",
+    ),
+    (
+        "SMART_PLUG_TOKEN",
+        "",
+        true,
+        false,
+        "Home Assistant long-lived access token",
+    ),
+    (
+        "SMART_PLUG_ENTITY_ID",
+        "",
+        false,
+        false,
+        "Home Assistant switch entity ID",
+    ),
+    (
+        "SMART_PLUG_WARMUP_SECONDS",
+        "10",
+        false,
+        false,
+        "Wait after powering on before printing (s)",
+    ),
     // --- Operation ---
     (
         "KEEP_ALIVE_INTERVAL",
@@ -114,13 +255,55 @@ const DEFS: &[(&str, &str, bool, bool, &str)] = &[
         false,
         "Show icons in clock display",
     ),
+    (
+        "CLOCK_CRON",
+        "0 * * * *",
+        false,
+        false,
+        "Cron expression for clock printing, evaluated in TIMEZONE",
+    ),
+    (
+        "PRINT_VOTE_ENABLED",
+        "false",
+        false,
+        false,
+        "Let viewers vote which chat message gets printed",
+    ),
+    (
+        "PRINT_VOTE_CRON",
+        "*/10 * * * *",
+        false,
+        false,
+        "Cron expression for printing the most-voted message, evaluated in TIMEZONE",
+    ),
+    (
+        "PRINT_VOTE_KEYWORD",
+        "!print",
+        false,
+        false,
+        "Reply with this word to vote for a chat message",
+    ),
+    (
+        "PRINT_VOTE_MIN_VOTES",
+        "2",
+        false,
+        false,
+        "Votes a message needs before it is printed (1-1000)",
+    ),
+    (
+        "PRINT_VOTE_REWARD_ID",
+        "",
+        false,
+        false,
+        "Channel point reward voting for the latest message of the viewer named in its input",
+    ),
     ("DEBUG_OUTPUT", "false", false, false, "Enable debug output"),
     (
         "TIMEZONE",
         "Asia/Tokyo",
         false,
         false,
-        "Timezone for clock display",
+        "IANA time zone for clock prints and schedules (e.g. Asia/Tokyo)",
     ),
     (
         "AUTO_DRY_RUN_WHEN_OFFLINE",
@@ -137,6 +320,48 @@ const DEFS: &[(&str, &str, bool, bool, &str)] = &[
         false,
         "Web server port for OBS overlay",
     ),
+    (
+        "SERVER_BIND_ADDRESS",
+        "0.0.0.0",
+        false,
+        false,
+        "Listen address (127.0.0.1 = this PC only, 0.0.0.0 = LAN)",
+    ),
+    (
+        "SERVER_IP_ALLOWLIST",
+        "",
+        false,
+        false,
+        "Comma-separated IPs/CIDRs allowed to connect besides localhost (empty = any)",
+    ),
+    (
+        "SERVER_PUBLIC_ROUTES",
+        "open",
+        false,
+        false,
+        "Overlay/FAX/WebSocket routes: open (any client) or allowlist",
+    ),
+    (
+        "HTTP_PROXY",
+        "",
+        false,
+        false,
+        "Proxy URL for outgoing requests (http://host:port); empty for a direct connection",
+    ),
+    (
+        "HTTP_NO_PROXY",
+        "localhost,127.0.0.1,::1,10.0.0.0/8,172.16.0.0/12,192.168.0.0/16",
+        false,
+        false,
+        "Comma-separated hosts/CIDRs reached without the proxy (LAN devices)",
+    ),
+    (
+        "HTTP_TIMEOUT_SECONDS",
+        "30",
+        false,
+        false,
+        "Timeout of outgoing requests in seconds",
+    ),
     // --- Font ---
     ("FONT_FILENAME", "", false, false, "Uploaded font file name"),
     // --- Window ---
@@ -739,6 +964,97 @@ const DEFS: &[(&str, &str, bool, bool, &str)] = &[
         false,
         "Enable lottery participant ticker",
     ),
+    (
+        "LOTTERY_PRIZE",
+        "",
+        false,
+        false,
+        "Prize text on the lottery winner receipt",
+    ),
+    (
+        "LOTTERY_TICKET_FORMULA",
+        "",
+        false,
+        false,
+        "Lottery ticket formula JSON (max_entries, tier_bonus, tier_coefficients, months_divisor, max_bonus); empty uses the defaults",
+    ),
+    (
+        "LOTTERY_RESULTS_PUBLIC",
+        "false",
+        false,
+        false,
+        "Serve past winners and draw proofs at /present/results",
+    ),
+    (
+        "LOTTERY_RESULTS_MASK_NAMES",
+        "true",
+        false,
+        false,
+        "Partly hide winner names on /present/results",
+    ),
+    (
+        "LOTTERY_WINNER_WHISPER",
+        "false",
+        false,
+        false,
+        "Whisper the lottery winner claim instructions",
+    ),
+    (
+        "LOTTERY_WINNER_WHISPER_MESSAGE",
+        "おめでとうございます！抽選で「{prize}」に当選しました。{deadline} までに配信のチャットでお知らせください。",
+        false,
+        false,
+        "Whisper to the lottery winner ({user}, {login}, {prize}, {deadline}, {minutes})",
+    ),
+    (
+        "LOTTERY_WINNER_ANNOUNCE",
+        "false",
+        false,
+        false,
+        "Announce the lottery winner in chat",
+    ),
+    (
+        "LOTTERY_WINNER_ANNOUNCE_MESSAGE",
+        "🎉 抽選の当選者は @{login} さんです！{deadline} までに受け取りの連絡をお願いします。",
+        false,
+        false,
+        "Chat announcement of the lottery winner ({user}, {login}, {prize}, {deadline}, {minutes})",
+    ),
+    (
+        "LOTTERY_CLAIM_MINUTES",
+        "10",
+        false,
+        false,
+        "Minutes the lottery winner has to claim the prize",
+    ),
+    (
+        "LOTTERY_CLAIM_COMMAND",
+        "!claim",
+        false,
+        false,
+        "Chat command the lottery winner sends to claim the prize",
+    ),
+    (
+        "LOTTERY_CLAIM_REWARD_ID",
+        "",
+        false,
+        false,
+        "Channel point reward the lottery winner redeems to claim the prize",
+    ),
+    (
+        "LOTTERY_AUTO_REDRAW",
+        "false",
+        false,
+        false,
+        "Re-draw automatically when the lottery winner misses the claim deadline",
+    ),
+    (
+        "LOTTERY_REDRAW_MESSAGE",
+        "⌛ {expired} さんが受け取り期限までに連絡しなかったため、再抽選します。",
+        false,
+        false,
+        "Chat message before an automatic lottery re-draw ({expired})",
+    ),
     // --- Ticker notice ---
     (
         "TICKER_NOTICE_ENABLED",
@@ -768,90 +1084,868 @@ const DEFS: &[(&str, &str, bool, bool, &str)] = &[
         false,
         "Ticker notice alignment",
     ),
-    // --- Notification ---
+    // --- AFK ---
     (
-        "NOTIFICATION_ENABLED",
-        "true",
+        "AFK_ENABLED",
+        "false",
         false,
         false,
-        "Enable chat notification window",
+        "Enable idle/AFK detection",
     ),
     (
-        "NOTIFICATION_WINDOW_X",
-        "",
+        "AFK_TIMEOUT_MINUTES",
+        "10",
         false,
         false,
-        "Notification window X",
+        "Minutes without activity before AFK",
     ),
     (
-        "NOTIFICATION_WINDOW_Y",
-        "",
+        "AFK_USE_OS_IDLE",
+        "false",
         false,
         false,
-        "Notification window Y",
+        "Also use OS keyboard/mouse idle time",
     ),
     (
-        "NOTIFICATION_WINDOW_WIDTH",
-        "400",
+        "AFK_ANNOUNCE_ENABLED",
+        "false",
         false,
         false,
-        "Notification window width",
+        "Announce AFK/return in chat",
     ),
     (
-        "NOTIFICATION_WINDOW_HEIGHT",
-        "150",
+        "AFK_MESSAGE",
+        "少し離席します。すぐ戻ります！",
         false,
         false,
-        "Notification window height",
+        "Chat message when going AFK",
     ),
     (
-        "NOTIFICATION_WINDOW_ABSOLUTE_X",
-        "",
+        "AFK_RETURN_MESSAGE",
+        "ただいま戻りました！",
         false,
         false,
-        "Notification absolute X",
+        "Chat message when returning",
     ),
     (
-        "NOTIFICATION_WINDOW_ABSOLUTE_Y",
-        "",
+        "AFK_OVERLAY_TEXT",
+        "離席中",
         false,
         false,
-        "Notification absolute Y",
+        "Text shown on the overlay while AFK",
     ),
+    // --- Milestones ---
     (
-        "NOTIFICATION_WINDOW_SCREEN_INDEX",
-        "0",
+        "MILESTONES_ENABLED",
+        "false",
         false,
         false,
-        "Notification screen index",
+        "Enable follower/viewer milestone celebrations",
     ),
     (
-        "NOTIFICATION_WINDOW_SCREEN_HASH",
-        "",
+        "MILESTONE_FOLLOWER_THRESHOLDS",
+        "100,500,1000,5000,10000",
         false,
         false,
-        "Notification screen hash",
+        "Follower milestones (comma-separated)",
     ),
     (
-        "NOTIFICATION_DISPLAY_DURATION",
-        "5",
+        "MILESTONE_VIEWER_THRESHOLDS",
+        "10,50,100,500",
         false,
         false,
-        "Notification duration (s)",
+        "Concurrent viewer milestones (comma-separated)",
     ),
     (
-        "NOTIFICATION_DISPLAY_MODE",
-        "queue",
+        "MILESTONE_CHECK_INTERVAL",
+        "60",
         false,
         false,
-        "Notification mode (queue/overwrite)",
+        "Milestone polling interval (s)",
     ),
     (
-        "NOTIFICATION_FONT_SIZE",
-        "14",
+        "MILESTONE_CHAT_ENABLED",
+        "false",
         false,
         false,
-        "Notification font size",
+        "Announce milestones in chat",
+    ),
+    (
+        "MILESTONE_CHAT_MESSAGE",
+        "🎉 {label} {threshold} 突破！ありがとうございます！",
+        false,
+        false,
+        "Milestone chat message ({label}, {threshold}, {value})",
+    ),
+    (
+        "MILESTONE_PRINT_ENABLED",
+        "false",
+        false,
+        false,
+        "Print a card when a milestone is reached",
+    ),
+    (
+        "HYPE_TRAIN_PRINT_ENABLED",
+        "true",
+        false,
+        false,
+        "Print a receipt with the top contributors when a Hype Train ends",
+    ),
+    // --- Notification ---
+    (
+        "NOTIFICATION_ENABLED",
+        "true",
+        false,
+        false,
+        "Enable chat notification window",
+    ),
+    (
+        "NOTIFICATION_WINDOW_X",
+        "",
+        false,
+        false,
+        "Notification window X",
+    ),
+    (
+        "NOTIFICATION_WINDOW_Y",
+        "",
+        false,
+        false,
+        "Notification window Y",
+    ),
+    (
+        "NOTIFICATION_WINDOW_WIDTH",
+        "400",
+        false,
+        false,
+        "Notification window width",
+    ),
+    (
+        "NOTIFICATION_WINDOW_HEIGHT",
+        "150",
+        false,
+        false,
+        "Notification window height",
+    ),
+    (
+        "NOTIFICATION_WINDOW_ABSOLUTE_X",
+        "",
+        false,
+        false,
+        "Notification absolute X",
+    ),
+    (
+        "NOTIFICATION_WINDOW_ABSOLUTE_Y",
+        "",
+        false,
+        false,
+        "Notification absolute Y",
+    ),
+    (
+        "NOTIFICATION_WINDOW_SCREEN_INDEX",
+        "0",
+        false,
+        false,
+        "Notification screen index",
+    ),
+    (
+        "NOTIFICATION_WINDOW_SCREEN_HASH",
+        "",
+        false,
+        false,
+        "Notification screen hash",
+    ),
+    (
+        "STREAMERBOT_BRIDGE_ENABLED",
+        "false",
+        false,
+        false,
+        "Accept Streamer.bot-compatible actions on /streamerbot",
+    ),
+    (
+        "STREAM_MONITOR_WINDOW_X",
+        "",
+        false,
+        false,
+        "Stream monitor window X",
+    ),
+    (
+        "STREAM_MONITOR_WINDOW_Y",
+        "",
+        false,
+        false,
+        "Stream monitor window Y",
+    ),
+    (
+        "STREAM_MONITOR_WINDOW_WIDTH",
+        "320",
+        false,
+        false,
+        "Stream monitor window width",
+    ),
+    (
+        "STREAM_MONITOR_WINDOW_HEIGHT",
+        "480",
+        false,
+        false,
+        "Stream monitor window height",
+    ),
+    (
+        "STREAM_MONITOR_OPEN_ON_STARTUP",
+        "false",
+        false,
+        false,
+        "Open the stream monitor window on startup",
+    ),
+    (
+        "NOTIFICATION_DISPLAY_DURATION",
+        "5",
+        false,
+        false,
+        "Notification duration (s)",
+    ),
+    (
+        "NOTIFICATION_DISPLAY_MODE",
+        "queue",
+        false,
+        false,
+        "Notification mode (queue/overwrite)",
+    ),
+    (
+        "NOTIFICATION_FONT_SIZE",
+        "14",
+        false,
+        false,
+        "Notification font size",
+    ),
+    (
+        "NOTIFICATION_INTERACTION_MODE",
+        "normal",
+        false,
+        false,
+        "Notification mouse mode (normal/click_through/dismiss_on_click)",
+    ),
+    (
+        "NOTIFICATION_ACTIONS_ENABLED",
+        "false",
+        false,
+        false,
+        "Show action buttons (e.g. shoutout) on notifications",
+    ),
+    (
+        "NOTIFICATION_PREEMPT_LEVEL",
+        "raid",
+        false,
+        false,
+        "Lowest alert class that interrupts a lower-priority notification (raid/sub/follow/off)",
+    ),
+    (
+        "NOTIFICATION_MAX_QUEUED_CHAT",
+        "10",
+        false,
+        false,
+        "Max queued chat notifications (oldest dropped)",
+    ),
+    (
+        "NOTIFICATION_MAX_QUEUED_FOLLOW",
+        "20",
+        false,
+        false,
+        "Max queued follow/shoutout notifications (oldest dropped)",
+    ),
+    (
+        "NOTIFICATION_MAX_QUEUED_SUB",
+        "50",
+        false,
+        false,
+        "Max queued sub/cheer notifications (oldest dropped)",
+    ),
+    (
+        "NOTIFICATION_MAX_QUEUED_RAID",
+        "50",
+        false,
+        false,
+        "Max queued raid notifications (oldest dropped)",
+    ),
+    (
+        "OSC_ENABLED",
+        "false",
+        false,
+        false,
+        "Send OSC messages on mapped channel events",
+    ),
+    (
+        "OSC_HOST",
+        "127.0.0.1",
+        false,
+        false,
+        "OSC destination host",
+    ),
+    (
+        "OSC_PORT",
+        "9000",
+        false,
+        false,
+        "OSC destination UDP port (VRChat listens on 9000)",
+    ),
+    (
+        "OBS_ENABLED",
+        "false",
+        false,
+        false,
+        "Run OBS scene/source rules on mapped channel events",
+    ),
+    ("OBS_HOST", "localhost", false, false, "obs-websocket host"),
+    ("OBS_PORT", "4455", false, false, "obs-websocket port"),
+    (
+        "OBS_PASSWORD",
+        "",
+        true,
+        false,
+        "obs-websocket server password (empty when authentication is off)",
+    ),
+    (
+        "DISCORD_ENABLED",
+        "false",
+        false,
+        false,
+        "Post stream start/end, raids and lottery winners to Discord",
+    ),
+    (
+        "DISCORD_WEBHOOK_URL",
+        "",
+        true,
+        false,
+        "Discord webhook URL (channel settings > Integrations > Webhooks)",
+    ),
+    (
+        "DISCORD_USERNAME",
+        "",
+        false,
+        false,
+        "Name shown on Discord posts (empty uses the webhook's name)",
+    ),
+    (
+        "PANIC_FOLLOWER_ONLY_MINUTES",
+        "10",
+        false,
+        false,
+        "Follow age required to chat after the panic button (0 = any follower)",
+    ),
+    (
+        "PANIC_CHAT_MODE",
+        "subscribers",
+        false,
+        false,
+        "Chat restriction the panic button turns on: followers or subscribers",
+    ),
+    (
+        "PRE_SHOW_MINUTES",
+        "5",
+        false,
+        false,
+        "Default countdown length of the starting-soon pre-show",
+    ),
+    (
+        "PRE_SHOW_TITLE",
+        "まもなく配信開始",
+        false,
+        false,
+        "Heading of the pre-show countdown widget",
+    ),
+    (
+        "PRE_SHOW_PLAYLIST",
+        "",
+        false,
+        false,
+        "Playlist played during the pre-show (empty leaves the music player alone)",
+    ),
+    (
+        "PRE_SHOW_STOP_MUSIC_ON_LIVE",
+        "true",
+        false,
+        false,
+        "Stop the pre-show playlist when the stream goes live",
+    ),
+    (
+        "TTS_ENABLED",
+        "false",
+        false,
+        false,
+        "Read chat messages and reward redemptions aloud",
+    ),
+    (
+        "TTS_ENGINE",
+        "system",
+        false,
+        false,
+        "Speech engine: 'system' (OS voice) or 'command' (TTS_COMMAND)",
+    ),
+    (
+        "TTS_COMMAND",
+        "",
+        false,
+        false,
+        "External TTS command line; {text}, {voice} and {rate} are replaced",
+    ),
+    (
+        "TTS_CHAT_ENABLED",
+        "true",
+        false,
+        false,
+        "Read chat messages (commands starting with ! are skipped)",
+    ),
+    (
+        "TTS_REWARDS_ENABLED",
+        "true",
+        false,
+        false,
+        "Read channel point redemptions and their input",
+    ),
+    (
+        "TTS_READ_USERNAME",
+        "true",
+        false,
+        false,
+        "Read the chatter's name before the message",
+    ),
+    (
+        "TTS_MAX_CHARS",
+        "140",
+        false,
+        false,
+        "Longest text read per message (the rest is cut)",
+    ),
+    (
+        "TTS_FILTER_LANGUAGES",
+        "en,ja",
+        false,
+        false,
+        "Word filter languages; matching messages are not read",
+    ),
+    (
+        "TTS_CHAT_VOICE",
+        "",
+        false,
+        false,
+        "Voice for chat (empty uses the engine default)",
+    ),
+    (
+        "TTS_CHAT_RATE",
+        "1.0",
+        false,
+        false,
+        "Speed for chat (0.5-3.0, 1.0 is normal)",
+    ),
+    (
+        "TTS_REWARD_VOICE",
+        "",
+        false,
+        false,
+        "Voice for redemptions (empty uses the engine default)",
+    ),
+    (
+        "TTS_REWARD_RATE",
+        "1.0",
+        false,
+        false,
+        "Speed for redemptions (0.5-3.0, 1.0 is normal)",
+    ),
+    (
+        "MIDI_ENABLED",
+        "false",
+        false,
+        false,
+        "Send MIDI notes/CC on mapped channel events",
+    ),
+    (
+        "MIDI_PORT_NAME",
+        "Cairo Overlay",
+        false,
+        false,
+        "MIDI output port (virtual port on macOS/Linux, e.g. loopMIDI on Windows)",
+    ),
+    (
+        "MIDI_CHANNEL",
+        "1",
+        false,
+        false,
+        "Default MIDI channel (1-16)",
+    ),
+    (
+        "LIGHTS_ENABLED",
+        "false",
+        false,
+        false,
+        "Flash Hue/WLED lights on mapped channel events",
+    ),
+    (
+        "LIGHTS_MIN_INTERVAL_SECONDS",
+        "10",
+        false,
+        false,
+        "Minimum seconds between the starts of two light flashes",
+    ),
+    (
+        "LIGHTS_HUE_BRIDGE_URL",
+        "",
+        false,
+        false,
+        "Hue bridge URL (e.g. http://192.168.1.10)",
+    ),
+    (
+        "LIGHTS_HUE_USERNAME",
+        "",
+        true,
+        false,
+        "Hue bridge API username (set by pairing)",
+    ),
+    (
+        "LIGHTS_HUE_GROUP",
+        "0",
+        false,
+        false,
+        "Hue group (room) ID to flash; 0 is all lights",
+    ),
+    (
+        "LIGHTS_WLED_URL",
+        "",
+        false,
+        false,
+        "WLED controller URL (e.g. http://192.168.1.30)",
+    ),
+    (
+        "EMOTE_RAIN_ENABLED",
+        "false",
+        false,
+        false,
+        "Rain an emote on the overlay when chat spams it",
+    ),
+    (
+        "EMOTE_RAIN_THRESHOLD",
+        "5",
+        false,
+        false,
+        "Messages using the same emote needed to start a rain",
+    ),
+    (
+        "EMOTE_RAIN_WINDOW_SECONDS",
+        "10",
+        false,
+        false,
+        "Time window for counting emote uses",
+    ),
+    (
+        "EMOTE_RAIN_COOLDOWN_SECONDS",
+        "60",
+        false,
+        false,
+        "Default per-emote cooldown between rains",
+    ),
+    (
+        "QUOTES_ENABLED",
+        "false",
+        false,
+        false,
+        "Answer !quote chat commands",
+    ),
+    (
+        "QUOTES_ADD_MOD_ONLY",
+        "true",
+        false,
+        false,
+        "Only moderators and the broadcaster can use !quote add",
+    ),
+    (
+        "QUOTE_OF_THE_DAY_PRINT",
+        "false",
+        false,
+        false,
+        "Print a random quote when the stream goes live",
+    ),
+    (
+        "CHAT_COMMANDS_ENABLED",
+        "true",
+        false,
+        false,
+        "Answer the custom chat commands in /api/chat/commands",
+    ),
+    // --- First-time chatters ---
+    (
+        "FIRST_CHAT_NOTIFY",
+        "off",
+        false,
+        false,
+        "Notify first chat messages: off, ever (first message ever) or stream (also first message of each stream)",
+    ),
+    (
+        "FIRST_CHAT_PRINT",
+        "off",
+        false,
+        false,
+        "Print first chat messages: off, ever or stream",
+    ),
+    // --- Viewer consent ---
+    (
+        "CONSENT_DEFAULT_POLICY",
+        "allow",
+        false,
+        false,
+        "Whether viewers without a recorded choice are printed, read by TTS and credited (allow/deny)",
+    ),
+    (
+        "CONSENT_COMMANDS_ENABLED",
+        "false",
+        false,
+        false,
+        "Answer !optin / !optout chat commands",
+    ),
+    (
+        "CONSENT_OPT_IN_REWARD_ID",
+        "",
+        false,
+        false,
+        "Custom reward that opts the redeemer in to everything",
+    ),
+    (
+        "CONSENT_OPT_OUT_REWARD_ID",
+        "",
+        false,
+        false,
+        "Custom reward that opts the redeemer out of everything",
+    ),
+    // --- Funding goal ---
+    (
+        "FUNDING_GOAL_ENABLED",
+        "false",
+        false,
+        false,
+        "Count bits and gifted subs toward the funding goal",
+    ),
+    (
+        "FUNDING_GOAL_TITLE",
+        "",
+        false,
+        false,
+        "Funding goal title shown on the overlay",
+    ),
+    (
+        "FUNDING_GOAL_TARGET",
+        "10000",
+        false,
+        false,
+        "Funding goal target in the goal currency",
+    ),
+    (
+        "FUNDING_GOAL_CURRENCY",
+        "JPY",
+        false,
+        false,
+        "Currency code of the funding goal",
+    ),
+    (
+        "FUNDING_BIT_VALUE",
+        "1.4",
+        false,
+        false,
+        "Goal currency value of one bit",
+    ),
+    (
+        "FUNDING_GIFT_SUB_VALUE",
+        "700",
+        false,
+        false,
+        "Goal currency value of one gifted Tier 1 sub (Tier 2 counts 2x, Tier 3 5x)",
+    ),
+    (
+        "FUNDING_GOAL_SINCE",
+        "0",
+        false,
+        false,
+        "Unix time the funding goal counts from (set by reset)",
+    ),
+    // --- Ad breaks ---
+    (
+        "AD_BREAK_AUTOMATION_ENABLED",
+        "false",
+        false,
+        false,
+        "Pause prints, show a countdown and announce in chat during ad breaks",
+    ),
+    (
+        "AD_BREAK_PAUSE_PRINTS",
+        "true",
+        false,
+        false,
+        "Pause the print queue while an ad break runs",
+    ),
+    (
+        "AD_BREAK_CHAT_MESSAGE",
+        "広告が流れます。{duration}秒後に戻ります！",
+        false,
+        false,
+        "Chat message when an ad break starts ({duration}, {minutes}); empty to disable",
+    ),
+    (
+        "AD_BREAK_END_CHAT_MESSAGE",
+        "",
+        false,
+        false,
+        "Chat message when an ad break ends; empty to disable",
+    ),
+    (
+        "AD_BREAK_PRINT_BRB",
+        "false",
+        false,
+        false,
+        "Print a BRB card when an ad break starts",
+    ),
+    (
+        "AD_BREAK_BRB_TEXT",
+        "CMのあとすぐ戻ります（{duration}秒）",
+        false,
+        false,
+        "Text of the BRB card ({duration}, {minutes})",
+    ),
+    (
+        "EVENT_ARCHIVE_ENABLED",
+        "true",
+        false,
+        false,
+        "Store every raw EventSub notification for debugging and rebuilds",
+    ),
+    (
+        "EVENT_ARCHIVE_MAX_MB",
+        "50",
+        false,
+        false,
+        "Maximum archived EventSub payload size in MB (oldest pruned first)",
+    ),
+    // --- Clips ---
+    (
+        "CLIP_REWARD_ID",
+        "",
+        false,
+        false,
+        "Channel point reward that clips the live stream",
+    ),
+    // --- Viewer profiles ---
+    (
+        "PROFILE_PROVIDERS",
+        "helix,ivr,decapi",
+        false,
+        false,
+        "Profile lookup order (helix, ivr, decapi); later ones are fallbacks",
+    ),
+    (
+        "PROFILE_THIRD_PARTY_ENABLED",
+        "true",
+        false,
+        false,
+        "Allow profile lookups through third-party services (ivr, decapi)",
+    ),
+    (
+        "PROFILE_CACHE_HOURS",
+        "24",
+        false,
+        false,
+        "Hours a looked-up viewer profile is reused",
+    ),
+    // --- Word filter enforcement ---
+    (
+        "WORD_FILTER_ACTION",
+        "off",
+        false,
+        false,
+        "What to do with filtered chat messages: off, flag, redact or timeout",
+    ),
+    (
+        "WORD_FILTER_LANGUAGES",
+        "en,ja",
+        false,
+        false,
+        "Word filter languages checked against chat (comma-separated)",
+    ),
+    (
+        "WORD_FILTER_MIN_SEVERITY",
+        "low",
+        false,
+        false,
+        "Lowest word filter severity acted on in chat: low, medium or high",
+    ),
+    (
+        "WORD_FILTER_PATTERNS",
+        "",
+        false,
+        false,
+        "Extra filtered patterns, one regular expression per line (case-insensitive)",
+    ),
+    (
+        "WORD_FILTER_TIMEOUT_SECONDS",
+        "60",
+        false,
+        false,
+        "Timeout length for the timeout action",
+    ),
+    // --- IRC relay ---
+    (
+        "IRC_ENABLED",
+        "true",
+        false,
+        false,
+        "Keep a Twitch IRC connection open for chat in other channels",
+    ),
+    (
+        "IRC_CHANNELS",
+        "",
+        false,
+        false,
+        "Channels (logins, comma-separated) the IRC relay joins and records",
+    ),
+    // --- Rundown ---
+    (
+        "RUNDOWN_PRINT_ON_STREAM_START",
+        "false",
+        false,
+        false,
+        "Print today's rundown when the stream goes online",
+    ),
+    // --- Overlay URLs ---
+    (
+        "OVERLAY_TOKEN_SECRET",
+        "",
+        true,
+        false,
+        "Key signing overlay URL tokens (generated on first use)",
+    ),
+    (
+        "OVERLAY_SESSION_ID",
+        "",
+        true,
+        false,
+        "Current overlay session; rotated when the stream goes offline",
+    ),
+    (
+        "OVERLAY_SESSION_TTL_HOURS",
+        "12",
+        false,
+        false,
+        "Lifetime of session overlay tokens in hours (renewed while connected)",
+    ),
+    // --- Setup wizard ---
+    (
+        "SETUP_COMPLETED",
+        "false",
+        false,
+        false,
+        "Whether the first-run setup wizard was finished",
+    ),
+    (
+        "SETUP_COMPLETED_STEPS",
+        "",
+        false,
+        false,
+        "Comma-separated setup wizard steps recorded as done",
     ),
 ];
 
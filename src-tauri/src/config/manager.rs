@@ -1,12 +1,59 @@
 //! SettingsManager: DB-backed settings with defaults, migration, and feature status.
 
 use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
 
 use overlay_db::Database;
 
 use super::defaults::DEFAULT_SETTINGS;
 use super::validation::validate_setting;
-use super::{FeatureStatus, SettingInfo, SettingType};
+use super::{FeatureFlags, FeatureStatus, SettingInfo, SettingType};
+
+/// Read caches shared by every `SettingsManager`, keyed by
+/// [`Database::id`] so test and scratch databases keep their own.
+static SETTINGS_CACHE: LazyLock<RwLock<HashMap<u64, SettingsCache>>> =
+    LazyLock::new(Default::default);
+
+#[derive(Default)]
+struct SettingsCache {
+    /// Bumped by every write and invalidation. A value read from the DB is
+    /// only stored if the generation has not moved since before the read,
+    /// so a read racing a write never caches the old value.
+    generation: u64,
+    values: HashMap<String, String>,
+}
+
+fn cached_value(db: &Database, key: &str) -> Option<String> {
+    let caches = SETTINGS_CACHE.read().ok()?;
+    caches.get(&db.id())?.values.get(key).cloned()
+}
+
+fn generation_of(db: &Database) -> u64 {
+    SETTINGS_CACHE
+        .read()
+        .ok()
+        .and_then(|caches| caches.get(&db.id()).map(|c| c.generation))
+        .unwrap_or(0)
+}
+
+/// Store a value read under `generation`; dropped if a write came between.
+fn store_value(db: &Database, generation: u64, key: &str, value: &str) {
+    if let Ok(mut caches) = SETTINGS_CACHE.write() {
+        let cache = caches.entry(db.id()).or_default();
+        if cache.generation == generation {
+            cache.values.insert(key.to_string(), value.to_string());
+        }
+    }
+}
+
+/// Forget `key` of `db` after a write.
+fn forget_value(db: &Database, key: &str) {
+    if let Ok(mut caches) = SETTINGS_CACHE.write() {
+        let cache = caches.entry(db.id()).or_default();
+        cache.generation += 1;
+        cache.values.remove(key);
+    }
+}
 
 /// Wraps [`Database`] to provide high-level settings operations.
 pub struct SettingsManager {
@@ -18,15 +65,41 @@ impl SettingsManager {
         Self { db }
     }
 
+    /// Drop every cached value of every database so the next read goes to
+    /// the DB.
+    ///
+    /// Call this after writing settings without going through
+    /// [`SettingsManager`] (bulk imports, another process).
+    pub fn invalidate_cache() {
+        if let Ok(mut caches) = SETTINGS_CACHE.write() {
+            for cache in caches.values_mut() {
+                cache.generation += 1;
+                cache.values.clear();
+            }
+        }
+    }
+
+    /// Cache generation of this manager's database; moves on every write.
+    pub fn cache_generation(&self) -> u64 {
+        generation_of(&self.db)
+    }
+
     /// Get a setting value. Falls back to default if not in DB.
     pub fn get_setting(&self, key: &str) -> Result<String, anyhow::Error> {
-        if let Some(val) = self.db.get_setting(key)? {
+        if let Some(val) = cached_value(&self.db, key) {
             return Ok(val);
         }
-        if let Some(def) = DEFAULT_SETTINGS.get(key) {
-            return Ok(def.default.to_string());
-        }
-        anyhow::bail!("setting not found: {key}");
+
+        let generation = generation_of(&self.db);
+        let value = match self.db.get_setting(key)? {
+            Some(val) => val,
+            None => match DEFAULT_SETTINGS.get(key) {
+                Some(def) => def.default.to_string(),
+                None => anyhow::bail!("setting not found: {key}"),
+            },
+        };
+        store_value(&self.db, generation, key, &value);
+        Ok(value)
     }
 
     /// Set a setting value with validation.
@@ -39,7 +112,14 @@ impl SettingsManager {
             .map_err(|e| anyhow::anyhow!("validation error for {key}: {e}"))?;
 
         let type_str = if def.secret { "secret" } else { "normal" };
-        self.db.set_setting(key, value, type_str)?;
+        self.set_raw(key, value, type_str)
+    }
+
+    /// Store a value without validation, for keys outside the defaults
+    /// (e.g. cache settings) and resets to a default.
+    pub fn set_raw(&self, key: &str, value: &str, setting_type: &str) -> Result<(), anyhow::Error> {
+        self.db.set_setting(key, value, setting_type)?;
+        forget_value(&self.db, key);
         Ok(())
     }
 
@@ -99,7 +179,7 @@ impl SettingsManager {
                 continue;
             }
             let type_str = if def.secret { "secret" } else { "normal" };
-            self.db.set_setting(key, def.default, type_str)?;
+            self.set_raw(key, def.default, type_str)?;
         }
         Ok(())
     }
@@ -115,7 +195,7 @@ impl SettingsManager {
                 if !env_val.is_empty() {
                     let def = &DEFAULT_SETTINGS[key];
                     let type_str = if def.secret { "secret" } else { "normal" };
-                    self.db.set_setting(key, &env_val, type_str)?;
+                    self.set_raw(key, &env_val, type_str)?;
                     tracing::info!("Migrated setting from env: {key}");
                     migrated += 1;
                 }
@@ -141,6 +221,7 @@ impl SettingsManager {
             printer_connected: false,
             missing_settings: Vec::new(),
             warnings: Vec::new(),
+            features: FeatureFlags::default(),
         };
 
         // Twitch settings check
@@ -158,23 +239,60 @@ impl SettingsManager {
         }
 
         // Printer settings check
-        let printer_addr = self.get_setting("PRINTER_ADDRESS").unwrap_or_default();
-        if printer_addr.is_empty() {
-            status.missing_settings.push("PRINTER_ADDRESS".into());
+        let (printer_key, printer_target) =
+            if self.get_setting("PRINTER_BACKEND").unwrap_or_default() == "escpos" {
+                ("ESCPOS_DEVICE", self.get_setting("ESCPOS_DEVICE"))
+            } else if self.get_setting("PRINTER_TYPE").unwrap_or_default() == "usb" {
+                ("USB_PRINTER_NAME", self.get_setting("USB_PRINTER_NAME"))
+            } else {
+                ("PRINTER_ADDRESS", self.get_setting("PRINTER_ADDRESS"))
+            };
+        if printer_target.unwrap_or_default().is_empty() {
+            status.missing_settings.push(printer_key.into());
         } else {
             status.printer_configured = true;
         }
 
+        // Feature flags (hardware is checked by services::features)
+        let enabled = |key: &str| self.get_setting(key).unwrap_or_default() == "true";
+        status.features = FeatureFlags {
+            printer: status.printer_configured,
+            music: enabled("MUSIC_ENABLED"),
+            lottery: enabled("LOTTERY_ENABLED"),
+            translations: enabled("MIC_TRANSCRIPT_TRANSLATION_ENABLED"),
+            tts: enabled("TTS_ENABLED"),
+        };
+
         // Warnings
         if self.get_setting("DRY_RUN_MODE").unwrap_or_default() == "true" {
             status
                 .warnings
                 .push("DRY_RUN_MODE is enabled - no actual printing".into());
         }
+        if let Ok(Some(token)) = self.db.get_latest_token() {
+            let missing = twitch_client::missing_scopes(token.scope.split_whitespace());
+            if !missing.is_empty() {
+                status.warnings.push(format!(
+                    "Twitch token is missing scopes: {}; authenticate with Twitch again",
+                    missing.join(", ")
+                ));
+            }
+        }
 
         Ok(status)
     }
 
+    /// Stored values that fail validation, as `(key, error)` sorted by key.
+    pub fn invalid_settings(&self) -> Result<Vec<(String, String)>, anyhow::Error> {
+        let mut invalid: Vec<(String, String)> = self
+            .get_all_settings()?
+            .into_values()
+            .filter_map(|s| validate_setting(&s.key, &s.value).err().map(|e| (s.key, e)))
+            .collect();
+        invalid.sort();
+        Ok(invalid)
+    }
+
     #[allow(dead_code)]
     pub fn db(&self) -> &Database {
         &self.db
@@ -191,3 +309,46 @@ fn has_secret_in_env() -> bool {
     .iter()
     .any(|k| std::env::var(k).is_ok_and(|v| !v.is_empty()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_follows_writes_and_invalidation() {
+        let db = Database::open_in_memory().unwrap();
+        let sm = SettingsManager::new(db.clone());
+
+        assert_eq!(sm.get_setting("DRY_RUN_MODE").unwrap(), "true");
+        sm.set_setting("DRY_RUN_MODE", "false").unwrap();
+        assert_eq!(sm.get_setting("DRY_RUN_MODE").unwrap(), "false");
+
+        // Raw writes bypass the cache until it is invalidated.
+        db.set_setting("DRY_RUN_MODE", "true", "normal").unwrap();
+        assert_eq!(sm.get_setting("DRY_RUN_MODE").unwrap(), "false");
+        let before = sm.cache_generation();
+        SettingsManager::invalidate_cache();
+        assert!(sm.cache_generation() > before);
+        assert_eq!(sm.get_setting("DRY_RUN_MODE").unwrap(), "true");
+
+        // Writes to a different database leave this one's cache alone.
+        let other = SettingsManager::new(Database::open_in_memory().unwrap());
+        let before = sm.cache_generation();
+        other.set_setting("DRY_RUN_MODE", "false").unwrap();
+        assert_eq!(sm.cache_generation(), before);
+        assert_eq!(sm.get_setting("DRY_RUN_MODE").unwrap(), "true");
+        assert_eq!(other.get_setting("DRY_RUN_MODE").unwrap(), "false");
+    }
+
+    #[test]
+    fn test_read_racing_a_write_is_not_cached() {
+        let db = Database::open_in_memory().unwrap();
+        let sm = SettingsManager::new(db.clone());
+
+        // A read that started before a write stores nothing.
+        let generation = sm.cache_generation();
+        sm.set_raw("DRY_RUN_MODE", "false", "normal").unwrap();
+        store_value(&db, generation, "DRY_RUN_MODE", "true");
+        assert_eq!(sm.get_setting("DRY_RUN_MODE").unwrap(), "false");
+    }
+}
@@ -3,6 +3,7 @@
 pub mod app_config;
 pub mod defaults;
 pub mod manager;
+pub mod portable;
 pub mod validation;
 
 pub use app_config::AppConfig;
@@ -38,4 +39,20 @@ pub struct FeatureStatus {
     pub printer_connected: bool,
     pub missing_settings: Vec<String>,
     pub warnings: Vec<String>,
+    pub features: FeatureFlags,
+}
+
+/// Optional subsystems. A disabled subsystem starts no background tasks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureFlags {
+    /// Printer configured and found (keep-alive).
+    pub printer: bool,
+    /// `MUSIC_ENABLED` (library check).
+    pub music: bool,
+    /// `LOTTERY_ENABLED` (claim deadline checks).
+    pub lottery: bool,
+    /// `MIC_TRANSCRIPT_TRANSLATION_ENABLED` (chat translation store).
+    pub translations: bool,
+    /// `TTS_ENABLED` (speech worker).
+    pub tts: bool,
 }
@@ -0,0 +1,66 @@
+//! Portable mode and file paths kept in the database.
+//!
+//! A `portable.flag` file next to the executable turns on portable mode: the
+//! data directory (database, fonts, caches, music) is `data/` beside the
+//! binary, `.env` is read only from that folder and
+//! `TWITCH_OVERLAY_DATA_DIR` is ignored, so the whole setup can be carried
+//! between PCs on a USB stick. In every mode, paths written to the database
+//! are relative to the data directory (see [`to_stored`]).
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+pub const FLAG_FILE: &str = "portable.flag";
+pub const DATA_DIR: &str = "data";
+
+static ROOT: LazyLock<Option<PathBuf>> = LazyLock::new(|| {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?.to_path_buf();
+    dir.join(FLAG_FILE).is_file().then_some(dir)
+});
+
+/// Folder holding the executable, when running portable.
+pub fn root() -> Option<&'static Path> {
+    ROOT.as_deref()
+}
+
+pub fn is_enabled() -> bool {
+    root().is_some()
+}
+
+/// Form of `path` to write to the database: relative to `data_dir` with `/`
+/// separators when it lies inside it, unchanged otherwise.
+pub fn to_stored(data_dir: &Path, path: &Path) -> String {
+    match path.strip_prefix(data_dir) {
+        Ok(relative) => relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => path.to_string_lossy().into_owned(),
+    }
+}
+
+/// Path on disk for a stored path. Absolute paths written by older
+/// versions are used as they are.
+pub fn resolve(data_dir: &Path, stored: &str) -> PathBuf {
+    data_dir.join(stored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_paths() {
+        let data_dir = Path::new("/media/usb/data");
+        let track = data_dir.join("music").join("tracks").join("a.mp3");
+        let stored = to_stored(data_dir, &track);
+        assert_eq!(stored, "music/tracks/a.mp3");
+        assert_eq!(resolve(data_dir, &stored), track);
+
+        let outside = Path::new("/tmp/other.mp3");
+        assert_eq!(to_stored(data_dir, outside), "/tmp/other.mp3");
+        assert_eq!(resolve(data_dir, "/tmp/other.mp3"), outside);
+    }
+}
@@ -20,6 +20,187 @@ pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
                 return Err("must be 'bluetooth' or 'usb'".into());
             }
         }
+        "PRINTER_BACKEND" => {
+            if value != "catprinter" && value != "escpos" {
+                return Err("must be 'catprinter' or 'escpos'".into());
+            }
+        }
+        "PRINTERS" => {
+            crate::services::print_queue::parse_printers(value)?;
+        }
+        "PRINTER_ROUTES" => {
+            crate::services::print_queue::parse_routes(value)?;
+        }
+        "PRINT_LOCALE" => {
+            if image_processor::PrintLocale::parse(value).is_none() {
+                return Err(format!(
+                    "must be one of: {}",
+                    image_processor::PrintLocale::CODES.join(", ")
+                ));
+            }
+        }
+        "TIMEZONE" => {
+            if !value.is_empty() {
+                crate::services::cron::parse_timezone(value)?;
+            }
+        }
+        "CLOCK_CRON" | "PRINT_VOTE_CRON" => {
+            crate::services::cron::CronExpr::parse(value)?;
+        }
+        "EMOTE_PRINT_PENDING_POLICY" | "CONSENT_DEFAULT_POLICY" => {
+            if value != "allow" && value != "deny" {
+                return Err("must be 'allow' or 'deny'".into());
+            }
+        }
+        "SMART_PLUG_TYPE" => {
+            if value != "tasmota" && value != "homeassistant" {
+                return Err("must be 'tasmota' or 'homeassistant'".into());
+            }
+        }
+        "SMART_PLUG_URL" | "LIGHTS_HUE_BRIDGE_URL" | "LIGHTS_WLED_URL" => {
+            if !value.is_empty() && !value.starts_with("http://") && !value.starts_with("https://")
+            {
+                return Err("must start with http:// or https://".into());
+            }
+        }
+        "SMART_PLUG_WARMUP_SECONDS" => validate_int_range(value, 0, 120)?,
+        "HTTP_PROXY" => {
+            if !value.trim().is_empty() {
+                crate::services::http::parse_proxy(value.trim(), "")?;
+            }
+        }
+        "HTTP_TIMEOUT_SECONDS" => validate_int_range(value, 1, 300)?,
+        "PROFILE_PROVIDERS" => {
+            let names = crate::services::profiles::parse_providers(value);
+            if let Some(name) = names
+                .iter()
+                .find(|n| !crate::services::profiles::PROVIDERS.contains(&n.as_str()))
+            {
+                return Err(format!("unknown provider '{name}' (helix, ivr, decapi)"));
+            }
+        }
+        "PROFILE_CACHE_HOURS" => validate_int_range(value, 0, 24 * 30)?,
+        "IRC_CHANNELS" => {
+            use crate::services::irc_relay::{is_login, parse_channels};
+            if let Some(name) = parse_channels(value).iter().find(|n| !is_login(n)) {
+                return Err(format!("'{name}' is not a Twitch login name"));
+            }
+        }
+        "WORD_FILTER_ACTION" => {
+            use crate::services::chat_filter::ACTIONS;
+            if !ACTIONS.contains(&value) {
+                return Err(format!("must be one of: {}", ACTIONS.join(", ")));
+            }
+        }
+        "WORD_FILTER_MIN_SEVERITY" => {
+            use overlay_db::word_filter::SEVERITIES;
+            if !SEVERITIES.contains(&value) {
+                return Err(format!("must be one of: {}", SEVERITIES.join(", ")));
+            }
+        }
+        "WORD_FILTER_PATTERNS" => {
+            crate::services::chat_filter::parse_patterns(value)?;
+        }
+        "WORD_FILTER_TIMEOUT_SECONDS" => validate_int_range(value, 1, 1_209_600)?,
+        "OSC_HOST" | "OBS_HOST" => {
+            if value.trim().is_empty() {
+                return Err("must not be empty".into());
+            }
+        }
+        "OSC_PORT" | "OBS_PORT" => validate_int_range(value, 1, 65535)?,
+        "DISCORD_WEBHOOK_URL" => {
+            if !value.is_empty() && !value.starts_with("https://") {
+                return Err("must start with https://".into());
+            }
+        }
+        "TTS_ENGINE" => {
+            if value != crate::services::tts::ENGINE_SYSTEM
+                && value != crate::services::tts::ENGINE_COMMAND
+            {
+                return Err("must be 'system' or 'command'".into());
+            }
+        }
+        "TTS_MAX_CHARS" => validate_int_range(value, 1, 500)?,
+        "TTS_CHAT_RATE" | "TTS_REWARD_RATE" => {
+            use crate::services::tts::{MAX_RATE, MIN_RATE};
+            let v: f64 = value.parse().map_err(|_| "must be a number")?;
+            if !(MIN_RATE..=MAX_RATE).contains(&v) {
+                return Err(format!("must be between {MIN_RATE} and {MAX_RATE}"));
+            }
+        }
+        "PRINT_VOTE_MIN_VOTES" => validate_int_range(value, 1, 1000)?,
+        "PRINT_VOTE_KEYWORD" => {
+            if value.is_empty() || value.contains(char::is_whitespace) {
+                return Err("must be a single word".into());
+            }
+        }
+        "PANIC_FOLLOWER_ONLY_MINUTES" => validate_int_range(value, 0, 129600)?,
+        "PANIC_CHAT_MODE" => {
+            if !["followers", "subscribers"].contains(&value) {
+                return Err("must be 'followers' or 'subscribers'".into());
+            }
+        }
+        "PRE_SHOW_MINUTES" => validate_int_range(value, 1, 120)?,
+        "PRE_SHOW_TITLE" => {
+            if value.chars().count() > 100 {
+                return Err("must be at most 100 characters".into());
+            }
+        }
+        "DISCORD_USERNAME" => {
+            if value.chars().count() > 80 {
+                return Err("must be at most 80 characters".into());
+            }
+        }
+        "EMOTE_PRIORITY_CHANNEL" => {
+            let login = value.trim();
+            if login.len() > 25 || !login.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err("must be a Twitch login name (letters, digits, _)".into());
+            }
+        }
+        "THIRD_PARTY_EMOTE_PROVIDERS" => {
+            use twitch_client::emote_providers::Provider;
+            for name in value.split(',').filter(|n| !n.trim().is_empty()) {
+                if Provider::parse(name).is_none() {
+                    return Err(format!(
+                        "unknown provider '{}' (7tv, bttv, ffz)",
+                        name.trim()
+                    ));
+                }
+            }
+        }
+        "SERVER_BIND_ADDRESS" => {
+            crate::server::access::parse_bind_address(value)?;
+        }
+        "SERVER_IP_ALLOWLIST" => {
+            crate::server::access::parse_allowlist(value)?;
+        }
+        "SERVER_PUBLIC_ROUTES" => {
+            if crate::server::access::PublicRoutes::parse(value).is_none() {
+                return Err("must be 'open' or 'allowlist'".into());
+            }
+        }
+        "MIDI_CHANNEL" => validate_int_range(value, 1, 16)?,
+        "LIGHTS_MIN_INTERVAL_SECONDS" => validate_int_range(value, 0, 3600)?,
+        "EMOTE_RAIN_THRESHOLD" => validate_int_range(value, 2, 100)?,
+        "EMOTE_RAIN_WINDOW_SECONDS" => validate_int_range(value, 1, 300)?,
+        "EMOTE_RAIN_COOLDOWN_SECONDS" => validate_int_range(value, 0, 3600)?,
+        "EVENT_ARCHIVE_MAX_MB" => validate_int_range(value, 1, 4096)?,
+        "FUNDING_GOAL_TARGET" | "FUNDING_BIT_VALUE" | "FUNDING_GIFT_SUB_VALUE" => {
+            let v: f64 = value.parse().map_err(|_| "must be a number")?;
+            if !v.is_finite() || v < 0.0 {
+                return Err("must be a non-negative number".into());
+            }
+        }
+        "FUNDING_GOAL_SINCE" => {
+            value
+                .parse::<i64>()
+                .map_err(|_| "must be a unix timestamp")?;
+        }
+        "FUNDING_GOAL_CURRENCY" => {
+            if value.len() != 3 || !value.chars().all(|c| c.is_ascii_uppercase()) {
+                return Err("must be a 3-letter currency code".into());
+            }
+        }
         "USB_PRINTER_NAME" => {
             if !value.is_empty() && value.len() > 255 {
                 return Err("printer name must be 1-255 characters".into());
@@ -53,12 +234,21 @@ pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
             }
         }
         "LOTTERY_DISPLAY_DURATION" => validate_int_range(value, 3, 15)?,
+        "LOTTERY_CLAIM_MINUTES" => validate_int_range(value, 1, 1440)?,
         "LOTTERY_ANIMATION_SPEED" => {
             let v: f64 = value.parse().map_err(|_| "must be a float")?;
             if !(0.5..=2.0).contains(&v) {
                 return Err("must be between 0.5 and 2.0".into());
             }
         }
+        "FIRST_CHAT_NOTIFY" | "FIRST_CHAT_PRINT" => {
+            if !crate::services::first_chat::SCOPES.contains(&value) {
+                return Err("must be 'off', 'ever' or 'stream'".into());
+            }
+        }
+        "LOTTERY_TICKET_FORMULA" => {
+            overlay_db::lottery_engine::TicketFormula::parse(value)?;
+        }
         "MIC_TRANSCRIPT_TRANSLATION_MODE" => {
             if value != "off" && value != "chrome" {
                 return Err("must be 'off' or 'chrome'".into());
@@ -87,6 +277,35 @@ pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
                 return Err("must be 'queue' or 'overwrite'".into());
             }
         }
+        "NOTIFICATION_PREEMPT_LEVEL" => {
+            if !["raid", "sub", "follow", "off"].contains(&value) {
+                return Err("must be 'raid', 'sub', 'follow', or 'off'".into());
+            }
+        }
+        "NOTIFICATION_MAX_QUEUED_CHAT"
+        | "NOTIFICATION_MAX_QUEUED_FOLLOW"
+        | "NOTIFICATION_MAX_QUEUED_SUB"
+        | "NOTIFICATION_MAX_QUEUED_RAID" => validate_int_range(value, 1, 100)?,
+        "NOTIFICATION_INTERACTION_MODE" => {
+            if !["normal", "click_through", "dismiss_on_click"].contains(&value) {
+                return Err("must be 'normal', 'click_through', or 'dismiss_on_click'".into());
+            }
+        }
+        "PRINT_QUOTA_PER_STREAM" => validate_int_range(value, 0, 1000)?,
+        "PRINT_COOLDOWN_MINUTES" => validate_int_range(value, 0, 1440)?,
+        "AFK_TIMEOUT_MINUTES" => validate_int_range(value, 1, 240)?,
+        "OVERLAY_SESSION_TTL_HOURS" => validate_int_range(value, 1, 72)?,
+        "MILESTONE_CHECK_INTERVAL" => validate_int_range(value, 30, 3600)?,
+        "MILESTONE_FOLLOWER_THRESHOLDS" | "MILESTONE_VIEWER_THRESHOLDS" => {
+            if value
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .any(|s| s.parse::<u64>().is_err())
+            {
+                return Err("must be comma-separated positive integers".into());
+            }
+        }
         "MIC_TRANSCRIPT_LINE_TTL_SECONDS" => validate_int_range(value, 1, 300)?,
         "MIC_TRANSCRIPT_LAST_TTL_SECONDS" => validate_int_range(value, 0, 300)?,
         // Boolean settings
@@ -116,15 +335,22 @@ fn is_boolean_setting(key: &str) -> bool {
             | "DITHER"
             | "AUTO_ROTATE"
             | "ROTATE_PRINT"
+            | "ESCPOS_CUT"
             | "KEEP_ALIVE_ENABLED"
             | "CLOCK_ENABLED"
             | "CLOCK_SHOW_ICONS"
+            | "PRINT_VOTE_ENABLED"
             | "DEBUG_OUTPUT"
             | "NOTIFICATION_ENABLED"
             | "REWARD_COUNT_ENABLED"
             | "LOTTERY_ENABLED"
             | "LOTTERY_LOCKED"
             | "LOTTERY_TICKER_ENABLED"
+            | "LOTTERY_RESULTS_PUBLIC"
+            | "LOTTERY_RESULTS_MASK_NAMES"
+            | "LOTTERY_WINNER_WHISPER"
+            | "LOTTERY_WINNER_ANNOUNCE"
+            | "LOTTERY_AUTO_REDRAW"
             | "TICKER_NOTICE_ENABLED"
             | "MUSIC_ENABLED"
             | "MUSIC_AUTO_PLAY"
@@ -142,6 +368,43 @@ fn is_boolean_setting(key: &str) -> bool {
             | "MIC_TRANSCRIPT_TRANSLATION_ENABLED"
             | "AUTO_DRY_RUN_WHEN_OFFLINE"
             | "WINDOW_FULLSCREEN"
+            | "AFK_ENABLED"
+            | "AFK_USE_OS_IDLE"
+            | "AFK_ANNOUNCE_ENABLED"
+            | "MILESTONES_ENABLED"
+            | "MILESTONE_CHAT_ENABLED"
+            | "MILESTONE_PRINT_ENABLED"
+            | "HYPE_TRAIN_PRINT_ENABLED"
+            | "PRINT_LIMIT_REPLY_ENABLED"
+            | "SMART_PLUG_ENABLED"
+            | "STREAM_MONITOR_OPEN_ON_STARTUP"
+            | "NOTIFICATION_ACTIONS_ENABLED"
+            | "STREAMERBOT_BRIDGE_ENABLED"
+            | "OSC_ENABLED"
+            | "OBS_ENABLED"
+            | "DISCORD_ENABLED"
+            | "PRE_SHOW_STOP_MUSIC_ON_LIVE"
+            | "TTS_ENABLED"
+            | "TTS_CHAT_ENABLED"
+            | "TTS_REWARDS_ENABLED"
+            | "TTS_READ_USERNAME"
+            | "MIDI_ENABLED"
+            | "LIGHTS_ENABLED"
+            | "EMOTE_RAIN_ENABLED"
+            | "QUOTES_ENABLED"
+            | "CHAT_COMMANDS_ENABLED"
+            | "QUOTES_ADD_MOD_ONLY"
+            | "QUOTE_OF_THE_DAY_PRINT"
+            | "CONSENT_COMMANDS_ENABLED"
+            | "FUNDING_GOAL_ENABLED"
+            | "AD_BREAK_AUTOMATION_ENABLED"
+            | "AD_BREAK_PAUSE_PRINTS"
+            | "AD_BREAK_PRINT_BRB"
+            | "PROFILE_THIRD_PARTY_ENABLED"
+            | "IRC_ENABLED"
+            | "EVENT_ARCHIVE_ENABLED"
+            | "SETUP_COMPLETED"
+            | "RUNDOWN_PRINT_ON_STREAM_START"
     )
 }
 
@@ -167,6 +430,54 @@ mod tests {
         assert!(validate_setting("PRINTER_ADDRESS", "").is_ok()); // empty is ok
     }
 
+    #[test]
+    fn test_valid_printer_backend() {
+        assert!(validate_setting("PRINTER_BACKEND", "catprinter").is_ok());
+        assert!(validate_setting("PRINTER_BACKEND", "escpos").is_ok());
+        assert!(validate_setting("PRINTER_BACKEND", "star").is_err());
+    }
+
+    #[test]
+    fn test_valid_printer_registry() {
+        assert!(validate_setting("PRINTERS", "[]").is_ok());
+        assert!(
+            validate_setting(
+                "PRINTERS",
+                r#"[{"name":"rewards","type":"bluetooth","address":"AA:BB:CC:DD:EE:FF"}]"#
+            )
+            .is_ok()
+        );
+        assert!(validate_setting("PRINTERS", r#"[{"name":"","type":"usb"}]"#).is_err());
+        assert!(validate_setting("PRINTER_ROUTES", r#"{"chat":"rewards"}"#).is_ok());
+        assert!(validate_setting("PRINTER_ROUTES", r#"{"raid":"rewards"}"#).is_err());
+        assert!(validate_setting("PRINTER_ROUTES", "chat=rewards").is_err());
+    }
+
+    #[test]
+    fn test_valid_server_access() {
+        assert!(validate_setting("SERVER_BIND_ADDRESS", "127.0.0.1").is_ok());
+        assert!(validate_setting("SERVER_BIND_ADDRESS", "localhost").is_err());
+        assert!(validate_setting("SERVER_IP_ALLOWLIST", "").is_ok());
+        assert!(validate_setting("SERVER_IP_ALLOWLIST", "192.168.1.0/24, 10.0.0.2").is_ok());
+        assert!(validate_setting("SERVER_IP_ALLOWLIST", "192.168.1.0/40").is_err());
+        assert!(validate_setting("SERVER_PUBLIC_ROUTES", "allowlist").is_ok());
+        assert!(validate_setting("SERVER_PUBLIC_ROUTES", "closed").is_err());
+    }
+
+    #[test]
+    fn test_valid_emote_priority_channel() {
+        assert!(validate_setting("EMOTE_PRIORITY_CHANNEL", "").is_ok());
+        assert!(validate_setting("EMOTE_PRIORITY_CHANNEL", "some_streamer42").is_ok());
+        assert!(validate_setting("EMOTE_PRIORITY_CHANNEL", "https://twitch.tv/x").is_err());
+    }
+
+    #[test]
+    fn test_valid_third_party_emote_providers() {
+        assert!(validate_setting("THIRD_PARTY_EMOTE_PROVIDERS", "").is_ok());
+        assert!(validate_setting("THIRD_PARTY_EMOTE_PROVIDERS", "7tv, BTTV,ffz").is_ok());
+        assert!(validate_setting("THIRD_PARTY_EMOTE_PROVIDERS", "7tv,twitch").is_err());
+    }
+
     #[test]
     fn test_valid_black_point() {
         assert!(validate_setting("BLACK_POINT", "0.5").is_ok());
@@ -175,4 +486,14 @@ mod tests {
         assert!(validate_setting("BLACK_POINT", "1.1").is_err());
         assert!(validate_setting("BLACK_POINT", "-0.1").is_err());
     }
+
+    #[test]
+    fn test_valid_smart_plug() {
+        assert!(validate_setting("SMART_PLUG_TYPE", "tasmota").is_ok());
+        assert!(validate_setting("SMART_PLUG_TYPE", "homeassistant").is_ok());
+        assert!(validate_setting("SMART_PLUG_TYPE", "shelly").is_err());
+        assert!(validate_setting("SMART_PLUG_URL", "").is_ok());
+        assert!(validate_setting("SMART_PLUG_URL", "http://192.168.1.20").is_ok());
+        assert!(validate_setting("SMART_PLUG_URL", "192.168.1.20").is_err());
+    }
 }
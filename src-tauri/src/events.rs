@@ -21,6 +21,7 @@ pub const MUSIC_CONTROL_COMMAND: &str = "music_control_command";
 pub const FAX_RECEIVED: &str = "fax_received";
 pub const EVENTSUB_EVENT: &str = "eventsub_event";
 pub const SAVE_WINDOW_POSITION: &str = "save_window_position";
+pub const OPEN_SETTINGS_TAB: &str = "open_settings_tab";
 
 // -- Payload types --
 
@@ -53,3 +54,8 @@ pub struct WindowPositionPayload {
     pub width: u32,
     pub height: u32,
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsTabPayload {
+    pub tab: String,
+}
@@ -1,31 +1,55 @@
-//! EventSub domain handlers (11 Twitch event types).
+//! EventSub domain handlers (24 Twitch event types).
 
 use serde_json::{Value, json};
 use twitch_client::eventsub;
+use twitch_client::hype_train::HypeTrainEvent;
 
 use crate::app::SharedState;
 use crate::events;
 use crate::eventsub_support::{
-    already_processed_redemption, enqueue_notification, non_empty, send_ws, str_field,
-    to_legacy_fragments, to_notification_fragments,
+    already_processed_redemption, enqueue_notification, enqueue_notification_with_actions,
+    non_empty, send_ws, str_field, to_legacy_fragments, to_notification_fragments,
 };
+use crate::notification;
 use crate::notification::types::NotificationType;
+use crate::services::discord;
+use crate::services::emotes;
+use crate::services::event_triggers::{self, ChannelEvent};
+use crate::services::hype_train;
+use crate::services::latency::{self, Stage};
+use crate::services::moderation_log;
+use overlay_db::polls::{self, Poll, PollChoice, Prediction, PredictionOutcome};
 
 pub async fn handle_event(state: &SharedState, event_type: &str, payload: &Value) {
     match event_type {
         eventsub::EVENT_CHAT_MESSAGE => handle_chat_message(state, payload).await,
-        eventsub::EVENT_STREAM_ONLINE => handle_stream_online(state, payload),
+        eventsub::EVENT_STREAM_ONLINE => handle_stream_online(state, payload).await,
         eventsub::EVENT_STREAM_OFFLINE => handle_stream_offline(state, payload),
         eventsub::EVENT_REWARD_REDEMPTION => handle_reward_redemption(state, payload).await,
         eventsub::EVENT_CHANNEL_CHEER => handle_cheer(state, payload).await,
         eventsub::EVENT_CHANNEL_FOLLOW => handle_follow(state, payload).await,
         eventsub::EVENT_CHANNEL_RAID => handle_raid(state, payload).await,
+        eventsub::EVENT_CHANNEL_RAID_OUTGOING => handle_raid_outgoing(state, payload),
         eventsub::EVENT_SHOUTOUT_RECEIVE => handle_shoutout(state, payload).await,
+        eventsub::EVENT_AD_BREAK_BEGIN => handle_ad_break_begin(state, payload).await,
         eventsub::EVENT_CHANNEL_SUBSCRIBE => handle_subscribe(state, payload).await,
         eventsub::EVENT_SUBSCRIPTION_GIFT => handle_subscription_gift(state, payload).await,
         eventsub::EVENT_SUBSCRIPTION_MESSAGE => {
             handle_subscription_message(state, payload).await;
         }
+        eventsub::EVENT_POLL_BEGIN => handle_poll(state, "begin", payload),
+        eventsub::EVENT_POLL_PROGRESS => handle_poll(state, "progress", payload),
+        eventsub::EVENT_POLL_END => handle_poll(state, "end", payload),
+        eventsub::EVENT_PREDICTION_BEGIN => handle_prediction(state, "begin", payload),
+        eventsub::EVENT_PREDICTION_PROGRESS => handle_prediction(state, "progress", payload),
+        eventsub::EVENT_PREDICTION_LOCK => handle_prediction(state, "lock", payload),
+        eventsub::EVENT_PREDICTION_END => handle_prediction(state, "end", payload),
+        eventsub::EVENT_HYPE_TRAIN_BEGIN => handle_hype_train(state, "begin", payload).await,
+        eventsub::EVENT_HYPE_TRAIN_PROGRESS => {
+            handle_hype_train(state, "progress", payload).await;
+        }
+        eventsub::EVENT_HYPE_TRAIN_END => handle_hype_train(state, "end", payload).await,
+        eventsub::EVENT_CHANNEL_MODERATE => moderation_log::record(state, payload),
         other => tracing::debug!(event_type = other, "Unhandled EventSub event type"),
     }
 }
@@ -37,13 +61,35 @@ async fn handle_chat_message(state: &SharedState, payload: &Value) {
         str_field(payload, &["chatter_user_name"]),
         str_field(payload, &["chatter_user_login"]),
     );
+    if notification::dedup::is_duplicate(
+        notification::dedup::Source::EventSub,
+        &message_id,
+        &username,
+        &str_field(payload, &["message", "text"]),
+    ) {
+        return;
+    }
+    // Past this point a redacted message is only seen masked; the
+    // moderation event keeps the original.
+    let verdict = crate::services::chat_filter::check(state, payload);
+    let redacted = verdict
+        .as_ref()
+        .filter(|v| v.redacts())
+        .map(|v| v.redact_payload(payload));
+    let original = payload;
+    let payload = redacted.as_ref().unwrap_or(original);
     let message_text = str_field(payload, &["message", "text"]);
     let message_fragments = payload
         .get("message")
         .and_then(|m| m.get("fragments"))
         .cloned()
         .unwrap_or(Value::Array(vec![]));
+    if user_id == str_field(payload, &["broadcaster_user_id"]) {
+        record_emote_use(state, &message_fragments).await;
+    }
     let fragments_json = message_fragments.to_string();
+    // Third-party emotes are resolved for display and printing only.
+    let display_fragments = emotes::resolve_fragments(&message_fragments);
 
     let msg = overlay_db::chat::ChatMessage {
         id: 0,
@@ -57,6 +103,11 @@ async fn handle_chat_message(state: &SharedState, payload: &Value) {
         translation_status: String::new(),
         translation_lang: String::new(),
         created_at: chrono::Utc::now().timestamp(),
+        badges_json: payload
+            .get("badges")
+            .map(Value::to_string)
+            .unwrap_or_default(),
+        color: str_field(payload, &["color"]),
     };
 
     match state.db().add_chat_message(&msg) {
@@ -64,37 +115,82 @@ async fn handle_chat_message(state: &SharedState, payload: &Value) {
             tracing::debug!(message_id, "Duplicate chat message ignored");
             return;
         }
-        Ok(true) => {}
+        Ok(true) => latency::mark(Stage::DbWrite),
         Err(e) => {
             tracing::warn!("Failed to save chat message: {e}");
         }
     }
 
-    let ws_payload = json!({
+    let mut ws_payload = json!({
         "username": username,
         "userId": user_id,
         "messageId": message_id,
         "message": message_text,
-        "fragments": to_legacy_fragments(&message_fragments),
+        "fragments": to_legacy_fragments(&display_fragments),
         "avatarUrl": "",
         "translation": "",
         "translationStatus": "",
         "translationLang": "",
         "timestamp": chrono::Utc::now().to_rfc3339(),
+        "consent": crate::services::consent::flags(state, &user_id),
     });
+    if let Some(verdict) = &verdict {
+        verdict.apply(&mut ws_payload);
+    }
     send_ws(state, "chat-message", ws_payload);
+    if let Some(verdict) = verdict {
+        crate::services::chat_filter::enforce(state, original, verdict);
+    }
+    crate::services::emote_rain::observe_message(state, &message_fragments).await;
+    crate::services::quotes::handle_chat_message(state, payload);
+    crate::services::chat_commands::handle_chat_message(state, payload);
+    crate::services::consent::handle_chat_message(state, payload);
+    crate::services::print_vote::handle_chat_message(state, payload);
+    crate::services::first_chat::handle_chat_message(state, payload).await;
+    crate::services::lottery_claims::handle_chat_message(state, payload);
+    if redacted.is_none() && str_field(payload, &["channel_points_custom_reward_id"]).is_empty() {
+        // Messages of redemptions are read with the redemption.
+        crate::services::tts::on_chat_message(state, &user_id, &username, &message_text);
+    }
+
+    if !user_id.is_empty() && user_id == state.config().await.twitch_user_id {
+        crate::services::afk::record_activity(state).await;
+    }
+
+    // Redemptions of the trigger reward arrive as chat messages carrying the
+    // reward ID; printing from here keeps the emote fragments.
+    let reward_id = str_field(payload, &["channel_points_custom_reward_id"]);
+    if !reward_id.is_empty() && reward_id == state.config().await.trigger_custom_reward_id {
+        let s = state.clone();
+        let (print_user_id, print_user) = (user_id.clone(), username.clone());
+        let print_fragments = display_fragments.clone();
+        let print_badges = payload.get("badges").cloned().unwrap_or(Value::Null);
+        tokio::spawn(async move {
+            if let Err(e) = crate::services::chat_print::print_redemption(
+                &s,
+                &print_user_id,
+                &print_user,
+                &print_badges,
+                &print_fragments,
+            )
+            .await
+            {
+                tracing::warn!("Failed to print redemption message: {e}");
+            }
+        });
+    }
 
     enqueue_notification(
         state,
         str_field(payload, &["chatter_user_name"]),
         str_field(payload, &["message", "text"]),
-        to_notification_fragments(&message_fragments),
+        to_notification_fragments(&display_fragments),
         NotificationType::Chat,
     )
     .await;
 }
 
-fn handle_stream_online(state: &SharedState, payload: &Value) {
+async fn handle_stream_online(state: &SharedState, payload: &Value) {
     let msg = json!({ "is_live": true, "payload": payload });
     send_ws(state, "stream_status_changed", msg.clone());
     send_ws(state, "stream_online", msg);
@@ -102,6 +198,37 @@ fn handle_stream_online(state: &SharedState, payload: &Value) {
         events::STREAM_STATUS_CHANGED,
         events::StreamStatusPayload { is_live: true },
     );
+    crate::services::print_rules::reset_usage().await;
+    crate::services::chat_timers::on_stream_online();
+    crate::services::pre_show::on_stream_online(state);
+    event_triggers::dispatch(
+        state,
+        ChannelEvent::new(
+            event_triggers::EVENT_STREAM_ONLINE,
+            str_field(payload, &["broadcaster_user_name"]),
+            0,
+        ),
+    );
+
+    let s = state.clone();
+    let session_payload = payload.clone();
+    let started_at =
+        time_field(payload, "started_at").unwrap_or_else(|| chrono::Utc::now().timestamp());
+    tokio::spawn(async move {
+        crate::services::stream_sessions::on_stream_online(&s, &session_payload, started_at).await;
+    });
+    let s = state.clone();
+    tokio::spawn(async move { crate::services::smart_plug::on_stream_online(&s).await });
+    let s = state.clone();
+    tokio::spawn(async move { crate::services::quotes::on_stream_online(&s).await });
+    let s = state.clone();
+    tokio::spawn(async move { crate::services::rundown::on_stream_online(&s).await });
+    let s = state.clone();
+    tokio::spawn(async move { crate::services::reward_groups::on_stream_online(&s).await });
+    let s = state.clone();
+    let login = str_field(payload, &["broadcaster_user_login"]);
+    let name = str_field(payload, &["broadcaster_user_name"]);
+    tokio::spawn(async move { discord::on_stream_online(&s, &login, &name).await });
 }
 
 fn handle_stream_offline(state: &SharedState, payload: &Value) {
@@ -112,6 +239,49 @@ fn handle_stream_offline(state: &SharedState, payload: &Value) {
         events::STREAM_STATUS_CHANGED,
         events::StreamStatusPayload { is_live: false },
     );
+    crate::server::api::segment::close_open_segment(state);
+    crate::services::overlay_tokens::on_stream_offline(state);
+    crate::services::chat_timers::on_stream_offline();
+    crate::services::pre_show::on_stream_offline(state);
+    let broadcaster = str_field(payload, &["broadcaster_user_name"]);
+    let url = discord::channel_url(&str_field(payload, &["broadcaster_user_login"]));
+    discord::notify(
+        state,
+        discord::EVENT_STREAM_OFFLINE,
+        &[("broadcaster", &broadcaster), ("url", &url)],
+    );
+    event_triggers::dispatch(
+        state,
+        ChannelEvent::new(
+            event_triggers::EVENT_STREAM_OFFLINE,
+            str_field(payload, &["broadcaster_user_name"]),
+            0,
+        ),
+    );
+
+    let s = state.clone();
+    tokio::spawn(async move { crate::services::stream_sessions::on_stream_offline(&s).await });
+    let s = state.clone();
+    tokio::spawn(async move { crate::services::smart_plug::on_stream_offline(&s).await });
+    let s = state.clone();
+    tokio::spawn(async move { crate::services::ad_break::end(&s).await });
+    let s = state.clone();
+    tokio::spawn(async move { crate::services::reward_groups::on_stream_offline(&s).await });
+}
+
+async fn handle_ad_break_begin(state: &SharedState, payload: &Value) {
+    let duration = payload
+        .get("duration_seconds")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    tracing::info!(
+        duration,
+        is_automatic = payload["is_automatic"].as_bool().unwrap_or(false),
+        "Ad break began"
+    );
+    if duration > 0 {
+        crate::services::ad_break::on_ad_break_begin(state, duration).await;
+    }
 }
 
 async fn handle_reward_redemption(state: &SharedState, payload: &Value) {
@@ -129,10 +299,19 @@ async fn handle_reward_redemption(state: &SharedState, payload: &Value) {
     );
 
     if !reward_id.is_empty() {
-        if let Err(e) = state.db().increment_reward_count(&reward_id, &user_name) {
-            tracing::warn!("Failed to increment reward count: {e}");
-        }
+        crate::services::reward_counts::on_redemption(state, &reward_id, &user_name);
     }
+    crate::services::consent::handle_redemption(state, payload);
+    crate::services::print_vote::handle_redemption(state, payload);
+    crate::services::lottery_claims::handle_redemption(state, payload);
+    crate::services::clips::handle_redemption(state, payload);
+    crate::services::tts::on_redemption(
+        state,
+        &str_field(payload, &["user_id"]),
+        &user_name,
+        &reward_title,
+        &str_field(payload, &["user_input"]),
+    );
 
     send_ws(
         state,
@@ -158,6 +337,11 @@ async fn handle_cheer(state: &SharedState, payload: &Value) {
         "ビッツありがとう".to_string()
     };
     send_ws(state, "cheer", payload.clone());
+    crate::services::funding::on_cheer(state, &username, bits);
+    event_triggers::dispatch(
+        state,
+        ChannelEvent::new(event_triggers::EVENT_CHEER, &username, bits as i64),
+    );
     enqueue_notification(state, username, message, vec![], NotificationType::Cheer).await;
 }
 
@@ -167,6 +351,10 @@ async fn handle_follow(state: &SharedState, payload: &Value) {
         str_field(payload, &["user_login"]),
     );
     send_ws(state, "follow", payload.clone());
+    event_triggers::dispatch(
+        state,
+        ChannelEvent::new(event_triggers::EVENT_FOLLOW, &username, 0),
+    );
     enqueue_notification(
         state,
         username,
@@ -188,8 +376,64 @@ async fn handle_raid(state: &SharedState, payload: &Value) {
     } else {
         "レイドありがとう".to_string()
     };
+    let raider_id = str_field(payload, &["from_broadcaster_user_id"]);
+    record_raid(state, overlay_db::raids::DIRECTION_IN, "from", payload);
+    let actions = if raider_id.is_empty() {
+        vec![]
+    } else {
+        vec![notification::actions::shoutout(raider_id)]
+    };
     send_ws(state, "raid", payload.clone());
-    enqueue_notification(state, username, message, vec![], NotificationType::Raid).await;
+    event_triggers::dispatch(
+        state,
+        ChannelEvent::new(event_triggers::EVENT_RAID, &username, viewers as i64),
+    );
+    let broadcaster = str_field(payload, &["to_broadcaster_user_name"]);
+    let url = discord::channel_url(&str_field(payload, &["to_broadcaster_user_login"]));
+    discord::notify(
+        state,
+        discord::EVENT_RAID,
+        &[
+            ("broadcaster", &broadcaster),
+            ("user", &username),
+            ("viewers", &viewers.to_string()),
+            ("url", &url),
+        ],
+    );
+    enqueue_notification_with_actions(
+        state,
+        username,
+        message,
+        vec![],
+        NotificationType::Raid,
+        actions,
+    )
+    .await;
+}
+
+fn handle_raid_outgoing(state: &SharedState, payload: &Value) {
+    record_raid(state, overlay_db::raids::DIRECTION_OUT, "to", payload);
+    send_ws(state, "raid_outgoing", payload.clone());
+}
+
+/// Store a raid; `side` is the payload prefix of the other channel.
+fn record_raid(state: &SharedState, direction: &str, side: &str, payload: &Value) {
+    let field = |name: &str| str_field(payload, &[&format!("{side}_broadcaster_user_{name}")]);
+    let channel_id = field("id");
+    if channel_id.is_empty() {
+        return;
+    }
+    let viewers = payload.get("viewers").and_then(|v| v.as_i64()).unwrap_or(0);
+    if let Err(e) = state.db().add_raid(
+        direction,
+        &channel_id,
+        &field("login"),
+        &field("name"),
+        viewers,
+        chrono::Utc::now().timestamp(),
+    ) {
+        tracing::warn!("Failed to record raid: {e}");
+    }
 }
 
 async fn handle_shoutout(state: &SharedState, payload: &Value) {
@@ -220,6 +464,14 @@ async fn handle_subscribe(state: &SharedState, payload: &Value) {
         format!("サブスクありがとう: Tier {tier}")
     };
     send_ws(state, "subscribe", payload.clone());
+    event_triggers::dispatch(
+        state,
+        ChannelEvent::new(
+            event_triggers::EVENT_SUBSCRIBE,
+            &username,
+            event_triggers::tier_amount(&tier),
+        ),
+    );
     enqueue_notification(
         state,
         username,
@@ -251,6 +503,11 @@ async fn handle_subscription_gift(state: &SharedState, payload: &Value) {
         format!("サブギフありがとう: Tier {tier}")
     };
     send_ws(state, "gift_sub", payload.clone());
+    crate::services::funding::on_gift_subs(state, &username, &tier, total);
+    event_triggers::dispatch(
+        state,
+        ChannelEvent::new(event_triggers::EVENT_GIFT_SUB, &username, total as i64),
+    );
     enqueue_notification(state, username, message, vec![], NotificationType::GiftSub).await;
 }
 
@@ -274,5 +531,172 @@ async fn handle_subscription_message(state: &SharedState, payload: &Value) {
         "サブスクありがとう".to_string()
     };
     send_ws(state, "resub", payload.clone());
+    event_triggers::dispatch(
+        state,
+        ChannelEvent::new(event_triggers::EVENT_RESUB, &username, months as i64),
+    );
     enqueue_notification(state, username, message, vec![], NotificationType::Resub).await;
 }
+
+/// Store the emotes the broadcaster sent for the recent group and emote
+/// search ranking.
+async fn record_emote_use(state: &SharedState, fragments: &Value) {
+    let used: Vec<(&str, &str)> = fragments
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|f| {
+            let id = f.get("emote")?.get("id")?.as_str()?;
+            let name = f.get("text").and_then(|t| t.as_str()).unwrap_or_default();
+            Some((id, name))
+        })
+        .collect();
+    if !used.is_empty() {
+        emotes::record_use(state, &used, chrono::Utc::now().timestamp()).await;
+    }
+}
+
+/// Unix seconds of an RFC 3339 payload field.
+fn time_field(payload: &Value, key: &str) -> Option<i64> {
+    let raw = payload.get(key)?.as_str()?;
+    chrono::DateTime::parse_from_rfc3339(raw)
+        .ok()
+        .map(|t| t.timestamp())
+}
+
+fn int_field(value: &Value, key: &str) -> i64 {
+    value.get(key).and_then(|v| v.as_i64()).unwrap_or(0)
+}
+
+/// `phase` is `begin`, `progress` or `end`; the end notification carries
+/// the final status (`completed`, `terminated`, `archived`).
+fn parse_poll(phase: &str, payload: &Value, now: i64) -> Poll {
+    let choices = payload
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .map(|choices| {
+            choices
+                .iter()
+                .map(|c| PollChoice {
+                    id: str_field(c, &["id"]),
+                    title: str_field(c, &["title"]),
+                    votes: int_field(c, "votes"),
+                    channel_points_votes: int_field(c, "channel_points_votes"),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let status = match phase {
+        "end" => non_empty(str_field(payload, &["status"]), "completed".to_string()),
+        _ => polls::STATUS_ACTIVE.to_string(),
+    };
+    Poll {
+        id: str_field(payload, &["id"]),
+        title: str_field(payload, &["title"]),
+        status,
+        choices,
+        started_at: time_field(payload, "started_at").unwrap_or(now),
+        ends_at: time_field(payload, "ends_at"),
+        ended_at: time_field(payload, "ended_at"),
+        updated_at: now,
+    }
+}
+
+/// `phase` is `begin`, `progress`, `lock` or `end`; the end notification
+/// carries the final status (`resolved`, `canceled`).
+fn parse_prediction(phase: &str, payload: &Value, now: i64) -> Prediction {
+    let outcomes = payload
+        .get("outcomes")
+        .and_then(|o| o.as_array())
+        .map(|outcomes| {
+            outcomes
+                .iter()
+                .map(|o| PredictionOutcome {
+                    id: str_field(o, &["id"]),
+                    title: str_field(o, &["title"]),
+                    color: str_field(o, &["color"]),
+                    users: int_field(o, "users"),
+                    channel_points: int_field(o, "channel_points"),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let status = match phase {
+        "lock" => polls::STATUS_LOCKED.to_string(),
+        "end" => non_empty(str_field(payload, &["status"]), "resolved".to_string()),
+        _ => polls::STATUS_ACTIVE.to_string(),
+    };
+    Prediction {
+        id: str_field(payload, &["id"]),
+        title: str_field(payload, &["title"]),
+        status,
+        outcomes,
+        winning_outcome_id: Some(str_field(payload, &["winning_outcome_id"]))
+            .filter(|id| !id.is_empty()),
+        started_at: time_field(payload, "started_at").unwrap_or(now),
+        locks_at: time_field(payload, "locks_at"),
+        locked_at: time_field(payload, "locked_at"),
+        ended_at: time_field(payload, "ended_at"),
+        updated_at: now,
+    }
+}
+
+fn handle_poll(state: &SharedState, phase: &str, payload: &Value) {
+    let poll = parse_poll(phase, payload, chrono::Utc::now().timestamp());
+    if poll.id.is_empty() {
+        return;
+    }
+    let poll = match state.db().upsert_poll(&poll) {
+        Ok(stored) => stored,
+        Err(e) => {
+            tracing::warn!("Failed to record poll: {e}");
+            poll
+        }
+    };
+    let total_votes: i64 = poll.choices.iter().map(|c| c.votes).sum();
+    send_ws(
+        state,
+        "poll_update",
+        json!({ "phase": phase, "poll": poll, "total_votes": total_votes }),
+    );
+}
+
+fn handle_prediction(state: &SharedState, phase: &str, payload: &Value) {
+    let prediction = parse_prediction(phase, payload, chrono::Utc::now().timestamp());
+    if prediction.id.is_empty() {
+        return;
+    }
+    let prediction = match state.db().upsert_prediction(&prediction) {
+        Ok(stored) => stored,
+        Err(e) => {
+            tracing::warn!("Failed to record prediction: {e}");
+            prediction
+        }
+    };
+    let total_users: i64 = prediction.outcomes.iter().map(|o| o.users).sum();
+    let total_points: i64 = prediction.outcomes.iter().map(|o| o.channel_points).sum();
+    send_ws(
+        state,
+        "prediction_update",
+        json!({
+            "phase": phase,
+            "prediction": prediction,
+            "total_users": total_users,
+            "total_points": total_points,
+        }),
+    );
+}
+
+async fn handle_hype_train(state: &SharedState, phase: &'static str, payload: &Value) {
+    let event = match HypeTrainEvent::from_payload(payload) {
+        Ok(event) => event,
+        Err(e) => {
+            tracing::warn!("Invalid Hype Train payload: {e}");
+            return;
+        }
+    };
+    send_ws(state, "hype_train", hype_train::update(phase, &event));
+    if phase == "end" {
+        hype_train::print_receipt(state, &event).await;
+    }
+}
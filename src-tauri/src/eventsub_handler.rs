@@ -3,15 +3,25 @@
 //! Waits for a valid token, connects to EventSub, and processes events
 //! by broadcasting to WebSocket clients and persisting to DB as needed.
 
+use std::sync::LazyLock;
 use std::time::Duration;
 
-use serde_json::json;
+use serde_json::{Value, json};
 use tokio::sync::mpsc;
 use tokio::time::sleep;
-use twitch_client::eventsub::{EventSubClient, EventSubConfig, EventSubEvent};
+use tracing::Instrument;
+use twitch_client::eventsub::{EventSubClient, EventSubConfig, EventSubEvent, EventSubMonitor};
 
 use crate::app::SharedState;
 use crate::events;
+use crate::services::latency::{self, Stage};
+
+/// Subscription health, shared by every connection this process makes.
+static MONITOR: LazyLock<EventSubMonitor> = LazyLock::new(EventSubMonitor::new);
+
+pub fn monitor() -> &'static EventSubMonitor {
+    &MONITOR
+}
 
 /// Start the EventSub handler loop.
 ///
@@ -48,7 +58,9 @@ pub async fn run(state: SharedState) {
 
         tracing::info!("Starting EventSub connection");
 
-        let config = EventSubConfig::with_all_events(client_id, access_token, broadcaster_id);
+        let config =
+            EventSubConfig::with_all_events(client_id, access_token, broadcaster_id, state.http())
+                .with_monitor(MONITOR.clone());
 
         match EventSubClient::connect(config).await {
             Ok((event_rx, _shutdown_tx)) => {
@@ -67,16 +79,34 @@ pub async fn run(state: SharedState) {
 /// Process events from the EventSub channel until it closes.
 async fn process_events(state: &SharedState, mut events: mpsc::Receiver<EventSubEvent>) {
     while let Some(event) = events.recv().await {
+        dispatch(state, &event.event_type, &event.payload).await;
+    }
+}
+
+/// Archive, forward, broadcast and handle one notification. Also used by
+/// demo mode to feed synthetic events.
+///
+/// Runs in an `eventsub` span with a correlation ID; see [`latency`].
+pub(crate) async fn dispatch(state: &SharedState, event_type: &str, payload: &Value) {
+    let trace = latency::Trace::start();
+    let span = tracing::info_span!("eventsub", trace_id = %trace.id, event_type);
+    let handled = trace.clone();
+    let pipeline = async {
+        crate::services::event_archive::record(state, event_type, payload);
+        crate::services::webhooks::forward(state, event_type, payload);
         // Always broadcast to WS clients
-        let payload = json!({
+        let message = json!({
             "type": "eventsub_event",
             "data": {
-                "event_type": &event.event_type,
-                "payload": &event.payload,
+                "event_type": event_type,
+                "payload": payload,
             }
         });
-        let _ = state.ws_sender().send(payload.to_string());
-        state.emit_event(events::EVENTSUB_EVENT, payload);
-        crate::eventsub_events::handle_event(state, &event.event_type, &event.payload).await;
-    }
+        let _ = state.ws_sender().send(message.to_string());
+        latency::mark(Stage::WsBroadcast);
+        state.emit_event(events::EVENTSUB_EVENT, message);
+        crate::eventsub_events::handle_event(state, event_type, payload).await;
+    };
+    latency::scope(trace, pipeline.instrument(span)).await;
+    handled.record(Stage::Handled);
 }
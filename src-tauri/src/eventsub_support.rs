@@ -8,7 +8,10 @@ use tokio::sync::RwLock;
 
 use crate::app::SharedState;
 use crate::notification::queue;
-use crate::notification::types::{ChatNotification, DisplayMode, FragmentInfo, NotificationType};
+use crate::notification::types::{
+    ChatNotification, DisplayMode, FragmentInfo, NotificationAction, NotificationType,
+};
+use crate::services::latency::{self, Stage};
 
 const REDEMPTION_CACHE_LIMIT: usize = 2000;
 
@@ -37,12 +40,37 @@ pub async fn already_processed_redemption(redemption_id: &str) -> bool {
 }
 
 pub async fn enqueue_notification(
+    state: &SharedState,
+    username: String,
+    message: String,
+    fragments: Vec<FragmentInfo>,
+    notification_type: NotificationType,
+) {
+    enqueue_notification_with_actions(
+        state,
+        username,
+        message,
+        fragments,
+        notification_type,
+        Vec::new(),
+    )
+    .await;
+}
+
+pub async fn enqueue_notification_with_actions(
     _state: &SharedState,
     username: String,
     message: String,
     fragments: Vec<FragmentInfo>,
     notification_type: NotificationType,
+    actions: Vec<NotificationAction>,
 ) {
+    // Chat keeps flowing during the pre-show; only alerts are held back.
+    if !matches!(notification_type, NotificationType::Chat)
+        && crate::services::pre_show::suppress_alert()
+    {
+        return;
+    }
     let notif = ChatNotification {
         username,
         message,
@@ -51,6 +79,8 @@ pub async fn enqueue_notification(
         color: None,
         display_mode: DisplayMode::Queue,
         notification_type,
+        actions,
+        trace: latency::current(),
     };
     if let Err(e) = queue::enqueue(notif).await {
         tracing::debug!("Notification queue is unavailable: {e}");
@@ -60,6 +90,7 @@ pub async fn enqueue_notification(
 pub fn send_ws(state: &SharedState, event_type: &str, data: impl serde::Serialize) {
     let msg = json!({ "type": event_type, "data": data });
     let _ = state.ws_sender().send(msg.to_string());
+    latency::mark(Stage::WsBroadcast);
 }
 
 pub fn str_field(value: &Value, path: &[&str]) -> String {
@@ -81,6 +112,15 @@ pub fn non_empty(primary: String, fallback: String) -> String {
     }
 }
 
+/// Image URL of an emote fragment: the `url` third-party emotes carry (see
+/// `services::emotes::resolve_fragments`), else the Twitch CDN.
+fn fragment_emote_url(item: &Value, id: &str) -> String {
+    match item["emote"]["url"].as_str() {
+        Some(url) => url.to_string(),
+        None => format!("https://static-cdn.jtvnw.net/emoticons/v2/{id}/static/light/2.0"),
+    }
+}
+
 pub fn to_notification_fragments(fragments: &Value) -> Vec<FragmentInfo> {
     let mut out = Vec::new();
     let Some(items) = fragments.as_array() else {
@@ -100,7 +140,7 @@ pub fn to_notification_fragments(fragments: &Value) -> Vec<FragmentInfo> {
                 .and_then(|v| v.as_str())
                 .unwrap_or_default()
                 .to_string();
-            let url = format!("https://static-cdn.jtvnw.net/emoticons/v2/{id}/static/light/2.0");
+            let url = fragment_emote_url(item, &id);
             out.push(FragmentInfo::Emote { id, url });
         } else {
             out.push(FragmentInfo::Text(text));
@@ -126,7 +166,7 @@ pub fn to_legacy_fragments(fragments: &Value) -> Vec<Value> {
                 .and_then(|e| e.get("id"))
                 .and_then(|v| v.as_str())
                 .unwrap_or_default();
-            let url = format!("https://static-cdn.jtvnw.net/emoticons/v2/{id}/static/light/2.0");
+            let url = fragment_emote_url(item, id);
             out.push(json!({
                 "type": "emote",
                 "text": text,
@@ -38,8 +38,13 @@ fn get_version() -> &'static str {
     "1.0.0"
 }
 
+const DB_FILE: &str = "local.db";
+
 /// Determine the data directory for the application.
 fn data_dir() -> PathBuf {
+    if let Some(root) = config::portable::root() {
+        return root.join(config::portable::DATA_DIR);
+    }
     if let Ok(dir) = std::env::var("TWITCH_OVERLAY_DATA_DIR") {
         return PathBuf::from(dir);
     }
@@ -48,8 +53,18 @@ fn data_dir() -> PathBuf {
         .join(".twitch-overlay")
 }
 
-/// Load .env from multiple candidate paths.
+/// Load .env from multiple candidate paths; in portable mode only from the
+/// folder holding the executable.
 fn load_dotenv() {
+    if let Some(root) = config::portable::root() {
+        let path = root.join(".env");
+        if dotenvy::from_path(&path).is_ok() {
+            tracing::info!("Portable mode: loaded .env from {}", path.display());
+        } else {
+            tracing::info!("Portable mode: no .env next to the executable");
+        }
+        return;
+    }
     let candidates = [".env", "../.env", "../../.env"];
     for path in &candidates {
         if dotenvy::from_filename(path).is_ok() {
@@ -65,12 +80,27 @@ pub fn init_foundation() -> Result<(Database, AppConfig, PathBuf), anyhow::Error
     load_dotenv();
     let dir = data_dir();
     std::fs::create_dir_all(&dir)?;
+    if config::portable::is_enabled() {
+        tracing::info!("Portable mode: data directory {}", dir.display());
+    }
+
+    let db = if services::demo::is_enabled() {
+        tracing::info!("Demo mode: using an in-memory database");
+        Database::open_in_memory()?
+    } else {
+        let db_path = dir.join(DB_FILE);
+        tracing::info!("Opening database at {}", db_path.display());
+        Database::open(&db_path)?
+    };
 
-    let db_path = dir.join("local.db");
-    tracing::info!("Opening database at {}", db_path.display());
-    let db = Database::open(&db_path)?;
+    let config = init_settings(&db)?;
+    tracing::info!("Settings loaded (port={})", config.server_port);
+    Ok((db, config, dir))
+}
 
-    if let Err(e) = seed_default_words(&db) {
+/// Steps 4-5: Word-filter defaults and settings on an open database.
+pub fn init_settings(db: &Database) -> Result<AppConfig, anyhow::Error> {
+    if let Err(e) = seed_default_words(db) {
         tracing::error!("Failed to seed word-filter defaults: {e}");
     }
 
@@ -92,9 +122,27 @@ pub fn init_foundation() -> Result<(Database, AppConfig, PathBuf), anyhow::Error
             );
         }
     }
+    Ok(config)
+}
 
-    tracing::info!("Settings loaded (port={})", config.server_port);
-    Ok((db, config, dir))
+/// `--check`: run the pre-flight checks without the GUI and return the
+/// process exit code (0 passed, 1 failed, 2 could not run). Release
+/// builds on Windows have no console, so redirect stdout to read the report.
+fn run_check() -> i32 {
+    load_dotenv();
+    let dir = data_dir();
+    let db_path = dir.join(DB_FILE);
+    let result = tauri::async_runtime::block_on(services::preflight::check(&db_path, dir));
+    match result {
+        Ok(report) => {
+            println!("{}", services::preflight::render(&report));
+            if report.passed { 0 } else { 1 }
+        }
+        Err(e) => {
+            eprintln!("Pre-flight check could not run: {e:#}");
+            2
+        }
+    }
 }
 
 /// Migrate legacy translation settings (ISO 639-3 → Chrome codes).
@@ -131,6 +179,12 @@ fn spawn_background_tasks(app: &mut tauri::App, state: app::SharedState) {
         window::position::restore_window_state(&main_window, state.db());
     }
 
+    // UI: Tray menu and stream monitor window
+    if let Err(e) = window::tray::install(app, state.clone()) {
+        tracing::warn!("Failed to install tray icon: {e}");
+    }
+    window::stream_monitor::restore_on_startup(&state);
+
     // Step 15: Web server
     let port = state.server_port();
     state.emit_event(
@@ -152,13 +206,9 @@ fn spawn_background_tasks(app: &mut tauri::App, state: app::SharedState) {
     let s = state.clone();
     tauri::async_runtime::spawn(async move { eventsub_handler::run(s).await });
 
-    // Step 10: Printer KeepAlive
-    let s = state.clone();
-    tauri::async_runtime::spawn(async move { background::printer_keepalive_loop(s).await });
-
-    // Print queue worker
+    // Service workers (shared with the headless server)
     let s = state.clone();
-    tauri::async_runtime::spawn(async move { services::print_queue::start_worker(s).await });
+    tauri::async_runtime::spawn(async move { background::spawn_workers(&s) });
 
     // Step 14: Notification
     let s = state.clone();
@@ -175,6 +225,10 @@ pub fn run() {
         .with(services::log_buffer::LogCaptureLayer::new())
         .init();
 
+    if std::env::args().any(|arg| arg == "--check") {
+        std::process::exit(run_check());
+    }
+
     // Steps 2-5: Foundation (fatal)
     let (db, config, dir) = init_foundation().expect("Failed to initialize");
     let shared_state = app::SharedState::new(db, config, dir);
@@ -187,7 +241,21 @@ pub fn run() {
             spawn_background_tasks(app, shared_state);
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![get_server_port, get_version])
+        .invoke_handler(tauri::generate_handler![
+            get_server_port,
+            get_version,
+            commands::window::window_status,
+            commands::window::show_main_window,
+            commands::window::hide_main_window,
+            commands::window::open_settings_tab,
+            commands::window::open_overlay_preview,
+            commands::window::close_overlay_preview,
+            commands::window::move_notification_window,
+            commands::window::toggle_stream_monitor,
+            commands::notification::dismiss_notification,
+            commands::notification::run_notification_action,
+            commands::system::validate,
+        ])
         .on_window_event(move |win, event| {
             use tauri::WindowEvent;
             match event {
@@ -0,0 +1,33 @@
+//! Notification action buttons (e.g. "Shoutout" on a raid alert).
+
+use crate::app::SharedState;
+use crate::services::shoutouts;
+
+use super::queue;
+use super::types::{NotificationAction, NotificationActionKind};
+
+/// Run an action requested from the notification window, then dismiss it.
+pub async fn run(_state: &SharedState, action: &NotificationAction) -> Result<(), String> {
+    if action.target_user_id.is_empty() {
+        return Err("target_user_id is required".into());
+    }
+
+    match action.action {
+        NotificationActionKind::Shoutout => {
+            let id = shoutouts::enqueue(&action.target_user_id, "", "notification").await;
+            tracing::info!(id, "Shoutout to {} queued", action.target_user_id);
+        }
+    }
+
+    queue::dismiss_current();
+    Ok(())
+}
+
+/// Shoutout button for a raid alert.
+pub fn shoutout(target_user_id: String) -> NotificationAction {
+    NotificationAction {
+        action: NotificationActionKind::Shoutout,
+        label: "シャウトアウト".to_string(),
+        target_user_id,
+    }
+}
@@ -0,0 +1,139 @@
+//! Cross-source deduplication of chat messages.
+//!
+//! The same chat line can reach the app more than once: EventSub redelivers
+//! notifications after a reconnect and Streamer.bot may forward a message
+//! EventSub already delivered. Every chat source checks here before it
+//! broadcasts or queues a notification.
+//!
+//! Messages are matched by `message_id` when both deliveries carry one.
+//! Otherwise the same user sending the same text within [`WINDOW`] counts
+//! as a duplicate, but only across different sources so that someone
+//! repeating themselves in chat is still shown.
+
+use std::collections::VecDeque;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a message without a matching ID is remembered.
+pub const WINDOW: Duration = Duration::from_secs(10);
+const CACHE_LIMIT: usize = 2000;
+
+/// Where a chat message came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    EventSub,
+    StreamerBot,
+}
+
+#[derive(Debug)]
+struct Seen {
+    source: Source,
+    message_id: String,
+    user: String,
+    text: String,
+    at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct Deduper {
+    entries: VecDeque<Seen>,
+}
+
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl Deduper {
+    /// Record a delivery; returns true if it duplicates an earlier one.
+    fn check(
+        &mut self,
+        source: Source,
+        message_id: &str,
+        user: &str,
+        text: &str,
+        now: Instant,
+    ) -> bool {
+        let user = user.trim().to_lowercase();
+        let text = normalize_text(text);
+        let duplicate = self.entries.iter().any(|seen| {
+            if !message_id.is_empty() && seen.message_id == message_id {
+                return true;
+            }
+            seen.source != source
+                && now.duration_since(seen.at) <= WINDOW
+                && !user.is_empty()
+                && seen.user == user
+                && seen.text == text
+        });
+        if duplicate {
+            return true;
+        }
+        self.entries.push_back(Seen {
+            source,
+            message_id: message_id.to_string(),
+            user,
+            text,
+            at: now,
+        });
+        while self.entries.len() > CACHE_LIMIT {
+            self.entries.pop_front();
+        }
+        false
+    }
+}
+
+static DEDUP: LazyLock<Mutex<Deduper>> = LazyLock::new(|| Mutex::new(Deduper::default()));
+
+/// Check a chat message from `source` and remember it. Returns true when
+/// it was already delivered and should be dropped.
+pub fn is_duplicate(source: Source, message_id: &str, user: &str, text: &str) -> bool {
+    let mut dedup = DEDUP.lock().unwrap_or_else(|e| e.into_inner());
+    let duplicate = dedup.check(source, message_id, user, text, Instant::now());
+    if duplicate {
+        tracing::debug!(?source, message_id, user, "Duplicate chat message dropped");
+    }
+    duplicate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_id_match() {
+        let mut dedup = Deduper::default();
+        let now = Instant::now();
+        assert!(!dedup.check(Source::EventSub, "m1", "Alice", "hi", now));
+        assert!(dedup.check(Source::EventSub, "m1", "Alice", "hi", now + WINDOW * 3));
+        assert!(!dedup.check(Source::EventSub, "m2", "Alice", "hi", now));
+    }
+
+    #[test]
+    fn test_cross_source_text_match() {
+        let mut dedup = Deduper::default();
+        let now = Instant::now();
+        assert!(!dedup.check(Source::EventSub, "m1", "Alice", "hello  world", now));
+        assert!(dedup.check(
+            Source::StreamerBot,
+            "",
+            "alice",
+            "hello world",
+            now + Duration::from_secs(2)
+        ));
+        // Same source, same text: a repeated message, not a duplicate.
+        assert!(!dedup.check(
+            Source::EventSub,
+            "m3",
+            "Alice",
+            "hello world",
+            now + Duration::from_secs(3)
+        ));
+        assert!(!dedup.check(
+            Source::StreamerBot,
+            "",
+            "alice",
+            "hello world",
+            now + WINDOW * 2
+        ));
+    }
+}
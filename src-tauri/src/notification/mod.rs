@@ -1,8 +1,11 @@
 //! Desktop notification system for Twitch events.
 //!
 //! Supports queue and overwrite display modes, multi-window rendering,
-//! and fragment-based content (text, emoji, emote).
+//! fragment-based content (text, emoji, emote), and mouse interaction modes
+//! with action buttons.
 
+pub mod actions;
+pub mod dedup;
 pub mod queue;
 pub mod types;
 pub mod window;
@@ -23,9 +26,7 @@ pub async fn initialize(state: &SharedState) {
         return;
     }
 
-    if let Err(e) = window::ensure_window(state) {
-        tracing::warn!("Failed to initialize notification window: {e}");
-    }
+    window::warm_pool(state);
 
     queue::start_worker(state.clone()).await;
     tracing::info!("Notification system initialized");
@@ -2,86 +2,237 @@
 //!
 //! Processes notifications sequentially (queue mode) or
 //! overwrites the current notification (overwrite mode).
+//!
+//! Pending alerts are ordered by [`Priority`] (raid > sub > follow > chat),
+//! oldest first within a class. Each class keeps at most
+//! `NOTIFICATION_MAX_QUEUED_<CLASS>` alerts; when a class is full its
+//! oldest alert is dropped, so a chat backlog never shows minutes late.
+//! An alert at or above `NOTIFICATION_PREEMPT_LEVEL` cuts short a
+//! lower-priority alert that is on screen.
 
+use std::collections::{HashMap, VecDeque};
 use std::sync::LazyLock;
 use std::time::Duration;
 
 use serde_json::{Value, json};
-use tokio::sync::{RwLock, mpsc};
-use tokio::time::sleep;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::{Instant, sleep, sleep_until};
 
 use crate::app::SharedState;
 use crate::config::SettingsManager;
+use crate::services::latency::Stage;
 
-use super::types::{ChatNotification, DisplayMode, FragmentInfo};
+use super::types::{ChatNotification, DisplayMode, FragmentInfo, Priority};
 use super::window;
 
-const QUEUE_CAPACITY: usize = 100;
 const DEFAULT_DURATION_SECS: u64 = 5;
 
-static NOTIF_TX: LazyLock<RwLock<Option<mpsc::Sender<ChatNotification>>>> =
-    LazyLock::new(|| RwLock::new(None));
+static QUEUE: LazyLock<Mutex<AlertQueue>> = LazyLock::new(|| Mutex::new(AlertQueue::default()));
+
+/// Wakes the worker when an alert is queued.
+static WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+/// Wakes the worker when the visible notification is dismissed early.
+static DISMISS: LazyLock<Notify> = LazyLock::new(Notify::new);
+/// Interrupts the visible notification for a higher-priority alert.
+static PREEMPT: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Queue limits, refreshed from settings by the worker.
+#[derive(Debug, Clone)]
+struct QueueLimits {
+    max_queued: HashMap<Priority, usize>,
+    /// Lowest priority allowed to interrupt; `None` disables preemption.
+    preempt_level: Option<Priority>,
+}
+
+impl Default for QueueLimits {
+    fn default() -> Self {
+        Self {
+            max_queued: Priority::ALL
+                .into_iter()
+                .map(|p| (p, default_max_queued(p)))
+                .collect(),
+            preempt_level: Some(Priority::Raid),
+        }
+    }
+}
+
+fn default_max_queued(priority: Priority) -> usize {
+    match priority {
+        Priority::Chat => 10,
+        Priority::Follow => 20,
+        Priority::Sub | Priority::Raid => 50,
+    }
+}
+
+#[derive(Debug)]
+struct Pending {
+    notification: ChatNotification,
+    priority: Priority,
+}
+
+#[derive(Debug, Default)]
+struct AlertQueue {
+    running: bool,
+    pending: VecDeque<Pending>,
+    limits: QueueLimits,
+    /// Priority of the alert on screen.
+    showing: Option<Priority>,
+    dropped: u64,
+}
+
+impl AlertQueue {
+    /// Queue an alert, dropping the oldest of its class when full. Returns
+    /// whether the alert on screen should be preempted.
+    fn push(&mut self, notification: ChatNotification) -> bool {
+        let priority = notification.notification_type.priority();
+        let max = self
+            .limits
+            .max_queued
+            .get(&priority)
+            .copied()
+            .unwrap_or_else(|| default_max_queued(priority))
+            .max(1);
+        while self
+            .pending
+            .iter()
+            .filter(|p| p.priority == priority)
+            .count()
+            >= max
+        {
+            let Some(oldest) = self.pending.iter().position(|p| p.priority == priority) else {
+                break;
+            };
+            self.pending.remove(oldest);
+            self.dropped += 1;
+            tracing::debug!(class = priority.as_str(), "Dropped stale notification");
+        }
+        self.pending.push_back(Pending {
+            notification,
+            priority,
+        });
+
+        self.limits
+            .preempt_level
+            .is_some_and(|level| priority >= level)
+            && self.showing.is_some_and(|showing| showing < priority)
+    }
+
+    /// Take the oldest alert of the highest priority class present, if it
+    /// is at least `min`.
+    fn pop(&mut self, min: Priority) -> Option<Pending> {
+        let top = self.pending.iter().map(|p| p.priority).max()?;
+        if top < min {
+            return None;
+        }
+        let index = self.pending.iter().position(|p| p.priority == top)?;
+        self.pending.remove(index)
+    }
+}
 
 /// Start the notification queue worker.
 pub async fn start_worker(state: SharedState) {
-    let (tx, rx) = mpsc::channel::<ChatNotification>(QUEUE_CAPACITY);
     {
-        let mut slot = NOTIF_TX.write().await;
-        *slot = Some(tx);
+        let mut queue = QUEUE.lock().await;
+        queue.running = true;
+        queue.limits = read_limits(&state);
     }
 
-    tokio::spawn(worker_loop(state, rx));
+    tokio::spawn(worker_loop(state));
     tracing::info!("Notification queue worker started");
 }
 
 /// Enqueue a notification for display.
 pub async fn enqueue(notification: ChatNotification) -> Result<(), String> {
-    let tx_guard = NOTIF_TX.read().await;
-    let tx = tx_guard
-        .as_ref()
-        .ok_or_else(|| "Notification queue not initialized".to_string())?;
-
-    tx.try_send(notification)
-        .map_err(|e| format!("Notification queue full or closed: {e}"))?;
+    let mut queue = QUEUE.lock().await;
+    if !queue.running {
+        return Err("Notification queue not initialized".to_string());
+    }
+    let preempt = queue.push(notification);
+    drop(queue);
 
+    WAKE.notify_one();
+    if preempt {
+        PREEMPT.notify_waiters();
+    }
     Ok(())
 }
 
+/// Drop every pending alert and hide the one on screen. Returns how many
+/// pending alerts were dropped.
+pub async fn clear() -> usize {
+    let dropped = {
+        let mut queue = QUEUE.lock().await;
+        let dropped = queue.pending.len();
+        queue.pending.clear();
+        dropped
+    };
+    dismiss_current();
+    dropped
+}
+
+/// Hide the currently visible notification before its timer expires
+/// (dismiss-on-click, or after an action button ran).
+pub fn dismiss_current() {
+    DISMISS.notify_waiters();
+}
+
 /// Worker loop — processes notifications based on display mode.
-async fn worker_loop(state: SharedState, mut rx: mpsc::Receiver<ChatNotification>) {
-    while let Some(notif) = rx.recv().await {
+async fn worker_loop(state: SharedState) {
+    loop {
+        let next = QUEUE.lock().await.pop(Priority::Chat);
+        let Some(mut current) = next else {
+            WAKE.notified().await;
+            continue;
+        };
         let (display_mode, duration) = read_settings(&state);
+        let duration = Duration::from_secs(duration);
 
-        match display_mode {
-            DisplayMode::Queue => {
-                show_notification(&state, &notif);
-                sleep(Duration::from_secs(duration)).await;
-                hide_notification(&state);
-                // Small gap between notifications
-                sleep(Duration::from_millis(200)).await;
-            }
-            DisplayMode::Overwrite => {
-                show_notification(&state, &notif);
-                // In overwrite mode, drain any pending notifications
-                // and show only the latest, resetting the timer
-                loop {
-                    match tokio::time::timeout(Duration::from_secs(duration), rx.recv()).await {
-                        Ok(Some(newer)) => {
-                            show_notification(&state, &newer);
-                            // Timer resets by continuing the loop
-                        }
-                        _ => {
-                            // Timeout or channel closed
-                            break;
+        {
+            let mut queue = QUEUE.lock().await;
+            queue.limits = read_limits(&state);
+            queue.showing = Some(current.priority);
+        }
+        show_notification(&state, &current.notification);
+        let mut deadline = Instant::now() + duration;
+
+        loop {
+            tokio::select! {
+                _ = sleep_until(deadline) => break,
+                _ = DISMISS.notified() => break,
+                _ = PREEMPT.notified() => {
+                    tracing::info!(
+                        class = current.priority.as_str(),
+                        "Notification preempted by a higher-priority alert"
+                    );
+                    break;
+                }
+                // In overwrite mode a newer alert of the same or higher
+                // priority replaces the visible one and resets the timer.
+                _ = WAKE.notified(), if display_mode == DisplayMode::Overwrite => {
+                    let newer = {
+                        let mut queue = QUEUE.lock().await;
+                        let newer = queue.pop(current.priority);
+                        if let Some(n) = &newer {
+                            queue.showing = Some(n.priority);
                         }
+                        newer
+                    };
+                    if let Some(newer) = newer {
+                        show_notification(&state, &newer.notification);
+                        current = newer;
+                        deadline = Instant::now() + duration;
                     }
                 }
-                hide_notification(&state);
             }
         }
-    }
 
-    tracing::info!("Notification queue worker stopped");
+        QUEUE.lock().await.showing = None;
+        hide_notification(&state);
+        if display_mode == DisplayMode::Queue {
+            // Small gap between notifications
+            sleep(Duration::from_millis(200)).await;
+        }
+    }
 }
 
 /// Read notification settings from DB.
@@ -102,9 +253,40 @@ fn read_settings(state: &SharedState) -> (DisplayMode, u64) {
     (display_mode, duration.max(1))
 }
 
+fn read_limits(state: &SharedState) -> QueueLimits {
+    let sm = SettingsManager::new(state.db().clone());
+    let max_queued = Priority::ALL
+        .into_iter()
+        .map(|p| {
+            let key = format!("NOTIFICATION_MAX_QUEUED_{}", p.as_str().to_uppercase());
+            let max = sm
+                .get_setting(&key)
+                .unwrap_or_default()
+                .parse()
+                .unwrap_or_else(|_| default_max_queued(p));
+            (p, max)
+        })
+        .collect();
+    let preempt = sm
+        .get_setting("NOTIFICATION_PREEMPT_LEVEL")
+        .unwrap_or_default();
+    let preempt_level = if preempt.is_empty() {
+        Some(Priority::Raid)
+    } else {
+        Priority::from_str_setting(&preempt)
+    };
+    QueueLimits {
+        max_queued,
+        preempt_level,
+    }
+}
+
 /// Send notification data to the frontend via Tauri emit + WS broadcast.
 fn show_notification(state: &SharedState, notif: &ChatNotification) {
     window::show(state);
+    if let Some(trace) = &notif.trace {
+        trace.record(Stage::NotificationDisplay);
+    }
 
     let legacy_data = to_legacy_notification_payload(state, notif);
     let legacy_ws = json!({
@@ -165,12 +347,30 @@ fn to_legacy_notification_payload(state: &SharedState, notif: &ChatNotification)
         .map(fragment_to_legacy)
         .collect::<Vec<_>>();
 
+    let actions = if window::actions_enabled(state.db()) {
+        notif
+            .actions
+            .iter()
+            .map(|a| {
+                json!({
+                    "action": a.action,
+                    "label": a.label,
+                    "targetUserId": a.target_user_id,
+                })
+            })
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+
     json!({
         "username": notif.username,
         "message": notif.message,
         "fragments": fragments,
         "fontSize": read_font_size(state),
         "avatarUrl": notif.avatar_url,
+        "interactionMode": window::interaction_mode(state.db()),
+        "actions": actions,
     })
 }
 
@@ -192,3 +392,73 @@ fn fragment_to_legacy(fragment: &FragmentInfo) -> Value {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::types::NotificationType;
+
+    fn alert(username: &str, notification_type: NotificationType) -> ChatNotification {
+        ChatNotification {
+            username: username.to_string(),
+            message: String::new(),
+            fragments: vec![],
+            avatar_url: None,
+            color: None,
+            display_mode: DisplayMode::Queue,
+            notification_type,
+            actions: vec![],
+            trace: None,
+        }
+    }
+
+    fn pop_name(q: &mut AlertQueue) -> Option<String> {
+        q.pop(Priority::Chat).map(|p| p.notification.username)
+    }
+
+    #[test]
+    fn test_priority_order() {
+        let mut q = AlertQueue::default();
+        q.push(alert("chat1", NotificationType::Chat));
+        q.push(alert("follow", NotificationType::Follow));
+        q.push(alert("chat2", NotificationType::Chat));
+        q.push(alert("raid", NotificationType::Raid));
+        q.push(alert("sub", NotificationType::Resub));
+
+        assert_eq!(pop_name(&mut q).as_deref(), Some("raid"));
+        assert_eq!(pop_name(&mut q).as_deref(), Some("sub"));
+        assert_eq!(pop_name(&mut q).as_deref(), Some("follow"));
+        assert!(q.pop(Priority::Follow).is_none());
+        assert_eq!(pop_name(&mut q).as_deref(), Some("chat1"));
+        assert_eq!(pop_name(&mut q).as_deref(), Some("chat2"));
+    }
+
+    #[test]
+    fn test_stale_alerts_dropped() {
+        let mut q = AlertQueue::default();
+        q.limits.max_queued.insert(Priority::Chat, 2);
+        for name in ["a", "b", "c"] {
+            q.push(alert(name, NotificationType::Chat));
+        }
+        q.push(alert("follow", NotificationType::Follow));
+        assert_eq!(q.dropped, 1);
+        assert_eq!(pop_name(&mut q).as_deref(), Some("follow"));
+        assert_eq!(pop_name(&mut q).as_deref(), Some("b"));
+        assert_eq!(pop_name(&mut q).as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_preemption() {
+        let mut q = AlertQueue::default();
+        assert!(!q.push(alert("raid", NotificationType::Raid)));
+        q.showing = Some(Priority::Chat);
+        assert!(!q.push(alert("sub", NotificationType::Subscribe)));
+        assert!(q.push(alert("raid", NotificationType::Raid)));
+        q.showing = Some(Priority::Raid);
+        assert!(!q.push(alert("raid", NotificationType::Raid)));
+
+        q.limits.preempt_level = None;
+        q.showing = Some(Priority::Chat);
+        assert!(!q.push(alert("raid", NotificationType::Raid)));
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::services::latency::Trace;
+
 /// A chat notification to be displayed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatNotification {
@@ -12,6 +14,27 @@ pub struct ChatNotification {
     pub color: Option<String>,
     pub display_mode: DisplayMode,
     pub notification_type: NotificationType,
+    /// Buttons shown when `NOTIFICATION_ACTIONS_ENABLED` is on.
+    #[serde(default)]
+    pub actions: Vec<NotificationAction>,
+    /// Latency trace of the EventSub notification that raised this alert.
+    #[serde(skip)]
+    pub trace: Option<Trace>,
+}
+
+/// A button on a notification that calls back into the app.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationAction {
+    pub action: NotificationActionKind,
+    pub label: String,
+    pub target_user_id: String,
+}
+
+/// What a notification action button does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationActionKind {
+    Shoutout,
 }
 
 /// Fragment types for mixed content rendering.
@@ -46,6 +69,28 @@ impl DisplayMode {
     }
 }
 
+/// How the notification window reacts to the mouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionMode {
+    #[default]
+    Normal,
+    /// Mouse events pass through to whatever is behind the window.
+    ClickThrough,
+    /// Clicking the notification hides it and advances the queue.
+    DismissOnClick,
+}
+
+impl InteractionMode {
+    pub fn from_str_setting(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "click_through" => Self::ClickThrough,
+            "dismiss_on_click" => Self::DismissOnClick,
+            _ => Self::Normal,
+        }
+    }
+}
+
 /// Type of notification event.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -58,4 +103,46 @@ pub enum NotificationType {
     Cheer,
     Raid,
     Shoutout,
+    /// A viewer's first message ever or in this stream.
+    FirstChat,
+}
+
+/// Queue priority class; higher classes are shown first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Chat,
+    Follow,
+    Sub,
+    Raid,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 4] = [Self::Chat, Self::Follow, Self::Sub, Self::Raid];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::Follow => "follow",
+            Self::Sub => "sub",
+            Self::Raid => "raid",
+        }
+    }
+
+    pub fn from_str_setting(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.as_str().eq_ignore_ascii_case(s.trim()))
+    }
+}
+
+impl NotificationType {
+    pub fn priority(self) -> Priority {
+        match self {
+            Self::Chat => Priority::Chat,
+            Self::Follow | Self::Shoutout | Self::FirstChat => Priority::Follow,
+            Self::Subscribe | Self::GiftSub | Self::Resub | Self::Cheer => Priority::Sub,
+            Self::Raid => Priority::Raid,
+        }
+    }
 }
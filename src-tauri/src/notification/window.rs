@@ -1,18 +1,31 @@
 //! Notification window management.
 //!
-//! Creates and controls the dedicated notification `WebviewWindow`,
-//! restores its saved position, and persists changes on move/resize.
+//! Creates and controls the dedicated notification `WebviewWindow`s,
+//! restores their saved position, persists changes on move/resize, and
+//! applies the configured mouse interaction mode.
+//!
+//! Creating a webview takes long enough to delay the first alert, so a
+//! small pool of hidden windows is created at startup ([`warm_pool`]).
+//! Alerts reuse a pooled window (hiding never destroys it) and a window
+//! that disappears is replaced in the background. Time-to-display is
+//! measured for every alert and logged when it exceeds [`DISPLAY_BUDGET`].
+
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
 
 use overlay_db::Database;
 use serde::{Deserialize, Serialize};
 use tauri::{
-    Manager, PhysicalPosition, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent,
+    Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+    WindowEvent,
 };
 
 use crate::app::SharedState;
 use crate::config::SettingsManager;
 use crate::window::monitor;
 
+use super::types::InteractionMode;
+
 pub const NOTIFICATION_WINDOW_LABEL: &str = "twitch-chat-notification";
 
 const SETTING_X: &str = "NOTIFICATION_WINDOW_X";
@@ -23,11 +36,52 @@ const SETTING_WIDTH: &str = "NOTIFICATION_WINDOW_WIDTH";
 const SETTING_HEIGHT: &str = "NOTIFICATION_WINDOW_HEIGHT";
 const SETTING_SCREEN_INDEX: &str = "NOTIFICATION_WINDOW_SCREEN_INDEX";
 const SETTING_SCREEN_HASH: &str = "NOTIFICATION_WINDOW_SCREEN_HASH";
+const SETTING_INTERACTION_MODE: &str = "NOTIFICATION_INTERACTION_MODE";
+const SETTING_ACTIONS_ENABLED: &str = "NOTIFICATION_ACTIONS_ENABLED";
 
 const DEFAULT_WIDTH: u32 = 400;
 const DEFAULT_HEIGHT: u32 = 150;
 const DEFAULT_MARGIN: i32 = 20;
 
+/// Windows kept alive for alerts (the first uses `NOTIFICATION_WINDOW_LABEL`).
+const POOL_SIZE: usize = 2;
+/// Target time from an alert being dequeued to its window being visible.
+pub const DISPLAY_BUDGET: Duration = Duration::from_millis(100);
+
+/// Label of the pooled window currently showing an alert.
+static ACTIVE: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::new(None));
+static DISPLAY_STATS: LazyLock<Mutex<DisplayStats>> =
+    LazyLock::new(|| Mutex::new(DisplayStats::default()));
+
+/// Time-to-display measurements since startup.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DisplayStats {
+    pub count: u64,
+    pub last_ms: u64,
+    pub max_ms: u64,
+    pub total_ms: u64,
+    /// Alerts over [`DISPLAY_BUDGET`].
+    pub over_budget: u64,
+    /// Alerts that had to create a window (pool exhausted).
+    pub cold_starts: u64,
+}
+
+impl DisplayStats {
+    fn record(&mut self, elapsed: Duration, cold: bool) {
+        let ms = elapsed.as_millis() as u64;
+        self.count += 1;
+        self.last_ms = ms;
+        self.max_ms = self.max_ms.max(ms);
+        self.total_ms += ms;
+        if elapsed > DISPLAY_BUDGET {
+            self.over_budget += 1;
+        }
+        if cold {
+            self.cold_starts += 1;
+        }
+    }
+}
+
 /// Saved notification window position.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationPosition {
@@ -46,47 +100,150 @@ impl Default for NotificationPosition {
     }
 }
 
+fn pool_label(index: usize) -> String {
+    if index == 0 {
+        NOTIFICATION_WINDOW_LABEL.to_string()
+    } else {
+        format!("{NOTIFICATION_WINDOW_LABEL}-{index}")
+    }
+}
+
+fn pool_labels() -> impl Iterator<Item = String> {
+    (0..POOL_SIZE).map(pool_label)
+}
+
+fn create_window(state: &SharedState, label: &str) -> Result<WebviewWindow, String> {
+    let Some(app) = state.app_handle() else {
+        return Err("Tauri AppHandle is not initialized".to_string());
+    };
+    let (width, height) = load_size(state.db());
+    let window = WebviewWindowBuilder::new(&app, label, WebviewUrl::App("/notification".into()))
+        .title("Twitch Chat")
+        .visible(false)
+        .decorations(false)
+        .always_on_top(true)
+        .resizable(true)
+        .inner_size(width as f64, height as f64)
+        .build()
+        .map_err(|e| format!("Failed to create notification window: {e}"))?;
+
+    restore_layout(&window, state.db(), width, height);
+    install_event_handlers(&window, state.db().clone());
+
+    tracing::info!(label, "Notification window created");
+    Ok(window)
+}
+
 /// Ensure the notification window exists. Creates it in hidden state when missing.
 pub fn ensure_window(state: &SharedState) -> Result<WebviewWindow, String> {
     let Some(app) = state.app_handle() else {
         return Err("Tauri AppHandle is not initialized".to_string());
     };
-
     if let Some(existing) = app.get_webview_window(NOTIFICATION_WINDOW_LABEL) {
         return Ok(existing);
     }
+    create_window(state, NOTIFICATION_WINDOW_LABEL)
+}
 
-    let (width, height) = load_size(state.db());
-    let window = WebviewWindowBuilder::new(
-        &app,
-        NOTIFICATION_WINDOW_LABEL,
-        WebviewUrl::App("/notification".into()),
-    )
-    .title("Twitch Chat")
-    .visible(false)
-    .decorations(false)
-    .always_on_top(true)
-    .resizable(true)
-    .inner_size(width as f64, height as f64)
-    .build()
-    .map_err(|e| format!("Failed to create notification window: {e}"))?;
+/// Create every missing pooled window (hidden).
+pub fn warm_pool(state: &SharedState) {
+    let Some(app) = state.app_handle() else {
+        return;
+    };
+    let start = Instant::now();
+    let mut created = 0;
+    for label in pool_labels() {
+        if app.get_webview_window(&label).is_some() {
+            continue;
+        }
+        match create_window(state, &label) {
+            Ok(_) => created += 1,
+            Err(e) => tracing::warn!("Failed to pre-create notification window: {e}"),
+        }
+    }
+    if created > 0 {
+        tracing::info!(
+            created,
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "Notification window pool ready"
+        );
+    }
+}
 
-    restore_layout(&window, state.db(), width, height);
-    install_event_handlers(&window, state.db().clone());
+/// A pooled window for the next alert: the one already showing an alert,
+/// else the first live one. Creates a window only when the pool is empty.
+fn acquire(state: &SharedState) -> Result<(WebviewWindow, bool), String> {
+    let Some(app) = state.app_handle() else {
+        return Err("Tauri AppHandle is not initialized".to_string());
+    };
+    let active = ACTIVE.lock().ok().and_then(|a| a.clone());
+    let pooled = active
+        .and_then(|label| app.get_webview_window(&label))
+        .or_else(|| pool_labels().find_map(|label| app.get_webview_window(&label)));
+    match pooled {
+        Some(window) => Ok((window, false)),
+        None => create_window(state, NOTIFICATION_WINDOW_LABEL).map(|w| (w, true)),
+    }
+}
 
-    tracing::info!("Notification window created");
-    Ok(window)
+/// Apply the saved size and position; pooled windows other than the one
+/// the user last moved may be stale.
+fn sync_layout(window: &WebviewWindow, db: &Database) {
+    let (width, height) = load_size(db);
+    if window
+        .outer_size()
+        .is_ok_and(|s| (s.width, s.height) != (width, height))
+    {
+        let _ = window.set_size(PhysicalSize::new(width, height));
+    }
+    let saved = load_position(db);
+    if has_saved_coordinates(db)
+        && window
+            .outer_position()
+            .is_ok_and(|p| (p.x, p.y) != (saved.x, saved.y))
+    {
+        let _ = window.set_position(PhysicalPosition::new(saved.x, saved.y));
+    }
 }
 
-/// Show the notification window (creates it if needed).
+/// Show a notification window (from the pool) and record how long it took.
 pub fn show(state: &SharedState) {
-    match ensure_window(state) {
-        Ok(window) => {
-            if let Err(e) = window.show() {
-                tracing::warn!("Failed to show notification window: {e}");
-            }
+    let start = Instant::now();
+    let (window, cold) = match acquire(state) {
+        Ok(acquired) => acquired,
+        Err(e) => {
+            tracing::warn!("Failed to ensure notification window: {e}");
+            return;
         }
-        Err(e) => tracing::warn!("Failed to ensure notification window: {e}"),
+    };
+    sync_layout(&window, state.db());
+    let click_through = interaction_mode(state.db()) == InteractionMode::ClickThrough;
+    if let Err(e) = window.set_ignore_cursor_events(click_through) {
+        tracing::warn!("Failed to apply notification click-through: {e}");
+    }
+    if let Err(e) = window.show() {
+        tracing::warn!("Failed to show notification window: {e}");
+        return;
+    }
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active = Some(window.label().to_string());
+    }
+
+    let elapsed = start.elapsed();
+    if let Ok(mut stats) = DISPLAY_STATS.lock() {
+        stats.record(elapsed, cold);
+    }
+    let elapsed_ms = elapsed.as_millis() as u64;
+    if elapsed > DISPLAY_BUDGET {
+        tracing::warn!(elapsed_ms, cold, "Notification display exceeded budget");
+    } else {
+        tracing::debug!(elapsed_ms, "Notification displayed");
+    }
+
+    if cold {
+        // Refill the rest of the pool without delaying this alert further.
+        let s = state.clone();
+        tokio::spawn(async move { warm_pool(&s) });
     }
 }
 
@@ -95,7 +252,8 @@ pub fn hide(state: &SharedState) {
     let Some(app) = state.app_handle() else {
         return;
     };
-    let Some(window) = app.get_webview_window(NOTIFICATION_WINDOW_LABEL) else {
+    let active = ACTIVE.lock().ok().and_then(|mut a| a.take());
+    let Some(window) = active.and_then(|label| app.get_webview_window(&label)) else {
         return;
     };
 
@@ -105,6 +263,73 @@ pub fn hide(state: &SharedState) {
     }
 }
 
+/// Whether any pooled window is showing.
+pub fn is_visible(state: &SharedState) -> bool {
+    let Some(app) = state.app_handle() else {
+        return false;
+    };
+    pool_labels().any(|label| {
+        app.get_webview_window(&label)
+            .and_then(|w| w.is_visible().ok())
+            .unwrap_or(false)
+    })
+}
+
+/// Time-to-display measurements since startup.
+pub fn display_stats() -> DisplayStats {
+    DISPLAY_STATS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Move the notification window (creating it if needed) and persist the layout.
+///
+/// With `screen_index`, `x`/`y` are offsets from that screen's origin.
+pub fn move_to(
+    state: &SharedState,
+    x: i32,
+    y: i32,
+    screen_index: Option<usize>,
+) -> Result<NotificationPosition, String> {
+    let window = match ACTIVE.lock().ok().and_then(|a| a.clone()) {
+        Some(label) => state
+            .app_handle()
+            .and_then(|app| app.get_webview_window(&label))
+            .map_or_else(|| ensure_window(state), Ok)?,
+        None => ensure_window(state)?,
+    };
+    let (abs_x, abs_y) = match screen_index {
+        Some(index) => {
+            let screens = monitor::get_all_screens(&window.app_handle());
+            let screen = screens
+                .get(index)
+                .ok_or_else(|| format!("Screen {index} not found"))?;
+            (screen.x + x, screen.y + y)
+        }
+        None => (x, y),
+    };
+
+    window
+        .set_position(PhysicalPosition::new(abs_x, abs_y))
+        .map_err(|e| format!("Failed to move notification window: {e}"))?;
+    persist_current_layout(&window, state.db());
+    Ok(load_position(state.db()))
+}
+
+/// Configured mouse interaction mode for the notification window.
+pub fn interaction_mode(db: &Database) -> InteractionMode {
+    let sm = SettingsManager::new(db.clone());
+    InteractionMode::from_str_setting(&sm.get_setting(SETTING_INTERACTION_MODE).unwrap_or_default())
+}
+
+/// Whether action buttons are shown (never in click-through mode).
+pub fn actions_enabled(db: &Database) -> bool {
+    let sm = SettingsManager::new(db.clone());
+    interaction_mode(db) != InteractionMode::ClickThrough
+        && sm
+            .get_setting(SETTING_ACTIONS_ENABLED)
+            .unwrap_or_default()
+            .eq_ignore_ascii_case("true")
+}
+
 /// Load notification window position from DB.
 pub fn load_position(db: &Database) -> NotificationPosition {
     let sm = SettingsManager::new(db.clone());
@@ -276,3 +501,28 @@ fn load_saved_coordinate(
         parse_saved_i32(legacy)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_labels() {
+        let labels: Vec<String> = pool_labels().collect();
+        assert_eq!(labels[0], NOTIFICATION_WINDOW_LABEL);
+        assert_eq!(labels.len(), POOL_SIZE);
+        assert!(labels[1..].iter().all(|l| l != NOTIFICATION_WINDOW_LABEL));
+    }
+
+    #[test]
+    fn test_display_stats() {
+        let mut stats = DisplayStats::default();
+        stats.record(Duration::from_millis(20), false);
+        stats.record(Duration::from_millis(250), true);
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.max_ms, 250);
+        assert_eq!(stats.last_ms, 250);
+        assert_eq!(stats.over_budget, 1);
+        assert_eq!(stats.cold_starts, 1);
+    }
+}
@@ -0,0 +1,311 @@
+//! Network exposure of the HTTP server.
+//!
+//! `SERVER_BIND_ADDRESS` picks the listen address: `127.0.0.1` keeps the
+//! server on this PC, `0.0.0.0` opens it to the LAN. `SERVER_IP_ALLOWLIST`
+//! (comma-separated IPs or CIDRs) limits which remote clients may connect;
+//! loopback is always allowed and an empty list allows everyone.
+//!
+//! `SERVER_PUBLIC_ROUTES` controls the public routes — overlay pages, FAX
+//! images, the overlay WebSocket, `/status` and the lottery results page
+//! (`/present/results`): `open` serves them to any client that can reach
+//! the bind address, `allowlist` applies the list to them too. Admin routes
+//! (dashboard, `/api`, `/debug`, Streamer.bot) always go through the
+//! allowlist.
+//!
+//! WebSocket commands that change state need admin access granted
+//! explicitly: loopback clients and those in the allowlist have it, an
+//! empty allowlist grants it to no one else. Browsers may only open the
+//! WebSocket from this server's own pages, the app window or a local page
+//! (see [`is_allowed_origin`]).
+//!
+//! The settings are read when the server is (re)started, see
+//! [`super::restart_server`].
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::config::SettingsManager;
+
+/// Settings that only take effect after a server restart.
+pub const SETTING_KEYS: &[&str] = &[
+    "SERVER_BIND_ADDRESS",
+    "SERVER_IP_ALLOWLIST",
+    "SERVER_PUBLIC_ROUTES",
+];
+
+pub const DEFAULT_BIND_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+
+/// Path prefixes served to overlays and browser sources.
+const PUBLIC_PREFIXES: &[&str] = &["/overlay", "/fax/", "/ws", "/status", "/present/results"];
+
+/// An IP network, e.g. `192.168.1.0/24`. A bare address is a `/32` (`/128`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (value, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("invalid IP address: {value}"))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("invalid prefix length: {value}"))?,
+            None => max,
+        };
+        Ok(Self {
+            addr: addr.to_canonical(),
+            prefix,
+        })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+pub fn parse_bind_address(value: &str) -> Result<IpAddr, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| "must be an IP address such as 127.0.0.1 or 0.0.0.0".to_string())
+}
+
+/// Parse `SERVER_IP_ALLOWLIST`; empty entries are ignored.
+pub fn parse_allowlist(value: &str) -> Result<Vec<IpNet>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(IpNet::parse)
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicRoutes {
+    Open,
+    Allowlist,
+}
+
+impl PublicRoutes {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(Self::Open),
+            "allowlist" => Some(Self::Allowlist),
+            _ => None,
+        }
+    }
+}
+
+fn is_public_path(path: &str) -> bool {
+    PUBLIC_PREFIXES.iter().any(|p| path.starts_with(p))
+}
+
+/// Listen address from `SERVER_BIND_ADDRESS`.
+pub fn bind_address(sm: &SettingsManager) -> IpAddr {
+    let value = sm.get_setting("SERVER_BIND_ADDRESS").unwrap_or_default();
+    if value.trim().is_empty() {
+        return DEFAULT_BIND_ADDRESS;
+    }
+    parse_bind_address(&value).unwrap_or_else(|e| {
+        tracing::warn!("SERVER_BIND_ADDRESS {e}; using {DEFAULT_BIND_ADDRESS}");
+        DEFAULT_BIND_ADDRESS
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessPolicy {
+    allowlist: Vec<IpNet>,
+    public_routes: PublicRoutes,
+}
+
+impl AccessPolicy {
+    /// Read the policy from settings. Invalid values are logged and treated
+    /// as unset so a typo cannot lock the dashboard out.
+    pub fn load(sm: &SettingsManager) -> Self {
+        let allowlist = parse_allowlist(&sm.get_setting("SERVER_IP_ALLOWLIST").unwrap_or_default())
+            .unwrap_or_else(|e| {
+                tracing::warn!("Ignoring SERVER_IP_ALLOWLIST: {e}");
+                Vec::new()
+            });
+        let public_routes =
+            PublicRoutes::parse(&sm.get_setting("SERVER_PUBLIC_ROUTES").unwrap_or_default())
+                .unwrap_or(PublicRoutes::Open);
+        Self {
+            allowlist,
+            public_routes,
+        }
+    }
+
+    pub fn allows(&self, ip: IpAddr, path: &str) -> bool {
+        if self.allowlist.is_empty() || ip.to_canonical().is_loopback() {
+            return true;
+        }
+        if self.public_routes == PublicRoutes::Open && is_public_path(path) {
+            return true;
+        }
+        self.allowlist.iter().any(|net| net.contains(ip))
+    }
+
+    /// Whether `ip` was granted admin access explicitly: loopback or in the
+    /// allowlist.
+    pub fn grants_admin(&self, ip: IpAddr) -> bool {
+        ip.to_canonical().is_loopback() || self.allowlist.iter().any(|net| net.contains(ip))
+    }
+}
+
+/// Host part of an `Origin` or `Host` value, without scheme and port.
+fn origin_host(value: &str) -> &str {
+    let authority = value.split_once("://").map_or(value, |(_, rest)| rest);
+    let authority = authority.split('/').next().unwrap_or_default();
+    match authority.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    }
+}
+
+/// Whether a browser page at `origin` may open the WebSocket of the server
+/// reached as `host` (the `Host` header): the server's own pages, the app
+/// window (`tauri://localhost`, `http://tauri.localhost`) and pages served
+/// from this PC, such as the dev server.
+pub fn is_allowed_origin(origin: &str, host: &str) -> bool {
+    let origin_authority = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    if origin_authority.eq_ignore_ascii_case(host) {
+        return true;
+    }
+    let origin_host = origin_host(origin).to_ascii_lowercase();
+    matches!(origin_host.as_str(), "localhost" | "tauri.localhost")
+        || origin_host
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.to_canonical().is_loopback())
+}
+
+/// Whether the client was granted admin access (see
+/// [`AccessPolicy::grants_admin`]); added to every request by [`enforce`]
+/// so the overlay WebSocket can tell admin clients apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdminAccess(pub bool);
+
+/// Middleware rejecting clients the policy does not allow with 403.
+pub async fn enforce(
+    State(policy): State<Arc<AccessPolicy>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if let Some(ip) = peer
+        && !policy.allows(ip, req.uri().path())
+    {
+        tracing::debug!(%ip, path = req.uri().path(), "Rejected by IP allowlist");
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let admin = peer.is_some_and(|ip| policy.grants_admin(ip));
+    req.extensions_mut().insert(AdminAccess(admin));
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ipnet_contains() {
+        let lan = IpNet::parse("192.168.1.0/24").unwrap();
+        assert!(lan.contains(ip("192.168.1.42")));
+        assert!(lan.contains(ip("::ffff:192.168.1.42")));
+        assert!(!lan.contains(ip("192.168.2.1")));
+        assert!(IpNet::parse("10.0.0.5").unwrap().contains(ip("10.0.0.5")));
+        assert!(!IpNet::parse("10.0.0.5").unwrap().contains(ip("10.0.0.6")));
+        assert!(IpNet::parse("0.0.0.0/0").unwrap().contains(ip("8.8.8.8")));
+        assert!(IpNet::parse("fd00::/8").unwrap().contains(ip("fd12::1")));
+        assert!(!IpNet::parse("fd00::/8").unwrap().contains(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn test_parse_allowlist() {
+        assert!(parse_allowlist("").unwrap().is_empty());
+        assert_eq!(
+            parse_allowlist("10.0.0.1, 192.168.0.0/16,").unwrap().len(),
+            2
+        );
+        assert!(parse_allowlist("10.0.0.1/33").is_err());
+        assert!(parse_allowlist("obs-pc").is_err());
+        assert!(parse_bind_address("127.0.0.1").is_ok());
+        assert!(parse_bind_address("localhost").is_err());
+    }
+
+    #[test]
+    fn test_policy_allows() {
+        let mut policy = AccessPolicy {
+            allowlist: parse_allowlist("192.168.1.10").unwrap(),
+            public_routes: PublicRoutes::Open,
+        };
+        assert!(policy.allows(ip("127.0.0.1"), "/api/settings/v2"));
+        assert!(policy.allows(ip("192.168.1.10"), "/api/settings/v2"));
+        assert!(!policy.allows(ip("192.168.1.20"), "/api/settings/v2"));
+        assert!(!policy.allows(ip("192.168.1.20"), "/"));
+        assert!(policy.allows(ip("192.168.1.20"), "/overlay/"));
+        assert!(policy.allows(ip("192.168.1.20"), "/fax/abc/mono"));
+        assert!(policy.allows(ip("192.168.1.20"), "/present/results"));
+
+        policy.public_routes = PublicRoutes::Allowlist;
+        assert!(!policy.allows(ip("192.168.1.20"), "/overlay/"));
+        assert!(policy.allows(ip("::1"), "/overlay/"));
+
+        assert!(policy.grants_admin(ip("192.168.1.10")));
+        assert!(!policy.grants_admin(ip("192.168.1.20")));
+
+        policy.allowlist.clear();
+        assert!(policy.allows(ip("203.0.113.5"), "/api/settings/v2"));
+        assert!(!policy.grants_admin(ip("203.0.113.5")));
+        assert!(policy.grants_admin(ip("::1")));
+    }
+
+    #[test]
+    fn test_allowed_origin() {
+        let lan = "192.168.1.5:8080";
+        let local = "localhost:8080";
+        assert!(is_allowed_origin("http://192.168.1.5:8080", lan));
+        assert!(is_allowed_origin("tauri://localhost", local));
+        assert!(is_allowed_origin("http://tauri.localhost", lan));
+        assert!(is_allowed_origin("http://localhost:5173", local));
+        assert!(is_allowed_origin("http://[::1]:5173", lan));
+        assert!(!is_allowed_origin("https://evil.example", local));
+        assert!(!is_allowed_origin("http://192.168.1.9:8080", lan));
+        assert!(!is_allowed_origin("http://localhost.evil.example", local));
+        assert!(!is_allowed_origin("null", local));
+    }
+}
@@ -0,0 +1,97 @@
+//! Macro API (see `services::macros` for the step format):
+//!   GET    /api/actions/macros           – list macros
+//!   POST   /api/actions/macros           – create `{ name, steps }`
+//!   GET    /api/actions/macros/{id}      – one macro
+//!   PUT    /api/actions/macros/{id}      – replace `{ name, steps }`
+//!   DELETE /api/actions/macros/{id}      – delete
+//!   POST   /api/actions/macros/{id}/run  – run as a background job
+
+use axum::Json;
+use axum::extract::{Path, State};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::macros;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+fn parse_body(body: &Value) -> Result<(String, Value), (axum::http::StatusCode, Json<Value>)> {
+    let name = body["name"].as_str().unwrap_or_default().trim();
+    if name.is_empty() {
+        return Err(err_json(400, "name is required"));
+    }
+    macros::parse_steps(&body["steps"]).map_err(|e| err_json(400, &e))?;
+    Ok((name.to_string(), body["steps"].clone()))
+}
+
+/// GET /api/actions/macros
+pub async fn get_macros(State(state): State<SharedState>) -> ApiResult {
+    let macros = state
+        .db()
+        .get_macros()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "macros": macros })))
+}
+
+/// POST /api/actions/macros
+pub async fn create_macro(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let (name, steps) = parse_body(&body)?;
+    let created = state
+        .db()
+        .add_macro(&name, &steps, chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "success": true, "macro": created })))
+}
+
+/// GET /api/actions/macros/{id}
+pub async fn get_macro(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    match state.db().get_macro(id) {
+        Ok(Some(found)) => Ok(Json(json!(found))),
+        Ok(None) => Err(err_json(404, "Macro not found")),
+        Err(e) => Err(err_json(500, &e.to_string())),
+    }
+}
+
+/// PUT /api/actions/macros/{id}
+pub async fn update_macro(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let (name, steps) = parse_body(&body)?;
+    let updated = state
+        .db()
+        .update_macro(id, &name, &steps, chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !updated {
+        return Err(err_json(404, "Macro not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// DELETE /api/actions/macros/{id}
+pub async fn delete_macro(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let deleted = state
+        .db()
+        .delete_macro(id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Macro not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/actions/macros/{id}/run
+pub async fn run_macro(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let stored = state
+        .db()
+        .get_macro(id)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Macro not found"))?;
+    let job = macros::run(&state, &stored)
+        .await
+        .map_err(|e| err_json(409, &e))?;
+    Ok(Json(json!({ "success": true, "job": job })))
+}
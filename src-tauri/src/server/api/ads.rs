@@ -0,0 +1,69 @@
+//! Ad-break automation API:
+//!   GET    /api/ads/break  – current break and time left
+//!   POST   /api/ads/break  – start a break by hand `{ duration_seconds }`
+//!   DELETE /api/ads/break  – end the running break early
+//!   GET    /api/twitch/ads/schedule – next scheduled ad and snoozes left
+//!   POST   /api/twitch/ads/snooze   – push the next ad back 5 minutes
+
+use axum::Json;
+use axum::extract::State;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::ad_break;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// GET /api/ads/break
+pub async fn get_break() -> ApiResult {
+    Ok(Json(json!(ad_break::status().await)))
+}
+
+/// POST /api/ads/break
+pub async fn start_break(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let duration = body["duration_seconds"]
+        .as_u64()
+        .filter(|d| (1..=ad_break::MAX_DURATION_SECS).contains(d))
+        .ok_or_else(|| {
+            err_json(
+                400,
+                &format!(
+                    "duration_seconds must be between 1 and {}",
+                    ad_break::MAX_DURATION_SECS
+                ),
+            )
+        })?;
+    let status = ad_break::start(&state, duration, ad_break::SOURCE_MANUAL).await;
+    Ok(Json(json!({ "success": true, "status": status })))
+}
+
+/// DELETE /api/ads/break
+pub async fn end_break(State(state): State<SharedState>) -> ApiResult {
+    if !ad_break::end(&state).await {
+        return Err(err_json(404, "No ad break running"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// GET /api/twitch/ads/schedule
+///
+/// Fetched from Helix; the last known schedule when that fails.
+pub async fn get_schedule(State(state): State<SharedState>) -> ApiResult {
+    match ad_break::refresh_schedule(&state).await {
+        Ok(schedule) => Ok(Json(json!({ "schedule": schedule }))),
+        Err(e) => match ad_break::schedule().await {
+            Some(schedule) => Ok(Json(json!({ "schedule": schedule, "stale": true }))),
+            None => Err(err_json(502, &e)),
+        },
+    }
+}
+
+/// POST /api/twitch/ads/snooze
+pub async fn snooze(State(state): State<SharedState>) -> ApiResult {
+    let schedule = ad_break::snooze(&state)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    Ok(Json(json!({ "success": true, "schedule": schedule })))
+}
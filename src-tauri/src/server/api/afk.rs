@@ -0,0 +1,38 @@
+//! AFK (away from keyboard) overlay state API.
+
+use axum::Json;
+use axum::extract::State;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::afk;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// GET /api/overlay/afk
+pub async fn get_afk(State(state): State<SharedState>) -> ApiResult {
+    let status = afk::current_status(&state).await;
+    Ok(Json(json!(status)))
+}
+
+/// POST /api/overlay/afk
+///
+/// Body: `{ "afk": true | false | null }`. `null` clears the manual override
+/// and returns to automatic idle detection.
+pub async fn set_afk(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let afk = match body.get("afk") {
+        None | Some(Value::Null) => None,
+        Some(Value::Bool(b)) => Some(*b),
+        Some(_) => return Err(err_json(400, "afk must be true, false, or null")),
+    };
+    let status = afk::set_manual(&state, afk).await;
+    Ok(Json(json!({ "success": true, "status": status })))
+}
+
+/// POST /api/overlay/afk/activity – report host activity (hotkey tools etc.)
+pub async fn report_activity(State(state): State<SharedState>) -> ApiResult {
+    afk::record_activity(&state).await;
+    Ok(Json(json!({ "success": true })))
+}
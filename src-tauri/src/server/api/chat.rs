@@ -2,10 +2,16 @@
 
 use axum::Json;
 use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use image_processor::chat_render::ChatRenderOptions;
+use overlay_db::chat::ChatSearch;
 use serde::Deserialize;
 use serde_json::{Value, json};
 
 use crate::app::SharedState;
+use crate::services::chat_wall::{self, Selection};
+use crate::services::{chat_render, print_render, profiles, twitch_chat};
 
 use super::err_json;
 
@@ -39,6 +45,60 @@ pub async fn get_messages(
     ))
 }
 
+const SEARCH_DEFAULT_LIMIT: i64 = 50;
+const SEARCH_MAX_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: Option<String>,
+    pub user: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/chat/search?q=&user=&from=&to=&offset=&limit=
+///
+/// Full-text search over the stored chat. Every whitespace-separated term
+/// of `q` must appear in the message or author name; `user` is a user ID
+/// or name and `from`/`to` are unix seconds. Results are ranked by
+/// relevance; `total` counts matches across all pages.
+pub async fn search_messages(
+    State(state): State<SharedState>,
+    Query(q): Query<SearchQuery>,
+) -> ApiResult {
+    let query = q.q.as_deref().unwrap_or_default().trim().to_string();
+    if query.is_empty() {
+        return Err(err_json(400, "q is required"));
+    }
+    let search = ChatSearch {
+        query,
+        user: q
+            .user
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty()),
+        from: q.from,
+        to: q.to,
+        offset: q.offset.unwrap_or(0).max(0),
+        limit: q
+            .limit
+            .unwrap_or(SEARCH_DEFAULT_LIMIT)
+            .clamp(1, SEARCH_MAX_LIMIT),
+    };
+    let page = state
+        .db()
+        .full_text_search_chat(&search)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "query": search.query,
+        "results": page.hits,
+        "total": page.total,
+        "offset": search.offset,
+        "limit": search.limit,
+    })))
+}
+
 /// GET /api/chat/history (legacy compatibility endpoint)
 pub async fn get_history(
     State(state): State<SharedState>,
@@ -58,6 +118,46 @@ pub async fn get_history(
     Ok(Json(json!({ "messages": messages })))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct PostChatBody {
+    pub message: String,
+    /// Post as a highlighted announcement.
+    #[serde(default)]
+    pub announce: bool,
+    /// Announcement color (`primary`, `blue`, `green`, `orange`, `purple`).
+    pub color: Option<String>,
+    /// Send as an action (`/me`) over the IRC relay.
+    #[serde(default)]
+    pub action: bool,
+}
+
+/// POST /api/chat/send
+///
+/// Posts to the broadcaster's own channel as the broadcaster.
+pub async fn send_message(
+    State(state): State<SharedState>,
+    Json(body): Json<PostChatBody>,
+) -> ApiResult {
+    if body.message.trim().is_empty() {
+        return Err(err_json(400, "message is required"));
+    }
+    if body.announce && body.action {
+        return Err(err_json(400, "announce and action can't be combined"));
+    }
+    if body.color.is_some() && !body.announce {
+        return Err(err_json(400, "color is only used with announce"));
+    }
+    let sent = if body.announce {
+        twitch_chat::send_announcement(&state, &body.message, body.color.as_deref()).await
+    } else if body.action {
+        twitch_chat::send_action(&state, &body.message)
+    } else {
+        twitch_chat::send_chat(&state, &body.message).await
+    };
+    sent.map_err(|e| err_json(502, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
+
 /// POST /api/chat/cleanup
 pub async fn cleanup_messages(
     State(state): State<SharedState>,
@@ -69,6 +169,10 @@ pub async fn cleanup_messages(
         .db()
         .cleanup_chat_messages_before(cutoff)
         .map_err(|e| err_json(500, &e.to_string()))?;
+    state
+        .db()
+        .cleanup_irc_chat_messages_before(cutoff)
+        .map_err(|e| err_json(500, &e.to_string()))?;
     Ok(Json(
         json!({ "status": "ok", "message": format!("Cleaned up messages older than {hours}h") }),
     ))
@@ -85,3 +189,153 @@ pub async fn get_avatar(
         .map_err(|e| err_json(500, &e.to_string()))?;
     Ok(Json(json!({ "avatar_url": url })))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct ProfileQuery {
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// GET /api/chat/profile/:user_id
+pub async fn get_profile(
+    State(state): State<SharedState>,
+    axum::extract::Path(user_id): axum::extract::Path<String>,
+    Query(q): Query<ProfileQuery>,
+) -> ApiResult {
+    let profile = profiles::lookup(&state, &user_id, q.refresh)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    Ok(Json(json!(profile)))
+}
+
+/// DELETE /api/chat/profiles
+pub async fn clear_profiles(State(state): State<SharedState>) -> ApiResult {
+    let removed = state
+        .db()
+        .clear_viewer_profiles()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "status": "ok", "removed": removed })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenderQuery {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub scale: Option<u32>,
+}
+
+/// GET /api/chat/render?from=&to=&scale=
+///
+/// Renders the chat between `from` and `to` (unix seconds, `to` defaults
+/// to now) as a PNG with emotes, badges and name colors. `scale` (1–4,
+/// default 2) multiplies the 384px printer width. At most
+/// [`chat_render::MAX_MESSAGES`] messages are drawn.
+pub async fn render_chat(
+    State(state): State<SharedState>,
+    Query(q): Query<RenderQuery>,
+) -> Result<Response, (axum::http::StatusCode, Json<Value>)> {
+    let from = q.from.ok_or_else(|| err_json(400, "from is required"))?;
+    let to = q.to.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    if to < from {
+        return Err(err_json(400, "to must not be before from"));
+    }
+    let scale = q.scale.unwrap_or(2);
+    if !(1..=4).contains(&scale) {
+        return Err(err_json(400, "scale must be between 1 and 4"));
+    }
+
+    let messages = chat_render::load_messages(&state, from, to).map_err(|e| err_json(500, &e))?;
+    if messages.is_empty() {
+        return Err(err_json(404, "No chat messages in range"));
+    }
+    let img = chat_render::render(&state, &messages, ChatRenderOptions::scaled(scale))
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    let png = print_render::encode_png(&img).map_err(|e| err_json(500, &e))?;
+    Ok(([(header::CONTENT_TYPE, "image/png")], png).into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WallRequest {
+    #[serde(default)]
+    pub message_ids: Vec<String>,
+    pub hashtag: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub columns: Option<u32>,
+    pub title: Option<String>,
+}
+
+/// Validate a chat wall request and render its poster. Returns the poster
+/// and the number of messages on it.
+async fn build_wall(
+    state: &SharedState,
+    req: WallRequest,
+) -> Result<(image::DynamicImage, usize), (axum::http::StatusCode, Json<Value>)> {
+    let columns = req.columns.unwrap_or(chat_wall::DEFAULT_COLUMNS);
+    if !(1..=chat_wall::MAX_COLUMNS).contains(&columns) {
+        return Err(err_json(
+            400,
+            &format!("columns must be between 1 and {}", chat_wall::MAX_COLUMNS),
+        ));
+    }
+    let hashtag = req.hashtag.as_deref().map(str::trim).unwrap_or_default();
+    let selection = match (req.message_ids.is_empty(), hashtag.is_empty()) {
+        (false, true) => Selection::Ids(req.message_ids),
+        (true, false) => Selection::hashtag(hashtag, req.from, req.to),
+        _ => {
+            return Err(err_json(
+                400,
+                "Exactly one of message_ids or hashtag is required",
+            ));
+        }
+    };
+    let title = req.title.unwrap_or_else(|| match &selection {
+        Selection::Hashtag { tag, .. } => format!("#{tag}"),
+        Selection::Ids(_) => String::new(),
+    });
+
+    let messages = chat_wall::select_messages(state, &selection).map_err(|e| err_json(500, &e))?;
+    if messages.is_empty() {
+        return Err(err_json(404, "No matching chat messages"));
+    }
+    let poster = chat_wall::render_poster(state, &messages, &title, columns)
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    Ok((poster, messages.len()))
+}
+
+/// POST /api/chat/wall/preview
+///
+/// Renders a chat wall poster as one PNG without printing it. The body is
+/// the same as for `POST /api/chat/wall`.
+pub async fn preview_wall(
+    State(state): State<SharedState>,
+    Json(req): Json<WallRequest>,
+) -> Result<Response, (axum::http::StatusCode, Json<Value>)> {
+    let (poster, _) = build_wall(&state, req).await?;
+    let png = print_render::encode_png(&poster).map_err(|e| err_json(500, &e))?;
+    Ok(([(header::CONTENT_TYPE, "image/png")], png).into_response())
+}
+
+/// POST /api/chat/wall
+///
+/// Prints selected chat messages as a poster of `columns` (1–4, default 3)
+/// strips to be laid side by side. Messages are chosen by `message_ids`,
+/// or by `hashtag` between `from` and `to` (unix seconds, default the last
+/// 12 hours). `title` defaults to the hashtag. At most
+/// [`chat_wall::MAX_MESSAGES`] messages are used.
+pub async fn print_wall(
+    State(state): State<SharedState>,
+    Json(req): Json<WallRequest>,
+) -> ApiResult {
+    let (poster, messages) = build_wall(&state, req).await?;
+    let strips = chat_wall::print_poster(&state, &poster)
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({
+        "success": true,
+        "strips": strips,
+        "messages": messages,
+    })))
+}
@@ -0,0 +1,184 @@
+//! Chat command API (see `services::chat_commands`):
+//!   GET    /api/chat/commands        – list commands and the placeholders
+//!   POST   /api/chat/commands        – create
+//!                                      `{ trigger, response, cooldown_secs?, permission?, enabled? }`
+//!   PUT    /api/chat/commands/{id}   – replace (keeps the usage counters)
+//!   DELETE /api/chat/commands/{id}   – delete
+
+use axum::Json;
+use axum::extract::{Path, State};
+use overlay_db::chat_commands::{ChatCommand, ChatCommandInput};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::chat_commands::{PERMISSIONS, VARIABLES, built_in};
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// Twitch rejects longer chat messages.
+const MAX_RESPONSE_CHARS: usize = 500;
+const MAX_TRIGGER_CHARS: usize = 32;
+const MAX_COOLDOWN_SECS: i64 = 24 * 3600;
+
+struct CommandBody {
+    trigger: String,
+    response: String,
+    cooldown_secs: i64,
+    permission: String,
+    enabled: bool,
+}
+
+impl CommandBody {
+    fn input(&self) -> ChatCommandInput<'_> {
+        ChatCommandInput {
+            trigger: &self.trigger,
+            response: &self.response,
+            cooldown_secs: self.cooldown_secs,
+            permission: &self.permission,
+            enabled: self.enabled,
+        }
+    }
+}
+
+fn parse_body(
+    body: &Value,
+    built_in: &[String],
+) -> Result<CommandBody, (axum::http::StatusCode, Json<Value>)> {
+    let trigger = body["trigger"]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    let valid_trigger = trigger.starts_with(|c: char| c.is_ascii_punctuation())
+        && (2..=MAX_TRIGGER_CHARS).contains(&trigger.chars().count())
+        && !trigger.contains(char::is_whitespace);
+    if !valid_trigger {
+        return Err(err_json(
+            400,
+            &format!(
+                "trigger must be a prefix such as '!' and a word, at most {MAX_TRIGGER_CHARS} characters"
+            ),
+        ));
+    }
+    if built_in.contains(&trigger) {
+        return Err(err_json(400, &format!("{trigger} is a built-in command")));
+    }
+    let response = body["response"].as_str().unwrap_or_default().trim();
+    if response.is_empty() || response.chars().count() > MAX_RESPONSE_CHARS {
+        return Err(err_json(
+            400,
+            &format!("response must be 1-{MAX_RESPONSE_CHARS} characters"),
+        ));
+    }
+    let cooldown_secs = body["cooldown_secs"].as_i64().unwrap_or(0);
+    if !(0..=MAX_COOLDOWN_SECS).contains(&cooldown_secs) {
+        return Err(err_json(
+            400,
+            &format!("cooldown_secs must be 0-{MAX_COOLDOWN_SECS}"),
+        ));
+    }
+    let permission = body["permission"].as_str().unwrap_or("everyone");
+    if !PERMISSIONS.contains(&permission) {
+        return Err(err_json(
+            400,
+            &format!("permission must be one of {}", PERMISSIONS.join(", ")),
+        ));
+    }
+    Ok(CommandBody {
+        trigger,
+        response: response.to_string(),
+        cooldown_secs,
+        permission: permission.to_string(),
+        enabled: body["enabled"].as_bool().unwrap_or(true),
+    })
+}
+
+fn find(
+    state: &SharedState,
+    id: i64,
+) -> Result<ChatCommand, (axum::http::StatusCode, Json<Value>)> {
+    state
+        .db()
+        .get_chat_command(id)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Command not found"))
+}
+
+/// Reject a trigger another command (not `id`) already uses.
+fn check_unique(
+    state: &SharedState,
+    trigger: &str,
+    id: Option<i64>,
+) -> Result<(), (axum::http::StatusCode, Json<Value>)> {
+    let existing = state
+        .db()
+        .get_chat_command_by_trigger(trigger)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    match existing {
+        Some(other) if Some(other.id) != id => {
+            Err(err_json(409, &format!("{trigger} already exists")))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// GET /api/chat/commands
+pub async fn get_commands(State(state): State<SharedState>) -> ApiResult {
+    let commands = state
+        .db()
+        .get_chat_commands()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "commands": commands,
+        "variables": VARIABLES,
+        "permissions": PERMISSIONS,
+    })))
+}
+
+/// POST /api/chat/commands
+pub async fn create_command(
+    State(state): State<SharedState>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let parsed = parse_body(&body, &built_in(&state))?;
+    check_unique(&state, &parsed.trigger, None)?;
+    let id = state
+        .db()
+        .add_chat_command(&parsed.input(), chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let command = find(&state, id)?;
+    Ok(Json(json!({ "success": true, "command": command })))
+}
+
+/// PUT /api/chat/commands/{id}
+pub async fn update_command(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let parsed = parse_body(&body, &built_in(&state))?;
+    check_unique(&state, &parsed.trigger, Some(id))?;
+    let updated = state
+        .db()
+        .update_chat_command(id, &parsed.input(), chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !updated {
+        return Err(err_json(404, "Command not found"));
+    }
+    let command = find(&state, id)?;
+    Ok(Json(json!({ "success": true, "command": command })))
+}
+
+/// DELETE /api/chat/commands/{id}
+pub async fn delete_command(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let deleted = state
+        .db()
+        .delete_chat_command(id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Command not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
@@ -0,0 +1,178 @@
+//! Chat timer API (see `services::chat_timers`):
+//!   GET    /api/chat/timers            – list timers
+//!   POST   /api/chat/timers            – create
+//!                                        `{ name, messages, interval_minutes, min_chat_messages?, enabled?,
+//!                                           announce?, announce_color? }`
+//!   PUT    /api/chat/timers/{id}       – replace (keeps the rotation position)
+//!   DELETE /api/chat/timers/{id}       – delete
+//!   POST   /api/chat/timers/{id}/send  – post the next message now
+
+use axum::Json;
+use axum::extract::{Path, State};
+use overlay_db::chat_timers::{ChatTimer, ChatTimerInput};
+use serde_json::{Value, json};
+use twitch_client::api::ANNOUNCEMENT_COLORS;
+
+use crate::app::SharedState;
+use crate::services::chat_timers;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// Twitch rejects longer chat messages.
+const MAX_MESSAGE_CHARS: usize = 500;
+const MAX_INTERVAL_MINUTES: i64 = 24 * 60;
+const MAX_MIN_CHAT_MESSAGES: i64 = 1000;
+
+struct TimerBody {
+    name: String,
+    messages: Vec<String>,
+    interval_minutes: i64,
+    min_chat_messages: i64,
+    enabled: bool,
+    announce: bool,
+    announce_color: String,
+}
+
+impl TimerBody {
+    fn input(&self) -> ChatTimerInput<'_> {
+        ChatTimerInput {
+            name: &self.name,
+            messages: &self.messages,
+            interval_minutes: self.interval_minutes,
+            min_chat_messages: self.min_chat_messages,
+            enabled: self.enabled,
+            announce: self.announce,
+            announce_color: &self.announce_color,
+        }
+    }
+}
+
+fn parse_body(body: &Value) -> Result<TimerBody, (axum::http::StatusCode, Json<Value>)> {
+    let name = body["name"].as_str().unwrap_or_default().trim();
+    if name.is_empty() {
+        return Err(err_json(400, "name is required"));
+    }
+    let messages: Vec<String> = body["messages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|m| {
+            m.as_str()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty() && s.chars().count() <= MAX_MESSAGE_CHARS)
+                .ok_or_else(|| {
+                    err_json(
+                        400,
+                        &format!("messages must be strings of 1-{MAX_MESSAGE_CHARS} characters"),
+                    )
+                })
+        })
+        .collect::<Result<_, _>>()?;
+    if messages.is_empty() {
+        return Err(err_json(400, "messages must not be empty"));
+    }
+    let interval_minutes = body["interval_minutes"].as_i64().unwrap_or(0);
+    if !(1..=MAX_INTERVAL_MINUTES).contains(&interval_minutes) {
+        return Err(err_json(
+            400,
+            &format!("interval_minutes must be 1-{MAX_INTERVAL_MINUTES}"),
+        ));
+    }
+    let min_chat_messages = body["min_chat_messages"].as_i64().unwrap_or(0);
+    if !(0..=MAX_MIN_CHAT_MESSAGES).contains(&min_chat_messages) {
+        return Err(err_json(
+            400,
+            &format!("min_chat_messages must be 0-{MAX_MIN_CHAT_MESSAGES}"),
+        ));
+    }
+    let announce_color = body["announce_color"].as_str().unwrap_or("primary");
+    if !ANNOUNCEMENT_COLORS.contains(&announce_color) {
+        return Err(err_json(
+            400,
+            &format!(
+                "announce_color must be one of: {}",
+                ANNOUNCEMENT_COLORS.join(", ")
+            ),
+        ));
+    }
+    Ok(TimerBody {
+        name: name.to_string(),
+        messages,
+        interval_minutes,
+        min_chat_messages,
+        enabled: body["enabled"].as_bool().unwrap_or(true),
+        announce: body["announce"].as_bool().unwrap_or(false),
+        announce_color: announce_color.to_string(),
+    })
+}
+
+fn find(state: &SharedState, id: i64) -> Result<ChatTimer, (axum::http::StatusCode, Json<Value>)> {
+    state
+        .db()
+        .get_chat_timer(id)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Timer not found"))
+}
+
+/// GET /api/chat/timers
+pub async fn get_timers(State(state): State<SharedState>) -> ApiResult {
+    let timers = state
+        .db()
+        .get_chat_timers()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(
+        json!({ "timers": timers, "live": chat_timers::is_live() }),
+    ))
+}
+
+/// POST /api/chat/timers
+pub async fn create_timer(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let parsed = parse_body(&body)?;
+    let id = state
+        .db()
+        .add_chat_timer(&parsed.input(), chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let timer = find(&state, id)?;
+    Ok(Json(json!({ "success": true, "timer": timer })))
+}
+
+/// PUT /api/chat/timers/{id}
+pub async fn update_timer(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let parsed = parse_body(&body)?;
+    let updated = state
+        .db()
+        .update_chat_timer(id, &parsed.input(), chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !updated {
+        return Err(err_json(404, "Timer not found"));
+    }
+    let timer = find(&state, id)?;
+    Ok(Json(json!({ "success": true, "timer": timer })))
+}
+
+/// DELETE /api/chat/timers/{id}
+pub async fn delete_timer(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let deleted = state
+        .db()
+        .delete_chat_timer(id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Timer not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/chat/timers/{id}/send
+pub async fn send_timer(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let timer = find(&state, id)?;
+    chat_timers::send_now(&state, &timer)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
@@ -1,7 +1,8 @@
-//! Debug event simulation API.
+//! Debug event simulation API and EventSub event archive.
 
 use axum::Json;
-use axum::extract::State;
+use axum::extract::{Query, State};
+use serde::Deserialize;
 use serde_json::{Value, json};
 
 use crate::app::SharedState;
@@ -121,3 +122,36 @@ pub async fn debug_printer_status(
     let _ = state.ws_sender().send(msg.to_string());
     Ok(Json(json!({ "success": true, "connected": connected })))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct ArchivedEventsQuery {
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    pub since: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/debug/events – Archived raw EventSub notifications, oldest first
+pub async fn get_archived_events(
+    State(state): State<SharedState>,
+    Query(q): Query<ArchivedEventsQuery>,
+) -> ApiResult {
+    let event_type = q.event_type.as_deref().filter(|t| !t.is_empty());
+    let events = state
+        .db()
+        .get_archived_events(
+            event_type,
+            q.since.unwrap_or(0),
+            q.limit.unwrap_or(100).clamp(1, 1000),
+        )
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let archive = state
+        .db()
+        .get_event_archive_stats()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "events": events,
+        "count": events.len(),
+        "archive": archive,
+    })))
+}
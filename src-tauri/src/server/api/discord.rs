@@ -0,0 +1,139 @@
+//! Discord notification API (see `services::discord`):
+//!   GET    /api/discord                     – settings and queue length
+//!   POST   /api/discord/test                – post a test embed right away
+//!   GET    /api/discord/templates           – template of every event
+//!   PUT    /api/discord/templates/{event}   – customize
+//!                                             `{ title, description?, color?, enabled? }`
+//!   DELETE /api/discord/templates/{event}   – back to the built-in template
+//!
+//! `color` is `0xRRGGBB` as a number or a `#RRGGBB` string.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use overlay_db::discord::DiscordTemplate;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::discord::{self, EventDef};
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+const MAX_TITLE_CHARS: usize = 256;
+const MAX_DESCRIPTION_CHARS: usize = 4096;
+
+fn find_event(event: &str) -> Result<&'static EventDef, (axum::http::StatusCode, Json<Value>)> {
+    discord::event_def(event).ok_or_else(|| err_json(404, &format!("Unknown event: {event}")))
+}
+
+fn parse_color(value: &Value) -> Option<i64> {
+    let color = match value {
+        Value::Number(n) => n.as_i64()?,
+        Value::String(s) => i64::from_str_radix(s.strip_prefix('#')?, 16).ok()?,
+        _ => return None,
+    };
+    (0..=0xFF_FFFF).contains(&color).then_some(color)
+}
+
+fn template_json(def: &EventDef, template: &DiscordTemplate, customized: bool) -> Value {
+    json!({
+        "event": def.event,
+        "enabled": template.enabled,
+        "title": template.title,
+        "description": template.description,
+        "color": template.color,
+        "placeholders": def.placeholders,
+        "customized": customized,
+        "updated_at": customized.then_some(template.updated_at),
+    })
+}
+
+/// GET /api/discord
+pub async fn get_status(State(state): State<SharedState>) -> Json<Value> {
+    Json(discord::status(&state))
+}
+
+/// POST /api/discord/test
+pub async fn test(State(state): State<SharedState>) -> ApiResult {
+    discord::send_test(&state)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// GET /api/discord/templates
+pub async fn get_templates(State(state): State<SharedState>) -> ApiResult {
+    let stored = state
+        .db()
+        .get_discord_templates()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let templates: Vec<Value> = discord::EVENTS
+        .iter()
+        .map(|def| match stored.iter().find(|t| t.event == def.event) {
+            Some(template) => template_json(def, template, true),
+            None => template_json(def, &discord::default_template(def), false),
+        })
+        .collect();
+    Ok(Json(json!({ "templates": templates })))
+}
+
+/// PUT /api/discord/templates/{event}
+pub async fn update_template(
+    State(state): State<SharedState>,
+    Path(event): Path<String>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let def = find_event(&event)?;
+    let title = body["title"].as_str().unwrap_or_default().trim();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_CHARS {
+        return Err(err_json(
+            400,
+            &format!("title must be 1-{MAX_TITLE_CHARS} characters"),
+        ));
+    }
+    let description = body["description"].as_str().unwrap_or_default().trim();
+    if description.chars().count() > MAX_DESCRIPTION_CHARS {
+        return Err(err_json(
+            400,
+            &format!("description must be at most {MAX_DESCRIPTION_CHARS} characters"),
+        ));
+    }
+    let color = match body.get("color") {
+        None | Some(Value::Null) => def.color,
+        Some(value) => parse_color(value)
+            .ok_or_else(|| err_json(400, "color must be 0xRRGGBB or \"#RRGGBB\""))?,
+    };
+    let template = DiscordTemplate {
+        event: def.event.to_string(),
+        enabled: body["enabled"].as_bool().unwrap_or(true),
+        title: title.to_string(),
+        description: description.to_string(),
+        color,
+        updated_at: chrono::Utc::now().timestamp(),
+    };
+    state
+        .db()
+        .upsert_discord_template(&template)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "success": true,
+        "template": template_json(def, &template, true),
+    })))
+}
+
+/// DELETE /api/discord/templates/{event}
+pub async fn reset_template(
+    State(state): State<SharedState>,
+    Path(event): Path<String>,
+) -> ApiResult {
+    let def = find_event(&event)?;
+    state
+        .db()
+        .delete_discord_template(def.event)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "success": true,
+        "template": template_json(def, &discord::default_template(def), false),
+    })))
+}
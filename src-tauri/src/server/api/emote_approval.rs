@@ -0,0 +1,51 @@
+//! Emote print approval API:
+//!   GET /api/printer/emote-approvals              – list rules (`?status=pending`)
+//!   PUT /api/printer/emote-approvals/{emote_id}   – set allowed/denied/pending
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalQuery {
+    pub status: Option<String>,
+}
+
+/// GET /api/printer/emote-approvals
+pub async fn get_approvals(
+    State(state): State<SharedState>,
+    Query(q): Query<ApprovalQuery>,
+) -> ApiResult {
+    let rules = state
+        .db()
+        .get_emote_print_rules(q.status.as_deref())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "emotes": rules, "count": rules.len() })))
+}
+
+/// PUT /api/printer/emote-approvals/{emote_id}
+pub async fn set_approval(
+    State(state): State<SharedState>,
+    Path(emote_id): Path<String>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let status = body["status"].as_str().unwrap_or_default();
+    let name = body["emote_name"].as_str().unwrap_or_default();
+    state
+        .db()
+        .set_emote_print_status(&emote_id, name, status, chrono::Utc::now().timestamp())
+        .map_err(|e| match e {
+            overlay_db::DbError::InvalidData(msg) => err_json(400, &msg),
+            other => err_json(500, &other.to_string()),
+        })?;
+    Ok(Json(
+        json!({ "success": true, "emote_id": emote_id, "status": status }),
+    ))
+}
@@ -0,0 +1,81 @@
+//! Emote rain API:
+//!   GET    /api/overlay/emote-rain              – settings and per-emote cooldowns
+//!   PUT    /api/overlay/emote-rain/{emote_id}   – set/clear a per-emote cooldown
+//!   DELETE /api/overlay/emote-rain/{emote_id}   – forget an emote
+//!   POST   /api/overlay/emote-rain/test         – rain an emote now (no cooldown)
+
+use axum::Json;
+use axum::extract::{Path, State};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::emote_rain;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// GET /api/overlay/emote-rain
+pub async fn get_emote_rain(State(state): State<SharedState>) -> ApiResult {
+    let emotes = state
+        .db()
+        .get_emote_rain_cooldowns()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "settings": emote_rain::settings_json(&state),
+        "emotes": emotes,
+    })))
+}
+
+/// PUT /api/overlay/emote-rain/{emote_id}
+pub async fn set_cooldown(
+    State(state): State<SharedState>,
+    Path(emote_id): Path<String>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let cooldown = match body.get("cooldown_seconds") {
+        None | Some(Value::Null) => None,
+        Some(v) => Some(
+            v.as_i64()
+                .filter(|s| (0..=86_400).contains(s))
+                .ok_or_else(|| err_json(400, "cooldown_seconds must be 0-86400 or null"))?,
+        ),
+    };
+    let name = body["emote_name"].as_str().unwrap_or_default();
+    state
+        .db()
+        .set_emote_rain_cooldown(&emote_id, name, cooldown)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "success": true,
+        "emote_id": emote_id,
+        "cooldown_seconds": cooldown,
+    })))
+}
+
+/// DELETE /api/overlay/emote-rain/{emote_id}
+pub async fn delete_cooldown(
+    State(state): State<SharedState>,
+    Path(emote_id): Path<String>,
+) -> ApiResult {
+    let deleted = state
+        .db()
+        .delete_emote_rain_cooldown(&emote_id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Emote not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/overlay/emote-rain/test
+pub async fn test_rain(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let emote_id = body["emote_id"].as_str().unwrap_or_default();
+    if emote_id.is_empty() {
+        return Err(err_json(400, "emote_id is required"));
+    }
+    let name = body["emote_name"].as_str().unwrap_or_default();
+    let intensity = body["intensity"].as_u64().unwrap_or(3).clamp(1, 5) as u32;
+    emote_rain::broadcast(&state, emote_id, name, 0, intensity);
+    Ok(Json(json!({ "success": true })))
+}
@@ -0,0 +1,109 @@
+//! Emote catalog API:
+//!   GET  /api/emotes              – cached emote groups with their emotes
+//!   GET  /api/emotes/groups       – group metadata with emote counts
+//!   GET  /api/emotes/groups/{id}  – one group's emotes (`?offset=&limit=`)
+//!   GET  /api/emotes/search       – autocomplete (`?q=&channel=&limit=`)
+//!   POST /api/emotes/refresh      – fetch every group from Helix again
+//!
+//! Groups and emotes carry a `source`: `twitch`, or `7tv`, `bttv` and
+//! `ffz` for third-party emotes (group IDs like `7tv:global`).
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::emotes;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+const DEFAULT_PAGE_LIMIT: usize = 200;
+const MAX_PAGE_LIMIT: usize = 1000;
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: Option<String>,
+    pub channel: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Start a warm-up when nothing has been cached yet.
+fn warm_up_if_empty(state: &SharedState, warmed_at: Option<i64>, warming: bool) {
+    if warmed_at.is_none() && !warming {
+        emotes::request_warm_up(state);
+    }
+}
+
+/// GET /api/emotes
+///
+/// Served from memory, led by the `recent` group once the broadcaster has
+/// sent emotes. Before the first warm-up has finished the other groups may
+/// be missing and `warming` is true.
+pub async fn get_emotes(State(state): State<SharedState>) -> ApiResult {
+    let catalog = emotes::catalog(&state).await;
+    warm_up_if_empty(&state, catalog.warmed_at, catalog.warming);
+    Ok(Json(json!(catalog)))
+}
+
+/// GET /api/emotes/groups
+pub async fn get_groups(State(state): State<SharedState>) -> ApiResult {
+    let list = emotes::groups(&state).await;
+    warm_up_if_empty(&state, list.warmed_at, list.warming);
+    Ok(Json(json!(list)))
+}
+
+/// GET /api/emotes/groups/{id}
+pub async fn get_group(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+    Query(q): Query<PageQuery>,
+) -> ApiResult {
+    let limit = q
+        .limit
+        .unwrap_or(DEFAULT_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+    let page = emotes::group_page(&state, &id, q.offset.unwrap_or(0), limit)
+        .await
+        .ok_or_else(|| err_json(404, "Emote group not found"))?;
+    Ok(Json(json!(page)))
+}
+
+/// GET /api/emotes/search
+///
+/// Matches emote names by prefix, substring or in-order characters and
+/// ranks usable and recently sent emotes first.
+pub async fn search_emotes(
+    State(state): State<SharedState>,
+    Query(q): Query<SearchQuery>,
+) -> ApiResult {
+    let query = q.q.unwrap_or_default();
+    let channel = q
+        .channel
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    let limit = q
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let results = emotes::search(&state, &query, channel, limit).await;
+    let count = results.len();
+    Ok(Json(json!({ "results": results, "count": count })))
+}
+
+/// POST /api/emotes/refresh
+pub async fn refresh_emotes(State(state): State<SharedState>) -> ApiResult {
+    emotes::request_warm_up(&state);
+    Ok(Json(json!({ "success": true })))
+}
@@ -9,6 +9,7 @@ use base64::Engine;
 use serde_json::{Value, json};
 
 use crate::app::SharedState;
+use crate::config::SettingsManager;
 use crate::services::font::FontService;
 
 use super::err_json;
@@ -32,7 +33,8 @@ pub async fn upload_font(State(state): State<SharedState>, mut multipart: Multip
                 .save_custom_font(&filename, &data)
                 .map_err(|e| err_json(400, &e.to_string()))?;
 
-            let _ = state.db().set_setting("FONT_FILENAME", &filename, "normal");
+            let _ =
+                SettingsManager::new(state.db().clone()).set_setting("FONT_FILENAME", &filename);
 
             return Ok(Json(json!({ "success": true, "font": info })));
         }
@@ -46,7 +48,7 @@ pub async fn delete_font(State(state): State<SharedState>) -> ApiResult {
     let svc = FontService::new(state.data_dir().clone());
     svc.delete_custom_font()
         .map_err(|e| err_json(500, &e.to_string()))?;
-    let _ = state.db().set_setting("FONT_FILENAME", "", "normal");
+    let _ = SettingsManager::new(state.db().clone()).set_setting("FONT_FILENAME", "");
     Ok(Json(json!({ "success": true, "message": "Font deleted" })))
 }
 
@@ -0,0 +1,90 @@
+//! Funding goal API:
+//!   GET  /api/goals/funding            – goal status with totals per source
+//!   GET  /api/goals/funding/entries    – contributions since the last reset
+//!                                        (`?source=&limit=`)
+//!   POST /api/goals/funding/donations  – add an external donation
+//!                                        `{ amount, username?, note? }`
+//!   POST /api/goals/funding/adjust     – manual correction `{ amount, reason }`
+//!   POST /api/goals/funding/reset      – start counting from now
+
+use axum::Json;
+use axum::extract::{Query, State};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::funding;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+const DEFAULT_ENTRY_LIMIT: i64 = 100;
+const MAX_ENTRY_LIMIT: i64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct EntriesQuery {
+    pub source: Option<String>,
+    pub limit: Option<i64>,
+}
+
+fn parse_amount(body: &Value) -> Result<f64, (axum::http::StatusCode, Json<Value>)> {
+    body["amount"]
+        .as_f64()
+        .filter(|v| v.is_finite() && *v != 0.0)
+        .ok_or_else(|| err_json(400, "amount must be a non-zero number"))
+}
+
+/// GET /api/goals/funding
+pub async fn get_funding(State(state): State<SharedState>) -> ApiResult {
+    let status = funding::status(&state).map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!(status)))
+}
+
+/// GET /api/goals/funding/entries
+pub async fn get_entries(
+    State(state): State<SharedState>,
+    Query(q): Query<EntriesQuery>,
+) -> ApiResult {
+    let source = q.source.as_deref().filter(|s| !s.is_empty());
+    if source.is_some_and(|s| !funding::is_source(s)) {
+        return Err(err_json(400, "Unknown source"));
+    }
+    let limit = q
+        .limit
+        .unwrap_or(DEFAULT_ENTRY_LIMIT)
+        .clamp(1, MAX_ENTRY_LIMIT);
+    let entries = funding::entries(&state, source, limit).map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "entries": entries, "count": entries.len() })))
+}
+
+/// POST /api/goals/funding/donations
+pub async fn add_donation(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let amount = parse_amount(&body)?;
+    if amount < 0.0 {
+        return Err(err_json(400, "amount must be positive"));
+    }
+    let username = body["username"].as_str().unwrap_or_default().trim();
+    let note = body["note"].as_str().unwrap_or_default().trim();
+    let entry =
+        funding::add_donation(&state, amount, username, note).map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "success": true, "entry": entry })))
+}
+
+/// POST /api/goals/funding/adjust
+pub async fn adjust(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let amount = parse_amount(&body)?;
+    let reason = body["reason"].as_str().unwrap_or_default().trim();
+    if reason.is_empty() {
+        return Err(err_json(400, "reason is required"));
+    }
+    let entry =
+        funding::adjust(&state, amount, reason, "dashboard").map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "success": true, "entry": entry })))
+}
+
+/// POST /api/goals/funding/reset
+pub async fn reset(State(state): State<SharedState>) -> ApiResult {
+    funding::reset(&state).map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
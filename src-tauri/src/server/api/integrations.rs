@@ -0,0 +1,182 @@
+//! External integration API (event trigger mappings, OSC, MIDI and lights).
+//!
+//!   GET    /api/integrations/events                            – mappable events
+//!   GET    /api/integrations/{integration}/triggers            – list mappings
+//!   POST   /api/integrations/{integration}/triggers            – add mapping
+//!   PUT    /api/integrations/{integration}/triggers/{id}       – update mapping
+//!   DELETE /api/integrations/{integration}/triggers/{id}       – delete mapping
+//!   GET    /api/integrations/osc                               – OSC settings
+//!   POST   /api/integrations/osc/test                          – send one message
+//!   GET    /api/integrations/midi                              – MIDI settings and ports
+//!   POST   /api/integrations/midi/test                         – send one note/CC
+//!   GET    /api/integrations/lights                            – light backends
+//!   POST   /api/integrations/lights/test                       – flash once
+//!   POST   /api/integrations/lights/hue/pair                   – pair with Hue bridge
+
+use axum::Json;
+use axum::extract::{Path, State};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::{event_triggers, lights, midi, osc};
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+fn require_integration(integration: &str) -> Result<(), (axum::http::StatusCode, Json<Value>)> {
+    if event_triggers::INTEGRATIONS.contains(&integration) {
+        Ok(())
+    } else {
+        Err(err_json(
+            404,
+            &format!("Unknown integration: {integration}"),
+        ))
+    }
+}
+
+/// Read `{event, min_amount, params}` from a trigger request body.
+fn trigger_fields(
+    integration: &str,
+    body: &Value,
+) -> Result<(String, i64, Value), (axum::http::StatusCode, Json<Value>)> {
+    let event = body["event"].as_str().unwrap_or_default().to_string();
+    let min_amount = body["min_amount"].as_i64().unwrap_or(0);
+    let params = body.get("params").cloned().unwrap_or(json!({}));
+    if min_amount < 0 {
+        return Err(err_json(400, "min_amount must not be negative"));
+    }
+    event_triggers::validate(integration, &event, &params).map_err(|e| err_json(400, &e))?;
+    Ok((event, min_amount, params))
+}
+
+/// GET /api/integrations/events
+pub async fn get_events() -> Json<Value> {
+    let events: Vec<Value> = event_triggers::EVENTS
+        .iter()
+        .map(|(name, amount)| json!({ "event": name, "amount": amount }))
+        .collect();
+    Json(json!({ "events": events, "integrations": event_triggers::INTEGRATIONS }))
+}
+
+/// GET /api/integrations/{integration}/triggers
+pub async fn get_triggers(
+    State(state): State<SharedState>,
+    Path(integration): Path<String>,
+) -> ApiResult {
+    require_integration(&integration)?;
+    let triggers = state
+        .db()
+        .get_event_triggers(&integration)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(
+        json!({ "triggers": triggers, "count": triggers.len() }),
+    ))
+}
+
+/// POST /api/integrations/{integration}/triggers
+pub async fn add_trigger(
+    State(state): State<SharedState>,
+    Path(integration): Path<String>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    require_integration(&integration)?;
+    let (event, min_amount, params) = trigger_fields(&integration, &body)?;
+    let id = state
+        .db()
+        .add_event_trigger(
+            &integration,
+            &event,
+            min_amount,
+            &params,
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "success": true, "id": id })))
+}
+
+/// PUT /api/integrations/{integration}/triggers/{id}
+pub async fn update_trigger(
+    State(state): State<SharedState>,
+    Path((integration, id)): Path<(String, i64)>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    require_integration(&integration)?;
+    let (event, min_amount, params) = trigger_fields(&integration, &body)?;
+    let enabled = body["enabled"].as_bool().unwrap_or(true);
+    let updated = state
+        .db()
+        .update_event_trigger(id, &integration, &event, min_amount, &params, enabled)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !updated {
+        return Err(err_json(404, "Trigger not found"));
+    }
+    Ok(Json(json!({ "success": true, "id": id })))
+}
+
+/// DELETE /api/integrations/{integration}/triggers/{id}
+pub async fn delete_trigger(
+    State(state): State<SharedState>,
+    Path((integration, id)): Path<(String, i64)>,
+) -> ApiResult {
+    require_integration(&integration)?;
+    let deleted = state
+        .db()
+        .delete_event_trigger(id, &integration)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Trigger not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// GET /api/integrations/osc
+pub async fn get_osc(State(state): State<SharedState>) -> Json<Value> {
+    Json(osc::status(&state))
+}
+
+/// POST /api/integrations/osc/test
+pub async fn test_osc(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let address = body["address"].as_str().unwrap_or_default();
+    let value = body.get("value").cloned().unwrap_or(json!(true));
+    osc::send_test(&state, address, &value)
+        .await
+        .map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// GET /api/integrations/midi
+pub async fn get_midi(State(state): State<SharedState>) -> Json<Value> {
+    Json(midi::status(&state))
+}
+
+/// POST /api/integrations/midi/test
+pub async fn test_midi(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    midi::send_test(&state, &body).map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// GET /api/integrations/lights
+pub async fn get_lights(State(state): State<SharedState>) -> Json<Value> {
+    Json(lights::status(&state))
+}
+
+/// POST /api/integrations/lights/test
+pub async fn test_lights(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    lights::send_test(&state, &body)
+        .await
+        .map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/integrations/lights/hue/pair
+pub async fn pair_hue(State(state): State<SharedState>) -> ApiResult {
+    let username = lights::pair_hue(&state)
+        .await
+        .map_err(|e| err_json(400, &e))?;
+    SettingsManager::new(state.db().clone())
+        .set_setting("LIGHTS_HUE_USERNAME", &username)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "success": true })))
+}
@@ -0,0 +1,79 @@
+//! IRC relay API (see `services::irc_relay`):
+//!   GET    /api/irc/status             – connection state and joined channels
+//!   GET    /api/irc/messages           – stored chat of other channels
+//!   POST   /api/irc/send               – send a message to a channel
+//!   POST   /api/irc/channels           – join a channel
+//!   DELETE /api/irc/channels/{channel} – leave a channel
+//!
+//! Send, join and part are also WebSocket commands (`irc.send`, `irc.join`,
+//! `irc.part`).
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::irc_relay;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// GET /api/irc/status
+pub async fn get_status(State(state): State<SharedState>) -> ApiResult {
+    Ok(Json(json!(irc_relay::status(&state))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MessagesQuery {
+    pub channel: Option<String>,
+    pub since: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/irc/messages?channel=&since=&limit=
+///
+/// The latest `limit` messages, oldest first; every channel when `channel`
+/// is left out.
+pub async fn get_messages(
+    State(state): State<SharedState>,
+    Query(q): Query<MessagesQuery>,
+) -> ApiResult {
+    let channel = q
+        .channel
+        .map(|c| twitch_client::irc::normalize_channel(&c))
+        .filter(|c| !c.is_empty());
+    let messages = state
+        .db()
+        .get_irc_chat_messages(
+            channel.as_deref(),
+            q.since.unwrap_or(0),
+            q.limit.unwrap_or(100).clamp(1, 1000),
+        )
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(
+        json!({ "messages": messages, "count": messages.len() }),
+    ))
+}
+
+/// POST /api/irc/send
+pub async fn send(Json(body): Json<Value>) -> ApiResult {
+    let channel = body["channel"].as_str().unwrap_or_default();
+    let message = body["message"].as_str().unwrap_or_default();
+    irc_relay::send(channel, message).map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/irc/channels
+pub async fn join(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let channel = body["channel"].as_str().unwrap_or_default();
+    let channels = irc_relay::join(&state, channel).map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({ "success": true, "channels": channels })))
+}
+
+/// DELETE /api/irc/channels/{channel}
+pub async fn part(State(state): State<SharedState>, Path(channel): Path<String>) -> ApiResult {
+    let channels = irc_relay::part(&state, &channel).map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({ "success": true, "channels": channels })))
+}
@@ -0,0 +1,149 @@
+//! Lottery preset API (see `services::lottery_presets`):
+//!   GET    /api/present/settings/presets              – saved presets
+//!   POST   /api/present/settings/presets              – import a preset document,
+//!                                                       or save the current settings
+//!                                                       (`{name, description}`);
+//!                                                       `?apply=true` also applies it
+//!   GET    /api/present/settings/presets/export       – current settings as a document
+//!   GET    /api/present/settings/presets/{name}       – a saved preset as a document
+//!   POST   /api/present/settings/presets/{name}/apply – apply a saved preset
+//!   DELETE /api/present/settings/presets/{name}       – delete a saved preset
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::lottery_presets::{self, PresetDocument};
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct SaveQuery {
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+fn load(
+    state: &SharedState,
+    name: &str,
+) -> Result<PresetDocument, (axum::http::StatusCode, Json<Value>)> {
+    let preset = state
+        .db()
+        .get_lottery_preset(name)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Preset not found"))?;
+    Ok(PresetDocument::new(
+        &preset.name,
+        &preset.description,
+        preset.settings,
+    ))
+}
+
+/// GET /api/present/settings/presets
+pub async fn get_presets(State(state): State<SharedState>) -> ApiResult {
+    let presets = state
+        .db()
+        .get_lottery_presets()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "presets": presets,
+        "keys": lottery_presets::PRESET_KEYS,
+    })))
+}
+
+/// POST /api/present/settings/presets
+pub async fn save_preset(
+    State(state): State<SharedState>,
+    Query(q): Query<SaveQuery>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let preset = if body.get("settings").is_some() {
+        lottery_presets::parse(&body).map_err(|e| err_json(400, &e))?
+    } else {
+        let name = body["name"].as_str().unwrap_or_default().trim();
+        if name.is_empty() {
+            return Err(err_json(400, "Preset name is required"));
+        }
+        PresetDocument::new(
+            name,
+            body["description"].as_str().unwrap_or_default(),
+            lottery_presets::current_settings(&state),
+        )
+    };
+    state
+        .db()
+        .upsert_lottery_preset(
+            &preset.name,
+            &preset.description,
+            &preset.settings,
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if q.apply {
+        lottery_presets::apply(&state, &preset.settings)
+            .await
+            .map_err(|e| err_json(500, &e))?;
+    }
+    Ok(Json(json!({
+        "success": true,
+        "preset": preset,
+        "applied": q.apply,
+    })))
+}
+
+/// GET /api/present/settings/presets/export
+pub async fn export_current(
+    State(state): State<SharedState>,
+    Query(q): Query<ExportQuery>,
+) -> Json<PresetDocument> {
+    Json(PresetDocument::new(
+        q.name.as_deref().unwrap_or("current"),
+        q.description.as_deref().unwrap_or_default(),
+        lottery_presets::current_settings(&state),
+    ))
+}
+
+/// GET /api/present/settings/presets/{name}
+pub async fn get_preset(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Result<Json<PresetDocument>, (axum::http::StatusCode, Json<Value>)> {
+    load(&state, &name).map(Json)
+}
+
+/// POST /api/present/settings/presets/{name}/apply
+pub async fn apply_preset(State(state): State<SharedState>, Path(name): Path<String>) -> ApiResult {
+    let preset = load(&state, &name)?;
+    lottery_presets::apply(&state, &preset.settings)
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    tracing::info!(preset = %preset.name, "Lottery preset applied");
+    Ok(Json(
+        json!({ "success": true, "settings": preset.settings }),
+    ))
+}
+
+/// DELETE /api/present/settings/presets/{name}
+pub async fn delete_preset(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> ApiResult {
+    let deleted = state
+        .db()
+        .delete_lottery_preset(&name)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Preset not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
@@ -0,0 +1,215 @@
+//! Public lottery results page:
+//!   GET /present/results – past winners with the proof of each draw
+//!
+//! Served on the public routes (see `server::access`) only while
+//! `LOTTERY_RESULTS_PUBLIC` is on, so viewers can audit giveaways without
+//! the admin UI. `LOTTERY_RESULTS_MASK_NAMES` partly hides names and lists
+//! the SHA-256 of each user ID instead of the ID; the entries digest covers
+//! the hashes, so the page stays verifiable. The hash is a stable identifier
+//! only: user IDs are short numbers, so anyone can recover one by hashing
+//! every ID.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::Html;
+use overlay_db::lottery::{DrawProof, LotteryDraw};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::lottery_draw;
+
+const DRAW_LIMIT: i64 = 20;
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `name` with all but its first and last characters replaced by `*`.
+fn mask_name(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    match chars.len() {
+        0 => String::new(),
+        1 => "*".to_string(),
+        2 => format!("{}*", chars[0]),
+        n => format!("{}{}{}", chars[0], "*".repeat(n - 2), chars[n - 1]),
+    }
+}
+
+fn format_time(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn render_proof(proof: &DrawProof, mask: bool) -> String {
+    let verified = match lottery_draw::verify(proof) {
+        Some(_) => "✓ 検証OK",
+        None => "✗ 検証できません",
+    };
+    let committed_at = proof
+        .committed_at
+        .map_or_else(|| "抽選時に生成".to_string(), format_time);
+    let mut first_ticket = 0;
+    let rows: String = proof
+        .entries
+        .iter()
+        .map(|e| {
+            let (user_id, name) = if mask {
+                (lottery_draw::user_id_hash(&e.user_id), mask_name(&e.name))
+            } else {
+                (e.user_id.clone(), e.name.clone())
+            };
+            let numbers = match e.tickets {
+                0 => "-".to_string(),
+                n => format!("{first_ticket}–{}", first_ticket + n - 1),
+            };
+            let row = format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{numbers}</td></tr>",
+                escape(&user_id),
+                escape(&name),
+                e.tickets,
+            );
+            first_ticket += e.tickets;
+            row
+        })
+        .collect();
+    let id_header = if mask {
+        "ユーザーID (SHA-256)"
+    } else {
+        "ユーザーID"
+    };
+    format!(
+        r#"<p class="verified">{verified}</p>
+<dl>
+<dt>方式</dt><dd><code>{algorithm}</code></dd>
+<dt>コミットメント</dt><dd><code>{commitment}</code></dd>
+<dt>公開日時</dt><dd>{committed_at}</dd>
+<dt>シード</dt><dd><code>{seed}</code></dd>
+<dt>参加者ダイジェスト</dt><dd><code>{digest}</code></dd>
+<dt>当選番号</dt><dd>{ticket} / {total} 口</dd>
+</dl>
+<details><summary>参加者 {count} 人</summary>
+<table><tr><th>{id_header}</th><th>名前</th><th>口数</th><th>番号</th></tr>{rows}</table>
+</details>"#,
+        algorithm = escape(&proof.algorithm),
+        commitment = escape(&proof.commitment),
+        seed = escape(&proof.seed),
+        digest = escape(&proof.entries_digest),
+        ticket = proof.winning_ticket,
+        total = proof.total_tickets,
+        count = proof.entries.len(),
+    )
+}
+
+fn render_draw(draw: &LotteryDraw, mask: bool) -> String {
+    let name = if draw.display_name.is_empty() {
+        &draw.username
+    } else {
+        &draw.display_name
+    };
+    let name = if mask { mask_name(name) } else { name.clone() };
+    let proof = draw.proof.as_ref().map_or_else(
+        || "<p>この抽選には検証情報がありません。</p>".to_string(),
+        |proof| render_proof(proof, mask),
+    );
+    format!(
+        "<section><h2>{name}</h2><p>{time}・参加者 {count} 人</p>{proof}</section>",
+        name = escape(&name),
+        time = format_time(draw.drawn_at),
+        count = draw.participant_count,
+    )
+}
+
+fn render_page(draws: &[LotteryDraw], commitment: Option<&str>, mask: bool) -> String {
+    let next = commitment.map_or_else(
+        || "<p>次の抽選のコミットメントはまだ公開されていません。</p>".to_string(),
+        |c| {
+            format!(
+                "<p>次の抽選のコミットメント: <code>{}</code></p>",
+                escape(c)
+            )
+        },
+    );
+    let sections: String = if draws.is_empty() {
+        "<p>まだ抽選はありません。</p>".to_string()
+    } else {
+        draws.iter().map(|d| render_draw(d, mask)).collect()
+    };
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ja"><head><meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>抽選結果</title>
+<style>
+body {{ font-family: sans-serif; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; }}
+section {{ border-top: 1px solid #ccc; padding: 0.5rem 0; }}
+code {{ word-break: break-all; }}
+dt {{ font-weight: bold; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #ccc; padding: 0.2rem 0.5rem; }}
+.verified {{ font-weight: bold; }}
+</style></head><body>
+<h1>抽選結果</h1>
+{next}
+<details><summary>検証方法</summary>
+<p>抽選開始時にランダムなシードを決め、その SHA-256（コミットメント）だけを公開します。抽選後にシードを公開するので、結果が事前に決まっていたことを誰でも確かめられます。</p>
+<ol>
+<li>SHA-256(シード) がコミットメントと一致すること。</li>
+<li>参加者を表の順に並べ、各行 <code>SHA-256(ユーザーID):口数</code> と改行を連結した文字列の SHA-256 が参加者ダイジェストと一致すること。SHA-256(ユーザーID) は参加者を見分けるための識別子で、ユーザーID を隠すものではありません。</li>
+<li><code>シード:参加者ダイジェスト</code> の SHA-256 の先頭 8 バイトをビッグエンディアンの整数とし、総口数で割った余りが当選番号であること。番号は 0 から参加者の順に割り振られます。</li>
+</ol>
+</details>
+{sections}
+</body></html>"#
+    )
+}
+
+/// GET /present/results
+pub async fn results_page(State(state): State<SharedState>) -> Result<Html<String>, StatusCode> {
+    let sm = SettingsManager::new(state.db().clone());
+    let flag = |key: &str| sm.get_setting(key).is_ok_and(|v| v == "true");
+    if !flag("LOTTERY_RESULTS_PUBLIC") {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let draws = state.db().get_lottery_draws(DRAW_LIMIT).map_err(|e| {
+        tracing::warn!("Failed to load lottery draws: {e}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let commitment = lottery_draw::commitment();
+    Ok(Html(render_page(
+        &draws,
+        commitment.as_deref(),
+        flag("LOTTERY_RESULTS_MASK_NAMES"),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_name() {
+        assert_eq!(mask_name("alice"), "a***e");
+        assert_eq!(mask_name("ゆき"), "ゆ*");
+        assert_eq!(mask_name("x"), "*");
+        assert_eq!(
+            escape("<b>\"a&b\"</b>"),
+            "&lt;b&gt;&quot;a&amp;b&quot;&lt;/b&gt;"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Viewer milestones API.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::milestones;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// GET /api/milestones
+pub async fn get_milestones(State(state): State<SharedState>) -> ApiResult {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let fired = state
+        .db()
+        .get_fired_milestones()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "enabled": get("MILESTONES_ENABLED") == "true",
+        "thresholds": {
+            "followers": milestones::parse_thresholds(&get("MILESTONE_FOLLOWER_THRESHOLDS")),
+            "viewers": milestones::parse_thresholds(&get("MILESTONE_VIEWER_THRESHOLDS")),
+        },
+        "fired": fired,
+    })))
+}
+
+/// DELETE /api/milestones/{kind}/{threshold} – allow a milestone to fire again
+pub async fn reset_milestone(
+    State(state): State<SharedState>,
+    Path((kind, threshold)): Path<(String, i64)>,
+) -> ApiResult {
+    state
+        .db()
+        .reset_milestone(&kind, threshold)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "success": true })))
+}
@@ -1,20 +1,52 @@
 //! REST API handlers grouped by domain.
 
+pub mod actions;
+pub mod ads;
+pub mod afk;
 pub mod cache;
 pub mod chat;
+pub mod chat_commands;
+pub mod chat_timers;
 pub mod debug;
+pub mod discord;
+pub mod emote_approval;
+pub mod emote_rain;
+pub mod emotes;
 pub mod fax;
 pub mod font;
+pub mod goals;
+pub mod integrations;
+pub mod irc;
 pub mod logs;
+pub mod lottery_presets;
+pub mod lottery_results;
+pub mod milestone;
+pub mod moderation;
 pub mod music;
 pub mod music_playlist;
 pub mod music_state;
+pub mod notification;
+pub mod obs;
 pub mod overlay;
+pub mod pre_show;
 pub mod present;
+pub mod print_vote;
+pub mod privacy;
 pub mod printer;
+pub mod quotes;
 pub mod reward;
+pub mod rundown;
+pub mod search;
+pub mod segment;
 pub mod settings;
+pub mod setup;
+pub mod stats;
+pub mod stream_sessions;
+pub mod system;
+pub mod tts;
 pub mod twitch;
+pub mod webhooks;
+pub mod window;
 pub mod word_filter;
 
 use axum::Json;
@@ -0,0 +1,156 @@
+//! Moderation API:
+//!   GET   /api/moderation/log           – logged moderator actions, newest first
+//!   GET   /api/moderation/events        – chat messages the word filter acted on
+//!                                         (`user`, `limit`; see `services::chat_filter`)
+//!   GET   /api/moderation/chat-settings – chat modes and Shield Mode status
+//!   PATCH /api/moderation/chat-settings – change follower-only, slow, emote-only
+//!                                         or subscriber-only mode
+//!   PUT   /api/moderation/shield-mode   – turn Shield Mode on or off
+//!
+//! Log filters (see `services::moderation_log`): `moderator` (user ID or
+//! login), `action` (e.g. `ban`, `timeout`), `from` / `to` (unix seconds),
+//! `limit`, `offset`. The one-click lockdown is `POST /api/system/panic`.
+
+use axum::Json;
+use axum::extract::{Query, State};
+use overlay_db::moderation::ModerationLogQuery;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use twitch_client::api::moderation::ChatSettingsUpdate;
+
+use crate::app::SharedState;
+use crate::services::helix;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct LogQuery {
+    pub moderator: Option<String>,
+    pub action: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// GET /api/moderation/log
+pub async fn get_log(State(state): State<SharedState>, Query(q): Query<LogQuery>) -> ApiResult {
+    let query = ModerationLogQuery {
+        moderator: non_empty(q.moderator),
+        action: non_empty(q.action),
+        from: q.from,
+        to: q.to,
+        offset: q.offset.unwrap_or(0).max(0),
+        limit: q.limit.unwrap_or(100).clamp(1, 1000),
+    };
+    let actions = state
+        .db()
+        .get_moderation_log(&query)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "actions": actions })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    pub user: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/moderation/events
+pub async fn get_events(
+    State(state): State<SharedState>,
+    Query(q): Query<EventsQuery>,
+) -> ApiResult {
+    let user = non_empty(q.user);
+    let events = state
+        .db()
+        .get_moderation_events(user.as_deref(), q.limit.unwrap_or(100).clamp(1, 1000))
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "events": events })))
+}
+
+/// GET /api/moderation/chat-settings
+pub async fn get_chat_settings(State(state): State<SharedState>) -> ApiResult {
+    let ctx = helix::context(&state)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    let id = &ctx.broadcaster_id;
+    let (settings, shield_mode) = tokio::join!(
+        ctx.api.get_chat_settings(&ctx.token, id, id),
+        ctx.api.get_shield_mode(&ctx.token, id, id),
+    );
+    let settings = settings.map_err(|e| err_json(502, &e.to_string()))?;
+    let shield_mode = shield_mode.map_err(|e| err_json(502, &e.to_string()))?;
+    Ok(Json(
+        json!({ "settings": settings, "shield_mode": shield_mode }),
+    ))
+}
+
+fn check_update(update: &ChatSettingsUpdate) -> Result<(), String> {
+    if update.is_empty() {
+        return Err("No chat settings given".into());
+    }
+    if update.follower_mode_duration.is_some_and(|m| m > 129_600) {
+        return Err("follower_mode_duration must be between 0 and 129600".into());
+    }
+    if update
+        .slow_mode_wait_time
+        .is_some_and(|s| !(3..=120).contains(&s))
+    {
+        return Err("slow_mode_wait_time must be between 3 and 120".into());
+    }
+    Ok(())
+}
+
+/// PATCH /api/moderation/chat-settings
+///
+/// Fields left out keep their current value.
+pub async fn update_chat_settings(
+    State(state): State<SharedState>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let update: ChatSettingsUpdate = serde_json::from_value(body)
+        .map_err(|e| err_json(400, &format!("Invalid chat settings: {e}")))?;
+    check_update(&update).map_err(|e| err_json(400, &e))?;
+    let ctx = helix::context(&state)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    let id = &ctx.broadcaster_id;
+    ctx.api
+        .update_chat_settings(&ctx.token, id, id, &update)
+        .await
+        .map_err(|e| err_json(502, &e.to_string()))?;
+    let settings = ctx
+        .api
+        .get_chat_settings(&ctx.token, id, id)
+        .await
+        .map_err(|e| err_json(502, &e.to_string()))?;
+    Ok(Json(json!({ "success": true, "settings": settings })))
+}
+
+/// PUT /api/moderation/shield-mode
+pub async fn set_shield_mode(
+    State(state): State<SharedState>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let is_active = body["is_active"]
+        .as_bool()
+        .ok_or_else(|| err_json(400, "is_active must be a boolean"))?;
+    let ctx = helix::context(&state)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    let id = &ctx.broadcaster_id;
+    ctx.api
+        .update_shield_mode(&ctx.token, id, id, is_active)
+        .await
+        .map_err(|e| err_json(502, &e.to_string()))?;
+    Ok(Json(json!({ "success": true, "is_active": is_active })))
+}
@@ -0,0 +1,48 @@
+//! Notification window interaction API (dismiss, action buttons).
+
+use axum::Json;
+use axum::extract::State;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::notification::types::{NotificationAction, NotificationActionKind};
+use crate::notification::{actions, queue};
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// POST /api/notification/dismiss
+pub async fn dismiss(State(_state): State<SharedState>) -> ApiResult {
+    queue::dismiss_current();
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/notification/action
+///
+/// Body: `{ "action": "shoutout", "targetUserId": "..." }` (the shape the
+/// notification payload carries).
+pub async fn run_action(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let action: NotificationActionKind =
+        serde_json::from_value(body.get("action").cloned().unwrap_or(Value::Null))
+            .map_err(|_| err_json(400, "Unknown or missing action"))?;
+    let target_user_id = body
+        .get("targetUserId")
+        .or_else(|| body.get("target_user_id"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    if target_user_id.is_empty() {
+        return Err(err_json(400, "targetUserId is required"));
+    }
+
+    let action = NotificationAction {
+        action,
+        label: String::new(),
+        target_user_id,
+    };
+    actions::run(&state, &action)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
@@ -0,0 +1,53 @@
+//! OBS integration API (see `services::obs` for the rule format):
+//!   GET    /api/obs/status       – settings, connection state and current scene
+//!   GET    /api/obs/rules        – list rules
+//!   POST   /api/obs/rules        – add rule `{ event, min_amount?, params }`
+//!   PUT    /api/obs/rules/{id}   – update rule `{ event, min_amount?, params, enabled? }`
+//!   DELETE /api/obs/rules/{id}   – delete rule
+//!
+//! Rules are `event_triggers` of the `obs` integration, so they are also
+//! reachable through `/api/integrations/obs/triggers`.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::services::obs;
+
+use super::integrations;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+fn integration() -> Path<String> {
+    Path(obs::INTEGRATION.to_string())
+}
+
+/// GET /api/obs/status
+pub async fn get_status(State(state): State<SharedState>) -> Json<Value> {
+    Json(obs::status(&state).await)
+}
+
+/// GET /api/obs/rules
+pub async fn get_rules(state: State<SharedState>) -> ApiResult {
+    integrations::get_triggers(state, integration()).await
+}
+
+/// POST /api/obs/rules
+pub async fn add_rule(state: State<SharedState>, body: Json<Value>) -> ApiResult {
+    integrations::add_trigger(state, integration(), body).await
+}
+
+/// PUT /api/obs/rules/{id}
+pub async fn update_rule(
+    state: State<SharedState>,
+    Path(id): Path<i64>,
+    body: Json<Value>,
+) -> ApiResult {
+    integrations::update_trigger(state, Path((obs::INTEGRATION.to_string(), id)), body).await
+}
+
+/// DELETE /api/obs/rules/{id}
+pub async fn delete_rule(state: State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    integrations::delete_trigger(state, Path((obs::INTEGRATION.to_string(), id))).await
+}
@@ -3,11 +3,23 @@
 //!   POST /api/settings/overlay         – update overlay settings (partial)
 //!   POST /api/overlay/refresh          – re-broadcast settings to all WS clients
 //!   GET  /api/settings/overlay/events  – SSE stream of overlay setting changes
+//!   GET  /api/overlay/preview          – preview simulator status
+//!   POST /api/overlay/preview          – start/stop the preview simulator
+//!   POST /api/overlay/preview/inject   – send one event to preview clients only
+//!   GET  /api/overlay/effects          – built-in effects and saved presets
+//!   POST /api/overlay/effects          – trigger an effect (`{effect|preset, params, duration_ms}`)
+//!   PUT  /api/overlay/effects/presets/{name} – save a preset
+//!   DELETE /api/overlay/effects/presets/{name} – delete a preset
+//!   GET  /api/overlay/urls             – signed browser-source URLs (`?widget=&layout=&topics=&session=`)
+//!   POST /api/overlay/urls/rotate      – new signing key (old URLs stop working)
+//!   POST /api/overlay/session/rotate   – end the overlay session (session URLs stop working)
+//!   GET  /api/overlay/token            – claims of a signed token (`?token=`)
 
 use axum::Json;
-use axum::extract::State;
+use axum::extract::{Path, Query, State};
 use axum::response::sse::{Event, KeepAlive, Sse};
 use futures::stream::Stream;
+use serde::Deserialize;
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::convert::Infallible;
@@ -16,6 +28,7 @@ use tokio_stream::wrappers::BroadcastStream;
 
 use crate::app::SharedState;
 use crate::config::SettingsManager;
+use crate::services::{overlay_effects, overlay_preview, overlay_tokens};
 
 use super::err_json;
 
@@ -166,6 +179,46 @@ pub async fn refresh_overlay(
     })))
 }
 
+/// GET /api/overlay/preview
+pub async fn get_preview() -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    Ok(Json(json!({
+        "simulating": overlay_preview::is_running().await,
+        "url": "/overlay/?preview=1",
+    })))
+}
+
+/// POST /api/overlay/preview
+///
+/// Body: `{ "simulate": bool }`.
+pub async fn set_preview(
+    Json(body): Json<Value>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    let Some(simulate) = body.get("simulate").and_then(|v| v.as_bool()) else {
+        return Err(err_json(400, "simulate must be a boolean"));
+    };
+    if simulate {
+        overlay_preview::start().await;
+    } else {
+        overlay_preview::stop().await;
+    }
+    Ok(Json(json!({ "success": true, "simulating": simulate })))
+}
+
+/// POST /api/overlay/preview/inject
+///
+/// Body: `{ "type": "fax", "data": { ... } }` — same payloads as `/debug/*`.
+pub async fn inject_preview_event(
+    Json(body): Json<Value>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    let event_type = body
+        .get("type")
+        .and_then(|v| v.as_str())
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| err_json(400, "type is required"))?;
+    overlay_preview::inject(event_type, body.get("data").cloned().unwrap_or(Value::Null));
+    Ok(Json(json!({ "success": true })))
+}
+
 /// Build the overlay settings JSON from DB.
 fn build_overlay_json(state: &SharedState) -> Result<Value, (axum::http::StatusCode, Json<Value>)> {
     let sm = SettingsManager::new(state.db().clone());
@@ -203,6 +256,166 @@ pub async fn overlay_events(
     Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
+/// GET /api/overlay/effects
+pub async fn get_effects(
+    State(state): State<SharedState>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    let presets = state
+        .db()
+        .get_effect_presets()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let effects: Vec<Value> = overlay_effects::EFFECTS
+        .iter()
+        .map(|(name, duration_ms)| json!({ "effect": name, "default_duration_ms": duration_ms }))
+        .collect();
+    Ok(Json(json!({ "effects": effects, "presets": presets })))
+}
+
+/// POST /api/overlay/effects
+pub async fn trigger_effect(
+    State(state): State<SharedState>,
+    Json(body): Json<Value>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    let effect = overlay_effects::resolve(&state, &body).map_err(|e| err_json(400, &e))?;
+    let sent = overlay_effects::trigger(&state, &effect, json!({ "event": "api" }));
+    Ok(Json(json!({ "success": true, "effect": sent })))
+}
+
+/// PUT /api/overlay/effects/presets/{name}
+pub async fn save_effect_preset(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(body): Json<Value>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    let effect = body["effect"].as_str().unwrap_or_default();
+    let params = body.get("params").cloned().unwrap_or(json!({}));
+    let duration_ms = body["duration_ms"].as_i64().unwrap_or_else(|| {
+        overlay_effects::EFFECTS
+            .iter()
+            .find(|(n, _)| *n == effect)
+            .map_or(0, |(_, ms)| *ms)
+    });
+    overlay_effects::validate_preset(effect, &params, duration_ms)
+        .map_err(|e| err_json(400, &e))?;
+    state
+        .db()
+        .upsert_effect_preset(
+            &name,
+            effect,
+            &params,
+            duration_ms,
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "success": true, "name": name })))
+}
+
+/// DELETE /api/overlay/effects/presets/{name}
+pub async fn delete_effect_preset(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    let deleted = state
+        .db()
+        .delete_effect_preset(&name)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Preset not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OverlayUrlQuery {
+    pub widget: Option<String>,
+    pub layout: Option<String>,
+    /// Comma-separated WebSocket message types.
+    pub topics: Option<String>,
+    /// Issue session tokens that expire when the stream ends.
+    #[serde(default)]
+    pub session: bool,
+}
+
+/// GET /api/overlay/urls
+///
+/// One URL per widget, or only `widget` when given; `session=true` binds
+/// them to the current overlay session.
+pub async fn get_overlay_urls(
+    State(state): State<SharedState>,
+    Query(q): Query<OverlayUrlQuery>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    let layout = q.layout.as_deref().unwrap_or("default");
+    let topics: Vec<String> = q
+        .topics
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+    let widgets: Vec<_> = overlay_tokens::WIDGETS
+        .iter()
+        .filter(|(id, _, _)| q.widget.as_deref().is_none_or(|w| w == *id))
+        .collect();
+    if widgets.is_empty() {
+        return Err(err_json(400, "Unknown widget"));
+    }
+
+    let port = state.server_port();
+    let mut urls = Vec::new();
+    for (id, _, description) in widgets {
+        let token = overlay_tokens::issue(&state, id, layout, topics.clone(), q.session)
+            .map_err(|e| err_json(400, &e))?;
+        urls.push(json!({
+            "widget": id,
+            "description": description,
+            "layout": layout,
+            "topics": topics,
+            "session": q.session,
+            "url": overlay_tokens::widget_url(port, id, &token),
+        }));
+    }
+    Ok(Json(json!({
+        "urls": urls,
+        "layouts": overlay_tokens::LAYOUTS,
+    })))
+}
+
+/// POST /api/overlay/urls/rotate
+pub async fn rotate_overlay_urls(
+    State(state): State<SharedState>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    overlay_tokens::rotate_secret(&state).map_err(|e| err_json(500, &e))?;
+    tracing::info!("Overlay URL signing key rotated");
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/overlay/session/rotate
+///
+/// End the overlay session now instead of at stream end.
+pub async fn rotate_overlay_session(
+    State(state): State<SharedState>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    overlay_tokens::rotate_session(&state).map_err(|e| err_json(500, &e))?;
+    tracing::info!("Overlay session rotated");
+    Ok(Json(json!({ "success": true })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OverlayTokenQuery {
+    pub token: String,
+}
+
+/// GET /api/overlay/token
+pub async fn get_overlay_token(
+    State(state): State<SharedState>,
+    Query(q): Query<OverlayTokenQuery>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    let claims = overlay_tokens::verify_token(&state, &q.token).map_err(|e| err_json(401, &e))?;
+    Ok(Json(json!(claims)))
+}
+
 /// Broadcast overlay settings to all WebSocket clients.
 fn broadcast_overlay_settings(
     state: &SharedState,
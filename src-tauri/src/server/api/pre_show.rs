@@ -0,0 +1,44 @@
+//! Starting-soon pre-show API (see `services::pre_show`):
+//!   GET  /api/stream/pre-show         – current phase and countdown
+//!   POST /api/stream/pre-show/start   – `{ minutes?, title?, playlist? }`
+//!   POST /api/stream/pre-show/stop    – cancel without going live
+//!
+//! Omitted fields fall back to the `PRE_SHOW_*` settings; `playlist: ""`
+//! starts without music.
+
+use axum::Json;
+use axum::extract::State;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::pre_show;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// GET /api/stream/pre-show
+pub async fn get_pre_show() -> Json<Value> {
+    Json(json!({ "pre_show": pre_show::current() }))
+}
+
+/// POST /api/stream/pre-show/start
+pub async fn start(State(state): State<SharedState>, body: Option<Json<Value>>) -> ApiResult {
+    let body = body.map(|b| b.0).unwrap_or_default();
+    let minutes = match body.get("minutes") {
+        None | Some(Value::Null) => None,
+        Some(v) => Some(
+            v.as_i64()
+                .ok_or_else(|| err_json(400, "minutes must be an integer"))?,
+        ),
+    };
+    let text = |key: &str| body.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let pre_show = pre_show::start(&state, minutes, text("title"), text("playlist"))
+        .map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({ "success": true, "pre_show": pre_show })))
+}
+
+/// POST /api/stream/pre-show/stop
+pub async fn stop(State(state): State<SharedState>) -> Json<Value> {
+    Json(json!({ "success": true, "pre_show": pre_show::cancel(&state) }))
+}
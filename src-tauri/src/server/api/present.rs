@@ -2,28 +2,20 @@
 
 use axum::Json;
 use axum::extract::State;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
 use serde_json::{Value, json};
-use std::sync::LazyLock;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::sync::RwLock;
 
 use crate::app::SharedState;
-use overlay_db::lottery::LotteryParticipant;
+use crate::config::SettingsManager;
+use crate::services::{lottery, lottery_claims, lottery_draw, lottery_receipt, lottery_winner};
+use overlay_db::lottery::{CLAIM_SOURCE_MANUAL, LotteryParticipant};
+use overlay_db::lottery_engine::{ENTRY_LIMIT, TicketFormula};
 
 use super::err_json;
 
 type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
 
-#[derive(Debug, Clone, Default)]
-struct LotteryRuntimeState {
-    is_running: bool,
-    is_locked: bool,
-    winner: Option<LotteryParticipant>,
-}
-
-static LOTTERY_RUNTIME: LazyLock<RwLock<LotteryRuntimeState>> =
-    LazyLock::new(|| RwLock::new(LotteryRuntimeState::default()));
-
 /// GET /api/lottery
 pub async fn get_lottery(State(state): State<SharedState>) -> ApiResult {
     let participants = get_all_participants(&state)?;
@@ -46,6 +38,7 @@ pub async fn add_participant(
         redeemed_at: body["redeemed_at"].as_str().unwrap_or("").to_string(),
         is_subscriber: body["is_subscriber"].as_bool().unwrap_or(false),
         subscriber_tier: body["subscriber_tier"].as_str().unwrap_or("").to_string(),
+        subscribed_months: body["subscribed_months"].as_i64().unwrap_or(0) as i32,
         entry_count: body["entry_count"].as_i64().unwrap_or(1) as i32,
         assigned_color: body["assigned_color"]
             .as_str()
@@ -70,9 +63,11 @@ pub async fn clear_lottery(State(state): State<SharedState>) -> ApiResult {
         .clear_all_lottery_participants()
         .map_err(|e| err_json(500, &e.to_string()))?;
 
-    let mut runtime = LOTTERY_RUNTIME.write().await;
+    let mut runtime = lottery::runtime().await;
     runtime.is_running = false;
     runtime.winner = None;
+    lottery_receipt::discard().await;
+    lottery::close_claims(&state);
 
     broadcast_participants_cleared(&state);
     Ok(Json(json!({ "success": true })))
@@ -94,17 +89,26 @@ pub async fn remove_participant(
 /// GET /api/present/participants
 pub async fn get_present_participants(State(state): State<SharedState>) -> ApiResult {
     let participants = get_all_participants(&state)?;
-    let mut runtime = LOTTERY_RUNTIME.write().await;
-    if let Ok(Some(locked)) = state.db().get_setting("LOTTERY_LOCKED") {
+    let mut runtime = lottery::runtime().await;
+    if let Ok(locked) = SettingsManager::new(state.db().clone()).get_setting("LOTTERY_LOCKED") {
         runtime.is_locked = locked == "true";
     }
 
+    let formula = lottery::ticket_formula(&state);
+    let tickets: serde_json::Map<String, Value> = participants
+        .iter()
+        .map(|p| (p.user_id.clone(), json!(formula.for_participant(p))))
+        .collect();
+
     Ok(Json(json!({
         "enabled": true,
         "is_running": runtime.is_running,
         "is_locked": runtime.is_locked,
         "participants": participants,
+        "tickets": tickets,
+        "commitment": lottery_draw::commitment(),
         "winner": runtime.winner.clone(),
+        "receipt": lottery_receipt::pending().await,
     })))
 }
 
@@ -135,7 +139,8 @@ pub async fn add_test_participant(State(state): State<SharedState>) -> ApiResult
         redeemed_at: now,
         is_subscriber,
         subscriber_tier: subscriber_tier.to_string(),
-        entry_count: ((next_id % 3) as i32 + 1).min(3),
+        subscribed_months: if is_subscriber { next_id as i32 } else { 0 },
+        entry_count: (next_id % 3) as i32 + 1,
         assigned_color: "#ffffff".to_string(),
     };
 
@@ -161,27 +166,36 @@ pub async fn start_present(State(state): State<SharedState>) -> ApiResult {
         return Err(err_json(400, "No participants"));
     }
 
-    let mut runtime = LOTTERY_RUNTIME.write().await;
+    let mut runtime = lottery::runtime().await;
     if runtime.is_running {
         return Err(err_json(400, "Lottery already running"));
     }
     runtime.is_running = true;
     runtime.winner = None;
+    lottery::close_claims(&state);
+    // Fix the seed now so the result can be checked against the commitment.
+    let commitment = lottery_draw::commit();
 
     let msg = json!({
         "type": "lottery_started",
-        "data": { "participants": participants, "started_at": chrono::Utc::now().to_rfc3339() }
+        "data": {
+            "participants": participants,
+            "started_at": chrono::Utc::now().to_rfc3339(),
+            "commitment": commitment,
+        }
     });
     let _ = state.ws_sender().send(msg.to_string());
 
-    Ok(Json(
-        json!({ "success": true, "message": "Lottery started" }),
-    ))
+    Ok(Json(json!({
+        "success": true,
+        "message": "Lottery started",
+        "commitment": commitment,
+    })))
 }
 
 /// POST /api/present/stop
 pub async fn stop_present(State(state): State<SharedState>) -> ApiResult {
-    let mut runtime = LOTTERY_RUNTIME.write().await;
+    let mut runtime = lottery::runtime().await;
     runtime.is_running = false;
 
     let msg = json!({
@@ -197,37 +211,113 @@ pub async fn stop_present(State(state): State<SharedState>) -> ApiResult {
 
 /// POST /api/present/draw
 pub async fn draw_present(State(state): State<SharedState>) -> ApiResult {
-    let participants = get_all_participants(&state)?;
-    if participants.is_empty() {
-        return Err(err_json(400, "No participants"));
-    }
+    let draw = lottery::draw_excluding(&state, &[], None)
+        .await
+        .map_err(|e| match e {
+            lottery::DrawError::Db(e) => err_json(500, &e.to_string()),
+            e => err_json(400, &e.to_string()),
+        })?;
+    Ok(Json(json!({
+        "success": true,
+        "winner": draw.winner,
+        "winner_index": draw.winner_index,
+        "draw_id": draw.draw_id,
+        "redraw_of": null,
+        "receipt": draw.receipt,
+        "proof": draw.proof,
+    })))
+}
 
-    let weighted = weighted_participants(&participants);
-    let now_nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos() as usize)
-        .unwrap_or(0);
-    let winner = weighted[now_nanos % weighted.len()].clone();
-    let winner_index = participants
-        .iter()
-        .position(|p| p.user_id == winner.user_id)
-        .unwrap_or(0);
+/// GET /api/present/history
+///
+/// Recent draws with how each winner was told.
+pub async fn get_draw_history(State(state): State<SharedState>) -> ApiResult {
+    let draws = state
+        .db()
+        .get_lottery_draws(50)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "draws": draws })))
+}
 
-    let mut runtime = LOTTERY_RUNTIME.write().await;
-    runtime.winner = Some(winner.clone());
-    runtime.is_running = false;
+/// POST /api/present/history/{id}/notify
+///
+/// Whisper and announce the winner of draw `id` again.
+pub async fn renotify_winner(
+    State(state): State<SharedState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> ApiResult {
+    let draw = state
+        .db()
+        .get_lottery_draw(id)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Draw not found"))?;
+    let delivery = lottery_winner::notify(&state, &draw).await;
+    Ok(Json(json!({ "success": true, "delivery": delivery })))
+}
 
-    let msg = json!({
-        "type": "lottery_winner",
-        "data": { "winner": winner, "winner_index": winner_index }
-    });
-    let _ = state.ws_sender().send(msg.to_string());
+/// POST /api/present/history/{id}/claim
+///
+/// Mark the winner of draw `id` as having claimed their prize.
+pub async fn claim_draw(
+    State(state): State<SharedState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> ApiResult {
+    let draw = state
+        .db()
+        .get_lottery_draw(id)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Draw not found"))?;
+    let claimed =
+        lottery_claims::claim(&state, &draw, CLAIM_SOURCE_MANUAL).map_err(|e| err_json(500, &e))?;
+    if !claimed {
+        return Err(err_json(409, "Draw is not waiting for a claim"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
 
-    Ok(Json(json!({
-        "success": true,
-        "winner": runtime.winner.clone(),
-        "winner_index": winner_index,
-    })))
+/// GET /api/present/winner/preview
+///
+/// PNG of the pending winner receipt as it would be printed.
+pub async fn get_winner_preview() -> Result<Response, (axum::http::StatusCode, Json<Value>)> {
+    let png = lottery_receipt::preview_png()
+        .await
+        .ok_or_else(|| err_json(404, "No winner receipt pending"))?;
+    Ok(([(header::CONTENT_TYPE, "image/png")], png).into_response())
+}
+
+/// POST /api/present/winner/preview
+///
+/// Re-render the pending receipt with `{ "prize": ".." }`.
+pub async fn update_winner_preview(
+    State(state): State<SharedState>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let prize = body["prize"].as_str().unwrap_or_default();
+    let receipt = lottery_receipt::set_prize(&state, prize)
+        .await
+        .map_err(|e| err_json(500, &e))?
+        .ok_or_else(|| err_json(404, "No winner receipt pending"))?;
+    Ok(Json(json!({ "success": true, "receipt": receipt })))
+}
+
+/// POST /api/present/winner/print
+///
+/// Print the pending receipt, after re-rendering it when the body has a
+/// `prize`.
+pub async fn print_winner_receipt(
+    State(state): State<SharedState>,
+    body: Option<Json<Value>>,
+) -> ApiResult {
+    if let Some(prize) = body.as_ref().and_then(|Json(b)| b["prize"].as_str()) {
+        lottery_receipt::set_prize(&state, prize)
+            .await
+            .map_err(|e| err_json(500, &e))?;
+    }
+    let receipt = lottery_receipt::print(&state)
+        .await
+        .map_err(|e| err_json(500, &e))?
+        .ok_or_else(|| err_json(404, "No winner receipt pending"))?;
+    Ok(Json(json!({ "success": true, "receipt": receipt })))
 }
 
 /// POST /api/present/clear
@@ -237,12 +327,11 @@ pub async fn clear_present(State(state): State<SharedState>) -> ApiResult {
 
 /// POST /api/present/lock
 pub async fn lock_present(State(state): State<SharedState>) -> ApiResult {
-    state
-        .db()
-        .set_setting("LOTTERY_LOCKED", "true", "normal")
+    SettingsManager::new(state.db().clone())
+        .set_setting("LOTTERY_LOCKED", "true")
         .map_err(|e| err_json(500, &e.to_string()))?;
 
-    let mut runtime = LOTTERY_RUNTIME.write().await;
+    let mut runtime = lottery::runtime().await;
     runtime.is_locked = true;
 
     let msg = json!({
@@ -258,12 +347,11 @@ pub async fn lock_present(State(state): State<SharedState>) -> ApiResult {
 
 /// POST /api/present/unlock
 pub async fn unlock_present(State(state): State<SharedState>) -> ApiResult {
-    state
-        .db()
-        .set_setting("LOTTERY_LOCKED", "false", "normal")
+    SettingsManager::new(state.db().clone())
+        .set_setting("LOTTERY_LOCKED", "false")
         .map_err(|e| err_json(500, &e.to_string()))?;
 
-    let mut runtime = LOTTERY_RUNTIME.write().await;
+    let mut runtime = lottery::runtime().await;
     runtime.is_locked = false;
 
     let msg = json!({
@@ -277,6 +365,45 @@ pub async fn unlock_present(State(state): State<SharedState>) -> ApiResult {
     ))
 }
 
+/// POST /api/present/simulate-tickets
+///
+/// Preview tickets for `user_id` (a current participant) or for the given
+/// `entry_count`, `is_subscriber`, `subscriber_tier` and `subscribed_months`.
+/// A `formula` object previews that formula instead of the saved one.
+pub async fn simulate_tickets(
+    State(state): State<SharedState>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let formula = match body.get("formula") {
+        Some(formula) => TicketFormula::parse(&formula.to_string())
+            .map_err(|e| err_json(400, &format!("Invalid formula: {e}")))?,
+        None => lottery::ticket_formula(&state),
+    };
+    let participant = match body["user_id"].as_str() {
+        Some(user_id) => Some(
+            get_all_participants(&state)?
+                .into_iter()
+                .find(|p| p.user_id == user_id)
+                .ok_or_else(|| err_json(404, "Participant not found"))?,
+        ),
+        None => None,
+    };
+    let tickets = match &participant {
+        Some(p) => formula.for_participant(p),
+        None => formula.tickets(
+            body["entry_count"].as_i64().unwrap_or(1) as i32,
+            body["is_subscriber"].as_bool().unwrap_or(false),
+            body["subscriber_tier"].as_str().unwrap_or(""),
+            body["subscribed_months"].as_i64().unwrap_or(0) as i32,
+        ),
+    };
+    Ok(Json(json!({
+        "tickets": tickets,
+        "formula": formula,
+        "participant": participant,
+    })))
+}
+
 /// POST /api/present/refresh-subscribers
 pub async fn refresh_present_subscribers(State(state): State<SharedState>) -> ApiResult {
     let participants = get_all_participants(&state)?;
@@ -307,7 +434,7 @@ pub async fn refresh_present_subscribers(State(state): State<SharedState>) -> Ap
         scope: db_token.scope,
         expires_at: db_token.expires_at,
     };
-    let api = twitch_client::api::TwitchApiClient::new(client_id);
+    let api = twitch_client::api::TwitchApiClient::with_http(client_id, state.http());
 
     let mut updated_count = 0u32;
     let mut updated_participants = participants.clone();
@@ -391,7 +518,10 @@ pub async fn update_present_participant(
         if p.user_id == user_id {
             found = true;
             if let Some(entry_count) = body.get("entry_count").and_then(|v| v.as_i64()) {
-                p.entry_count = (entry_count as i32).clamp(1, 3);
+                p.entry_count = (entry_count as i32).clamp(1, ENTRY_LIMIT);
+            }
+            if let Some(months) = body.get("subscribed_months").and_then(|v| v.as_i64()) {
+                p.subscribed_months = (months as i32).max(0);
             }
             if let Some(is_subscriber) = body.get("is_subscriber").and_then(|v| v.as_bool()) {
                 p.is_subscriber = is_subscriber;
@@ -437,20 +567,6 @@ fn get_all_participants(
         .map_err(|e| err_json(500, &e.to_string()))
 }
 
-fn weighted_participants(participants: &[LotteryParticipant]) -> Vec<LotteryParticipant> {
-    let mut weighted = Vec::new();
-    for p in participants {
-        let count = p.entry_count.max(1).min(3) as usize;
-        for _ in 0..count {
-            weighted.push(p.clone());
-        }
-    }
-    if weighted.is_empty() {
-        return participants.to_vec();
-    }
-    weighted
-}
-
 fn broadcast_participant_added(state: &SharedState, participant: &LotteryParticipant) {
     let msg = json!({ "type": "lottery_participant_added", "data": participant });
     let _ = state.ws_sender().send(msg.to_string());
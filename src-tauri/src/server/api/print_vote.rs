@@ -0,0 +1,40 @@
+//! Chat-to-print voting API (see `services::print_vote`):
+//!   GET    /api/chat/print-votes         – settings, tally, recent winners
+//!   POST   /api/chat/print-votes/print   – print the current winner now
+//!   DELETE /api/chat/print-votes         – drop the current round's votes
+
+use axum::Json;
+use axum::extract::State;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::eventsub_support::send_ws;
+use crate::services::print_vote;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// GET /api/chat/print-votes
+pub async fn get_print_votes(State(state): State<SharedState>) -> Json<Value> {
+    Json(print_vote::status(&state))
+}
+
+/// POST /api/chat/print-votes/print
+pub async fn print_winner(State(state): State<SharedState>) -> ApiResult {
+    match print_vote::print_winner(&state).await {
+        Ok(Some(winner)) => Ok(Json(json!({ "success": true, "winner": winner }))),
+        Ok(None) => Err(err_json(400, "No message has enough votes")),
+        Err(e) => Err(err_json(500, &e)),
+    }
+}
+
+/// DELETE /api/chat/print-votes
+pub async fn clear_print_votes(State(state): State<SharedState>) -> ApiResult {
+    let cleared = state
+        .db()
+        .clear_print_votes()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    send_ws(&state, "print_vote", json!({ "tally": [] }));
+    Ok(Json(json!({ "success": true, "cleared": cleared })))
+}
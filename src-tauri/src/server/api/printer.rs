@@ -1,12 +1,19 @@
-//! Printer control API (scan, test, status, reconnect).
+//! Printer control API (scan, test, status, printers, reconnect, image
+//! prints and previews, schedules, queue).
 
 use axum::Json;
-use axum::extract::State;
+use axum::extract::{FromRequest, Multipart, Path, Request, State};
+use axum::http::header::CONTENT_TYPE;
+use base64::Engine;
 use serde_json::{Value, json};
 
 use crate::app::SharedState;
+use crate::services::print_queue::{self, PrintCategory, PrinterTarget};
+use crate::services::print_render;
 use crate::services::printer;
 use crate::services::printer_pipeline;
+use crate::services::scheduler;
+use crate::services::smart_plug;
 
 type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
 
@@ -88,28 +95,34 @@ pub async fn test_printer(State(_state): State<SharedState>, Json(body): Json<Va
 
 /// GET /api/printer/status
 pub async fn printer_status(State(state): State<SharedState>) -> ApiResult {
-    let (dry_run_mode, mut printer_type, printer_address, usb_printer_name) = {
+    let (dry_run_mode, mut printer_type, printer_address, usb_printer_name, escpos_device) = {
         let config = state.config().await;
         (
             config.dry_run_mode,
             config.printer_type.clone(),
             config.printer_address.clone(),
             config.usb_printer_name.clone(),
+            config.uses_escpos().then(|| config.escpos_device.clone()),
         )
     };
 
     if printer_type.is_empty() {
         printer_type = "bluetooth".to_string();
     }
+    if escpos_device.is_some() {
+        printer_type = "escpos".to_string();
+    }
 
-    let configured = if printer_type == "usb" {
-        !usb_printer_name.is_empty()
-    } else {
-        !printer_address.is_empty()
+    let configured = match &escpos_device {
+        Some(device) => !device.is_empty(),
+        None if printer_type == "usb" => !usb_printer_name.is_empty(),
+        None => !printer_address.is_empty(),
     };
 
     let runtime = printer::get_runtime_state().await;
-    let connected = if printer_type == "usb" {
+    let connected = if let Some(device) = &escpos_device {
+        printer::is_escpos_device_available(device)
+    } else if printer_type == "usb" {
         if usb_printer_name.is_empty() {
             false
         } else {
@@ -129,12 +142,51 @@ pub async fn printer_status(State(state): State<SharedState>) -> ApiResult {
         "printer_address": printer_address,
         "printer_type": printer_type,
         "usb_printer_name": usb_printer_name,
+        "escpos_device": escpos_device,
         "configured": configured,
-        "print_queue": 0,
+        "print_queue": print_queue::queue_status(&state).await.0,
         "error": runtime.last_error,
     })))
 }
 
+/// GET /api/printer/printers – Configured printers, their state and routes
+pub async fn list_printers(State(state): State<SharedState>) -> ApiResult {
+    let registry = print_queue::registry(&state).await;
+    let mut printers = Vec::with_capacity(registry.printers.len());
+    for p in &registry.printers {
+        let runtime = printer::get_printer_state(&p.name).await;
+        let connected = match &p.target {
+            PrinterTarget::Bluetooth { address } => {
+                runtime.connected && runtime.connected_target.as_deref() == Some(address.as_str())
+            }
+            PrinterTarget::Usb { printer_name } => {
+                !printer_name.is_empty()
+                    && printer::is_usb_printer_available(printer_name)
+                        .await
+                        .unwrap_or(false)
+            }
+            PrinterTarget::Escpos { device, .. } => printer::is_escpos_device_available(device),
+        };
+        let categories: Vec<&str> = PrintCategory::ROUTABLE
+            .into_iter()
+            .filter(|c| registry.resolve(*c).is_some_and(|r| r.name == p.name))
+            .map(PrintCategory::as_str)
+            .collect();
+        printers.push(json!({
+            "name": p.name,
+            "type": p.target.kind(),
+            "target": p.target.target(),
+            "connected": connected,
+            "error": runtime.last_error,
+            "categories": categories,
+        }));
+    }
+    Ok(Json(json!({
+        "printers": printers,
+        "routes": registry.routes,
+    })))
+}
+
 /// POST /api/printer/reconnect
 pub async fn reconnect_printer(State(state): State<SharedState>) -> ApiResult {
     let (mut printer_type, printer_address) = {
@@ -176,7 +228,7 @@ pub async fn reconnect_printer(State(state): State<SharedState>) -> ApiResult {
 
 /// POST /api/printer/test-print
 pub async fn test_print(State(state): State<SharedState>, Json(_body): Json<Value>) -> ApiResult {
-    let (dry_run_mode, mut printer_type, printer_address, usb_printer_name, rotate_print) = {
+    let (dry_run_mode, mut printer_type, printer_address, usb_printer_name, rotate_print, escpos) = {
         let config = state.config().await;
         (
             config.dry_run_mode,
@@ -184,12 +236,24 @@ pub async fn test_print(State(state): State<SharedState>, Json(_body): Json<Valu
             config.printer_address.clone(),
             config.usb_printer_name.clone(),
             config.rotate_print,
+            config
+                .uses_escpos()
+                .then(|| (config.escpos_device.clone(), config.escpos_cut)),
         )
     };
 
     if printer_type.is_empty() {
         printer_type = "bluetooth".to_string();
     }
+    if escpos.is_some() {
+        printer_type = "escpos".to_string();
+    }
+
+    if let Some((device, _)) = &escpos
+        && device.is_empty()
+    {
+        return Err(err_json(400, "ESC/POS printer device is not configured"));
+    }
 
     if printer_type == "usb" && usb_printer_name.is_empty() {
         return Err(err_json(400, "USB printer name is not configured"));
@@ -211,6 +275,10 @@ pub async fn test_print(State(state): State<SharedState>, Json(_body): Json<Valu
     let bitmap = printer_pipeline::generate_test_bitmap(width);
 
     let print_result = match printer_type.as_str() {
+        "escpos" => {
+            let (device, cut) = escpos.unwrap_or_default();
+            printer_pipeline::print_bitmap_escpos(&device, &bitmap, width, rotate_print, cut).await
+        }
         "usb" => {
             printer_pipeline::print_bitmap_usb(&usb_printer_name, &bitmap, width, rotate_print)
                 .await
@@ -250,3 +318,217 @@ pub async fn list_system_printers(State(_state): State<SharedState>) -> ApiResul
         }
     }
 }
+
+/// GET /api/printer/schedules – scheduled prints with their next run
+pub async fn get_schedules(State(state): State<SharedState>) -> ApiResult {
+    let (tz, schedules) = scheduler::list(&state);
+    Ok(Json(json!({
+        "timezone": tz.name(),
+        "now": chrono::Utc::now().with_timezone(&tz).to_rfc3339(),
+        "schedules": schedules,
+    })))
+}
+
+/// Multipart fields shared by `print-image` and `preview`: the uploaded
+/// file and its options (unset options follow the printer settings).
+async fn read_image_upload(
+    state: &SharedState,
+    mut multipart: Multipart,
+) -> Result<(String, Vec<u8>, print_render::ImagePrintOptions), (axum::http::StatusCode, Json<Value>)>
+{
+    let mut options = {
+        let config = state.config().await;
+        print_render::ImagePrintOptions {
+            dither: config.dither,
+            black_point: config.black_point,
+            auto_rotate: config.auto_rotate,
+        }
+    };
+    let mut upload: Option<(String, Vec<u8>)> = None;
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        let name = field.name().unwrap_or("").to_string();
+        match name.as_str() {
+            "image" => {
+                let filename = field.file_name().unwrap_or("image").to_string();
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| err_json(400, &e.to_string()))?;
+                upload = Some((filename, data.to_vec()));
+            }
+            "dither" => options.dither = field.text().await.unwrap_or_default() == "true",
+            "auto_rotate" => {
+                options.auto_rotate = field.text().await.unwrap_or_default() == "true";
+            }
+            "black_point" => {
+                let text = field.text().await.unwrap_or_default();
+                options.black_point = text
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|v| (0.0..=1.0).contains(v))
+                    .ok_or_else(|| err_json(400, "black_point must be between 0.0 and 1.0"))?;
+            }
+            _ => {}
+        }
+    }
+
+    let (filename, data) = upload.ok_or_else(|| err_json(400, "No image provided"))?;
+    Ok((filename, data, options))
+}
+
+/// POST /api/printer/print-image – print an uploaded PNG/JPEG
+///
+/// Multipart fields: `image` (file), optional `dither` / `auto_rotate`
+/// (`true`/`false`) and `black_point` (0.0–1.0); unset options follow the
+/// printer settings.
+pub async fn print_image(State(state): State<SharedState>, multipart: Multipart) -> ApiResult {
+    let (filename, data, options) = read_image_upload(&state, multipart).await?;
+    let height =
+        print_render::print_uploaded_image(&state, &data, options, &format!("Image {filename}"))
+            .await
+            .map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({
+        "success": true,
+        "height": height,
+        "dither": options.dither,
+        "auto_rotate": options.auto_rotate,
+    })))
+}
+
+/// POST /api/printer/preview – render a print to PNG without printing
+///
+/// Takes the same multipart payload as `print-image`; a JSON body (as sent
+/// to `test-print`) previews the test pattern. The PNG is what the printer
+/// would output, after dithering and `rotate_print`.
+pub async fn preview_print(State(state): State<SharedState>, req: Request) -> ApiResult {
+    let is_multipart = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("multipart/form-data"));
+    let rotate_print = state.config().await.rotate_print;
+
+    let (preview, options) = if is_multipart {
+        let multipart = Multipart::from_request(req, &state)
+            .await
+            .map_err(|e| err_json(400, &e.body_text()))?;
+        let (_, data, options) = read_image_upload(&state, multipart).await?;
+        let img = print_render::decode_upload(&data, options).map_err(|e| err_json(400, &e))?;
+        let preview =
+            print_render::preview_image(&img, options.dither, options.black_point, rotate_print);
+        (preview, Some(options))
+    } else {
+        let width = catprinter::PRINT_WIDTH;
+        let bitmap = printer_pipeline::generate_test_bitmap(width);
+        (
+            print_render::preview_bitmap(&bitmap, width, rotate_print),
+            None,
+        )
+    };
+    let preview = preview.map_err(|e| err_json(500, &e))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "png": base64::engine::general_purpose::STANDARD.encode(&preview.png),
+        "width": preview.width,
+        "height": preview.height,
+        "rotate_print": rotate_print,
+        "dither": options.map(|o| o.dither),
+        "auto_rotate": options.map(|o| o.auto_rotate),
+    })))
+}
+
+/// GET /api/printer/queue – pending and failed print jobs
+pub async fn get_queue(State(state): State<SharedState>) -> ApiResult {
+    let snapshot = print_queue::snapshot(&state)
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!(snapshot)))
+}
+
+/// DELETE /api/printer/queue – drop every pending job
+pub async fn clear_queue(State(state): State<SharedState>) -> ApiResult {
+    let removed = print_queue::clear(&state, false)
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "success": true, "removed": removed })))
+}
+
+/// DELETE /api/printer/queue/failed – drop every failed job
+pub async fn clear_failed_jobs(State(state): State<SharedState>) -> ApiResult {
+    let removed = print_queue::clear(&state, true)
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "success": true, "removed": removed })))
+}
+
+/// POST /api/printer/queue/pause
+pub async fn pause_queue() -> ApiResult {
+    print_queue::pause().await;
+    Ok(Json(json!({ "success": true, "paused": true })))
+}
+
+/// POST /api/printer/queue/resume
+pub async fn resume_queue() -> ApiResult {
+    print_queue::resume().await;
+    Ok(Json(json!({ "success": true, "paused": false })))
+}
+
+/// PUT /api/printer/queue/order – reorder pending jobs (`{ "ids": [3, 1, 2] }`)
+pub async fn reorder_queue(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let ids: Vec<i64> = body["ids"]
+        .as_array()
+        .ok_or_else(|| err_json(400, "ids must be an array"))?
+        .iter()
+        .filter_map(Value::as_i64)
+        .collect();
+    let jobs = print_queue::reorder(&state, &ids)
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "success": true, "jobs": jobs })))
+}
+
+/// POST /api/printer/queue/{id}/retry – queue a failed job again
+pub async fn retry_queued_job(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    if !print_queue::retry(&state, id)
+        .await
+        .map_err(|e| err_json(500, &e))?
+    {
+        return Err(err_json(404, "Job not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// DELETE /api/printer/queue/{id} – delete a pending or failed job
+pub async fn cancel_queued_job(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    if !print_queue::cancel(&state, id)
+        .await
+        .map_err(|e| err_json(500, &e))?
+    {
+        return Err(err_json(404, "Job not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// GET /api/printer/power – smart-plug power state
+pub async fn get_power(State(state): State<SharedState>) -> ApiResult {
+    match smart_plug::status(&state).await {
+        Ok(on) => Ok(Json(json!({
+            "enabled": on.is_some(),
+            "on": on,
+        }))),
+        Err(e) => Err(err_json(502, &format!("Smart plug unreachable: {e}"))),
+    }
+}
+
+/// POST /api/printer/power – switch the smart plug (`{ "on": true }`)
+pub async fn set_power(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let on = body["on"]
+        .as_bool()
+        .ok_or_else(|| err_json(400, "on must be a boolean"))?;
+    smart_plug::set_power(&state, on)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    Ok(Json(json!({ "success": true, "on": on })))
+}
@@ -0,0 +1,115 @@
+//! Viewer consent API:
+//!   GET    /api/privacy/consents            – list (`?search=` filters names)
+//!   GET    /api/privacy/consents/{user_id}  – one viewer's effective choices
+//!   PUT    /api/privacy/consents/{user_id}  – set `{ username?, print?, tts?, credits? }`
+//!                                             (`null` resets a scope to the default)
+//!   DELETE /api/privacy/consents/{user_id}  – forget a viewer
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use overlay_db::consents::ConsentScope;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::consent;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct ConsentQuery {
+    pub search: Option<String>,
+}
+
+/// GET /api/privacy/consents
+pub async fn get_consents(
+    State(state): State<SharedState>,
+    Query(q): Query<ConsentQuery>,
+) -> ApiResult {
+    let consents = state
+        .db()
+        .get_consents(q.search.as_deref())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let default_policy = SettingsManager::new(state.db().clone())
+        .get_setting("CONSENT_DEFAULT_POLICY")
+        .unwrap_or_default();
+    Ok(Json(json!({
+        "consents": consents,
+        "count": consents.len(),
+        "default_policy": default_policy,
+    })))
+}
+
+/// GET /api/privacy/consents/{user_id}
+pub async fn get_consent(
+    State(state): State<SharedState>,
+    Path(user_id): Path<String>,
+) -> ApiResult {
+    let recorded = state
+        .db()
+        .get_consent(&user_id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "consent": recorded,
+        "effective": consent::flags(&state, &user_id),
+    })))
+}
+
+/// PUT /api/privacy/consents/{user_id}
+pub async fn set_consent(
+    State(state): State<SharedState>,
+    Path(user_id): Path<String>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let username = body["username"].as_str().unwrap_or_default().trim();
+    let mut changes = Vec::new();
+    for scope in ConsentScope::ALL {
+        let Some(value) = body.get(scope.as_str()) else {
+            continue;
+        };
+        let allowed = match value {
+            Value::Null => None,
+            Value::Bool(b) => Some(*b),
+            _ => {
+                return Err(err_json(
+                    400,
+                    &format!("{} must be true, false or null", scope.as_str()),
+                ));
+            }
+        };
+        changes.push((scope, allowed));
+    }
+    if changes.is_empty() {
+        return Err(err_json(400, "No consent scope given"));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    for (scope, allowed) in changes {
+        state
+            .db()
+            .set_consent(&user_id, username, scope, allowed, "dashboard", now)
+            .map_err(|e| err_json(500, &e.to_string()))?;
+    }
+    Ok(Json(json!({
+        "success": true,
+        "effective": consent::flags(&state, &user_id),
+    })))
+}
+
+/// DELETE /api/privacy/consents/{user_id}
+pub async fn delete_consent(
+    State(state): State<SharedState>,
+    Path(user_id): Path<String>,
+) -> ApiResult {
+    let deleted = state
+        .db()
+        .delete_consent(&user_id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Viewer not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
@@ -0,0 +1,100 @@
+//! Quote database API:
+//!   GET    /api/quotes              – list (`?search=` filters text/author)
+//!   POST   /api/quotes              – add `{ text, author? }`
+//!   PUT    /api/quotes/{id}         – edit `{ text, author? }`
+//!   DELETE /api/quotes/{id}         – delete
+//!   POST   /api/quotes/{id}/print   – print a quote card
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::quotes;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct QuoteQuery {
+    pub search: Option<String>,
+}
+
+fn parse_body(body: &Value) -> Result<(String, String), (axum::http::StatusCode, Json<Value>)> {
+    let text = body["text"].as_str().unwrap_or_default().trim().to_string();
+    if text.is_empty() {
+        return Err(err_json(400, "text is required"));
+    }
+    let author = body["author"]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    Ok((text, author))
+}
+
+/// GET /api/quotes
+pub async fn get_quotes(
+    State(state): State<SharedState>,
+    Query(q): Query<QuoteQuery>,
+) -> ApiResult {
+    let quotes = state
+        .db()
+        .get_quotes(q.search.as_deref())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "quotes": quotes, "count": quotes.len() })))
+}
+
+/// POST /api/quotes
+pub async fn add_quote(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let (text, author) = parse_body(&body)?;
+    let quote = state
+        .db()
+        .add_quote(&text, &author, "dashboard", chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "success": true, "quote": quote })))
+}
+
+/// PUT /api/quotes/{id}
+pub async fn update_quote(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let (text, author) = parse_body(&body)?;
+    let updated = state
+        .db()
+        .update_quote(id, &text, &author)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !updated {
+        return Err(err_json(404, "Quote not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// DELETE /api/quotes/{id}
+pub async fn delete_quote(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let deleted = state
+        .db()
+        .delete_quote(id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Quote not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/quotes/{id}/print
+pub async fn print_quote(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let quote = state
+        .db()
+        .get_quote(id)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Quote not found"))?;
+    quotes::print_quote(&state, &quote)
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
@@ -2,10 +2,12 @@
 
 use axum::Json;
 use axum::extract::{Path, State};
+use overlay_db::rewards::{RewardGroupPolicy, TRIGGER_STREAM_OFFLINE, TRIGGER_STREAM_ONLINE};
 use serde::Deserialize;
 use serde_json::{Value, json};
 
 use crate::app::SharedState;
+use crate::services::{overlay_effects, reward_counts, reward_groups};
 
 use super::err_json;
 
@@ -28,7 +30,7 @@ pub async fn reset_all_counts(State(state): State<SharedState>) -> ApiResult {
         .db()
         .reset_all_reward_counts()
         .map_err(|e| err_json(500, &e.to_string()))?;
-    broadcast_reward_update(&state);
+    reward_counts::broadcast(&state);
     Ok(Json(json!({ "status": "success" })))
 }
 
@@ -38,7 +40,7 @@ pub async fn reset_count(State(state): State<SharedState>, Path(id): Path<String
         .db()
         .reset_reward_count(&id)
         .map_err(|e| err_json(500, &e.to_string()))?;
-    broadcast_reward_update(&state);
+    reward_counts::broadcast(&state);
     Ok(Json(json!({ "status": "success" })))
 }
 
@@ -51,10 +53,73 @@ pub async fn remove_user_from_count(
         .db()
         .remove_one_user_from_reward_count(&reward_id, index)
         .map_err(|e| err_json(500, &e.to_string()))?;
-    broadcast_reward_update(&state);
+    reward_counts::broadcast(&state);
     Ok(Json(json!({ "success": true, "message": "User removed" })))
 }
 
+/// GET /api/reward/counts/stream
+pub async fn get_count_feed(State(state): State<SharedState>) -> ApiResult {
+    let items = reward_counts::feed(&state).map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "rewards": items })))
+}
+
+/// GET /api/reward/counts/goals
+pub async fn get_count_goals(State(state): State<SharedState>) -> ApiResult {
+    let goals = state
+        .db()
+        .get_reward_count_goals()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "goals": goals })))
+}
+
+/// PUT /api/reward/counts/:reward_id/goal
+///
+/// `{ "goal": 10, "label": "hydrate", "effect": { "preset": ".." } }`;
+/// `label` and `effect` are optional.
+pub async fn set_count_goal(
+    State(state): State<SharedState>,
+    Path(reward_id): Path<String>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let goal = body["goal"]
+        .as_i64()
+        .filter(|g| (1..=i32::MAX as i64).contains(g))
+        .ok_or_else(|| err_json(400, "goal must be a positive integer"))?;
+    let label = body["label"].as_str().unwrap_or_default().trim();
+    let effect = body.get("effect").filter(|e| !e.is_null());
+    if let Some(effect) = effect {
+        overlay_effects::validate_params(effect).map_err(|e| err_json(400, &e))?;
+    }
+    let saved = state
+        .db()
+        .set_reward_count_goal(
+            &reward_id,
+            goal as i32,
+            label,
+            effect,
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    reward_counts::broadcast(&state);
+    Ok(Json(json!({ "success": true, "goal": saved })))
+}
+
+/// DELETE /api/reward/counts/:reward_id/goal
+pub async fn delete_count_goal(
+    State(state): State<SharedState>,
+    Path(reward_id): Path<String>,
+) -> ApiResult {
+    let deleted = state
+        .db()
+        .delete_reward_count_goal(&reward_id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Goal not found"));
+    }
+    reward_counts::broadcast(&state);
+    Ok(Json(json!({ "success": true })))
+}
+
 /// PUT /api/twitch/rewards/:id/display-name
 pub async fn set_display_name(
     State(state): State<SharedState>,
@@ -83,10 +148,11 @@ pub async fn get_groups(State(state): State<SharedState>) -> ApiResult {
     let mut result = Vec::new();
     for g in groups {
         let ids = state.db().get_group_rewards(g.id).unwrap_or_default();
+        let policy = state.db().get_reward_group_policy(g.id).unwrap_or_default();
         result.push(json!({
             "id": g.id, "name": g.name, "is_enabled": g.is_enabled,
             "created_at": g.created_at, "updated_at": g.updated_at,
-            "reward_ids": ids,
+            "reward_ids": ids, "policy": policy,
         }));
     }
     Ok(Json(json!({ "data": result })))
@@ -99,6 +165,7 @@ pub async fn get_group(State(state): State<SharedState>, Path(id): Path<i64>) ->
         .get_reward_group(id)
         .map_err(|e| err_json(404, &e.to_string()))?;
     let ids = state.db().get_group_rewards(g.id).unwrap_or_default();
+    let policy = state.db().get_reward_group_policy(g.id).unwrap_or_default();
     Ok(Json(json!({
         "id": g.id,
         "name": g.name,
@@ -106,6 +173,7 @@ pub async fn get_group(State(state): State<SharedState>, Path(id): Path<i64>) ->
         "created_at": g.created_at,
         "updated_at": g.updated_at,
         "reward_ids": ids,
+        "policy": policy,
     })))
 }
 
@@ -144,6 +212,132 @@ pub async fn toggle_group(State(state): State<SharedState>, Path(id): Path<i64>)
     Ok(Json(json!({ "success": true, "enabled": new_val })))
 }
 
+async fn switch_group(state: &SharedState, id: i64, enabled: bool) -> ApiResult {
+    state
+        .db()
+        .get_reward_group(id)
+        .map_err(|e| err_json(404, &e.to_string()))?;
+    let job = reward_groups::spawn_switch(state, id, enabled)
+        .await
+        .map_err(|e| err_json(409, &e))?;
+    Ok(Json(json!({ "success": true, "job": job })))
+}
+
+/// POST /api/twitch/reward-groups/:id/enable
+pub async fn enable_group(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    switch_group(&state, id, true).await
+}
+
+/// POST /api/twitch/reward-groups/:id/disable
+pub async fn disable_group(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    switch_group(&state, id, false).await
+}
+
+const SCHEDULE_TRIGGERS: [&str; 2] = [TRIGGER_STREAM_ONLINE, TRIGGER_STREAM_OFFLINE];
+
+fn schedule_json(state: &SharedState, id: i64) -> ApiResult {
+    let schedules = state
+        .db()
+        .get_reward_group_schedules(id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let mut out = serde_json::Map::new();
+    for trigger in SCHEDULE_TRIGGERS {
+        let action = schedules
+            .iter()
+            .find(|s| s.trigger == trigger)
+            .map(|s| if s.enabled { "enable" } else { "disable" });
+        out.insert(trigger.to_string(), json!(action));
+    }
+    Ok(Json(Value::Object(out)))
+}
+
+/// GET /api/twitch/reward-groups/:id/schedule
+pub async fn get_group_schedule(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+) -> ApiResult {
+    schedule_json(&state, id)
+}
+
+/// PUT /api/twitch/reward-groups/:id/schedule
+///
+/// `{ "stream_online": "enable" | "disable" | null, "stream_offline": .. }`;
+/// omitted triggers are left unchanged.
+pub async fn set_group_schedule(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    state
+        .db()
+        .get_reward_group(id)
+        .map_err(|e| err_json(404, &e.to_string()))?;
+    let mut changes = Vec::new();
+    for trigger in SCHEDULE_TRIGGERS {
+        let enabled = match body.get(trigger) {
+            None => continue,
+            Some(Value::Null) => None,
+            Some(v) => match v.as_str() {
+                Some("enable") => Some(true),
+                Some("disable") => Some(false),
+                _ => {
+                    return Err(err_json(
+                        400,
+                        &format!("{trigger} must be \"enable\", \"disable\" or null"),
+                    ));
+                }
+            },
+        };
+        changes.push((trigger, enabled));
+    }
+    for (trigger, enabled) in changes {
+        state
+            .db()
+            .set_reward_group_schedule(id, trigger, enabled)
+            .map_err(|e| err_json(500, &e.to_string()))?;
+    }
+    schedule_json(&state, id)
+}
+
+/// GET /api/twitch/reward-groups/:id/policy
+pub async fn get_group_policy(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let policy = state
+        .db()
+        .get_reward_group_policy(id)
+        .map_err(|e| err_json(404, &e.to_string()))?;
+    Ok(Json(json!(policy)))
+}
+
+/// PUT /api/twitch/reward-groups/:id/policy
+///
+/// `{ "mode": "manual" | "live" | "offline",
+///    "windows": [{ "days": [5, 6], "start": "20:00", "end": "02:00" }] }`;
+/// days are 0 (Sunday) to 6, times are local. The group is switched right
+/// away when the policy wants it the other way.
+pub async fn set_group_policy(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(policy): Json<RewardGroupPolicy>,
+) -> ApiResult {
+    state
+        .db()
+        .get_reward_group(id)
+        .map_err(|e| err_json(404, &e.to_string()))?;
+    reward_groups::validate_policy(&policy).map_err(|e| err_json(400, &e))?;
+    state
+        .db()
+        .set_reward_group_policy(id, &policy)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let live = state
+        .db()
+        .get_open_stream_session()
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .is_some();
+    let s = state.clone();
+    tokio::spawn(async move { reward_groups::apply_policies(&s, live).await });
+    Ok(Json(json!({ "success": true, "policy": policy })))
+}
+
 /// POST /api/twitch/reward-groups/:gid/rewards/:rid
 pub async fn add_reward_to_group(
     State(state): State<SharedState>,
@@ -194,9 +388,3 @@ pub async fn get_group_counts(State(state): State<SharedState>, Path(id): Path<i
         .map_err(|e| err_json(500, &e.to_string()))?;
     Ok(Json(json!(counts)))
 }
-
-fn broadcast_reward_update(state: &SharedState) {
-    let counts = state.db().get_all_reward_counts().unwrap_or_default();
-    let msg = json!({ "type": "reward_counts", "data": counts });
-    let _ = state.ws_sender().send(msg.to_string());
-}
@@ -0,0 +1,146 @@
+//! Stream rundown API (`?date=` / `date` default to today):
+//!   GET    /api/rundown              – list a day's items
+//!   POST   /api/rundown              – add `{ text, date? }`
+//!   PUT    /api/rundown/order        – reorder `{ ids, date? }`
+//!   POST   /api/rundown/print        – print `{ date? }`
+//!   PUT    /api/rundown/{id}         – edit `{ text?, checked? }`
+//!   DELETE /api/rundown/{id}         – delete
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::rundown;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct RundownQuery {
+    pub date: Option<String>,
+}
+
+/// The requested day, or today when absent.
+fn resolve_date(
+    state: &SharedState,
+    date: Option<&str>,
+) -> Result<String, (axum::http::StatusCode, Json<Value>)> {
+    match date.map(str::trim).filter(|d| !d.is_empty()) {
+        None => Ok(rundown::today(state)),
+        Some(d) if rundown::is_valid_date(d) => Ok(d.to_string()),
+        Some(_) => Err(err_json(400, "date must be YYYY-MM-DD")),
+    }
+}
+
+/// GET /api/rundown
+pub async fn get_rundown(
+    State(state): State<SharedState>,
+    Query(q): Query<RundownQuery>,
+) -> ApiResult {
+    let date = resolve_date(&state, q.date.as_deref())?;
+    let items = state
+        .db()
+        .get_rundown(&date)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "date": date, "items": items })))
+}
+
+/// POST /api/rundown
+pub async fn add_item(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let date = resolve_date(&state, body["date"].as_str())?;
+    let text = body["text"].as_str().unwrap_or_default().trim();
+    if text.is_empty() {
+        return Err(err_json(400, "text is required"));
+    }
+    let item = state
+        .db()
+        .add_rundown_item(&date, text, chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    rundown::broadcast(&state, &date);
+    Ok(Json(json!({ "success": true, "item": item })))
+}
+
+/// PUT /api/rundown/{id}
+pub async fn update_item(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let db = state.db();
+    let item = db
+        .get_rundown_item(id)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Rundown item not found"))?;
+
+    if let Some(text) = body.get("text") {
+        let text = text.as_str().unwrap_or_default().trim();
+        if text.is_empty() {
+            return Err(err_json(400, "text must not be empty"));
+        }
+        db.update_rundown_item_text(id, text)
+            .map_err(|e| err_json(500, &e.to_string()))?;
+    }
+    if let Some(checked) = body.get("checked") {
+        let checked = checked
+            .as_bool()
+            .ok_or_else(|| err_json(400, "checked must be a boolean"))?;
+        db.set_rundown_item_checked(id, checked, chrono::Utc::now().timestamp())
+            .map_err(|e| err_json(500, &e.to_string()))?;
+    }
+
+    let updated = db
+        .get_rundown_item(id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    rundown::broadcast(&state, &item.stream_date);
+    Ok(Json(json!({ "success": true, "item": updated })))
+}
+
+/// DELETE /api/rundown/{id}
+pub async fn delete_item(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let item = state
+        .db()
+        .get_rundown_item(id)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Rundown item not found"))?;
+    state
+        .db()
+        .delete_rundown_item(id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    rundown::broadcast(&state, &item.stream_date);
+    Ok(Json(json!({ "success": true })))
+}
+
+/// PUT /api/rundown/order
+pub async fn reorder(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let date = resolve_date(&state, body["date"].as_str())?;
+    let ids: Vec<i64> = body["ids"]
+        .as_array()
+        .ok_or_else(|| err_json(400, "ids must be an array"))?
+        .iter()
+        .filter_map(Value::as_i64)
+        .collect();
+    state
+        .db()
+        .reorder_rundown(&date, &ids)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    rundown::broadcast(&state, &date);
+    let items = state
+        .db()
+        .get_rundown(&date)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(
+        json!({ "success": true, "date": date, "items": items }),
+    ))
+}
+
+/// POST /api/rundown/print
+pub async fn print_rundown(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let date = resolve_date(&state, body["date"].as_str())?;
+    rundown::print(&state, &date)
+        .await
+        .map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({ "success": true, "date": date })))
+}
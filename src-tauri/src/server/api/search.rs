@@ -0,0 +1,38 @@
+//! Global search API:
+//!   GET /api/search?q=&types=&limit= – search chat, archived events,
+//!                                      lottery draws and logs
+//!
+//! `types` is a comma-separated subset of `chat,event,lottery,log`.
+
+use axum::Json;
+use axum::extract::{Query, State};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::search;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+    pub types: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// GET /api/search
+pub async fn search(State(state): State<SharedState>, Query(q): Query<SearchQuery>) -> ApiResult {
+    let types = search::parse_types(q.types.as_deref()).map_err(|e| err_json(400, &e))?;
+    let limit = q.limit.unwrap_or(search::DEFAULT_LIMIT);
+    let response = search::search(&state, &q.q, &types, limit).map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({
+        "query": response.query,
+        "results": response.results,
+        "count": response.results.len(),
+        "errors": response.errors,
+    })))
+}
@@ -0,0 +1,171 @@
+//! Stream segments API ("Just Chatting", "Ranked", ...).
+//!
+//! The streamer switches segments from the dashboard or a hotkey tool
+//! (Stream Deck etc.) calling `POST /api/segments`.
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use overlay_db::segments::{SegmentStats, StreamSegment};
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct SegmentQuery {
+    pub since: Option<i64>,
+    pub hours: Option<i64>,
+    pub format: Option<String>,
+}
+
+impl SegmentQuery {
+    fn since(&self) -> i64 {
+        self.since
+            .or_else(|| {
+                self.hours
+                    .map(|h| chrono::Utc::now().timestamp() - h * 3600)
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// GET /api/segments
+pub async fn get_segments(
+    State(state): State<SharedState>,
+    Query(q): Query<SegmentQuery>,
+) -> ApiResult {
+    let stats = collect_stats(&state, q.since())?;
+    Ok(Json(json!({ "segments": stats, "count": stats.len() })))
+}
+
+/// GET /api/segments/current
+pub async fn get_current(State(state): State<SharedState>) -> ApiResult {
+    let current = state
+        .db()
+        .get_current_segment()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let stats = match current {
+        Some(seg) => Some(
+            state
+                .db()
+                .get_segment_stats(&seg, chrono::Utc::now().timestamp())
+                .map_err(|e| err_json(500, &e.to_string()))?,
+        ),
+        None => None,
+    };
+    Ok(Json(json!({ "segment": stats })))
+}
+
+/// POST /api/segments
+pub async fn start_segment(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let name = body["name"].as_str().unwrap_or("").trim();
+    if name.is_empty() {
+        return Err(err_json(400, "name is required"));
+    }
+    let segment = state
+        .db()
+        .start_segment(name, chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    broadcast_segment_changed(&state, Some(&segment));
+    Ok(Json(json!({ "success": true, "segment": segment })))
+}
+
+/// POST /api/segments/end
+pub async fn end_segment(State(state): State<SharedState>) -> ApiResult {
+    let ended = state
+        .db()
+        .end_current_segment(chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if ended.is_some() {
+        broadcast_segment_changed(&state, None);
+    }
+    Ok(Json(json!({ "success": true, "segment": ended })))
+}
+
+/// DELETE /api/segments/{id}
+pub async fn delete_segment(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    state.db().delete_segment(id).map_err(|e| match e {
+        overlay_db::DbError::NotFound(_) => err_json(404, "Segment not found"),
+        other => err_json(500, &other.to_string()),
+    })?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// GET /api/segments/export?format=json|chapters
+///
+/// `chapters` renders a YouTube-style chapter list relative to the first
+/// segment in range, for pasting into VOD descriptions or credits.
+pub async fn export_segments(
+    State(state): State<SharedState>,
+    Query(q): Query<SegmentQuery>,
+) -> Result<Response, (axum::http::StatusCode, Json<Value>)> {
+    let stats = collect_stats(&state, q.since())?;
+
+    if q.format.as_deref() == Some("chapters") {
+        let body = format_chapters(&stats);
+        return Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], body).into_response());
+    }
+
+    Ok(Json(json!({
+        "segments": stats,
+        "generated_at": chrono::Utc::now().to_rfc3339(),
+    }))
+    .into_response())
+}
+
+/// Close the open segment (used when the stream goes offline).
+pub fn close_open_segment(state: &SharedState) {
+    match state
+        .db()
+        .end_current_segment(chrono::Utc::now().timestamp())
+    {
+        Ok(Some(_)) => broadcast_segment_changed(state, None),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to close segment: {e}"),
+    }
+}
+
+fn collect_stats(
+    state: &SharedState,
+    since: i64,
+) -> Result<Vec<SegmentStats>, (axum::http::StatusCode, Json<Value>)> {
+    let now = chrono::Utc::now().timestamp();
+    let segments = state
+        .db()
+        .get_segments_since(since)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    segments
+        .iter()
+        .map(|seg| state.db().get_segment_stats(seg, now))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| err_json(500, &e.to_string()))
+}
+
+fn format_chapters(stats: &[SegmentStats]) -> String {
+    let Some(origin) = stats.first().map(|s| s.segment.started_at) else {
+        return String::new();
+    };
+    let mut out = String::new();
+    for s in stats {
+        let offset = (s.segment.started_at - origin).max(0);
+        let (h, m, sec) = (offset / 3600, (offset % 3600) / 60, offset % 60);
+        let stamp = if h > 0 {
+            format!("{h}:{m:02}:{sec:02}")
+        } else {
+            format!("{m:02}:{sec:02}")
+        };
+        out.push_str(&format!("{stamp} {}\n", s.segment.name));
+    }
+    out
+}
+
+fn broadcast_segment_changed(state: &SharedState, segment: Option<&StreamSegment>) {
+    let msg = json!({ "type": "segment_changed", "data": { "segment": segment } });
+    let _ = state.ws_sender().send(msg.to_string());
+}
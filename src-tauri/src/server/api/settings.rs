@@ -3,6 +3,7 @@
 //!   PUT  /api/settings/v2   – update settings
 //!   POST /api/settings/v2   – reset settings to defaults
 //!   GET  /api/settings/status – lightweight feature status
+//!   GET  /api/features        – feature flags with hardware detection
 
 use axum::Json;
 use axum::extract::State;
@@ -11,6 +12,7 @@ use std::collections::HashMap;
 
 use crate::app::SharedState;
 use crate::config::SettingsManager;
+use crate::services::features;
 use crate::services::font::FontService;
 
 use super::err_json;
@@ -64,12 +66,28 @@ pub async fn update_settings(
             .map_err(|e| err_json(400, &format!("{key}: {e}")))?;
         updated += 1;
     }
+    if body.contains_key("MIDI_ENABLED") || body.contains_key("MIDI_PORT_NAME") {
+        crate::services::midi::disconnect();
+    }
 
     // Reload runtime config
     state
         .reload_config()
         .await
         .map_err(|e| err_json(500, &format!("Failed to reload config: {e}")))?;
+    features::start_enabled_tasks(&state).await;
+    if crate::server::access::SETTING_KEYS
+        .iter()
+        .any(|key| body.contains_key(*key))
+    {
+        crate::server::restart_server();
+    }
+    if crate::services::emotes::SETTING_KEYS
+        .iter()
+        .any(|key| body.contains_key(*key))
+    {
+        crate::services::emotes::request_warm_up(&state);
+    }
 
     let status = sm
         .check_feature_status()
@@ -111,7 +129,7 @@ pub async fn reset_settings(
 ) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
     use crate::config::defaults::DEFAULT_SETTINGS;
 
-    let _sm = SettingsManager::new(state.db().clone());
+    let sm = SettingsManager::new(state.db().clone());
     let keys: Vec<String> = body
         .get("keys")
         .and_then(|v| serde_json::from_value(v.clone()).ok())
@@ -127,9 +145,7 @@ pub async fn reset_settings(
     for key in &targets {
         if let Some(def) = DEFAULT_SETTINGS.get(key) {
             let type_str = if def.secret { "secret" } else { "normal" };
-            state
-                .db()
-                .set_setting(key, def.default, type_str)
+            sm.set_raw(key, def.default, type_str)
                 .map_err(|e| err_json(500, &format!("Failed to reset {key}: {e}")))?;
             reset_count += 1;
         }
@@ -157,6 +173,21 @@ pub async fn get_settings_status(
     Ok(Json(serde_json::to_value(status).unwrap()))
 }
 
+/// GET /api/features
+pub async fn get_features(
+    State(state): State<SharedState>,
+) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+    let status = features::detect(&state)
+        .await
+        .map_err(|e| err_json(500, &format!("Failed to check status: {e}")))?;
+    Ok(Json(json!({
+        "features": status.features,
+        "printer_connected": status.printer_connected,
+        "running": features::running().await,
+        "warnings": status.warnings,
+    })))
+}
+
 /// GET /api/settings (legacy compatibility endpoint)
 pub async fn get_settings_legacy(
     State(state): State<SharedState>,
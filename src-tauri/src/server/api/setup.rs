@@ -0,0 +1,114 @@
+//! First-run setup wizard API:
+//!   GET  /api/setup              – progress (steps, current step)
+//!   POST /api/setup/credentials  – `{client_id, client_secret}`
+//!   GET  /api/setup/oauth        – OAuth URL to open
+//!   POST /api/setup/broadcaster  – `{login?}` (defaults to the token owner)
+//!   POST /api/setup/printer      – `{type, address|name}` or `{skip: true}`
+//!   POST /api/setup/overlay      – overlay URLs; finishes the wizard
+//!   POST /api/setup/reset        – forget recorded progress
+
+use axum::Json;
+use axum::extract::State;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::setup::{self, PrinterChoice, SetupError};
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+fn to_api((code, msg): SetupError) -> (axum::http::StatusCode, Json<Value>) {
+    err_json(code, &msg)
+}
+
+fn str_param<'a>(body: &'a Value, key: &str) -> &'a str {
+    body.get(key).and_then(|v| v.as_str()).unwrap_or_default()
+}
+
+/// GET /api/setup
+pub async fn get_setup(State(state): State<SharedState>) -> ApiResult {
+    Ok(Json(json!(setup::status(&state))))
+}
+
+/// POST /api/setup/credentials
+pub async fn set_credentials(
+    State(state): State<SharedState>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let status = setup::set_credentials(
+        &state,
+        str_param(&body, "client_id"),
+        str_param(&body, "client_secret"),
+    )
+    .await
+    .map_err(to_api)?;
+    Ok(Json(json!(status)))
+}
+
+/// GET /api/setup/oauth
+pub async fn get_oauth(State(state): State<SharedState>) -> ApiResult {
+    let auth_url = setup::oauth_url(&state).await.map_err(to_api)?;
+    Ok(Json(json!({
+        "auth_url": auth_url,
+        "status": setup::status(&state),
+    })))
+}
+
+/// POST /api/setup/broadcaster
+pub async fn set_broadcaster(
+    State(state): State<SharedState>,
+    body: Option<Json<Value>>,
+) -> ApiResult {
+    let body = body.map(|Json(b)| b).unwrap_or_default();
+    let login = body.get("login").and_then(|v| v.as_str());
+    let status = setup::set_broadcaster(&state, login)
+        .await
+        .map_err(to_api)?;
+    Ok(Json(json!(status)))
+}
+
+/// POST /api/setup/printer
+pub async fn set_printer(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let choice = if body.get("skip").and_then(|v| v.as_bool()) == Some(true) {
+        PrinterChoice::Skip
+    } else {
+        match str_param(&body, "type") {
+            "bluetooth" => PrinterChoice::Bluetooth {
+                address: str_param(&body, "address").trim().to_string(),
+            },
+            "usb" => PrinterChoice::Usb {
+                name: str_param(&body, "name").trim().to_string(),
+            },
+            other => return Err(err_json(400, &format!("Unknown printer type: {other}"))),
+        }
+    };
+    match &choice {
+        PrinterChoice::Bluetooth { address } if address.is_empty() => {
+            return Err(err_json(400, "address is required"));
+        }
+        PrinterChoice::Usb { name } if name.is_empty() => {
+            return Err(err_json(400, "name is required"));
+        }
+        _ => {}
+    }
+    let status = setup::set_printer(&state, choice).await.map_err(to_api)?;
+    Ok(Json(json!(status)))
+}
+
+/// POST /api/setup/overlay
+pub async fn finish(State(state): State<SharedState>) -> ApiResult {
+    let status = setup::finish(&state).map_err(to_api)?;
+    let urls: serde_json::Map<String, Value> = setup::overlay_urls(&state)
+        .map_err(to_api)?
+        .into_iter()
+        .map(|(name, url)| (name.to_string(), Value::String(url)))
+        .collect();
+    Ok(Json(json!({ "overlay_urls": urls, "status": status })))
+}
+
+/// POST /api/setup/reset
+pub async fn reset(State(state): State<SharedState>) -> ApiResult {
+    let status = setup::reset(&state).map_err(to_api)?;
+    Ok(Json(json!(status)))
+}
@@ -0,0 +1,320 @@
+//! Channel statistics API:
+//!   GET  /api/stats/wordcloud         – weighted terms (`format=png` renders an image)
+//!   POST /api/stats/wordcloud/print   – print the rendered word cloud
+//!   GET  /api/stats/raids             – raid history and per-channel reciprocity
+//!   GET  /api/chat/stats              – top chatters, messages per hour, emote usage
+//!
+//! Word cloud query: `session` = segment id or `current` (default: the open
+//! segment, else the last 12 hours), `lang` = comma-separated
+//! stopword/filter languages (default `en,ja`), `limit` = max terms
+//! (default 50).
+//!
+//! Raid query: `since` (unix seconds) or `hours`, default all time;
+//! `limit` caps the recent raid list (default 50).
+//!
+//! Chat stats query: `session` as for the word cloud, or `from`/`to` (unix
+//! seconds; `to` defaults to now); `limit` caps the chatter and emote lists
+//! (default 10). The broadcaster's own messages are left out unless
+//! `include_broadcaster=true`.
+
+use ab_glyph::FontRef;
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use word_filter::WordMatcher;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::print_queue::PrintCategory;
+use crate::services::print_render;
+use crate::services::wordcloud::{self, WordCloudTerm};
+use overlay_db::raids::RaidPartner;
+use overlay_db::segments::StreamSegment;
+
+use super::err_json;
+
+type ApiError = (axum::http::StatusCode, Json<Value>);
+type ApiResult = Result<Json<Value>, ApiError>;
+
+const FALLBACK_HOURS: i64 = 12;
+const DEFAULT_TITLE: &str = "Chat Word Cloud";
+
+#[derive(Debug, Deserialize)]
+pub struct WordCloudQuery {
+    pub session: Option<String>,
+    pub lang: Option<String>,
+    pub limit: Option<usize>,
+    pub format: Option<String>,
+}
+
+struct WordCloud {
+    session: Option<StreamSegment>,
+    since: i64,
+    until: i64,
+    messages: usize,
+    terms: Vec<WordCloudTerm>,
+}
+
+impl WordCloud {
+    fn title(&self) -> &str {
+        self.session
+            .as_ref()
+            .map_or(DEFAULT_TITLE, |s| s.name.as_str())
+    }
+}
+
+/// GET /api/stats/wordcloud
+pub async fn get_wordcloud(
+    State(state): State<SharedState>,
+    Query(q): Query<WordCloudQuery>,
+) -> Result<Response, ApiError> {
+    let cloud = build_wordcloud(&state, &q)?;
+
+    if q.format.as_deref() == Some("png") {
+        let png =
+            print_render::encode_png(&render(&state, &cloud)?).map_err(|e| err_json(500, &e))?;
+        return Ok(([(header::CONTENT_TYPE, "image/png")], png).into_response());
+    }
+
+    Ok(Json(json!({
+        "session": cloud.session,
+        "since": cloud.since,
+        "until": cloud.until,
+        "messages": cloud.messages,
+        "terms": cloud.terms,
+    }))
+    .into_response())
+}
+
+/// POST /api/stats/wordcloud/print
+pub async fn print_wordcloud(
+    State(state): State<SharedState>,
+    Query(q): Query<WordCloudQuery>,
+) -> ApiResult {
+    let cloud = build_wordcloud(&state, &q)?;
+    if cloud.terms.is_empty() {
+        return Err(err_json(400, "No chat words to print"));
+    }
+    let img = render(&state, &cloud)?;
+    print_render::enqueue_image(&state, &img, "word cloud", PrintCategory::Other)
+        .await
+        .map_err(|e| err_json(500, &e))?;
+    Ok(Json(json!({ "success": true, "terms": cloud.terms.len() })))
+}
+
+fn build_wordcloud(state: &SharedState, q: &WordCloudQuery) -> Result<WordCloud, ApiError> {
+    let now = chrono::Utc::now().timestamp();
+    let session = resolve_session(state, q.session.as_deref())?;
+    let (since, until) = match &session {
+        Some(s) => (s.started_at, s.ended_at.unwrap_or(now)),
+        None => (now - FALLBACK_HOURS * 3600, now),
+    };
+
+    let lang = q.lang.as_deref().unwrap_or("en,ja");
+    let languages: Vec<&str> = lang
+        .split(',')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let matcher =
+        WordMatcher::cached(state.db(), &languages).map_err(|e| err_json(500, &e.to_string()))?;
+
+    let messages: Vec<_> = state
+        .db()
+        .get_chat_messages_since(since, None)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .into_iter()
+        .filter(|m| m.created_at <= until)
+        .collect();
+    let limit = q
+        .limit
+        .unwrap_or(wordcloud::DEFAULT_LIMIT)
+        .clamp(1, wordcloud::MAX_LIMIT);
+    let terms = wordcloud::build(&messages, &languages, &matcher, limit);
+
+    Ok(WordCloud {
+        session,
+        since,
+        until,
+        messages: messages.len(),
+        terms,
+    })
+}
+
+fn resolve_session(
+    state: &SharedState,
+    session: Option<&str>,
+) -> Result<Option<StreamSegment>, ApiError> {
+    match session {
+        None | Some("") | Some("current") => state
+            .db()
+            .get_current_segment()
+            .map_err(|e| err_json(500, &e.to_string())),
+        Some(id) => {
+            let id: i64 = id
+                .parse()
+                .map_err(|_| err_json(400, "session must be a segment id or 'current'"))?;
+            state
+                .db()
+                .get_segment(id)
+                .map_err(|e| err_json(500, &e.to_string()))?
+                .ok_or_else(|| err_json(404, "Session not found"))
+                .map(Some)
+        }
+    }
+}
+
+fn render(state: &SharedState, cloud: &WordCloud) -> Result<image::DynamicImage, ApiError> {
+    let font_data = print_render::load_font(state).map_err(|e| err_json(400, &e))?;
+    let font = FontRef::try_from_slice(&font_data)
+        .map_err(|_| err_json(400, "Invalid font data (failed to parse TTF/OTF)"))?;
+    let terms: Vec<(String, f32)> = cloud
+        .terms
+        .iter()
+        .map(|t| (t.text.clone(), t.weight))
+        .collect();
+    Ok(image_processor::wordcloud::generate_word_cloud_image(
+        cloud.title(),
+        &terms,
+        &font,
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatStatsQuery {
+    pub session: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub limit: Option<i64>,
+    pub include_broadcaster: Option<bool>,
+}
+
+/// GET /api/chat/stats
+pub async fn get_chat_stats(
+    State(state): State<SharedState>,
+    Query(q): Query<ChatStatsQuery>,
+) -> ApiResult {
+    let now = chrono::Utc::now().timestamp();
+    let (session, since, until) = match (q.from, q.session.as_deref()) {
+        (Some(from), None) => (None, from, q.to.unwrap_or(now)),
+        (_, session) => {
+            let session = resolve_session(&state, session)?;
+            let (since, until) = match &session {
+                Some(s) => (s.started_at, s.ended_at.unwrap_or(now)),
+                None => (now - FALLBACK_HOURS * 3600, now),
+            };
+            (session, since, until)
+        }
+    };
+    if until < since {
+        return Err(err_json(400, "to must not be before from"));
+    }
+
+    let exclude = if q.include_broadcaster.unwrap_or(false) {
+        String::new()
+    } else {
+        SettingsManager::new(state.db().clone())
+            .get_setting("TWITCH_USER_ID")
+            .unwrap_or_default()
+    };
+    let db_err = |e: overlay_db::DbError| err_json(500, &e.to_string());
+    let stats = state
+        .db()
+        .get_chat_stats(since, until, &exclude, q.limit.unwrap_or(10).clamp(1, 100))
+        .map_err(db_err)?;
+
+    // Unique chatters per segment started in the range.
+    let segments = state
+        .db()
+        .get_segments_since(since)
+        .map_err(db_err)?
+        .into_iter()
+        .filter(|s| s.started_at < until)
+        .map(|s| state.db().get_segment_stats(&s, now))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_err)?;
+
+    Ok(Json(json!({
+        "session": session,
+        "since": since,
+        "until": until,
+        "stats": stats,
+        "segments": segments,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RaidQuery {
+    pub since: Option<i64>,
+    pub hours: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/stats/raids
+pub async fn get_raids(State(state): State<SharedState>, Query(q): Query<RaidQuery>) -> ApiResult {
+    let since = q
+        .since
+        .or_else(|| q.hours.map(|h| chrono::Utc::now().timestamp() - h * 3600))
+        .unwrap_or(0);
+    let raids = state
+        .db()
+        .get_raids_since(since, q.limit.unwrap_or(50).clamp(1, 500))
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let partners = state
+        .db()
+        .get_raid_partners(since)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+
+    let (raids_in, raids_out) = partners
+        .iter()
+        .fold((0, 0), |(i, o), p| (i + p.raids_in, o + p.raids_out));
+    let partners: Vec<Value> = partners
+        .iter()
+        .map(|p| {
+            let (reciprocity, summary) = reciprocity(p);
+            let mut v = json!(p);
+            v["reciprocity"] = json!(reciprocity);
+            v["summary"] = json!(summary);
+            v
+        })
+        .collect();
+
+    Ok(Json(json!({
+        "since": since,
+        "raids": raids,
+        "partners": partners,
+        "totals": { "raids_in": raids_in, "raids_out": raids_out },
+    })))
+}
+
+/// Classify a raid relationship and describe it in one line.
+fn reciprocity(p: &RaidPartner) -> (&'static str, String) {
+    let times = |n: i64| {
+        if n == 1 {
+            "once".to_string()
+        } else {
+            format!("{n} times")
+        }
+    };
+    match (p.raids_out, p.raids_in) {
+        (out, 0) => (
+            "unreturned",
+            format!("You raided them {}, they never raided back.", times(out)),
+        ),
+        (0, inbound) => (
+            "owed",
+            format!("They raided you {}, you never raided back.", times(inbound)),
+        ),
+        (out, inbound) => (
+            "mutual",
+            format!(
+                "You raided them {}, they raided you {}.",
+                times(out),
+                times(inbound)
+            ),
+        ),
+    }
+}
@@ -0,0 +1,132 @@
+//! Per-stream recap API (see `services::stream_sessions`):
+//!   GET /api/stream/sessions        – sessions, newest first (`limit`, `offset`)
+//!   GET /api/stream/sessions/{id}   – one session with its chat stats;
+//!                                     `current` is the open session
+//!   GET /api/stream/viewers/history – a session's sampled viewer counts
+//!                                     (`session_id`, default the open or
+//!                                     latest session)
+//!
+//! Each session carries `duration_secs`, `follower_delta` and `sub_delta`
+//! (null until two samples exist). An open session is measured up to now.
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use overlay_db::stream_sessions::StreamSession;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct ViewerHistoryQuery {
+    pub session_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// A session with its derived numbers.
+fn summary(session: &StreamSession, now: i64) -> Value {
+    let end = session.ended_at.unwrap_or(now);
+    let mut v = json!(session);
+    v["duration_secs"] = json!((end - session.started_at).max(0));
+    v["follower_delta"] = json!(session.follower_delta());
+    v["sub_delta"] = json!(session.sub_delta());
+    v
+}
+
+/// GET /api/stream/sessions
+pub async fn get_sessions(
+    State(state): State<SharedState>,
+    Query(q): Query<SessionsQuery>,
+) -> ApiResult {
+    let now = chrono::Utc::now().timestamp();
+    let sessions = state
+        .db()
+        .get_stream_sessions(
+            q.limit.unwrap_or(20).clamp(1, 200),
+            q.offset.unwrap_or(0).max(0),
+        )
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let sessions: Vec<Value> = sessions.iter().map(|s| summary(s, now)).collect();
+    Ok(Json(json!({ "sessions": sessions })))
+}
+
+/// GET /api/stream/sessions/{id}
+pub async fn get_session(State(state): State<SharedState>, Path(id): Path<String>) -> ApiResult {
+    let db_err = |e: overlay_db::DbError| err_json(500, &e.to_string());
+    let session = if id == "current" {
+        state.db().get_open_stream_session().map_err(db_err)?
+    } else {
+        let id: i64 = id
+            .parse()
+            .map_err(|_| err_json(400, "id must be a session id or 'current'"))?;
+        state.db().get_stream_session(id).map_err(db_err)?
+    }
+    .ok_or_else(|| err_json(404, "Stream session not found"))?;
+
+    let now = chrono::Utc::now().timestamp();
+    let end = session.ended_at.unwrap_or(now);
+    let broadcaster_id = SettingsManager::new(state.db().clone())
+        .get_setting("TWITCH_USER_ID")
+        .unwrap_or_default();
+    let chat = state
+        .db()
+        .get_chat_stats(session.started_at, end, &broadcaster_id, 10)
+        .map_err(db_err)?;
+    let segments = state
+        .db()
+        .get_segments_since(session.started_at)
+        .map_err(db_err)?
+        .into_iter()
+        .filter(|s| s.started_at < end)
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!({
+        "session": summary(&session, now),
+        "chat": chat,
+        "segments": segments,
+    })))
+}
+
+/// GET /api/stream/viewers/history
+pub async fn get_viewer_history(
+    State(state): State<SharedState>,
+    Query(q): Query<ViewerHistoryQuery>,
+) -> ApiResult {
+    let db_err = |e: overlay_db::DbError| err_json(500, &e.to_string());
+    let session = match q.session_id {
+        Some(id) => state.db().get_stream_session(id).map_err(db_err)?,
+        None => match state.db().get_open_stream_session().map_err(db_err)? {
+            Some(open) => Some(open),
+            None => state
+                .db()
+                .get_stream_sessions(1, 0)
+                .map_err(db_err)?
+                .into_iter()
+                .next(),
+        },
+    }
+    .ok_or_else(|| err_json(404, "Stream session not found"))?;
+
+    let samples = state.db().get_viewer_samples(session.id).map_err(db_err)?;
+    let average = match samples.len() {
+        0 => 0,
+        n => samples.iter().map(|s| s.viewers).sum::<i64>() / n as i64,
+    };
+    Ok(Json(json!({
+        "session_id": session.id,
+        "live": session.ended_at.is_none(),
+        "peak": session.peak_viewers,
+        "average": average,
+        "samples": samples,
+    })))
+}
@@ -0,0 +1,185 @@
+//! System maintenance API.
+
+use std::path::PathBuf;
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
+use axum::http::header::USER_AGENT;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::{jobs, latency, panic, projections, selftest};
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// POST /api/system/import-legacy
+///
+/// Body: `{ "path": "/path/to/go-version.db" }`. The current database is
+/// backed up next to itself before anything is written.
+pub async fn import_legacy(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let raw = body
+        .get("path")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| err_json(400, "path is required"))?;
+    let path = match raw.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(raw)),
+        None => PathBuf::from(raw),
+    };
+    if !path.is_file() {
+        return Err(err_json(
+            404,
+            &format!("File not found: {}", path.display()),
+        ));
+    }
+
+    let backup = state.data_dir().join(format!(
+        "local.db.pre-import-{}.bak",
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    state
+        .db()
+        .backup_to(&backup)
+        .map_err(|e| err_json(500, &format!("Backup failed: {e}")))?;
+
+    let summary = state
+        .db()
+        .import_legacy(&path)
+        .map_err(|e| err_json(400, &format!("Import failed: {e}")))?;
+
+    // Imported settings/tokens must be visible to the running app.
+    SettingsManager::invalidate_cache();
+    state
+        .reload_config()
+        .await
+        .map_err(|e| err_json(500, &format!("Failed to reload config: {e}")))?;
+
+    tracing::info!("Imported legacy database {}", path.display());
+    Ok(Json(json!({
+        "success": true,
+        "backup": backup.to_string_lossy(),
+        "tables": summary.tables,
+    })))
+}
+
+/// POST /api/system/rebuild-projections
+///
+/// Body (optional): `{ "projections": ["reward_counts", ...] }`; all
+/// projections are rebuilt when omitted. Returns the job to poll via
+/// `GET /api/system/jobs/{id}` (progress is also broadcast over WebSocket).
+pub async fn rebuild_projections(
+    State(state): State<SharedState>,
+    body: Option<Json<Value>>,
+) -> ApiResult {
+    let requested: Vec<String> = body
+        .and_then(|Json(b)| b.get("projections").cloned())
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let names = projections::resolve_names(&requested).map_err(|e| err_json(400, &e))?;
+    let job = projections::start(&state, names)
+        .await
+        .map_err(|e| err_json(409, &e))?;
+    Ok(Json(json!({ "success": true, "job": job })))
+}
+
+/// GET /api/system/jobs
+pub async fn list_jobs() -> ApiResult {
+    Ok(Json(json!({ "jobs": jobs::list().await })))
+}
+
+/// GET /api/system/jobs/{id}
+pub async fn get_job(Path(id): Path<String>) -> ApiResult {
+    let job = jobs::get(&id)
+        .await
+        .ok_or_else(|| err_json(404, "Job not found"))?;
+    Ok(Json(json!({ "job": job })))
+}
+
+/// POST /api/system/selftest
+///
+/// Runs the startup diagnostics (database, port, Twitch token and scopes,
+/// EventSub, printer, fonts) and returns a pass/fail report per check.
+pub async fn selftest(State(state): State<SharedState>) -> ApiResult {
+    let report = selftest::run(&state).await;
+    Ok(Json(json!(report)))
+}
+
+/// GET /api/system/metrics
+///
+/// Per-stage latency histograms of the EventSub pipeline (receipt → DB
+/// write → WebSocket broadcast → handled → notification display) and the
+/// notification window's time-to-display.
+pub async fn metrics() -> ApiResult {
+    Ok(Json(json!({
+        "event_latency": latency::snapshot(),
+        "notification_window": crate::notification::window::display_stats(),
+    })))
+}
+
+/// POST /api/system/metrics/reset
+pub async fn reset_metrics() -> ApiResult {
+    latency::reset();
+    Ok(Json(json!({ "success": true })))
+}
+
+/// GET /api/system/panic
+pub async fn get_panic() -> Json<Value> {
+    Json(panic::status())
+}
+
+/// POST /api/system/panic
+///
+/// Body (optional): `{ "source": "stream-deck" }`, logged with the trigger;
+/// the User-Agent is logged otherwise. Responds with the outcome of every
+/// step; a failed Twitch call does not fail the request.
+pub async fn trigger_panic(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    body: Option<Json<Value>>,
+) -> Json<Value> {
+    let source = body
+        .as_ref()
+        .and_then(|b| b["source"].as_str().map(str::to_string))
+        .or_else(|| {
+            headers
+                .get(USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    Json(panic::trigger(&state, &source).await)
+}
+
+/// DELETE /api/system/panic
+///
+/// Shows the hidden widgets and resumes printing and TTS. Shield Mode and
+/// follower-only chat are left on.
+pub async fn release_panic(State(state): State<SharedState>) -> Json<Value> {
+    Json(panic::release(&state).await)
+}
+
+/// POST /api/system/restart-server
+///
+/// Rebinds the web server with the current port, bind address and IP
+/// allowlist. The response is sent before the old listener closes.
+pub async fn restart_server(State(state): State<SharedState>) -> ApiResult {
+    state
+        .reload_config()
+        .await
+        .map_err(|e| err_json(500, &format!("Failed to reload config: {e}")))?;
+    let sm = SettingsManager::new(state.db().clone());
+    let bind = crate::server::access::bind_address(&sm);
+    crate::server::restart_server();
+    Ok(Json(json!({
+        "success": true,
+        "bind_address": bind.to_string(),
+        "port": state.server_port(),
+    })))
+}
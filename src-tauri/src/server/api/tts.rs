@@ -0,0 +1,56 @@
+//! Text-to-speech API (see `services::tts`):
+//!   GET  /api/tts          – settings, queue and current utterance
+//!   POST /api/tts/speak    – queue `{ text }` with the chat voice
+//!   POST /api/tts/pause    – hold the queue after the current utterance
+//!   POST /api/tts/resume
+//!   POST /api/tts/skip     – stop the current utterance
+//!   POST /api/tts/clear    – drop every queued utterance
+
+use axum::Json;
+use axum::extract::State;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::tts;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// GET /api/tts
+pub async fn get_status(State(state): State<SharedState>) -> Json<Value> {
+    Json(tts::status(&state))
+}
+
+/// POST /api/tts/speak
+pub async fn speak(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let text = body["text"].as_str().unwrap_or_default().trim();
+    if text.is_empty() {
+        return Err(err_json(400, "text is required"));
+    }
+    tts::say(&state, text);
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/tts/pause
+pub async fn pause() -> Json<Value> {
+    tts::pause();
+    Json(json!({ "success": true, "paused": true }))
+}
+
+/// POST /api/tts/resume
+pub async fn resume() -> Json<Value> {
+    tts::resume();
+    Json(json!({ "success": true, "paused": false }))
+}
+
+/// POST /api/tts/skip
+pub async fn skip() -> Json<Value> {
+    tts::skip();
+    Json(json!({ "success": true }))
+}
+
+/// POST /api/tts/clear
+pub async fn clear() -> Json<Value> {
+    Json(json!({ "success": true, "cleared": tts::clear() }))
+}
@@ -1,4 +1,5 @@
-//! Twitch API endpoints (OAuth, verification, custom rewards, stream status).
+//! Twitch API endpoints (OAuth, verification, custom rewards, redemption
+//! queue, clips, stream status, EventSub health).
 
 use axum::Json;
 use axum::extract::{Path, Query, State};
@@ -7,7 +8,10 @@ use serde::Deserialize;
 use serde_json::{Value, json};
 
 use twitch_client::TwitchError;
-use twitch_client::api::{CreateRewardRequest, TwitchApiClient, UpdateRewardRequest};
+use twitch_client::api::{
+    CreateRewardRequest, REDEMPTION_CANCELED, REDEMPTION_FULFILLED, REDEMPTION_UNFULFILLED,
+    TwitchApiClient, UpdateRewardRequest,
+};
 use twitch_client::auth::TwitchAuth;
 
 use crate::app::SharedState;
@@ -54,10 +58,11 @@ async fn create_auth(
         return Err(err_json(400, "Twitch credentials not configured"));
     }
     let redirect_uri = format!("http://127.0.0.1:{}/callback", config.server_port);
-    Ok(TwitchAuth::new(
+    Ok(TwitchAuth::with_http(
         config.client_id.clone(),
         config.client_secret.clone(),
         redirect_uri,
+        state.http(),
     ))
 }
 
@@ -125,10 +130,16 @@ pub async fn auth_status(State(state): State<SharedState>) -> ApiResult {
         Ok(auth) => auth.get_auth_url().unwrap_or_default(),
         Err(_) => String::new(),
     };
+    // Scopes added since the token was issued need a new authorization.
+    let missing_scopes = token.as_ref().map_or_else(Vec::new, |t| {
+        twitch_client::missing_scopes(t.scope.split_whitespace())
+    });
     Ok(Json(json!({
         "authenticated": token.is_some(),
         "authUrl": auth_url,
         "expiresAt": token.as_ref().map(|t| t.expires_at),
+        "missingScopes": missing_scopes,
+        "reauthRequired": !missing_scopes.is_empty(),
     })))
 }
 
@@ -226,7 +237,7 @@ pub async fn stream_status(State(state): State<SharedState>) -> ApiResult {
     if config.twitch_user_id.is_empty() {
         return Ok(Json(json!({ "is_live": false, "viewer_count": 0 })));
     }
-    let client = TwitchApiClient::new(config.client_id.clone());
+    let client = TwitchApiClient::with_http(config.client_id.clone(), state.http());
     let status = client
         .get_stream_info(&token, &config.twitch_user_id)
         .await
@@ -238,6 +249,64 @@ pub async fn stream_status(State(state): State<SharedState>) -> ApiResult {
     })))
 }
 
+// ---------------------------------------------------------------------------
+// Shoutout queue
+// ---------------------------------------------------------------------------
+
+/// POST /api/twitch/shoutouts – queue a shoutout `{ user_id, user_name? }`
+pub async fn queue_shoutout(Json(body): Json<Value>) -> ApiResult {
+    let user_id = body["user_id"].as_str().unwrap_or_default().trim();
+    if user_id.is_empty() {
+        return Err(err_json(400, "user_id is required"));
+    }
+    let user_name = body["user_name"].as_str().unwrap_or_default();
+    let id = crate::services::shoutouts::enqueue(user_id, user_name, "dashboard").await;
+    Ok(Json(json!({ "success": true, "id": id })))
+}
+
+/// GET /api/twitch/shoutouts/pending
+pub async fn pending_shoutouts() -> ApiResult {
+    let pending = crate::services::shoutouts::pending().await;
+    Ok(Json(json!({ "pending": pending, "count": pending.len() })))
+}
+
+/// DELETE /api/twitch/shoutouts/pending/:id
+pub async fn cancel_shoutout(Path(id): Path<u64>) -> ApiResult {
+    if !crate::services::shoutouts::cancel(id).await {
+        return Err(err_json(404, "Shoutout not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+// ---------------------------------------------------------------------------
+// EventSub health
+// ---------------------------------------------------------------------------
+
+/// GET /api/twitch/eventsub/status – connection and per-subscription state
+pub async fn eventsub_status() -> ApiResult {
+    let status = crate::eventsub_handler::monitor().status();
+    Ok(Json(json!(status)))
+}
+
+/// POST /api/twitch/eventsub/resubscribe – subscribe again without
+/// reconnecting (`{ "event_types": [...] }`; omitted or empty means every
+/// subscription that is not enabled)
+pub async fn eventsub_resubscribe(Json(body): Json<Value>) -> ApiResult {
+    let event_types: Vec<String> = body["event_types"]
+        .as_array()
+        .map(|types| {
+            types
+                .iter()
+                .filter_map(|t| t.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    let queued = crate::eventsub_handler::monitor()
+        .request_resubscribe(&event_types)
+        .map_err(|e| err_json(409, &e))?;
+    Ok(Json(json!({ "success": true, "event_types": queued })))
+}
+
 // ---------------------------------------------------------------------------
 // Custom rewards CRUD
 // ---------------------------------------------------------------------------
@@ -249,7 +318,7 @@ pub async fn get_custom_rewards(State(state): State<SharedState>) -> ApiResult {
     if config.twitch_user_id.is_empty() {
         return Err(err_json(400, "TWITCH_USER_ID is not configured"));
     }
-    let client = TwitchApiClient::new(config.client_id.clone());
+    let client = TwitchApiClient::with_http(config.client_id.clone(), state.http());
     let rewards = client
         .get_custom_rewards(&token, &config.twitch_user_id)
         .await
@@ -267,7 +336,7 @@ pub async fn create_custom_reward(
     if config.twitch_user_id.is_empty() {
         return Err(err_json(400, "TWITCH_USER_ID is not configured"));
     }
-    let client = TwitchApiClient::new(config.client_id.clone());
+    let client = TwitchApiClient::with_http(config.client_id.clone(), state.http());
     let req = CreateRewardRequest {
         title: body["title"].as_str().unwrap_or("").to_string(),
         cost: body["cost"].as_u64().unwrap_or(100),
@@ -307,7 +376,7 @@ pub async fn update_custom_reward(
     if config.twitch_user_id.is_empty() {
         return Err(err_json(400, "TWITCH_USER_ID is not configured"));
     }
-    let client = TwitchApiClient::new(config.client_id.clone());
+    let client = TwitchApiClient::with_http(config.client_id.clone(), state.http());
     let req = UpdateRewardRequest {
         title: body["title"].as_str().map(String::from),
         cost: body["cost"].as_u64(),
@@ -334,7 +403,7 @@ pub async fn toggle_custom_reward(
     if config.twitch_user_id.is_empty() {
         return Err(err_json(400, "TWITCH_USER_ID is not configured"));
     }
-    let client = TwitchApiClient::new(config.client_id.clone());
+    let client = TwitchApiClient::with_http(config.client_id.clone(), state.http());
 
     let target_enabled = body
         .as_ref()
@@ -372,7 +441,7 @@ pub async fn delete_custom_reward(
     if config.twitch_user_id.is_empty() {
         return Err(err_json(400, "TWITCH_USER_ID is not configured"));
     }
-    let client = TwitchApiClient::new(config.client_id.clone());
+    let client = TwitchApiClient::with_http(config.client_id.clone(), state.http());
     client
         .delete_custom_reward(&token, &config.twitch_user_id, &id)
         .await
@@ -380,6 +449,164 @@ pub async fn delete_custom_reward(
     Ok(Json(json!({ "success": true })))
 }
 
+// ---------------------------------------------------------------------------
+// Redemption queue
+// ---------------------------------------------------------------------------
+
+/// Most redemptions listed per reward.
+const REDEMPTION_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct RedemptionsQuery {
+    pub reward_id: Option<String>,
+    pub status: Option<String>,
+}
+
+/// GET /api/twitch/redemptions
+///
+/// With `?reward_id=` the redemptions of that reward (`status` defaults to
+/// `UNFULFILLED`). Without it, the review queue: unfulfilled redemptions of
+/// every custom reward, grouped by reward. Rewards created outside this app
+/// cannot be managed through Helix and come back with `manageable: false`.
+pub async fn get_redemptions(
+    State(state): State<SharedState>,
+    Query(q): Query<RedemptionsQuery>,
+) -> ApiResult {
+    let token = get_valid_token(&state).await?;
+    let config = state.config().await;
+    if config.twitch_user_id.is_empty() {
+        return Err(err_json(400, "TWITCH_USER_ID is not configured"));
+    }
+    let client = TwitchApiClient::with_http(config.client_id.clone(), state.http());
+    let broadcaster_id = &config.twitch_user_id;
+
+    if let Some(reward_id) = q.reward_id.filter(|id| !id.is_empty()) {
+        let status = q.status.as_deref().unwrap_or(REDEMPTION_UNFULFILLED);
+        let redemptions = client
+            .get_redemptions(&token, broadcaster_id, &reward_id, status, REDEMPTION_LIMIT)
+            .await
+            .map_err(map_twitch_error)?;
+        return Ok(Json(json!({ "data": redemptions })));
+    }
+
+    let rewards = client
+        .get_custom_rewards(&token, broadcaster_id)
+        .await
+        .map_err(map_twitch_error)?;
+    let mut queue = Vec::new();
+    let mut total = 0;
+    for reward in rewards {
+        let result = client
+            .get_redemptions(
+                &token,
+                broadcaster_id,
+                &reward.id,
+                REDEMPTION_UNFULFILLED,
+                REDEMPTION_LIMIT,
+            )
+            .await;
+        let (manageable, redemptions) = match result {
+            Ok(redemptions) => (true, redemptions),
+            Err(TwitchError::ApiError { status: 403, .. }) => (false, Vec::new()),
+            Err(e) => return Err(map_twitch_error(e)),
+        };
+        total += redemptions.len();
+        queue.push(json!({
+            "reward": reward,
+            "manageable": manageable,
+            "redemptions": redemptions,
+        }));
+    }
+    Ok(Json(json!({ "rewards": queue, "total": total })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RedemptionUpdate {
+    pub reward_id: String,
+    pub ids: Vec<String>,
+}
+
+async fn update_redemptions(
+    state: &SharedState,
+    body: RedemptionUpdate,
+    status: &str,
+) -> ApiResult {
+    if body.reward_id.is_empty() || body.ids.is_empty() {
+        return Err(err_json(400, "reward_id and ids are required"));
+    }
+    if body.ids.len() > 50 {
+        return Err(err_json(400, "At most 50 redemptions at a time"));
+    }
+    let token = get_valid_token(state).await?;
+    let config = state.config().await;
+    if config.twitch_user_id.is_empty() {
+        return Err(err_json(400, "TWITCH_USER_ID is not configured"));
+    }
+    let client = TwitchApiClient::with_http(config.client_id.clone(), state.http());
+    let updated = client
+        .update_redemption_status(
+            &token,
+            &config.twitch_user_id,
+            &body.reward_id,
+            &body.ids,
+            status,
+        )
+        .await
+        .map_err(map_twitch_error)?;
+    tracing::info!(
+        reward_id = %body.reward_id,
+        count = updated.len(),
+        status,
+        "Redemptions updated"
+    );
+    let _ = state.ws_sender().send(
+        json!({
+            "type": "redemptions_updated",
+            "data": { "reward_id": body.reward_id, "ids": body.ids, "status": status },
+        })
+        .to_string(),
+    );
+    Ok(Json(json!({ "success": true, "data": updated })))
+}
+
+/// POST /api/twitch/redemptions/fulfill – `{ "reward_id", "ids": [..] }`
+pub async fn fulfill_redemptions(
+    State(state): State<SharedState>,
+    Json(body): Json<RedemptionUpdate>,
+) -> ApiResult {
+    update_redemptions(&state, body, REDEMPTION_FULFILLED).await
+}
+
+/// POST /api/twitch/redemptions/refund – `{ "reward_id", "ids": [..] }`
+///
+/// Cancels the redemptions, which returns the viewers' channel points.
+pub async fn refund_redemptions(
+    State(state): State<SharedState>,
+    Json(body): Json<RedemptionUpdate>,
+) -> ApiResult {
+    update_redemptions(&state, body, REDEMPTION_CANCELED).await
+}
+
+// ---------------------------------------------------------------------------
+// Clips
+// ---------------------------------------------------------------------------
+
+/// POST /api/twitch/clip
+///
+/// Clip the live stream. Overlays get `clip_created` once Twitch has
+/// published it.
+pub async fn create_clip(State(state): State<SharedState>) -> ApiResult {
+    let clip = crate::services::clips::create(&state, crate::services::clips::SOURCE_DASHBOARD, "")
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    Ok(Json(json!({
+        "success": true,
+        "id": clip.id,
+        "url": crate::services::clips::clip_url(&clip.id),
+        "edit_url": clip.edit_url,
+    })))
+}
+
 // ---------------------------------------------------------------------------
 // Reward groups by reward
 // ---------------------------------------------------------------------------
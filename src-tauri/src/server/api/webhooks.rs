@@ -0,0 +1,218 @@
+//! Outbound webhook API (see `services::webhooks` for the request format):
+//!   GET    /api/webhooks                        – list webhooks
+//!   POST   /api/webhooks                        – create
+//!                                                 `{ name, url, secret?, event_types?, enabled? }`
+//!   PUT    /api/webhooks/{id}                   – replace; an omitted `secret` is kept
+//!   DELETE /api/webhooks/{id}                   – delete with its queued deliveries
+//!   POST   /api/webhooks/{id}/test              – send a test event right away
+//!   GET    /api/webhooks/deliveries             – queued deliveries (`?status=&limit=`)
+//!   DELETE /api/webhooks/deliveries             – clear failed deliveries
+//!   POST   /api/webhooks/deliveries/{id}/retry  – queue a failed delivery again
+//!
+//! Secrets are never returned; webhooks report `has_secret` instead.
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use overlay_db::webhooks::{STATUS_FAILED, STATUS_PENDING, Webhook, WebhookInput};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::webhooks;
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+const DEFAULT_DELIVERY_LIMIT: i64 = 100;
+const MAX_DELIVERY_LIMIT: i64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+struct WebhookBody {
+    name: String,
+    url: String,
+    secret: Option<String>,
+    event_types: Vec<String>,
+    enabled: bool,
+}
+
+fn parse_body(body: &Value) -> Result<WebhookBody, (axum::http::StatusCode, Json<Value>)> {
+    let name = body["name"].as_str().unwrap_or_default().trim();
+    if name.is_empty() {
+        return Err(err_json(400, "name is required"));
+    }
+    let url = body["url"].as_str().unwrap_or_default().trim();
+    let valid_url = reqwest::Url::parse(url)
+        .is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.host().is_some());
+    if !valid_url {
+        return Err(err_json(400, "url must be an http(s) URL"));
+    }
+    let event_types = match &body["event_types"] {
+        Value::Null => Vec::new(),
+        Value::Array(items) => items
+            .iter()
+            .map(|t| {
+                t.as_str()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .ok_or_else(|| err_json(400, "event_types must be non-empty strings"))
+            })
+            .collect::<Result<_, _>>()?,
+        _ => return Err(err_json(400, "event_types must be an array")),
+    };
+    Ok(WebhookBody {
+        name: name.to_string(),
+        url: url.to_string(),
+        secret: body["secret"].as_str().map(|s| s.trim().to_string()),
+        event_types,
+        enabled: body["enabled"].as_bool().unwrap_or(true),
+    })
+}
+
+fn public(hook: &Webhook) -> Value {
+    json!({
+        "id": hook.id,
+        "name": hook.name,
+        "url": hook.url,
+        "has_secret": !hook.secret.is_empty(),
+        "event_types": hook.event_types,
+        "enabled": hook.enabled,
+        "created_at": hook.created_at,
+        "updated_at": hook.updated_at,
+    })
+}
+
+fn find(state: &SharedState, id: i64) -> Result<Webhook, (axum::http::StatusCode, Json<Value>)> {
+    state
+        .db()
+        .get_webhook(id)
+        .map_err(|e| err_json(500, &e.to_string()))?
+        .ok_or_else(|| err_json(404, "Webhook not found"))
+}
+
+/// GET /api/webhooks
+pub async fn get_webhooks(State(state): State<SharedState>) -> ApiResult {
+    let hooks = state
+        .db()
+        .get_webhooks()
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let hooks: Vec<Value> = hooks.iter().map(public).collect();
+    Ok(Json(json!({ "webhooks": hooks })))
+}
+
+/// POST /api/webhooks
+pub async fn create_webhook(
+    State(state): State<SharedState>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let parsed = parse_body(&body)?;
+    let db = state.db();
+    let id = db
+        .add_webhook(
+            &WebhookInput {
+                name: &parsed.name,
+                url: &parsed.url,
+                secret: parsed.secret.as_deref().unwrap_or_default(),
+                event_types: &parsed.event_types,
+                enabled: parsed.enabled,
+            },
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let created = public(&find(&state, id)?);
+    Ok(Json(json!({ "success": true, "webhook": created })))
+}
+
+/// PUT /api/webhooks/{id}
+pub async fn update_webhook(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let parsed = parse_body(&body)?;
+    let existing = find(&state, id)?;
+    state
+        .db()
+        .update_webhook(
+            id,
+            &WebhookInput {
+                name: &parsed.name,
+                url: &parsed.url,
+                secret: parsed.secret.as_deref().unwrap_or(&existing.secret),
+                event_types: &parsed.event_types,
+                enabled: parsed.enabled,
+            },
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// DELETE /api/webhooks/{id}
+pub async fn delete_webhook(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let deleted = state
+        .db()
+        .delete_webhook(id)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !deleted {
+        return Err(err_json(404, "Webhook not found"));
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/webhooks/{id}/test
+pub async fn test_webhook(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let hook = find(&state, id)?;
+    webhooks::send_test(&state, &hook)
+        .await
+        .map_err(|e| err_json(502, &e))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// GET /api/webhooks/deliveries
+pub async fn get_deliveries(
+    State(state): State<SharedState>,
+    Query(q): Query<DeliveriesQuery>,
+) -> ApiResult {
+    let status = q.status.as_deref().unwrap_or(STATUS_FAILED);
+    if status != STATUS_FAILED && status != STATUS_PENDING {
+        return Err(err_json(400, "status must be pending or failed"));
+    }
+    let limit = q
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+        .clamp(1, MAX_DELIVERY_LIMIT);
+    let deliveries = state
+        .db()
+        .list_webhook_deliveries(status, limit)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    let count = deliveries.len();
+    Ok(Json(json!({ "deliveries": deliveries, "count": count })))
+}
+
+/// DELETE /api/webhooks/deliveries
+pub async fn clear_deliveries(State(state): State<SharedState>) -> ApiResult {
+    let cleared = state
+        .db()
+        .clear_webhook_deliveries(STATUS_FAILED)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "success": true, "cleared": cleared })))
+}
+
+/// POST /api/webhooks/deliveries/{id}/retry
+pub async fn retry_delivery(State(state): State<SharedState>, Path(id): Path<i64>) -> ApiResult {
+    let queued = state
+        .db()
+        .retry_webhook_delivery(id, chrono::Utc::now().timestamp())
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    if !queued {
+        return Err(err_json(404, "Delivery not found"));
+    }
+    webhooks::wake();
+    Ok(Json(json!({ "success": true })))
+}
@@ -0,0 +1,110 @@
+//! Desktop window control API (Stream Deck / remote devices).
+
+use axum::Json;
+use axum::extract::{Path, State};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::window::{control, stream_monitor};
+
+use super::err_json;
+
+type ApiResult = Result<Json<Value>, (axum::http::StatusCode, Json<Value>)>;
+
+/// Window control needs the desktop app; the headless server has no windows.
+fn require_desktop(state: &SharedState) -> Result<(), (axum::http::StatusCode, Json<Value>)> {
+    if state.app_handle().is_none() {
+        return Err(err_json(
+            503,
+            "Window control is unavailable in headless mode",
+        ));
+    }
+    Ok(())
+}
+
+fn to_api<T>(result: Result<T, String>) -> Result<T, (axum::http::StatusCode, Json<Value>)> {
+    result.map_err(|e| err_json(500, &e))
+}
+
+/// GET /api/window/status
+pub async fn get_status(State(state): State<SharedState>) -> ApiResult {
+    Ok(Json(json!(control::status(&state))))
+}
+
+/// POST /api/window/main/show
+pub async fn show_main(State(state): State<SharedState>) -> ApiResult {
+    require_desktop(&state)?;
+    to_api(control::show_main(&state))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/window/main/hide
+pub async fn hide_main(State(state): State<SharedState>) -> ApiResult {
+    require_desktop(&state)?;
+    to_api(control::hide_main(&state))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/window/settings/{tab}
+pub async fn open_settings_tab(
+    State(state): State<SharedState>,
+    Path(tab): Path<String>,
+) -> ApiResult {
+    require_desktop(&state)?;
+    if !control::SETTINGS_TABS.contains(&tab.as_str()) {
+        return Err(err_json(400, &format!("Unknown settings tab: {tab}")));
+    }
+    to_api(control::open_settings_tab(&state, &tab))?;
+    Ok(Json(json!({ "success": true, "tab": tab })))
+}
+
+/// POST /api/window/preview/open
+pub async fn open_preview(State(state): State<SharedState>) -> ApiResult {
+    require_desktop(&state)?;
+    to_api(control::open_overlay_preview(&state))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/window/preview/close
+pub async fn close_preview(State(state): State<SharedState>) -> ApiResult {
+    require_desktop(&state)?;
+    to_api(control::close_overlay_preview(&state))?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// POST /api/window/monitor/toggle
+pub async fn toggle_stream_monitor(State(state): State<SharedState>) -> ApiResult {
+    require_desktop(&state)?;
+    let visible = to_api(stream_monitor::toggle(&state))?;
+    Ok(Json(json!({ "success": true, "visible": visible })))
+}
+
+/// POST /api/window/notification/position
+///
+/// Body: `{ "x": i32, "y": i32, "screen_index"?: usize }`. With
+/// `screen_index`, `x`/`y` are relative to that screen.
+pub async fn move_notification(
+    State(state): State<SharedState>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    require_desktop(&state)?;
+    let coord = |key: &str| {
+        body.get(key)
+            .and_then(|v| v.as_i64())
+            .and_then(|v| i32::try_from(v).ok())
+    };
+    let (Some(x), Some(y)) = (coord("x"), coord("y")) else {
+        return Err(err_json(400, "x and y are required integers"));
+    };
+    let screen_index = match body.get("screen_index") {
+        None | Some(Value::Null) => None,
+        Some(v) => match v.as_u64() {
+            Some(i) => Some(i as usize),
+            None => return Err(err_json(400, "screen_index must be a non-negative integer")),
+        },
+    };
+
+    let position =
+        control::move_notification(&state, x, y, screen_index).map_err(|e| err_json(400, &e))?;
+    Ok(Json(json!({ "success": true, "position": position })))
+}
@@ -1,9 +1,24 @@
-//! Word filter CRUD API.
+//! Word filter CRUD API, plus list sharing. Entries take an optional
+//! `match_type` (`exact`, `substring` (default), `wildcard`, `regex`) and
+//! `severity` (`low`, `medium` (default), `high`).
+//!
+//!   GET  /api/word-filter/export?format=json|csv&lang=xx – download lists
+//!                                                         (all languages without `lang`)
+//!   POST /api/word-filter/import      – `{ mode: merge|replace, words: [...] }` or
+//!                                       `{ mode, csv: "language,word,type\n..." }`;
+//!                                       `replace` empties the imported languages first
+//!   POST /api/word-filter/bulk        – `{ language, type, match_type?, severity?,
+//!                                         words: ["...", ...] }`
+//!   POST /api/word-filter/bulk-delete – `{ ids: [1, 2, ...] }`
 
 use axum::Json;
 use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use overlay_db::word_filter::WordFilterWord;
 use serde::Deserialize;
 use serde_json::{Value, json};
+use word_filter::transfer;
 
 use crate::app::SharedState;
 
@@ -28,21 +43,30 @@ pub async fn get_words(State(state): State<SharedState>, Query(q): Query<LangQue
     ))
 }
 
+/// Entry described by `item` with `word`, defaulting to a medium-severity
+/// substring entry of `language` and `word_type`.
+fn entry(item: &Value, language: &str, word_type: &str, word: &str) -> WordFilterWord {
+    let field = |key: &str, default: &str| item[key].as_str().unwrap_or(default).to_string();
+    WordFilterWord {
+        language: field("language", language),
+        word_type: field("type", word_type),
+        match_type: field("match_type", "substring"),
+        severity: field("severity", "medium"),
+        ..WordFilterWord::new("", word, "")
+    }
+}
+
 /// POST /api/word-filter
 pub async fn add_word(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
-    let language = body["language"].as_str().unwrap_or("en");
     let word = body["word"]
         .as_str()
         .ok_or_else(|| err_json(400, "word is required"))?;
-    let word_type = body["type"].as_str().unwrap_or("bad");
-
-    if word_type != "bad" && word_type != "good" {
-        return Err(err_json(400, "type must be 'bad' or 'good'"));
-    }
+    let word =
+        transfer::normalize(&entry(&body, "en", "bad", word)).map_err(|e| err_json(400, &e))?;
 
     let w = state
         .db()
-        .add_word_filter_word(language, word, word_type)
+        .add_word_filter_word(&word)
         .map_err(|e| err_json(500, &e.to_string()))?;
     Ok(Json(json!({ "status": "ok", "word": w })))
 }
@@ -64,3 +88,119 @@ pub async fn get_languages(State(state): State<SharedState>) -> ApiResult {
         .map_err(|e| err_json(500, &e.to_string()))?;
     Ok(Json(json!({ "languages": langs })))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<String>,
+    pub lang: Option<String>,
+}
+
+/// GET /api/word-filter/export
+pub async fn export_words(
+    State(state): State<SharedState>,
+    Query(q): Query<ExportQuery>,
+) -> Result<Response, (axum::http::StatusCode, Json<Value>)> {
+    let words = match q.lang.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+        Some(lang) => state.db().get_word_filter_words(lang),
+        None => state.db().get_all_word_filter_words(),
+    }
+    .map_err(|e| err_json(500, &e.to_string()))?;
+
+    if q.format.as_deref() == Some("csv") {
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"word-filter.csv\"",
+                ),
+            ],
+            transfer::to_csv(&words),
+        )
+            .into_response());
+    }
+    let body = json!({
+        "words": words,
+        "count": words.len(),
+        "generated_at": chrono::Utc::now().to_rfc3339(),
+    });
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"word-filter.json\"",
+        )],
+        Json(body),
+    )
+        .into_response())
+}
+
+fn parse_words(body: &Value) -> Result<Vec<WordFilterWord>, String> {
+    if let Some(csv) = body["csv"].as_str() {
+        return transfer::from_csv(csv);
+    }
+    let items = body["words"]
+        .as_array()
+        .ok_or("words (array) or csv (string) is required")?;
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let word = entry(item, "", "bad", item["word"].as_str().unwrap_or_default());
+            transfer::normalize(&word).map_err(|e| format!("words[{i}]: {e}"))
+        })
+        .collect()
+}
+
+/// POST /api/word-filter/import
+pub async fn import_words(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let replace = match body["mode"].as_str().unwrap_or("merge") {
+        "merge" => false,
+        "replace" => true,
+        _ => return Err(err_json(400, "mode must be 'merge' or 'replace'")),
+    };
+    let words = parse_words(&body).map_err(|e| err_json(400, &e))?;
+    if words.is_empty() {
+        return Err(err_json(400, "No words to import"));
+    }
+    let imported = state
+        .db()
+        .import_word_filter_words(&words, replace)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({
+        "status": "ok",
+        "imported": imported,
+        "skipped": words.len() - imported,
+    })))
+}
+
+/// POST /api/word-filter/bulk
+pub async fn bulk_add(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let words = body["words"]
+        .as_array()
+        .ok_or_else(|| err_json(400, "words (array) is required"))?
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|word| transfer::normalize(&entry(&body, "en", "bad", word)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| err_json(400, &e))?;
+    let added = state
+        .db()
+        .import_word_filter_words(&words, false)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "status": "ok", "added": added })))
+}
+
+/// POST /api/word-filter/bulk-delete
+pub async fn bulk_delete(State(state): State<SharedState>, Json(body): Json<Value>) -> ApiResult {
+    let ids: Vec<i64> = body["ids"]
+        .as_array()
+        .ok_or_else(|| err_json(400, "ids (array) is required"))?
+        .iter()
+        .filter_map(Value::as_i64)
+        .collect();
+    let deleted = state
+        .db()
+        .delete_word_filter_words(&ids)
+        .map_err(|e| err_json(500, &e.to_string()))?;
+    Ok(Json(json!({ "status": "ok", "deleted": deleted })))
+}
@@ -1,21 +1,47 @@
+pub mod access;
 pub mod api;
 pub mod assets;
 pub mod router;
+pub mod streamerbot;
 pub mod websocket;
+pub mod ws_commands;
+
+use std::future::IntoFuture;
+use std::net::SocketAddr;
 
 use crate::app::SharedState;
+use crate::config::SettingsManager;
 use anyhow::Result;
+use tokio::sync::Notify;
+
+static RESTART: Notify = Notify::const_new();
 
 /// Start the axum HTTP + WebSocket server.
+///
+/// Runs until the process exits; [`restart_server`] rebinds it with the
+/// current port, bind address and IP allowlist. Connections already open
+/// (e.g. overlay WebSockets) are left to finish on their own.
 pub async fn start_server(state: SharedState) -> Result<()> {
-    let port = state.server_port();
-    let app = router::create_router(state);
+    loop {
+        let sm = SettingsManager::new(state.db().clone());
+        let addr = SocketAddr::new(access::bind_address(&sm), state.server_port());
+        let app = router::create_router(state.clone());
 
-    let addr = format!("0.0.0.0:{}", port);
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    tracing::info!("Overlay server listening on http://{}", addr);
+        let listener = tokio::net::TcpListener::bind(&addr).await?;
+        tracing::info!("Overlay server listening on http://{}", addr);
 
-    axum::serve(listener, app.into_make_service()).await?;
+        let serve = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        );
+        tokio::select! {
+            res = serve.into_future() => return res.map_err(Into::into),
+            _ = RESTART.notified() => tracing::info!("Restarting overlay server"),
+        }
+    }
+}
 
-    Ok(())
+/// Ask the running server to stop accepting connections and rebind.
+pub fn restart_server() {
+    RESTART.notify_one();
 }
@@ -1,17 +1,28 @@
+use std::sync::Arc;
+
 use axum::{
     Router,
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{delete, get, patch, post, put},
 };
 use tower_http::cors::CorsLayer;
 
-use super::{api, assets, websocket};
+use super::access::{self, AccessPolicy};
+use super::{api, assets, streamerbot, websocket};
 use crate::app::SharedState;
+use crate::config::SettingsManager;
 
 /// Create the axum router with all routes.
+///
+/// The IP allowlist is read here, so it takes effect on the next
+/// (re)start of the server.
 pub fn create_router(state: SharedState) -> Router {
+    let policy = Arc::new(AccessPolicy::load(&SettingsManager::new(state.db().clone())));
     Router::new()
         // --- Core ---
         .route("/status", get(status_handler))
+        .route("/present/results", get(api::lottery_results::results_page))
         .route("/ws", get(websocket::ws_handler))
         .route("/auth", get(api::twitch::auth_redirect))
         .route("/callback", get(api::twitch::callback))
@@ -29,6 +40,15 @@ pub fn create_router(state: SharedState) -> Router {
             "/api/settings/status",
             get(api::settings::get_settings_status),
         )
+        .route("/api/features", get(api::settings::get_features))
+        // --- Setup wizard ---
+        .route("/api/setup", get(api::setup::get_setup))
+        .route("/api/setup/credentials", post(api::setup::set_credentials))
+        .route("/api/setup/oauth", get(api::setup::get_oauth))
+        .route("/api/setup/broadcaster", post(api::setup::set_broadcaster))
+        .route("/api/setup/printer", post(api::setup::set_printer))
+        .route("/api/setup/overlay", post(api::setup::finish))
+        .route("/api/setup/reset", post(api::setup::reset))
         .route("/api/settings/auth/status", get(api::twitch::auth_status))
         // --- Font ---
         .route(
@@ -44,10 +64,48 @@ pub fn create_router(state: SharedState) -> Router {
             get(api::overlay::get_overlay_settings).post(api::overlay::update_overlay_settings),
         )
         .route("/api/overlay/refresh", post(api::overlay::refresh_overlay))
+        .route(
+            "/api/overlay/preview",
+            get(api::overlay::get_preview).post(api::overlay::set_preview),
+        )
+        .route(
+            "/api/overlay/preview/inject",
+            post(api::overlay::inject_preview_event),
+        )
+        .route(
+            "/api/overlay/effects",
+            get(api::overlay::get_effects).post(api::overlay::trigger_effect),
+        )
+        .route(
+            "/api/overlay/effects/presets/{name}",
+            put(api::overlay::save_effect_preset).delete(api::overlay::delete_effect_preset),
+        )
+        .route(
+            "/api/overlay/emote-rain",
+            get(api::emote_rain::get_emote_rain),
+        )
+        .route(
+            "/api/overlay/emote-rain/test",
+            post(api::emote_rain::test_rain),
+        )
+        .route(
+            "/api/overlay/emote-rain/{emote_id}",
+            put(api::emote_rain::set_cooldown).delete(api::emote_rain::delete_cooldown),
+        )
         .route(
             "/api/settings/overlay/events",
             get(api::overlay::overlay_events),
         )
+        .route("/api/overlay/urls", get(api::overlay::get_overlay_urls))
+        .route(
+            "/api/overlay/urls/rotate",
+            post(api::overlay::rotate_overlay_urls),
+        )
+        .route(
+            "/api/overlay/session/rotate",
+            post(api::overlay::rotate_overlay_session),
+        )
+        .route("/api/overlay/token", get(api::overlay::get_overlay_token))
         // --- Music tracks ---
         .route("/api/music/upload", post(api::music::upload_track))
         .route("/api/music/tracks", get(api::music::get_tracks))
@@ -130,6 +188,19 @@ pub fn create_router(state: SharedState) -> Router {
             "/api/word-filter/languages",
             get(api::word_filter::get_languages),
         )
+        .route(
+            "/api/word-filter/export",
+            get(api::word_filter::export_words),
+        )
+        .route(
+            "/api/word-filter/import",
+            post(api::word_filter::import_words),
+        )
+        .route("/api/word-filter/bulk", post(api::word_filter::bulk_add))
+        .route(
+            "/api/word-filter/bulk-delete",
+            post(api::word_filter::bulk_delete),
+        )
         // --- Reward counts ---
         .route(
             "/api/twitch/reward-counts",
@@ -151,6 +222,18 @@ pub fn create_router(state: SharedState) -> Router {
             "/api/twitch/rewards/{id}/display-name",
             put(api::reward::set_display_name),
         )
+        .route(
+            "/api/reward/counts/stream",
+            get(api::reward::get_count_feed),
+        )
+        .route(
+            "/api/reward/counts/goals",
+            get(api::reward::get_count_goals),
+        )
+        .route(
+            "/api/reward/counts/{reward_id}/goal",
+            put(api::reward::set_count_goal).delete(api::reward::delete_count_goal),
+        )
         // --- Reward groups ---
         .route(
             "/api/twitch/reward-groups",
@@ -164,6 +247,22 @@ pub fn create_router(state: SharedState) -> Router {
             "/api/twitch/reward-groups/{id}/toggle",
             post(api::reward::toggle_group),
         )
+        .route(
+            "/api/twitch/reward-groups/{id}/enable",
+            post(api::reward::enable_group),
+        )
+        .route(
+            "/api/twitch/reward-groups/{id}/disable",
+            post(api::reward::disable_group),
+        )
+        .route(
+            "/api/twitch/reward-groups/{id}/schedule",
+            get(api::reward::get_group_schedule).put(api::reward::set_group_schedule),
+        )
+        .route(
+            "/api/twitch/reward-groups/{id}/policy",
+            get(api::reward::get_group_policy).put(api::reward::set_group_policy),
+        )
         .route(
             "/api/twitch/reward-groups/{gid}/rewards",
             post(api::reward::add_reward_to_group_legacy),
@@ -197,6 +296,16 @@ pub fn create_router(state: SharedState) -> Router {
             "/api/twitch/custom-rewards/{id}/toggle",
             patch(api::twitch::toggle_custom_reward),
         )
+        .route("/api/twitch/redemptions", get(api::twitch::get_redemptions))
+        .route(
+            "/api/twitch/redemptions/fulfill",
+            post(api::twitch::fulfill_redemptions),
+        )
+        .route(
+            "/api/twitch/redemptions/refund",
+            post(api::twitch::refund_redemptions),
+        )
+        .route("/api/twitch/clip", post(api::twitch::create_clip))
         // --- Lottery / Present ---
         .route("/api/lottery", get(api::present::get_lottery))
         .route(
@@ -227,26 +336,149 @@ pub fn create_router(state: SharedState) -> Router {
         .route("/api/present/clear", post(api::present::clear_present))
         .route("/api/present/lock", post(api::present::lock_present))
         .route("/api/present/unlock", post(api::present::unlock_present))
+        .route(
+            "/api/present/winner/preview",
+            get(api::present::get_winner_preview).post(api::present::update_winner_preview),
+        )
+        .route(
+            "/api/present/winner/print",
+            post(api::present::print_winner_receipt),
+        )
+        .route(
+            "/api/present/simulate-tickets",
+            post(api::present::simulate_tickets),
+        )
+        .route("/api/present/history", get(api::present::get_draw_history))
+        .route(
+            "/api/present/history/{id}/notify",
+            post(api::present::renotify_winner),
+        )
+        .route(
+            "/api/present/history/{id}/claim",
+            post(api::present::claim_draw),
+        )
         .route(
             "/api/present/refresh-subscribers",
             post(api::present::refresh_present_subscribers),
         )
+        .route(
+            "/api/present/settings/presets",
+            get(api::lottery_presets::get_presets).post(api::lottery_presets::save_preset),
+        )
+        .route(
+            "/api/present/settings/presets/export",
+            get(api::lottery_presets::export_current),
+        )
+        .route(
+            "/api/present/settings/presets/{name}",
+            get(api::lottery_presets::get_preset).delete(api::lottery_presets::delete_preset),
+        )
+        .route(
+            "/api/present/settings/presets/{name}/apply",
+            post(api::lottery_presets::apply_preset),
+        )
         // --- Chat ---
         .route("/api/chat/messages", get(api::chat::get_messages))
         .route("/api/chat/history", get(api::chat::get_history))
+        .route("/api/chat/search", get(api::chat::search_messages))
+        .route("/api/chat/send", post(api::chat::send_message))
         .route("/api/chat/cleanup", post(api::chat::cleanup_messages))
         .route("/api/chat/avatar/{user_id}", get(api::chat::get_avatar))
+        .route("/api/chat/profile/{user_id}", get(api::chat::get_profile))
+        .route("/api/chat/profiles", delete(api::chat::clear_profiles))
+        .route("/api/chat/render", get(api::chat::render_chat))
+        .route("/api/chat/wall", post(api::chat::print_wall))
+        .route(
+            "/api/chat/print-votes",
+            get(api::print_vote::get_print_votes).delete(api::print_vote::clear_print_votes),
+        )
+        .route(
+            "/api/chat/print-votes/print",
+            post(api::print_vote::print_winner),
+        )
+        .route("/api/chat/wall/preview", post(api::chat::preview_wall))
+        .route(
+            "/api/chat/timers",
+            get(api::chat_timers::get_timers).post(api::chat_timers::create_timer),
+        )
+        .route(
+            "/api/chat/timers/{id}",
+            put(api::chat_timers::update_timer).delete(api::chat_timers::delete_timer),
+        )
+        .route(
+            "/api/chat/timers/{id}/send",
+            post(api::chat_timers::send_timer),
+        )
+        .route(
+            "/api/chat/commands",
+            get(api::chat_commands::get_commands).post(api::chat_commands::create_command),
+        )
+        .route(
+            "/api/chat/commands/{id}",
+            put(api::chat_commands::update_command).delete(api::chat_commands::delete_command),
+        )
         // --- Twitch ---
+        .route("/api/emotes", get(api::emotes::get_emotes))
+        .route("/api/emotes/groups", get(api::emotes::get_groups))
+        .route("/api/emotes/groups/{id}", get(api::emotes::get_group))
+        .route("/api/emotes/search", get(api::emotes::search_emotes))
+        .route("/api/emotes/refresh", post(api::emotes::refresh_emotes))
         .route("/api/twitch/verify", get(api::twitch::verify_twitch))
         .route(
             "/api/twitch/refresh-token",
             post(api::twitch::refresh_token),
         )
         .route("/api/stream/status", get(api::twitch::stream_status))
+        .route("/api/stream/pre-show", get(api::pre_show::get_pre_show))
+        .route("/api/stream/pre-show/start", post(api::pre_show::start))
+        .route("/api/stream/pre-show/stop", post(api::pre_show::stop))
+        .route(
+            "/api/stream/sessions",
+            get(api::stream_sessions::get_sessions),
+        )
+        .route(
+            "/api/stream/sessions/{id}",
+            get(api::stream_sessions::get_session),
+        )
+        .route(
+            "/api/stream/viewers/history",
+            get(api::stream_sessions::get_viewer_history),
+        )
+        .route("/api/moderation/log", get(api::moderation::get_log))
+        .route("/api/moderation/events", get(api::moderation::get_events))
+        .route("/api/irc/status", get(api::irc::get_status))
+        .route("/api/irc/messages", get(api::irc::get_messages))
+        .route("/api/irc/send", post(api::irc::send))
+        .route("/api/irc/channels", post(api::irc::join))
+        .route("/api/irc/channels/{channel}", delete(api::irc::part))
+        .route(
+            "/api/moderation/chat-settings",
+            get(api::moderation::get_chat_settings)
+                .patch(api::moderation::update_chat_settings),
+        )
+        .route("/api/moderation/shield-mode", put(api::moderation::set_shield_mode))
+        .route(
+            "/api/twitch/eventsub/status",
+            get(api::twitch::eventsub_status),
+        )
+        .route(
+            "/api/twitch/eventsub/resubscribe",
+            post(api::twitch::eventsub_resubscribe),
+        )
+        .route("/api/twitch/shoutouts", post(api::twitch::queue_shoutout))
+        .route(
+            "/api/twitch/shoutouts/pending",
+            get(api::twitch::pending_shoutouts),
+        )
+        .route(
+            "/api/twitch/shoutouts/pending/{id}",
+            delete(api::twitch::cancel_shoutout),
+        )
         // --- Printer ---
         .route("/api/printer/scan", post(api::printer::scan_printers))
         .route("/api/printer/test", post(api::printer::test_printer))
         .route("/api/printer/status", get(api::printer::printer_status))
+        .route("/api/printer/printers", get(api::printer::list_printers))
         .route(
             "/api/printer/reconnect",
             post(api::printer::reconnect_printer),
@@ -256,11 +488,269 @@ pub fn create_router(state: SharedState) -> Router {
             "/api/printer/system-printers",
             get(api::printer::list_system_printers),
         )
+        .route(
+            "/api/printer/power",
+            get(api::printer::get_power).post(api::printer::set_power),
+        )
+        .route(
+            "/api/printer/print-image",
+            post(api::printer::print_image).layer(DefaultBodyLimit::max(
+                crate::services::print_render::MAX_UPLOAD_BYTES + 64 * 1024,
+            )),
+        )
+        .route(
+            "/api/printer/preview",
+            post(api::printer::preview_print).layer(DefaultBodyLimit::max(
+                crate::services::print_render::MAX_UPLOAD_BYTES + 64 * 1024,
+            )),
+        )
+        .route(
+            "/api/printer/queue",
+            get(api::printer::get_queue).delete(api::printer::clear_queue),
+        )
+        .route("/api/printer/queue/pause", post(api::printer::pause_queue))
+        .route(
+            "/api/printer/queue/resume",
+            post(api::printer::resume_queue),
+        )
+        .route("/api/printer/queue/order", put(api::printer::reorder_queue))
+        .route(
+            "/api/printer/queue/failed",
+            delete(api::printer::clear_failed_jobs),
+        )
+        .route(
+            "/api/printer/queue/{id}",
+            delete(api::printer::cancel_queued_job),
+        )
+        .route(
+            "/api/printer/queue/{id}/retry",
+            post(api::printer::retry_queued_job),
+        )
+        .route("/api/printer/schedules", get(api::printer::get_schedules))
+        .route(
+            "/api/printer/emote-approvals",
+            get(api::emote_approval::get_approvals),
+        )
+        .route(
+            "/api/printer/emote-approvals/{emote_id}",
+            put(api::emote_approval::set_approval),
+        )
+        // --- AFK ---
+        .route(
+            "/api/ads/break",
+            get(api::ads::get_break)
+                .post(api::ads::start_break)
+                .delete(api::ads::end_break),
+        )
+        .route("/api/twitch/ads/schedule", get(api::ads::get_schedule))
+        .route("/api/twitch/ads/snooze", post(api::ads::snooze))
+        .route(
+            "/api/overlay/afk",
+            get(api::afk::get_afk).post(api::afk::set_afk),
+        )
+        .route(
+            "/api/overlay/afk/activity",
+            post(api::afk::report_activity),
+        )
+        // --- Milestones ---
+        .route("/api/milestones", get(api::milestone::get_milestones))
+        .route(
+            "/api/milestones/{kind}/{threshold}",
+            delete(api::milestone::reset_milestone),
+        )
+        // --- Stream segments ---
+        .route(
+            "/api/segments",
+            get(api::segment::get_segments).post(api::segment::start_segment),
+        )
+        .route("/api/segments/current", get(api::segment::get_current))
+        .route("/api/segments/end", post(api::segment::end_segment))
+        .route("/api/segments/export", get(api::segment::export_segments))
+        .route("/api/segments/{id}", delete(api::segment::delete_segment))
+        // --- Quotes ---
+        .route(
+            "/api/quotes",
+            get(api::quotes::get_quotes).post(api::quotes::add_quote),
+        )
+        .route(
+            "/api/quotes/{id}",
+            put(api::quotes::update_quote).delete(api::quotes::delete_quote),
+        )
+        .route("/api/quotes/{id}/print", post(api::quotes::print_quote))
+        .route("/api/goals/funding", get(api::goals::get_funding))
+        .route("/api/goals/funding/entries", get(api::goals::get_entries))
+        .route(
+            "/api/goals/funding/donations",
+            post(api::goals::add_donation),
+        )
+        .route("/api/goals/funding/adjust", post(api::goals::adjust))
+        .route("/api/goals/funding/reset", post(api::goals::reset))
+        .route("/api/privacy/consents", get(api::privacy::get_consents))
+        .route(
+            "/api/privacy/consents/{user_id}",
+            get(api::privacy::get_consent)
+                .put(api::privacy::set_consent)
+                .delete(api::privacy::delete_consent),
+        )
+        // --- Rundown ---
+        .route(
+            "/api/rundown",
+            get(api::rundown::get_rundown).post(api::rundown::add_item),
+        )
+        .route("/api/rundown/order", put(api::rundown::reorder))
+        .route("/api/rundown/print", post(api::rundown::print_rundown))
+        .route(
+            "/api/rundown/{id}",
+            put(api::rundown::update_item).delete(api::rundown::delete_item),
+        )
+        // --- Chat stats ---
+        .route("/api/stats/wordcloud", get(api::stats::get_wordcloud))
+        .route(
+            "/api/stats/wordcloud/print",
+            post(api::stats::print_wordcloud),
+        )
+        .route("/api/stats/raids", get(api::stats::get_raids))
+        .route("/api/chat/stats", get(api::stats::get_chat_stats))
+        // --- Notification window ---
+        .route("/api/notification/dismiss", post(api::notification::dismiss))
+        .route("/api/notification/action", post(api::notification::run_action))
+        // --- Window ---
+        .route("/api/window/status", get(api::window::get_status))
+        .route("/api/window/main/show", post(api::window::show_main))
+        .route("/api/window/main/hide", post(api::window::hide_main))
+        .route(
+            "/api/window/settings/{tab}",
+            post(api::window::open_settings_tab),
+        )
+        .route("/api/window/preview/open", post(api::window::open_preview))
+        .route("/api/window/preview/close", post(api::window::close_preview))
+        .route(
+            "/api/window/monitor/toggle",
+            post(api::window::toggle_stream_monitor),
+        )
+        .route(
+            "/api/window/notification/position",
+            post(api::window::move_notification),
+        )
+        // --- Integrations ---
+        .route("/api/integrations/events", get(api::integrations::get_events))
+        .route("/api/integrations/osc", get(api::integrations::get_osc))
+        .route("/api/integrations/osc/test", post(api::integrations::test_osc))
+        .route("/api/integrations/midi", get(api::integrations::get_midi))
+        .route("/api/integrations/midi/test", post(api::integrations::test_midi))
+        .route("/api/integrations/lights", get(api::integrations::get_lights))
+        .route(
+            "/api/integrations/lights/test",
+            post(api::integrations::test_lights),
+        )
+        .route(
+            "/api/integrations/lights/hue/pair",
+            post(api::integrations::pair_hue),
+        )
+        .route(
+            "/api/integrations/{integration}/triggers",
+            get(api::integrations::get_triggers).post(api::integrations::add_trigger),
+        )
+        .route(
+            "/api/integrations/{integration}/triggers/{id}",
+            put(api::integrations::update_trigger).delete(api::integrations::delete_trigger),
+        )
+        // --- Macros ---
+        .route(
+            "/api/actions/macros",
+            get(api::actions::get_macros).post(api::actions::create_macro),
+        )
+        .route(
+            "/api/actions/macros/{id}",
+            get(api::actions::get_macro)
+                .put(api::actions::update_macro)
+                .delete(api::actions::delete_macro),
+        )
+        .route(
+            "/api/actions/macros/{id}/run",
+            post(api::actions::run_macro),
+        )
+        // --- OBS ---
+        .route("/api/obs/status", get(api::obs::get_status))
+        .route(
+            "/api/obs/rules",
+            get(api::obs::get_rules).post(api::obs::add_rule),
+        )
+        .route(
+            "/api/obs/rules/{id}",
+            put(api::obs::update_rule).delete(api::obs::delete_rule),
+        )
+        // --- Text-to-speech ---
+        .route("/api/tts", get(api::tts::get_status))
+        .route("/api/tts/speak", post(api::tts::speak))
+        .route("/api/tts/pause", post(api::tts::pause))
+        .route("/api/tts/resume", post(api::tts::resume))
+        .route("/api/tts/skip", post(api::tts::skip))
+        .route("/api/tts/clear", post(api::tts::clear))
+        // --- Discord ---
+        .route("/api/discord", get(api::discord::get_status))
+        .route("/api/discord/test", post(api::discord::test))
+        .route("/api/discord/templates", get(api::discord::get_templates))
+        .route(
+            "/api/discord/templates/{event}",
+            put(api::discord::update_template).delete(api::discord::reset_template),
+        )
+        // --- Webhooks ---
+        .route(
+            "/api/webhooks",
+            get(api::webhooks::get_webhooks).post(api::webhooks::create_webhook),
+        )
+        .route(
+            "/api/webhooks/deliveries",
+            get(api::webhooks::get_deliveries).delete(api::webhooks::clear_deliveries),
+        )
+        .route(
+            "/api/webhooks/deliveries/{id}/retry",
+            post(api::webhooks::retry_delivery),
+        )
+        .route(
+            "/api/webhooks/{id}",
+            put(api::webhooks::update_webhook).delete(api::webhooks::delete_webhook),
+        )
+        .route("/api/webhooks/{id}/test", post(api::webhooks::test_webhook))
+        // --- Streamer.bot compatibility ---
+        .route("/streamerbot", get(streamerbot::ws_handler))
+        .route("/streamerbot/DoAction", post(streamerbot::http_do_action))
+        .route("/streamerbot/GetActions", get(streamerbot::http_get_actions))
+        // --- System ---
+        .route(
+            "/api/system/import-legacy",
+            post(api::system::import_legacy),
+        )
+        .route(
+            "/api/system/rebuild-projections",
+            post(api::system::rebuild_projections),
+        )
+        .route("/api/system/jobs", get(api::system::list_jobs))
+        .route("/api/system/jobs/{id}", get(api::system::get_job))
+        .route("/api/system/selftest", post(api::system::selftest))
+        .route("/api/system/metrics", get(api::system::metrics))
+        .route(
+            "/api/system/panic",
+            get(api::system::get_panic)
+                .post(api::system::trigger_panic)
+                .delete(api::system::release_panic),
+        )
+        .route(
+            "/api/system/metrics/reset",
+            post(api::system::reset_metrics),
+        )
+        .route(
+            "/api/system/restart-server",
+            post(api::system::restart_server),
+        )
         // --- Logs ---
         .route("/api/logs", get(api::logs::get_logs))
         .route("/api/logs/stream", get(api::logs::stream_logs))
         .route("/api/logs/download", get(api::logs::download_logs))
         .route("/api/logs/clear", post(api::logs::clear_logs))
+        // --- Search ---
+        .route("/api/search", get(api::search::search))
         // --- Debug ---
         .route("/debug/fax", post(api::debug::debug_fax))
         .route(
@@ -287,6 +777,7 @@ pub fn create_router(state: SharedState) -> Router {
             "/api/debug/printer-status",
             post(api::debug::debug_printer_status),
         )
+        .route("/api/debug/events", get(api::debug::get_archived_events))
         // --- Overlay static files ---
         .route("/overlay/", get(assets::overlay_index))
         .route("/overlay/{*path}", get(assets::overlay_handler))
@@ -295,12 +786,14 @@ pub fn create_router(state: SharedState) -> Router {
         .fallback(assets::dashboard_fallback)
         // --- Middleware ---
         .layer(CorsLayer::permissive())
+        .layer(middleware::from_fn_with_state(policy, access::enforce))
         .with_state(state)
 }
 
 async fn status_handler() -> axum::Json<serde_json::Value> {
     axum::Json(serde_json::json!({
         "status": "ok",
-        "version": "1.0.0"
+        "version": "1.0.0",
+        "demo": crate::services::demo::is_enabled()
     }))
 }
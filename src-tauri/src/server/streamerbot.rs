@@ -0,0 +1,265 @@
+//! Streamer.bot compatibility bridge.
+//!
+//! Implements the subset of the Streamer.bot WebSocket protocol that trigger
+//! tools (Streamer.bot clients, SAMMI, Touch Portal plugins) rely on:
+//! `Hello`, `GetInfo`, `GetActions`, `DoAction` and no-op
+//! `Subscribe`/`UnSubscribe`. The same actions are available over the
+//! Streamer.bot HTTP shape (`POST /streamerbot/DoAction`,
+//! `GET /streamerbot/GetActions`).
+//!
+//! Actions are matched by ID or by name (case-insensitive), so existing
+//! automations only need the endpoint changed.
+
+use axum::extract::ws::{Message, WebSocket};
+use axum::extract::{State, WebSocketUpgrade};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, http::StatusCode};
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::{enqueue_notification, send_ws};
+use crate::notification::dedup;
+use crate::notification::types::NotificationType;
+use crate::services::print_queue::PrintCategory;
+use crate::services::print_render;
+
+use super::api;
+
+const SETTING_ENABLED: &str = "STREAMERBOT_BRIDGE_ENABLED";
+const BRIDGE_GROUP: &str = "Cairo Overlay";
+
+/// Actions exposed to Streamer.bot clients: (id, name).
+const ACTIONS: &[(&str, &str)] = &[
+    ("cairo-alert", "Show Alert"),
+    ("cairo-print", "Print Text"),
+    ("cairo-overlay-event", "Overlay Event"),
+    ("cairo-lottery-start", "Lottery Start"),
+    ("cairo-lottery-stop", "Lottery Stop"),
+    ("cairo-lottery-draw", "Lottery Draw"),
+    ("cairo-lottery-clear", "Lottery Clear"),
+];
+
+fn enabled(state: &SharedState) -> bool {
+    SettingsManager::new(state.db().clone())
+        .get_setting(SETTING_ENABLED)
+        .unwrap_or_default()
+        .eq_ignore_ascii_case("true")
+}
+
+fn disabled_response() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({ "status": "error", "error": "Streamer.bot bridge is disabled" })),
+    )
+        .into_response()
+}
+
+/// GET /streamerbot – WebSocket endpoint.
+pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<SharedState>) -> Response {
+    if !enabled(&state) {
+        return disabled_response();
+    }
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+        .into_response()
+}
+
+/// POST /streamerbot/DoAction – HTTP form of `DoAction`.
+pub async fn http_do_action(State(state): State<SharedState>, Json(body): Json<Value>) -> Response {
+    if !enabled(&state) {
+        return disabled_response();
+    }
+    match do_action(&state, &body).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "status": "error", "error": e })),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /streamerbot/GetActions – HTTP form of `GetActions`.
+pub async fn http_get_actions(State(state): State<SharedState>) -> Response {
+    if !enabled(&state) {
+        return disabled_response();
+    }
+    Json(json!({ "actions": action_list(), "count": ACTIONS.len() })).into_response()
+}
+
+async fn handle_socket(socket: WebSocket, state: SharedState) {
+    let (mut sender, mut receiver) = socket.split();
+
+    let hello = json!({
+        "timeStamp": chrono::Utc::now().to_rfc3339(),
+        "session": uuid::Uuid::new_v4().to_string(),
+        "request": "Hello",
+        "info": info(),
+    });
+    if sender
+        .send(Message::Text(hello.to_string().into()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+    tracing::info!("Streamer.bot bridge client connected");
+
+    while let Some(Ok(msg)) = receiver.next().await {
+        let Message::Text(text) = msg else {
+            if matches!(msg, Message::Close(_)) {
+                break;
+            }
+            continue;
+        };
+        let Ok(request) = serde_json::from_str::<Value>(&text) else {
+            continue;
+        };
+        let response = handle_request(&state, &request).await;
+        if sender
+            .send(Message::Text(response.to_string().into()))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+    tracing::info!("Streamer.bot bridge client disconnected");
+}
+
+/// Answer one protocol request. Every response echoes the request `id`.
+async fn handle_request(state: &SharedState, request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let kind = request
+        .get("request")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+
+    let mut response = match kind {
+        "GetInfo" => json!({ "info": info() }),
+        "GetActions" => json!({ "actions": action_list(), "count": ACTIONS.len() }),
+        "Subscribe" | "UnSubscribe" | "GetEvents" => {
+            // Events are not forwarded; acknowledge so clients keep working.
+            json!({ "events": request.get("events").cloned().unwrap_or(json!({})) })
+        }
+        "DoAction" => match do_action(state, request).await {
+            Ok(()) => json!({}),
+            Err(e) => json!({ "status": "error", "error": e }),
+        },
+        _ => json!({ "status": "error", "error": format!("Unsupported request: {kind}") }),
+    };
+
+    if let Some(obj) = response.as_object_mut() {
+        obj.insert("id".into(), id);
+        obj.entry("status").or_insert_with(|| json!("ok"));
+    }
+    response
+}
+
+fn info() -> Value {
+    json!({
+        "instanceId": "cairo-overlay",
+        "name": "Cairo Overlay",
+        "os": std::env::consts::OS,
+        "version": env!("CARGO_PKG_VERSION"),
+    })
+}
+
+fn action_list() -> Vec<Value> {
+    ACTIONS
+        .iter()
+        .map(|(id, name)| {
+            json!({
+                "id": id,
+                "name": name,
+                "group": BRIDGE_GROUP,
+                "enabled": true,
+                "subaction_count": 0,
+            })
+        })
+        .collect()
+}
+
+/// Resolve `{ "action": { "id" | "name" }, "args": { ... } }` and run it.
+async fn do_action(state: &SharedState, request: &Value) -> Result<(), String> {
+    let action = request.get("action").unwrap_or(&Value::Null);
+    let by_id = action
+        .get("id")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let by_name = action
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let (id, name) = ACTIONS
+        .iter()
+        .find(|(id, name)| *id == by_id || name.eq_ignore_ascii_case(by_name))
+        .ok_or_else(|| {
+            let requested = if by_name.is_empty() { by_id } else { by_name };
+            format!("Unknown action: {requested}")
+        })?;
+
+    let args = request.get("args").cloned().unwrap_or(json!({}));
+    let arg = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| args.get(*k).and_then(|v| v.as_str()))
+            .unwrap_or_default()
+            .to_string()
+    };
+    tracing::info!("Streamer.bot bridge action: {name}");
+
+    match *id {
+        "cairo-alert" => {
+            let user = arg(&["user", "userName", "username"]);
+            let message = arg(&["message", "rawInput"]);
+            let message_id = arg(&["msgId"]);
+            if dedup::is_duplicate(dedup::Source::StreamerBot, &message_id, &user, &message) {
+                return Ok(());
+            }
+            enqueue_notification(state, user, message, vec![], NotificationType::Chat).await;
+            Ok(())
+        }
+        "cairo-print" => {
+            let title = arg(&["title"]);
+            print_render::print_titled(
+                state,
+                if title.is_empty() {
+                    "Streamer.bot"
+                } else {
+                    &title
+                },
+                &arg(&["user", "userName", "username"]),
+                &arg(&["message", "rawInput"]),
+                PrintCategory::Other,
+            )
+            .await
+        }
+        "cairo-overlay-event" => {
+            let event_type = arg(&["type"]);
+            if event_type.is_empty() {
+                return Err("args.type is required".into());
+            }
+            send_ws(
+                state,
+                &event_type,
+                args.get("data").cloned().unwrap_or(Value::Null),
+            );
+            Ok(())
+        }
+        "cairo-lottery-start" => lottery(api::present::start_present(State(state.clone())).await),
+        "cairo-lottery-stop" => lottery(api::present::stop_present(State(state.clone())).await),
+        "cairo-lottery-draw" => lottery(api::present::draw_present(State(state.clone())).await),
+        "cairo-lottery-clear" => lottery(api::present::clear_present(State(state.clone())).await),
+        _ => Err(format!("Unhandled action: {name}")),
+    }
+}
+
+fn lottery(result: Result<Json<Value>, (StatusCode, Json<Value>)>) -> Result<(), String> {
+    result.map(|_| ()).map_err(|(_, Json(body))| {
+        body.get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Lottery action failed")
+            .to_string()
+    })
+}
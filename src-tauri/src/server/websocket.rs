@@ -1,25 +1,79 @@
 use axum::{
+    Extension,
     extract::{
-        State, WebSocketUpgrade,
-        ws::{Message, WebSocket},
+        Query, State, WebSocketUpgrade,
+        ws::{CloseFrame, Message, WebSocket, close_code},
     },
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
 };
 use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::{broadcast, mpsc};
 
+use super::access::{self, AdminAccess};
+use super::ws_commands::{self, Caller};
 use crate::app::SharedState;
+use crate::services::overlay_preview;
+use crate::services::overlay_tokens::{self, OverlayClaims};
+
+/// How often a session-token client is re-checked (and its token renewed).
+const SESSION_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(Deserialize)]
+pub struct WsQuery {
+    /// `1` for overlay preview clients, which also receive simulated events.
+    preview: Option<String>,
+    /// Signed overlay token; its topics limit the messages sent.
+    token: Option<String>,
+}
 
 /// WebSocket upgrade handler.
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<SharedState>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    Query(query): Query<WsQuery>,
+    headers: HeaderMap,
+    admin: Option<Extension<AdminAccess>>,
+) -> Response {
+    // Browsers always send `Origin`; other clients (OBS, scripts) may not.
+    if let Some(origin) = headers.get(header::ORIGIN) {
+        let origin = origin.to_str().unwrap_or_default();
+        let host = headers
+            .get(header::HOST)
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or_default();
+        if !access::is_allowed_origin(origin, host) {
+            tracing::warn!(origin, "Rejected cross-origin WebSocket upgrade");
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+    let preview = query.preview.as_deref() == Some("1");
+    let claims = match query.token.as_deref().filter(|t| !t.is_empty()) {
+        Some(token) => match overlay_tokens::verify_token(&state, token) {
+            Ok(claims) => Some(claims),
+            Err(e) => return (StatusCode::UNAUTHORIZED, e).into_response(),
+        },
+        None => None,
+    };
+    // Admin commands need access granted by the access middleware.
+    let admin = admin.is_some_and(|Extension(AdminAccess(admin))| admin);
+    let caller = Caller::new(admin, claims.as_ref());
+    ws.on_upgrade(move |socket| handle_socket(socket, state, preview, claims, caller))
 }
 
-async fn handle_socket(socket: WebSocket, state: SharedState) {
+async fn handle_socket(
+    socket: WebSocket,
+    state: SharedState,
+    preview: bool,
+    claims: Option<OverlayClaims>,
+    caller: Caller,
+) {
     let (mut sender, mut receiver) = socket.split();
+    // Command results go to this client only.
+    let (reply_tx, mut reply_rx) = mpsc::unbounded_channel::<String>();
     let mut rx = state.subscribe_ws();
+    let mut preview_rx = preview.then(overlay_preview::subscribe);
 
     // Send connection confirmation
     let client_id = uuid::Uuid::new_v4().to_string();
@@ -38,8 +92,44 @@ async fn handle_socket(socket: WebSocket, state: SharedState) {
     tracing::info!("WebSocket client connected: {}", client_id);
 
     // Forward broadcast messages to this client
+    let session_state = state.clone();
+    let mut claims = claims;
+    let session_bound = claims.as_ref().is_some_and(|c| c.session.is_some());
+    let mut session_check = tokio::time::interval(SESSION_CHECK_INTERVAL);
     let mut send_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
+        loop {
+            let msg = tokio::select! {
+                msg = rx.recv() => msg,
+                msg = recv_preview(&mut preview_rx) => msg,
+                Some(reply) = reply_rx.recv() => {
+                    if sender.send(Message::Text(reply.into())).await.is_err() {
+                        break;
+                    }
+                    continue;
+                }
+                _ = session_check.tick(), if session_bound => {
+                    let Some(c) = claims.as_mut() else { continue };
+                    match overlay_tokens::renew(&session_state, c) {
+                        Ok(Some(renewed)) => Ok(renewed),
+                        Ok(None) => continue,
+                        Err(reason) => {
+                            tracing::info!("Closing overlay WebSocket: {reason}");
+                            let frame = CloseFrame {
+                                code: close_code::POLICY,
+                                reason: reason.into(),
+                            };
+                            let _ = sender.send(Message::Close(Some(frame))).await;
+                            break;
+                        }
+                    }
+                }
+            };
+            let Ok(msg) = msg else {
+                break;
+            };
+            if !is_subscribed(claims.as_ref(), &msg) {
+                continue;
+            }
             if sender.send(Message::Text(msg.into())).await.is_err() {
                 break;
             }
@@ -53,7 +143,16 @@ async fn handle_socket(socket: WebSocket, state: SharedState) {
         while let Some(Ok(msg)) = receiver.next().await {
             match msg {
                 Message::Text(text) => {
-                    handle_client_message(&text, &ws_tx);
+                    if let Some(request) = ws_commands::parse(&text) {
+                        let (state, caller, reply_tx) =
+                            (state.clone(), caller.clone(), reply_tx.clone());
+                        tokio::spawn(async move {
+                            let result = ws_commands::execute(&state, &caller, request).await;
+                            let _ = reply_tx.send(result.to_string());
+                        });
+                    } else {
+                        handle_client_message(&text, &ws_tx);
+                    }
                 }
                 Message::Close(_) => break,
                 _ => {}
@@ -69,6 +168,24 @@ async fn handle_socket(socket: WebSocket, state: SharedState) {
     }
 }
 
+/// Whether a broadcast message matches the topics of the client's token.
+fn is_subscribed(claims: Option<&OverlayClaims>, msg: &str) -> bool {
+    let Some(claims) = claims.filter(|c| !c.topics.is_empty()) else {
+        return true;
+    };
+    overlay_tokens::message_type(msg).is_some_and(|t| claims.allows(&t))
+}
+
+/// Next preview-only message; never resolves for regular clients.
+async fn recv_preview(
+    rx: &mut Option<broadcast::Receiver<String>>,
+) -> Result<String, broadcast::error::RecvError> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Route incoming client messages.
 fn handle_client_message(text: &str, ws_tx: &tokio::sync::broadcast::Sender<String>) {
     // Try to parse as JSON to detect message type
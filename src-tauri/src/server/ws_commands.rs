@@ -0,0 +1,192 @@
+//! Commands sent by clients over the overlay WebSocket.
+//!
+//! A client sends
+//! `{ "type": "command", "data": { "id": "c1", "command": "lottery.draw", "args": {} } }`
+//! and only that client gets the answer:
+//! `{ "type": "command_result", "data": { "id": "c1", "ok": true, "result": {..} } }`
+//! (`"ok": false, "error": ".."` on failure). Commands run the same handlers
+//! as the REST endpoints, so the two behave identically.
+//!
+//! Clients the access policy lets use `/api` may run every command. Overlays
+//! connected with a signed token may run only the commands of their widget;
+//! anyone else may run none.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::notification;
+use crate::services::overlay_tokens::OverlayClaims;
+
+use super::api;
+
+/// Commands and the overlay widgets that may run them besides admin clients.
+const COMMANDS: &[(&str, &[&str])] = &[
+    ("lottery.start", &["present"]),
+    ("lottery.stop", &["present"]),
+    ("lottery.draw", &["present"]),
+    ("lottery.clear", &["present"]),
+    ("lottery.lock", &["present"]),
+    ("lottery.unlock", &["present"]),
+    ("overlay.effect", &["effects"]),
+    ("notification.dismiss", &[]),
+    ("print_queue.pause", &[]),
+    ("print_queue.resume", &[]),
+    ("tts.pause", &[]),
+    ("tts.resume", &[]),
+    ("tts.skip", &[]),
+    ("pre_show.start", &[]),
+    ("pre_show.stop", &[]),
+    ("panic", &[]),
+    ("irc.send", &[]),
+    ("irc.join", &[]),
+    ("irc.part", &[]),
+];
+
+/// Who is on the other end of a WebSocket.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Caller {
+    /// Allowed to use the admin routes.
+    Admin,
+    /// An overlay holding a token for this widget.
+    Widget(String),
+    Anonymous,
+}
+
+impl Caller {
+    pub fn new(admin: bool, claims: Option<&OverlayClaims>) -> Self {
+        match claims {
+            _ if admin => Self::Admin,
+            Some(claims) => Self::Widget(claims.widget.clone()),
+            None => Self::Anonymous,
+        }
+    }
+
+    fn may_run(&self, widgets: &[&str]) -> bool {
+        match self {
+            Self::Admin => true,
+            Self::Widget(widget) if widget == "all" => !widgets.is_empty(),
+            Self::Widget(widget) => widgets.contains(&widget.as_str()),
+            Self::Anonymous => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandRequest {
+    pub id: Value,
+    pub command: String,
+    pub args: Value,
+}
+
+/// The command in a client message; `None` for any other message.
+pub fn parse(text: &str) -> Option<CommandRequest> {
+    let msg: Value = serde_json::from_str(text).ok()?;
+    if msg["type"] != "command" {
+        return None;
+    }
+    let data = &msg["data"];
+    Some(CommandRequest {
+        id: data.get("id").cloned().unwrap_or(Value::Null),
+        command: data["command"].as_str().unwrap_or_default().to_string(),
+        args: data.get("args").cloned().unwrap_or_else(|| json!({})),
+    })
+}
+
+/// Body of a REST handler's response, or its error message.
+fn api_result(result: Result<Json<Value>, (StatusCode, Json<Value>)>) -> Result<Value, String> {
+    result.map(|Json(body)| body).map_err(|(_, Json(body))| {
+        body["error"]
+            .as_str()
+            .unwrap_or("Command failed")
+            .to_string()
+    })
+}
+
+async fn run(state: &SharedState, command: &str, args: Value) -> Result<Value, String> {
+    let s = || State(state.clone());
+    match command {
+        "lottery.start" => api_result(api::present::start_present(s()).await),
+        "lottery.stop" => api_result(api::present::stop_present(s()).await),
+        "lottery.draw" => api_result(api::present::draw_present(s()).await),
+        "lottery.clear" => api_result(api::present::clear_present(s()).await),
+        "lottery.lock" => api_result(api::present::lock_present(s()).await),
+        "lottery.unlock" => api_result(api::present::unlock_present(s()).await),
+        "overlay.effect" => api_result(api::overlay::trigger_effect(s(), Json(args)).await),
+        "notification.dismiss" => {
+            notification::queue::dismiss_current();
+            Ok(json!({ "success": true }))
+        }
+        "print_queue.pause" => api_result(api::printer::pause_queue().await),
+        "print_queue.resume" => api_result(api::printer::resume_queue().await),
+        "tts.pause" => Ok(api::tts::pause().await.0),
+        "tts.resume" => Ok(api::tts::resume().await.0),
+        "tts.skip" => Ok(api::tts::skip().await.0),
+        "pre_show.start" => api_result(api::pre_show::start(s(), Some(Json(args))).await),
+        "pre_show.stop" => Ok(api::pre_show::stop(s()).await.0),
+        "panic" => {
+            let source = args["source"].as_str().unwrap_or("websocket");
+            let body = Json(json!({ "source": source }));
+            let Json(result) = api::system::trigger_panic(s(), HeaderMap::new(), Some(body)).await;
+            Ok(result)
+        }
+        "irc.send" => api_result(api::irc::send(Json(args)).await),
+        "irc.join" => api_result(api::irc::join(s(), Json(args)).await),
+        "irc.part" => {
+            let channel = args["channel"].as_str().unwrap_or_default().to_string();
+            api_result(api::irc::part(s(), Path(channel)).await)
+        }
+        other => Err(format!("Unhandled command: {other}")),
+    }
+}
+
+/// Run `request` for `caller` and build the `command_result` message.
+pub async fn execute(state: &SharedState, caller: &Caller, request: CommandRequest) -> Value {
+    let result = match COMMANDS.iter().find(|(name, _)| *name == request.command) {
+        None => Err(format!("Unknown command: {}", request.command)),
+        Some((_, widgets)) if !caller.may_run(widgets) => {
+            tracing::debug!(command = %request.command, ?caller, "WebSocket command denied");
+            Err(format!("Not allowed: {}", request.command))
+        }
+        Some((name, _)) => {
+            tracing::info!(command = name, "WebSocket command");
+            run(state, name, request.args).await
+        }
+    };
+    let data = match result {
+        Ok(result) => json!({ "id": request.id, "ok": true, "result": result }),
+        Err(error) => json!({ "id": request.id, "ok": false, "error": error }),
+    };
+    json!({ "type": "command_result", "data": data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        let request = parse(
+            r#"{"type":"command","data":{"id":"c1","command":"lottery.draw","args":{"x":1}}}"#,
+        )
+        .unwrap();
+        assert_eq!(request.id, json!("c1"));
+        assert_eq!(request.command, "lottery.draw");
+        assert_eq!(request.args, json!({ "x": 1 }));
+        assert_eq!(parse(r#"{"type":"ping"}"#), None);
+        assert_eq!(parse("not json"), None);
+    }
+
+    #[test]
+    fn test_caller_permissions() {
+        let present = &["present"][..];
+        assert!(Caller::Admin.may_run(&[]));
+        assert!(Caller::Widget("present".into()).may_run(present));
+        assert!(!Caller::Widget("fax".into()).may_run(present));
+        assert!(Caller::Widget("all".into()).may_run(present));
+        assert!(!Caller::Widget("all".into()).may_run(&[]));
+        assert!(!Caller::Anonymous.may_run(present));
+    }
+}
@@ -0,0 +1,351 @@
+//! Ad-break automation.
+//!
+//! When an ad break starts — reported by `channel.ad_break.begin` or
+//! triggered by hand from the dashboard — the print queue and TTS are paused
+//! (unless they already were), overlays get an `ad_break` message with the
+//! end time for a countdown, and `AD_BREAK_CHAT_MESSAGE` is
+//! posted. With `AD_BREAK_PRINT_BRB` on, a BRB card is printed too; the
+//! pause then waits [`BRB_PRINT_GRACE`] so the card goes out first. When
+//! the break is over everything is resumed. A break that starts while
+//! another runs extends it.
+//!
+//! While the automation is on, [`run`] also follows the Helix ad schedule
+//! and sends overlays `ad_schedule` (next ad time, length and snoozes) for
+//! a countdown before the break; [`snooze`] pushes the next ad back.
+
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::RwLock;
+use twitch_client::api::AdSchedule;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::print_queue::PrintCategory;
+use crate::services::{helix, print_queue, print_render, tts, twitch_chat};
+
+/// Longest break accepted (Twitch allows up to 3 minutes).
+pub const MAX_DURATION_SECS: u64 = 600;
+
+pub const SOURCE_EVENTSUB: &str = "eventsub";
+pub const SOURCE_MANUAL: &str = "manual";
+
+/// How long pausing prints waits for the BRB card to be picked up.
+const BRB_PRINT_GRACE: Duration = Duration::from_secs(10);
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+
+struct AdBreakSettings {
+    enabled: bool,
+    pause_prints: bool,
+    chat_message: String,
+    end_chat_message: String,
+    print_brb: bool,
+    brb_text: String,
+}
+
+fn load_settings(state: &SharedState) -> AdBreakSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    AdBreakSettings {
+        enabled: get("AD_BREAK_AUTOMATION_ENABLED") == "true",
+        pause_prints: get("AD_BREAK_PAUSE_PRINTS") != "false",
+        chat_message: get("AD_BREAK_CHAT_MESSAGE"),
+        end_chat_message: get("AD_BREAK_END_CHAT_MESSAGE"),
+        print_brb: get("AD_BREAK_PRINT_BRB") == "true",
+        brb_text: get("AD_BREAK_BRB_TEXT"),
+    }
+}
+
+#[derive(Debug)]
+struct ActiveBreak {
+    /// Changes when the break is extended so stale end timers do nothing.
+    generation: u64,
+    source: &'static str,
+    started_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    /// Whether this break paused the print queue (and must resume it).
+    paused_prints: bool,
+    /// Whether this break paused TTS (and must resume it).
+    paused_tts: bool,
+}
+
+static ACTIVE: LazyLock<RwLock<Option<ActiveBreak>>> = LazyLock::new(|| RwLock::new(None));
+static SCHEDULE: LazyLock<RwLock<Option<AdSchedule>>> = LazyLock::new(|| RwLock::new(None));
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Snapshot returned by the API and broadcast to overlays.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdBreakStatus {
+    pub active: bool,
+    pub source: Option<String>,
+    pub started_at: Option<String>,
+    pub ends_at: Option<String>,
+    pub remaining_seconds: u64,
+    pub prints_paused: bool,
+}
+
+fn snapshot(active: Option<&ActiveBreak>) -> AdBreakStatus {
+    match active {
+        Some(b) => AdBreakStatus {
+            active: true,
+            source: Some(b.source.to_string()),
+            started_at: Some(b.started_at.to_rfc3339()),
+            ends_at: Some(b.ends_at.to_rfc3339()),
+            remaining_seconds: (b.ends_at - Utc::now()).num_seconds().max(0) as u64,
+            prints_paused: b.paused_prints,
+        },
+        None => AdBreakStatus {
+            active: false,
+            source: None,
+            started_at: None,
+            ends_at: None,
+            remaining_seconds: 0,
+            prints_paused: false,
+        },
+    }
+}
+
+pub async fn status() -> AdBreakStatus {
+    snapshot(ACTIVE.read().await.as_ref())
+}
+
+fn broadcast(state: &SharedState, status: &AdBreakStatus) {
+    let msg = json!({ "type": "ad_break", "data": status });
+    let _ = state.ws_sender().send(msg.to_string());
+}
+
+/// Fill `{duration}` (seconds) and `{minutes}` (rounded up) in a chat
+/// template.
+pub fn render_message(template: &str, duration_secs: u64) -> String {
+    template
+        .replace("{duration}", &duration_secs.to_string())
+        .replace("{minutes}", &duration_secs.div_ceil(60).to_string())
+}
+
+fn send_chat_in_background(state: &SharedState, message: String) {
+    if message.trim().is_empty() {
+        return;
+    }
+    let s = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = twitch_chat::send_chat(&s, &message).await {
+            tracing::warn!("Failed to send ad break message: {e}");
+        }
+    });
+}
+
+/// Handle `channel.ad_break.begin` when the automation is enabled.
+pub async fn on_ad_break_begin(state: &SharedState, duration_secs: u64) {
+    if !load_settings(state).enabled {
+        return;
+    }
+    start(state, duration_secs, SOURCE_EVENTSUB).await;
+}
+
+/// Start (or extend) an ad break of `duration_secs`.
+pub async fn start(state: &SharedState, duration_secs: u64, source: &'static str) -> AdBreakStatus {
+    let settings = load_settings(state);
+    let duration_secs = duration_secs.clamp(1, MAX_DURATION_SECS);
+    let now = Utc::now();
+    let ends_at = now + chrono::Duration::seconds(duration_secs as i64);
+    let generation = GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+
+    let (status, is_new, break_end) = {
+        let mut active = ACTIVE.write().await;
+        let is_new = active.is_none();
+        match active.as_mut() {
+            Some(current) => {
+                current.generation = generation;
+                current.ends_at = current.ends_at.max(ends_at);
+            }
+            None => {
+                let brb = settings.print_brb && print_brb(state, &settings, duration_secs).await;
+                let paused_prints = settings.pause_prints && !print_queue::is_paused().await;
+                if paused_prints && brb {
+                    tokio::spawn(pause_after_brb(now));
+                } else if paused_prints {
+                    print_queue::pause().await;
+                }
+                let paused_tts = !tts::is_paused();
+                if paused_tts {
+                    tts::pause();
+                }
+                *active = Some(ActiveBreak {
+                    generation,
+                    source,
+                    started_at: now,
+                    ends_at,
+                    paused_prints,
+                    paused_tts,
+                });
+            }
+        }
+        let break_end = active.as_ref().map_or(ends_at, |b| b.ends_at);
+        (snapshot(active.as_ref()), is_new, break_end)
+    };
+
+    tracing::info!(
+        source,
+        duration_secs,
+        extended = !is_new,
+        "Ad break started"
+    );
+    broadcast(state, &status);
+    if is_new {
+        send_chat_in_background(state, render_message(&settings.chat_message, duration_secs));
+    }
+
+    let s = state.clone();
+    let wait = (break_end - Utc::now()).to_std().unwrap_or_default();
+    tokio::spawn(async move {
+        tokio::time::sleep(wait).await;
+        finish(&s, Some(generation)).await;
+    });
+    status
+}
+
+/// Queue the BRB card; false when it could not be queued.
+async fn print_brb(state: &SharedState, settings: &AdBreakSettings, duration_secs: u64) -> bool {
+    let text = render_message(&settings.brb_text, duration_secs);
+    match print_render::print_titled(state, "BRB", "", &text, PrintCategory::Other).await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("Failed to print BRB card: {e}");
+            false
+        }
+    }
+}
+
+/// Pause prints for the break that started at `started_at` once the BRB
+/// card has had time to print, unless that break is already over.
+async fn pause_after_brb(started_at: DateTime<Utc>) {
+    tokio::time::sleep(BRB_PRINT_GRACE).await;
+    let active = ACTIVE.read().await;
+    if active.as_ref().is_some_and(|b| b.started_at == started_at) {
+        print_queue::pause().await;
+    }
+}
+
+/// End the running ad break and resume what it paused. Returns false when
+/// none is running.
+pub async fn end(state: &SharedState) -> bool {
+    finish(state, None).await
+}
+
+/// End the break, or with `generation` only if it was not extended since.
+async fn finish(state: &SharedState, generation: Option<u64>) -> bool {
+    let ended = ACTIVE
+        .write()
+        .await
+        .take_if(|b| generation.is_none_or(|g| b.generation == g));
+    let Some(ended) = ended else {
+        return false;
+    };
+    if ended.paused_prints {
+        print_queue::resume().await;
+    }
+    if ended.paused_tts {
+        tts::resume();
+    }
+    tracing::info!(source = ended.source, "Ad break ended");
+    broadcast(state, &snapshot(None));
+    send_chat_in_background(state, load_settings(state).end_chat_message);
+    true
+}
+
+/// Overlay payload for an ad schedule.
+fn schedule_json(schedule: &AdSchedule) -> serde_json::Value {
+    let now = Utc::now().timestamp();
+    json!({
+        "next_ad_at": schedule.next_ad_at,
+        "seconds_until_next": schedule.next_ad_at.map(|at| (at - now).max(0)),
+        "duration": schedule.duration,
+        "last_ad_at": schedule.last_ad_at,
+        "preroll_free_time": schedule.preroll_free_time,
+        "snooze_count": schedule.snooze_count,
+        "snooze_refresh_at": schedule.snooze_refresh_at,
+    })
+}
+
+/// The last known ad schedule, for the API.
+pub async fn schedule() -> Option<serde_json::Value> {
+    SCHEDULE.read().await.as_ref().map(schedule_json)
+}
+
+/// Remember `schedule` and tell overlays when it changed.
+async fn update_schedule(state: &SharedState, schedule: AdSchedule) {
+    let mut current = SCHEDULE.write().await;
+    if current.as_ref() == Some(&schedule) {
+        return;
+    }
+    let msg = json!({ "type": "ad_schedule", "data": schedule_json(&schedule) });
+    let _ = state.ws_sender().send(msg.to_string());
+    *current = Some(schedule);
+}
+
+/// Fetch the ad schedule from Helix.
+pub async fn refresh_schedule(state: &SharedState) -> Result<serde_json::Value, String> {
+    let ctx = helix::context(state).await?;
+    let schedule = ctx
+        .api
+        .get_ad_schedule(&ctx.token, &ctx.broadcaster_id)
+        .await
+        .map_err(|e| format!("Failed to get ad schedule: {e}"))?;
+    let data = schedule_json(&schedule);
+    update_schedule(state, schedule).await;
+    Ok(data)
+}
+
+/// Push the next ad back with one of the channel's snoozes.
+pub async fn snooze(state: &SharedState) -> Result<serde_json::Value, String> {
+    let ctx = helix::context(state).await?;
+    let snoozed = ctx
+        .api
+        .snooze_next_ad(&ctx.token, &ctx.broadcaster_id)
+        .await
+        .map_err(|e| format!("Failed to snooze ad: {e}"))?;
+    tracing::info!(
+        next_ad_at = snoozed.next_ad_at,
+        snooze_count = snoozed.snooze_count,
+        "Next ad snoozed"
+    );
+    // The snooze response lacks the break length; keep the known one.
+    let mut schedule = SCHEDULE.read().await.clone().unwrap_or_default();
+    schedule.next_ad_at = snoozed.next_ad_at;
+    schedule.snooze_count = snoozed.snooze_count;
+    schedule.snooze_refresh_at = snoozed.snooze_refresh_at;
+    let data = schedule_json(&schedule);
+    update_schedule(state, schedule).await;
+    Ok(data)
+}
+
+/// Background worker following the ad schedule while the automation is on.
+pub async fn run(state: SharedState) {
+    loop {
+        if load_settings(&state).enabled
+            && let Err(e) = refresh_schedule(&state).await
+        {
+            tracing::debug!("Ad schedule unavailable: {e}");
+        }
+        tokio::time::sleep(SCHEDULE_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_message() {
+        assert_eq!(
+            render_message("{duration}秒（約{minutes}分）", 90),
+            "90秒（約2分）"
+        );
+        assert_eq!(render_message("{minutes} min", 180), "3 min");
+        assert_eq!(render_message("back soon", 60), "back soon");
+    }
+}
@@ -0,0 +1,228 @@
+//! Idle/AFK detection.
+//!
+//! The host counts as active while they chat in their own channel, use
+//! hotkey tools that call `POST /api/overlay/afk/activity`, or (optionally)
+//! touch the keyboard/mouse according to the OS idle timer. After
+//! `AFK_TIMEOUT_MINUTES` without activity the overlay switches to the AFK
+//! state; the next activity switches it back. A manual override from
+//! `POST /api/overlay/afk` wins over automatic detection until cleared.
+
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::RwLock;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::twitch_chat;
+
+/// How often the monitor re-evaluates idle time.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+struct AfkRuntime {
+    last_activity: Instant,
+    is_afk: bool,
+    manual: Option<bool>,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+static AFK_RUNTIME: LazyLock<RwLock<AfkRuntime>> = LazyLock::new(|| {
+    RwLock::new(AfkRuntime {
+        last_activity: Instant::now(),
+        is_afk: false,
+        manual: None,
+        since: None,
+    })
+});
+
+/// Snapshot returned by the API and broadcast to overlays.
+#[derive(Debug, Clone, Serialize)]
+pub struct AfkStatus {
+    pub is_afk: bool,
+    pub manual: Option<bool>,
+    pub since: Option<String>,
+    pub idle_secs: u64,
+    pub text: String,
+}
+
+struct AfkSettings {
+    enabled: bool,
+    timeout: Duration,
+    use_os_idle: bool,
+    announce: bool,
+    message: String,
+    return_message: String,
+    text: String,
+}
+
+fn load_settings(state: &SharedState) -> AfkSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    AfkSettings {
+        enabled: get("AFK_ENABLED") == "true",
+        timeout: Duration::from_secs(get("AFK_TIMEOUT_MINUTES").parse::<u64>().unwrap_or(10) * 60),
+        use_os_idle: get("AFK_USE_OS_IDLE") == "true",
+        announce: get("AFK_ANNOUNCE_ENABLED") == "true",
+        message: get("AFK_MESSAGE"),
+        return_message: get("AFK_RETURN_MESSAGE"),
+        text: get("AFK_OVERLAY_TEXT"),
+    }
+}
+
+/// Background loop that flips the AFK state based on idle time.
+pub async fn run_monitor(state: SharedState) {
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let settings = load_settings(&state);
+        if !settings.enabled {
+            continue;
+        }
+
+        let (app_idle, manual, is_afk) = {
+            let rt = AFK_RUNTIME.read().await;
+            (rt.last_activity.elapsed(), rt.manual, rt.is_afk)
+        };
+        if manual.is_some() {
+            continue;
+        }
+
+        let os_idle = if settings.use_os_idle {
+            os_idle_time().await
+        } else {
+            None
+        };
+        let idle = os_idle.map_or(app_idle, |os| app_idle.min(os));
+
+        if !is_afk && idle >= settings.timeout {
+            tracing::info!(idle_secs = idle.as_secs(), "Host idle, entering AFK state");
+            apply(&state, true, &settings).await;
+        } else if is_afk && idle < settings.timeout {
+            tracing::info!("Host activity detected, leaving AFK state");
+            apply(&state, false, &settings).await;
+        }
+    }
+}
+
+/// Record host activity (own chat message, hotkey ping, dashboard action).
+pub async fn record_activity(state: &SharedState) {
+    let leave = {
+        let mut rt = AFK_RUNTIME.write().await;
+        rt.last_activity = Instant::now();
+        rt.is_afk && rt.manual.is_none()
+    };
+    if leave {
+        let settings = load_settings(state);
+        if settings.enabled {
+            apply(state, false, &settings).await;
+        }
+    }
+}
+
+/// Force the AFK state on/off, or `None` to return to automatic detection.
+pub async fn set_manual(state: &SharedState, afk: Option<bool>) -> AfkStatus {
+    let settings = load_settings(state);
+    let current = {
+        let mut rt = AFK_RUNTIME.write().await;
+        rt.manual = afk;
+        if afk == Some(false) || afk.is_none() {
+            rt.last_activity = Instant::now();
+        }
+        rt.is_afk
+    };
+    let target = afk.unwrap_or(false);
+    if target != current {
+        apply(state, target, &settings).await;
+    } else {
+        broadcast(state, &settings.text).await;
+    }
+    status(&settings.text).await
+}
+
+/// Current AFK status.
+pub async fn current_status(state: &SharedState) -> AfkStatus {
+    status(&load_settings(state).text).await
+}
+
+async fn apply(state: &SharedState, afk: bool, settings: &AfkSettings) {
+    {
+        let mut rt = AFK_RUNTIME.write().await;
+        if rt.is_afk == afk {
+            return;
+        }
+        rt.is_afk = afk;
+        rt.since = afk.then(chrono::Utc::now);
+    }
+    broadcast(state, &settings.text).await;
+
+    if settings.announce {
+        let text = if afk {
+            &settings.message
+        } else {
+            &settings.return_message
+        };
+        if !text.trim().is_empty() {
+            if let Err(e) = twitch_chat::send_chat(state, text).await {
+                tracing::warn!("Failed to send AFK announcement: {e}");
+            }
+        }
+    }
+}
+
+async fn status(text: &str) -> AfkStatus {
+    let rt = AFK_RUNTIME.read().await;
+    AfkStatus {
+        is_afk: rt.is_afk,
+        manual: rt.manual,
+        since: rt.since.map(|t| t.to_rfc3339()),
+        idle_secs: rt.last_activity.elapsed().as_secs(),
+        text: text.to_string(),
+    }
+}
+
+async fn broadcast(state: &SharedState, text: &str) {
+    let status = status(text).await;
+    let msg = json!({ "type": "afk_state", "data": status });
+    let _ = state.ws_sender().send(msg.to_string());
+}
+
+/// Time since the last keyboard/mouse input, if the platform exposes it.
+async fn os_idle_time() -> Option<Duration> {
+    #[cfg(target_os = "macos")]
+    {
+        let out = tokio::process::Command::new("ioreg")
+            .args(["-c", "IOHIDSystem", "-d", "4"])
+            .output()
+            .await
+            .ok()?;
+        let text = String::from_utf8_lossy(&out.stdout);
+        let nanos = text
+            .lines()
+            .find(|l| l.contains("\"HIDIdleTime\""))?
+            .rsplit('=')
+            .next()?
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(Duration::from_nanos(nanos))
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let out = tokio::process::Command::new("xprintidle")
+            .output()
+            .await
+            .ok()?;
+        let millis = String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(Duration::from_millis(millis))
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
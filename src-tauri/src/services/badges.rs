@@ -0,0 +1,183 @@
+//! Chat badge cache.
+//!
+//! Badge keys (`set_id/id`, e.g. `subscriber/24`) are resolved to image
+//! URLs from the global and channel badge lists, which are fetched from
+//! Helix on first use and refreshed every [`REFRESH_INTERVAL`]; channel
+//! badges override global ones. Images go through the on-disk image
+//! cache, so a badge is downloaded once.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use image::DynamicImage;
+use serde_json::Value;
+use tokio::sync::RwLock;
+use twitch_client::api::ChatBadgeSet;
+
+use crate::app::SharedState;
+use crate::services::{emote_images, helix};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+/// Wait before retrying after a failed fetch.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Default)]
+struct BadgeCache {
+    /// `set_id/id` → 2x image URL.
+    urls: HashMap<String, String>,
+    fetched_at: Option<Instant>,
+    failed_at: Option<Instant>,
+}
+
+static CACHE: LazyLock<RwLock<BadgeCache>> = LazyLock::new(|| RwLock::new(BadgeCache::default()));
+
+/// Cache key of a badge.
+pub fn key(set_id: &str, id: &str) -> String {
+    format!("{set_id}/{id}")
+}
+
+/// Badge keys of an EventSub `badges` list.
+pub fn keys_of(badges: &Value) -> Vec<(String, String)> {
+    badges
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|b| {
+            let set_id = b["set_id"].as_str()?;
+            let id = b["id"].as_str()?;
+            Some((set_id.to_string(), id.to_string()))
+        })
+        .collect()
+}
+
+fn index(global: Vec<ChatBadgeSet>, channel: Vec<ChatBadgeSet>) -> HashMap<String, String> {
+    let mut urls = HashMap::new();
+    for set in global.into_iter().chain(channel) {
+        for version in set.versions {
+            urls.insert(key(&set.set_id, &version.id), version.image_url_2x);
+        }
+    }
+    urls
+}
+
+async fn refresh(state: &SharedState) -> Result<HashMap<String, String>, String> {
+    let ctx = helix::context(state).await?;
+    let global = ctx
+        .api
+        .get_global_chat_badges(&ctx.token)
+        .await
+        .map_err(|e| e.to_string())?;
+    let channel = ctx
+        .api
+        .get_channel_chat_badges(&ctx.token, &ctx.broadcaster_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to fetch channel badges: {e}");
+            Vec::new()
+        });
+    Ok(index(global, channel))
+}
+
+/// Image URL of a badge, refreshing the badge lists when stale.
+pub async fn resolve_url(state: &SharedState, set_id: &str, id: &str) -> Option<String> {
+    let key = key(set_id, id);
+    {
+        let cache = CACHE.read().await;
+        let fresh = cache
+            .fetched_at
+            .is_some_and(|t| t.elapsed() < REFRESH_INTERVAL);
+        let backing_off = cache
+            .failed_at
+            .is_some_and(|t| t.elapsed() < RETRY_INTERVAL);
+        if fresh || backing_off {
+            return cache.urls.get(&key).cloned();
+        }
+    }
+
+    let mut cache = CACHE.write().await;
+    // Another task may have refreshed (or failed to) while we waited.
+    let settled = cache
+        .fetched_at
+        .is_some_and(|t| t.elapsed() < REFRESH_INTERVAL)
+        || cache
+            .failed_at
+            .is_some_and(|t| t.elapsed() < RETRY_INTERVAL);
+    if !settled {
+        match refresh(state).await {
+            Ok(urls) => {
+                tracing::debug!(count = urls.len(), "Chat badge cache refreshed");
+                cache.urls = urls;
+                cache.fetched_at = Some(Instant::now());
+                cache.failed_at = None;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to refresh chat badges: {e}");
+                cache.failed_at = Some(Instant::now());
+            }
+        }
+    }
+    cache.urls.get(&key).cloned()
+}
+
+/// Badge image, or an error when the key is unknown or the download fails.
+pub async fn fetch_badge(
+    state: &SharedState,
+    set_id: &str,
+    id: &str,
+) -> Result<DynamicImage, String> {
+    let url = resolve_url(state, set_id, id)
+        .await
+        .ok_or_else(|| format!("Unknown badge {}", key(set_id, id)))?;
+    emote_images::fetch_image(state, &url).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use twitch_client::api::ChatBadgeVersion;
+
+    fn set(set_id: &str, id: &str, url: &str) -> ChatBadgeSet {
+        ChatBadgeSet {
+            set_id: set_id.into(),
+            versions: vec![ChatBadgeVersion {
+                id: id.into(),
+                image_url_1x: String::new(),
+                image_url_2x: url.into(),
+                image_url_4x: String::new(),
+                title: String::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_index_prefers_channel_badges() {
+        let urls = index(
+            vec![
+                set("subscriber", "0", "global"),
+                set("moderator", "1", "mod"),
+            ],
+            vec![set("subscriber", "0", "channel")],
+        );
+        assert_eq!(urls["subscriber/0"], "channel");
+        assert_eq!(urls["moderator/1"], "mod");
+    }
+
+    #[test]
+    fn test_keys_of() {
+        let badges = json!([
+            { "set_id": "subscriber", "id": "24", "info": "25" },
+            { "set_id": "moderator", "id": "1", "info": "" },
+            { "id": "broken" },
+        ]);
+        assert_eq!(
+            keys_of(&badges),
+            vec![
+                ("subscriber".to_string(), "24".to_string()),
+                ("moderator".to_string(), "1".to_string()),
+            ]
+        );
+        assert!(keys_of(&json!(null)).is_empty());
+    }
+}
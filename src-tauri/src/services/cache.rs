@@ -8,6 +8,8 @@ use overlay_db::cache::{CacheEntry, CacheStats};
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 
+use crate::config::{SettingsManager, portable};
+
 const DEFAULT_EXPIRY_DAYS: i64 = 7;
 const DEFAULT_MAX_SIZE_MB: i64 = 100;
 
@@ -17,6 +19,8 @@ pub enum CacheError {
     Io(#[from] std::io::Error),
     #[error("Database error: {0}")]
     Db(#[from] overlay_db::DbError),
+    #[error("Settings error: {0}")]
+    Settings(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +57,19 @@ impl CacheService {
         self.data_dir.join("cache")
     }
 
+    /// File of `entry` on disk.
+    fn path_of(&self, entry: &CacheEntry) -> PathBuf {
+        portable::resolve(&self.data_dir, &entry.file_path)
+    }
+
+    /// `entry` with its file path on disk.
+    fn resolve(&self, entry: CacheEntry) -> CacheEntry {
+        CacheEntry {
+            file_path: self.path_of(&entry).to_string_lossy().into_owned(),
+            ..entry
+        }
+    }
+
     fn ensure_dir(&self) -> Result<(), CacheError> {
         std::fs::create_dir_all(self.cache_dir())?;
         Ok(())
@@ -71,13 +88,14 @@ impl CacheService {
         let file_path = self.cache_dir().join(&url_hash);
         std::fs::write(&file_path, data)?;
 
-        let path_str = file_path.to_string_lossy().into_owned();
+        let path_str = portable::to_stored(&self.data_dir, &file_path);
         self.db
             .add_cache_entry(&url_hash, url, &path_str, data.len() as i64)?;
 
         let entry = self
             .db
             .get_cache_entry(&url_hash)?
+            .map(|e| self.resolve(e))
             .ok_or_else(|| CacheError::Db(overlay_db::DbError::NotFound("just inserted".into())))?;
 
         tracing::debug!(url_hash = %url_hash, "Cache entry added");
@@ -91,7 +109,7 @@ impl CacheService {
         if entry.is_some() {
             let _ = self.db.touch_cache_entry(&url_hash);
         }
-        Ok(entry)
+        Ok(entry.map(|e| self.resolve(e)))
     }
 
     pub fn get_settings(&self) -> CacheSettings {
@@ -115,20 +133,16 @@ impl CacheService {
     }
 
     pub fn update_settings(&self, s: &CacheSettings) -> Result<(), CacheError> {
-        self.db
-            .set_setting("cache_expiry_days", &s.expiry_days.to_string(), "cache")?;
-        self.db
-            .set_setting("cache_max_size_mb", &s.max_size_mb.to_string(), "cache")?;
-        self.db.set_setting(
-            "cache_cleanup_enabled",
-            &s.cleanup_enabled.to_string(),
-            "cache",
-        )?;
-        self.db.set_setting(
-            "cache_cleanup_on_start",
-            &s.cleanup_on_start.to_string(),
-            "cache",
-        )?;
+        let sm = SettingsManager::new(self.db.clone());
+        for (key, value) in [
+            ("cache_expiry_days", s.expiry_days.to_string()),
+            ("cache_max_size_mb", s.max_size_mb.to_string()),
+            ("cache_cleanup_enabled", s.cleanup_enabled.to_string()),
+            ("cache_cleanup_on_start", s.cleanup_on_start.to_string()),
+        ] {
+            sm.set_raw(key, &value, "cache")
+                .map_err(|e| CacheError::Settings(e.to_string()))?;
+        }
         Ok(())
     }
 
@@ -150,7 +164,7 @@ impl CacheService {
             {
                 let age = now.naive_utc() - created;
                 if age.num_seconds() > expiry_secs {
-                    let _ = std::fs::remove_file(&entry.file_path);
+                    let _ = std::fs::remove_file(self.path_of(entry));
                     let _ = self.db.delete_cache_entry(&entry.url_hash);
                     deleted += 1;
                 }
@@ -180,7 +194,7 @@ impl CacheService {
             if to_free <= 0 {
                 break;
             }
-            let _ = std::fs::remove_file(&entry.file_path);
+            let _ = std::fs::remove_file(self.path_of(entry));
             let _ = self.db.delete_cache_entry(&entry.url_hash);
             to_free -= entry.file_size;
             deleted += 1;
@@ -193,7 +207,7 @@ impl CacheService {
     pub fn clear_all(&self) -> Result<(), CacheError> {
         let entries = self.db.get_all_cache_entries()?;
         for entry in &entries {
-            let _ = std::fs::remove_file(&entry.file_path);
+            let _ = std::fs::remove_file(self.path_of(entry));
         }
         self.db.clear_all_cache_entries()?;
         tracing::info!("All cache cleared");
@@ -0,0 +1,266 @@
+//! Custom chat commands.
+//!
+//! A chat message whose first word is a stored trigger (`!discord`) is
+//! answered with the command's response. Responses expand the placeholders
+//! in [`VARIABLES`]; `{uptime}`, `{game}` and `{title}` come from Helix and
+//! are only looked up when used. A command answers chatters at or above its
+//! permission level and then stays quiet for `cooldown_secs`, except for
+//! moderators and the broadcaster.
+
+use overlay_db::chat_commands::ChatCommand;
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::str_field;
+use crate::services::{consent, helix, lottery_claims, print_vote, quotes, twitch_chat};
+
+/// Permission levels, lowest first.
+pub const PERMISSIONS: &[&str] = &["everyone", "subscriber", "vip", "moderator", "broadcaster"];
+
+/// Placeholders a response may use.
+pub const VARIABLES: &[&str] = &[
+    "user", "login", "args", "count", "channel", "uptime", "game", "title",
+];
+
+/// Twitch rejects longer chat messages.
+const MAX_REPLY_CHARS: usize = 500;
+
+/// Triggers (lowercase) handled elsewhere: quotes, viewer consent, the
+/// lottery claim command and the print vote keyword, the last two as
+/// currently configured.
+pub fn built_in(state: &SharedState) -> Vec<String> {
+    [
+        quotes::COMMAND.to_string(),
+        consent::OPT_IN.to_string(),
+        consent::OPT_OUT.to_string(),
+        lottery_claims::claim_command(state),
+        print_vote::keyword(state),
+    ]
+    .into_iter()
+    .map(|trigger| trigger.trim().to_lowercase())
+    .collect()
+}
+
+/// Rank of a permission level; unknown levels rank as broadcaster-only.
+pub fn permission_rank(permission: &str) -> usize {
+    PERMISSIONS
+        .iter()
+        .position(|p| *p == permission)
+        .unwrap_or(PERMISSIONS.len() - 1)
+}
+
+/// Rank of a chatter's highest role, from their EventSub badges.
+pub fn chatter_rank(badges: &Value) -> usize {
+    let sets: Vec<&str> = badges
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|b| b["set_id"].as_str())
+        .collect();
+    let role = ["broadcaster", "moderator", "vip", "subscriber"]
+        .into_iter()
+        .find(|role| sets.contains(role) || (*role == "subscriber" && sets.contains(&"founder")))
+        .unwrap_or("everyone");
+    permission_rank(role)
+}
+
+/// Trigger (lowercase) and arguments of a message that starts with a
+/// command prefix.
+pub fn split_command(text: &str) -> Option<(String, &str)> {
+    let text = text.trim();
+    if !text.starts_with(|c: char| c.is_ascii_punctuation()) {
+        return None;
+    }
+    let (trigger, args) = text
+        .split_once(char::is_whitespace)
+        .map_or((text, ""), |(t, a)| (t, a.trim()));
+    Some((trigger.to_lowercase(), args))
+}
+
+/// Whether a chatter of `rank` may use `command` at `now`.
+pub fn may_use(command: &ChatCommand, rank: usize, now: i64) -> bool {
+    if !command.enabled || rank < permission_rank(&command.permission) {
+        return false;
+    }
+    rank >= permission_rank("moderator")
+        || command
+            .last_used_at
+            .is_none_or(|at| now - at >= command.cooldown_secs)
+}
+
+/// Replace `{name}` placeholders with `values`; unknown ones are kept.
+pub fn render(template: &str, values: &[(&str, String)]) -> String {
+    let mut out = template.to_string();
+    for (name, value) in values {
+        out = out.replace(&format!("{{{name}}}"), value);
+    }
+    out.chars().take(MAX_REPLY_CHARS).collect()
+}
+
+/// `1h 05m`, or `12m` under an hour.
+pub fn format_uptime(secs: i64) -> String {
+    let minutes = secs.max(0) / 60;
+    match minutes / 60 {
+        0 => format!("{minutes}m"),
+        hours => format!("{hours}h {:02}m", minutes % 60),
+    }
+}
+
+fn uses(template: &str, name: &str) -> bool {
+    template.contains(&format!("{{{name}}}"))
+}
+
+/// `{uptime}`, `{game}` and `{title}` as far as `response` uses them.
+async fn helix_values(state: &SharedState, response: &str) -> Vec<(&'static str, String)> {
+    let mut values = Vec::new();
+    let wants_channel = uses(response, "game") || uses(response, "title");
+    if !uses(response, "uptime") && !wants_channel {
+        return values;
+    }
+    let ctx = match helix::context(state).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            tracing::debug!("Chat command variables unavailable: {e}");
+            return values;
+        }
+    };
+    if uses(response, "uptime") {
+        let started = match ctx
+            .api
+            .get_stream_info(&ctx.token, &ctx.broadcaster_id)
+            .await
+        {
+            Ok(status) => status
+                .info
+                .and_then(|info| chrono::DateTime::parse_from_rfc3339(&info.started_at).ok()),
+            Err(e) => {
+                tracing::debug!("Failed to get stream info for {{uptime}}: {e}");
+                None
+            }
+        };
+        let uptime = started.map_or_else(
+            || "offline".to_string(),
+            |t| format_uptime(chrono::Utc::now().timestamp() - t.timestamp()),
+        );
+        values.push(("uptime", uptime));
+    }
+    if wants_channel {
+        match ctx
+            .api
+            .get_channel_information(&ctx.token, &ctx.broadcaster_id)
+            .await
+        {
+            Ok(Some(info)) => {
+                values.push(("game", info.game_name));
+                values.push(("title", info.title));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::debug!("Failed to get channel info for chat command: {e}"),
+        }
+    }
+    values
+}
+
+fn load_enabled(state: &SharedState) -> bool {
+    SettingsManager::new(state.db().clone())
+        .get_setting("CHAT_COMMANDS_ENABLED")
+        .unwrap_or_default()
+        == "true"
+}
+
+/// Answer a chat message if it is a custom command (replies in the
+/// background).
+pub fn handle_chat_message(state: &SharedState, payload: &Value) {
+    let text = str_field(payload, &["message", "text"]);
+    let Some((trigger, args)) = split_command(&text) else {
+        return;
+    };
+    if !load_enabled(state) {
+        return;
+    }
+    let command = match state.db().get_chat_command_by_trigger(&trigger) {
+        Ok(Some(command)) => command,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Failed to look up chat command: {e}");
+            return;
+        }
+    };
+    let now = chrono::Utc::now().timestamp();
+    if !may_use(&command, chatter_rank(&payload["badges"]), now) {
+        return;
+    }
+    // Marked before replying so messages arriving meanwhile see the cooldown.
+    if let Err(e) = state.db().mark_chat_command_used(command.id, now) {
+        tracing::warn!("Failed to record chat command use: {e}");
+    }
+
+    let mut values = vec![
+        ("user", str_field(payload, &["chatter_user_name"])),
+        ("login", str_field(payload, &["chatter_user_login"])),
+        ("args", args.to_string()),
+        ("count", (command.use_count + 1).to_string()),
+        ("channel", str_field(payload, &["broadcaster_user_login"])),
+    ];
+    let s = state.clone();
+    tokio::spawn(async move {
+        values.extend(helix_values(&s, &command.response).await);
+        let reply = render(&command.response, &values);
+        if let Err(e) = twitch_chat::send_chat(&s, &reply).await {
+            tracing::warn!(trigger = %command.trigger, "Failed to send chat command reply: {e}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn command(permission: &str, last_used_at: Option<i64>) -> ChatCommand {
+        ChatCommand {
+            id: 1,
+            trigger: "!discord".into(),
+            response: "{user}: https://example.com".into(),
+            cooldown_secs: 30,
+            permission: permission.into(),
+            enabled: true,
+            use_count: 0,
+            last_used_at,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_permissions_and_cooldown() {
+        let viewer = chatter_rank(&json!([]));
+        let founder = chatter_rank(&json!([{ "set_id": "founder" }]));
+        let moderator =
+            chatter_rank(&json!([{ "set_id": "subscriber" }, { "set_id": "moderator" }]));
+        assert_eq!(founder, permission_rank("subscriber"));
+
+        assert!(may_use(&command("everyone", None), viewer, 100));
+        assert!(!may_use(&command("subscriber", None), viewer, 100));
+        assert!(may_use(&command("subscriber", None), founder, 100));
+        assert!(!may_use(&command("everyone", Some(90)), viewer, 100));
+        assert!(may_use(&command("everyone", Some(70)), viewer, 100));
+        assert!(may_use(&command("everyone", Some(90)), moderator, 100));
+        assert!(!may_use(&command("unknown", None), moderator, 100));
+    }
+
+    #[test]
+    fn test_split_and_render() {
+        assert_eq!(
+            split_command("  !SO  @alice "),
+            Some(("!so".to_string(), "@alice"))
+        );
+        assert_eq!(split_command("hello !so"), None);
+
+        let values = [("user", "Alice".to_string()), ("args", String::new())];
+        assert_eq!(render("hi {user}{args} {game}", &values), "hi Alice {game}");
+        assert_eq!(format_uptime(59), "0m");
+        assert_eq!(format_uptime(3_900), "1h 05m");
+    }
+}
@@ -0,0 +1,339 @@
+//! Word filter enforcement on incoming chat.
+//!
+//! Messages are checked against the word lists of `WORD_FILTER_LANGUAGES`
+//! (entries below `WORD_FILTER_MIN_SEVERITY` are ignored) and the regular
+//! expressions in `WORD_FILTER_PATTERNS`. A match is handled per
+//! `WORD_FILTER_ACTION`:
+//!
+//! - `flag`: the message is shown as usual but marked `flagged`
+//! - `redact`: matched terms are masked with `*` on overlays
+//! - `timeout`: redacted, and the chatter is timed out through Helix for
+//!   `WORD_FILTER_TIMEOUT_SECONDS`
+//!
+//! Moderators and the broadcaster are never filtered. Every match is
+//! stored in `moderation_events` with the original text and sent to
+//! overlays as `moderation_event`. Redacted messages are masked everywhere
+//! else too: chat history, notifications, prints and the chat features
+//! (commands, quotes, print votes, first chat); they are not read aloud.
+
+use std::sync::{Arc, LazyLock, Mutex};
+
+use overlay_db::moderation::ModerationEvent;
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
+use word_filter::WordMatcher;
+use word_filter::matcher::severity_rank;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::{send_ws, str_field};
+use crate::services::chat_commands::{chatter_rank, permission_rank};
+use crate::services::helix;
+
+/// Values of `WORD_FILTER_ACTION`.
+pub const ACTIONS: &[&str] = &["off", "flag", "redact", "timeout"];
+
+/// Compiled `WORD_FILTER_PATTERNS`, reused while the setting is unchanged.
+static PATTERNS: LazyLock<Mutex<(String, Vec<Regex>)>> =
+    LazyLock::new(|| Mutex::new((String::new(), Vec::new())));
+
+/// One case-insensitive regular expression per non-empty line.
+pub fn parse_patterns(value: &str) -> Result<Vec<Regex>, String> {
+    value
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            RegexBuilder::new(l)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| format!("invalid pattern '{l}': {e}"))
+        })
+        .collect()
+}
+
+fn patterns(value: &str) -> Vec<Regex> {
+    let mut cached = PATTERNS.lock().unwrap_or_else(|e| e.into_inner());
+    if cached.0 != value {
+        let compiled = parse_patterns(value).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring word filter patterns: {e}");
+            Vec::new()
+        });
+        *cached = (value.to_string(), compiled);
+    }
+    cached.1.clone()
+}
+
+/// Word lists and patterns a message is checked against.
+#[derive(Debug, Clone)]
+pub struct Filter {
+    matcher: Arc<WordMatcher>,
+    patterns: Vec<Regex>,
+    /// Word list matches of lower severity are ignored.
+    min_severity: usize,
+}
+
+impl Filter {
+    pub fn new(matcher: Arc<WordMatcher>, patterns: Vec<Regex>, min_severity: usize) -> Self {
+        Self {
+            matcher,
+            patterns,
+            min_severity,
+        }
+    }
+
+    /// Byte ranges of `text` that are filtered: word list matches of at
+    /// least the minimum severity and pattern matches, sorted by start.
+    pub fn find(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut ranges: Vec<(usize, usize)> = self
+            .matcher
+            .find(text)
+            .into_iter()
+            .filter(|m| m.severity >= self.min_severity)
+            .map(|m| (m.start, m.end))
+            .collect();
+        for pattern in &self.patterns {
+            ranges.extend(
+                pattern
+                    .find_iter(text)
+                    .filter(|m| !m.is_empty())
+                    .map(|m| (m.start(), m.end())),
+            );
+        }
+        ranges.sort_unstable();
+        ranges
+    }
+
+    /// `text` with filtered ranges masked by `*`, one per character.
+    pub fn redact(&self, text: &str) -> String {
+        let ranges = self.find(text);
+        text.char_indices()
+            .map(|(i, c)| {
+                let masked =
+                    !c.is_whitespace() && ranges.iter().any(|&(start, end)| start <= i && i < end);
+                if masked { '*' } else { c }
+            })
+            .collect()
+    }
+}
+
+/// A chat message the filter caught.
+#[derive(Debug, Clone)]
+pub struct Verdict {
+    pub action: String,
+    pub matched: Vec<String>,
+    filter: Filter,
+    timeout_secs: u32,
+}
+
+impl Verdict {
+    /// Whether the message is masked (every action but `flag`).
+    pub fn redacts(&self) -> bool {
+        self.action != "flag"
+    }
+
+    /// Mask the text of `type: text` fragments.
+    fn redact_fragments(&self, fragments: &mut Value) {
+        for fragment in fragments.as_array_mut().into_iter().flatten() {
+            if fragment["type"] != "text" {
+                continue;
+            }
+            if let Some(text) = fragment["text"].as_str() {
+                fragment["text"] = self.filter.redact(text).into();
+            }
+        }
+    }
+
+    /// A `channel.chat.message` payload with the message text and text
+    /// fragments masked.
+    pub fn redact_payload(&self, payload: &Value) -> Value {
+        let mut out = payload.clone();
+        let message = &mut out["message"];
+        if let Some(text) = message["text"].as_str() {
+            message["text"] = self.filter.redact(text).into();
+        }
+        self.redact_fragments(&mut message["fragments"]);
+        out
+    }
+
+    /// Mark the overlay `chat-message` payload, masking the message text
+    /// and text fragments unless the action only flags.
+    pub fn apply(&self, ws_payload: &mut Value) {
+        ws_payload["flagged"] = true.into();
+        ws_payload["filterAction"] = self.action.as_str().into();
+        if !self.redacts() {
+            return;
+        }
+        if let Some(text) = ws_payload["message"].as_str() {
+            ws_payload["message"] = self.filter.redact(text).into();
+        }
+        self.redact_fragments(&mut ws_payload["fragments"]);
+    }
+}
+
+/// Check a `channel.chat.message` payload. `None` when the filter is off,
+/// the chatter is a moderator or nothing matched.
+pub fn check(state: &SharedState, payload: &Value) -> Option<Verdict> {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let action = get("WORD_FILTER_ACTION");
+    if action.is_empty() || action == "off" {
+        return None;
+    }
+    if chatter_rank(&payload["badges"]) >= permission_rank("moderator") {
+        return None;
+    }
+    let text = str_field(payload, &["message", "text"]);
+    let languages = get("WORD_FILTER_LANGUAGES");
+    let languages: Vec<&str> = languages
+        .split(',')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let matcher = match WordMatcher::cached(state.db(), &languages) {
+        Ok(matcher) => matcher,
+        Err(e) => {
+            tracing::warn!("Failed to load word filter for chat: {e}");
+            return None;
+        }
+    };
+    let filter = Filter::new(
+        matcher,
+        patterns(&get("WORD_FILTER_PATTERNS")),
+        severity_rank(&get("WORD_FILTER_MIN_SEVERITY")),
+    );
+    let mut matched: Vec<String> = filter
+        .find(&text)
+        .into_iter()
+        .map(|(start, end)| text[start..end].to_string())
+        .collect();
+    if matched.is_empty() {
+        return None;
+    }
+    matched.sort();
+    matched.dedup();
+    Some(Verdict {
+        action,
+        matched,
+        filter,
+        timeout_secs: get("WORD_FILTER_TIMEOUT_SECONDS").parse().unwrap_or(60),
+    })
+}
+
+/// Record `verdict` and time the chatter out if the action says so.
+pub fn enforce(state: &SharedState, payload: &Value, verdict: Verdict) {
+    let timeout = verdict.action == "timeout";
+    let mut event = ModerationEvent {
+        id: 0,
+        message_id: str_field(payload, &["message_id"]),
+        user_id: str_field(payload, &["chatter_user_id"]),
+        user_login: str_field(payload, &["chatter_user_login"]),
+        user_name: str_field(payload, &["chatter_user_name"]),
+        message: str_field(payload, &["message", "text"]),
+        matched: verdict.matched,
+        action: verdict.action,
+        duration_secs: timeout.then_some(i64::from(verdict.timeout_secs)),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    match state.db().add_moderation_event(&event) {
+        Ok(id) => event.id = id,
+        Err(e) => tracing::warn!("Failed to record moderation event: {e}"),
+    }
+    send_ws(state, "moderation_event", &event);
+    if !timeout || event.user_id.is_empty() {
+        return;
+    }
+    let s = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = timeout_user(&s, &event.user_id, verdict.timeout_secs).await {
+            tracing::warn!(user = %event.user_login, "Word filter timeout failed: {e}");
+        }
+    });
+}
+
+async fn timeout_user(state: &SharedState, user_id: &str, secs: u32) -> Result<(), String> {
+    let ctx = helix::context(state).await?;
+    ctx.api
+        .ban_user(
+            &ctx.token,
+            &ctx.broadcaster_id,
+            &ctx.broadcaster_id,
+            user_id,
+            Some(secs),
+            "Word filter",
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filter(patterns: &str) -> Filter {
+        Filter::new(
+            Arc::new(WordMatcher::new(vec!["badword".into()], vec![])),
+            parse_patterns(patterns).unwrap(),
+            0,
+        )
+    }
+
+    #[test]
+    fn test_find_and_redact() {
+        let f = filter("b[a@]d\\s*guy");
+        let text = "hi BADWORD, you b@d guy";
+        let found: Vec<&str> = f.find(text).iter().map(|&(s, e)| &text[s..e]).collect();
+        assert_eq!(found, vec!["BADWORD,", "b@d guy"]);
+        assert_eq!(f.redact(text), "hi ******** you *** ***");
+        assert_eq!(f.redact("こんにちは"), "こんにちは");
+        assert!(parse_patterns("ok\n(unclosed").is_err());
+
+        let strict = Filter::new(
+            Arc::new(WordMatcher::new(vec!["badword".into()], vec![])),
+            Vec::new(),
+            severity_rank("high"),
+        );
+        assert!(strict.find("badword").is_empty());
+    }
+
+    #[test]
+    fn test_apply_redacts_text_fragments() {
+        let verdict = |action: &str| Verdict {
+            action: action.into(),
+            matched: vec!["badword".into()],
+            filter: filter(""),
+            timeout_secs: 60,
+        };
+        let payload = json!({
+            "message": "badword Kappa",
+            "fragments": [
+                { "type": "text", "text": "badword " },
+                { "type": "emote", "text": "Kappa", "emoteId": "25" },
+            ],
+        });
+
+        let mut redacted = payload.clone();
+        verdict("redact").apply(&mut redacted);
+        assert_eq!(redacted["message"], "******* Kappa");
+        assert_eq!(redacted["fragments"][0]["text"], "******* ");
+        assert_eq!(redacted["fragments"][1]["text"], "Kappa");
+
+        let mut flagged = payload.clone();
+        verdict("flag").apply(&mut flagged);
+        assert_eq!(flagged["flagged"], true);
+        assert_eq!(flagged["message"], "badword Kappa");
+
+        let event = json!({
+            "chatter_user_id": "1",
+            "message": {
+                "text": "badword Kappa",
+                "fragments": [{ "type": "text", "text": "badword " }],
+            },
+        });
+        let masked = verdict("timeout").redact_payload(&event);
+        assert_eq!(masked["message"]["text"], "******* Kappa");
+        assert_eq!(masked["message"]["fragments"][0]["text"], "******* ");
+        assert_eq!(masked["chatter_user_id"], "1");
+    }
+}
@@ -0,0 +1,181 @@
+//! Printing chat messages (text + emotes) on the thermal printer.
+//!
+//! Emotes go through the per-emote approval list: denied emotes — and, if
+//! `EMOTE_PRINT_PENDING_POLICY` is `deny`, not-yet-reviewed ones — are
+//! printed as their text name instead of the (often badly dithered) image.
+//!
+//! Chat badges are printed as small icons before the username, resolved
+//! through the badge cache; a badge whose image is unavailable is printed
+//! as a short text tag such as `[MOD]`.
+
+use ab_glyph::FontRef;
+use image_processor::chat_render::Badge;
+use image_processor::text::Fragment;
+use overlay_db::consents::ConsentScope;
+use overlay_db::emote_rules::{STATUS_ALLOWED, STATUS_PENDING};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::print_queue::PrintCategory;
+use crate::services::{badges, consent, emote_images, print_render, print_rules, twitch_chat};
+
+/// Apply the print rules for a redemption and print it if allowed.
+pub async fn print_redemption(
+    state: &SharedState,
+    user_id: &str,
+    username: &str,
+    badges: &Value,
+    fragments: &Value,
+) -> Result<(), String> {
+    if !consent::allows(state, user_id, ConsentScope::Print) {
+        tracing::info!(user_id, "Redemption print skipped: viewer opted out");
+        return Ok(());
+    }
+    if let Err(rejection) = print_rules::check_and_record(state, user_id).await {
+        tracing::info!(
+            user_id,
+            ?rejection,
+            "Redemption print refused by print rules"
+        );
+        if let Some(reply) = print_rules::rejection_message(state, username, &rejection) {
+            if let Err(e) = twitch_chat::send_chat(state, &reply).await {
+                tracing::warn!("Failed to send print limit reply: {e}");
+            }
+        }
+        return Ok(());
+    }
+    print_chat_message(state, username, badges, fragments, PrintCategory::Reward).await
+}
+
+/// Render a chat message and queue it for printing. `badges` is the
+/// EventSub `badges` list.
+pub async fn print_chat_message(
+    state: &SharedState,
+    username: &str,
+    badges: &Value,
+    fragments: &Value,
+    category: PrintCategory,
+) -> Result<(), String> {
+    let badges = build_badges(state, badges).await;
+    let fragments = build_fragments(state, fragments).await;
+    let font_data = print_render::load_font(state)?;
+    let font = FontRef::try_from_slice(&font_data).map_err(|_| "Invalid font data")?;
+    let img = image_processor::message::message_to_image_with_badges(
+        username, &badges, &fragments, &font, false,
+    );
+    print_render::enqueue_image(state, &img, &format!("chat: {username}"), category).await
+}
+
+/// Short tag printed for a badge without an image.
+fn badge_tag(set_id: &str) -> String {
+    match set_id {
+        "broadcaster" => "LIVE".into(),
+        "moderator" => "MOD".into(),
+        "vip" => "VIP".into(),
+        "subscriber" => "SUB".into(),
+        "founder" => "FOUNDER".into(),
+        other => other.to_string(),
+    }
+}
+
+/// Resolve badge images through the badge cache.
+async fn build_badges(state: &SharedState, badge_list: &Value) -> Vec<Badge> {
+    let mut out = Vec::new();
+    for (set_id, id) in badges::keys_of(badge_list) {
+        let image = match badges::fetch_badge(state, &set_id, &id).await {
+            Ok(img) => Some(img),
+            Err(e) => {
+                tracing::debug!(badge = %badges::key(&set_id, &id), "Badge image unavailable: {e}");
+                None
+            }
+        };
+        out.push(Badge {
+            label: badge_tag(&set_id),
+            image,
+        });
+    }
+    out
+}
+
+/// Convert EventSub message fragments into printable fragments.
+async fn build_fragments(state: &SharedState, fragments: &Value) -> Vec<Fragment> {
+    let pending_allowed = SettingsManager::new(state.db().clone())
+        .get_setting("EMOTE_PRINT_PENDING_POLICY")
+        .map(|v| v != "deny")
+        .unwrap_or(true);
+    let now = chrono::Utc::now().timestamp();
+
+    let mut out = Vec::new();
+    for item in fragments.as_array().into_iter().flatten() {
+        let text = item["text"].as_str().unwrap_or_default().to_string();
+        let emote_id = match item["type"].as_str() {
+            Some("emote") => item["emote"]["id"].as_str().unwrap_or_default(),
+            _ => "",
+        };
+        if emote_id.is_empty() {
+            out.push(Fragment {
+                text,
+                is_emote: false,
+                emote_image: None,
+            });
+            continue;
+        }
+
+        let status = match state.db().observe_emote(emote_id, &text, now) {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::warn!("Failed to look up emote rule: {e}");
+                STATUS_PENDING.to_string()
+            }
+        };
+        if status == STATUS_PENDING {
+            notify_pending(state, emote_id, &text);
+        }
+
+        let printable = status == STATUS_ALLOWED || (status == STATUS_PENDING && pending_allowed);
+        let emote_image = if printable {
+            // Third-party emotes carry their image URL.
+            let fetched = match item["emote"]["url"].as_str() {
+                Some(url) => emote_images::fetch_image(state, url).await,
+                None => emote_images::fetch_emote(state, emote_id).await,
+            };
+            match fetched {
+                Ok(img) => Some(img),
+                Err(e) => {
+                    tracing::debug!(emote_id, "Emote image unavailable: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        // Without an image, message_to_image falls back to the emote name.
+        out.push(Fragment {
+            text,
+            is_emote: true,
+            emote_image,
+        });
+    }
+    out
+}
+
+fn notify_pending(state: &SharedState, emote_id: &str, name: &str) {
+    let msg = json!({
+        "type": "emote_approval_pending",
+        "data": { "emote_id": emote_id, "emote_name": name },
+    });
+    let _ = state.ws_sender().send(msg.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_badge_tag() {
+        assert_eq!(badge_tag("moderator"), "MOD");
+        assert_eq!(badge_tag("subscriber"), "SUB");
+        assert_eq!(badge_tag("premium"), "premium");
+    }
+}
@@ -0,0 +1,112 @@
+//! Chat excerpts rendered to PNG for sharing.
+//!
+//! Stored messages are drawn with their emotes, badge icons and name
+//! colors by [`image_processor::chat_render`]. Unlike printing, emote
+//! approval rules do not apply: the image is meant to look like chat did.
+//! Times are shown in the configured `TIMEZONE`.
+
+use std::collections::HashMap;
+
+use ab_glyph::FontRef;
+use image::DynamicImage;
+use image_processor::chat_render::{self, Badge, ChatLine, ChatRenderOptions};
+use image_processor::text::Fragment;
+use overlay_db::chat::ChatMessage;
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::services::{badges, emote_images, print_render, scheduler};
+
+/// Most messages in one render.
+pub const MAX_MESSAGES: i64 = 200;
+
+/// Messages with `from <= created_at <= to` (unix seconds), oldest first.
+pub fn load_messages(state: &SharedState, from: i64, to: i64) -> Result<Vec<ChatMessage>, String> {
+    state
+        .db()
+        .get_chat_messages_between(from, to, MAX_MESSAGES)
+        .map_err(|e| e.to_string())
+}
+
+/// Render messages to an image.
+pub async fn render(
+    state: &SharedState,
+    messages: &[ChatMessage],
+    options: ChatRenderOptions,
+) -> Result<DynamicImage, String> {
+    let lines = build_lines(state, messages).await;
+    let font_data = print_render::load_font(state)?;
+    let font = FontRef::try_from_slice(&font_data).map_err(|_| "Invalid font data")?;
+    Ok(chat_render::chat_to_image(&lines, &font, options))
+}
+
+/// Resolve emote and badge images of stored messages. Images that cannot
+/// be loaded fall back to their text.
+pub async fn build_lines(state: &SharedState, messages: &[ChatMessage]) -> Vec<ChatLine> {
+    let tz = scheduler::timezone(state);
+    let mut images: HashMap<String, Option<DynamicImage>> = HashMap::new();
+    let mut lines = Vec::with_capacity(messages.len());
+
+    for msg in messages {
+        let fragments: Value = serde_json::from_str(&msg.fragments_json).unwrap_or(Value::Null);
+        let mut line_fragments = Vec::new();
+        for item in fragments.as_array().into_iter().flatten() {
+            let text = item["text"].as_str().unwrap_or_default().to_string();
+            let emote_id = match item["type"].as_str() {
+                Some("emote") => item["emote"]["id"].as_str().unwrap_or_default(),
+                _ => "",
+            };
+            if emote_id.is_empty() {
+                line_fragments.push(Fragment {
+                    text,
+                    is_emote: false,
+                    emote_image: None,
+                });
+                continue;
+            }
+            let cache_key = format!("emote:{emote_id}");
+            if !images.contains_key(&cache_key) {
+                let img = emote_images::fetch_emote(state, emote_id).await.ok();
+                images.insert(cache_key.clone(), img);
+            }
+            line_fragments.push(Fragment {
+                text,
+                is_emote: true,
+                emote_image: images[&cache_key].clone(),
+            });
+        }
+        if line_fragments.is_empty() {
+            line_fragments.push(Fragment {
+                text: msg.message.clone(),
+                is_emote: false,
+                emote_image: None,
+            });
+        }
+
+        let badge_list: Value = serde_json::from_str(&msg.badges_json).unwrap_or(Value::Null);
+        let mut line_badges = Vec::new();
+        for (set_id, id) in badges::keys_of(&badge_list) {
+            let cache_key = format!("badge:{}", badges::key(&set_id, &id));
+            if !images.contains_key(&cache_key) {
+                let img = badges::fetch_badge(state, &set_id, &id).await.ok();
+                images.insert(cache_key.clone(), img);
+            }
+            line_badges.push(Badge {
+                label: set_id,
+                image: images[&cache_key].clone(),
+            });
+        }
+
+        let time = chrono::DateTime::from_timestamp(msg.created_at, 0)
+            .map(|t| t.with_timezone(&tz).format("%H:%M").to_string())
+            .unwrap_or_default();
+        lines.push(ChatLine {
+            time,
+            username: msg.username.clone(),
+            color: chat_render::parse_name_color(&msg.color),
+            badges: line_badges,
+            fragments: line_fragments,
+        });
+    }
+    lines
+}
@@ -0,0 +1,175 @@
+//! Timed chat messages posted while the stream is live.
+//!
+//! A timer is due once `interval_minutes` have passed since its last post
+//! (or since the stream went live) and viewers sent at least
+//! `min_chat_messages` messages in that time. Every [`TICK`] the worker
+//! posts the longest-waiting due timer, so timers sharing an interval take
+//! turns instead of posting together. Each post takes the next of the
+//! timer's messages, as a plain chat message or, for `announce` timers, as
+//! an announcement in `announce_color`.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
+
+use overlay_db::chat_timers::ChatTimer;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::{helix, twitch_chat};
+
+const TICK: Duration = Duration::from_secs(30);
+
+/// Unix seconds the stream went live; 0 while offline.
+static LIVE_SINCE: AtomicI64 = AtomicI64::new(0);
+
+pub fn on_stream_online() {
+    LIVE_SINCE.store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+}
+
+pub fn on_stream_offline() {
+    LIVE_SINCE.store(0, Ordering::Relaxed);
+}
+
+pub fn is_live() -> bool {
+    LIVE_SINCE.load(Ordering::Relaxed) != 0
+}
+
+/// Start of the wait for the next post of `timer`.
+fn waiting_since(timer: &ChatTimer, live_since: i64) -> i64 {
+    timer.last_sent_at.unwrap_or(0).max(live_since)
+}
+
+/// Whether `timer` has waited its interval at `now`; the chat message
+/// requirement is checked separately.
+fn interval_elapsed(timer: &ChatTimer, live_since: i64, now: i64) -> bool {
+    timer.enabled
+        && timer.next_message().is_some()
+        && now - waiting_since(timer, live_since) >= timer.interval_minutes * 60
+}
+
+fn db_err(e: overlay_db::DbError) -> String {
+    format!("Chat timer database error: {e}")
+}
+
+/// Post `timer`'s next message now and advance its rotation.
+pub async fn send_now(state: &SharedState, timer: &ChatTimer) -> Result<(), String> {
+    let message = timer.next_message().ok_or("timer has no messages")?;
+    if timer.announce {
+        twitch_chat::send_announcement(state, message, Some(&timer.announce_color)).await?;
+    } else {
+        twitch_chat::send_chat(state, message).await?;
+    }
+    state
+        .db()
+        .mark_chat_timer_sent(timer.id, chrono::Utc::now().timestamp())
+        .map_err(db_err)
+}
+
+/// Post the longest-waiting due timer, if any.
+async fn tick(state: &SharedState) -> Result<(), String> {
+    let live_since = LIVE_SINCE.load(Ordering::Relaxed);
+    if live_since == 0 {
+        return Ok(());
+    }
+    let now = chrono::Utc::now().timestamp();
+    let mut due: Vec<ChatTimer> = state
+        .db()
+        .get_chat_timers()
+        .map_err(db_err)?
+        .into_iter()
+        .filter(|t| interval_elapsed(t, live_since, now))
+        .collect();
+    due.sort_by_key(|t| waiting_since(t, live_since));
+
+    let broadcaster_id = SettingsManager::new(state.db().clone())
+        .get_setting("TWITCH_USER_ID")
+        .unwrap_or_default();
+    for timer in due {
+        if timer.min_chat_messages > 0 {
+            let chatted = state
+                .db()
+                .count_chat_messages_since(waiting_since(&timer, live_since), &broadcaster_id)
+                .map_err(db_err)?;
+            if chatted < timer.min_chat_messages {
+                continue;
+            }
+        }
+        if let Err(e) = send_now(state, &timer).await {
+            tracing::warn!(timer = %timer.name, "Failed to post chat timer: {e}");
+        }
+        return Ok(());
+    }
+    Ok(())
+}
+
+/// Whether the stream is live right now, from Helix.
+async fn fetch_live(state: &SharedState) -> Result<bool, String> {
+    let ctx = helix::context(state).await?;
+    let stream = ctx
+        .api
+        .get_stream_info(&ctx.token, &ctx.broadcaster_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(stream.is_live)
+}
+
+/// Background worker. A stream already live at startup counts as live
+/// from startup.
+pub async fn run(state: SharedState) {
+    match fetch_live(&state).await {
+        Ok(true) => on_stream_online(),
+        Ok(false) => {}
+        Err(e) => tracing::debug!("Live status unknown at startup: {e}"),
+    }
+    loop {
+        tokio::time::sleep(TICK).await;
+        if let Err(e) = tick(&state).await {
+            tracing::warn!("{e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timer(last_sent_at: Option<i64>) -> ChatTimer {
+        ChatTimer {
+            id: 1,
+            name: "discord".into(),
+            messages: vec!["https://example.com".into()],
+            interval_minutes: 20,
+            min_chat_messages: 0,
+            enabled: true,
+            announce: false,
+            announce_color: "primary".into(),
+            next_index: 0,
+            last_sent_at,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_interval_counts_from_going_live() {
+        let live_since = 10_000;
+        // Last posted during an earlier stream.
+        let t = timer(Some(100));
+        assert!(!interval_elapsed(&t, live_since, live_since + 60));
+        assert!(interval_elapsed(&t, live_since, live_since + 20 * 60));
+
+        let t = timer(Some(live_since + 600));
+        assert!(!interval_elapsed(&t, live_since, live_since + 20 * 60));
+        assert!(interval_elapsed(&t, live_since, live_since + 30 * 60));
+    }
+
+    #[test]
+    fn test_disabled_or_empty_timer_is_never_due() {
+        let mut t = timer(None);
+        t.enabled = false;
+        assert!(!interval_elapsed(&t, 1, 100_000));
+        let mut t = timer(None);
+        t.messages.clear();
+        assert!(!interval_elapsed(&t, 1, 100_000));
+    }
+}
@@ -0,0 +1,151 @@
+//! Chat wall posters.
+//!
+//! Selected chat messages — picked by ID, or every message with a hashtag
+//! — are rendered as cards and laid out as a poster several paper widths
+//! wide by [`image_processor::poster`], then printed one strip per column
+//! as a keepsake of special streams.
+
+use std::slice;
+
+use ab_glyph::FontRef;
+use image::DynamicImage;
+use image_processor::chat_render::{self, ChatRenderOptions};
+use image_processor::poster;
+use overlay_db::chat::ChatMessage;
+use overlay_db::consents::ConsentScope;
+
+use crate::app::SharedState;
+use crate::services::chat_render::build_lines;
+use crate::services::print_queue::PrintCategory;
+use crate::services::{consent, print_render};
+
+/// Most messages on one poster.
+pub const MAX_MESSAGES: usize = 60;
+pub const MAX_COLUMNS: u32 = 4;
+pub const DEFAULT_COLUMNS: u32 = 3;
+/// Hashtag search window when no range is given.
+const DEFAULT_RANGE_SECS: i64 = 12 * 3600;
+/// Most messages scanned for a hashtag.
+const HASHTAG_SCAN_LIMIT: i64 = 5000;
+
+/// Which messages go on the poster.
+#[derive(Debug, Clone)]
+pub enum Selection {
+    Ids(Vec<String>),
+    /// Messages containing `#tag` between `from` and `to` (unix seconds).
+    Hashtag {
+        tag: String,
+        from: i64,
+        to: i64,
+    },
+}
+
+impl Selection {
+    /// Hashtag selection over the last 12 hours unless a range is given.
+    pub fn hashtag(tag: &str, from: Option<i64>, to: Option<i64>) -> Self {
+        let to = to.unwrap_or_else(|| chrono::Utc::now().timestamp());
+        Self::Hashtag {
+            tag: tag.trim().trim_start_matches(['#', '＃']).to_string(),
+            from: from.unwrap_or(to - DEFAULT_RANGE_SECS),
+            to,
+        }
+    }
+}
+
+/// Whether `message` contains `#tag`, ignoring case and trailing
+/// punctuation.
+fn has_hashtag(message: &str, tag: &str) -> bool {
+    if tag.is_empty() {
+        return false;
+    }
+    let tag = tag.to_lowercase();
+    message.split_whitespace().any(|word| {
+        word.strip_prefix('#')
+            .or_else(|| word.strip_prefix('＃'))
+            .is_some_and(|w| {
+                w.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_')
+                    .to_lowercase()
+                    == tag
+            })
+    })
+}
+
+/// Selected messages from viewers who allow printing, oldest first, at
+/// most [`MAX_MESSAGES`].
+pub fn select_messages(
+    state: &SharedState,
+    selection: &Selection,
+) -> Result<Vec<ChatMessage>, String> {
+    let db = state.db();
+    let mut messages = match selection {
+        Selection::Ids(ids) => db
+            .get_chat_messages_by_ids(ids)
+            .map_err(|e| e.to_string())?,
+        Selection::Hashtag { tag, from, to } => db
+            .get_chat_messages_between(*from, *to, HASHTAG_SCAN_LIMIT)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|m| has_hashtag(&m.message, tag))
+            .collect(),
+    };
+    messages.retain(|m| consent::allows(state, &m.user_id, ConsentScope::Print));
+    messages.truncate(MAX_MESSAGES);
+    Ok(messages)
+}
+
+/// Render messages as one card each and lay them out under `title`.
+pub async fn render_poster(
+    state: &SharedState,
+    messages: &[ChatMessage],
+    title: &str,
+    columns: u32,
+) -> Result<DynamicImage, String> {
+    let lines = build_lines(state, messages).await;
+    let font_data = print_render::load_font(state)?;
+    let font = FontRef::try_from_slice(&font_data).map_err(|_| "Invalid font data")?;
+    let cards: Vec<DynamicImage> = lines
+        .iter()
+        .map(|line| {
+            chat_render::chat_to_image(slice::from_ref(line), &font, ChatRenderOptions::scaled(1))
+        })
+        .collect();
+    Ok(poster::masonry(title, &cards, columns, &font))
+}
+
+/// Queue a poster as one print per strip. Returns the number of strips.
+pub async fn print_poster(state: &SharedState, poster_img: &DynamicImage) -> Result<usize, String> {
+    let font_data = print_render::load_font(state)?;
+    let font = FontRef::try_from_slice(&font_data).map_err(|_| "Invalid font data")?;
+    let strips = poster::split_strips(poster_img, &font);
+    let count = strips.len();
+    for (i, strip) in strips.iter().enumerate() {
+        let description = format!("Chat wall {}/{count}", i + 1);
+        print_render::enqueue_image(state, strip, &description, PrintCategory::Chat).await?;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_hashtag() {
+        assert!(has_hashtag("GG! #Thanks100k", "thanks100k"));
+        assert!(has_hashtag("おめでとう #記念配信!", "記念配信"));
+        assert!(has_hashtag("＃記念配信 すごい", "記念配信"));
+        assert!(!has_hashtag("#thanks100kk", "thanks100k"));
+        assert!(!has_hashtag("thanks100k", "thanks100k"));
+        assert!(!has_hashtag("#anything", ""));
+    }
+
+    #[test]
+    fn test_hashtag_selection_defaults() {
+        let Selection::Hashtag { tag, from, to } = Selection::hashtag(" #GG ", None, Some(100_000))
+        else {
+            panic!("expected hashtag selection");
+        };
+        assert_eq!(tag, "GG");
+        assert_eq!((from, to), (100_000 - DEFAULT_RANGE_SECS, 100_000));
+    }
+}
@@ -0,0 +1,123 @@
+//! Clip creation.
+//!
+//! Clips are made from the dashboard (`POST /api/twitch/clip`) or by
+//! redeeming `CLIP_REWARD_ID`. Twitch processes a new clip in the
+//! background, so [`create`] returns its ID right away and a follow-up task
+//! waits for the clip to be published before sending overlays
+//! `clip_created` with its URL and thumbnail.
+
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use twitch_client::api::CreatedClip;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::{send_ws, str_field};
+use crate::services::helix;
+
+pub const SOURCE_DASHBOARD: &str = "dashboard";
+pub const SOURCE_REWARD: &str = "reward";
+
+/// Twitch suggests waiting 15 seconds before looking up a new clip.
+const FIRST_LOOKUP: Duration = Duration::from_secs(15);
+const LOOKUP_INTERVAL: Duration = Duration::from_secs(5);
+const LOOKUP_ATTEMPTS: u32 = 6;
+
+/// Public URL of clip `id`.
+pub fn clip_url(id: &str) -> String {
+    format!("https://clips.twitch.tv/{id}")
+}
+
+/// Clip the live stream. `requested_by` is the viewer whose redemption
+/// asked for it, if any.
+pub async fn create(
+    state: &SharedState,
+    source: &str,
+    requested_by: &str,
+) -> Result<CreatedClip, String> {
+    let ctx = helix::context(state).await?;
+    let clip = ctx
+        .api
+        .create_clip(&ctx.token, &ctx.broadcaster_id, false)
+        .await
+        .map_err(|e| format!("Failed to create clip: {e}"))?;
+    tracing::info!(id = %clip.id, source, "Clip created");
+
+    let s = state.clone();
+    let id = clip.id.clone();
+    let source = source.to_string();
+    let requested_by = requested_by.to_string();
+    tokio::spawn(async move { publish(&s, &id, &source, &requested_by).await });
+    Ok(clip)
+}
+
+/// Wait for clip `id` to be processed and announce it to overlays.
+async fn publish(state: &SharedState, id: &str, source: &str, requested_by: &str) {
+    tokio::time::sleep(FIRST_LOOKUP).await;
+    let mut clip = None;
+    for attempt in 0..LOOKUP_ATTEMPTS {
+        if attempt > 0 {
+            tokio::time::sleep(LOOKUP_INTERVAL).await;
+        }
+        let found = match helix::context(state).await {
+            Ok(ctx) => ctx
+                .api
+                .get_clip(&ctx.token, id)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        match found {
+            Ok(Some(found)) => {
+                clip = Some(found);
+                break;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::debug!(id, "Clip lookup failed: {e}"),
+        }
+    }
+
+    // A clip that is still processing is announced without a thumbnail.
+    let data = match clip {
+        Some(clip) => json!({
+            "id": clip.id,
+            "url": clip.url,
+            "thumbnail_url": clip.thumbnail_url,
+            "title": clip.title,
+            "duration": clip.duration,
+            "source": source,
+            "requested_by": requested_by,
+        }),
+        None => {
+            tracing::warn!(id, "Clip was not published in time");
+            json!({
+                "id": id,
+                "url": clip_url(id),
+                "thumbnail_url": "",
+                "title": "",
+                "duration": 0,
+                "source": source,
+                "requested_by": requested_by,
+            })
+        }
+    };
+    send_ws(state, "clip_created", data);
+}
+
+/// Create a clip when `CLIP_REWARD_ID` is redeemed.
+pub fn handle_redemption(state: &SharedState, payload: &Value) {
+    let sm = SettingsManager::new(state.db().clone());
+    let clip_reward = sm.get_setting("CLIP_REWARD_ID").unwrap_or_default();
+    let reward_id = str_field(payload, &["reward", "id"]);
+    if clip_reward.is_empty() || reward_id != clip_reward {
+        return;
+    }
+    let user_name = str_field(payload, &["user_name"]);
+    let s = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = create(&s, SOURCE_REWARD, &user_name).await {
+            tracing::warn!("{e}");
+        }
+    });
+}
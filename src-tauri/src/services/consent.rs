@@ -0,0 +1,251 @@
+//! Viewer consent for prints, TTS and credits.
+//!
+//! Viewers record their choice with `!optin` / `!optout` (optionally
+//! followed by `print`, `tts` or `credits`; everything otherwise) or by
+//! redeeming the opt-in / opt-out rewards. Scopes without a choice follow
+//! `CONSENT_DEFAULT_POLICY`. Printing viewer messages and TTS check
+//! [`allows`]; overlays doing credits get the flags with each chat message.
+
+use overlay_db::consents::ConsentScope;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::{non_empty, str_field};
+use crate::services::twitch_chat;
+
+pub const OPT_IN: &str = "!optin";
+pub const OPT_OUT: &str = "!optout";
+
+/// A parsed `!optin` / `!optout` command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsentCommand {
+    pub allowed: bool,
+    pub scopes: Vec<ConsentScope>,
+}
+
+/// Whether viewers without a recorded choice are allowed.
+fn default_allowed(state: &SharedState) -> bool {
+    SettingsManager::new(state.db().clone())
+        .get_setting("CONSENT_DEFAULT_POLICY")
+        .unwrap_or_default()
+        != "deny"
+}
+
+/// Whether the viewer allows `scope`. Unknown viewers and lookup failures
+/// follow the default policy.
+pub fn allows(state: &SharedState, user_id: &str, scope: ConsentScope) -> bool {
+    let recorded = if user_id.is_empty() {
+        None
+    } else {
+        state
+            .db()
+            .get_consent(user_id)
+            .map_err(|e| tracing::warn!("Failed to load viewer consent: {e}"))
+            .ok()
+            .flatten()
+            .and_then(|c| c.get(scope))
+    };
+    recorded.unwrap_or_else(|| default_allowed(state))
+}
+
+/// Every scope for a viewer, e.g. `{"print": true, "tts": false, ...}`.
+pub fn flags(state: &SharedState, user_id: &str) -> Value {
+    let consent = if user_id.is_empty() {
+        None
+    } else {
+        state.db().get_consent(user_id).ok().flatten()
+    };
+    let default = default_allowed(state);
+    let mut flags = serde_json::Map::new();
+    for scope in ConsentScope::ALL {
+        let allowed = consent
+            .as_ref()
+            .and_then(|c| c.get(scope))
+            .unwrap_or(default);
+        flags.insert(scope.as_str().to_string(), json!(allowed));
+    }
+    Value::Object(flags)
+}
+
+/// Parse a consent command. Returns `None` for other messages or unknown
+/// scopes.
+pub fn parse_command(text: &str) -> Option<ConsentCommand> {
+    let mut parts = text.split_whitespace();
+    let command = parts.next()?;
+    let allowed = if command.eq_ignore_ascii_case(OPT_IN) {
+        true
+    } else if command.eq_ignore_ascii_case(OPT_OUT) {
+        false
+    } else {
+        return None;
+    };
+    let scopes = parse_scopes(parts)?;
+    Some(ConsentCommand { allowed, scopes })
+}
+
+/// Scopes named in `words`; all of them when none (or `all`) is given.
+fn parse_scopes<'a>(words: impl Iterator<Item = &'a str>) -> Option<Vec<ConsentScope>> {
+    let mut scopes = Vec::new();
+    for word in words {
+        if word.eq_ignore_ascii_case("all") {
+            return Some(ConsentScope::ALL.to_vec());
+        }
+        let scope = ConsentScope::parse(word)?;
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    if scopes.is_empty() {
+        scopes = ConsentScope::ALL.to_vec();
+    }
+    Some(scopes)
+}
+
+/// Chat reply confirming a change.
+fn confirmation(username: &str, command: &ConsentCommand) -> String {
+    let scopes: Vec<&str> = command.scopes.iter().map(|s| s.as_str()).collect();
+    let verb = if command.allowed {
+        "opted in to"
+    } else {
+        "opted out of"
+    };
+    format!("@{username} {verb} {}.", scopes.join(", "))
+}
+
+fn record(
+    state: &SharedState,
+    user_id: &str,
+    username: &str,
+    command: &ConsentCommand,
+    source: &str,
+) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp();
+    for &scope in &command.scopes {
+        state
+            .db()
+            .set_consent(user_id, username, scope, Some(command.allowed), source, now)
+            .map_err(|e| e.to_string())?;
+    }
+    tracing::info!(
+        user_id,
+        allowed = command.allowed,
+        scopes = ?command.scopes,
+        source,
+        "Viewer consent recorded"
+    );
+    Ok(())
+}
+
+/// Handle a chat message if it is a consent command (replies in the
+/// background).
+pub fn handle_chat_message(state: &SharedState, payload: &Value) {
+    let Some(command) = parse_command(&str_field(payload, &["message", "text"])) else {
+        return;
+    };
+    let enabled = SettingsManager::new(state.db().clone())
+        .get_setting("CONSENT_COMMANDS_ENABLED")
+        .unwrap_or_default()
+        == "true";
+    let user_id = str_field(payload, &["chatter_user_id"]);
+    if !enabled || user_id.is_empty() {
+        return;
+    }
+    let username = non_empty(
+        str_field(payload, &["chatter_user_name"]),
+        str_field(payload, &["chatter_user_login"]),
+    );
+    if let Err(e) = record(state, &user_id, &username, &command, "command") {
+        tracing::warn!("Failed to record viewer consent: {e}");
+        return;
+    }
+
+    let s = state.clone();
+    let reply = confirmation(&username, &command);
+    tokio::spawn(async move {
+        if let Err(e) = twitch_chat::send_chat(&s, &reply).await {
+            tracing::warn!("Failed to send consent reply: {e}");
+        }
+    });
+}
+
+/// Record consent for a redemption of the opt-in / opt-out reward. The
+/// redeemer may name scopes in the reward input.
+pub fn handle_redemption(state: &SharedState, payload: &Value) {
+    let reward_id = str_field(payload, &["reward", "id"]);
+    if reward_id.is_empty() {
+        return;
+    }
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let allowed = if reward_id == get("CONSENT_OPT_IN_REWARD_ID") {
+        true
+    } else if reward_id == get("CONSENT_OPT_OUT_REWARD_ID") {
+        false
+    } else {
+        return;
+    };
+
+    let user_id = str_field(payload, &["user_id"]);
+    if user_id.is_empty() {
+        return;
+    }
+    let user_input = str_field(payload, &["user_input"]);
+    let scopes =
+        parse_scopes(user_input.split_whitespace()).unwrap_or_else(|| ConsentScope::ALL.to_vec());
+    let username = non_empty(
+        str_field(payload, &["user_name"]),
+        str_field(payload, &["user_login"]),
+    );
+    if let Err(e) = record(
+        state,
+        &user_id,
+        &username,
+        &ConsentCommand { allowed, scopes },
+        "reward",
+    ) {
+        tracing::warn!("Failed to record viewer consent: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            parse_command("!optout"),
+            Some(ConsentCommand {
+                allowed: false,
+                scopes: ConsentScope::ALL.to_vec(),
+            })
+        );
+        assert_eq!(
+            parse_command("!OptIn tts print tts"),
+            Some(ConsentCommand {
+                allowed: true,
+                scopes: vec![ConsentScope::Tts, ConsentScope::Print],
+            })
+        );
+        assert_eq!(
+            parse_command("!optin credits all").map(|c| c.scopes),
+            Some(ConsentScope::ALL.to_vec())
+        );
+        assert_eq!(parse_command("!optout everything"), None);
+        assert_eq!(parse_command("!optouts"), None);
+        assert_eq!(parse_command("hello !optin"), None);
+    }
+
+    #[test]
+    fn test_confirmation() {
+        let command = ConsentCommand {
+            allowed: false,
+            scopes: vec![ConsentScope::Print, ConsentScope::Tts],
+        };
+        assert_eq!(
+            confirmation("alice", &command),
+            "@alice opted out of print, tts."
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! Five-field cron expressions evaluated in an IANA time zone.
+//!
+//! `minute hour day-of-month month day-of-week`, each field `*`, a value, a
+//! range `a-b`, a list `a,b` or a step `*/n` / `a-b/n`. Day-of-week is 0–6
+//! with Sunday 0 (7 is accepted as Sunday). When both day fields are
+//! restricted a day matches either, as in Vixie cron.
+//!
+//! Times are matched against the wall clock of the zone. Across DST
+//! changes a time skipped by the spring-forward gap fires at the first
+//! valid minute after the gap, and a time repeated by the fall-back
+//! overlap fires once (at its first occurrence).
+
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDateTime, TimeZone, Timelike};
+use chrono_tz::Tz;
+
+/// How far ahead to search before giving up (e.g. `0 0 30 2 *`).
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_any: bool,
+    weekdays_any: bool,
+}
+
+/// Parse one field into a bit set over `min..=max`.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (
+                r,
+                s.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid step: {part}"))?,
+            ),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, min, max)?, parse_value(b, min, max)?)
+        } else {
+            let v = parse_value(range, min, max)?;
+            (v, if part.contains('/') { max } else { v })
+        };
+        if start > end {
+            return Err(format!("Invalid range: {part}"));
+        }
+        for v in (start..=end).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(s: &str, min: u32, max: u32) -> Result<u32, String> {
+    s.parse::<u32>()
+        .ok()
+        .filter(|v| (min..=max).contains(v))
+        .ok_or_else(|| format!("Value out of range {min}-{max}: {s}"))
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("Expected 5 fields, got {}", fields.len()));
+        };
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            days_any: day == "*",
+            weekdays_any: weekday == "*",
+        })
+    }
+
+    fn matches_day(&self, t: &NaiveDateTime) -> bool {
+        if self.months & (1 << t.month()) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << t.day()) != 0;
+        let weekday = self.weekdays & (1 << t.weekday().num_days_from_sunday()) != 0;
+        match (self.days_any, self.weekdays_any) {
+            (true, true) => true,
+            (false, true) => day,
+            (true, false) => weekday,
+            (false, false) => day || weekday,
+        }
+    }
+
+    /// First wall-clock match strictly after `after`, in the zone of `after`.
+    pub fn next_after(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)?;
+        let limit = start + Duration::days(MAX_SEARCH_DAYS);
+        let mut t = start + Duration::minutes(1);
+        while t < limit {
+            if !self.matches_day(&t) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+                continue;
+            }
+            if let Some(instant) = resolve(&tz, t).filter(|i| i > after) {
+                return Some(instant);
+            }
+            t += Duration::minutes(1);
+        }
+        None
+    }
+}
+
+/// Map a wall-clock time to an instant; times in a DST gap move to the
+/// first valid minute after it.
+fn resolve(tz: &Tz, t: NaiveDateTime) -> Option<DateTime<Tz>> {
+    match tz.from_local_datetime(&t) {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => (1..=180).find_map(|m| {
+            tz.from_local_datetime(&(t + Duration::minutes(m)))
+                .earliest()
+        }),
+    }
+}
+
+/// Parse an IANA zone name such as `Asia/Tokyo`.
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>()
+        .map_err(|_| format!("Unknown time zone: {name}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(tz: Tz, y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Tz> {
+        tz.with_ymd_and_hms(y, m, d, h, min, 0).earliest().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(CronExpr::parse("0 * * * *").is_ok());
+        assert!(CronExpr::parse("*/15 9-17 * * 1-5").is_ok());
+        assert!(CronExpr::parse("0 0 1,15 * 7").is_ok());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("* * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+        assert!(CronExpr::parse("5-1 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        let tz = chrono_tz::Asia::Tokyo;
+        let hourly = CronExpr::parse("0 * * * *").unwrap();
+        assert_eq!(
+            hourly.next_after(&at(tz, 2026, 10, 16, 21, 5)),
+            Some(at(tz, 2026, 10, 16, 22, 0))
+        );
+        assert_eq!(
+            hourly.next_after(&at(tz, 2026, 10, 16, 22, 0)),
+            Some(at(tz, 2026, 10, 16, 23, 0))
+        );
+        let weekdays = CronExpr::parse("30 9 * * 1-5").unwrap();
+        // 2026-10-16 is a Friday.
+        assert_eq!(
+            weekdays.next_after(&at(tz, 2026, 10, 16, 10, 0)),
+            Some(at(tz, 2026, 10, 19, 9, 30))
+        );
+        assert_eq!(
+            CronExpr::parse("0 0 30 2 *")
+                .unwrap()
+                .next_after(&at(tz, 2026, 1, 1, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dst() {
+        let tz = chrono_tz::America::New_York;
+        let daily = CronExpr::parse("30 2 * * *").unwrap();
+        // 2026-03-08 02:30 does not exist; it fires right after the gap.
+        let next = daily.next_after(&at(tz, 2026, 3, 8, 0, 0)).unwrap();
+        assert_eq!(next.naive_local().to_string(), "2026-03-08 03:00:00");
+
+        // 2026-11-01 01:30 happens twice; it fires once.
+        let daily = CronExpr::parse("30 1 * * *").unwrap();
+        let first = daily.next_after(&at(tz, 2026, 11, 1, 0, 0)).unwrap();
+        assert_eq!(first.naive_local().to_string(), "2026-11-01 01:30:00");
+        let second = daily.next_after(&first).unwrap();
+        assert_eq!(second.naive_local().to_string(), "2026-11-02 01:30:00");
+    }
+}
@@ -0,0 +1,316 @@
+//! Offline demo mode.
+//!
+//! With `TWITCH_OVERLAY_DEMO=1` the app runs on an in-memory database that
+//! is seeded with fake chat, lottery participants, reward counts, raids,
+//! quotes, a rundown and archived alerts, and a background task feeds
+//! synthetic EventSub notifications through the normal event pipeline.
+//! Nothing is persisted and no Twitch connection is needed, so the overlay
+//! and control panel can be shown or developed completely offline.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use overlay_db::{Database, DbError};
+use serde_json::{Value, json};
+use twitch_client::eventsub;
+
+use crate::app::SharedState;
+use crate::services::rundown;
+
+/// Delay between synthetic events.
+const EVENT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reward whose counts are seeded.
+const DEMO_REWARD_ID: &str = "demo-reward-hydrate";
+
+/// Fake viewers: `(user_id, login, display name)`.
+const VIEWERS: &[(&str, &str, &str)] = &[
+    ("900001", "demo_sakura", "さくら"),
+    ("900002", "demo_kaede", "かえで"),
+    ("900003", "demo_hinata", "ひなた"),
+    ("900004", "demo_ren", "れん"),
+    ("900005", "demo_mio", "みお"),
+    ("900006", "demo_taro", "DemoTaro"),
+    ("900007", "demo_alice", "DemoAlice"),
+    ("900008", "demo_bob", "DemoBob"),
+];
+
+const CHAT_LINES: &[&str] = &[
+    "こんばんは！",
+    "初見です",
+    "今日も配信ありがとう",
+    "その装備強そう",
+    "GG",
+    "待ってました",
+    "BGM いいね",
+    "おつかれさま",
+];
+
+/// Twitch global emotes used in chat: `(id, name)`.
+const EMOTES: &[(&str, &str)] = &[
+    ("25", "Kappa"),
+    ("88", "PogChamp"),
+    ("354", "4Head"),
+    ("1902", "Keepo"),
+];
+
+/// Name colors, cycled per viewer.
+const DEMO_COLORS: &[&str] = &["#9146FF", "#FF4500", "#1E90FF", "#2E8B57", "#DAA520"];
+
+const QUOTES: &[&str] = &[
+    "今日は早めに寝ます（寝ない）",
+    "これは勝ったな",
+    "ボスより雑魚のほうが強い",
+];
+
+const RUNDOWN: &[&str] = &[
+    "オープニング",
+    "雑談",
+    "ゲーム本編",
+    "抽選会",
+    "エンディング",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DemoEvent {
+    Chat,
+    Follow,
+    Cheer,
+    Subscribe,
+    GiftSub,
+    Raid,
+}
+
+/// One cycle of synthetic events; mostly chat with an alert now and then.
+const SCRIPT: &[DemoEvent] = &[
+    DemoEvent::Chat,
+    DemoEvent::Chat,
+    DemoEvent::Follow,
+    DemoEvent::Chat,
+    DemoEvent::Chat,
+    DemoEvent::Cheer,
+    DemoEvent::Chat,
+    DemoEvent::Chat,
+    DemoEvent::Subscribe,
+    DemoEvent::Chat,
+    DemoEvent::Chat,
+    DemoEvent::Raid,
+    DemoEvent::Chat,
+    DemoEvent::GiftSub,
+];
+
+static ENABLED: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var("TWITCH_OVERLAY_DEMO")
+        .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+});
+
+/// Whether the process runs in demo mode.
+pub fn is_enabled() -> bool {
+    *ENABLED
+}
+
+fn viewer(n: usize) -> (&'static str, &'static str, &'static str) {
+    VIEWERS[n % VIEWERS.len()]
+}
+
+/// EventSub badges of viewer `n`: some subscribers and a moderator.
+fn demo_badges(n: usize) -> Value {
+    match n % VIEWERS.len() {
+        0 => json!([{ "set_id": "moderator", "id": "1", "info": "" }]),
+        i if i % 3 == 0 => json!([{ "set_id": "subscriber", "id": "0", "info": "1" }]),
+        _ => json!([]),
+    }
+}
+
+/// EventSub chat fragments for line `n`, with an emote on every other line.
+fn chat_fragments(n: usize) -> (String, Value) {
+    let line = CHAT_LINES[n % CHAT_LINES.len()];
+    if n % 2 == 0 {
+        return (line.to_string(), json!([{ "type": "text", "text": line }]));
+    }
+    let (id, name) = EMOTES[n / 2 % EMOTES.len()];
+    let text = format!("{line} {name}");
+    let fragments = json!([
+        { "type": "text", "text": format!("{line} ") },
+        {
+            "type": "emote",
+            "text": name,
+            "emote": { "id": id, "emote_set_id": "0", "owner_id": "0", "format": ["static"] },
+        },
+    ]);
+    (text, fragments)
+}
+
+/// Synthetic EventSub notification `n` of `kind`: `(event type, payload)`.
+fn event_payload(kind: DemoEvent, n: usize) -> (&'static str, Value) {
+    let (id, login, name) = viewer(n);
+    let user = json!({ "user_id": id, "user_login": login, "user_name": name });
+    let with_user = |extra: Value| {
+        let mut payload = user.clone();
+        if let (Some(p), Some(e)) = (payload.as_object_mut(), extra.as_object()) {
+            p.extend(e.clone());
+        }
+        payload
+    };
+    match kind {
+        DemoEvent::Chat => {
+            let (text, fragments) = chat_fragments(n);
+            (
+                eventsub::EVENT_CHAT_MESSAGE,
+                json!({
+                    "message_id": uuid::Uuid::new_v4().to_string(),
+                    "chatter_user_id": id,
+                    "chatter_user_login": login,
+                    "chatter_user_name": name,
+                    "color": DEMO_COLORS[n % DEMO_COLORS.len()],
+                    "badges": demo_badges(n),
+                    "message": { "text": text, "fragments": fragments },
+                }),
+            )
+        }
+        DemoEvent::Follow => (
+            eventsub::EVENT_CHANNEL_FOLLOW,
+            with_user(json!({ "followed_at": chrono::Utc::now().to_rfc3339() })),
+        ),
+        DemoEvent::Cheer => (
+            eventsub::EVENT_CHANNEL_CHEER,
+            with_user(
+                json!({ "bits": 100 * (n % 5 + 1), "message": "Cheer100", "is_anonymous": false }),
+            ),
+        ),
+        DemoEvent::Subscribe => (
+            eventsub::EVENT_CHANNEL_SUBSCRIBE,
+            with_user(json!({ "tier": "1000", "is_gift": false })),
+        ),
+        DemoEvent::GiftSub => (
+            eventsub::EVENT_SUBSCRIPTION_GIFT,
+            with_user(json!({ "total": 5, "tier": "1000", "is_anonymous": false })),
+        ),
+        DemoEvent::Raid => (
+            eventsub::EVENT_CHANNEL_RAID,
+            json!({
+                "from_broadcaster_user_id": id,
+                "from_broadcaster_user_login": login,
+                "from_broadcaster_user_name": name,
+                "viewers": 10 + n % 40,
+            }),
+        ),
+    }
+}
+
+/// Fill a fresh database with demo data. `today` is the rundown day.
+pub fn seed(db: &Database, today: &str, now: i64) -> Result<(), DbError> {
+    for n in 0..30 {
+        let (id, login, name) = viewer(n);
+        let (text, fragments) = chat_fragments(n);
+        db.add_chat_message(&overlay_db::chat::ChatMessage {
+            id: 0,
+            message_id: format!("demo-chat-{n}"),
+            user_id: id.to_string(),
+            username: name.to_string(),
+            message: text,
+            fragments_json: fragments.to_string(),
+            avatar_url: String::new(),
+            translation_text: String::new(),
+            translation_status: String::new(),
+            translation_lang: String::new(),
+            created_at: now - (30 - n as i64) * 60,
+            badges_json: demo_badges(n).to_string(),
+            color: DEMO_COLORS[n % DEMO_COLORS.len()].to_string(),
+        })?;
+        if n < VIEWERS.len() {
+            db.add_lottery_participant(&overlay_db::lottery::LotteryParticipant {
+                user_id: id.to_string(),
+                username: login.to_string(),
+                display_name: name.to_string(),
+                avatar_url: String::new(),
+                redeemed_at: chrono::DateTime::from_timestamp(now - n as i64 * 90, 0)
+                    .unwrap_or_default()
+                    .to_rfc3339(),
+                is_subscriber: n % 3 == 0,
+                subscriber_tier: if n % 3 == 0 { "1000" } else { "" }.to_string(),
+                subscribed_months: if n % 3 == 0 { n as i32 + 1 } else { 0 },
+                entry_count: (n % 3 + 1) as i32,
+                assigned_color: String::new(),
+            })?;
+        }
+        if n % 4 == 0 {
+            db.increment_reward_count(DEMO_REWARD_ID, name)?;
+        }
+    }
+    db.set_reward_display_name(DEMO_REWARD_ID, "水分補給")?;
+
+    for n in 0..3 {
+        let (id, login, name) = viewer(n + 4);
+        db.add_raid(
+            overlay_db::raids::DIRECTION_IN,
+            id,
+            login,
+            name,
+            12 + n as i64 * 7,
+            now - (3 - n as i64) * 86_400,
+        )?;
+    }
+    for (n, text) in QUOTES.iter().enumerate() {
+        db.add_quote(text, "streamer", viewer(n).2, now - 3600)?;
+    }
+    for text in RUNDOWN {
+        db.add_rundown_item(today, text, now)?;
+    }
+    for (n, kind) in [DemoEvent::Follow, DemoEvent::Subscribe, DemoEvent::Cheer]
+        .into_iter()
+        .enumerate()
+    {
+        let (event_type, payload) = event_payload(kind, n);
+        db.archive_event(event_type, &payload, now - 600 + n as i64 * 60)?;
+    }
+    Ok(())
+}
+
+/// Seed the database, then emit synthetic events until the process exits.
+pub async fn run(state: SharedState) {
+    let today = rundown::today(&state);
+    if let Err(e) = seed(state.db(), &today, chrono::Utc::now().timestamp()) {
+        tracing::error!("Failed to seed demo data: {e}");
+    }
+    tracing::info!("Demo mode: emitting synthetic events every {EVENT_INTERVAL:?}");
+
+    let mut interval = tokio::time::interval(EVENT_INTERVAL);
+    interval.tick().await;
+    for n in 0.. {
+        interval.tick().await;
+        let (event_type, payload) = event_payload(SCRIPT[n % SCRIPT.len()], n);
+        crate::eventsub_handler::dispatch(&state, event_type, &payload).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed() {
+        let db = Database::open_in_memory().unwrap();
+        seed(&db, "2026-10-16", 1_790_000_000).unwrap();
+        assert!(!db.get_chat_messages_since(0, Some(100)).unwrap().is_empty());
+        assert_eq!(
+            db.get_all_lottery_participants().unwrap().len(),
+            VIEWERS.len()
+        );
+        assert_eq!(db.get_rundown("2026-10-16").unwrap().len(), RUNDOWN.len());
+        assert_eq!(db.get_quotes(None).unwrap().len(), QUOTES.len());
+    }
+
+    #[test]
+    fn test_event_payload() {
+        let (event_type, payload) = event_payload(DemoEvent::Chat, 1);
+        assert_eq!(event_type, eventsub::EVENT_CHAT_MESSAGE);
+        assert_eq!(payload["message"]["fragments"][1]["type"], "emote");
+        let (event_type, payload) = event_payload(DemoEvent::Raid, 0);
+        assert_eq!(event_type, eventsub::EVENT_CHANNEL_RAID);
+        assert_eq!(payload["from_broadcaster_user_login"], "demo_sakura");
+        let (_, payload) = event_payload(DemoEvent::Cheer, 2);
+        assert_eq!(payload["user_name"], "ひなた");
+        assert_eq!(payload["bits"], 300);
+    }
+}
@@ -0,0 +1,417 @@
+//! Discord notifications: stream lifecycle and big events posted as embeds
+//! to a Discord webhook (`DISCORD_WEBHOOK_URL`).
+//!
+//! Each event has a built-in template; a customized one stored in
+//! `discord_templates` replaces it. Titles and descriptions expand
+//! `{name}` placeholders listed per event in [`EVENTS`].
+//!
+//! Posts go through an in-memory queue drained by [`run`], at most one per
+//! [`MIN_INTERVAL`]. A 429 response is waited out and the post is retried;
+//! other failures are retried up to [`MAX_ATTEMPTS`] times.
+
+use std::collections::VecDeque;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use overlay_db::discord::DiscordTemplate;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::sync::Notify;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::helix;
+
+pub const EVENT_STREAM_ONLINE: &str = "stream_online";
+pub const EVENT_STREAM_OFFLINE: &str = "stream_offline";
+pub const EVENT_RAID: &str = "raid";
+pub const EVENT_LOTTERY_WINNER: &str = "lottery_winner";
+
+/// A built-in template and the placeholders its event provides.
+pub struct EventDef {
+    pub event: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub color: i64,
+    pub placeholders: &'static [&'static str],
+}
+
+pub const EVENTS: &[EventDef] = &[
+    EventDef {
+        event: EVENT_STREAM_ONLINE,
+        title: "{broadcaster} が配信を開始しました",
+        description: "{title}\n{game}\n{url}",
+        color: 0x9146ff,
+        placeholders: &["broadcaster", "title", "game", "url"],
+    },
+    EventDef {
+        event: EVENT_STREAM_OFFLINE,
+        title: "{broadcaster} の配信が終了しました",
+        description: "ご視聴ありがとうございました",
+        color: 0x747f8d,
+        placeholders: &["broadcaster", "url"],
+    },
+    EventDef {
+        event: EVENT_RAID,
+        title: "{user} さんからレイド",
+        description: "{viewers} 人のレイドが来ました",
+        color: 0xe91e63,
+        placeholders: &["broadcaster", "user", "viewers", "url"],
+    },
+    EventDef {
+        event: EVENT_LOTTERY_WINNER,
+        title: "抽選の当選者: {user}",
+        description: "参加者 {participants} 人から選ばれました",
+        color: 0xf1c40f,
+        placeholders: &["user", "participants"],
+    },
+];
+
+/// Discord allows about 30 webhook posts per minute.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(2);
+/// Attempts per post before it is dropped (429 responses do not count).
+pub const MAX_ATTEMPTS: u32 = 3;
+/// Posts kept waiting at most; newer ones are dropped beyond it.
+const QUEUE_CAPACITY: usize = 50;
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Embed as sent to Discord.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Embed {
+    pub title: String,
+    pub description: String,
+    pub color: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub timestamp: String,
+}
+
+struct QueuedPost {
+    event: &'static str,
+    embed: Embed,
+    attempts: u32,
+}
+
+static QUEUE: LazyLock<Mutex<VecDeque<QueuedPost>>> = LazyLock::new(Mutex::default);
+static WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+struct DiscordSettings {
+    enabled: bool,
+    webhook_url: String,
+    username: String,
+}
+
+fn load_settings(state: &SharedState) -> DiscordSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    DiscordSettings {
+        enabled: get("DISCORD_ENABLED") == "true",
+        webhook_url: get("DISCORD_WEBHOOK_URL").trim().to_string(),
+        username: get("DISCORD_USERNAME").trim().to_string(),
+    }
+}
+
+pub fn event_def(event: &str) -> Option<&'static EventDef> {
+    EVENTS.iter().find(|def| def.event == event)
+}
+
+/// The built-in template of an event, as a stored row would look.
+pub fn default_template(def: &EventDef) -> DiscordTemplate {
+    DiscordTemplate {
+        event: def.event.to_string(),
+        enabled: true,
+        title: def.title.to_string(),
+        description: def.description.to_string(),
+        color: def.color,
+        updated_at: 0,
+    }
+}
+
+/// The customized template of `def`, or its built-in one.
+pub fn template(state: &SharedState, def: &EventDef) -> DiscordTemplate {
+    match state.db().get_discord_template(def.event) {
+        Ok(Some(template)) => template,
+        Ok(None) => default_template(def),
+        Err(e) => {
+            tracing::warn!("Failed to load Discord template: {e}");
+            default_template(def)
+        }
+    }
+}
+
+/// Replace `{name}` placeholders with `vars`.
+pub fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |out, (name, value)| {
+            out.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Build the embed of `template` for one event.
+pub fn embed(template: &DiscordTemplate, vars: &[(&str, &str)]) -> Embed {
+    let url = vars
+        .iter()
+        .find(|(name, _)| *name == "url")
+        .map(|(_, url)| url.to_string())
+        .filter(|url| !url.is_empty());
+    Embed {
+        title: render(&template.title, vars),
+        description: render(&template.description, vars).trim().to_string(),
+        color: template.color,
+        url,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Twitch channel URL for the `url` placeholder.
+pub fn channel_url(login: &str) -> String {
+    if login.is_empty() {
+        String::new()
+    } else {
+        format!("https://www.twitch.tv/{login}")
+    }
+}
+
+fn is_active(settings: &DiscordSettings) -> bool {
+    settings.enabled && !settings.webhook_url.is_empty()
+}
+
+/// Queue a post for `event` if Discord notifications are on and the event's
+/// template is enabled.
+pub fn notify(state: &SharedState, event: &str, vars: &[(&str, &str)]) {
+    if !is_active(&load_settings(state)) {
+        return;
+    }
+    let Some(def) = event_def(event) else {
+        return;
+    };
+    let template = template(state, def);
+    if !template.enabled {
+        return;
+    }
+    let post = QueuedPost {
+        event: def.event,
+        embed: embed(&template, vars),
+        attempts: 0,
+    };
+    {
+        let mut queue = QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+        if queue.len() >= QUEUE_CAPACITY {
+            tracing::warn!(event, "Discord queue full ({QUEUE_CAPACITY}); post dropped");
+            return;
+        }
+        queue.push_back(post);
+    }
+    WAKE.notify_one();
+}
+
+/// Post the stream-online embed with the stream title and game from Helix
+/// (left empty when Helix does not list the stream yet).
+pub async fn on_stream_online(state: &SharedState, login: &str, name: &str) {
+    if !is_active(&load_settings(state)) {
+        return;
+    }
+    let info = match helix::context(state).await {
+        Ok(ctx) => match ctx
+            .api
+            .get_stream_info(&ctx.token, &ctx.broadcaster_id)
+            .await
+        {
+            Ok(status) => status.info,
+            Err(e) => {
+                tracing::debug!("Stream info unavailable for Discord: {e}");
+                None
+            }
+        },
+        Err(e) => {
+            tracing::debug!("Stream info unavailable for Discord: {e}");
+            None
+        }
+    };
+    let (title, game) = info.map(|i| (i.title, i.game_name)).unwrap_or_default();
+    let url = channel_url(login);
+    notify(
+        state,
+        EVENT_STREAM_ONLINE,
+        &[
+            ("broadcaster", name),
+            ("title", &title),
+            ("game", &game),
+            ("url", &url),
+        ],
+    );
+}
+
+/// Posts waiting in the queue.
+pub fn queued() -> usize {
+    QUEUE.lock().unwrap_or_else(|e| e.into_inner()).len()
+}
+
+/// Current settings and queue length (for the status API).
+pub fn status(state: &SharedState) -> Value {
+    let s = load_settings(state);
+    json!({
+        "enabled": s.enabled,
+        "has_webhook_url": !s.webhook_url.is_empty(),
+        "username": s.username,
+        "queued": queued(),
+    })
+}
+
+enum PostError {
+    /// 429: wait this long and try again.
+    RateLimited(Duration),
+    Failed(String),
+}
+
+async fn post(
+    http: &reqwest::Client,
+    settings: &DiscordSettings,
+    embed: &Embed,
+) -> Result<(), PostError> {
+    let mut body = json!({ "embeds": [embed] });
+    if !settings.username.is_empty() {
+        body["username"] = json!(settings.username);
+    }
+    let response = http
+        .post(&settings.webhook_url)
+        .timeout(HTTP_TIMEOUT)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| PostError::Failed(e.to_string()))?;
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let header_secs = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<f64>().ok());
+        let body_secs = response
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|v| v["retry_after"].as_f64());
+        let secs = body_secs.or(header_secs).unwrap_or(5.0).clamp(0.5, 600.0);
+        return Err(PostError::RateLimited(Duration::from_secs_f64(secs)));
+    }
+    response
+        .error_for_status()
+        .map(|_| ())
+        .map_err(|e| PostError::Failed(e.to_string()))
+}
+
+/// Post a test embed right away, bypassing the queue and the enabled flag.
+pub async fn send_test(state: &SharedState) -> Result<(), String> {
+    let settings = load_settings(state);
+    if settings.webhook_url.is_empty() {
+        return Err("DISCORD_WEBHOOK_URL is not set".into());
+    }
+    let embed = Embed {
+        title: "テスト通知".into(),
+        description: "Discord への通知が設定されました".into(),
+        color: 0x9146ff,
+        url: None,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    match post(&state.http(), &settings, &embed).await {
+        Ok(()) => Ok(()),
+        Err(PostError::RateLimited(wait)) => Err(format!(
+            "rate limited by Discord; retry in {:.1}s",
+            wait.as_secs_f64()
+        )),
+        Err(PostError::Failed(e)) => Err(e),
+    }
+}
+
+/// Background worker draining the queue.
+pub async fn run(state: SharedState) {
+    loop {
+        let next = QUEUE.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+        let Some(mut item) = next else {
+            WAKE.notified().await;
+            continue;
+        };
+        let settings = load_settings(&state);
+        if !is_active(&settings) {
+            // Turned off since the post was queued.
+            continue;
+        }
+        match post(&state.http(), &settings, &item.embed).await {
+            Ok(()) => {
+                tracing::debug!(event = item.event, "Discord notification sent");
+                tokio::time::sleep(MIN_INTERVAL).await;
+            }
+            Err(PostError::RateLimited(wait)) => {
+                tracing::warn!(
+                    event = item.event,
+                    "Discord rate limit hit; waiting {:.1}s",
+                    wait.as_secs_f64()
+                );
+                QUEUE
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push_front(item);
+                tokio::time::sleep(wait).await;
+            }
+            Err(PostError::Failed(e)) => {
+                item.attempts += 1;
+                if item.attempts < MAX_ATTEMPTS {
+                    tracing::warn!(
+                        event = item.event,
+                        attempts = item.attempts,
+                        "Discord notification failed; retrying: {e}"
+                    );
+                    QUEUE
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .push_front(item);
+                    tokio::time::sleep(RETRY_DELAY).await;
+                } else {
+                    tracing::error!(
+                        event = item.event,
+                        "Discord notification dropped after {MAX_ATTEMPTS} attempts: {e}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embed_expands_placeholders() {
+        let def = event_def(EVENT_RAID).unwrap();
+        let embed = embed(
+            &default_template(def),
+            &[
+                ("url", "https://www.twitch.tv/alice"),
+                ("user", "bob"),
+                ("viewers", "42"),
+            ],
+        );
+        assert_eq!(embed.title, "bob さんからレイド");
+        assert_eq!(embed.description, "42 人のレイドが来ました");
+        assert_eq!(embed.url.as_deref(), Some("https://www.twitch.tv/alice"));
+    }
+
+    #[test]
+    fn test_empty_lines_are_trimmed() {
+        let def = event_def(EVENT_STREAM_ONLINE).unwrap();
+        let embed = embed(
+            &default_template(def),
+            &[
+                ("broadcaster", "alice"),
+                ("title", ""),
+                ("game", ""),
+                ("url", ""),
+            ],
+        );
+        assert_eq!(embed.title, "alice が配信を開始しました");
+        assert_eq!(embed.description, "");
+        assert_eq!(embed.url, None);
+    }
+}
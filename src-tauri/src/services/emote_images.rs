@@ -0,0 +1,45 @@
+//! Emote image download with the on-disk image cache.
+
+use image::DynamicImage;
+
+use crate::app::SharedState;
+use crate::services::cache::CacheService;
+
+/// Static (non-animated) CDN URL for an emote.
+pub fn emote_url(emote_id: &str) -> String {
+    format!("https://static-cdn.jtvnw.net/emoticons/v2/{emote_id}/static/light/2.0")
+}
+
+/// Fetch an image by URL, serving from the cache when possible.
+pub async fn fetch_image(state: &SharedState, url: &str) -> Result<DynamicImage, String> {
+    let cache = CacheService::new(state.db().clone(), state.data_dir().clone());
+
+    if let Ok(Some(entry)) = cache.get_entry(url) {
+        if let Ok(bytes) = std::fs::read(&entry.file_path) {
+            if let Ok(img) = image::load_from_memory(&bytes) {
+                return Ok(img);
+            }
+        }
+    }
+
+    let resp = state
+        .http()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {} for {url}", resp.status()));
+    }
+    let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+    let img = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+    if let Err(e) = cache.add_entry(url, &bytes) {
+        tracing::debug!("Failed to cache image {url}: {e}");
+    }
+    Ok(img)
+}
+
+/// Fetch an emote image by ID.
+pub async fn fetch_emote(state: &SharedState, emote_id: &str) -> Result<DynamicImage, String> {
+    fetch_image(state, &emote_url(emote_id)).await
+}
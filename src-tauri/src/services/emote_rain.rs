@@ -0,0 +1,220 @@
+//! Emote rain: detect bursts of the same emote in chat.
+//!
+//! Every chat message counts once per distinct emote it contains. When an
+//! emote is used `EMOTE_RAIN_THRESHOLD` times within
+//! `EMOTE_RAIN_WINDOW_SECONDS`, an `emote_rain` event is broadcast unless
+//! the emote is still on cooldown (tracked in the DB, optionally per emote).
+//! `intensity` (1-5) grows the faster the threshold was reached.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+use tokio::sync::RwLock;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::send_ws;
+
+const MAX_INTENSITY: u32 = 5;
+
+static DETECTOR: LazyLock<RwLock<BurstDetector>> =
+    LazyLock::new(|| RwLock::new(BurstDetector::default()));
+
+/// Sliding-window usage counter per emote.
+#[derive(Debug, Default)]
+struct BurstDetector {
+    uses: HashMap<String, VecDeque<Instant>>,
+}
+
+impl BurstDetector {
+    /// Record one use and return the uses still inside `window`, oldest first.
+    fn record(&mut self, emote_id: &str, now: Instant, window: Duration) -> &VecDeque<Instant> {
+        let entry = self.uses.entry(emote_id.to_string()).or_default();
+        entry.push_back(now);
+        while entry
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) > window)
+        {
+            entry.pop_front();
+        }
+        entry
+    }
+
+    fn clear(&mut self, emote_id: &str) {
+        self.uses.remove(emote_id);
+    }
+
+    /// Drop emotes with no use inside `window` (keeps the map small).
+    fn prune(&mut self, now: Instant, window: Duration) {
+        self.uses.retain(|_, uses| {
+            uses.back()
+                .is_some_and(|t| now.saturating_duration_since(*t) <= window)
+        });
+    }
+}
+
+struct RainSettings {
+    enabled: bool,
+    threshold: usize,
+    window: Duration,
+    cooldown_secs: i64,
+}
+
+fn load_settings(state: &SharedState) -> RainSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    RainSettings {
+        enabled: get("EMOTE_RAIN_ENABLED") == "true",
+        threshold: get("EMOTE_RAIN_THRESHOLD").parse().unwrap_or(5).max(1),
+        window: Duration::from_secs(get("EMOTE_RAIN_WINDOW_SECONDS").parse().unwrap_or(10)),
+        cooldown_secs: get("EMOTE_RAIN_COOLDOWN_SECONDS").parse().unwrap_or(60),
+    }
+}
+
+/// Current settings as JSON (for the API).
+pub fn settings_json(state: &SharedState) -> Value {
+    let s = load_settings(state);
+    json!({
+        "enabled": s.enabled,
+        "threshold": s.threshold,
+        "window_seconds": s.window.as_secs(),
+        "cooldown_seconds": s.cooldown_secs,
+    })
+}
+
+/// Animated (when available) emote image for the overlay.
+pub fn rain_image_url(emote_id: &str) -> String {
+    format!("https://static-cdn.jtvnw.net/emoticons/v2/{emote_id}/default/dark/3.0")
+}
+
+/// Count the emotes of one chat message and start a rain on a burst.
+pub async fn observe_message(state: &SharedState, fragments: &Value) {
+    let settings = load_settings(state);
+    if !settings.enabled {
+        return;
+    }
+    let emotes = distinct_emotes(fragments);
+    if emotes.is_empty() {
+        return;
+    }
+
+    let now = Instant::now();
+    let mut bursts = Vec::new();
+    {
+        let mut detector = DETECTOR.write().await;
+        detector.prune(now, settings.window);
+        for (id, name) in emotes {
+            let uses = detector.record(&id, now, settings.window);
+            if uses.len() >= settings.threshold {
+                let elapsed = uses.front().map_or(Duration::ZERO, |first| {
+                    now.saturating_duration_since(*first)
+                });
+                bursts.push((id.clone(), name, uses.len(), elapsed));
+                detector.clear(&id);
+            }
+        }
+    }
+
+    for (id, name, count, elapsed) in bursts {
+        let fired = state.db().try_fire_emote_rain(
+            &id,
+            &name,
+            settings.cooldown_secs,
+            chrono::Utc::now().timestamp(),
+        );
+        match fired {
+            Ok(true) => {
+                let intensity = intensity(elapsed, settings.window);
+                tracing::info!(emote = name, count, intensity, "Emote rain");
+                broadcast(state, &id, &name, count, intensity);
+            }
+            Ok(false) => tracing::debug!(emote = name, "Emote rain on cooldown"),
+            Err(e) => tracing::warn!("Failed to record emote rain: {e}"),
+        }
+    }
+}
+
+/// Broadcast an `emote_rain` event.
+pub fn broadcast(state: &SharedState, emote_id: &str, name: &str, count: usize, intensity: u32) {
+    send_ws(
+        state,
+        "emote_rain",
+        json!({
+            "emote_id": emote_id,
+            "emote_name": name,
+            "url": rain_image_url(emote_id),
+            "count": count,
+            "intensity": intensity,
+        }),
+    );
+}
+
+/// Distinct `(id, name)` emotes in EventSub message fragments.
+fn distinct_emotes(fragments: &Value) -> Vec<(String, String)> {
+    let mut seen = HashSet::new();
+    fragments
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|f| f["type"].as_str() == Some("emote"))
+        .filter_map(|f| {
+            let id = f["emote"]["id"].as_str()?.to_string();
+            let name = f["text"].as_str().unwrap_or_default().to_string();
+            seen.insert(id.clone()).then_some((id, name))
+        })
+        .collect()
+}
+
+/// 1 when the threshold took the whole window, up to 5 when it was instant.
+fn intensity(elapsed: Duration, window: Duration) -> u32 {
+    if window.is_zero() {
+        return 1;
+    }
+    let ratio = 1.0 - elapsed.as_secs_f64() / window.as_secs_f64();
+    (1 + (ratio.clamp(0.0, 1.0) * f64::from(MAX_INTENSITY - 1)).round() as u32).min(MAX_INTENSITY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detector_window() {
+        let mut d = BurstDetector::default();
+        let t0 = Instant::now();
+        let window = Duration::from_secs(10);
+        assert_eq!(d.record("e", t0, window).len(), 1);
+        assert_eq!(d.record("e", t0 + Duration::from_secs(5), window).len(), 2);
+        // The first use has left the window.
+        assert_eq!(d.record("e", t0 + Duration::from_secs(12), window).len(), 2);
+        d.prune(t0 + Duration::from_secs(30), window);
+        assert!(d.uses.is_empty());
+    }
+
+    #[test]
+    fn test_distinct_emotes() {
+        let fragments = json!([
+            { "type": "emote", "text": "Kappa", "emote": { "id": "25" } },
+            { "type": "text", "text": " " },
+            { "type": "emote", "text": "Kappa", "emote": { "id": "25" } },
+            { "type": "emote", "text": "LUL", "emote": { "id": "425618" } },
+        ]);
+        assert_eq!(
+            distinct_emotes(&fragments),
+            vec![
+                ("25".to_string(), "Kappa".to_string()),
+                ("425618".to_string(), "LUL".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_intensity() {
+        let window = Duration::from_secs(10);
+        assert_eq!(intensity(Duration::ZERO, window), 5);
+        assert_eq!(intensity(Duration::from_secs(10), window), 1);
+        assert_eq!(intensity(Duration::from_secs(5), window), 3);
+    }
+}
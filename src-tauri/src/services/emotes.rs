@@ -0,0 +1,880 @@
+//! Emote catalog for the control panel.
+//!
+//! Global, broadcaster, priority-channel (`EMOTE_PRIORITY_CHANNEL`) and
+//! followed-channel emotes are fetched into an [`EmoteCache`] at startup and
+//! whenever the broadcaster or priority channel changes, so
+//! `GET /api/emotes` answers from memory instead of waiting on Helix.
+//! [`groups`] and [`group_page`] serve the same data one group at a time
+//! for clients that should not load every channel at once, and [`search`]
+//! backs the emote autocomplete of the chat input.
+//!
+//! Emotes the broadcaster sends are stored in `recent_emotes` and listed
+//! first as the `recent` group, so every control panel device sees the same
+//! list.
+//!
+//! 7TV, BetterTTV and FrankerFaceZ emotes (`THIRD_PARTY_EMOTE_PROVIDERS`)
+//! are fetched alongside as global and broadcaster groups tagged with their
+//! `source`. Chat shows them as plain words, so [`resolve_fragments`] turns
+//! their names back into emote fragments for overlays and printing.
+//!
+//! Each finished warm-up is stored in the image cache as one snapshot;
+//! after a restart the snapshot is served until the next warm-up replaces
+//! it.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock as SyncRwLock};
+use std::time::Duration;
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::sync::RwLock;
+use twitch_client::emote_providers::{EmoteProviders, Provider};
+use twitch_client::emotes::{Emote, EmoteCache, GLOBAL_OWNER, SOURCE_TWITCH};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::cache::CacheService;
+use crate::services::helix;
+
+/// Settings whose change triggers a new warm-up.
+pub const SETTING_KEYS: &[&str] = &[
+    "TWITCH_USER_ID",
+    "EMOTE_PRIORITY_CHANNEL",
+    "THIRD_PARTY_EMOTE_PROVIDERS",
+];
+
+/// Followed channels fetched at most (newest follows first).
+const MAX_FOLLOWED_CHANNELS: usize = 100;
+/// Parallel Helix requests while fetching followed channels.
+const FETCH_CONCURRENCY: usize = 8;
+/// Wait before retrying a startup warm-up that could not reach Helix.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+/// Image-cache key of the stored snapshot.
+const SNAPSHOT_KEY: &str = "twitch-overlay://emotes/catalog";
+/// Emotes whose last use is stored for the recent group and search ranking.
+const RECENT_LIMIT: i64 = 500;
+/// Emotes listed in the recent group.
+const RECENT_GROUP_SIZE: i64 = 40;
+/// Image of a recently used emote that is not in the cache.
+const CDN_URL_TEMPLATE: &str = "https://static-cdn.jtvnw.net/emoticons/v2/{id}/default/dark/2.0";
+
+/// Group ID of the broadcaster's recently sent emotes.
+pub const RECENT_GROUP_ID: &str = "recent";
+
+/// Group role; the variant order is the display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupKind {
+    Recent,
+    Priority,
+    Broadcaster,
+    Followed,
+    Global,
+}
+
+/// An emote group without its emotes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmoteGroup {
+    /// Broadcaster ID, `global` or `recent`.
+    pub id: String,
+    pub kind: GroupKind,
+    pub channel_login: String,
+    pub channel_name: String,
+    /// `twitch`, `7tv`, `bttv` or `ffz`.
+    #[serde(default = "twitch_source")]
+    pub source: String,
+}
+
+fn twitch_source() -> String {
+    SOURCE_TWITCH.to_string()
+}
+
+/// An emote as listed by the API.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmoteItem {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub animated: bool,
+    pub emote_type: String,
+    pub tier: String,
+    pub source: String,
+}
+
+impl From<&Emote> for EmoteItem {
+    fn from(emote: &Emote) -> Self {
+        Self {
+            id: emote.id.clone(),
+            name: emote.name.clone(),
+            url: emote.images.url_2x.clone(),
+            animated: emote.is_animated(),
+            emote_type: emote.emote_type.clone(),
+            tier: emote.tier.clone(),
+            source: emote.source.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupWithEmotes {
+    #[serde(flatten)]
+    pub group: EmoteGroup,
+    pub emotes: Vec<EmoteItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Catalog {
+    pub groups: Vec<GroupWithEmotes>,
+    /// Unix seconds of the last completed warm-up.
+    pub warmed_at: Option<i64>,
+    pub warming: bool,
+}
+
+/// A group with the number of cached emotes, without the emotes.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupSummary {
+    #[serde(flatten)]
+    pub group: EmoteGroup,
+    pub emote_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupList {
+    pub groups: Vec<GroupSummary>,
+    pub warmed_at: Option<i64>,
+    pub warming: bool,
+}
+
+/// One page of a group's emotes.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmotePage {
+    #[serde(flatten)]
+    pub group: EmoteGroup,
+    pub emotes: Vec<EmoteItem>,
+    pub total: usize,
+    pub offset: usize,
+    /// Offset of the next page; `None` on the last one.
+    pub next_offset: Option<usize>,
+}
+
+/// An emote found by [`search`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub emote: EmoteItem,
+    pub group_id: String,
+    pub channel_login: String,
+    /// Whether the broadcaster can send it without subscribing or cheering.
+    pub usable: bool,
+    /// Unix seconds the broadcaster last sent it.
+    pub last_used_at: Option<i64>,
+}
+
+/// Stored form of a warm-up.
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    warmed_at: i64,
+    sets: Vec<(EmoteGroup, Vec<Emote>)>,
+}
+
+struct CatalogState {
+    cache: EmoteCache,
+    groups: Vec<EmoteGroup>,
+    warmed_at: Option<i64>,
+    warming: bool,
+    /// A warm-up was requested while one was running.
+    rerun: bool,
+}
+
+static STATE: LazyLock<RwLock<CatalogState>> = LazyLock::new(|| {
+    RwLock::new(CatalogState {
+        cache: EmoteCache::new(String::new()),
+        groups: Vec::new(),
+        warmed_at: None,
+        warming: false,
+        rerun: false,
+    })
+});
+
+/// Third-party emotes by name; channel emotes shadow global ones. Kept
+/// apart from [`STATE`] so chat handling can read it synchronously.
+static THIRD_PARTY: LazyLock<SyncRwLock<HashMap<String, EmoteItem>>> =
+    LazyLock::new(Default::default);
+
+/// Order groups for display: priority channel, broadcaster, followed
+/// channels, then global. A channel appears once, in its first role.
+fn ordered_groups(mut groups: Vec<EmoteGroup>) -> Vec<EmoteGroup> {
+    groups.sort_by_key(|g| g.kind);
+    let mut out: Vec<EmoteGroup> = Vec::with_capacity(groups.len());
+    for group in groups {
+        if !out.iter().any(|g| g.id == group.id) {
+            out.push(group);
+        }
+    }
+    out
+}
+
+fn recent_group() -> EmoteGroup {
+    EmoteGroup {
+        id: RECENT_GROUP_ID.into(),
+        kind: GroupKind::Recent,
+        channel_login: String::new(),
+        channel_name: String::new(),
+        source: SOURCE_TWITCH.into(),
+    }
+}
+
+/// The broadcaster's recently sent emotes, newest first. Emotes missing
+/// from the cache (e.g. from channels that are not fetched) keep the name
+/// they were sent with and link to the Twitch CDN image.
+fn recent_emotes(state: &SharedState, cache: &EmoteCache) -> Vec<EmoteItem> {
+    let rows = match state.db().recent_emotes(RECENT_GROUP_SIZE) {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::warn!("Failed to load recent emotes: {e}");
+            return Vec::new();
+        }
+    };
+    rows.into_iter()
+        .map(|row| match cache.get(&row.emote_id) {
+            Some(emote) => EmoteItem::from(emote),
+            None => EmoteItem {
+                url: CDN_URL_TEMPLATE.replace("{id}", &row.emote_id),
+                id: row.emote_id,
+                name: row.name,
+                animated: false,
+                emote_type: String::new(),
+                tier: String::new(),
+                source: SOURCE_TWITCH.into(),
+            },
+        })
+        .collect()
+}
+
+/// Everything cached, in display order, after the recent group (omitted
+/// while empty).
+pub async fn catalog(state: &SharedState) -> Catalog {
+    let cached = STATE.read().await;
+    let recent = recent_emotes(state, &cached.cache);
+    let mut groups = Vec::with_capacity(cached.groups.len() + 1);
+    if !recent.is_empty() {
+        groups.push(GroupWithEmotes {
+            group: recent_group(),
+            emotes: recent,
+        });
+    }
+    groups.extend(cached.groups.iter().map(|group| {
+        GroupWithEmotes {
+            group: group.clone(),
+            emotes: cached
+                .cache
+                .channel_emotes(&group.id)
+                .into_iter()
+                .map(EmoteItem::from)
+                .collect(),
+        }
+    }));
+    Catalog {
+        groups,
+        warmed_at: cached.warmed_at,
+        warming: cached.warming,
+    }
+}
+
+/// Group metadata in display order, after the recent group (omitted while
+/// empty).
+pub async fn groups(state: &SharedState) -> GroupList {
+    let cached = STATE.read().await;
+    let recent = recent_emotes(state, &cached.cache).len();
+    let mut groups = Vec::with_capacity(cached.groups.len() + 1);
+    if recent > 0 {
+        groups.push(GroupSummary {
+            group: recent_group(),
+            emote_count: recent,
+        });
+    }
+    groups.extend(cached.groups.iter().map(|group| GroupSummary {
+        group: group.clone(),
+        emote_count: cached.cache.channel_emotes(&group.id).len(),
+    }));
+    GroupList {
+        groups,
+        warmed_at: cached.warmed_at,
+        warming: cached.warming,
+    }
+}
+
+/// `(start, next_offset)` of the page at `offset` in `total` items.
+fn page_bounds(total: usize, offset: usize, limit: usize) -> (usize, Option<usize>) {
+    let start = offset.min(total);
+    let end = start.saturating_add(limit).min(total);
+    (start, (end < total).then_some(end))
+}
+
+/// Up to `limit` emotes of group `id` starting at `offset`; `None` when the
+/// group is not cached.
+pub async fn group_page(
+    state: &SharedState,
+    id: &str,
+    offset: usize,
+    limit: usize,
+) -> Option<EmotePage> {
+    let cached = STATE.read().await;
+    if id == RECENT_GROUP_ID {
+        let emotes = recent_emotes(state, &cached.cache);
+        let (start, next_offset) = page_bounds(emotes.len(), offset, limit);
+        let end = next_offset.unwrap_or(emotes.len());
+        return Some(EmotePage {
+            emotes: emotes[start..end].to_vec(),
+            total: emotes.len(),
+            offset: start,
+            next_offset,
+            group: recent_group(),
+        });
+    }
+    let group = cached.groups.iter().find(|g| g.id == id)?.clone();
+    let emotes = cached.cache.channel_emotes(&group.id);
+    let (start, next_offset) = page_bounds(emotes.len(), offset, limit);
+    let end = next_offset.unwrap_or(emotes.len());
+    Some(EmotePage {
+        emotes: emotes[start..end]
+            .iter()
+            .copied()
+            .map(EmoteItem::from)
+            .collect(),
+        total: emotes.len(),
+        offset: start,
+        next_offset,
+        group,
+    })
+}
+
+/// Store that the broadcaster sent these `(emote ID, name)` pairs at `at`
+/// and push the new recent group to the control panels as an
+/// `emotes_recent` WebSocket event.
+pub async fn record_use(state: &SharedState, emotes: &[(&str, &str)], at: i64) {
+    if let Err(e) = state.db().record_emote_uses(emotes, at, RECENT_LIMIT) {
+        tracing::warn!("Failed to record emote use: {e}");
+        return;
+    }
+    let recent = {
+        let cached = STATE.read().await;
+        recent_emotes(state, &cached.cache)
+    };
+    let data = json!({ "group": recent_group(), "emotes": recent });
+    let msg = json!({ "type": "emotes_recent", "data": data });
+    let _ = state.ws_sender().send(msg.to_string());
+}
+
+/// How a query matched an emote name; earlier variants rank first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchKind {
+    Exact,
+    Prefix,
+    Substring,
+    /// The query's characters appear in order (`lul` → `LuvLuL`).
+    Fuzzy,
+}
+
+/// Match `name` against an already lowercased query.
+fn match_kind(query: &str, name: &str) -> Option<MatchKind> {
+    let name = name.to_lowercase();
+    if name == query {
+        Some(MatchKind::Exact)
+    } else if name.starts_with(query) {
+        Some(MatchKind::Prefix)
+    } else if name.contains(query) {
+        Some(MatchKind::Substring)
+    } else {
+        let mut chars = name.chars();
+        query
+            .chars()
+            .all(|q| chars.any(|c| c == q))
+            .then_some(MatchKind::Fuzzy)
+    }
+}
+
+/// 0: always usable (global and own emotes), 1: free for followers,
+/// 2: needs a subscription or bits.
+fn usability(group: &EmoteGroup, emote: &Emote) -> u8 {
+    if matches!(group.kind, GroupKind::Global | GroupKind::Broadcaster) {
+        0
+    } else if emote.emote_type == "follower" {
+        1
+    } else {
+        2
+    }
+}
+
+/// Up to `limit` emotes whose name matches `query`, best first: by match
+/// quality, then usability, then most recently used. `channel` (broadcaster
+/// ID or login, or `global`) restricts the search to one group.
+pub async fn search(
+    state: &SharedState,
+    query: &str,
+    channel: Option<&str>,
+    limit: usize,
+) -> Vec<SearchHit> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let recent: HashMap<String, i64> = match state.db().recent_emotes(RECENT_LIMIT) {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|row| (row.emote_id, row.last_used_at))
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to load recent emotes: {e}");
+            HashMap::new()
+        }
+    };
+    let cached = STATE.read().await;
+    let mut ranked = Vec::new();
+    for group in &cached.groups {
+        if channel.is_some_and(|c| group.id != c && !group.channel_login.eq_ignore_ascii_case(c)) {
+            continue;
+        }
+        for emote in cached.cache.channel_emotes(&group.id) {
+            let Some(kind) = match_kind(&query, &emote.name) else {
+                continue;
+            };
+            let usability = usability(group, emote);
+            let last_used_at = recent.get(&emote.id).copied();
+            ranked.push((
+                (kind, usability, Reverse(last_used_at), emote.name.len()),
+                SearchHit {
+                    emote: EmoteItem::from(emote),
+                    group_id: group.id.clone(),
+                    channel_login: group.channel_login.clone(),
+                    usable: usability < 2,
+                    last_used_at,
+                },
+            ));
+        }
+    }
+    ranked.sort_by_key(|(key, _)| *key);
+    ranked.truncate(limit);
+    ranked.into_iter().map(|(_, hit)| hit).collect()
+}
+
+fn install(
+    state: &mut CatalogState,
+    mut cache: EmoteCache,
+    sets: Vec<(EmoteGroup, Vec<Emote>)>,
+    at: i64,
+) {
+    let mut groups = Vec::with_capacity(sets.len());
+    for (group, emotes) in sets {
+        cache.insert_channel(&group.id, emotes);
+        groups.push(group);
+    }
+    state.groups = ordered_groups(groups);
+    let mut names = HashMap::new();
+    for group in state.groups.iter().rev() {
+        if group.source == SOURCE_TWITCH {
+            continue;
+        }
+        for emote in cache.channel_emotes(&group.id) {
+            names.insert(emote.name.clone(), EmoteItem::from(emote));
+        }
+    }
+    *THIRD_PARTY.write().unwrap_or_else(|e| e.into_inner()) = names;
+    state.cache = cache;
+    state.warmed_at = Some(at);
+}
+
+/// Text of a fragment split into words and the whitespace between them,
+/// with third-party emote names in `names` as emote fragments.
+fn split_fragment(text: &str, names: &HashMap<String, EmoteItem>, out: &mut Vec<Value>) {
+    let mut plain = String::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(end);
+        let space = tail.len() - tail.trim_start().len();
+        match names.get(word) {
+            Some(emote) => {
+                if !plain.is_empty() {
+                    out.push(json!({ "type": "text", "text": std::mem::take(&mut plain) }));
+                }
+                out.push(json!({
+                    "type": "emote",
+                    "text": word,
+                    "emote": { "id": emote.id, "url": emote.url, "source": emote.source },
+                }));
+            }
+            None => plain.push_str(word),
+        }
+        plain.push_str(&tail[..space]);
+        rest = &tail[space..];
+    }
+    if !plain.is_empty() {
+        out.push(json!({ "type": "text", "text": plain }));
+    }
+}
+
+fn resolve_with(fragments: &Value, names: &HashMap<String, EmoteItem>) -> Value {
+    let Some(items) = fragments.as_array() else {
+        return fragments.clone();
+    };
+    let mut out = Vec::with_capacity(items.len());
+    for item in items {
+        match (item["type"].as_str(), item["text"].as_str()) {
+            (Some("text"), Some(text)) if !names.is_empty() => {
+                split_fragment(text, names, &mut out)
+            }
+            _ => out.push(item.clone()),
+        }
+    }
+    Value::Array(out)
+}
+
+/// EventSub message fragments with third-party emote names in text
+/// fragments split out as `emote` fragments. Those carry the image `url`
+/// and `source` in `emote` next to the prefixed ID.
+pub fn resolve_fragments(fragments: &Value) -> Value {
+    let names = THIRD_PARTY.read().unwrap_or_else(|e| e.into_inner());
+    resolve_with(fragments, &names)
+}
+
+/// Enabled providers from `THIRD_PARTY_EMOTE_PROVIDERS`.
+fn enabled_providers(state: &SharedState) -> Vec<Provider> {
+    SettingsManager::new(state.db().clone())
+        .get_setting("THIRD_PARTY_EMOTE_PROVIDERS")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(Provider::parse)
+        .collect()
+}
+
+/// Global and broadcaster emotes of the enabled third-party providers.
+/// Failures only skip the affected set.
+async fn fetch_third_party(
+    state: &SharedState,
+    broadcaster_id: &str,
+) -> Vec<(EmoteGroup, Vec<Emote>)> {
+    let fetcher = EmoteProviders::with_http(state.http());
+    let mut sets = Vec::new();
+    for provider in enabled_providers(state) {
+        let source = provider.as_str();
+        let group = |id: String, kind| EmoteGroup {
+            id,
+            kind,
+            channel_login: String::new(),
+            channel_name: provider.label().to_string(),
+            source: source.to_string(),
+        };
+        match fetcher.get_global_emotes(provider).await {
+            Ok(emotes) => sets.push((group(format!("{source}:global"), GroupKind::Global), emotes)),
+            Err(e) => tracing::warn!(provider = source, "Failed to fetch global emotes: {e}"),
+        }
+        match fetcher.get_channel_emotes(provider, broadcaster_id).await {
+            Ok(emotes) if !emotes.is_empty() => sets.push((
+                group(format!("{source}:{broadcaster_id}"), GroupKind::Broadcaster),
+                emotes,
+            )),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(provider = source, "Failed to fetch channel emotes: {e}"),
+        }
+    }
+    sets
+}
+
+fn snapshot_service(state: &SharedState) -> CacheService {
+    CacheService::new(state.db().clone(), state.data_dir().clone())
+}
+
+/// Serve the stored snapshot until the first warm-up finishes.
+async fn load_snapshot(state: &SharedState) {
+    let entry = match snapshot_service(state).get_entry(SNAPSHOT_KEY) {
+        Ok(Some(entry)) => entry,
+        _ => return,
+    };
+    let snapshot: Snapshot = match std::fs::read(&entry.file_path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|e| e.to_string()))
+    {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            tracing::debug!("Ignoring stored emote catalog: {e}");
+            return;
+        }
+    };
+    let mut catalog = STATE.write().await;
+    if catalog.warmed_at.is_none() {
+        let count = snapshot.sets.len();
+        install(
+            &mut catalog,
+            EmoteCache::with_http(String::new(), state.http()),
+            snapshot.sets,
+            snapshot.warmed_at,
+        );
+        tracing::info!(groups = count, "Loaded stored emote catalog");
+    }
+}
+
+fn store_snapshot(state: &SharedState, snapshot: &Snapshot) {
+    let result = serde_json::to_vec(snapshot)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| {
+            snapshot_service(state)
+                .add_entry(SNAPSHOT_KEY, &bytes)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        tracing::warn!("Failed to store emote catalog: {e}");
+    }
+}
+
+/// Fetch every group from Helix and the third-party providers.
+async fn fetch_all(
+    state: &SharedState,
+) -> Result<(EmoteCache, Vec<(EmoteGroup, Vec<Emote>)>), String> {
+    let ctx = helix::context(state).await?;
+    let priority_login = SettingsManager::new(state.db().clone())
+        .get_setting("EMOTE_PRIORITY_CHANNEL")
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    let fetcher = EmoteCache::with_http(state.config().await.client_id.clone(), state.http());
+    let mut sets = Vec::new();
+
+    let global = fetcher
+        .get_global_emotes(&ctx.token)
+        .await
+        .map_err(|e| format!("global emotes: {e}"))?;
+    sets.push((
+        EmoteGroup {
+            id: GLOBAL_OWNER.to_string(),
+            kind: GroupKind::Global,
+            channel_login: String::new(),
+            channel_name: String::new(),
+            source: SOURCE_TWITCH.into(),
+        },
+        global,
+    ));
+
+    let mut channels = vec![(
+        ctx.broadcaster_id.clone(),
+        GroupKind::Broadcaster,
+        String::new(),
+        String::new(),
+    )];
+    if !priority_login.is_empty() {
+        match ctx.api.get_user_by_login(&ctx.token, &priority_login).await {
+            Ok(Some(user)) => {
+                channels.push((user.id, GroupKind::Priority, user.login, user.display_name))
+            }
+            Ok(None) => tracing::warn!("EMOTE_PRIORITY_CHANNEL {priority_login} not found"),
+            Err(e) => tracing::warn!("Failed to look up {priority_login}: {e}"),
+        }
+    }
+    match ctx
+        .api
+        .get_followed_channels(&ctx.token, &ctx.broadcaster_id, MAX_FOLLOWED_CHANNELS)
+        .await
+    {
+        Ok(followed) => channels.extend(followed.into_iter().map(|c| {
+            (
+                c.broadcaster_id,
+                GroupKind::Followed,
+                c.broadcaster_login,
+                c.broadcaster_name,
+            )
+        })),
+        // Tokens issued before `user:read:follows` was requested land here.
+        Err(e) => tracing::warn!("Failed to fetch followed channels: {e}"),
+    }
+
+    let fetched: Vec<_> = futures::stream::iter(channels)
+        .map(|(id, kind, login, name)| {
+            let (fetcher, token) = (&fetcher, &ctx.token);
+            async move {
+                let emotes = fetcher.get_channel_emotes(token, &id).await;
+                (
+                    EmoteGroup {
+                        id,
+                        kind,
+                        channel_login: login,
+                        channel_name: name,
+                        source: SOURCE_TWITCH.into(),
+                    },
+                    emotes,
+                )
+            }
+        })
+        .buffered(FETCH_CONCURRENCY)
+        .collect()
+        .await;
+    for (group, emotes) in fetched {
+        match emotes {
+            Ok(emotes) if !emotes.is_empty() || group.kind != GroupKind::Followed => {
+                sets.push((group, emotes));
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!(channel = %group.id, "Failed to fetch channel emotes: {e}"),
+        }
+    }
+    sets.extend(fetch_third_party(state, &ctx.broadcaster_id).await);
+    Ok((fetcher, sets))
+}
+
+/// Refresh the catalog from Helix. A call made while a warm-up is running
+/// makes that warm-up run once more instead of starting another.
+pub async fn warm_up(state: &SharedState) -> Result<(), String> {
+    {
+        let mut catalog = STATE.write().await;
+        if catalog.warming {
+            catalog.rerun = true;
+            return Ok(());
+        }
+        catalog.warming = true;
+    }
+    loop {
+        let started = std::time::Instant::now();
+        let result = fetch_all(state).await;
+        let mut catalog = STATE.write().await;
+        let outcome = match result {
+            Ok((cache, sets)) => {
+                let warmed_at = chrono::Utc::now().timestamp();
+                let snapshot = Snapshot { warmed_at, sets };
+                store_snapshot(state, &snapshot);
+                let groups = snapshot.sets.len();
+                install(&mut catalog, cache, snapshot.sets, warmed_at);
+                tracing::info!(
+                    groups,
+                    emotes = catalog.cache.len(),
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    "Emote cache warmed up"
+                );
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+        if !std::mem::take(&mut catalog.rerun) {
+            catalog.warming = false;
+            return outcome;
+        }
+    }
+}
+
+/// Start a warm-up in the background.
+pub fn request_warm_up(state: &SharedState) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = warm_up(&state).await {
+            tracing::warn!("Emote warm-up failed: {e}");
+        }
+    });
+}
+
+/// Startup task: serve the stored snapshot, then warm up once Helix is
+/// reachable.
+pub async fn run(state: SharedState) {
+    load_snapshot(&state).await;
+    loop {
+        match warm_up(&state).await {
+            Ok(()) => return,
+            Err(e) => tracing::debug!("Emote warm-up postponed: {e}"),
+        }
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(id: &str, kind: GroupKind) -> EmoteGroup {
+        EmoteGroup {
+            id: id.into(),
+            kind,
+            channel_login: String::new(),
+            channel_name: String::new(),
+            source: SOURCE_TWITCH.into(),
+        }
+    }
+
+    #[test]
+    fn test_ordered_groups() {
+        let groups = ordered_groups(vec![
+            group(GLOBAL_OWNER, GroupKind::Global),
+            group("1", GroupKind::Broadcaster),
+            group("3", GroupKind::Followed),
+            group("2", GroupKind::Priority),
+            group("2", GroupKind::Followed),
+        ]);
+        let ids: Vec<_> = groups.iter().map(|g| (g.id.as_str(), g.kind)).collect();
+        assert_eq!(
+            ids,
+            [
+                ("2", GroupKind::Priority),
+                ("1", GroupKind::Broadcaster),
+                ("3", GroupKind::Followed),
+                (GLOBAL_OWNER, GroupKind::Global),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_page_bounds() {
+        assert_eq!(page_bounds(5, 0, 2), (0, Some(2)));
+        assert_eq!(page_bounds(5, 4, 2), (4, None));
+        assert_eq!(page_bounds(5, 3, 2), (3, None));
+        assert_eq!(page_bounds(5, 9, 2), (5, None));
+        assert_eq!(page_bounds(0, 0, 2), (0, None));
+    }
+
+    #[test]
+    fn test_match_kind() {
+        assert_eq!(match_kind("kappa", "Kappa"), Some(MatchKind::Exact));
+        assert_eq!(match_kind("kap", "KappaPride"), Some(MatchKind::Prefix));
+        assert_eq!(
+            match_kind("pride", "KappaPride"),
+            Some(MatchKind::Substring)
+        );
+        assert_eq!(match_kind("kpp", "KappaPride"), Some(MatchKind::Fuzzy));
+        assert_eq!(match_kind("xyz", "KappaPride"), None);
+        assert!(MatchKind::Prefix < MatchKind::Fuzzy);
+    }
+
+    #[test]
+    fn test_resolve_fragments() {
+        let emote = EmoteItem {
+            id: "7tv:01A".into(),
+            name: "catJAM".into(),
+            url: "https://cdn.7tv.app/emote/01A/2x.gif".into(),
+            animated: true,
+            emote_type: String::new(),
+            tier: String::new(),
+            source: "7tv".into(),
+        };
+        let names = HashMap::from([("catJAM".to_string(), emote)]);
+        let fragments = json!([
+            { "type": "text", "text": "hi catJAM  catJAMs catJAM" },
+            { "type": "emote", "text": "Kappa", "emote": { "id": "25" } },
+        ]);
+        let resolved = resolve_with(&fragments, &names);
+        let parts: Vec<(&str, &str)> = resolved
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| (f["type"].as_str().unwrap(), f["text"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            parts,
+            [
+                ("text", "hi "),
+                ("emote", "catJAM"),
+                ("text", "  catJAMs "),
+                ("emote", "catJAM"),
+                ("emote", "Kappa"),
+            ]
+        );
+        assert_eq!(resolved[1]["emote"]["source"], "7tv");
+        assert_eq!(resolve_with(&fragments, &HashMap::new()), fragments);
+    }
+}
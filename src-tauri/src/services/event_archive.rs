@@ -0,0 +1,56 @@
+//! Raw EventSub notification archive.
+//!
+//! Every notification is stored as received so derived data can be rebuilt
+//! and incidents inspected later. The archive is bounded by
+//! `EVENT_ARCHIVE_MAX_MB` of payload; the oldest events are pruned first.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::latency::{self, Stage};
+
+/// Check the size bound once per this many archived events.
+const PRUNE_EVERY: u32 = 100;
+
+static SINCE_PRUNE: AtomicU32 = AtomicU32::new(0);
+
+struct ArchiveSettings {
+    enabled: bool,
+    max_bytes: i64,
+}
+
+fn load_settings(state: &SharedState) -> ArchiveSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let max_mb: i64 = get("EVENT_ARCHIVE_MAX_MB").parse().unwrap_or(50);
+    ArchiveSettings {
+        enabled: get("EVENT_ARCHIVE_ENABLED") != "false",
+        max_bytes: max_mb.max(1) * 1024 * 1024,
+    }
+}
+
+/// Store one notification, pruning the archive every [`PRUNE_EVERY`] events.
+pub fn record(state: &SharedState, event_type: &str, payload: &Value) {
+    let settings = load_settings(state);
+    if !settings.enabled {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = state.db().archive_event(event_type, payload, now) {
+        tracing::warn!("Failed to archive EventSub event: {e}");
+        return;
+    }
+    latency::mark(Stage::DbWrite);
+    if SINCE_PRUNE.fetch_add(1, Ordering::Relaxed) + 1 < PRUNE_EVERY {
+        return;
+    }
+    SINCE_PRUNE.store(0, Ordering::Relaxed);
+    match state.db().prune_event_archive(settings.max_bytes) {
+        Ok(0) => {}
+        Ok(n) => tracing::debug!(pruned = n, "Pruned event archive"),
+        Err(e) => tracing::warn!("Failed to prune event archive: {e}"),
+    }
+}
@@ -0,0 +1,106 @@
+//! Channel event fan-out to integrations (OSC, MIDI, lights, OBS, overlay
+//! effects).
+//!
+//! EventSub handlers report each alert-worthy event once via [`dispatch`];
+//! every enabled integration then looks up its own mappings in the
+//! `event_triggers` table and reacts in its own task, so a slow one (a
+//! light effect runs for up to a minute) does not hold back the others.
+
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::services::{lights, midi, obs, osc, overlay_effects, pre_show};
+
+pub const EVENT_FOLLOW: &str = "follow";
+pub const EVENT_SUBSCRIBE: &str = "subscribe";
+pub const EVENT_RESUB: &str = "resub";
+pub const EVENT_GIFT_SUB: &str = "gift_sub";
+pub const EVENT_CHEER: &str = "cheer";
+pub const EVENT_RAID: &str = "raid";
+pub const EVENT_STREAM_ONLINE: &str = "stream_online";
+pub const EVENT_STREAM_OFFLINE: &str = "stream_offline";
+
+/// Events that can be mapped. The amount compared against `min_amount` is
+/// noted per event.
+pub const EVENTS: &[(&str, &str)] = &[
+    (EVENT_FOLLOW, "none"),
+    (EVENT_SUBSCRIBE, "tier (1-3)"),
+    (EVENT_RESUB, "cumulative months"),
+    (EVENT_GIFT_SUB, "gifted subs"),
+    (EVENT_CHEER, "bits"),
+    (EVENT_RAID, "viewers"),
+    (EVENT_STREAM_ONLINE, "none"),
+    (EVENT_STREAM_OFFLINE, "none"),
+];
+
+/// Integrations that store mappings in `event_triggers`.
+pub const INTEGRATIONS: &[&str] = &[
+    osc::INTEGRATION,
+    midi::INTEGRATION,
+    lights::INTEGRATION,
+    obs::INTEGRATION,
+    overlay_effects::INTEGRATION,
+];
+
+/// A channel event as seen by integrations.
+#[derive(Debug, Clone)]
+pub struct ChannelEvent {
+    pub kind: &'static str,
+    pub user: String,
+    pub amount: i64,
+}
+
+impl ChannelEvent {
+    pub fn new(kind: &'static str, user: impl Into<String>, amount: i64) -> Self {
+        Self {
+            kind,
+            user: user.into(),
+            amount,
+        }
+    }
+}
+
+/// Hand an event to every integration without blocking the caller.
+pub fn dispatch(state: &SharedState, event: ChannelEvent) {
+    if pre_show::suppress_alert() {
+        tracing::debug!(event = event.kind, "Held back during the pre-show");
+        return;
+    }
+    let (s, e) = (state.clone(), event.clone());
+    tokio::spawn(async move { overlay_effects::handle_event(&s, &e).await });
+    let (s, e) = (state.clone(), event.clone());
+    tokio::spawn(async move { osc::handle_event(&s, &e).await });
+    let (s, e) = (state.clone(), event.clone());
+    tokio::spawn(async move { midi::handle_event(&s, &e).await });
+    let (s, e) = (state.clone(), event.clone());
+    tokio::spawn(async move { lights::handle_event(&s, &e).await });
+    let s = state.clone();
+    tokio::spawn(async move { obs::handle_event(&s, &event).await });
+}
+
+/// Validate an integration name and its trigger payload.
+pub fn validate(integration: &str, event: &str, params: &Value) -> Result<(), String> {
+    if !EVENTS.iter().any(|(name, _)| *name == event) {
+        return Err(format!("Unknown event: {event}"));
+    }
+    match integration {
+        osc::INTEGRATION => osc::validate_params(params),
+        midi::INTEGRATION => midi::validate_params(params),
+        lights::INTEGRATION => lights::validate_params(params),
+        obs::INTEGRATION => obs::validate_params(params),
+        overlay_effects::INTEGRATION => overlay_effects::validate_params(params),
+        other => Err(format!("Unknown integration: {other}")),
+    }
+}
+
+/// Subscription tier string ("1000"/"2000"/"3000") as 1-3.
+pub fn tier_amount(tier: &str) -> i64 {
+    tier.parse::<i64>().map(|t| t / 1000).unwrap_or(1)
+}
+
+/// Replace `{user}` and `{amount}` placeholders in a mapped value.
+pub fn expand(template: &str, event: &ChannelEvent) -> String {
+    template
+        .replace("{user}", &event.user)
+        .replace("{amount}", &event.amount.to_string())
+}
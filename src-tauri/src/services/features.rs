@@ -0,0 +1,124 @@
+//! Feature flags: which optional subsystems are enabled.
+//!
+//! Flags come from settings ([`SettingsManager::check_feature_status`]) and
+//! are narrowed by hardware detection here. Background tasks of a subsystem
+//! are started only while its flag is on; enabling a flag later starts them
+//! without a restart, disabling it takes effect on the next launch.
+//!
+//! The print queue worker and scheduled prints always run: dry-run mode,
+//! macros and rundown prints queue jobs without a detected printer, and the
+//! worker reports failed jobs itself.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use tokio::sync::Mutex;
+
+use crate::app::SharedState;
+use crate::background;
+use crate::config::{FeatureStatus, SettingsManager};
+use crate::services::music::MusicService;
+use crate::services::{lottery_claims, print_queue, printer, scheduler, translations, tts};
+
+/// Subsystems whose background tasks are running.
+static STARTED: LazyLock<Mutex<HashSet<&'static str>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Settings-based status with hardware detection applied.
+pub async fn detect(state: &SharedState) -> anyhow::Result<FeatureStatus> {
+    let sm = SettingsManager::new(state.db().clone());
+    let mut status = sm.check_feature_status()?;
+
+    let runtime = printer::get_runtime_state().await;
+    status.printer_connected = runtime.connected;
+    let escpos = sm.get_setting("PRINTER_BACKEND").unwrap_or_default() == "escpos";
+    if status.features.printer && escpos {
+        let device = sm.get_setting("ESCPOS_DEVICE").unwrap_or_default();
+        if printer::is_escpos_device_available(&device) {
+            status.printer_connected = true;
+        } else {
+            status.features.printer = false;
+            status
+                .warnings
+                .push(format!("ESC/POS printer not found: {device}"));
+        }
+    } else if status.features.printer && sm.get_setting("PRINTER_TYPE").unwrap_or_default() == "usb"
+    {
+        let name = sm.get_setting("USB_PRINTER_NAME").unwrap_or_default();
+        match printer::is_usb_printer_available(&name).await {
+            Ok(true) => status.printer_connected = true,
+            Ok(false) => {
+                status.features.printer = false;
+                status
+                    .warnings
+                    .push(format!("USB printer not found: {name}"));
+            }
+            Err(e) => {
+                status.features.printer = false;
+                status
+                    .warnings
+                    .push(format!("Printer detection failed: {e}"));
+            }
+        }
+    }
+    Ok(status)
+}
+
+/// Names of the subsystems with running background tasks.
+pub async fn running() -> Vec<&'static str> {
+    let mut names: Vec<_> = STARTED.lock().await.iter().copied().collect();
+    names.sort_unstable();
+    names
+}
+
+/// Start the background tasks of every enabled subsystem not yet running.
+pub async fn start_enabled_tasks(state: &SharedState) {
+    let mut started = STARTED.lock().await;
+
+    if started.insert("print_queue") {
+        // Print queue worker
+        print_queue::start_worker(state.clone()).await;
+        // Scheduled prints (clock)
+        let s = state.clone();
+        tokio::spawn(async move { scheduler::run(s).await });
+    }
+
+    let status = match detect(state).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::warn!("Failed to detect features: {e}");
+            return;
+        }
+    };
+
+    if status.features.printer && started.insert("printer") {
+        tracing::info!("Starting printer subsystem");
+        // Printer KeepAlive
+        let s = state.clone();
+        tokio::spawn(async move { background::printer_keepalive_loop(s).await });
+    }
+    if status.features.music && started.insert("music") {
+        // Music library check
+        let svc = MusicService::new(state.db().clone(), state.data_dir().clone());
+        tokio::task::spawn_blocking(move || match svc.prune_missing() {
+            Ok(0) => {}
+            Ok(count) => tracing::info!(count, "Removed music tracks with missing files"),
+            Err(e) => tracing::warn!("Music library check failed: {e}"),
+        });
+    }
+    if status.features.lottery && started.insert("lottery") {
+        // Lottery claim deadlines
+        let s = state.clone();
+        tokio::spawn(async move { lottery_claims::run(s).await });
+    }
+    if status.features.translations && started.insert("translations") {
+        // Chat translation store
+        let s = state.clone();
+        tokio::spawn(async move { translations::run(s).await });
+    }
+    if status.features.tts && started.insert("tts") {
+        // Text-to-speech
+        let s = state.clone();
+        tokio::spawn(async move { tts::run(s).await });
+    }
+}
@@ -0,0 +1,160 @@
+//! First-time chatter greetings.
+//!
+//! Every chat message is checked against the chatter history
+//! (`overlay_db::chatters`). A viewer's first message ever, or their first
+//! in the open stream session, sends a `first_chat` WebSocket event.
+//! `FIRST_CHAT_NOTIFY` and `FIRST_CHAT_PRINT` choose which of those also
+//! show a notification and get printed: `off`, `ever` (first message ever
+//! only) or `stream` (first message of each stream as well).
+
+use overlay_db::chatters::FirstChat;
+use overlay_db::consents::ConsentScope;
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::{
+    enqueue_notification, non_empty, send_ws, str_field, to_notification_fragments,
+};
+use crate::notification::types::{FragmentInfo, NotificationType};
+use crate::services::print_queue::PrintCategory;
+use crate::services::{chat_print, consent, emotes};
+
+/// Values of `FIRST_CHAT_NOTIFY` and `FIRST_CHAT_PRINT`.
+pub const SCOPES: &[&str] = &["off", "ever", "stream"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scope {
+    Off,
+    Ever,
+    Stream,
+}
+
+impl Scope {
+    fn parse(value: &str) -> Self {
+        match value {
+            "ever" => Self::Ever,
+            "stream" => Self::Stream,
+            _ => Self::Off,
+        }
+    }
+
+    fn covers(self, first: &FirstChat) -> bool {
+        match self {
+            Self::Off => false,
+            Self::Ever => first.first_ever,
+            Self::Stream => first.any(),
+        }
+    }
+}
+
+fn label(first: &FirstChat) -> &'static str {
+    if first.first_ever {
+        "初コメント"
+    } else {
+        "今日初コメント"
+    }
+}
+
+/// Record the message's author and greet them on a first message. The
+/// broadcaster is left out.
+pub async fn handle_chat_message(state: &SharedState, payload: &Value) {
+    let user_id = str_field(payload, &["chatter_user_id"]);
+    if user_id.is_empty() || user_id == str_field(payload, &["broadcaster_user_id"]) {
+        return;
+    }
+    let username = non_empty(
+        str_field(payload, &["chatter_user_name"]),
+        str_field(payload, &["chatter_user_login"]),
+    );
+    let now = chrono::Utc::now().timestamp();
+    let first = match state.db().record_chatter(&user_id, &username, now) {
+        Ok(first) if first.any() => first,
+        Ok(_) => return,
+        Err(e) => {
+            tracing::warn!("Failed to record chatter: {e}");
+            return;
+        }
+    };
+
+    let text = str_field(payload, &["message", "text"]);
+    let fragments =
+        emotes::resolve_fragments(payload.pointer("/message/fragments").unwrap_or(&json!([])));
+    tracing::info!(
+        user = %username,
+        first_ever = first.first_ever,
+        "First chat message"
+    );
+    send_ws(
+        state,
+        "first_chat",
+        json!({
+            "user_id": user_id,
+            "username": username,
+            "message": text,
+            "first_ever": first.first_ever,
+            "first_this_stream": first.first_this_stream,
+            "session_id": first.session_id,
+        }),
+    );
+
+    let sm = SettingsManager::new(state.db().clone());
+    let scope = |key: &str| Scope::parse(&sm.get_setting(key).unwrap_or_default());
+    let (notify, print) = (scope("FIRST_CHAT_NOTIFY"), scope("FIRST_CHAT_PRINT"));
+    let label = label(&first);
+
+    if notify.covers(&first) {
+        let mut notification_fragments = vec![FragmentInfo::Text(format!("{label}: "))];
+        notification_fragments.extend(to_notification_fragments(&fragments));
+        enqueue_notification(
+            state,
+            username.clone(),
+            format!("{label}: {text}"),
+            notification_fragments,
+            NotificationType::FirstChat,
+        )
+        .await;
+    }
+
+    if print.covers(&first) && consent::allows(state, &user_id, ConsentScope::Print) {
+        let s = state.clone();
+        let print_label = format!("{username} ({label})");
+        let badges = payload.get("badges").cloned().unwrap_or(Value::Null);
+        tokio::spawn(async move {
+            if let Err(e) = chat_print::print_chat_message(
+                &s,
+                &print_label,
+                &badges,
+                &fragments,
+                PrintCategory::Chat,
+            )
+            .await
+            {
+                tracing::warn!("Failed to print first chat message: {e}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_covers() {
+        let ever = FirstChat {
+            first_ever: true,
+            first_this_stream: true,
+            session_id: Some(1),
+        };
+        let stream = FirstChat {
+            first_ever: false,
+            ..ever
+        };
+        assert!(Scope::parse("ever").covers(&ever));
+        assert!(!Scope::parse("ever").covers(&stream));
+        assert!(Scope::parse("stream").covers(&stream));
+        assert!(!Scope::parse("off").covers(&ever));
+        assert_eq!(Scope::parse(""), Scope::Off);
+    }
+}
@@ -0,0 +1,244 @@
+//! Funding goal ("thermometer") across bits, gifted subs and donations.
+//!
+//! Every contribution is stored with its value in the goal currency,
+//! converted when it arrives (`FUNDING_BIT_VALUE` per bit,
+//! `FUNDING_GIFT_SUB_VALUE` per Tier 1 gift), so changing the rates later
+//! does not rewrite history. External donations are posted by webhooks or
+//! the dashboard, and manual adjustments are stored as entries of their
+//! own with a reason, which makes them the audit log. The total counts
+//! entries since `FUNDING_GOAL_SINCE`, so it survives restarts until the
+//! goal is reset. Overlays receive a `funding_goal` message on each change.
+
+use overlay_db::funding::{
+    FundingEntry, FundingTotals, SOURCE_ADJUSTMENT, SOURCE_BITS, SOURCE_DONATION, SOURCE_GIFT_SUBS,
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+
+/// Entries included in the status snapshot.
+const RECENT_ENTRIES: i64 = 10;
+
+struct FundingSettings {
+    enabled: bool,
+    title: String,
+    target: f64,
+    currency: String,
+    bit_value: f64,
+    gift_sub_value: f64,
+    since: i64,
+}
+
+fn load_settings(state: &SharedState) -> FundingSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let number = |key: &str, default: f64| get(key).parse::<f64>().unwrap_or(default);
+    FundingSettings {
+        enabled: get("FUNDING_GOAL_ENABLED") == "true",
+        title: get("FUNDING_GOAL_TITLE"),
+        target: number("FUNDING_GOAL_TARGET", 10000.0),
+        currency: get("FUNDING_GOAL_CURRENCY"),
+        bit_value: number("FUNDING_BIT_VALUE", 1.4),
+        gift_sub_value: number("FUNDING_GIFT_SUB_VALUE", 700.0),
+        since: get("FUNDING_GOAL_SINCE").parse().unwrap_or(0),
+    }
+}
+
+/// Snapshot returned by the API and broadcast to overlays.
+#[derive(Debug, Clone, Serialize)]
+pub struct FundingStatus {
+    pub enabled: bool,
+    pub title: String,
+    pub target: f64,
+    pub currency: String,
+    pub total: f64,
+    /// `total / target`, not capped at 1.
+    pub progress: f64,
+    pub totals: FundingTotals,
+    pub since: i64,
+    pub recent: Vec<FundingEntry>,
+}
+
+/// Value of `count` gifted subs of `tier` (`1000`, `2000` or `3000`),
+/// priced like Twitch subs relative to Tier 1.
+pub fn gift_sub_value(tier: &str, count: u64, tier1_value: f64) -> f64 {
+    let multiplier = match tier {
+        "2000" => 2.0,
+        "3000" => 5.0,
+        _ => 1.0,
+    };
+    count as f64 * tier1_value * multiplier
+}
+
+fn progress(total: f64, target: f64) -> f64 {
+    if target > 0.0 { total / target } else { 0.0 }
+}
+
+pub fn status(state: &SharedState) -> Result<FundingStatus, String> {
+    let settings = load_settings(state);
+    let totals = state
+        .db()
+        .get_funding_totals(settings.since)
+        .map_err(|e| e.to_string())?;
+    let recent = state
+        .db()
+        .get_funding_entries(settings.since, None, RECENT_ENTRIES)
+        .map_err(|e| e.to_string())?;
+    let total = totals.total();
+    Ok(FundingStatus {
+        enabled: settings.enabled,
+        title: settings.title,
+        target: settings.target,
+        currency: settings.currency,
+        total,
+        progress: progress(total, settings.target),
+        totals,
+        since: settings.since,
+        recent,
+    })
+}
+
+fn broadcast(state: &SharedState) {
+    match status(state) {
+        Ok(status) => {
+            let msg = json!({ "type": "funding_goal", "data": status });
+            let _ = state.ws_sender().send(msg.to_string());
+        }
+        Err(e) => tracing::warn!("Failed to load funding goal: {e}"),
+    }
+}
+
+/// Store an entry and push the new total to overlays.
+pub fn record(
+    state: &SharedState,
+    source: &str,
+    quantity: f64,
+    value: f64,
+    username: &str,
+    note: &str,
+) -> Result<FundingEntry, String> {
+    let entry = state
+        .db()
+        .add_funding_entry(
+            source,
+            quantity,
+            value,
+            username,
+            note,
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|e| e.to_string())?;
+    tracing::info!(source, value, username, "Funding goal entry recorded");
+    broadcast(state);
+    Ok(entry)
+}
+
+/// Count a cheer toward the goal when enabled.
+pub fn on_cheer(state: &SharedState, username: &str, bits: u64) {
+    let settings = load_settings(state);
+    if !settings.enabled || bits == 0 {
+        return;
+    }
+    let value = bits as f64 * settings.bit_value;
+    if let Err(e) = record(state, SOURCE_BITS, bits as f64, value, username, "") {
+        tracing::warn!("Failed to record bits for funding goal: {e}");
+    }
+}
+
+/// Count gifted subs toward the goal when enabled.
+pub fn on_gift_subs(state: &SharedState, username: &str, tier: &str, count: u64) {
+    let settings = load_settings(state);
+    if !settings.enabled || count == 0 {
+        return;
+    }
+    let value = gift_sub_value(tier, count, settings.gift_sub_value);
+    let note = format!("tier {tier}");
+    if let Err(e) = record(
+        state,
+        SOURCE_GIFT_SUBS,
+        count as f64,
+        value,
+        username,
+        &note,
+    ) {
+        tracing::warn!("Failed to record gift subs for funding goal: {e}");
+    }
+}
+
+/// Record an external donation already in the goal currency.
+pub fn add_donation(
+    state: &SharedState,
+    amount: f64,
+    username: &str,
+    note: &str,
+) -> Result<FundingEntry, String> {
+    record(state, SOURCE_DONATION, amount, amount, username, note)
+}
+
+/// Correct the total by `amount` (may be negative) with a reason.
+pub fn adjust(
+    state: &SharedState,
+    amount: f64,
+    reason: &str,
+    actor: &str,
+) -> Result<FundingEntry, String> {
+    record(state, SOURCE_ADJUSTMENT, amount, amount, actor, reason)
+}
+
+/// Start counting from now. Earlier entries are kept for the audit log.
+pub fn reset(state: &SharedState) -> Result<(), String> {
+    SettingsManager::new(state.db().clone())
+        .set_setting(
+            "FUNDING_GOAL_SINCE",
+            &chrono::Utc::now().timestamp().to_string(),
+        )
+        .map_err(|e| e.to_string())?;
+    broadcast(state);
+    Ok(())
+}
+
+/// Entries since the last reset, newest first.
+pub fn entries(
+    state: &SharedState,
+    source: Option<&str>,
+    limit: i64,
+) -> Result<Vec<FundingEntry>, String> {
+    let since = load_settings(state).since;
+    state
+        .db()
+        .get_funding_entries(since, source, limit)
+        .map_err(|e| e.to_string())
+}
+
+/// Whether `source` names an entry source.
+pub fn is_source(source: &str) -> bool {
+    [
+        SOURCE_BITS,
+        SOURCE_GIFT_SUBS,
+        SOURCE_DONATION,
+        SOURCE_ADJUSTMENT,
+    ]
+    .contains(&source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gift_sub_value() {
+        assert_eq!(gift_sub_value("1000", 5, 700.0), 3500.0);
+        assert_eq!(gift_sub_value("2000", 1, 700.0), 1400.0);
+        assert_eq!(gift_sub_value("3000", 2, 700.0), 7000.0);
+        assert_eq!(gift_sub_value("", 1, 700.0), 700.0);
+    }
+
+    #[test]
+    fn test_progress() {
+        assert_eq!(progress(2500.0, 10000.0), 0.25);
+        assert_eq!(progress(12000.0, 10000.0), 1.2);
+        assert_eq!(progress(100.0, 0.0), 0.0);
+    }
+}
@@ -0,0 +1,43 @@
+//! Shared access to the Twitch Helix API for background services.
+
+use twitch_client::Token;
+use twitch_client::api::TwitchApiClient;
+
+use crate::app::SharedState;
+
+/// API client plus the stored token and broadcaster ID.
+pub struct HelixContext {
+    pub api: TwitchApiClient,
+    pub token: Token,
+    pub broadcaster_id: String,
+}
+
+/// Build a Helix context from the stored token and configured credentials.
+///
+/// Token refresh is left to `background::token_refresh_loop`.
+pub async fn context(state: &SharedState) -> Result<HelixContext, String> {
+    let db_token = state
+        .db()
+        .get_latest_token()
+        .map_err(|e| e.to_string())?
+        .ok_or("No Twitch token available")?;
+
+    let (client_id, broadcaster_id) = {
+        let config = state.config().await;
+        (config.client_id.clone(), config.twitch_user_id.clone())
+    };
+    if client_id.is_empty() || broadcaster_id.is_empty() {
+        return Err("Twitch credentials not configured".into());
+    }
+
+    Ok(HelixContext {
+        api: TwitchApiClient::with_http(client_id, state.http()),
+        token: Token {
+            access_token: db_token.access_token,
+            refresh_token: db_token.refresh_token,
+            scope: db_token.scope,
+            expires_at: db_token.expires_at,
+        },
+        broadcaster_id,
+    })
+}
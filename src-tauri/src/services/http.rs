@@ -0,0 +1,86 @@
+//! Shared outgoing HTTP client.
+//!
+//! Requests to Twitch, Discord, webhooks and LAN devices all go through one
+//! pooled `reqwest::Client` kept in `SharedState` (see
+//! `SharedState::http`). It is built from `HTTP_PROXY`, `HTTP_NO_PROXY` and
+//! `HTTP_TIMEOUT_SECONDS` and rebuilt whenever the config is reloaded.
+//! Services that need a shorter timeout set it per request.
+
+use std::time::Duration;
+
+use overlay_db::Database;
+
+use crate::config::SettingsManager;
+
+pub const USER_AGENT: &str = concat!("cairo-overlay/", env!("CARGO_PKG_VERSION"));
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpSettings {
+    /// Proxy URL for every request; empty for a direct connection.
+    pub proxy: String,
+    /// Hosts, domains and CIDRs reached without the proxy.
+    pub no_proxy: String,
+    pub timeout: Duration,
+}
+
+impl HttpSettings {
+    pub fn load(db: &Database) -> Self {
+        let sm = SettingsManager::new(db.clone());
+        let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+        Self {
+            proxy: get("HTTP_PROXY").trim().to_string(),
+            no_proxy: get("HTTP_NO_PROXY"),
+            timeout: Duration::from_secs(
+                get("HTTP_TIMEOUT_SECONDS")
+                    .parse()
+                    .unwrap_or(DEFAULT_TIMEOUT_SECS),
+            ),
+        }
+    }
+}
+
+/// Parse a proxy URL, bypassing it for `no_proxy`.
+pub fn parse_proxy(proxy: &str, no_proxy: &str) -> Result<reqwest::Proxy, String> {
+    let parsed = reqwest::Proxy::all(proxy).map_err(|e| format!("invalid proxy URL: {e}"))?;
+    Ok(parsed.no_proxy(reqwest::NoProxy::from_string(no_proxy)))
+}
+
+/// Build a client for `settings`. An unusable proxy is logged and skipped
+/// so the app keeps working without it.
+pub fn build(settings: &HttpSettings) -> reqwest::Client {
+    let builder = || {
+        reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(settings.timeout)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+    };
+    let with_proxy = if settings.proxy.is_empty() {
+        Ok(builder())
+    } else {
+        parse_proxy(&settings.proxy, &settings.no_proxy).map(|p| builder().proxy(p))
+    };
+    match with_proxy.and_then(|b| b.build().map_err(|e| e.to_string())) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("HTTP client settings ignored: {e}");
+            builder().build().unwrap_or_default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proxy() {
+        assert!(parse_proxy("http://127.0.0.1:3128", "localhost").is_ok());
+        assert!(parse_proxy("proxy.local:3128", "192.168.0.0/16").is_ok());
+        assert!(parse_proxy("not a url", "").is_err());
+    }
+}
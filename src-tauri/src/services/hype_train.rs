@@ -0,0 +1,153 @@
+//! Hype Train: progress data for the overlay and a receipt when it ends.
+//!
+//! Every `channel.hype_train.*` notification is broadcast as a `hype_train`
+//! WebSocket event; the end notification is also printed unless
+//! `HYPE_TRAIN_PRINT_ENABLED` is off.
+
+use serde::Serialize;
+use twitch_client::hype_train::{HypeTrainContribution, HypeTrainEvent};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::print_queue::PrintCategory;
+use crate::services::print_render;
+use crate::services::print_templates::{self, Template};
+
+/// Contributors listed in the overlay data and on the receipt.
+const TOP_CONTRIBUTORS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TopContributor {
+    pub user_login: String,
+    pub user_name: String,
+    /// `bits`, `subscription` or `other`.
+    #[serde(rename = "type")]
+    pub contribution_type: String,
+    pub total: u64,
+}
+
+impl From<&HypeTrainContribution> for TopContributor {
+    fn from(c: &HypeTrainContribution) -> Self {
+        Self {
+            user_login: c.user_login.clone(),
+            user_name: if c.user_name.is_empty() {
+                c.user_login.clone()
+            } else {
+                c.user_name.clone()
+            },
+            contribution_type: c.contribution_type.clone(),
+            total: c.total,
+        }
+    }
+}
+
+/// Data of the `hype_train` WebSocket event.
+#[derive(Debug, Clone, Serialize)]
+pub struct HypeTrainUpdate {
+    /// `begin`, `progress` or `end`.
+    pub phase: &'static str,
+    pub id: String,
+    pub level: u32,
+    pub total: u64,
+    pub progress: u64,
+    pub goal: u64,
+    /// Progress towards the next level (0–100).
+    pub percent: u32,
+    #[serde(rename = "type")]
+    pub train_type: String,
+    pub top_contributors: Vec<TopContributor>,
+    pub expires_at: Option<String>,
+    pub ended_at: Option<String>,
+}
+
+/// Largest contributions first, at most [`TOP_CONTRIBUTORS`].
+fn top_contributors(event: &HypeTrainEvent) -> Vec<TopContributor> {
+    let mut top: Vec<TopContributor> = event
+        .top_contributions
+        .iter()
+        .map(TopContributor::from)
+        .collect();
+    top.sort_by_key(|c| std::cmp::Reverse(c.total));
+    top.truncate(TOP_CONTRIBUTORS);
+    top
+}
+
+pub fn update(phase: &'static str, event: &HypeTrainEvent) -> HypeTrainUpdate {
+    HypeTrainUpdate {
+        phase,
+        id: event.id.clone(),
+        level: event.level,
+        total: event.total,
+        progress: event.progress,
+        goal: event.goal,
+        percent: event.percent(),
+        train_type: event.train_type.clone(),
+        top_contributors: top_contributors(event),
+        expires_at: event.expires_at.clone(),
+        ended_at: event.ended_at.clone(),
+    }
+}
+
+/// Print the end-of-train receipt if enabled.
+pub async fn print_receipt(state: &SharedState, event: &HypeTrainEvent) {
+    let enabled = SettingsManager::new(state.db().clone())
+        .get_setting("HYPE_TRAIN_PRINT_ENABLED")
+        .is_ok_and(|v| v == "true");
+    if !enabled {
+        return;
+    }
+    let locale = print_templates::locale(state);
+    let level = event.level.to_string();
+    let total = locale.format_number(event.total as i64);
+    let mut details = print_templates::render(
+        locale,
+        Template::HypeTrainDetails,
+        &[("level", &level), ("total", &total)],
+    );
+    let top = top_contributors(event);
+    if !top.is_empty() {
+        let names: Vec<String> = top
+            .iter()
+            .map(|c| format!("{} {}", c.user_name, locale.format_number(c.total as i64)))
+            .collect();
+        details.push_str(&format!(" TOP: {}", names.join(" / ")));
+    }
+    let title = print_templates::text(locale, Template::HypeTrainTitle);
+    if let Err(e) = print_render::print_titled(
+        state,
+        title,
+        &event.broadcaster_user_name,
+        &details,
+        PrintCategory::Other,
+    )
+    .await
+    {
+        tracing::warn!("Failed to print Hype Train receipt: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_contributors() {
+        let contribution = |login: &str, total| HypeTrainContribution {
+            user_login: login.into(),
+            total,
+            ..Default::default()
+        };
+        let event = HypeTrainEvent {
+            top_contributions: vec![
+                contribution("a", 100),
+                contribution("b", 500),
+                contribution("c", 300),
+                contribution("d", 50),
+            ],
+            ..Default::default()
+        };
+        let top = top_contributors(&event);
+        let names: Vec<_> = top.iter().map(|c| c.user_name.as_str()).collect();
+        assert_eq!(names, ["b", "c", "a"]);
+    }
+}
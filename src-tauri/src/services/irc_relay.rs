@@ -0,0 +1,423 @@
+//! Persistent Twitch IRC connection for chat in other channels.
+//!
+//! [`run`] keeps one connection open as the broadcaster while `IRC_ENABLED`
+//! is on, joins the channels listed in `IRC_CHANNELS` and reconnects with
+//! backoff when it drops. Messages in joined channels other than the
+//! broadcaster's own (that chat arrives through EventSub) are stored in
+//! `irc_chat_messages` and sent to overlays as `irc_chat_message`.
+//!
+//! [`send`], [`send_action`], [`join`] and [`part`] queue commands for the
+//! open connection; joins and parts are saved to `IRC_CHANNELS` so they
+//! survive restarts.
+
+use std::collections::VecDeque;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::{SinkExt, StreamExt};
+use overlay_db::irc::IrcChatMessage;
+use serde::Serialize;
+use tokio::net::TcpStream;
+use tokio::sync::Notify;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use twitch_client::irc::{self, IrcMessage};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::send_ws;
+use crate::services::helix;
+
+/// Longest chat message Twitch accepts.
+pub const MAX_MESSAGE_CHARS: usize = 500;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MIN_RETRY: Duration = Duration::from_secs(2);
+const MAX_RETRY: Duration = Duration::from_secs(120);
+/// How often settings and the connection's liveness are checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Twitch pings about every five minutes; a connection silent for longer is
+/// dead.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(360);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+enum Command {
+    Send { channel: String, text: String },
+    Join(String),
+    Part(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RelayStatus {
+    pub enabled: bool,
+    pub connected: bool,
+    /// Login the connection is authenticated as.
+    pub login: String,
+    /// Channels saved in `IRC_CHANNELS`.
+    pub channels: Vec<String>,
+    /// Channels currently joined.
+    pub joined: Vec<String>,
+    pub last_error: Option<String>,
+}
+
+static OUTBOX: LazyLock<Mutex<VecDeque<Command>>> = LazyLock::new(Mutex::default);
+static WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+static STATUS: LazyLock<Mutex<RelayStatus>> = LazyLock::new(Mutex::default);
+
+struct RelaySettings {
+    enabled: bool,
+    channels: Vec<String>,
+}
+
+fn load_settings(state: &SharedState) -> RelaySettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    RelaySettings {
+        enabled: get("IRC_ENABLED") == "true",
+        channels: parse_channels(&get("IRC_CHANNELS")),
+    }
+}
+
+/// Channel logins from a comma-separated list, normalized and without
+/// duplicates.
+pub fn parse_channels(value: &str) -> Vec<String> {
+    let mut channels: Vec<String> = Vec::new();
+    for channel in value.split(',').map(irc::normalize_channel) {
+        if !channel.is_empty() && !channels.contains(&channel) {
+            channels.push(channel);
+        }
+    }
+    channels
+}
+
+/// Whether `name` can be a Twitch login.
+pub fn is_login(name: &str) -> bool {
+    (1..=25).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn with_status<T>(f: impl FnOnce(&mut RelayStatus) -> T) -> T {
+    f(&mut STATUS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+pub fn status(state: &SharedState) -> RelayStatus {
+    let settings = load_settings(state);
+    RelayStatus {
+        enabled: settings.enabled,
+        channels: settings.channels,
+        ..with_status(|s| s.clone())
+    }
+}
+
+fn push(command: Command) {
+    OUTBOX
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push_back(command);
+    WAKE.notify_one();
+}
+
+fn channel_arg(channel: &str) -> Result<String, String> {
+    let channel = irc::normalize_channel(channel);
+    if !is_login(&channel) {
+        return Err(format!("Invalid channel: '{channel}'"));
+    }
+    Ok(channel)
+}
+
+/// Send `message` to `channel` over the open connection.
+pub fn send(channel: &str, message: &str) -> Result<(), String> {
+    queue_message(channel, message, false)
+}
+
+/// Send `message` to `channel` as an action (`/me`), shown in the
+/// sender's name color.
+pub fn send_action(channel: &str, message: &str) -> Result<(), String> {
+    queue_message(channel, message, true)
+}
+
+fn queue_message(channel: &str, message: &str, action: bool) -> Result<(), String> {
+    let channel = channel_arg(channel)?;
+    let text = message.trim();
+    if text.is_empty() {
+        return Err("message is empty".into());
+    }
+    if text.chars().count() > MAX_MESSAGE_CHARS {
+        return Err(format!(
+            "message must be at most {MAX_MESSAGE_CHARS} characters"
+        ));
+    }
+    if !with_status(|s| s.connected) {
+        return Err("IRC relay is not connected".into());
+    }
+    let text = if action {
+        format!("\u{1}ACTION {text}\u{1}")
+    } else {
+        text.to_string()
+    };
+    push(Command::Send { channel, text });
+    Ok(())
+}
+
+fn save_channels(state: &SharedState, channels: &[String]) -> Result<(), String> {
+    SettingsManager::new(state.db().clone())
+        .set_setting("IRC_CHANNELS", &channels.join(","))
+        .map_err(|e| e.to_string())
+}
+
+/// Join `channel` now (when connected) and on every reconnect. Returns the
+/// saved channel list.
+pub fn join(state: &SharedState, channel: &str) -> Result<Vec<String>, String> {
+    let channel = channel_arg(channel)?;
+    let mut channels = load_settings(state).channels;
+    if !channels.contains(&channel) {
+        channels.push(channel.clone());
+        save_channels(state, &channels)?;
+    }
+    push(Command::Join(channel));
+    Ok(channels)
+}
+
+/// Leave `channel` and drop it from `IRC_CHANNELS`. Returns the saved
+/// channel list.
+pub fn part(state: &SharedState, channel: &str) -> Result<Vec<String>, String> {
+    let channel = channel_arg(channel)?;
+    let mut channels = load_settings(state).channels;
+    if let Some(i) = channels.iter().position(|c| *c == channel) {
+        channels.remove(i);
+        save_channels(state, &channels)?;
+    }
+    push(Command::Part(channel));
+    Ok(channels)
+}
+
+/// Stored form of a `PRIVMSG` received at `now` (unix seconds).
+pub fn chat_message(msg: &IrcMessage, now: i64) -> IrcChatMessage {
+    let channel = msg.channel().to_string();
+    let login = msg.nick().to_string();
+    let sent_ms = msg.tag("tmi-sent-ts").parse::<i64>().ok();
+    let message_id = match msg.tag("id") {
+        "" => format!("{channel}:{login}:{}", sent_ms.unwrap_or(now * 1000)),
+        id => id.to_string(),
+    };
+    let display_name = match msg.tag("display-name") {
+        "" => login.clone(),
+        name => name.to_string(),
+    };
+    // `/me` messages come wrapped in a CTCP ACTION.
+    let text = msg.text();
+    let text = text
+        .strip_prefix("\u{1}ACTION ")
+        .map(|t| t.trim_end_matches('\u{1}'))
+        .unwrap_or(text);
+    IrcChatMessage {
+        id: 0,
+        message_id,
+        user_id: msg.tag("user-id").to_string(),
+        display_name,
+        message: text.to_string(),
+        color: msg.tag("color").to_string(),
+        badges: msg.tag("badges").to_string(),
+        created_at: sent_ms.map_or(now, |ms| ms / 1000),
+        channel,
+        login,
+    }
+}
+
+fn handle_privmsg(state: &SharedState, own_login: &str, msg: &IrcMessage) {
+    if msg.channel().is_empty() || msg.channel() == own_login {
+        return;
+    }
+    let mut entry = chat_message(msg, chrono::Utc::now().timestamp());
+    match state.db().add_irc_chat_message(&entry) {
+        Ok(Some(id)) => entry.id = id,
+        Ok(None) => return,
+        Err(e) => tracing::warn!("Failed to store IRC chat message: {e}"),
+    }
+    send_ws(state, "irc_chat_message", &entry);
+}
+
+async fn send_line(ws: &mut Socket, line: String) -> Result<(), String> {
+    ws.send(Message::Text(line.into()))
+        .await
+        .map_err(|e| format!("Failed to send to Twitch IRC: {e}"))
+}
+
+/// Send queued commands. Messages to channels not joined join them first,
+/// without saving them.
+async fn flush(ws: &mut Socket, joined: &[String]) -> Result<(), String> {
+    let commands: Vec<Command> = OUTBOX
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .drain(..)
+        .collect();
+    for command in commands {
+        match command {
+            Command::Send { channel, text } => {
+                if !joined.contains(&channel) {
+                    send_line(ws, format!("JOIN #{channel}")).await?;
+                }
+                send_line(ws, irc::privmsg(&channel, &text)).await?;
+            }
+            Command::Join(channel) => send_line(ws, format!("JOIN #{channel}")).await?,
+            Command::Part(channel) => send_line(ws, format!("PART #{channel}")).await?,
+        }
+    }
+    Ok(())
+}
+
+/// One connection, until it fails or the relay is turned off.
+async fn session(state: &SharedState) -> Result<(), String> {
+    let ctx = helix::context(state).await?;
+    let login = ctx
+        .api
+        .get_user_by_id(&ctx.token, &ctx.broadcaster_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Broadcaster not found")?
+        .login;
+    let (mut ws, _) = tokio::time::timeout(
+        CONNECT_TIMEOUT,
+        tokio_tungstenite::connect_async(irc::IRC_WS_URL),
+    )
+    .await
+    .map_err(|_| "Timed out connecting to Twitch IRC".to_string())?
+    .map_err(|e| format!("Failed to connect to Twitch IRC: {e}"))?;
+    // Commands queued while disconnected are covered by `IRC_CHANNELS`.
+    OUTBOX.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    send_line(&mut ws, format!("CAP REQ :{}", irc::CAPABILITIES)).await?;
+    send_line(&mut ws, format!("PASS oauth:{}", ctx.token.access_token)).await?;
+    send_line(&mut ws, format!("NICK {login}")).await?;
+
+    let mut logged_in = false;
+    let mut last_seen = Instant::now();
+    let mut check = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            frame = ws.next() => {
+                let text = match frame {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => {
+                        return Err("Twitch IRC closed the connection".into());
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(format!("Twitch IRC connection error: {e}")),
+                };
+                last_seen = Instant::now();
+                for msg in text.as_str().lines().filter_map(IrcMessage::parse) {
+                    match msg.command.as_str() {
+                        "PING" => send_line(&mut ws, format!("PONG :{}", msg.text())).await?,
+                        "001" => {
+                            logged_in = true;
+                            tracing::info!(login = %login, "Connected to Twitch IRC");
+                            with_status(|s| {
+                                s.connected = true;
+                                s.login = login.clone();
+                                s.last_error = None;
+                            });
+                            for channel in load_settings(state).channels {
+                                send_line(&mut ws, format!("JOIN #{channel}")).await?;
+                            }
+                            WAKE.notify_one();
+                        }
+                        "JOIN" if msg.nick() == login => with_status(|s| {
+                            let channel = msg.channel().to_string();
+                            if !s.joined.contains(&channel) {
+                                s.joined.push(channel);
+                            }
+                        }),
+                        "PART" if msg.nick() == login => {
+                            with_status(|s| s.joined.retain(|c| c != msg.channel()));
+                        }
+                        "PRIVMSG" => handle_privmsg(state, &login, &msg),
+                        "NOTICE" if !logged_in => {
+                            return Err(format!("Twitch IRC login failed: {}", msg.text()));
+                        }
+                        "RECONNECT" => return Err("Twitch IRC asked to reconnect".into()),
+                        _ => {}
+                    }
+                }
+            }
+            _ = WAKE.notified(), if logged_in => {
+                let joined = with_status(|s| s.joined.clone());
+                flush(&mut ws, &joined).await?;
+            }
+            _ = check.tick() => {
+                if !load_settings(state).enabled {
+                    let _ = ws.close(None).await;
+                    return Ok(());
+                }
+                if last_seen.elapsed() > IDLE_TIMEOUT {
+                    return Err("No data from Twitch IRC".into());
+                }
+            }
+        }
+    }
+}
+
+/// Keep the relay connected while it is enabled.
+pub async fn run(state: SharedState) {
+    let mut retry = MIN_RETRY;
+    loop {
+        if !load_settings(&state).enabled {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            continue;
+        }
+        let started = Instant::now();
+        let result = session(&state).await;
+        with_status(|s| {
+            s.connected = false;
+            s.joined.clear();
+        });
+        match result {
+            Ok(()) => {
+                tracing::info!("IRC relay turned off");
+                retry = MIN_RETRY;
+            }
+            Err(e) => {
+                if started.elapsed() > MAX_RETRY {
+                    retry = MIN_RETRY;
+                }
+                tracing::warn!("IRC relay disconnected: {e}; retrying in {retry:?}");
+                with_status(|s| s.last_error = Some(e));
+                tokio::time::sleep(retry).await;
+                retry = (retry * 2).min(MAX_RETRY);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_message() {
+        let msg = IrcMessage::parse(
+            "@badges=subscriber/12;color=#FF0000;display-name=Alice;id=m1;tmi-sent-ts=1700000000123;\
+             user-id=42 :alice!alice@alice.tmi.twitch.tv PRIVMSG #bob :\u{1}ACTION waves\u{1}",
+        )
+        .unwrap();
+        let entry = chat_message(&msg, 0);
+        assert_eq!(entry.channel, "bob");
+        assert_eq!(entry.message_id, "m1");
+        assert_eq!(entry.login, "alice");
+        assert_eq!(entry.display_name, "Alice");
+        assert_eq!(entry.message, "waves");
+        assert_eq!(entry.created_at, 1_700_000_000);
+
+        let bare = IrcMessage::parse(":carol!carol@carol.tmi.twitch.tv PRIVMSG #bob :hi").unwrap();
+        let entry = chat_message(&bare, 5);
+        assert_eq!(entry.message_id, "bob:carol:5000");
+        assert_eq!(entry.display_name, "carol");
+        assert_eq!(entry.created_at, 5);
+    }
+
+    #[test]
+    fn test_parse_channels() {
+        assert_eq!(parse_channels(" #Alice,bob,, alice"), vec!["alice", "bob"]);
+        assert!(is_login("some_user1"));
+        assert!(!is_login("bad name"));
+        assert!(!is_login(""));
+    }
+}
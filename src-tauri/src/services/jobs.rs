@@ -0,0 +1,143 @@
+//! Long-running background jobs with progress reporting.
+//!
+//! A job runs as a tokio task and reports progress through its
+//! [`JobHandle`]; every update is kept in memory (the last
+//! `MAX_FINISHED_JOBS` finished jobs are retained) and broadcast as a
+//! `job_progress` WebSocket message. Only one job per kind runs at a time.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::LazyLock;
+
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::app::SharedState;
+use crate::eventsub_support::send_ws;
+
+const MAX_FINISHED_JOBS: usize = 50;
+
+pub const STATUS_RUNNING: &str = "running";
+pub const STATUS_COMPLETED: &str = "completed";
+pub const STATUS_FAILED: &str = "failed";
+
+static JOBS: LazyLock<RwLock<VecDeque<JobInfo>>> = LazyLock::new(|| RwLock::new(VecDeque::new()));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub kind: String,
+    pub status: &'static str,
+    /// Completed steps out of `total`.
+    pub done: u64,
+    pub total: u64,
+    pub message: String,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+/// Progress reporter passed to the job body.
+pub struct JobHandle {
+    state: SharedState,
+    id: String,
+}
+
+impl JobHandle {
+    pub async fn progress(&self, done: u64, total: u64, message: impl Into<String>) {
+        let message = message.into();
+        update(&self.state, &self.id, |job| {
+            job.done = done;
+            job.total = total;
+            job.message = message;
+        })
+        .await;
+    }
+}
+
+async fn update(state: &SharedState, id: &str, f: impl FnOnce(&mut JobInfo)) {
+    let snapshot = {
+        let mut jobs = JOBS.write().await;
+        let Some(job) = jobs.iter_mut().find(|j| j.id == id) else {
+            return;
+        };
+        f(job);
+        job.clone()
+    };
+    send_ws(state, "job_progress", snapshot);
+}
+
+/// Start a job. Fails if a job of the same kind is still running.
+pub async fn spawn<F, Fut>(state: &SharedState, kind: &str, body: F) -> Result<JobInfo, String>
+where
+    F: FnOnce(JobHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Value, String>> + Send + 'static,
+{
+    let job = JobInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: kind.to_string(),
+        status: STATUS_RUNNING,
+        done: 0,
+        total: 0,
+        message: String::new(),
+        result: None,
+        error: None,
+        started_at: chrono::Utc::now().timestamp(),
+        finished_at: None,
+    };
+    {
+        let mut jobs = JOBS.write().await;
+        if jobs
+            .iter()
+            .any(|j| j.kind == kind && j.status == STATUS_RUNNING)
+        {
+            return Err(format!("A {kind} job is already running"));
+        }
+        jobs.push_back(job.clone());
+        while jobs.len() > MAX_FINISHED_JOBS {
+            let Some(index) = jobs.iter().position(|j| j.status != STATUS_RUNNING) else {
+                break;
+            };
+            jobs.remove(index);
+        }
+    }
+
+    let handle = JobHandle {
+        state: state.clone(),
+        id: job.id.clone(),
+    };
+    let s = state.clone();
+    let id = job.id.clone();
+    tokio::spawn(async move {
+        let outcome = body(handle).await;
+        if let Err(e) = &outcome {
+            tracing::warn!(job = id, "Job failed: {e}");
+        }
+        update(&s, &id, |job| {
+            job.finished_at = Some(chrono::Utc::now().timestamp());
+            match outcome {
+                Ok(result) => {
+                    job.status = STATUS_COMPLETED;
+                    job.result = Some(result);
+                }
+                Err(e) => {
+                    job.status = STATUS_FAILED;
+                    job.error = Some(e);
+                }
+            }
+        })
+        .await;
+    });
+    Ok(job)
+}
+
+/// Jobs, newest first.
+pub async fn list() -> Vec<JobInfo> {
+    JOBS.read().await.iter().rev().cloned().collect()
+}
+
+pub async fn get(id: &str) -> Option<JobInfo> {
+    JOBS.read().await.iter().find(|j| j.id == id).cloned()
+}
@@ -0,0 +1,245 @@
+//! End-to-end latency of EventSub notifications.
+//!
+//! Each notification gets a correlation ID when it is received and is
+//! handled inside an `eventsub` span carrying it, so every log line of the
+//! pipeline can be tied back to one event. The time from receipt to each
+//! [`Stage`] is recorded once per event into a histogram exposed by
+//! `GET /api/system/metrics`; a stage slower than its threshold is logged
+//! as a warning with the correlation ID.
+//!
+//! The trace travels through the handler as a task-local, and with queued
+//! notifications so their display can be measured from receipt.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Histogram bucket upper bounds in milliseconds; one overflow bucket follows.
+const BUCKETS_MS: [u64; 10] = [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    /// The event is stored in the database.
+    DbWrite,
+    /// The first WebSocket message derived from the event is sent.
+    WsBroadcast,
+    /// The handler finished.
+    Handled,
+    /// The alert is shown. Includes time spent waiting behind other alerts.
+    NotificationDisplay,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [
+        Self::DbWrite,
+        Self::WsBroadcast,
+        Self::Handled,
+        Self::NotificationDisplay,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DbWrite => "db_write",
+            Self::WsBroadcast => "ws_broadcast",
+            Self::Handled => "handled",
+            Self::NotificationDisplay => "notification_display",
+        }
+    }
+
+    /// Latency above which the stage is logged as slow.
+    pub fn slow_threshold(self) -> Duration {
+        match self {
+            Self::DbWrite => Duration::from_millis(50),
+            Self::WsBroadcast => Duration::from_millis(100),
+            Self::Handled => Duration::from_millis(500),
+            Self::NotificationDisplay => Duration::from_secs(3),
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Correlation ID and receipt time of one notification.
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub id: String,
+    received: Instant,
+    /// Stages already recorded, as [`Stage::bit`] flags.
+    recorded: Arc<AtomicU8>,
+}
+
+impl Trace {
+    pub fn start() -> Self {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        Self {
+            id: id[..12].to_string(),
+            received: Instant::now(),
+            recorded: Arc::new(AtomicU8::new(0)),
+        }
+    }
+
+    /// Record the time since receipt for `stage`, once per trace.
+    pub fn record(&self, stage: Stage) {
+        if self.recorded.fetch_or(stage.bit(), Ordering::Relaxed) & stage.bit() != 0 {
+            return;
+        }
+        let elapsed = self.received.elapsed();
+        let elapsed_ms = elapsed.as_millis() as u64;
+        let slow = elapsed > stage.slow_threshold();
+        if slow {
+            tracing::warn!(
+                trace_id = %self.id,
+                stage = stage.as_str(),
+                elapsed_ms,
+                "Slow event pipeline stage"
+            );
+        } else {
+            tracing::trace!(trace_id = %self.id, stage = stage.as_str(), elapsed_ms);
+        }
+        if let Ok(mut stats) = STATS.lock() {
+            stats.entry(stage).or_default().observe(elapsed_ms, slow);
+        }
+    }
+}
+
+tokio::task_local! {
+    static CURRENT: Trace;
+}
+
+/// Run `f` with `trace` as the current trace.
+pub async fn scope<F: Future>(trace: Trace, f: F) -> F::Output {
+    CURRENT.scope(trace, f).await
+}
+
+/// The trace of the event being handled by this task, if any.
+pub fn current() -> Option<Trace> {
+    CURRENT.try_with(Trace::clone).ok()
+}
+
+/// Record `stage` for the current trace; a no-op outside event handling.
+pub fn mark(stage: Stage) {
+    let _ = CURRENT.try_with(|trace| trace.record(stage));
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Histogram {
+    pub count: u64,
+    pub sum_ms: u64,
+    pub max_ms: u64,
+    /// Observations over the stage's slow threshold.
+    pub slow: u64,
+    /// Per-bucket counts matching [`BUCKETS_MS`], then the overflow bucket.
+    pub buckets: Vec<u64>,
+}
+
+impl Histogram {
+    fn observe(&mut self, ms: u64, slow: bool) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; BUCKETS_MS.len() + 1];
+        }
+        let i = BUCKETS_MS
+            .iter()
+            .position(|&le| ms <= le)
+            .unwrap_or(BUCKETS_MS.len());
+        self.buckets[i] += 1;
+        self.count += 1;
+        self.sum_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+        if slow {
+            self.slow += 1;
+        }
+    }
+
+    /// Upper bound of the bucket holding quantile `q` (0–1); the maximum
+    /// when it falls in the overflow bucket.
+    pub fn quantile_ms(&self, q: f64) -> Option<u64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = ((self.count as f64) * q).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                return Some(BUCKETS_MS.get(i).copied().unwrap_or(self.max_ms));
+            }
+        }
+        Some(self.max_ms)
+    }
+}
+
+static STATS: LazyLock<Mutex<BTreeMap<Stage, Histogram>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+/// Histograms of every stage for the metrics endpoint.
+pub fn snapshot() -> serde_json::Value {
+    let stats = STATS.lock().map(|s| s.clone()).unwrap_or_default();
+    let stages: serde_json::Map<String, serde_json::Value> = Stage::ALL
+        .into_iter()
+        .map(|stage| {
+            let h = stats.get(&stage).cloned().unwrap_or_default();
+            let value = serde_json::json!({
+                "count": h.count,
+                "avg_ms": h.sum_ms.checked_div(h.count).unwrap_or(0),
+                "max_ms": h.max_ms,
+                "p50_ms": h.quantile_ms(0.5),
+                "p95_ms": h.quantile_ms(0.95),
+                "slow": h.slow,
+                "slow_threshold_ms": stage.slow_threshold().as_millis() as u64,
+                "buckets": h.buckets,
+            });
+            (stage.as_str().to_string(), value)
+        })
+        .collect();
+    serde_json::json!({ "buckets_ms": BUCKETS_MS, "stages": stages })
+}
+
+/// Clear every histogram.
+pub fn reset() {
+    if let Ok(mut stats) = STATS.lock() {
+        stats.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram() {
+        let mut h = Histogram::default();
+        assert_eq!(h.quantile_ms(0.5), None);
+        for ms in [1, 3, 20, 40, 90, 7000] {
+            h.observe(ms, ms > 50);
+        }
+        assert_eq!(h.count, 6);
+        assert_eq!(h.slow, 2);
+        assert_eq!(h.max_ms, 7000);
+        assert_eq!(h.buckets[0], 2);
+        assert_eq!(h.buckets[BUCKETS_MS.len()], 1);
+        assert_eq!(h.quantile_ms(0.5), Some(25));
+        assert_eq!(h.quantile_ms(1.0), Some(7000));
+    }
+
+    #[tokio::test]
+    async fn test_record_once_per_stage() {
+        let trace = Trace::start();
+        assert_eq!(trace.id.len(), 12);
+        assert!(current().is_none());
+        scope(trace.clone(), async {
+            assert_eq!(current().map(|t| t.id), Some(trace.id.clone()));
+            mark(Stage::WsBroadcast);
+            mark(Stage::WsBroadcast);
+        })
+        .await;
+        assert_eq!(
+            trace.recorded.load(Ordering::Relaxed),
+            Stage::WsBroadcast.bit()
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! Room light flashes via Philips Hue bridges and WLED controllers.
+//!
+//! Mapped channel events flash every configured backend in a color, then
+//! restore the state captured just before the flash. A trigger's `params`
+//! look like:
+//!
+//! ```json
+//! { "color": "#ff00ff", "effect": "flash", "brightness": 255, "duration_ms": 3000 }
+//! ```
+//!
+//! Flashes are rate-limited: a new one starts only after the previous one
+//! has ended and `LIGHTS_MIN_INTERVAL_SECONDS` have passed since it began,
+//! so a burst of cheers does not strobe the room. Extra events are dropped.
+
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+use tokio::sync::RwLock;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::event_triggers::ChannelEvent;
+
+pub const INTEGRATION: &str = "lights";
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_DURATION_MS: u64 = 3000;
+const MAX_DURATION_MS: u64 = 60_000;
+
+/// Effects accepted in trigger params.
+pub const EFFECTS: &[&str] = &["solid", "flash", "pulse", "strobe", "rainbow"];
+
+/// Earliest time the next flash may start.
+static NEXT_ALLOWED: LazyLock<RwLock<Option<Instant>>> = LazyLock::new(|| RwLock::new(None));
+
+/// A parsed light flash.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flash {
+    pub rgb: (u8, u8, u8),
+    pub effect: String,
+    pub brightness: u8,
+    pub duration: Duration,
+}
+
+struct LightSettings {
+    enabled: bool,
+    min_interval: Duration,
+    hue_url: String,
+    hue_username: String,
+    hue_group: String,
+    wled_url: String,
+}
+
+impl LightSettings {
+    fn hue_configured(&self) -> bool {
+        !self.hue_url.is_empty() && !self.hue_username.is_empty()
+    }
+
+    fn wled_configured(&self) -> bool {
+        !self.wled_url.is_empty()
+    }
+
+    fn hue_group_url(&self) -> String {
+        format!(
+            "{}/api/{}/groups/{}",
+            self.hue_url, self.hue_username, self.hue_group
+        )
+    }
+}
+
+fn load_settings(state: &SharedState) -> LightSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let hue_group = get("LIGHTS_HUE_GROUP");
+    LightSettings {
+        enabled: get("LIGHTS_ENABLED") == "true",
+        min_interval: Duration::from_secs(get("LIGHTS_MIN_INTERVAL_SECONDS").parse().unwrap_or(10)),
+        hue_url: get("LIGHTS_HUE_BRIDGE_URL")
+            .trim_end_matches('/')
+            .to_string(),
+        hue_username: get("LIGHTS_HUE_USERNAME"),
+        hue_group: if hue_group.is_empty() {
+            "0".to_string()
+        } else {
+            hue_group
+        },
+        wled_url: get("LIGHTS_WLED_URL").trim_end_matches('/').to_string(),
+    }
+}
+
+/// Which backends are configured (for the status API).
+pub fn status(state: &SharedState) -> Value {
+    let s = load_settings(state);
+    json!({
+        "enabled": s.enabled,
+        "min_interval_seconds": s.min_interval.as_secs(),
+        "hue": { "configured": s.hue_configured(), "bridge_url": s.hue_url, "group": s.hue_group },
+        "wled": { "configured": s.wled_configured(), "url": s.wled_url },
+        "effects": EFFECTS,
+    })
+}
+
+/// Check the `params` of a light trigger.
+pub fn validate_params(params: &Value) -> Result<(), String> {
+    parse_flash(params).map(|_| ())
+}
+
+/// Flash the lights mapped to `event`. No-op when disabled or rate-limited.
+pub async fn handle_event(state: &SharedState, event: &ChannelEvent) {
+    let settings = load_settings(state);
+    if !settings.enabled {
+        return;
+    }
+    let triggers =
+        match state
+            .db()
+            .get_matching_event_triggers(INTEGRATION, event.kind, event.amount)
+        {
+            Ok(t) => t,
+            Err(e) => {
+                tracing::warn!("Failed to load light triggers: {e}");
+                return;
+            }
+        };
+    // One flash per event: the first mapping at the matched threshold wins.
+    let Some(trigger) = triggers.first() else {
+        return;
+    };
+    let flash = match parse_flash(&trigger.params) {
+        Ok(f) => f,
+        Err(e) => {
+            tracing::warn!(id = trigger.id, "Invalid light trigger: {e}");
+            return;
+        }
+    };
+
+    if !try_reserve(&flash, settings.min_interval).await {
+        tracing::debug!(event = event.kind, "Light flash skipped (rate limited)");
+        return;
+    }
+    run_flash(&state.http(), &settings, &flash).await;
+}
+
+/// Flash once regardless of mappings and the rate limiter (test endpoint).
+pub async fn send_test(state: &SharedState, params: &Value) -> Result<(), String> {
+    let settings = load_settings(state);
+    if !settings.hue_configured() && !settings.wled_configured() {
+        return Err("No Hue bridge or WLED controller is configured".into());
+    }
+    let flash = parse_flash(params)?;
+    run_flash(&state.http(), &settings, &flash).await;
+    Ok(())
+}
+
+/// Pair with a Hue bridge (the link button must have been pressed) and
+/// return the new API username.
+pub async fn pair_hue(state: &SharedState) -> Result<String, String> {
+    let settings = load_settings(state);
+    if settings.hue_url.is_empty() {
+        return Err("LIGHTS_HUE_BRIDGE_URL is not set".into());
+    }
+    let body: Value = state
+        .http()
+        .post(format!("{}/api", settings.hue_url))
+        .timeout(HTTP_TIMEOUT)
+        .json(&json!({ "devicetype": "cairo_overlay#app" }))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    let first = &body[0];
+    if let Some(username) = first["success"]["username"].as_str() {
+        return Ok(username.to_string());
+    }
+    Err(first["error"]["description"]
+        .as_str()
+        .unwrap_or("Unexpected response from Hue bridge")
+        .to_string())
+}
+
+/// Reserve the next flash slot if the rate limiter allows it.
+async fn try_reserve(flash: &Flash, min_interval: Duration) -> bool {
+    let mut next = NEXT_ALLOWED.write().await;
+    let now = Instant::now();
+    if next.is_some_and(|at| now < at) {
+        return false;
+    }
+    *next = Some(now + flash.duration.max(min_interval));
+    true
+}
+
+async fn run_flash(http: &reqwest::Client, settings: &LightSettings, flash: &Flash) {
+    let hue = async {
+        if !settings.hue_configured() {
+            return;
+        }
+        if let Err(e) = flash_hue(http, settings, flash).await {
+            tracing::warn!("Hue flash failed: {e}");
+        }
+    };
+    let wled = async {
+        if !settings.wled_configured() {
+            return;
+        }
+        if let Err(e) = flash_wled(http, settings, flash).await {
+            tracing::warn!("WLED flash failed: {e}");
+        }
+    };
+    tokio::join!(hue, wled);
+}
+
+async fn flash_hue(
+    http: &reqwest::Client,
+    settings: &LightSettings,
+    flash: &Flash,
+) -> Result<(), String> {
+    let group_url = settings.hue_group_url();
+    let before: Value = http
+        .get(&group_url)
+        .timeout(HTTP_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (x, y) = rgb_to_xy(flash.rgb);
+    let mut action = json!({
+        "on": true,
+        "bri": flash.brightness.clamp(1, 254),
+        "xy": [x, y],
+        "transitiontime": 1,
+    });
+    match flash.effect.as_str() {
+        "flash" | "strobe" => action["alert"] = json!("lselect"),
+        "pulse" => action["alert"] = json!("select"),
+        "rainbow" => action["effect"] = json!("colorloop"),
+        _ => {}
+    }
+    put_hue_action(http, &group_url, &action).await?;
+
+    tokio::time::sleep(flash.duration).await;
+    put_hue_action(http, &group_url, &hue_restore_action(&before["action"])).await
+}
+
+async fn put_hue_action(
+    http: &reqwest::Client,
+    group_url: &str,
+    action: &Value,
+) -> Result<(), String> {
+    http.put(format!("{group_url}/action"))
+        .timeout(HTTP_TIMEOUT)
+        .json(action)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Hue action that returns a group to the captured state.
+fn hue_restore_action(before: &Value) -> Value {
+    let mut action = json!({
+        "on": before["on"].as_bool().unwrap_or(false),
+        "alert": "none",
+        "effect": "none",
+        "transitiontime": 4,
+    });
+    if let Some(bri) = before.get("bri") {
+        action["bri"] = bri.clone();
+    }
+    match before["colormode"].as_str() {
+        Some("ct") => action["ct"] = before["ct"].clone(),
+        Some("hs") => {
+            action["hue"] = before["hue"].clone();
+            action["sat"] = before["sat"].clone();
+        }
+        Some(_) => action["xy"] = before["xy"].clone(),
+        None => {}
+    }
+    action
+}
+
+async fn flash_wled(
+    http: &reqwest::Client,
+    settings: &LightSettings,
+    flash: &Flash,
+) -> Result<(), String> {
+    let state_url = format!("{}/json/state", settings.wled_url);
+    let before: Value = http
+        .get(&state_url)
+        .timeout(HTTP_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (r, g, b) = flash.rgb;
+    let body = json!({
+        "on": true,
+        "bri": flash.brightness,
+        "transition": 0,
+        "seg": [{ "col": [[r, g, b]], "fx": wled_effect_id(&flash.effect) }],
+    });
+    post_json(http, &state_url, &body).await?;
+
+    tokio::time::sleep(flash.duration).await;
+    // WLED accepts the state object it returned as-is.
+    post_json(http, &state_url, &before).await
+}
+
+async fn post_json(http: &reqwest::Client, url: &str, body: &Value) -> Result<(), String> {
+    http.post(url)
+        .timeout(HTTP_TIMEOUT)
+        .json(body)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// WLED effect ID for an effect name.
+fn wled_effect_id(effect: &str) -> u8 {
+    match effect {
+        "flash" => 1,   // Blink
+        "pulse" => 2,   // Breathe
+        "rainbow" => 9, // Rainbow
+        "strobe" => 23, // Strobe
+        _ => 0,         // Solid
+    }
+}
+
+/// Parse trigger params into a flash.
+fn parse_flash(params: &Value) -> Result<Flash, String> {
+    let color = params["color"].as_str().unwrap_or_default();
+    let rgb = parse_hex_color(color).ok_or("params.color must be a hex color like #ff00ff")?;
+    let effect = params["effect"].as_str().unwrap_or("flash").to_string();
+    if !EFFECTS.contains(&effect.as_str()) {
+        return Err(format!(
+            "params.effect must be one of {}",
+            EFFECTS.join(", ")
+        ));
+    }
+    let brightness = params["brightness"].as_u64().unwrap_or(255);
+    if !(1..=255).contains(&brightness) {
+        return Err("params.brightness must be 1-255".into());
+    }
+    let duration_ms = params["duration_ms"]
+        .as_u64()
+        .unwrap_or(DEFAULT_DURATION_MS);
+    if duration_ms == 0 || duration_ms > MAX_DURATION_MS {
+        return Err(format!("params.duration_ms must be 1-{MAX_DURATION_MS}"));
+    }
+    Ok(Flash {
+        rgb,
+        effect,
+        brightness: brightness as u8,
+        duration: Duration::from_millis(duration_ms),
+    })
+}
+
+fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Convert sRGB to CIE 1931 xy (Hue's color space).
+fn rgb_to_xy((r, g, b): (u8, u8, u8)) -> (f64, f64) {
+    let linear = |c: u8| {
+        let c = f64::from(c) / 255.0;
+        if c > 0.04045 {
+            ((c + 0.055) / 1.055).powf(2.4)
+        } else {
+            c / 12.92
+        }
+    };
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+    let x = r * 0.664_511 + g * 0.154_324 + b * 0.162_028;
+    let y = r * 0.283_881 + g * 0.668_433 + b * 0.047_685;
+    let z = r * 0.000_088 + g * 0.072_310 + b * 0.986_039;
+    let sum = x + y + z;
+    if sum == 0.0 {
+        return (0.3227, 0.329);
+    }
+    let round = |v: f64| (v * 10_000.0).round() / 10_000.0;
+    (round(x / sum), round(y / sum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flash_defaults() {
+        let flash = parse_flash(&json!({ "color": "#FF8000" })).unwrap();
+        assert_eq!(flash.rgb, (255, 128, 0));
+        assert_eq!(flash.effect, "flash");
+        assert_eq!(flash.brightness, 255);
+        assert_eq!(flash.duration, Duration::from_millis(DEFAULT_DURATION_MS));
+    }
+
+    #[test]
+    fn test_parse_flash_rejects_invalid() {
+        assert!(parse_flash(&json!({})).is_err());
+        assert!(parse_flash(&json!({ "color": "#12345" })).is_err());
+        assert!(parse_flash(&json!({ "color": "#123456", "effect": "disco" })).is_err());
+        assert!(parse_flash(&json!({ "color": "#123456", "brightness": 0 })).is_err());
+        assert!(parse_flash(&json!({ "color": "#123456", "duration_ms": 0 })).is_err());
+    }
+
+    #[test]
+    fn test_rgb_to_xy() {
+        let (x, y) = rgb_to_xy((255, 0, 0));
+        assert!(x > 0.6 && y < 0.35);
+        assert_eq!(rgb_to_xy((0, 0, 0)), (0.3227, 0.329));
+    }
+
+    #[test]
+    fn test_hue_restore_action_uses_color_mode() {
+        let before =
+            json!({ "on": true, "bri": 100, "colormode": "ct", "ct": 366, "xy": [0.4, 0.4] });
+        let action = hue_restore_action(&before);
+        assert_eq!(action["ct"], 366);
+        assert_eq!(action["bri"], 100);
+        assert!(action.get("xy").is_none());
+    }
+}
@@ -0,0 +1,166 @@
+//! Lottery state and winner draws.
+//!
+//! Shared by the `/api/present` handlers and the [`super::lottery_claims`]
+//! worker, which re-draws when a winner lets the claim deadline pass.
+//! Starting or clearing a lottery closes the claims still pending from the
+//! previous one so that they are never re-drawn into the new one.
+
+use std::sync::LazyLock;
+
+use overlay_db::lottery::{DrawProof, LotteryParticipant};
+use overlay_db::lottery_engine::TicketFormula;
+use serde_json::json;
+use tokio::sync::{RwLock, RwLockWriteGuard};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::{discord, lottery_draw, lottery_receipt, lottery_winner};
+
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeState {
+    pub is_running: bool,
+    pub is_locked: bool,
+    pub winner: Option<LotteryParticipant>,
+}
+
+static RUNTIME: LazyLock<RwLock<RuntimeState>> =
+    LazyLock::new(|| RwLock::new(RuntimeState::default()));
+
+/// The in-memory lottery state, locked for the caller.
+pub async fn runtime() -> RwLockWriteGuard<'static, RuntimeState> {
+    RUNTIME.write().await
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DrawError {
+    #[error("No participants")]
+    NoParticipants,
+    #[error("No participant has a ticket")]
+    NoTickets,
+    #[error("Database error: {0}")]
+    Db(#[from] overlay_db::DbError),
+}
+
+/// Result of a draw.
+pub struct Draw {
+    pub winner: LotteryParticipant,
+    /// Index of the winner among the eligible participants.
+    pub winner_index: usize,
+    /// `None` when the draw could not be recorded.
+    pub draw_id: Option<i64>,
+    pub receipt: Option<lottery_receipt::ReceiptInfo>,
+    pub proof: DrawProof,
+}
+
+/// The configured `LOTTERY_TICKET_FORMULA`, or the default one when it is
+/// unset or invalid.
+pub fn ticket_formula(state: &SharedState) -> TicketFormula {
+    let value = SettingsManager::new(state.db().clone())
+        .get_setting("LOTTERY_TICKET_FORMULA")
+        .unwrap_or_default();
+    TicketFormula::parse(&value).unwrap_or_else(|e| {
+        tracing::warn!("Invalid LOTTERY_TICKET_FORMULA, using the default: {e}");
+        TicketFormula::default()
+    })
+}
+
+/// Expire the claims still pending from earlier draws.
+pub fn close_claims(state: &SharedState) {
+    match state.db().expire_pending_lottery_draws() {
+        Ok(0) => {}
+        Ok(closed) => tracing::info!(closed, "Closed pending lottery claims"),
+        Err(e) => tracing::warn!("Failed to close pending lottery claims: {e}"),
+    }
+}
+
+/// Draw a winner from the participants not in `exclude` (user IDs). A
+/// re-draw passes the draw it replaces as `redraw_of`.
+pub async fn draw_excluding(
+    state: &SharedState,
+    exclude: &[String],
+    redraw_of: Option<i64>,
+) -> Result<Draw, DrawError> {
+    let participants: Vec<LotteryParticipant> = state
+        .db()
+        .get_all_lottery_participants()?
+        .into_iter()
+        .filter(|p| !exclude.contains(&p.user_id))
+        .collect();
+    if participants.is_empty() {
+        return Err(DrawError::NoParticipants);
+    }
+
+    let (winner, proof) =
+        lottery_draw::draw(&participants, &ticket_formula(state)).ok_or(DrawError::NoTickets)?;
+    let winner_index = participants
+        .iter()
+        .position(|p| p.user_id == winner.user_id)
+        .unwrap_or(0);
+
+    let draw = state.db().add_lottery_draw(
+        &winner,
+        participants.len() as i64,
+        chrono::Utc::now().timestamp(),
+        Some(&proof),
+    );
+    let draw_id = match draw {
+        Ok(mut draw) => {
+            if let Some(of) = redraw_of {
+                if let Err(e) = state.db().set_lottery_draw_redraw_of(draw.id, of) {
+                    tracing::warn!("Failed to link lottery re-draw: {e}");
+                }
+                draw.redraw_of = Some(of);
+            }
+            let s = state.clone();
+            tokio::spawn(async move {
+                lottery_winner::notify(&s, &draw).await;
+            });
+            Some(draw.id)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to record lottery draw: {e}");
+            None
+        }
+    };
+
+    let winner_name = if winner.display_name.is_empty() {
+        &winner.username
+    } else {
+        &winner.display_name
+    };
+    // Printed only once confirmed via POST /api/present/winner/print.
+    let receipt = match lottery_receipt::prepare(state, winner_name).await {
+        Ok(receipt) => Some(receipt),
+        Err(e) => {
+            tracing::warn!("Failed to render lottery winner receipt: {e}");
+            lottery_receipt::discard().await;
+            None
+        }
+    };
+
+    let mut runtime = runtime().await;
+    runtime.winner = Some(winner.clone());
+    runtime.is_running = false;
+
+    let msg = json!({
+        "type": "lottery_winner",
+        "data": { "winner": winner, "winner_index": winner_index, "redraw_of": redraw_of }
+    });
+    let _ = state.ws_sender().send(msg.to_string());
+    discord::notify(
+        state,
+        discord::EVENT_LOTTERY_WINNER,
+        &[
+            ("user", winner_name),
+            ("participants", &participants.len().to_string()),
+        ],
+    );
+
+    Ok(Draw {
+        winner,
+        winner_index,
+        draw_id,
+        receipt,
+        proof,
+    })
+}
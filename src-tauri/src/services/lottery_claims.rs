@@ -0,0 +1,212 @@
+//! Lottery prize claims and automatic re-draws.
+//!
+//! Each draw waits for its winner to claim the prize by sending
+//! `LOTTERY_CLAIM_COMMAND` (`!claim` by default) in chat, redeeming
+//! `LOTTERY_CLAIM_REWARD_ID`, or being marked from the admin UI, before the
+//! claim deadline set by [`super::lottery_winner`]. With
+//! `LOTTERY_AUTO_REDRAW` on, the [`run`] worker expires unclaimed draws,
+//! posts `LOTTERY_REDRAW_MESSAGE` and draws again without the winners who
+//! let it lapse; the new draw records the one it replaced.
+
+use std::time::Duration;
+
+use overlay_db::lottery::{CLAIM_SOURCE_COMMAND, CLAIM_SOURCE_REWARD, LotteryDraw};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::{send_ws, str_field};
+use crate::services::{discord, lottery, twitch_chat};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const DEFAULT_COMMAND: &str = "!claim";
+
+struct ClaimSettings {
+    command: String,
+    reward_id: String,
+    auto_redraw: bool,
+    redraw_message: String,
+}
+
+fn load_settings(state: &SharedState) -> ClaimSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let command = get("LOTTERY_CLAIM_COMMAND");
+    ClaimSettings {
+        command: if command.trim().is_empty() {
+            DEFAULT_COMMAND.to_string()
+        } else {
+            command.trim().to_string()
+        },
+        reward_id: get("LOTTERY_CLAIM_REWARD_ID"),
+        auto_redraw: get("LOTTERY_AUTO_REDRAW") == "true",
+        redraw_message: get("LOTTERY_REDRAW_MESSAGE"),
+    }
+}
+
+/// The configured claim command (`LOTTERY_CLAIM_COMMAND`, default `!claim`).
+pub fn claim_command(state: &SharedState) -> String {
+    load_settings(state).command
+}
+
+fn display_name(draw: &LotteryDraw) -> &str {
+    if draw.display_name.is_empty() {
+        &draw.username
+    } else {
+        &draw.display_name
+    }
+}
+
+/// Whether a chat message is the claim command.
+fn is_claim(text: &str, command: &str) -> bool {
+    text.split_whitespace()
+        .next()
+        .is_some_and(|word| word.eq_ignore_ascii_case(command))
+}
+
+/// Mark `draw` claimed; `Ok(false)` when it was not waiting for a claim.
+pub fn claim(state: &SharedState, draw: &LotteryDraw, source: &str) -> Result<bool, String> {
+    let now = chrono::Utc::now().timestamp();
+    let claimed = state
+        .db()
+        .claim_lottery_draw(draw.id, source, now)
+        .map_err(|e| format!("Failed to record lottery claim: {e}"))?;
+    if claimed {
+        tracing::info!(draw_id = draw.id, source, "Lottery prize claimed");
+        send_ws(
+            state,
+            "lottery_claimed",
+            json!({ "draw_id": draw.id, "user_id": draw.user_id, "source": source }),
+        );
+    }
+    Ok(claimed)
+}
+
+/// Claim the pending draw of `user_id`, if any.
+fn claim_for_user(state: &SharedState, user_id: &str, source: &str) {
+    if user_id.is_empty() {
+        return;
+    }
+    let draw = match state.db().get_pending_lottery_draw(user_id) {
+        Ok(Some(draw)) => draw,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Failed to look up pending lottery draw: {e}");
+            return;
+        }
+    };
+    if let Err(e) = claim(state, &draw, source) {
+        tracing::warn!("{e}");
+    }
+}
+
+/// Claim the sender's prize when a chat message is the claim command.
+pub fn handle_chat_message(state: &SharedState, payload: &Value) {
+    let s = load_settings(state);
+    if !is_claim(&str_field(payload, &["message", "text"]), &s.command) {
+        return;
+    }
+    let user_id = str_field(payload, &["chatter_user_id"]);
+    claim_for_user(state, &user_id, CLAIM_SOURCE_COMMAND);
+}
+
+/// Claim the redeemer's prize when the claim reward is redeemed.
+pub fn handle_redemption(state: &SharedState, payload: &Value) {
+    let s = load_settings(state);
+    let reward_id = str_field(payload, &["reward", "id"]);
+    if s.reward_id.is_empty() || reward_id != s.reward_id {
+        return;
+    }
+    let user_id = str_field(payload, &["user_id"]);
+    claim_for_user(state, &user_id, CLAIM_SOURCE_REWARD);
+}
+
+/// User IDs of `draw`'s winner and of every winner it was re-drawn after.
+fn lapsed_winners(state: &SharedState, draw: &LotteryDraw) -> Vec<String> {
+    let mut winners = vec![draw.user_id.clone()];
+    let mut seen = vec![draw.id];
+    let mut previous = draw.redraw_of;
+    while let Some(id) = previous.filter(|id| !seen.contains(id)) {
+        seen.push(id);
+        match state.db().get_lottery_draw(id) {
+            Ok(Some(d)) => {
+                winners.push(d.user_id);
+                previous = d.redraw_of;
+            }
+            Ok(None) => break,
+            Err(e) => {
+                tracing::warn!("Failed to load lottery draw {id}: {e}");
+                break;
+            }
+        }
+    }
+    winners
+}
+
+/// Expire `draw` and, when it is still the latest draw, draw again.
+async fn redraw(state: &SharedState, draw: &LotteryDraw, message: &str) -> Result<(), String> {
+    let db_err = |e: overlay_db::DbError| format!("Lottery database error: {e}");
+    if !state.db().expire_lottery_draw(draw.id).map_err(db_err)? {
+        return Ok(());
+    }
+    tracing::info!(draw_id = draw.id, "Lottery claim deadline passed");
+    send_ws(
+        state,
+        "lottery_claim_expired",
+        json!({ "draw_id": draw.id, "user_id": draw.user_id }),
+    );
+    let latest = state.db().get_lottery_draws(1).map_err(db_err)?;
+    if latest.first().is_none_or(|d| d.id != draw.id) {
+        // A newer draw has been made since; it stands.
+        return Ok(());
+    }
+
+    if !message.trim().is_empty() {
+        let text = discord::render(message, &[("expired", display_name(draw))]);
+        if let Err(e) = twitch_chat::send_chat(state, &text).await {
+            tracing::warn!("Failed to announce lottery re-draw: {e}");
+        }
+    }
+    let exclude = lapsed_winners(state, draw);
+    lottery::draw_excluding(state, &exclude, Some(draw.id))
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Lottery re-draw failed: {e}"))
+}
+
+/// Background worker expiring unclaimed draws and re-drawing them.
+pub async fn run(state: SharedState) {
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        let s = load_settings(&state);
+        if !s.auto_redraw {
+            continue;
+        }
+        let now = chrono::Utc::now().timestamp();
+        let overdue = match state.db().get_overdue_lottery_draws(now) {
+            Ok(draws) => draws,
+            Err(e) => {
+                tracing::warn!("Failed to load overdue lottery draws: {e}");
+                continue;
+            }
+        };
+        for draw in &overdue {
+            if let Err(e) = redraw(&state, draw, &s.redraw_message).await {
+                tracing::warn!("{e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_command() {
+        assert!(is_claim("!claim", "!claim"));
+        assert!(is_claim("  !CLAIM ありがとう", "!claim"));
+        assert!(!is_claim("!claimed", "!claim"));
+        assert!(!is_claim("please !claim", "!claim"));
+    }
+}
@@ -0,0 +1,220 @@
+//! Verifiable lottery draws (commit–reveal).
+//!
+//! Starting the lottery ([`commit`]) makes a random seed and publishes only
+//! its SHA-256, the commitment. The draw reveals the seed and picks the
+//! winning ticket from it, so the result was fixed before anyone knew it
+//! and anyone can recompute it:
+//!
+//! 1. Entries are the participants sorted by user ID, each with their
+//!    tickets from `lottery_engine`; tickets are numbered from 0 in that
+//!    order.
+//! 2. `entries_digest` = hex SHA-256 of the lines `"{id_hash}:{tickets}\n"`,
+//!    where `id_hash` is the hex SHA-256 of the user ID, so the results
+//!    page can list the hashes instead of the IDs. The hash is unsalted and
+//!    Twitch user IDs are short numbers, so it only gives each user a stable
+//!    identifier; it does not hide who they are.
+//! 3. `winning_ticket` = the first 8 bytes of SHA-256 of
+//!    `"{seed}:{entries_digest}"`, big-endian, modulo the total tickets.
+//! 4. `commitment` = hex SHA-256 of `seed`.
+//!
+//! A draw without a prior [`commit`] makes its seed at draw time; its proof
+//! has no `committed_at`.
+
+use std::sync::{LazyLock, Mutex};
+
+use overlay_db::lottery::{DrawEntry, DrawProof, LotteryParticipant};
+use overlay_db::lottery_engine::TicketFormula;
+use sha2::{Digest, Sha256};
+
+pub const ALGORITHM: &str = "sha256-commit-reveal-v2";
+
+struct Commitment {
+    seed: String,
+    committed_at: i64,
+}
+
+static COMMITTED: LazyLock<Mutex<Option<Commitment>>> = LazyLock::new(|| Mutex::new(None));
+
+fn sha256_hex(data: &str) -> String {
+    hex::encode(Sha256::digest(data.as_bytes()))
+}
+
+fn new_seed() -> String {
+    // Two v4 UUIDs give 244 random bits from the OS generator.
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Make the seed for the next draw and return its commitment.
+pub fn commit() -> String {
+    let seed = new_seed();
+    let commitment = sha256_hex(&seed);
+    *COMMITTED.lock().unwrap_or_else(|e| e.into_inner()) = Some(Commitment {
+        seed,
+        committed_at: chrono::Utc::now().timestamp(),
+    });
+    commitment
+}
+
+/// The published commitment for the next draw, if any.
+pub fn commitment() -> Option<String> {
+    COMMITTED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|c| sha256_hex(&c.seed))
+}
+
+/// A user ID as it appears in the entries digest.
+pub fn user_id_hash(user_id: &str) -> String {
+    sha256_hex(user_id)
+}
+
+pub fn entries_digest(entries: &[DrawEntry]) -> String {
+    let lines: String = entries
+        .iter()
+        .map(|e| format!("{}:{}\n", user_id_hash(&e.user_id), e.tickets))
+        .collect();
+    sha256_hex(&lines)
+}
+
+/// Winning ticket number for `seed` over entries with `entries_digest`.
+pub fn winning_ticket(seed: &str, entries_digest: &str, total_tickets: i64) -> i64 {
+    let hash = Sha256::digest(format!("{seed}:{entries_digest}").as_bytes());
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&hash[..8]);
+    (u64::from_be_bytes(head) % total_tickets.max(1) as u64) as i64
+}
+
+/// The entry holding ticket `ticket`.
+pub fn ticket_holder(entries: &[DrawEntry], ticket: i64) -> Option<&DrawEntry> {
+    let mut end = 0;
+    entries.iter().find(|e| {
+        end += e.tickets;
+        ticket < end
+    })
+}
+
+/// Draw a winner, using up the committed seed. `None` without participants.
+pub fn draw(
+    participants: &[LotteryParticipant],
+    formula: &TicketFormula,
+) -> Option<(LotteryParticipant, DrawProof)> {
+    let mut sorted: Vec<&LotteryParticipant> = participants.iter().collect();
+    sorted.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+    let entries: Vec<DrawEntry> = sorted
+        .iter()
+        .map(|p| DrawEntry {
+            user_id: p.user_id.clone(),
+            name: if p.display_name.is_empty() {
+                p.username.clone()
+            } else {
+                p.display_name.clone()
+            },
+            tickets: i64::from(formula.for_participant(p).total),
+        })
+        .collect();
+    let total_tickets: i64 = entries.iter().map(|e| e.tickets).sum();
+    if total_tickets == 0 {
+        return None;
+    }
+
+    let committed = COMMITTED.lock().unwrap_or_else(|e| e.into_inner()).take();
+    let (seed, committed_at) = match committed {
+        Some(c) => (c.seed, Some(c.committed_at)),
+        None => (new_seed(), None),
+    };
+    let digest = entries_digest(&entries);
+    let ticket = winning_ticket(&seed, &digest, total_tickets);
+    let holder = ticket_holder(&entries, ticket)?;
+    let winner = participants
+        .iter()
+        .find(|p| p.user_id == holder.user_id)?
+        .clone();
+    Some((
+        winner,
+        DrawProof {
+            algorithm: ALGORITHM.to_string(),
+            commitment: sha256_hex(&seed),
+            committed_at,
+            seed,
+            entries_digest: digest,
+            entries,
+            total_tickets,
+            winning_ticket: ticket,
+        },
+    ))
+}
+
+/// Recompute a proof; returns the winning entry's user ID when every step
+/// checks out.
+pub fn verify(proof: &DrawProof) -> Option<&str> {
+    if proof.algorithm != ALGORITHM {
+        return None;
+    }
+    let total: i64 = proof.entries.iter().map(|e| e.tickets).sum();
+    let consistent = sha256_hex(&proof.seed) == proof.commitment
+        && entries_digest(&proof.entries) == proof.entries_digest
+        && total == proof.total_tickets
+        && winning_ticket(&proof.seed, &proof.entries_digest, total) == proof.winning_ticket;
+    if !consistent {
+        return None;
+    }
+    ticket_holder(&proof.entries, proof.winning_ticket).map(|e| e.user_id.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participant(user_id: &str, entry_count: i32) -> LotteryParticipant {
+        LotteryParticipant {
+            user_id: user_id.to_string(),
+            username: user_id.to_string(),
+            display_name: String::new(),
+            avatar_url: String::new(),
+            redeemed_at: String::new(),
+            is_subscriber: false,
+            subscriber_tier: String::new(),
+            subscribed_months: 0,
+            entry_count,
+            assigned_color: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_draw_is_verifiable() {
+        let participants = vec![participant("u2", 3), participant("u1", 1)];
+        let published = commit();
+        let (winner, proof) = draw(&participants, &TicketFormula::default()).unwrap();
+        assert_eq!(proof.commitment, published);
+        assert!(proof.committed_at.is_some());
+        assert_eq!(proof.total_tickets, 4);
+        assert_eq!(proof.entries[0].user_id, "u1");
+        assert_eq!(verify(&proof), Some(winner.user_id.as_str()));
+
+        let mut tampered = proof.clone();
+        tampered.entries[0].tickets = 2;
+        assert_eq!(verify(&tampered), None);
+
+        // The seed is used up.
+        assert_eq!(commitment(), None);
+    }
+
+    #[test]
+    fn test_ticket_holder() {
+        let entries = [("a", 1), ("b", 3)].map(|(id, tickets)| DrawEntry {
+            user_id: id.to_string(),
+            name: id.to_string(),
+            tickets,
+        });
+        let holder = |t| ticket_holder(&entries, t).map(|e| e.user_id.as_str());
+        assert_eq!(holder(0), Some("a"));
+        assert_eq!(holder(1), Some("b"));
+        assert_eq!(holder(3), Some("b"));
+        assert_eq!(holder(4), None);
+    }
+}
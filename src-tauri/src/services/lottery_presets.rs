@@ -0,0 +1,147 @@
+//! Shareable lottery presets.
+//!
+//! A preset is a JSON document carrying the lottery's rule settings, so a
+//! fair-draw configuration can be handed to another channel and imported
+//! there:
+//!
+//! ```json
+//! { "format": "twitch-overlay/lottery-preset", "version": 1,
+//!   "name": "fair", "description": "..",
+//!   "settings": { "LOTTERY_DISPLAY_DURATION": "5" } }
+//! ```
+//!
+//! Only [`PRESET_KEYS`] travel in a preset; the reward ID and the
+//! enabled/locked state stay with the channel. Keys a preset leaves out are
+//! not touched when it is applied, so older presets stay valid as keys are
+//! added.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::config::validation::validate_setting;
+
+pub const FORMAT: &str = "twitch-overlay/lottery-preset";
+pub const VERSION: u32 = 1;
+
+/// Settings a preset may carry.
+pub const PRESET_KEYS: &[&str] = &[
+    "LOTTERY_DISPLAY_DURATION",
+    "LOTTERY_ANIMATION_SPEED",
+    "LOTTERY_TICKER_ENABLED",
+    "LOTTERY_TICKET_FORMULA",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetDocument {
+    pub format: String,
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub settings: BTreeMap<String, String>,
+}
+
+impl PresetDocument {
+    pub fn new(name: &str, description: &str, settings: BTreeMap<String, String>) -> Self {
+        Self {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            name: name.to_string(),
+            description: description.to_string(),
+            settings,
+        }
+    }
+}
+
+/// The current values of [`PRESET_KEYS`].
+pub fn current_settings(state: &SharedState) -> BTreeMap<String, String> {
+    let sm = SettingsManager::new(state.db().clone());
+    PRESET_KEYS
+        .iter()
+        .map(|key| (key.to_string(), sm.get_setting(key).unwrap_or_default()))
+        .collect()
+}
+
+/// Check an imported document. Values may be written as JSON strings,
+/// numbers or booleans; they come back as setting strings.
+pub fn parse(doc: &Value) -> Result<PresetDocument, String> {
+    if doc["format"] != FORMAT {
+        return Err(format!("Not a lottery preset (format must be '{FORMAT}')"));
+    }
+    let version = doc["version"].as_u64().unwrap_or(0);
+    if version == 0 || version > u64::from(VERSION) {
+        return Err(format!("Unsupported preset version: {version}"));
+    }
+    let name = doc["name"].as_str().unwrap_or_default().trim();
+    if name.is_empty() {
+        return Err("Preset name is required".to_string());
+    }
+    let Some(entries) = doc["settings"].as_object() else {
+        return Err("Preset settings must be an object".to_string());
+    };
+
+    let mut settings = BTreeMap::new();
+    for (key, value) in entries {
+        if !PRESET_KEYS.contains(&key.as_str()) {
+            return Err(format!("{key} cannot be set by a preset"));
+        }
+        let value = match value {
+            Value::String(s) => s.clone(),
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            _ => return Err(format!("{key}: value must be a string, number or boolean")),
+        };
+        validate_setting(key, &value).map_err(|e| format!("{key}: {e}"))?;
+        settings.insert(key.clone(), value);
+    }
+    Ok(PresetDocument::new(
+        name,
+        doc["description"].as_str().unwrap_or_default(),
+        settings,
+    ))
+}
+
+/// Write a preset's settings and reload the runtime config.
+pub async fn apply(state: &SharedState, settings: &BTreeMap<String, String>) -> Result<(), String> {
+    let sm = SettingsManager::new(state.db().clone());
+    for (key, value) in settings {
+        sm.set_setting(key, value).map_err(|e| e.to_string())?;
+    }
+    state
+        .reload_config()
+        .await
+        .map_err(|e| format!("Failed to reload config: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn doc(format: &str, version: u32, settings: Value) -> Value {
+        json!({ "format": format, "version": version, "name": "x", "settings": settings })
+    }
+
+    #[test]
+    fn test_parse_preset() {
+        let preset = parse(&json!({
+            "format": FORMAT,
+            "version": 1,
+            "name": " fair ",
+            "settings": { "LOTTERY_DISPLAY_DURATION": 8, "LOTTERY_TICKER_ENABLED": true },
+        }))
+        .unwrap();
+        assert_eq!(preset.name, "fair");
+        assert_eq!(preset.settings["LOTTERY_DISPLAY_DURATION"], "8");
+        assert_eq!(preset.settings["LOTTERY_TICKER_ENABLED"], "true");
+
+        assert!(parse(&doc(FORMAT, 1, json!({ "LOTTERY_REWARD_ID": "abc" }))).is_err());
+        assert!(parse(&doc(FORMAT, 1, json!({ "LOTTERY_DISPLAY_DURATION": "99" }))).is_err());
+        assert!(parse(&doc("other", 1, json!({}))).is_err());
+        assert!(parse(&doc(FORMAT, 2, json!({}))).is_err());
+    }
+}
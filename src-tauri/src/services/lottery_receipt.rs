@@ -0,0 +1,136 @@
+//! Winner receipts for the lottery.
+//!
+//! Drawing a winner renders their receipt (title, name and the
+//! `LOTTERY_PRIZE` text) but does not print it. The receipt waits here
+//! until it is confirmed with `POST /api/present/winner/print`, so the
+//! preview can be checked and the prize text corrected first. Drawing again
+//! or clearing the lottery drops the pending receipt.
+
+use std::sync::LazyLock;
+
+use ab_glyph::FontRef;
+use image::DynamicImage;
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::print_queue::PrintCategory;
+use crate::services::print_render;
+use crate::services::print_templates::{self, Template};
+
+/// Where the pending receipt's preview is served.
+pub const PREVIEW_PATH: &str = "/api/present/winner/preview";
+
+struct Pending {
+    winner: String,
+    prize: String,
+    image: DynamicImage,
+    /// PNG of the receipt as the printer would output it.
+    preview: Vec<u8>,
+    rendered_at: i64,
+}
+
+static PENDING: LazyLock<RwLock<Option<Pending>>> = LazyLock::new(|| RwLock::new(None));
+
+/// What the admin UI shows for the pending receipt.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiptInfo {
+    pub winner: String,
+    pub prize: String,
+    /// Changes with every render, so the browser does not show a stale image.
+    pub preview_url: String,
+}
+
+impl Pending {
+    fn info(&self) -> ReceiptInfo {
+        ReceiptInfo {
+            winner: self.winner.clone(),
+            prize: self.prize.clone(),
+            preview_url: format!("{PREVIEW_PATH}?v={}", self.rendered_at),
+        }
+    }
+}
+
+async fn render(state: &SharedState, winner: &str, prize: &str) -> Result<Pending, String> {
+    let locale = print_templates::locale(state);
+    let title = print_templates::text(locale, Template::LotteryWinnerTitle);
+    let details = if prize.is_empty() {
+        String::new()
+    } else {
+        print_templates::render(locale, Template::LotteryWinnerDetails, &[("prize", prize)])
+    };
+    let font_data = print_render::load_font(state)?;
+    let font = FontRef::try_from_slice(&font_data).map_err(|_| "Invalid font data")?;
+    let image = image_processor::message::message_to_image_with_title(
+        title, winner, &details, None, &font, false, locale,
+    );
+    let (dither, black_point, rotate_print) = {
+        let config = state.config().await;
+        (config.dither, config.black_point, config.rotate_print)
+    };
+    let preview = print_render::preview_image(&image, dither, black_point, rotate_print)?;
+    Ok(Pending {
+        winner: winner.to_string(),
+        prize: prize.to_string(),
+        image,
+        preview: preview.png,
+        rendered_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+/// Render the receipt for a new winner with the configured prize, replacing
+/// any pending one.
+pub async fn prepare(state: &SharedState, winner: &str) -> Result<ReceiptInfo, String> {
+    let prize = SettingsManager::new(state.db().clone())
+        .get_setting("LOTTERY_PRIZE")
+        .unwrap_or_default();
+    let pending = render(state, winner, prize.trim()).await?;
+    let info = pending.info();
+    *PENDING.write().await = Some(pending);
+    Ok(info)
+}
+
+/// Re-render the pending receipt with a corrected prize text. `Ok(None)`
+/// when no receipt is pending.
+pub async fn set_prize(state: &SharedState, prize: &str) -> Result<Option<ReceiptInfo>, String> {
+    let Some(winner) = PENDING.read().await.as_ref().map(|p| p.winner.clone()) else {
+        return Ok(None);
+    };
+    let pending = render(state, &winner, prize.trim()).await?;
+    let info = pending.info();
+    *PENDING.write().await = Some(pending);
+    Ok(Some(info))
+}
+
+pub async fn pending() -> Option<ReceiptInfo> {
+    PENDING.read().await.as_ref().map(Pending::info)
+}
+
+pub async fn preview_png() -> Option<Vec<u8>> {
+    PENDING.read().await.as_ref().map(|p| p.preview.clone())
+}
+
+/// Queue the pending receipt and clear it. `Ok(None)` when none is pending.
+pub async fn print(state: &SharedState) -> Result<Option<ReceiptInfo>, String> {
+    let Some(pending) = PENDING.write().await.take() else {
+        return Ok(None);
+    };
+    let description = format!("Lottery winner: {}", pending.winner);
+    if let Err(e) =
+        print_render::enqueue_image(state, &pending.image, &description, PrintCategory::Other).await
+    {
+        // Keep it so the print can be retried.
+        let mut slot = PENDING.write().await;
+        if slot.is_none() {
+            *slot = Some(pending);
+        }
+        return Err(e);
+    }
+    tracing::info!(winner = %pending.winner, "Lottery winner receipt queued");
+    Ok(Some(pending.info()))
+}
+
+pub async fn discard() {
+    PENDING.write().await.take();
+}
@@ -0,0 +1,153 @@
+//! Telling the lottery winner after a draw.
+//!
+//! With `LOTTERY_WINNER_WHISPER` on, the winner is whispered
+//! `LOTTERY_WINNER_WHISPER_MESSAGE` (claim instructions); with
+//! `LOTTERY_WINNER_ANNOUNCE` on, `LOTTERY_WINNER_ANNOUNCE_MESSAGE` is posted
+//! to chat. Both templates take `{user}` (display name), `{login}`,
+//! `{prize}` (`LOTTERY_PRIZE`), `{deadline}` (local `HH:MM`) and
+//! `{minutes}`; the claim deadline is `LOTTERY_CLAIM_MINUTES` after the
+//! draw. What went out is recorded on the draw (`DrawDelivery`) and sent to
+//! overlays as `lottery_delivery`.
+
+use overlay_db::lottery::{DELIVERY_FAILED, DELIVERY_SENT, DrawDelivery, LotteryDraw};
+use serde_json::json;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::send_ws;
+use crate::services::{discord, twitch_chat};
+
+const DEFAULT_CLAIM_MINUTES: i64 = 10;
+const DEFAULT_PRIZE: &str = "景品";
+
+struct WinnerSettings {
+    whisper: bool,
+    whisper_message: String,
+    announce: bool,
+    announce_message: String,
+    claim_minutes: i64,
+    prize: String,
+}
+
+fn load_settings(state: &SharedState) -> WinnerSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let prize = get("LOTTERY_PRIZE");
+    WinnerSettings {
+        whisper: get("LOTTERY_WINNER_WHISPER") == "true",
+        whisper_message: get("LOTTERY_WINNER_WHISPER_MESSAGE"),
+        announce: get("LOTTERY_WINNER_ANNOUNCE") == "true",
+        announce_message: get("LOTTERY_WINNER_ANNOUNCE_MESSAGE"),
+        claim_minutes: get("LOTTERY_CLAIM_MINUTES")
+            .parse()
+            .unwrap_or(DEFAULT_CLAIM_MINUTES),
+        prize: if prize.trim().is_empty() {
+            DEFAULT_PRIZE.to_string()
+        } else {
+            prize
+        },
+    }
+}
+
+fn format_deadline(deadline: i64) -> String {
+    chrono::DateTime::from_timestamp(deadline, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// Fill a winner message template for `draw`.
+pub fn render_message(template: &str, draw: &LotteryDraw, prize: &str, minutes: i64) -> String {
+    let user = if draw.display_name.is_empty() {
+        &draw.username
+    } else {
+        &draw.display_name
+    };
+    let deadline = draw
+        .delivery
+        .claim_deadline
+        .map(format_deadline)
+        .unwrap_or_default();
+    discord::render(
+        template,
+        &[
+            ("user", user),
+            ("login", &draw.username),
+            ("prize", prize),
+            ("deadline", &deadline),
+            ("minutes", &minutes.to_string()),
+        ],
+    )
+}
+
+/// Whisper and announce the winner of `draw` as configured, and record the
+/// outcome on the draw.
+pub async fn notify(state: &SharedState, draw: &LotteryDraw) -> DrawDelivery {
+    let s = load_settings(state);
+    let mut draw = draw.clone();
+    draw.delivery = DrawDelivery {
+        claim_deadline: Some(draw.drawn_at + s.claim_minutes * 60),
+        ..Default::default()
+    };
+    let mut errors = Vec::new();
+
+    if s.whisper && !draw.user_id.is_empty() {
+        let message = render_message(&s.whisper_message, &draw, &s.prize, s.claim_minutes);
+        draw.delivery.whisper_status =
+            match twitch_chat::send_whisper(state, &draw.user_id, &message).await {
+                Ok(()) => DELIVERY_SENT.to_string(),
+                Err(e) => {
+                    tracing::warn!("Failed to whisper lottery winner: {e}");
+                    errors.push(format!("whisper: {e}"));
+                    DELIVERY_FAILED.to_string()
+                }
+            };
+    }
+    if s.announce {
+        let message = render_message(&s.announce_message, &draw, &s.prize, s.claim_minutes);
+        draw.delivery.announce_status = match twitch_chat::send_chat(state, &message).await {
+            Ok(()) => DELIVERY_SENT.to_string(),
+            Err(e) => {
+                tracing::warn!("Failed to announce lottery winner: {e}");
+                errors.push(format!("announce: {e}"));
+                DELIVERY_FAILED.to_string()
+            }
+        };
+    }
+    draw.delivery.error = errors.join("; ");
+
+    if let Err(e) = state
+        .db()
+        .set_lottery_draw_delivery(draw.id, &draw.delivery)
+    {
+        tracing::warn!("Failed to record lottery winner delivery: {e}");
+    }
+    send_ws(
+        state,
+        "lottery_delivery",
+        json!({ "draw_id": draw.id, "delivery": draw.delivery }),
+    );
+    draw.delivery
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_message() {
+        let draw = LotteryDraw {
+            id: 1,
+            user_id: "u1".into(),
+            username: "alice".into(),
+            display_name: "アリス".into(),
+            participant_count: 3,
+            drawn_at: 0,
+            proof: None,
+            delivery: DrawDelivery::default(),
+            claim: Default::default(),
+            redraw_of: None,
+        };
+        let message = render_message("@{login} {user}: {prize} ({minutes}分)", &draw, "本", 10);
+        assert_eq!(message, "@alice アリス: 本 (10分)");
+    }
+}
@@ -0,0 +1,267 @@
+//! Macros: one action that runs an ordered sequence of steps.
+//!
+//! A macro is stored as a JSON array of steps, e.g.
+//! `[{ "type": "reward_group", "group_id": 1, "enabled": true },
+//!   { "type": "print_rundown", "delay_ms": 2000 },
+//!   { "type": "chat", "message": "こんばんは！" }]`.
+//! Each step waits `delay_ms` before it runs. A failing step stops the
+//! macro unless it has `"on_error": "continue"`. Runs are background jobs
+//! (`macro:<id>`), so progress is reported as `job_progress` and the same
+//! macro cannot run twice at once.
+
+use overlay_db::macros::Macro;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::services::jobs::{self, JobInfo};
+use crate::services::print_queue::PrintCategory;
+use crate::services::{
+    ad_break, obs, overlay_effects, print_queue, print_render, reward_groups, rundown, twitch_chat,
+};
+
+pub const MAX_STEPS: usize = 50;
+pub const MAX_DELAY_MS: u64 = 10 * 60 * 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MacroAction {
+    /// Does nothing; only `delay_ms` applies.
+    Wait,
+    Chat {
+        message: String,
+    },
+    PrintText {
+        title: String,
+        #[serde(default)]
+        text: String,
+    },
+    /// Print the rundown of `date`, or today's.
+    PrintRundown {
+        #[serde(default)]
+        date: Option<String>,
+    },
+    RewardGroup {
+        group_id: i64,
+        enabled: bool,
+    },
+    /// An overlay effect request (`{ "preset": .. }` or `{ "effect": .. }`).
+    OverlayEffect {
+        request: Value,
+    },
+    PrintQueue {
+        paused: bool,
+    },
+    AdBreak {
+        duration_seconds: u64,
+    },
+    /// Switch the OBS program scene.
+    ObsScene {
+        scene: String,
+    },
+}
+
+impl MacroAction {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Wait => "wait",
+            Self::Chat { .. } => "chat",
+            Self::PrintText { .. } => "print_text",
+            Self::PrintRundown { .. } => "print_rundown",
+            Self::RewardGroup { .. } => "reward_group",
+            Self::OverlayEffect { .. } => "overlay_effect",
+            Self::PrintQueue { .. } => "print_queue",
+            Self::AdBreak { .. } => "ad_break",
+            Self::ObsScene { .. } => "obs_scene",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnError {
+    #[default]
+    Stop,
+    Continue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacroStep {
+    #[serde(flatten)]
+    pub action: MacroAction,
+    /// Wait before the step runs.
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(default)]
+    pub on_error: OnError,
+}
+
+fn validate_step(step: &MacroStep) -> Result<(), String> {
+    if step.delay_ms > MAX_DELAY_MS {
+        return Err(format!("delay_ms must be at most {MAX_DELAY_MS}"));
+    }
+    match &step.action {
+        MacroAction::Chat { message } if message.trim().is_empty() => {
+            Err("message is required".into())
+        }
+        MacroAction::PrintText { title, .. } if title.trim().is_empty() => {
+            Err("title is required".into())
+        }
+        MacroAction::PrintRundown { date: Some(date) } if !rundown::is_valid_date(date) => {
+            Err("date must be YYYY-MM-DD".into())
+        }
+        MacroAction::OverlayEffect { request } => overlay_effects::validate_params(request),
+        MacroAction::ObsScene { scene } if scene.trim().is_empty() => {
+            Err("scene is required".into())
+        }
+        MacroAction::AdBreak { duration_seconds }
+            if !(1..=ad_break::MAX_DURATION_SECS).contains(duration_seconds) =>
+        {
+            Err(format!(
+                "duration_seconds must be 1-{}",
+                ad_break::MAX_DURATION_SECS
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parse and validate the steps of a macro definition.
+pub fn parse_steps(steps: &Value) -> Result<Vec<MacroStep>, String> {
+    let steps: Vec<MacroStep> =
+        serde_json::from_value(steps.clone()).map_err(|e| format!("Invalid steps: {e}"))?;
+    if steps.is_empty() {
+        return Err("At least one step is required".into());
+    }
+    if steps.len() > MAX_STEPS {
+        return Err(format!("At most {MAX_STEPS} steps are allowed"));
+    }
+    for (i, step) in steps.iter().enumerate() {
+        validate_step(step).map_err(|e| format!("Step {}: {e}", i + 1))?;
+    }
+    Ok(steps)
+}
+
+async fn execute(state: &SharedState, action: &MacroAction, source: &Value) -> Result<(), String> {
+    match action {
+        MacroAction::Wait => Ok(()),
+        MacroAction::Chat { message } => twitch_chat::send_chat(state, message).await,
+        MacroAction::PrintText { title, text } => {
+            print_render::print_titled(state, title, "", text, PrintCategory::Other).await
+        }
+        MacroAction::PrintRundown { date } => {
+            let date = date.clone().unwrap_or_else(|| rundown::today(state));
+            rundown::print(state, &date).await
+        }
+        MacroAction::RewardGroup { group_id, enabled } => {
+            reward_groups::set_enabled(state, *group_id, *enabled)
+                .await
+                .map(|_| ())
+        }
+        MacroAction::OverlayEffect { request } => {
+            let effect = overlay_effects::resolve(state, request)?;
+            overlay_effects::trigger(state, &effect, source.clone());
+            Ok(())
+        }
+        MacroAction::PrintQueue { paused: true } => {
+            print_queue::pause().await;
+            Ok(())
+        }
+        MacroAction::PrintQueue { paused: false } => {
+            print_queue::resume().await;
+            Ok(())
+        }
+        MacroAction::AdBreak { duration_seconds } => {
+            ad_break::start(state, *duration_seconds, ad_break::SOURCE_MANUAL).await;
+            Ok(())
+        }
+        MacroAction::ObsScene { scene } => obs::switch_scene(state, scene).await,
+    }
+}
+
+/// Start a stored macro as a background job.
+pub async fn run(state: &SharedState, stored: &Macro) -> Result<JobInfo, String> {
+    let steps = parse_steps(&stored.steps)?;
+    let macro_id = stored.id;
+    let source = json!({ "type": "macro", "id": stored.id, "name": stored.name });
+    let s = state.clone();
+    jobs::spawn(state, &format!("macro:{macro_id}"), move |job| async move {
+        let total = steps.len() as u64;
+        let mut results = Vec::with_capacity(steps.len());
+        let mut failed = 0;
+        for (i, step) in steps.iter().enumerate() {
+            let kind = step.action.kind();
+            job.progress(i as u64, total, format!("Step {}: {kind}", i + 1)).await;
+            if step.delay_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(step.delay_ms)).await;
+            }
+            match execute(&s, &step.action, &source).await {
+                Ok(()) => results.push(json!({ "step": i + 1, "type": kind, "ok": true })),
+                Err(e) if step.on_error == OnError::Continue => {
+                    tracing::warn!(macro_id, step = i + 1, "Macro step failed: {e}");
+                    failed += 1;
+                    results.push(json!({
+                        "step": i + 1,
+                        "type": kind,
+                        "ok": false,
+                        "error": e,
+                    }));
+                }
+                Err(e) => return Err(format!("Step {} ({kind}) failed: {e}", i + 1)),
+            }
+        }
+        job.progress(total, total, "Done").await;
+        tracing::info!(macro_id, failed, "Macro finished");
+        Ok(json!({ "steps": results, "failed": failed }))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_steps() {
+        let steps = parse_steps(&json!([
+            { "type": "reward_group", "group_id": 3, "enabled": true },
+            { "type": "print_rundown", "delay_ms": 1500 },
+            { "type": "chat", "message": "hello", "on_error": "continue" },
+            { "type": "wait", "delay_ms": 500 },
+            { "type": "obs_scene", "scene": "BRB" }
+        ]))
+        .unwrap();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(
+            steps[0].action,
+            MacroAction::RewardGroup {
+                group_id: 3,
+                enabled: true
+            }
+        );
+        assert_eq!(steps[0].on_error, OnError::Stop);
+        assert_eq!(steps[1].action, MacroAction::PrintRundown { date: None });
+        assert_eq!(steps[1].delay_ms, 1500);
+        assert_eq!(steps[2].on_error, OnError::Continue);
+        assert_eq!(steps[3].action.kind(), "wait");
+        assert_eq!(
+            steps[4].action,
+            MacroAction::ObsScene {
+                scene: "BRB".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_steps_rejects_invalid() {
+        assert!(parse_steps(&json!([])).is_err());
+        assert!(parse_steps(&json!([{ "type": "unknown" }])).is_err());
+        assert!(parse_steps(&json!([{ "type": "chat", "message": " " }])).is_err());
+        assert!(parse_steps(&json!([{ "type": "print_rundown", "date": "tomorrow" }])).is_err());
+        assert!(parse_steps(&json!([{ "type": "ad_break", "duration_seconds": 0 }])).is_err());
+        assert!(parse_steps(&json!([{ "type": "obs_scene", "scene": "" }])).is_err());
+        assert!(parse_steps(&json!([{ "type": "wait", "delay_ms": MAX_DELAY_MS + 1 }])).is_err());
+        let too_many = vec![json!({ "type": "wait" }); MAX_STEPS + 1];
+        assert!(parse_steps(&Value::Array(too_many)).is_err());
+    }
+}
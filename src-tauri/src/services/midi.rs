@@ -0,0 +1,342 @@
+//! MIDI output for lighting consoles and DAWs.
+//!
+//! Mapped channel events send a note or control change to the configured
+//! port. On macOS/Linux a virtual port named `MIDI_PORT_NAME` is created
+//! when no existing port matches; on Windows an existing port (e.g. one
+//! created with loopMIDI) is required. A trigger's `params` look like:
+//!
+//! ```json
+//! { "type": "note", "note": 60, "velocity": 127, "duration_ms": 200 }
+//! { "type": "cc", "controller": 20, "value": 127,
+//!   "reset_value": 0, "reset_after_ms": 1000 }
+//! ```
+//!
+//! `channel` (1-16) may be set per trigger and defaults to `MIDI_CHANNEL`.
+
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use midir::{MidiOutput, MidiOutputConnection};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::event_triggers::ChannelEvent;
+
+pub const INTEGRATION: &str = "midi";
+
+const CLIENT_NAME: &str = "Cairo Overlay";
+const DEFAULT_NOTE_DURATION_MS: u64 = 200;
+const DEFAULT_RESET_AFTER_MS: u64 = 1000;
+
+/// Open output connection and the port name it was opened for.
+static CONNECTION: LazyLock<Mutex<Option<(String, MidiOutputConnection)>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// What a trigger sends.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MidiAction {
+    Note {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+        duration: Duration,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+        reset: Option<(u8, Duration)>,
+    },
+}
+
+impl MidiAction {
+    /// Messages sent immediately.
+    fn start_message(&self) -> [u8; 3] {
+        match *self {
+            Self::Note {
+                channel,
+                note,
+                velocity,
+                ..
+            } => [0x90 | channel, note, velocity],
+            Self::ControlChange {
+                channel,
+                controller,
+                value,
+                ..
+            } => [0xB0 | channel, controller, value],
+        }
+    }
+
+    /// Follow-up message (note off / CC reset) and its delay.
+    fn end_message(&self) -> Option<([u8; 3], Duration)> {
+        match *self {
+            Self::Note {
+                channel,
+                note,
+                duration,
+                ..
+            } => Some(([0x80 | channel, note, 0], duration)),
+            Self::ControlChange {
+                channel,
+                controller,
+                reset,
+                ..
+            } => reset.map(|(value, delay)| ([0xB0 | channel, controller, value], delay)),
+        }
+    }
+}
+
+struct MidiSettings {
+    enabled: bool,
+    port_name: String,
+    channel: u8,
+}
+
+fn load_settings(state: &SharedState) -> MidiSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    MidiSettings {
+        enabled: get("MIDI_ENABLED") == "true",
+        port_name: get("MIDI_PORT_NAME"),
+        channel: get("MIDI_CHANNEL").parse().unwrap_or(1),
+    }
+}
+
+/// Settings, connection state and available ports (for the status API).
+pub fn status(state: &SharedState) -> Value {
+    let s = load_settings(state);
+    let connected = CONNECTION
+        .lock()
+        .ok()
+        .and_then(|c| c.as_ref().map(|(name, _)| name.clone()));
+    json!({
+        "enabled": s.enabled,
+        "port_name": s.port_name,
+        "channel": s.channel,
+        "connected_port": connected,
+        "available_ports": list_ports().unwrap_or_default(),
+        "virtual_ports_supported": cfg!(unix),
+    })
+}
+
+/// Names of the system's MIDI output ports.
+pub fn list_ports() -> Result<Vec<String>, String> {
+    let out = MidiOutput::new(CLIENT_NAME).map_err(|e| e.to_string())?;
+    Ok(out
+        .ports()
+        .iter()
+        .filter_map(|p| out.port_name(p).ok())
+        .collect())
+}
+
+/// Check the `params` of a MIDI trigger.
+pub fn validate_params(params: &Value) -> Result<(), String> {
+    parse_action(params, 1).map(|_| ())
+}
+
+/// Send the MIDI messages mapped to `event`. No-op when MIDI is disabled.
+pub async fn handle_event(state: &SharedState, event: &ChannelEvent) {
+    let settings = load_settings(state);
+    if !settings.enabled {
+        return;
+    }
+    let triggers =
+        match state
+            .db()
+            .get_matching_event_triggers(INTEGRATION, event.kind, event.amount)
+        {
+            Ok(t) => t,
+            Err(e) => {
+                tracing::warn!("Failed to load MIDI triggers: {e}");
+                return;
+            }
+        };
+
+    for trigger in triggers {
+        match parse_action(&trigger.params, settings.channel) {
+            Ok(action) => {
+                if let Err(e) = play(&settings.port_name, action) {
+                    tracing::warn!(event = event.kind, "Failed to send MIDI message: {e}");
+                }
+            }
+            Err(e) => tracing::warn!(id = trigger.id, "Invalid MIDI trigger: {e}"),
+        }
+    }
+}
+
+/// Send one action regardless of mappings (used by the test endpoint).
+pub fn send_test(state: &SharedState, params: &Value) -> Result<(), String> {
+    let settings = load_settings(state);
+    let action = parse_action(params, settings.channel)?;
+    play(&settings.port_name, action)
+}
+
+/// Close the port (called when settings change so the next send reopens it).
+pub fn disconnect() {
+    let taken = CONNECTION.lock().ok().and_then(|mut c| c.take());
+    if let Some((_, conn)) = taken {
+        conn.close();
+    }
+}
+
+/// Send the start message now and schedule the end message, if any.
+fn play(port_name: &str, action: MidiAction) -> Result<(), String> {
+    send(port_name, &action.start_message())?;
+    if let Some((message, delay)) = action.end_message() {
+        let port_name = port_name.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if let Err(e) = send(&port_name, &message) {
+                tracing::warn!("Failed to send MIDI release: {e}");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn send(port_name: &str, message: &[u8]) -> Result<(), String> {
+    if port_name.is_empty() {
+        return Err("MIDI_PORT_NAME is not set".into());
+    }
+    let mut guard = CONNECTION.lock().map_err(|e| e.to_string())?;
+    if guard.as_ref().is_none_or(|(name, _)| name != port_name) {
+        if let Some((_, old)) = guard.take() {
+            old.close();
+        }
+        *guard = Some((port_name.to_string(), connect(port_name)?));
+    }
+    let Some((_, conn)) = guard.as_mut() else {
+        return Err("MIDI port is not connected".into());
+    };
+    if let Err(e) = conn.send(message) {
+        // Drop the connection so the next send reconnects (device unplugged).
+        *guard = None;
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+/// Connect to the first port whose name contains `port_name`, or create a
+/// virtual port where the platform supports it.
+fn connect(port_name: &str) -> Result<MidiOutputConnection, String> {
+    let out = MidiOutput::new(CLIENT_NAME).map_err(|e| e.to_string())?;
+    let existing = out.ports().into_iter().find(|p| {
+        out.port_name(p)
+            .is_ok_and(|name| name.to_lowercase().contains(&port_name.to_lowercase()))
+    });
+    if let Some(port) = existing {
+        tracing::info!(port_name, "Connected to MIDI port");
+        return out.connect(&port, port_name).map_err(|e| e.to_string());
+    }
+
+    #[cfg(unix)]
+    {
+        use midir::os::unix::VirtualOutput;
+        tracing::info!(port_name, "Created virtual MIDI port");
+        out.create_virtual(port_name).map_err(|e| e.to_string())
+    }
+    #[cfg(not(unix))]
+    {
+        Err(format!("MIDI port not found: {port_name}"))
+    }
+}
+
+/// Parse trigger params into an action. `default_channel` is 1-16.
+fn parse_action(params: &Value, default_channel: u8) -> Result<MidiAction, String> {
+    let byte = |key: &str, default: Option<u64>| -> Result<u8, String> {
+        let v = params
+            .get(key)
+            .and_then(|v| v.as_u64())
+            .or(default)
+            .ok_or_else(|| format!("params.{key} is required"))?;
+        u8::try_from(v)
+            .ok()
+            .filter(|b| *b <= 127)
+            .ok_or_else(|| format!("params.{key} must be 0-127"))
+    };
+    let ms = |key: &str, default: u64| {
+        Duration::from_millis(params.get(key).and_then(|v| v.as_u64()).unwrap_or(default))
+    };
+
+    let channel = params
+        .get("channel")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(u64::from(default_channel));
+    if !(1..=16).contains(&channel) {
+        return Err("params.channel must be 1-16".into());
+    }
+    let channel = channel as u8 - 1;
+
+    match params
+        .get("type")
+        .and_then(|v| v.as_str())
+        .unwrap_or("note")
+    {
+        "note" => Ok(MidiAction::Note {
+            channel,
+            note: byte("note", None)?,
+            velocity: byte("velocity", Some(127))?,
+            duration: ms("duration_ms", DEFAULT_NOTE_DURATION_MS),
+        }),
+        "cc" => {
+            let reset = match params.get("reset_value") {
+                Some(_) => Some((
+                    byte("reset_value", None)?,
+                    ms("reset_after_ms", DEFAULT_RESET_AFTER_MS),
+                )),
+                None => None,
+            };
+            Ok(MidiAction::ControlChange {
+                channel,
+                controller: byte("controller", None)?,
+                value: byte("value", Some(127))?,
+                reset,
+            })
+        }
+        other => Err(format!(
+            "params.type must be 'note' or 'cc' (got '{other}')"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_messages() {
+        let action = parse_action(&json!({ "note": 60, "channel": 2 }), 1).unwrap();
+        assert_eq!(action.start_message(), [0x91, 60, 127]);
+        assert_eq!(
+            action.end_message(),
+            Some(([0x81, 60, 0], Duration::from_millis(200)))
+        );
+    }
+
+    #[test]
+    fn test_cc_messages() {
+        let action = parse_action(
+            &json!({ "type": "cc", "controller": 20, "value": 100, "reset_value": 0 }),
+            10,
+        )
+        .unwrap();
+        assert_eq!(action.start_message(), [0xB9, 20, 100]);
+        assert_eq!(
+            action.end_message(),
+            Some(([0xB9, 20, 0], Duration::from_millis(1000)))
+        );
+
+        let no_reset = parse_action(&json!({ "type": "cc", "controller": 1 }), 1).unwrap();
+        assert_eq!(no_reset.end_message(), None);
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        assert!(parse_action(&json!({}), 1).is_err());
+        assert!(parse_action(&json!({ "note": 128 }), 1).is_err());
+        assert!(parse_action(&json!({ "note": 60, "channel": 17 }), 1).is_err());
+        assert!(parse_action(&json!({ "type": "pc", "note": 1 }), 1).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! Viewer milestones: celebrate follower/viewer round numbers.
+//!
+//! Aggregate stats are polled periodically. When a configured threshold is
+//! crossed it is recorded in the DB first, so each milestone fires exactly
+//! once even across restarts. If several thresholds are crossed at once
+//! (e.g. the feature was just enabled), only the highest is celebrated.
+
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::print_queue::PrintCategory;
+use crate::services::print_templates::{self, Template};
+use crate::services::{helix, print_render, twitch_chat};
+
+pub const KIND_FOLLOWERS: &str = "followers";
+pub const KIND_VIEWERS: &str = "viewers";
+
+struct MilestoneSettings {
+    enabled: bool,
+    interval: Duration,
+    follower_thresholds: Vec<i64>,
+    viewer_thresholds: Vec<i64>,
+    chat_enabled: bool,
+    chat_message: String,
+    print_enabled: bool,
+}
+
+fn load_settings(state: &SharedState) -> MilestoneSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    MilestoneSettings {
+        enabled: get("MILESTONES_ENABLED") == "true",
+        interval: Duration::from_secs(
+            get("MILESTONE_CHECK_INTERVAL")
+                .parse::<u64>()
+                .unwrap_or(60)
+                .max(30),
+        ),
+        follower_thresholds: parse_thresholds(&get("MILESTONE_FOLLOWER_THRESHOLDS")),
+        viewer_thresholds: parse_thresholds(&get("MILESTONE_VIEWER_THRESHOLDS")),
+        chat_enabled: get("MILESTONE_CHAT_ENABLED") == "true",
+        chat_message: get("MILESTONE_CHAT_MESSAGE"),
+        print_enabled: get("MILESTONE_PRINT_ENABLED") == "true",
+    }
+}
+
+/// Parse a comma-separated threshold list into a sorted, de-duplicated list.
+pub fn parse_thresholds(raw: &str) -> Vec<i64> {
+    let mut out: Vec<i64> = raw
+        .split(',')
+        .filter_map(|s| s.trim().parse::<i64>().ok())
+        .filter(|v| *v > 0)
+        .collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+/// Background loop polling follower and viewer counts.
+pub async fn run_monitor(state: SharedState) {
+    tokio::time::sleep(Duration::from_secs(20)).await;
+
+    loop {
+        let settings = load_settings(&state);
+        if settings.enabled {
+            if let Err(e) = check_once(&state, &settings).await {
+                tracing::debug!("Milestone check skipped: {e}");
+            }
+        }
+        tokio::time::sleep(settings.interval).await;
+    }
+}
+
+async fn check_once(state: &SharedState, settings: &MilestoneSettings) -> Result<(), String> {
+    let ctx = helix::context(state).await?;
+
+    if !settings.follower_thresholds.is_empty() {
+        let followers = ctx
+            .api
+            .get_follower_count(&ctx.token, &ctx.broadcaster_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        evaluate(
+            state,
+            settings,
+            KIND_FOLLOWERS,
+            &settings.follower_thresholds,
+            followers as i64,
+        )
+        .await;
+    }
+
+    if !settings.viewer_thresholds.is_empty() {
+        let stream = ctx
+            .api
+            .get_stream_info(&ctx.token, &ctx.broadcaster_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if stream.is_live {
+            evaluate(
+                state,
+                settings,
+                KIND_VIEWERS,
+                &settings.viewer_thresholds,
+                stream.viewer_count as i64,
+            )
+            .await;
+        }
+    }
+    Ok(())
+}
+
+async fn evaluate(
+    state: &SharedState,
+    settings: &MilestoneSettings,
+    kind: &str,
+    thresholds: &[i64],
+    value: i64,
+) {
+    let now = chrono::Utc::now().timestamp();
+    let mut newest = None;
+    for &threshold in thresholds.iter().filter(|t| **t <= value) {
+        match state.db().mark_milestone_fired(kind, threshold, value, now) {
+            Ok(true) => newest = Some(threshold),
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("Failed to record milestone {kind}/{threshold}: {e}");
+                return;
+            }
+        }
+    }
+    if let Some(threshold) = newest {
+        celebrate(state, settings, kind, threshold, value).await;
+    }
+}
+
+async fn celebrate(
+    state: &SharedState,
+    settings: &MilestoneSettings,
+    kind: &str,
+    threshold: i64,
+    value: i64,
+) {
+    tracing::info!(kind, threshold, value, "Milestone reached");
+    let label = kind_label(kind);
+
+    let msg = json!({
+        "type": "milestone_reached",
+        "data": {
+            "kind": kind,
+            "label": label,
+            "threshold": threshold,
+            "value": value,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        }
+    });
+    let _ = state.ws_sender().send(msg.to_string());
+
+    if settings.chat_enabled {
+        let text = settings
+            .chat_message
+            .replace("{label}", label)
+            .replace("{threshold}", &threshold.to_string())
+            .replace("{value}", &value.to_string());
+        if let Err(e) = twitch_chat::send_chat(state, &text).await {
+            tracing::warn!("Failed to send milestone chat message: {e}");
+        }
+    }
+
+    if settings.print_enabled {
+        let locale = print_templates::locale(state);
+        let label = print_templates::text(locale, kind_template(kind));
+        let details = print_templates::render(
+            locale,
+            Template::MilestoneDetails,
+            &[
+                ("label", label),
+                ("threshold", &locale.format_number(threshold)),
+                ("value", &locale.format_number(value)),
+            ],
+        );
+        let title = print_templates::text(locale, Template::MilestoneTitle);
+        // Follower milestones go to the printer routed for follows.
+        let category = if kind == KIND_FOLLOWERS {
+            PrintCategory::Follow
+        } else {
+            PrintCategory::Other
+        };
+        if let Err(e) = print_render::print_titled(state, title, label, &details, category).await {
+            tracing::warn!("Failed to print milestone: {e}");
+        }
+    }
+}
+
+fn kind_template(kind: &str) -> Template {
+    match kind {
+        KIND_FOLLOWERS => Template::MilestoneFollowers,
+        KIND_VIEWERS => Template::MilestoneViewers,
+        _ => Template::MilestoneOther,
+    }
+}
+
+fn kind_label(kind: &str) -> &'static str {
+    match kind {
+        KIND_FOLLOWERS => "フォロワー",
+        KIND_VIEWERS => "同時視聴者",
+        _ => "マイルストーン",
+    }
+}
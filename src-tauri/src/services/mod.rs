@@ -1,10 +1,79 @@
+pub mod ad_break;
+pub mod afk;
+pub mod badges;
 pub mod cache;
+pub mod chat_commands;
+pub mod chat_filter;
+pub mod chat_print;
+pub mod chat_render;
+pub mod chat_timers;
+pub mod chat_wall;
+pub mod clips;
+pub mod consent;
+pub mod cron;
+pub mod demo;
+pub mod discord;
+pub mod emote_images;
+pub mod emote_rain;
+pub mod emotes;
+pub mod event_archive;
+pub mod event_triggers;
 pub mod fax;
+pub mod features;
+pub mod first_chat;
 pub mod font;
+pub mod funding;
+pub mod helix;
+pub mod http;
+pub mod hype_train;
+pub mod irc_relay;
+pub mod jobs;
+pub mod latency;
+pub mod lights;
 pub mod log_buffer;
+pub mod lottery;
+pub mod lottery_claims;
+pub mod lottery_draw;
+pub mod lottery_presets;
+pub mod lottery_receipt;
+pub mod lottery_winner;
+pub mod macros;
+pub mod midi;
+pub mod milestones;
+pub mod moderation_log;
 pub mod music;
 pub mod music_playlist;
+pub mod obs;
+pub mod osc;
+pub mod overlay_effects;
+pub mod overlay_preview;
+pub mod overlay_tokens;
+pub mod panic;
+pub mod pre_show;
+pub mod preflight;
 pub mod print_queue;
 pub mod printer;
+pub mod print_render;
+pub mod print_rules;
+pub mod print_templates;
+pub mod print_vote;
+pub mod profiles;
 pub mod printer_pipeline;
+pub mod projections;
+pub mod quotes;
+pub mod reward_counts;
+pub mod reward_groups;
+pub mod rundown;
+pub mod scheduler;
+pub mod search;
+pub mod selftest;
+pub mod setup;
+pub mod shoutouts;
+pub mod smart_plug;
 pub mod status;
+pub mod stream_sessions;
+pub mod translations;
+pub mod tts;
+pub mod twitch_chat;
+pub mod webhooks;
+pub mod wordcloud;
@@ -0,0 +1,115 @@
+//! Moderator action log from EventSub `channel.moderate`.
+//!
+//! Every action (bans, timeouts, message deletions, chat mode changes, ...)
+//! is stored in `moderation_log` with the moderator, the user acted on and
+//! the action's own payload object, and sent to overlays as
+//! `moderation_action`. The log is read back through
+//! `GET /api/moderation/log`.
+
+use overlay_db::moderation::ModerationAction;
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::eventsub_support::{send_ws, str_field};
+
+/// Payload key of the object describing `action`. Most actions use their
+/// own name; a few share one.
+fn details_key(action: &str) -> &str {
+    match action {
+        "add_blocked_term"
+        | "add_permitted_term"
+        | "remove_blocked_term"
+        | "remove_permitted_term" => "automod_terms",
+        "approve_unban_request" | "deny_unban_request" => "unban_request",
+        other => other,
+    }
+}
+
+/// Build the log entry for a `channel.moderate` payload received at `now`.
+pub fn parse_action(payload: &Value, now: i64) -> ModerationAction {
+    let action = str_field(payload, &["action"]);
+    let details = payload
+        .get(details_key(&action))
+        .filter(|d| d.is_object())
+        .cloned()
+        .unwrap_or_else(|| Value::Object(Default::default()));
+    let duration_secs = details
+        .get("expires_at")
+        .and_then(|v| v.as_str())
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| (t.timestamp() - now).max(0));
+    ModerationAction {
+        id: 0,
+        moderator_id: str_field(payload, &["moderator_user_id"]),
+        moderator_login: str_field(payload, &["moderator_user_login"]),
+        moderator_name: str_field(payload, &["moderator_user_name"]),
+        target_id: str_field(&details, &["user_id"]),
+        target_login: str_field(&details, &["user_login"]),
+        target_name: str_field(&details, &["user_name"]),
+        reason: str_field(&details, &["reason"]),
+        duration_secs,
+        details,
+        action,
+        created_at: now,
+    }
+}
+
+/// Store a `channel.moderate` notification and forward it to overlays.
+pub fn record(state: &SharedState, payload: &Value) {
+    let mut entry = parse_action(payload, chrono::Utc::now().timestamp());
+    if entry.action.is_empty() {
+        return;
+    }
+    match state.db().add_moderation_action(&entry) {
+        Ok(id) => entry.id = id,
+        Err(e) => tracing::warn!("Failed to record moderation action: {e}"),
+    }
+    send_ws(state, "moderation_action", &entry);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_timeout() {
+        let payload = json!({
+            "moderator_user_id": "10",
+            "moderator_user_login": "mod",
+            "moderator_user_name": "Mod",
+            "action": "timeout",
+            "timeout": {
+                "user_id": "20",
+                "user_login": "troll",
+                "user_name": "Troll",
+                "reason": "spam",
+                "expires_at": "2024-01-01T00:10:00Z",
+            },
+        });
+        let now = chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .timestamp();
+        let entry = parse_action(&payload, now);
+        assert_eq!(entry.action, "timeout");
+        assert_eq!(entry.moderator_login, "mod");
+        assert_eq!(entry.target_login, "troll");
+        assert_eq!(entry.reason, "spam");
+        assert_eq!(entry.duration_secs, Some(600));
+    }
+
+    #[test]
+    fn test_parse_chat_wide_action() {
+        let payload = json!({
+            "action": "add_blocked_term",
+            "automod_terms": { "action": "add", "list": "blocked", "terms": ["x"] },
+        });
+        let entry = parse_action(&payload, 0);
+        assert_eq!(entry.details["terms"][0], "x");
+        assert!(entry.target_id.is_empty());
+        assert_eq!(entry.duration_secs, None);
+
+        let entry = parse_action(&json!({ "action": "emoteonly" }), 0);
+        assert!(entry.details.as_object().is_some_and(|d| d.is_empty()));
+    }
+}
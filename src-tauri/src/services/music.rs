@@ -10,6 +10,8 @@ use overlay_db::Database;
 use overlay_db::music::Track;
 use sha2::{Digest, Sha256};
 
+use crate::config::portable;
+
 const MAX_FILE_SIZE: usize = 50 * 1024 * 1024; // 50MB
 const VALID_EXTENSIONS: &[&str] = &["mp3", "wav", "m4a", "ogg"];
 
@@ -101,7 +103,7 @@ impl MusicService {
 
         let track = Track {
             id: id.clone(),
-            file_path: file_path.to_string_lossy().into_owned(),
+            file_path: portable::to_stored(&self.data_dir, &file_path),
             title: meta.title,
             artist: meta.artist,
             album: meta.album,
@@ -128,7 +130,7 @@ impl MusicService {
 
     pub fn delete_track(&self, id: &str) -> Result<(), MusicError> {
         let track = self.get_track(id)?;
-        let _ = std::fs::remove_file(&track.file_path);
+        let _ = std::fs::remove_file(self.track_file(&track));
         let artwork_path = self.artwork_dir().join(format!("{id}.jpg"));
         let _ = std::fs::remove_file(artwork_path);
         self.db.delete_track(id)?;
@@ -139,7 +141,7 @@ impl MusicService {
     pub fn delete_all_tracks(&self) -> Result<(), MusicError> {
         let tracks = self.db.get_all_tracks()?;
         for track in &tracks {
-            let _ = std::fs::remove_file(&track.file_path);
+            let _ = std::fs::remove_file(self.track_file(track));
             let artwork_path = self.artwork_dir().join(format!("{}.jpg", track.id));
             let _ = std::fs::remove_file(artwork_path);
             self.db.delete_track(&track.id)?;
@@ -148,9 +150,31 @@ impl MusicService {
         Ok(())
     }
 
+    /// Remove the tracks whose audio file is gone (e.g. deleted by hand).
+    /// Returns how many were removed.
+    pub fn prune_missing(&self) -> Result<usize, MusicError> {
+        let mut removed = 0;
+        for track in self.db.get_all_tracks()? {
+            if self.track_file(&track).exists() {
+                continue;
+            }
+            let artwork_path = self.artwork_dir().join(format!("{}.jpg", track.id));
+            let _ = std::fs::remove_file(artwork_path);
+            self.db.delete_track(&track.id)?;
+            tracing::info!(id = %track.id, "Track file missing, removed from library");
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
     pub fn get_track_path(&self, id: &str) -> Result<PathBuf, MusicError> {
         let track = self.get_track(id)?;
-        Ok(PathBuf::from(&track.file_path))
+        Ok(self.track_file(&track))
+    }
+
+    /// Audio file of `track` on disk.
+    fn track_file(&self, track: &Track) -> PathBuf {
+        portable::resolve(&self.data_dir, &track.file_path)
     }
 
     pub fn get_artwork_path(&self, id: &str) -> Option<PathBuf> {
@@ -0,0 +1,358 @@
+//! OBS Studio control over obs-websocket v5.
+//!
+//! Mapped channel events run OBS actions. A rule is an `event_triggers` row
+//! of the `obs` integration whose `params` look like:
+//!
+//! ```json
+//! { "action": "switch_scene", "scene": "Raid" }
+//! { "action": "set_source", "scene": "Main", "source": "LiveBadge", "visible": true }
+//! ```
+//!
+//! `set_source` toggles the source when `visible` is omitted or null.
+//! Strings expand `{user}` and `{amount}`. A connection is opened for each
+//! event and closed once its rules have run. Macros switch scenes with
+//! [`switch_scene`].
+
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::event_triggers::{ChannelEvent, expand};
+
+pub const INTEGRATION: &str = "obs";
+
+pub const ACTION_SWITCH_SCENE: &str = "switch_scene";
+pub const ACTION_SET_SOURCE: &str = "set_source";
+
+const RPC_VERSION: u64 = 1;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// obs-websocket opcodes.
+const OP_HELLO: u64 = 0;
+const OP_IDENTIFY: u64 = 1;
+const OP_IDENTIFIED: u64 = 2;
+const OP_REQUEST: u64 = 6;
+const OP_REQUEST_RESPONSE: u64 = 7;
+
+struct ObsSettings {
+    enabled: bool,
+    host: String,
+    port: u16,
+    password: String,
+}
+
+fn load_settings(state: &SharedState) -> ObsSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    ObsSettings {
+        enabled: get("OBS_ENABLED") == "true",
+        host: get("OBS_HOST"),
+        port: get("OBS_PORT").parse().unwrap_or(4455),
+        password: get("OBS_PASSWORD"),
+    }
+}
+
+/// `authentication` string of the Identify message:
+/// `base64(sha256(base64(sha256(password + salt)) + challenge))`.
+pub fn auth_string(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{password}{salt}")));
+    BASE64.encode(Sha256::digest(format!("{secret}{challenge}")))
+}
+
+/// An identified obs-websocket session.
+struct Session {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+}
+
+impl Session {
+    async fn connect(settings: &ObsSettings) -> Result<Self, String> {
+        if settings.host.is_empty() {
+            return Err("OBS_HOST is not set".into());
+        }
+        let url = format!("ws://{}:{}", settings.host, settings.port);
+        let (ws, _) = tokio::time::timeout(TIMEOUT, tokio_tungstenite::connect_async(url))
+            .await
+            .map_err(|_| "Timed out connecting to OBS".to_string())?
+            .map_err(|e| format!("Failed to connect to OBS: {e}"))?;
+        let mut session = Self { ws, next_id: 0 };
+
+        let hello = session.recv_op(OP_HELLO).await?;
+        let mut identify = json!({ "rpcVersion": RPC_VERSION, "eventSubscriptions": 0 });
+        if let Some(auth) = hello.get("authentication") {
+            if settings.password.is_empty() {
+                return Err("OBS requires a password (OBS_PASSWORD)".into());
+            }
+            let salt = auth["salt"].as_str().unwrap_or_default();
+            let challenge = auth["challenge"].as_str().unwrap_or_default();
+            identify["authentication"] = json!(auth_string(&settings.password, salt, challenge));
+        }
+        session.send_op(OP_IDENTIFY, identify).await?;
+        session.recv_op(OP_IDENTIFIED).await?;
+        Ok(session)
+    }
+
+    async fn send_op(&mut self, op: u64, d: Value) -> Result<(), String> {
+        let text = json!({ "op": op, "d": d }).to_string();
+        self.ws
+            .send(Message::Text(text.into()))
+            .await
+            .map_err(|e| format!("Failed to send to OBS: {e}"))
+    }
+
+    /// Wait for the next message with opcode `op` and return its data.
+    async fn recv_op(&mut self, op: u64) -> Result<Value, String> {
+        loop {
+            let msg = tokio::time::timeout(TIMEOUT, self.ws.next())
+                .await
+                .map_err(|_| "Timed out waiting for OBS".to_string())?;
+            let text = match msg {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(frame))) => {
+                    let reason = frame.map(|f| f.reason.to_string()).unwrap_or_default();
+                    return Err(format!("OBS closed the connection: {reason}"));
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(format!("OBS connection error: {e}")),
+                None => return Err("OBS closed the connection".into()),
+            };
+            let Ok(value) = serde_json::from_str::<Value>(text.as_str()) else {
+                continue;
+            };
+            if value["op"].as_u64() == Some(op) {
+                return Ok(value["d"].clone());
+            }
+        }
+    }
+
+    /// Run one request and return its `responseData`.
+    async fn request(&mut self, request_type: &str, data: Value) -> Result<Value, String> {
+        self.next_id += 1;
+        let request_id = self.next_id.to_string();
+        self.send_op(
+            OP_REQUEST,
+            json!({ "requestType": request_type, "requestId": request_id, "requestData": data }),
+        )
+        .await?;
+        loop {
+            let d = self.recv_op(OP_REQUEST_RESPONSE).await?;
+            if d["requestId"].as_str() != Some(request_id.as_str()) {
+                continue;
+            }
+            let status = &d["requestStatus"];
+            if status["result"].as_bool() != Some(true) {
+                let comment = status["comment"].as_str().unwrap_or("request failed");
+                return Err(format!("{request_type}: {comment}"));
+            }
+            return Ok(d.get("responseData").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    async fn close(mut self) {
+        let _ = self.ws.close(None).await;
+    }
+}
+
+/// OBS settings and, when enabled, the connection state and current scene.
+pub async fn status(state: &SharedState) -> Value {
+    let s = load_settings(state);
+    let mut status = json!({
+        "enabled": s.enabled,
+        "host": s.host,
+        "port": s.port,
+        "has_password": !s.password.is_empty(),
+        "connected": false,
+    });
+    if !s.enabled {
+        return status;
+    }
+    let probe = async {
+        let mut session = Session::connect(&s).await?;
+        let version = session.request("GetVersion", json!({})).await;
+        let scene = session.request("GetCurrentProgramScene", json!({})).await;
+        session.close().await;
+        Ok::<_, String>((version?, scene?))
+    };
+    match probe.await {
+        Ok((version, scene)) => {
+            status["connected"] = json!(true);
+            status["obs_version"] = version["obsVersion"].clone();
+            status["websocket_version"] = version["obsWebSocketVersion"].clone();
+            status["current_scene"] = scene["currentProgramSceneName"].clone();
+        }
+        Err(e) => status["error"] = json!(e),
+    }
+    status
+}
+
+/// Switch the program scene to `scene`.
+pub async fn switch_scene(state: &SharedState, scene: &str) -> Result<(), String> {
+    let settings = load_settings(state);
+    if !settings.enabled {
+        return Err("OBS integration is disabled".into());
+    }
+    let mut session = Session::connect(&settings).await?;
+    let result = session
+        .request("SetCurrentProgramScene", json!({ "sceneName": scene }))
+        .await;
+    session.close().await;
+    result.map(|_| ())
+}
+
+fn required_str<'a>(params: &'a Value, key: &str) -> Result<&'a str, String> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| format!("params.{key} is required"))
+}
+
+/// Check the `params` of an OBS rule.
+pub fn validate_params(params: &Value) -> Result<(), String> {
+    match params.get("action").and_then(|v| v.as_str()) {
+        Some(ACTION_SWITCH_SCENE) => {
+            required_str(params, "scene")?;
+        }
+        Some(ACTION_SET_SOURCE) => {
+            required_str(params, "scene")?;
+            required_str(params, "source")?;
+            if !matches!(
+                params.get("visible"),
+                None | Some(Value::Null) | Some(Value::Bool(_))
+            ) {
+                return Err("params.visible must be true, false or null (toggle)".into());
+            }
+        }
+        _ => {
+            return Err(format!(
+                "params.action must be '{ACTION_SWITCH_SCENE}' or '{ACTION_SET_SOURCE}'"
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn run_action(
+    session: &mut Session,
+    params: &Value,
+    event: &ChannelEvent,
+) -> Result<(), String> {
+    let field = |key: &str| expand(params[key].as_str().unwrap_or_default(), event);
+    let scene = field("scene");
+    match params["action"].as_str() {
+        Some(ACTION_SWITCH_SCENE) => {
+            session
+                .request("SetCurrentProgramScene", json!({ "sceneName": scene }))
+                .await?;
+        }
+        Some(ACTION_SET_SOURCE) => {
+            let source = field("source");
+            let item = session
+                .request(
+                    "GetSceneItemId",
+                    json!({ "sceneName": scene, "sourceName": source }),
+                )
+                .await?;
+            let item_id = item["sceneItemId"].clone();
+            let visible = match params["visible"].as_bool() {
+                Some(visible) => visible,
+                None => {
+                    let current = session
+                        .request(
+                            "GetSceneItemEnabled",
+                            json!({ "sceneName": scene, "sceneItemId": item_id }),
+                        )
+                        .await?;
+                    !current["sceneItemEnabled"].as_bool().unwrap_or(false)
+                }
+            };
+            session
+                .request(
+                    "SetSceneItemEnabled",
+                    json!({ "sceneName": scene, "sceneItemId": item_id, "sceneItemEnabled": visible }),
+                )
+                .await?;
+        }
+        other => return Err(format!("Unknown OBS action: {other:?}")),
+    }
+    Ok(())
+}
+
+/// Run the OBS rules mapped to `event`. No-op when OBS is disabled.
+pub async fn handle_event(state: &SharedState, event: &ChannelEvent) {
+    let settings = load_settings(state);
+    if !settings.enabled {
+        return;
+    }
+    let rules = match state
+        .db()
+        .get_matching_event_triggers(INTEGRATION, event.kind, event.amount)
+    {
+        Ok(rules) => rules,
+        Err(e) => {
+            tracing::warn!("Failed to load OBS rules: {e}");
+            return;
+        }
+    };
+    if rules.is_empty() {
+        return;
+    }
+    let mut session = match Session::connect(&settings).await {
+        Ok(session) => session,
+        Err(e) => {
+            tracing::warn!("{e}");
+            return;
+        }
+    };
+    for rule in rules {
+        match run_action(&mut session, &rule.params, event).await {
+            Ok(()) => tracing::debug!(rule = rule.id, event = event.kind, "OBS action run"),
+            Err(e) => tracing::warn!(rule = rule.id, "OBS action failed: {e}"),
+        }
+    }
+    session.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_string() {
+        // Example from the obs-websocket protocol documentation.
+        assert_eq!(
+            auth_string(
+                "supersecretpassword",
+                "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+                "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY=",
+            ),
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+    }
+
+    #[test]
+    fn test_validate_params() {
+        assert!(validate_params(&json!({ "action": "switch_scene", "scene": "Raid" })).is_ok());
+        assert!(
+            validate_params(&json!({ "action": "set_source", "scene": "Main", "source": "Badge" }))
+                .is_ok()
+        );
+        assert!(
+            validate_params(
+                &json!({ "action": "set_source", "scene": "Main", "source": "Badge", "visible": "yes" })
+            )
+            .is_err()
+        );
+        assert!(validate_params(&json!({ "action": "switch_scene" })).is_err());
+        assert!(validate_params(&json!({ "scene": "Raid" })).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! OSC output for avatar integrations (VRChat, VSeeFace, …).
+//!
+//! Mapped channel events are sent as OSC messages over UDP. A trigger's
+//! `params` look like:
+//!
+//! ```json
+//! { "address": "/avatar/parameters/Cheer", "value": true,
+//!   "reset_value": false, "reset_after_ms": 1500 }
+//! ```
+//!
+//! `value` may be a bool, integer, float, string or an array of those
+//! (multiple arguments). Strings expand `{user}` and `{amount}`; the exact
+//! string `"{amount}"` is sent as an integer. With `reset_value`, a second
+//! message is sent after `reset_after_ms` (default 1000 ms), which is how
+//! VRChat parameters are usually pulsed.
+
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tokio::net::UdpSocket;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::event_triggers::{ChannelEvent, expand};
+
+pub const INTEGRATION: &str = "osc";
+
+const DEFAULT_RESET_AFTER_MS: u64 = 1000;
+
+/// A single OSC argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+    Bool(bool),
+}
+
+struct OscSettings {
+    enabled: bool,
+    host: String,
+    port: u16,
+}
+
+fn load_settings(state: &SharedState) -> OscSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    OscSettings {
+        enabled: get("OSC_ENABLED") == "true",
+        host: get("OSC_HOST"),
+        port: get("OSC_PORT").parse().unwrap_or(9000),
+    }
+}
+
+/// Current OSC settings as JSON (for the status API).
+pub fn status(state: &SharedState) -> Value {
+    let s = load_settings(state);
+    json!({ "enabled": s.enabled, "host": s.host, "port": s.port })
+}
+
+/// Check the `params` of an OSC trigger.
+pub fn validate_params(params: &Value) -> Result<(), String> {
+    let address = params
+        .get("address")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if !address.starts_with('/') {
+        return Err("params.address must start with '/'".into());
+    }
+    if params
+        .get("value")
+        .is_some_and(|v| args_from_json(v, None).is_none())
+    {
+        return Err("params.value must be a bool, number, string or array of those".into());
+    }
+    Ok(())
+}
+
+/// Send the OSC messages mapped to `event`. No-op when OSC is disabled.
+pub async fn handle_event(state: &SharedState, event: &ChannelEvent) {
+    let settings = load_settings(state);
+    if !settings.enabled {
+        return;
+    }
+    let triggers =
+        match state
+            .db()
+            .get_matching_event_triggers(INTEGRATION, event.kind, event.amount)
+        {
+            Ok(t) => t,
+            Err(e) => {
+                tracing::warn!("Failed to load OSC triggers: {e}");
+                return;
+            }
+        };
+
+    for trigger in triggers {
+        let params = &trigger.params;
+        let address = params["address"].as_str().unwrap_or_default().to_string();
+        let value = params.get("value").cloned().unwrap_or(json!(true));
+        let Some(args) = args_from_json(&value, Some(event)) else {
+            continue;
+        };
+        if let Err(e) = send(&settings, &address, &args).await {
+            tracing::warn!(address, "Failed to send OSC message: {e}");
+            continue;
+        }
+        tracing::debug!(address, event = event.kind, "OSC message sent");
+
+        let Some(reset) = params
+            .get("reset_value")
+            .and_then(|v| args_from_json(v, Some(event)))
+        else {
+            continue;
+        };
+        let delay = Duration::from_millis(
+            params["reset_after_ms"]
+                .as_u64()
+                .unwrap_or(DEFAULT_RESET_AFTER_MS),
+        );
+        let settings = load_settings(state);
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if let Err(e) = send(&settings, &address, &reset).await {
+                tracing::warn!(address, "Failed to send OSC reset: {e}");
+            }
+        });
+    }
+}
+
+/// Send one message regardless of mappings (used by the test endpoint).
+pub async fn send_test(state: &SharedState, address: &str, value: &Value) -> Result<(), String> {
+    let settings = load_settings(state);
+    if !address.starts_with('/') {
+        return Err("address must start with '/'".into());
+    }
+    let args = args_from_json(value, None).ok_or("unsupported value type")?;
+    send(&settings, address, &args).await
+}
+
+async fn send(settings: &OscSettings, address: &str, args: &[OscArg]) -> Result<(), String> {
+    if settings.host.is_empty() {
+        return Err("OSC_HOST is not set".into());
+    }
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| e.to_string())?;
+    socket
+        .send_to(
+            &encode_message(address, args),
+            (settings.host.as_str(), settings.port),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Convert a mapped JSON value into OSC arguments.
+fn args_from_json(value: &Value, event: Option<&ChannelEvent>) -> Option<Vec<OscArg>> {
+    let single = |v: &Value| -> Option<OscArg> {
+        match v {
+            Value::Bool(b) => Some(OscArg::Bool(*b)),
+            Value::Number(n) if n.is_i64() || n.is_u64() => {
+                Some(OscArg::Int(n.as_i64().unwrap_or(i64::from(i32::MAX)) as i32))
+            }
+            Value::Number(n) => Some(OscArg::Float(n.as_f64()? as f32)),
+            Value::String(s) => match event {
+                Some(e) if s == "{amount}" => Some(OscArg::Int(e.amount as i32)),
+                Some(e) => Some(OscArg::Str(expand(s, e))),
+                None => Some(OscArg::Str(s.clone())),
+            },
+            _ => None,
+        }
+    };
+    match value {
+        Value::Array(items) => items.iter().map(single).collect(),
+        other => single(other).map(|a| vec![a]),
+    }
+}
+
+/// Encode an OSC 1.0 message.
+pub fn encode_message(address: &str, args: &[OscArg]) -> Vec<u8> {
+    let mut tags = String::from(",");
+    let mut data = Vec::new();
+    for arg in args {
+        match arg {
+            OscArg::Int(i) => {
+                tags.push('i');
+                data.extend_from_slice(&i.to_be_bytes());
+            }
+            OscArg::Float(f) => {
+                tags.push('f');
+                data.extend_from_slice(&f.to_be_bytes());
+            }
+            OscArg::Str(s) => {
+                tags.push('s');
+                push_padded_str(&mut data, s);
+            }
+            OscArg::Bool(b) => tags.push(if *b { 'T' } else { 'F' }),
+        }
+    }
+
+    let mut packet = Vec::new();
+    push_padded_str(&mut packet, address);
+    push_padded_str(&mut packet, &tags);
+    packet.extend(data);
+    packet
+}
+
+/// Null-terminated string padded to a multiple of 4 bytes.
+fn push_padded_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(s.as_bytes());
+    let pad = 4 - s.len() % 4;
+    buf.extend(std::iter::repeat_n(0u8, pad));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_bool_message() {
+        let packet = encode_message("/a", &[OscArg::Bool(true)]);
+        assert_eq!(packet, b"/a\0\0,T\0\0".to_vec());
+    }
+
+    #[test]
+    fn test_encode_int_float_string() {
+        let packet = encode_message(
+            "/test",
+            &[OscArg::Int(1), OscArg::Float(0.5), OscArg::Str("hi".into())],
+        );
+        let mut expected = b"/test\0\0\0,ifs\0\0\0\0".to_vec();
+        expected.extend_from_slice(&1i32.to_be_bytes());
+        expected.extend_from_slice(&0.5f32.to_be_bytes());
+        expected.extend_from_slice(b"hi\0\0");
+        assert_eq!(packet, expected);
+        assert_eq!(packet.len() % 4, 0);
+    }
+
+    #[test]
+    fn test_args_from_json_expands_placeholders() {
+        let event = ChannelEvent::new("cheer", "alice", 500);
+        let args = args_from_json(&json!(["{user}: {amount}", "{amount}", 1.5]), Some(&event));
+        assert_eq!(
+            args,
+            Some(vec![
+                OscArg::Str("alice: 500".into()),
+                OscArg::Int(500),
+                OscArg::Float(1.5),
+            ])
+        );
+        assert!(args_from_json(&json!({ "x": 1 }), None).is_none());
+    }
+
+    #[test]
+    fn test_validate_params() {
+        assert!(validate_params(&json!({ "address": "/avatar/parameters/Cheer" })).is_ok());
+        assert!(validate_params(&json!({ "address": "avatar" })).is_err());
+        assert!(validate_params(&json!({ "address": "/a", "value": null })).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Overlay visual effects (confetti, fireworks, screen shake).
+//!
+//! Effects are broadcast as `overlay_effect` WebSocket messages; the
+//! overlay renders them for `duration_ms`. A request names either a built-in
+//! effect with optional `params`, or a stored preset whose values the
+//! request may override. Event triggers use the same shape, e.g.
+//! `{ "preset": "big-cheer" }` or `{ "effect": "confetti" }`.
+
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::eventsub_support::send_ws;
+use crate::services::event_triggers::ChannelEvent;
+
+pub const INTEGRATION: &str = "effects";
+
+const MAX_DURATION_MS: i64 = 60_000;
+
+/// Built-in effects and their default durations in milliseconds.
+pub const EFFECTS: &[(&str, i64)] = &[
+    ("confetti", 4000),
+    ("fireworks", 5000),
+    ("screen_shake", 800),
+];
+
+/// A fully resolved effect ready to broadcast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedEffect {
+    pub effect: String,
+    pub params: Value,
+    pub duration_ms: i64,
+    pub preset: Option<String>,
+}
+
+/// Check the `params` of an effects trigger (the request shape).
+pub fn validate_params(params: &Value) -> Result<(), String> {
+    match params.get("preset").and_then(|v| v.as_str()) {
+        Some(preset) if !preset.is_empty() => Ok(()),
+        _ => resolve_inline(params, None).map(|_| ()),
+    }
+}
+
+/// Validate a preset definition.
+pub fn validate_preset(effect: &str, params: &Value, duration_ms: i64) -> Result<(), String> {
+    resolve_inline(
+        &json!({ "effect": effect, "params": params, "duration_ms": duration_ms }),
+        None,
+    )
+    .map(|_| ())
+}
+
+/// Resolve a request (preset and/or inline effect) against stored presets.
+pub fn resolve(state: &SharedState, request: &Value) -> Result<ResolvedEffect, String> {
+    let preset_name = request
+        .get("preset")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty());
+    let Some(name) = preset_name else {
+        return resolve_inline(request, None);
+    };
+    let preset = state
+        .db()
+        .get_effect_preset(name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Unknown effect preset: {name}"))?;
+    let base = ResolvedEffect {
+        effect: preset.effect,
+        params: preset.params,
+        duration_ms: preset.duration_ms,
+        preset: Some(preset.name),
+    };
+    resolve_inline(request, Some(base))
+}
+
+/// Apply request fields over `base` (a preset) or over the effect defaults.
+fn resolve_inline(request: &Value, base: Option<ResolvedEffect>) -> Result<ResolvedEffect, String> {
+    let effect = request
+        .get("effect")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .or_else(|| base.as_ref().map(|b| b.effect.clone()))
+        .ok_or("effect or preset is required")?;
+    let default_duration = EFFECTS
+        .iter()
+        .find(|(name, _)| *name == effect)
+        .map(|(_, ms)| *ms)
+        .ok_or_else(|| format!("Unknown effect: {effect}"))?;
+
+    let mut params = base
+        .as_ref()
+        .map(|b| b.params.clone())
+        .filter(Value::is_object)
+        .unwrap_or_else(|| json!({}));
+    match request.get("params") {
+        Some(Value::Object(overrides)) => {
+            for (k, v) in overrides {
+                params[k] = v.clone();
+            }
+        }
+        Some(Value::Null) | None => {}
+        Some(_) => return Err("params must be an object".into()),
+    }
+
+    let duration_ms = request
+        .get("duration_ms")
+        .and_then(|v| v.as_i64())
+        .or_else(|| base.as_ref().map(|b| b.duration_ms))
+        .unwrap_or(default_duration);
+    if !(1..=MAX_DURATION_MS).contains(&duration_ms) {
+        return Err(format!("duration_ms must be 1-{MAX_DURATION_MS}"));
+    }
+
+    Ok(ResolvedEffect {
+        effect,
+        params,
+        duration_ms,
+        preset: base.and_then(|b| b.preset),
+    })
+}
+
+/// Broadcast an effect to the overlay and return the sent payload.
+pub fn trigger(state: &SharedState, effect: &ResolvedEffect, source: Value) -> Value {
+    let data = json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "effect": effect.effect,
+        "params": effect.params,
+        "duration_ms": effect.duration_ms,
+        "preset": effect.preset,
+        "source": source,
+    });
+    send_ws(state, "overlay_effect", data.clone());
+    data
+}
+
+/// Fire the effects mapped to `event`.
+pub async fn handle_event(state: &SharedState, event: &ChannelEvent) {
+    let triggers =
+        match state
+            .db()
+            .get_matching_event_triggers(INTEGRATION, event.kind, event.amount)
+        {
+            Ok(t) => t,
+            Err(e) => {
+                tracing::warn!("Failed to load effect triggers: {e}");
+                return;
+            }
+        };
+    for trigger_row in triggers {
+        match resolve(state, &trigger_row.params) {
+            Ok(effect) => {
+                trigger(
+                    state,
+                    &effect,
+                    json!({ "event": event.kind, "user": event.user, "amount": event.amount }),
+                );
+            }
+            Err(e) => tracing::warn!(id = trigger_row.id, "Invalid effect trigger: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_inline_defaults() {
+        let effect = resolve_inline(&json!({ "effect": "confetti" }), None).unwrap();
+        assert_eq!(effect.duration_ms, 4000);
+        assert_eq!(effect.params, json!({}));
+        assert!(resolve_inline(&json!({ "effect": "lasers" }), None).is_err());
+        assert!(resolve_inline(&json!({}), None).is_err());
+    }
+
+    #[test]
+    fn test_resolve_overrides_preset() {
+        let base = ResolvedEffect {
+            effect: "fireworks".into(),
+            params: json!({ "bursts": 5, "colors": ["#ff0000"] }),
+            duration_ms: 6000,
+            preset: Some("big".into()),
+        };
+        let effect = resolve_inline(&json!({ "params": { "bursts": 10 } }), Some(base)).unwrap();
+        assert_eq!(effect.effect, "fireworks");
+        assert_eq!(effect.duration_ms, 6000);
+        assert_eq!(
+            effect.params,
+            json!({ "bursts": 10, "colors": ["#ff0000"] })
+        );
+        assert_eq!(effect.preset.as_deref(), Some("big"));
+    }
+
+    #[test]
+    fn test_validate_params() {
+        assert!(validate_params(&json!({ "preset": "big" })).is_ok());
+        assert!(validate_params(&json!({ "effect": "screen_shake", "duration_ms": 0 })).is_err());
+        assert!(validate_params(&json!({ "effect": "confetti", "params": 3 })).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Overlay preview event simulator.
+//!
+//! Feeds sample events (the same payloads the `/debug/*` injectors send) to
+//! overlay clients connected with `?preview=1`, so layouts can be designed
+//! without OBS. Preview traffic uses its own channel and never reaches the
+//! real overlay.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tokio::sync::{RwLock, broadcast};
+use tokio::task::AbortHandle;
+
+const PREVIEW_CHANNEL_CAPACITY: usize = 64;
+const EVENT_INTERVAL: Duration = Duration::from_secs(8);
+
+static PREVIEW_TX: LazyLock<broadcast::Sender<String>> =
+    LazyLock::new(|| broadcast::channel(PREVIEW_CHANNEL_CAPACITY).0);
+
+static SIMULATOR: LazyLock<RwLock<Option<AbortHandle>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Subscribe to preview-only messages (used by the WebSocket handler).
+pub fn subscribe() -> broadcast::Receiver<String> {
+    PREVIEW_TX.subscribe()
+}
+
+/// Send one `{type, data}` message to preview clients only.
+pub fn inject(event_type: &str, data: Value) {
+    let msg = json!({ "type": event_type, "data": data });
+    let _ = PREVIEW_TX.send(msg.to_string());
+}
+
+/// Whether the simulator loop is running.
+pub async fn is_running() -> bool {
+    SIMULATOR.read().await.is_some()
+}
+
+/// Start cycling sample events (no-op if already running).
+pub async fn start() {
+    let mut slot = SIMULATOR.write().await;
+    if slot.is_some() {
+        return;
+    }
+    let handle = tokio::spawn(async {
+        let mut step = 0usize;
+        loop {
+            let (event_type, data) = sample_event(step);
+            inject(event_type, data);
+            step = step.wrapping_add(1);
+            tokio::time::sleep(EVENT_INTERVAL).await;
+        }
+    });
+    *slot = Some(handle.abort_handle());
+    tracing::info!("Overlay preview simulator started");
+}
+
+/// Stop the simulator loop.
+pub async fn stop() {
+    if let Some(handle) = SIMULATOR.write().await.take() {
+        handle.abort();
+        tracing::info!("Overlay preview simulator stopped");
+    }
+}
+
+/// Sample event for the given step, cycling through the overlay's widgets.
+fn sample_event(step: usize) -> (&'static str, Value) {
+    let now = chrono::Utc::now();
+    let n = step + 1;
+    match step % 5 {
+        0 => (
+            "fax",
+            json!({
+                "id": format!("preview-fax-{n}"),
+                "type": "fax",
+                "timestamp": now.timestamp_millis(),
+                "username": "preview_viewer",
+                "displayName": "プレビュー視聴者",
+                "message": format!("テストFAX #{n}"),
+            }),
+        ),
+        1 => (
+            "lottery_participant_added",
+            json!({
+                "user_id": format!("preview-{n}"),
+                "username": format!("preview_user_{n}"),
+                "display_name": format!("参加者{n}"),
+                "avatar_url": "",
+                "redeemed_at": now.to_rfc3339(),
+                "is_subscriber": n % 2 == 0,
+                "subscriber_tier": if n % 2 == 0 { "1000" } else { "" },
+                "entry_count": 1,
+                "assigned_color": "#9147ff",
+            }),
+        ),
+        2 => (
+            "mic_transcript",
+            json!({
+                "id": format!("preview-transcript-{n}"),
+                "text": "これはオーバーレイプレビューの字幕テストです",
+                "is_interim": false,
+                "timestamp_ms": now.timestamp_millis(),
+            }),
+        ),
+        3 => (
+            "overlay_effect",
+            json!({
+                "id": format!("preview-effect-{n}"),
+                "effect": "confetti",
+                "params": {},
+                "duration_ms": 4000,
+                "preset": null,
+                "source": { "event": "preview" },
+            }),
+        ),
+        _ => (
+            "stream_status_changed",
+            json!({ "is_live": true, "payload": {} }),
+        ),
+    }
+}
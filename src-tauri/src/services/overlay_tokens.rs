@@ -0,0 +1,357 @@
+//! Signed per-widget overlay URLs.
+//!
+//! A token is `base64url(claims JSON) "." base64url(HMAC-SHA256)` keyed by
+//! `OVERLAY_TOKEN_SECRET` (generated on first use). The claims select the
+//! widget and layout the overlay renders and may restrict which WebSocket
+//! message types the browser source receives. Rotating the secret
+//! invalidates every issued URL.
+//!
+//! Session tokens are also bound to the current overlay session and expire
+//! after `OVERLAY_SESSION_TTL_HOURS`. The session is rotated when the stream
+//! goes offline, so a URL leaked during one stream stops working once it
+//! ends; sockets holding such a token are closed. While a session lasts,
+//! connected overlays get a fresh token (`overlay_token` message) before
+//! theirs expires.
+
+use std::sync::{Mutex, MutexGuard};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SECRET_KEY: &str = "OVERLAY_TOKEN_SECRET";
+const SESSION_KEY: &str = "OVERLAY_SESSION_ID";
+
+const DEFAULT_SESSION_TTL_HOURS: i64 = 12;
+/// Session tokens are renewed this long before they expire.
+pub const SESSION_REFRESH_BEFORE_SECS: i64 = 60 * 60;
+
+/// Widgets: `(id, overlay path, description)`.
+pub const WIDGETS: &[(&str, &str, &str)] = &[
+    ("all", "/", "すべてのウィジェット"),
+    ("fax", "/", "FAX"),
+    ("transcript", "/", "字幕・翻訳"),
+    ("effects", "/", "エフェクト"),
+    ("emote_rain", "/", "エモートレイン"),
+    ("polls", "/", "投票・予想"),
+    ("hype_train", "/", "ハイプトレイン"),
+    ("ticker", "/", "抽選ティッカー"),
+    ("pre_show", "/", "配信開始カウントダウン"),
+    ("present", "/present", "プレゼントルーレット"),
+];
+
+pub const LAYOUTS: &[&str] = &["default", "compact"];
+
+/// Message types every overlay needs regardless of its topics.
+const ALWAYS_ALLOWED: &[&str] = &["connected", "pong", "settings", "overlay_token"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverlayClaims {
+    pub widget: String,
+    pub layout: String,
+    /// WebSocket message types to deliver; empty means all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+    pub issued_at: i64,
+    /// Overlay session the token belongs to; `None` for permanent tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+impl OverlayClaims {
+    /// Whether a WebSocket message of `msg_type` may reach this overlay.
+    pub fn allows(&self, msg_type: &str) -> bool {
+        self.topics.is_empty()
+            || ALWAYS_ALLOWED.contains(&msg_type)
+            || self.topics.iter().any(|t| t == msg_type)
+    }
+
+    /// Reject expired tokens and tokens of an ended session.
+    pub fn check(&self, now: i64, current_session: Option<&str>) -> Result<(), String> {
+        if self.expires_at.is_some_and(|exp| now >= exp) {
+            return Err("Overlay token expired".into());
+        }
+        if self.session.is_some() && self.session.as_deref() != current_session {
+            return Err("Overlay session ended".into());
+        }
+        Ok(())
+    }
+
+    /// Whether a session token is close enough to expiry to be renewed.
+    pub fn needs_refresh(&self, now: i64) -> bool {
+        self.session.is_some()
+            && self
+                .expires_at
+                .is_some_and(|exp| exp - now <= SESSION_REFRESH_BEFORE_SECS)
+    }
+}
+
+fn mac(secret: &[u8], payload: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// Sign `claims` with `secret`.
+pub fn sign(secret: &[u8], claims: &OverlayClaims) -> String {
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).unwrap_or_default());
+    let signature = URL_SAFE_NO_PAD.encode(mac(secret, &payload).finalize().into_bytes());
+    format!("{payload}.{signature}")
+}
+
+/// Check the signature of `token` and decode its claims.
+pub fn verify(secret: &[u8], token: &str) -> Result<OverlayClaims, String> {
+    let (payload, signature) = token.split_once('.').ok_or("Malformed overlay token")?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| "Malformed overlay token")?;
+    mac(secret, payload)
+        .verify_slice(&signature)
+        .map_err(|_| "Invalid overlay token signature")?;
+    let json = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| "Malformed overlay token")?;
+    serde_json::from_slice(&json).map_err(|e| format!("Malformed overlay token: {e}"))
+}
+
+/// Held while the secret or the session is created or rotated, so that
+/// concurrent first uses agree on one instead of each storing their own.
+static KEY_LOCK: Mutex<()> = Mutex::new(());
+
+fn key_lock() -> MutexGuard<'static, ()> {
+    KEY_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The signing secret, generated and stored on first use.
+fn secret(state: &SharedState) -> Result<Vec<u8>, String> {
+    let _lock = key_lock();
+    let sm = SettingsManager::new(state.db().clone());
+    let current = sm.get_setting(SECRET_KEY).unwrap_or_default();
+    if !current.is_empty() {
+        return Ok(current.into_bytes());
+    }
+    store_secret(state)
+}
+
+/// Replace the signing secret; every issued URL stops working.
+pub fn rotate_secret(state: &SharedState) -> Result<Vec<u8>, String> {
+    let _lock = key_lock();
+    store_secret(state)
+}
+
+fn store_secret(state: &SharedState) -> Result<Vec<u8>, String> {
+    let secret = random_key();
+    SettingsManager::new(state.db().clone())
+        .set_setting(SECRET_KEY, &secret)
+        .map_err(|e| e.to_string())?;
+    Ok(secret.into_bytes())
+}
+
+fn random_key() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn stored_session(state: &SharedState) -> Option<String> {
+    SettingsManager::new(state.db().clone())
+        .get_setting(SESSION_KEY)
+        .ok()
+        .filter(|s| !s.is_empty())
+}
+
+/// The current overlay session, started on first use.
+fn session_id(state: &SharedState) -> Result<String, String> {
+    let _lock = key_lock();
+    match stored_session(state) {
+        Some(session) => Ok(session),
+        None => store_session(state),
+    }
+}
+
+/// Start a new overlay session; every session token stops working.
+pub fn rotate_session(state: &SharedState) -> Result<String, String> {
+    let _lock = key_lock();
+    store_session(state)
+}
+
+fn store_session(state: &SharedState) -> Result<String, String> {
+    let session = uuid::Uuid::new_v4().simple().to_string();
+    SettingsManager::new(state.db().clone())
+        .set_setting(SESSION_KEY, &session)
+        .map_err(|e| e.to_string())?;
+    Ok(session)
+}
+
+fn session_ttl_secs(state: &SharedState) -> i64 {
+    let hours = SettingsManager::new(state.db().clone())
+        .get_setting("OVERLAY_SESSION_TTL_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_SESSION_TTL_HOURS);
+    hours.max(1) * 60 * 60
+}
+
+/// Validate the requested widget, layout and topics and sign them. With
+/// `session` the token is bound to the current overlay session and expires.
+pub fn issue(
+    state: &SharedState,
+    widget: &str,
+    layout: &str,
+    topics: Vec<String>,
+    session: bool,
+) -> Result<String, String> {
+    if !WIDGETS.iter().any(|(id, _, _)| *id == widget) {
+        return Err(format!("Unknown widget: {widget}"));
+    }
+    if !LAYOUTS.contains(&layout) {
+        return Err(format!("Unknown layout: {layout}"));
+    }
+    let now = chrono::Utc::now().timestamp();
+    let (session, expires_at) = if session {
+        (
+            Some(session_id(state)?),
+            Some(now + session_ttl_secs(state)),
+        )
+    } else {
+        (None, None)
+    };
+    let claims = OverlayClaims {
+        widget: widget.to_string(),
+        layout: layout.to_string(),
+        topics,
+        issued_at: now,
+        session,
+        expires_at,
+    };
+    Ok(sign(&secret(state)?, &claims))
+}
+
+/// Verify a token with the stored secret, its expiry and its session.
+pub fn verify_token(state: &SharedState, token: &str) -> Result<OverlayClaims, String> {
+    let claims = verify(&secret(state)?, token)?;
+    let current = claims.session.as_ref().and_then(|_| stored_session(state));
+    claims.check(chrono::Utc::now().timestamp(), current.as_deref())?;
+    Ok(claims)
+}
+
+/// Re-check the claims of a connected overlay. Returns an `overlay_token`
+/// message carrying a renewed token when the current one is about to
+/// expire, and an error once the token is no longer accepted.
+pub fn renew(state: &SharedState, claims: &mut OverlayClaims) -> Result<Option<String>, String> {
+    let now = chrono::Utc::now().timestamp();
+    claims.check(now, stored_session(state).as_deref())?;
+    if !claims.needs_refresh(now) {
+        return Ok(None);
+    }
+    claims.issued_at = now;
+    claims.expires_at = Some(now + session_ttl_secs(state));
+    let token = sign(&secret(state)?, claims);
+    let msg = serde_json::json!({
+        "type": "overlay_token",
+        "data": { "token": token, "expires_at": claims.expires_at },
+    });
+    Ok(Some(msg.to_string()))
+}
+
+/// End the overlay session with the stream.
+pub fn on_stream_offline(state: &SharedState) {
+    match rotate_session(state) {
+        Ok(_) => tracing::info!("Overlay session rotated; session URLs of this stream expired"),
+        Err(e) => tracing::warn!("Failed to rotate overlay session: {e}"),
+    }
+}
+
+/// Browser-source URL for `widget` carrying `token`.
+pub fn widget_url(port: u16, widget: &str, token: &str) -> String {
+    let path = WIDGETS
+        .iter()
+        .find(|(id, _, _)| *id == widget)
+        .map_or("/", |(_, path, _)| *path);
+    format!("http://localhost:{port}/overlay{path}?token={token}")
+}
+
+/// `type` of a broadcast WebSocket message.
+pub fn message_type(msg: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(msg).ok()?;
+    value.get("type")?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(topics: &[&str]) -> OverlayClaims {
+        OverlayClaims {
+            widget: "effects".into(),
+            layout: "default".into(),
+            topics: topics.iter().map(|t| t.to_string()).collect(),
+            issued_at: 1_700_000_000,
+            session: None,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let c = claims(&["overlay_effect"]);
+        let token = sign(b"secret", &c);
+        assert_eq!(verify(b"secret", &token).unwrap(), c);
+        assert!(verify(b"other", &token).is_err());
+
+        // Changing the claims breaks the signature.
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims(&[])).unwrap());
+        assert!(verify(b"secret", &format!("{forged}.{signature}")).is_err());
+        assert!(verify(b"secret", "garbage").is_err());
+    }
+
+    #[test]
+    fn test_topics() {
+        assert!(claims(&[]).allows("fax"));
+        let c = claims(&["overlay_effect"]);
+        assert!(c.allows("overlay_effect"));
+        assert!(c.allows("settings"));
+        assert!(!c.allows("fax"));
+        assert_eq!(
+            message_type(r#"{"type":"fax","data":{}}"#).as_deref(),
+            Some("fax")
+        );
+    }
+
+    #[test]
+    fn test_session_claims() {
+        let permanent = claims(&[]);
+        assert!(permanent.check(i64::MAX - 1, None).is_ok());
+        assert!(!permanent.needs_refresh(0));
+
+        let session = OverlayClaims {
+            session: Some("s1".into()),
+            expires_at: Some(1_700_043_200),
+            ..claims(&[])
+        };
+        let token = sign(b"secret", &session);
+        assert_eq!(verify(b"secret", &token).unwrap(), session);
+        assert!(session.check(1_700_000_000, Some("s1")).is_ok());
+        assert_eq!(
+            session.check(1_700_043_200, Some("s1")).unwrap_err(),
+            "Overlay token expired"
+        );
+        assert!(session.check(1_700_000_000, Some("s2")).is_err());
+        assert!(session.check(1_700_000_000, None).is_err());
+
+        assert!(!session.needs_refresh(1_700_000_000));
+        assert!(session.needs_refresh(1_700_043_200 - SESSION_REFRESH_BEFORE_SECS));
+    }
+}
@@ -0,0 +1,136 @@
+//! Emergency "panic button" for hate raids.
+//!
+//! [`trigger`] does everything at once: clears the alert queue, pauses
+//! printing, stops and empties TTS, turns on Shield Mode and subscriber-only
+//! or follower-only chat (`PANIC_CHAT_MODE`) and hides the chat-driven
+//! overlay widgets. Each step runs even when an earlier one failed, and its
+//! outcome is returned.
+//!
+//! [`release`] shows the widgets again and resumes printing and TTS. Shield
+//! Mode and the chat restriction stay on until the broadcaster turns them
+//! off (`PATCH /api/moderation/chat-settings`, `PUT
+//! /api/moderation/shield-mode`).
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use serde_json::{Value, json};
+use twitch_client::api::moderation::ChatSettingsUpdate;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::send_ws;
+use crate::notification;
+use crate::services::{helix, print_queue, tts};
+
+/// Overlay widgets showing chat content, hidden while the panic is active.
+pub const HIDDEN_WIDGETS: &[&str] = &["fax", "emote_rain"];
+
+/// Unix seconds the panic button was pressed; 0 when not active.
+static ACTIVE_SINCE: AtomicI64 = AtomicI64::new(0);
+
+pub fn status() -> Value {
+    let since = ACTIVE_SINCE.load(Ordering::Relaxed);
+    json!({
+        "active": since != 0,
+        "triggered_at": (since != 0).then_some(since),
+        "hidden_widgets": if since != 0 { HIDDEN_WIDGETS } else { &[] },
+    })
+}
+
+fn outcome(step: &str, result: Result<(), String>) -> Value {
+    match result {
+        Ok(()) => json!({ "step": step, "ok": true }),
+        Err(e) => {
+            tracing::warn!(step, "Panic step failed: {e}");
+            json!({ "step": step, "ok": false, "error": e })
+        }
+    }
+}
+
+/// Chat restriction for `PANIC_CHAT_MODE` and the name of its step;
+/// subscriber-only unless the mode is `followers`.
+fn chat_restriction(mode: &str, follower_minutes: u32) -> (&'static str, ChatSettingsUpdate) {
+    if mode == "followers" {
+        let settings = ChatSettingsUpdate {
+            follower_mode: Some(true),
+            follower_mode_duration: Some(follower_minutes),
+            ..Default::default()
+        };
+        return ("follower_only", settings);
+    }
+    let settings = ChatSettingsUpdate {
+        subscriber_mode: Some(true),
+        ..Default::default()
+    };
+    ("subscriber_only", settings)
+}
+
+/// Turn on Shield Mode and the `PANIC_CHAT_MODE` chat restriction.
+async fn lock_down_chat(state: &SharedState) -> [Value; 2] {
+    let sm = SettingsManager::new(state.db().clone());
+    let minutes = sm
+        .get_setting("PANIC_FOLLOWER_ONLY_MINUTES")
+        .unwrap_or_default()
+        .parse()
+        .unwrap_or(10);
+    let mode = sm.get_setting("PANIC_CHAT_MODE").unwrap_or_default();
+    let (step, settings) = chat_restriction(&mode, minutes);
+    let ctx = match helix::context(state).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            return [
+                outcome("shield_mode", Err(e.clone())),
+                outcome(step, Err(e)),
+            ];
+        }
+    };
+    let id = &ctx.broadcaster_id;
+    let shield = ctx
+        .api
+        .update_shield_mode(&ctx.token, id, id, true)
+        .await
+        .map_err(|e| e.to_string());
+    let restricted = ctx
+        .api
+        .update_chat_settings(&ctx.token, id, id, &settings)
+        .await
+        .map_err(|e| e.to_string());
+    [outcome("shield_mode", shield), outcome(step, restricted)]
+}
+
+/// Press the panic button. `source` says who pressed it, for the log.
+pub async fn trigger(state: &SharedState, source: &str) -> Value {
+    tracing::warn!(source, "Panic button pressed");
+    ACTIVE_SINCE.store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+
+    let cleared_alerts = notification::queue::clear().await;
+    print_queue::pause().await;
+    tts::pause();
+    tts::skip();
+    let cleared_tts = tts::clear();
+    send_ws(state, "panic", status());
+
+    let mut steps = vec![
+        json!({ "step": "clear_alerts", "ok": true, "cleared": cleared_alerts }),
+        json!({ "step": "pause_prints", "ok": true }),
+        json!({ "step": "pause_tts", "ok": true, "cleared": cleared_tts }),
+        json!({ "step": "hide_widgets", "ok": true, "widgets": HIDDEN_WIDGETS }),
+    ];
+    steps.extend(lock_down_chat(state).await);
+
+    let mut status = status();
+    status["steps"] = json!(steps);
+    status
+}
+
+/// Show the widgets again and resume printing and TTS.
+pub async fn release(state: &SharedState) -> Value {
+    if ACTIVE_SINCE.swap(0, Ordering::Relaxed) != 0 {
+        tracing::info!("Panic mode released");
+        print_queue::resume().await;
+        tts::resume();
+    }
+    let status = status();
+    send_ws(state, "panic", &status);
+    status
+}
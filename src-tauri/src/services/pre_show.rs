@@ -0,0 +1,240 @@
+//! "Starting soon" pre-show.
+//!
+//! Starting the pre-show shows a countdown on the overlay, loads a playlist
+//! into the music player and holds back alerts until the stream goes live.
+//! When `stream.online` arrives it switches to live on its own: alerts
+//! resume and, with `PRE_SHOW_STOP_MUSIC_ON_LIVE`, the music stops.
+//!
+//! Phases run `idle` → `pre_show` → `live` → `idle` (stream offline).
+//! Every change is broadcast as a `pre_show` WebSocket message carrying the
+//! full [`PreShow`] state.
+
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::send_ws;
+use crate::services::music_playlist::PlaylistService;
+
+pub const MAX_MINUTES: i64 = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Idle,
+    PreShow,
+    Live,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreShow {
+    pub phase: Phase,
+    pub title: String,
+    /// Unix seconds the countdown reaches zero.
+    pub ends_at: Option<i64>,
+    pub started_at: Option<i64>,
+    pub live_at: Option<i64>,
+    /// Playlist id loaded into the music player.
+    pub playlist: Option<String>,
+    /// Alerts held back during the pre-show.
+    pub suppressed_alerts: u64,
+}
+
+impl PreShow {
+    const fn idle() -> Self {
+        Self {
+            phase: Phase::Idle,
+            title: String::new(),
+            ends_at: None,
+            started_at: None,
+            live_at: None,
+            playlist: None,
+            suppressed_alerts: 0,
+        }
+    }
+
+    /// Enter the pre-show; restarting it resets the countdown.
+    fn start(&mut self, now: i64, minutes: i64, title: String, playlist: Option<String>) {
+        *self = Self {
+            phase: Phase::PreShow,
+            title,
+            ends_at: Some(now + minutes * 60),
+            started_at: Some(now),
+            live_at: None,
+            playlist,
+            suppressed_alerts: 0,
+        };
+    }
+
+    /// Switch from the pre-show to live. Returns false in any other phase.
+    fn go_live(&mut self, now: i64) -> bool {
+        if self.phase != Phase::PreShow {
+            return false;
+        }
+        self.phase = Phase::Live;
+        self.live_at = Some(now);
+        true
+    }
+
+    /// Count an alert when it is to be held back.
+    fn suppress(&mut self) -> bool {
+        if self.phase != Phase::PreShow {
+            return false;
+        }
+        self.suppressed_alerts += 1;
+        true
+    }
+}
+
+static STATE: LazyLock<Mutex<PreShow>> = LazyLock::new(|| Mutex::new(PreShow::idle()));
+
+fn lock() -> MutexGuard<'static, PreShow> {
+    STATE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn current() -> PreShow {
+    lock().clone()
+}
+
+/// Whether an alert should be held back right now; counts it when so.
+pub fn suppress_alert() -> bool {
+    lock().suppress()
+}
+
+fn broadcast(state: &SharedState, pre_show: &PreShow) {
+    send_ws(state, "pre_show", pre_show);
+}
+
+fn music_control(state: &SharedState, command: serde_json::Value) {
+    let msg = json!({ "type": "music_control", "data": command });
+    let _ = state.ws_sender().send(msg.to_string());
+}
+
+/// Stop the pre-show playlist if one was loaded and the setting asks for it.
+fn stop_music(state: &SharedState, pre_show: &PreShow) {
+    let stop = SettingsManager::new(state.db().clone())
+        .get_setting("PRE_SHOW_STOP_MUSIC_ON_LIVE")
+        .unwrap_or_default()
+        == "true";
+    if stop && pre_show.playlist.is_some() {
+        music_control(state, json!({ "type": "stop" }));
+    }
+}
+
+/// Start the pre-show. `None` arguments fall back to the `PRE_SHOW_*`
+/// settings; an empty playlist leaves the music player alone.
+pub fn start(
+    state: &SharedState,
+    minutes: Option<i64>,
+    title: Option<String>,
+    playlist: Option<String>,
+) -> Result<PreShow, String> {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let minutes = minutes.unwrap_or_else(|| get("PRE_SHOW_MINUTES").parse().unwrap_or(5));
+    if !(1..=MAX_MINUTES).contains(&minutes) {
+        return Err(format!("minutes must be between 1 and {MAX_MINUTES}"));
+    }
+    let title = title.unwrap_or_else(|| get("PRE_SHOW_TITLE"));
+    let playlist = playlist
+        .unwrap_or_else(|| get("PRE_SHOW_PLAYLIST"))
+        .trim()
+        .to_string();
+    let playlist = if playlist.is_empty() {
+        None
+    } else {
+        match PlaylistService::new(state.db().clone()).get_playlist(&playlist) {
+            Ok(Some(_)) => Some(playlist),
+            Ok(None) => return Err(format!("Unknown playlist: {playlist}")),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    let pre_show = {
+        let mut current = lock();
+        current.start(chrono::Utc::now().timestamp(), minutes, title, playlist);
+        current.clone()
+    };
+    if let Some(id) = &pre_show.playlist {
+        music_control(
+            state,
+            json!({ "type": "load_playlist", "playlist": id, "autoplay": true }),
+        );
+    }
+    broadcast(state, &pre_show);
+    tracing::info!(minutes, "Pre-show started");
+    Ok(pre_show)
+}
+
+/// Leave the pre-show without going live.
+pub fn cancel(state: &SharedState) -> PreShow {
+    let previous = std::mem::replace(&mut *lock(), PreShow::idle());
+    if previous.phase == Phase::PreShow {
+        stop_music(state, &previous);
+    }
+    let idle = PreShow::idle();
+    broadcast(state, &idle);
+    idle
+}
+
+/// Switch a running pre-show to live. Call before dispatching the
+/// `stream_online` event so it is not held back.
+pub fn on_stream_online(state: &SharedState) {
+    let live = {
+        let mut current = lock();
+        if !current.go_live(chrono::Utc::now().timestamp()) {
+            return;
+        }
+        current.clone()
+    };
+    tracing::info!(
+        suppressed_alerts = live.suppressed_alerts,
+        "Pre-show switched to live"
+    );
+    stop_music(state, &live);
+    broadcast(state, &live);
+}
+
+pub fn on_stream_offline(state: &SharedState) {
+    let was_idle = {
+        let mut current = lock();
+        std::mem::replace(&mut *current, PreShow::idle()).phase == Phase::Idle
+    };
+    if !was_idle {
+        broadcast(state, &PreShow::idle());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pre_show_goes_live_once() {
+        let mut s = PreShow::idle();
+        assert!(!s.go_live(100));
+        s.start(1_000, 5, "Soon".into(), Some("bgm".into()));
+        assert_eq!(s.phase, Phase::PreShow);
+        assert_eq!(s.ends_at, Some(1_300));
+        assert!(s.go_live(1_200));
+        assert_eq!(s.phase, Phase::Live);
+        assert_eq!(s.live_at, Some(1_200));
+        assert!(!s.go_live(1_250));
+    }
+
+    #[test]
+    fn test_alerts_held_back_only_during_pre_show() {
+        let mut s = PreShow::idle();
+        assert!(!s.suppress());
+        s.start(0, 1, String::new(), None);
+        assert!(s.suppress());
+        assert!(s.suppress());
+        assert_eq!(s.suppressed_alerts, 2);
+        s.go_live(10);
+        assert!(!s.suppress());
+        assert_eq!(s.suppressed_alerts, 2);
+    }
+}
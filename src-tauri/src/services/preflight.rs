@@ -0,0 +1,227 @@
+//! Pre-flight checks for scripts and support (`--check` and the `validate`
+//! command).
+//!
+//! A report covers the schema migrations the database still needs, setting
+//! values that fail validation and the self-test. `--check` runs against a
+//! scratch copy of the database so it never migrates or writes to the real
+//! one; the copy is migrated to prove the pending migrations apply.
+
+use std::path::{Path, PathBuf};
+
+use overlay_db::Database;
+use serde::Serialize;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::selftest::{self, SelfTestReport};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaCheck {
+    /// Whether a database file exists yet.
+    pub exists: bool,
+    pub current: u32,
+    pub latest: u32,
+    pub pending: Vec<u32>,
+    /// Why the schema can't be brought up to date, if it can't.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvalidSetting {
+    pub key: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightReport {
+    pub passed: bool,
+    pub schema: SchemaCheck,
+    pub invalid_settings: Vec<InvalidSetting>,
+    /// Required settings that are empty; reported but not a failure.
+    pub missing_settings: Vec<String>,
+    pub warnings: Vec<String>,
+    pub selftest: SelfTestReport,
+}
+
+impl SchemaCheck {
+    fn of(db: &Database) -> Result<Self, overlay_db::DbError> {
+        // A database newer than this build has no pending list.
+        let (pending, error) = match db.pending_migrations() {
+            Ok(pending) => (pending, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        Ok(Self {
+            exists: true,
+            current: db.schema_version()?,
+            latest: overlay_db::schema::latest_version(),
+            pending,
+            error,
+        })
+    }
+}
+
+/// Check the running app.
+pub async fn validate(state: &SharedState) -> Result<PreflightReport, String> {
+    let schema = SchemaCheck::of(state.db()).map_err(|e| e.to_string())?;
+    let selftest = selftest::run(state).await;
+    report(state, schema, selftest)
+}
+
+/// Removes the scratch database and its WAL files when dropped.
+struct ScratchFile(PathBuf);
+
+impl Drop for ScratchFile {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut path = self.0.clone().into_os_string();
+            path.push(suffix);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Check without starting the app: copy and migrate the database at
+/// `db_path`, initialize settings on the copy and run the self-test with
+/// the server port expected to be free.
+pub async fn check(db_path: &Path, data_dir: PathBuf) -> Result<PreflightReport, anyhow::Error> {
+    let scratch = ScratchFile(
+        std::env::temp_dir().join(format!("twitch-overlay-check-{}.db", std::process::id())),
+    );
+    let (db, mut schema) = if db_path.exists() {
+        let db = Database::open_copy(db_path, &scratch.0)?;
+        let schema = SchemaCheck::of(&db)?;
+        (db, schema)
+    } else {
+        let schema = SchemaCheck {
+            exists: false,
+            current: 0,
+            latest: overlay_db::schema::latest_version(),
+            pending: Vec::new(),
+            error: None,
+        };
+        (Database::open_in_memory()?, schema)
+    };
+    let migrated = if schema.error.is_none() {
+        db.apply_pending_migrations().map(|_| ())
+    } else {
+        Ok(())
+    };
+    if let Err(e) = migrated {
+        schema.error = Some(format!("migration failed: {e}"));
+    }
+    if schema.error.is_some() {
+        // Settings and the self-test need the current schema.
+        return Ok(PreflightReport {
+            passed: false,
+            schema,
+            invalid_settings: Vec::new(),
+            missing_settings: Vec::new(),
+            warnings: Vec::new(),
+            selftest: SelfTestReport::new(Vec::new()),
+        });
+    }
+
+    let config = crate::init_settings(&db)?;
+    let state = SharedState::new(db, config, data_dir);
+    let selftest = selftest::run_before_start(&state).await;
+    report(&state, schema, selftest).map_err(anyhow::Error::msg)
+}
+
+fn report(
+    state: &SharedState,
+    schema: SchemaCheck,
+    selftest: SelfTestReport,
+) -> Result<PreflightReport, String> {
+    let sm = SettingsManager::new(state.db().clone());
+    let invalid_settings: Vec<InvalidSetting> = sm
+        .invalid_settings()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(key, error)| InvalidSetting { key, error })
+        .collect();
+    let status = sm.check_feature_status().map_err(|e| e.to_string())?;
+    Ok(PreflightReport {
+        passed: schema.error.is_none() && invalid_settings.is_empty() && selftest.passed,
+        schema,
+        invalid_settings,
+        missing_settings: status.missing_settings,
+        warnings: status.warnings,
+        selftest,
+    })
+}
+
+/// Plain-text rendering of `report` for the terminal.
+pub fn render(report: &PreflightReport) -> String {
+    let mut lines = Vec::new();
+    let schema = &report.schema;
+    if !schema.exists {
+        lines.push(format!(
+            "[skip] schema: no database yet; version {} will be created",
+            schema.latest
+        ));
+    } else if let Some(e) = &schema.error {
+        lines.push(format!("[fail] schema: {e}"));
+    } else if schema.pending.is_empty() {
+        lines.push(format!(
+            "[pass] schema: version {} is current",
+            schema.current
+        ));
+    } else {
+        lines.push(format!(
+            "[pass] schema: version {} → {} applies cleanly (pending: {:?})",
+            schema.current, schema.latest, schema.pending
+        ));
+    }
+    if report.invalid_settings.is_empty() {
+        lines.push("[pass] settings: all values are valid".to_string());
+    }
+    for s in &report.invalid_settings {
+        lines.push(format!("[fail] settings: {}: {}", s.key, s.error));
+    }
+    for key in &report.missing_settings {
+        lines.push(format!("[warn] settings: {key} is not set"));
+    }
+    for warning in &report.warnings {
+        lines.push(format!("[warn] settings: {warning}"));
+    }
+    for c in &report.selftest.checks {
+        lines.push(format!("[{}] {}: {}", c.status.as_str(), c.name, c.message));
+    }
+    lines.push(if report.passed {
+        "Pre-flight check passed".to_string()
+    } else {
+        "Pre-flight check failed".to_string()
+    });
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let report = PreflightReport {
+            passed: false,
+            schema: SchemaCheck {
+                exists: true,
+                current: 33,
+                latest: 35,
+                pending: vec![34, 35],
+                error: None,
+            },
+            invalid_settings: vec![InvalidSetting {
+                key: "OSC_PORT".into(),
+                error: "must be between 1 and 65535".into(),
+            }],
+            missing_settings: vec!["CLIENT_ID".into()],
+            warnings: Vec::new(),
+            selftest: SelfTestReport::new(Vec::new()),
+        };
+        let text = render(&report);
+        assert!(text.contains("[pass] schema: version 33 → 35 applies cleanly"));
+        assert!(text.contains("[fail] settings: OSC_PORT: must be between 1 and 65535"));
+        assert!(text.contains("[warn] settings: CLIENT_ID is not set"));
+        assert!(text.ends_with("Pre-flight check failed"));
+    }
+}
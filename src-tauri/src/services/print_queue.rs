@@ -1,19 +1,253 @@
 //! Print job queue and orchestration.
 //!
 //! Manages a background worker that processes print jobs sequentially,
-//! handles BLE/USB printing, and respects dry-run mode.
+//! handles BLE/USB printing, and respects dry-run mode. Pending jobs stay
+//! inspectable until the worker takes them: the queue can be paused,
+//! single jobs cancelled and the order changed, so a flood of prints can
+//! be trimmed before it reaches the printer.
+//!
+//! Jobs are stored in the `print_jobs` table, so they survive a crash or
+//! restart. A failed job is retried with exponential backoff (10 s, 20 s,
+//! ... up to 5 min); after `MAX_ATTEMPTS` it is kept as a failed job until
+//! it is retried or deleted from the dashboard.
+//!
+//! Several printers can be configured. The `PRINTER_*` settings describe
+//! the `default` printer; `PRINTERS` adds named ones, e.g.
+//! `[{ "name": "rewards", "type": "bluetooth", "address": "AA:BB:.." },
+//!   { "name": "receipt", "type": "escpos", "device": "/dev/usb/lp0" }]`.
+//! `PRINTER_ROUTES` sends a job category to a printer by name, e.g.
+//! `{ "chat": "receipt", "reward": "rewards" }`; unrouted categories and
+//! routes to unknown printers use the default printer.
 
+use std::collections::BTreeMap;
 use std::sync::LazyLock;
 
+use overlay_db::print_jobs::{
+    ClaimedPrintJob, NewPrintJob, PrintJobRecord, STATUS_FAILED, STATUS_PENDING,
+};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{Notify, RwLock};
 
 use crate::app::SharedState;
-use crate::services::printer_pipeline;
+use crate::config::{AppConfig, SettingsManager};
+use crate::services::{printer_pipeline, smart_plug};
 
 /// Maximum number of queued print jobs.
 const QUEUE_CAPACITY: usize = 100;
 
+/// Attempts before a job is moved to the failed jobs (dead letter).
+const MAX_ATTEMPTS: i64 = 5;
+const RETRY_BASE_SECS: i64 = 10;
+const RETRY_MAX_SECS: i64 = 300;
+
+/// Name of the printer configured by the `PRINTER_*` settings.
+pub const DEFAULT_PRINTER: &str = "default";
+
+/// What a job prints; selects the printer through `PRINTER_ROUTES`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PrintCategory {
+    Chat,
+    Follow,
+    Reward,
+    Clock,
+    /// Manual prints, quotes, rundowns, ...; always the default printer.
+    #[default]
+    Other,
+}
+
+impl PrintCategory {
+    /// Categories that can be routed.
+    pub const ROUTABLE: [Self; 4] = [Self::Chat, Self::Follow, Self::Reward, Self::Clock];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::Follow => "follow",
+            Self::Reward => "reward",
+            Self::Clock => "clock",
+            Self::Other => "other",
+        }
+    }
+
+    fn parse_routable(value: &str) -> Option<Self> {
+        Self::ROUTABLE.into_iter().find(|c| c.as_str() == value)
+    }
+
+    /// Parse a stored category; unknown values are `Other`.
+    fn parse(value: &str) -> Self {
+        Self::parse_routable(value).unwrap_or_default()
+    }
+}
+
+fn default_cut() -> bool {
+    true
+}
+
+/// How a printer is reached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PrinterTarget {
+    Bluetooth {
+        address: String,
+    },
+    Usb {
+        printer_name: String,
+    },
+    Escpos {
+        device: String,
+        #[serde(default = "default_cut")]
+        cut: bool,
+    },
+}
+
+impl PrinterTarget {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Bluetooth { .. } => "bluetooth",
+            Self::Usb { .. } => "usb",
+            Self::Escpos { .. } => "escpos",
+        }
+    }
+
+    /// Address, CUPS name or device path.
+    pub fn target(&self) -> &str {
+        match self {
+            Self::Bluetooth { address } => address,
+            Self::Usb { printer_name } => printer_name,
+            Self::Escpos { device, .. } => device,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedPrinter {
+    pub name: String,
+    #[serde(flatten)]
+    pub target: PrinterTarget,
+}
+
+/// Configured printers and category routes.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PrinterRegistry {
+    /// The default printer first.
+    pub printers: Vec<NamedPrinter>,
+    pub routes: BTreeMap<PrintCategory, String>,
+}
+
+impl PrinterRegistry {
+    pub fn get(&self, name: &str) -> Option<&NamedPrinter> {
+        self.printers.iter().find(|p| p.name == name)
+    }
+
+    /// Printer for a category: its route, else the default printer.
+    pub fn resolve(&self, category: PrintCategory) -> Option<&NamedPrinter> {
+        let routed = self.routes.get(&category).and_then(|name| {
+            let printer = self.get(name);
+            if printer.is_none() {
+                tracing::warn!(
+                    category = category.as_str(),
+                    printer = %name,
+                    "Print route points to an unknown printer; using the default"
+                );
+            }
+            printer
+        });
+        routed.or_else(|| self.get(DEFAULT_PRINTER))
+    }
+}
+
+/// Parse the `PRINTERS` setting. Names must be unique and not `default`.
+pub fn parse_printers(value: &str) -> Result<Vec<NamedPrinter>, String> {
+    if value.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let printers: Vec<NamedPrinter> =
+        serde_json::from_str(value).map_err(|e| format!("invalid printer list: {e}"))?;
+    for (i, printer) in printers.iter().enumerate() {
+        let name = printer.name.trim();
+        if name.is_empty() {
+            return Err(format!("printer {} has no name", i + 1));
+        }
+        if name == DEFAULT_PRINTER {
+            return Err(format!(
+                "'{DEFAULT_PRINTER}' is reserved for the main printer"
+            ));
+        }
+        if printers[..i].iter().any(|p| p.name.trim() == name) {
+            return Err(format!("duplicate printer name '{name}'"));
+        }
+        if printer.target.target().trim().is_empty() {
+            return Err(format!("printer '{name}' has no address or device"));
+        }
+    }
+    Ok(printers)
+}
+
+/// Parse the `PRINTER_ROUTES` setting (`{ category: printer name }`).
+/// Empty names are dropped.
+pub fn parse_routes(value: &str) -> Result<BTreeMap<PrintCategory, String>, String> {
+    if value.trim().is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    let raw: BTreeMap<String, String> =
+        serde_json::from_str(value).map_err(|e| format!("invalid routes: {e}"))?;
+    let mut routes = BTreeMap::new();
+    for (key, name) in raw {
+        let category = PrintCategory::parse_routable(&key).ok_or_else(|| {
+            let names: Vec<&str> = PrintCategory::ROUTABLE.iter().map(|c| c.as_str()).collect();
+            format!("unknown category '{key}' (use {})", names.join(", "))
+        })?;
+        if !name.trim().is_empty() {
+            routes.insert(category, name.trim().to_string());
+        }
+    }
+    Ok(routes)
+}
+
+/// The printer described by the `PRINTER_*` settings.
+fn default_printer(config: &AppConfig) -> NamedPrinter {
+    let target = if config.uses_escpos() {
+        PrinterTarget::Escpos {
+            device: config.escpos_device.clone(),
+            cut: config.escpos_cut,
+        }
+    } else if config.printer_type == "usb" {
+        PrinterTarget::Usb {
+            printer_name: config.usb_printer_name.clone(),
+        }
+    } else {
+        PrinterTarget::Bluetooth {
+            address: config.printer_address.clone(),
+        }
+    };
+    NamedPrinter {
+        name: DEFAULT_PRINTER.to_string(),
+        target,
+    }
+}
+
+/// Current printers and routes. Invalid `PRINTERS`/`PRINTER_ROUTES` values
+/// are logged and ignored.
+pub async fn registry(state: &SharedState) -> PrinterRegistry {
+    let default = default_printer(&*state.config().await);
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let mut printers = vec![default];
+    match parse_printers(&get("PRINTERS")) {
+        Ok(extra) => printers.extend(extra),
+        Err(e) => tracing::warn!("Ignoring PRINTERS: {e}"),
+    }
+    let routes = parse_routes(&get("PRINTER_ROUTES")).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring PRINTER_ROUTES: {e}");
+        BTreeMap::new()
+    });
+    PrinterRegistry { printers, routes }
+}
+
 /// A print job to be processed by the worker.
 #[derive(Debug)]
 pub struct PrintJob {
@@ -27,11 +261,91 @@ pub struct PrintJob {
     pub description: String,
     /// Force print even in dry-run mode.
     pub force: bool,
+    pub category: PrintCategory,
+}
+
+impl PrintJob {
+    fn width(&self) -> u16 {
+        if self.mono_width == 0 {
+            catprinter::PRINT_WIDTH
+        } else {
+            self.mono_width
+        }
+    }
+
+    fn from_claimed(claimed: ClaimedPrintJob) -> Self {
+        Self {
+            mono_width: u16::try_from(claimed.record.width).unwrap_or(0),
+            mono_image: claimed.payload,
+            color_image: claimed.color_payload,
+            description: claimed.record.description,
+            force: claimed.record.force,
+            category: PrintCategory::parse(&claimed.record.category),
+        }
+    }
+}
+
+/// Metadata of a stored job.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: i64,
+    pub description: String,
+    /// Printed height in pixels.
+    pub height: i64,
+    pub has_color: bool,
+    pub force: bool,
+    pub category: PrintCategory,
+    pub queued_at: String,
+    /// Failed attempts so far.
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    /// When a job waiting after a failure is tried again.
+    pub retry_at: Option<String>,
+}
+
+fn rfc3339(unix: i64) -> String {
+    chrono::DateTime::from_timestamp(unix, 0)
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+impl From<PrintJobRecord> for JobInfo {
+    fn from(record: PrintJobRecord) -> Self {
+        let waiting = record.status == STATUS_PENDING && record.attempts > 0;
+        Self {
+            id: record.id,
+            height: record.height,
+            has_color: record.has_color,
+            force: record.force,
+            category: PrintCategory::parse(&record.category),
+            queued_at: rfc3339(record.created_at),
+            attempts: record.attempts,
+            retry_at: waiting.then(|| rfc3339(record.next_attempt_at)),
+            last_error: record.last_error,
+            description: record.description,
+        }
+    }
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueSnapshot {
+    pub paused: bool,
+    pub running: bool,
+    /// Job being printed right now.
+    pub printing: Option<JobInfo>,
+    pub jobs: Vec<JobInfo>,
+    /// Jobs that used up their attempts (dead letters).
+    pub failed: Vec<JobInfo>,
+    pub total_processed: u64,
+    pub last_print_at: Option<String>,
+}
+
+/// Worker state; the jobs themselves live in the `print_jobs` table.
 #[derive(Debug, Default)]
 struct QueueState {
-    pending_count: usize,
+    running: bool,
+    paused: bool,
+    printing: Option<JobInfo>,
     total_processed: u64,
     last_print_at: Option<String>,
 }
@@ -39,77 +353,237 @@ struct QueueState {
 static QUEUE_STATE: LazyLock<RwLock<QueueState>> =
     LazyLock::new(|| RwLock::new(QueueState::default()));
 
-static JOB_TX: LazyLock<RwLock<Option<mpsc::Sender<PrintJob>>>> =
-    LazyLock::new(|| RwLock::new(None));
+/// Wakes the worker when a job is queued or the queue is resumed.
+static WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
 
-/// Initialize the print queue and start the background worker.
+/// Seconds to wait before attempt `attempts + 1`, doubling per failure.
+fn retry_delay_secs(attempts: i64) -> i64 {
+    let doublings = attempts.saturating_sub(1).clamp(0, 10) as u32;
+    (RETRY_BASE_SECS << doublings).min(RETRY_MAX_SECS)
+}
+
+fn db_err(e: overlay_db::DbError) -> String {
+    format!("Print queue database error: {e}")
+}
+
+/// Initialize the print queue and start the background worker. Jobs left
+/// printing by a crash are queued again.
 pub async fn start_worker(state: SharedState) {
-    let (tx, rx) = mpsc::channel::<PrintJob>(QUEUE_CAPACITY);
+    match state
+        .db()
+        .requeue_interrupted_print_jobs(chrono::Utc::now().timestamp())
     {
-        let mut slot = JOB_TX.write().await;
-        *slot = Some(tx);
+        Ok(0) => {}
+        Ok(n) => tracing::warn!("Requeued {n} print job(s) interrupted by a restart"),
+        Err(e) => tracing::error!("{}", db_err(e)),
     }
-
-    tokio::spawn(worker_loop(state, rx));
+    QUEUE_STATE.write().await.running = true;
+    tokio::spawn(worker_loop(state));
     tracing::info!("Print queue worker started (capacity={QUEUE_CAPACITY})");
 }
 
 /// Enqueue a print job. Returns error if the queue is full.
-pub async fn enqueue(job: PrintJob) -> Result<(), String> {
-    let tx_guard = JOB_TX.read().await;
-    let tx = tx_guard
-        .as_ref()
-        .ok_or_else(|| "Print queue not initialized".to_string())?;
-
-    tx.try_send(job)
-        .map_err(|e| format!("Print queue full or closed: {e}"))?;
+pub async fn enqueue(state: &SharedState, job: PrintJob) -> Result<i64, String> {
+    if !QUEUE_STATE.read().await.running {
+        return Err("Print queue not running".to_string());
+    }
+    let db = state.db();
+    let pending = db.count_print_jobs(STATUS_PENDING).map_err(db_err)?;
+    if pending >= QUEUE_CAPACITY as i64 {
+        return Err(format!("Print queue full ({QUEUE_CAPACITY} jobs)"));
+    }
+    let width = job.width();
+    let id = db
+        .add_print_job(
+            &NewPrintJob {
+                description: &job.description,
+                category: job.category.as_str(),
+                force: job.force,
+                width: i64::from(width),
+                height: (job.mono_image.len() / usize::from(width)) as i64,
+                payload: &job.mono_image,
+                color_payload: job.color_image.as_deref(),
+            },
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(db_err)?;
+    WAKE.notify_one();
+    Ok(id)
+}
 
-    let mut qs = QUEUE_STATE.write().await;
-    qs.pending_count += 1;
+/// Number of pending jobs and jobs processed since startup.
+pub async fn queue_status(state: &SharedState) -> (usize, u64) {
+    let pending = state.db().count_print_jobs(STATUS_PENDING).unwrap_or(0);
+    (pending as usize, QUEUE_STATE.read().await.total_processed)
+}
 
-    Ok(())
+fn list_jobs(state: &SharedState, status: &str) -> Result<Vec<JobInfo>, String> {
+    let jobs = state.db().list_print_jobs(status).map_err(db_err)?;
+    Ok(jobs.into_iter().map(JobInfo::from).collect())
 }
 
-/// Get the current queue status.
-pub async fn queue_status() -> (usize, u64) {
+/// Pending and failed jobs and worker state.
+pub async fn snapshot(state: &SharedState) -> Result<QueueSnapshot, String> {
+    let jobs = list_jobs(state, STATUS_PENDING)?;
+    let failed = list_jobs(state, STATUS_FAILED)?;
     let qs = QUEUE_STATE.read().await;
-    (qs.pending_count, qs.total_processed)
+    Ok(QueueSnapshot {
+        paused: qs.paused,
+        running: qs.running,
+        printing: qs.printing.clone(),
+        jobs,
+        failed,
+        total_processed: qs.total_processed,
+        last_print_at: qs.last_print_at.clone(),
+    })
+}
+
+pub async fn is_paused() -> bool {
+    QUEUE_STATE.read().await.paused
+}
+
+/// Stop taking jobs; the job already printing finishes.
+pub async fn pause() {
+    QUEUE_STATE.write().await.paused = true;
+    tracing::info!("Print queue paused");
+}
+
+pub async fn resume() {
+    QUEUE_STATE.write().await.paused = false;
+    WAKE.notify_one();
+    tracing::info!("Print queue resumed");
+}
+
+/// Delete a pending or failed job. Returns false when it does not exist
+/// (anymore) or is printing right now.
+pub async fn cancel(state: &SharedState, id: i64) -> Result<bool, String> {
+    let printing = QUEUE_STATE.read().await.printing.as_ref().map(|j| j.id);
+    if printing == Some(id) {
+        return Ok(false);
+    }
+    state.db().delete_print_job(id).map_err(db_err)
+}
+
+/// Drop every pending job (or every failed one); returns how many were
+/// removed.
+pub async fn clear(state: &SharedState, failed: bool) -> Result<usize, String> {
+    let status = if failed {
+        STATUS_FAILED
+    } else {
+        STATUS_PENDING
+    };
+    state.db().clear_print_jobs(status).map_err(db_err)
+}
+
+/// Queue a failed job again with a fresh set of attempts.
+pub async fn retry(state: &SharedState, id: i64) -> Result<bool, String> {
+    let retried = state
+        .db()
+        .retry_print_job(id, chrono::Utc::now().timestamp())
+        .map_err(db_err)?;
+    if retried {
+        WAKE.notify_one();
+    }
+    Ok(retried)
+}
+
+/// Put the jobs listed in `ids` first, in that order; jobs not listed
+/// (e.g. queued after the client loaded the list) keep their order after
+/// them. Unknown ids are ignored.
+pub async fn reorder(state: &SharedState, ids: &[i64]) -> Result<Vec<JobInfo>, String> {
+    state.db().reorder_print_jobs(ids).map_err(db_err)?;
+    list_jobs(state, STATUS_PENDING)
+}
+
+/// Wait until a job may be due: woken by a new job, or when the earliest
+/// backed-off job is due.
+async fn wait_for_work(state: &SharedState) {
+    let now = chrono::Utc::now().timestamp();
+    let wait = match state.db().next_print_job_due() {
+        Ok(Some(due)) => (due - now).clamp(1, RETRY_MAX_SECS),
+        Ok(None) => RETRY_MAX_SECS,
+        Err(e) => {
+            tracing::error!("{}", db_err(e));
+            5
+        }
+    };
+    let _ =
+        tokio::time::timeout(std::time::Duration::from_secs(wait as u64), WAKE.notified()).await;
 }
 
 /// Background worker loop — processes jobs sequentially.
-async fn worker_loop(state: SharedState, mut rx: mpsc::Receiver<PrintJob>) {
-    while let Some(job) = rx.recv().await {
-        {
-            let mut qs = QUEUE_STATE.write().await;
-            qs.pending_count = qs.pending_count.saturating_sub(1);
+async fn worker_loop(state: SharedState) {
+    loop {
+        if QUEUE_STATE.read().await.paused {
+            WAKE.notified().await;
+            continue;
         }
+        let claimed = match state
+            .db()
+            .claim_next_print_job(chrono::Utc::now().timestamp())
+        {
+            Ok(Some(claimed)) => claimed,
+            Ok(None) => {
+                wait_for_work(&state).await;
+                continue;
+            }
+            Err(e) => {
+                tracing::error!("{}", db_err(e));
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+        let id = claimed.record.id;
+        let attempts = claimed.record.attempts + 1;
+        QUEUE_STATE.write().await.printing = Some(JobInfo::from(claimed.record.clone()));
+        let job = PrintJob::from_claimed(claimed);
 
         let should_dry_run = should_use_dry_run(&state).await && !job.force;
 
-        if should_dry_run {
+        let result = if should_dry_run {
             tracing::info!(desc = %job.description, "Print job (dry run)");
             broadcast_print_event(&state, "print_success", &job.description, true);
+            Ok(())
         } else {
-            match execute_print(&state, &job).await {
-                Ok(()) => {
+            execute_print(&state, &job).await
+        };
+        let now = chrono::Utc::now().timestamp();
+        let stored = match result {
+            Ok(()) => {
+                if !should_dry_run {
                     tracing::info!(desc = %job.description, "Print job completed");
                     broadcast_print_event(&state, "print_success", &job.description, false);
                 }
-                Err(e) => {
-                    tracing::error!(desc = %job.description, error = %e, "Print job failed");
-                    broadcast_print_event(&state, "print_error", &e, false);
-                    // Wait before retrying next job
-                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                state.db().delete_print_job(id).map(|_| ())
+            }
+            Err(e) => {
+                let retry_at = (attempts < MAX_ATTEMPTS).then(|| now + retry_delay_secs(attempts));
+                match retry_at {
+                    Some(at) => tracing::warn!(
+                        desc = %job.description, error = %e, attempts,
+                        "Print job failed; retrying in {}s", at - now
+                    ),
+                    None => tracing::error!(
+                        desc = %job.description, error = %e, attempts,
+                        "Print job failed; moved to failed jobs"
+                    ),
                 }
+                broadcast_print_event(&state, "print_error", &e, false);
+                let stored = state.db().fail_print_job(id, &e, retry_at, now);
+                // Wait before retrying next job
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                stored
             }
+        };
+        if let Err(e) = stored {
+            tracing::error!("{}", db_err(e));
         }
 
         let mut qs = QUEUE_STATE.write().await;
+        qs.printing = None;
         qs.total_processed += 1;
         qs.last_print_at = Some(chrono::Utc::now().to_rfc3339());
     }
-
-    tracing::info!("Print queue worker stopped");
 }
 
 /// Check whether dry-run mode should be used.
@@ -123,41 +597,46 @@ async fn should_use_dry_run(state: &SharedState) -> bool {
     false
 }
 
-/// Execute the actual printing.
+/// Execute the actual printing on the printer routed for the job.
 async fn execute_print(state: &SharedState, job: &PrintJob) -> Result<(), String> {
-    let config = state.config().await;
-    let printer_type = if config.printer_type.is_empty() {
-        "bluetooth".to_string()
-    } else {
-        config.printer_type.clone()
-    };
-    let address = config.printer_address.clone();
-    let usb_name = config.usb_printer_name.clone();
-    let rotate_print = config.rotate_print;
-    drop(config);
+    smart_plug::ensure_powered(state)
+        .await
+        .map_err(|e| format!("Printer power check failed: {e}"))?;
 
-    let width = if job.mono_width == 0 {
-        catprinter::PRINT_WIDTH
-    } else {
-        job.mono_width
-    };
+    let registry = registry(state).await;
+    let printer = registry
+        .resolve(job.category)
+        .ok_or("No printer configured")?;
+    let rotate_print = state.config().await.rotate_print;
+
+    let width = job.width();
+    tracing::debug!(desc = %job.description, printer = %printer.name, "Printing job");
 
-    match printer_type.as_str() {
-        "usb" => {
-            if usb_name.is_empty() {
+    match &printer.target {
+        PrinterTarget::Escpos { device, cut } => {
+            printer_pipeline::print_bitmap_escpos(
+                device,
+                &job.mono_image,
+                width,
+                rotate_print,
+                *cut,
+            )
+            .await
+        }
+        PrinterTarget::Usb { printer_name } => {
+            if printer_name.is_empty() {
                 return Err("USB printer name not configured".into());
             }
-            printer_pipeline::print_bitmap_usb(&usb_name, &job.mono_image, width, rotate_print)
+            printer_pipeline::print_bitmap_usb(printer_name, &job.mono_image, width, rotate_print)
                 .await
         }
-        "bluetooth" => {
+        PrinterTarget::Bluetooth { address } => {
             if address.is_empty() {
                 return Err("Bluetooth printer address not configured".into());
             }
-            printer_pipeline::print_bitmap_bluetooth(&address, &job.mono_image, width, rotate_print)
+            printer_pipeline::print_bitmap_bluetooth(address, &job.mono_image, width, rotate_print)
                 .await
         }
-        _ => Err(format!("Unsupported printer type: {printer_type}")),
     }
 }
 
@@ -172,3 +651,113 @@ fn broadcast_print_event(state: &SharedState, event_type: &str, message: &str, d
     });
     let _ = state.ws_sender().send(msg.to_string());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        assert_eq!(retry_delay_secs(1), RETRY_BASE_SECS);
+        assert_eq!(retry_delay_secs(2), RETRY_BASE_SECS * 2);
+        assert_eq!(retry_delay_secs(3), RETRY_BASE_SECS * 4);
+        assert_eq!(retry_delay_secs(40), RETRY_MAX_SECS);
+    }
+
+    #[test]
+    fn test_job_info_from_record() {
+        let record = PrintJobRecord {
+            id: 3,
+            status: STATUS_PENDING.into(),
+            description: "chat".into(),
+            category: "reward".into(),
+            force: false,
+            width: 384,
+            height: 10,
+            has_color: true,
+            attempts: 2,
+            last_error: Some("offline".into()),
+            next_attempt_at: 60,
+            created_at: 0,
+            updated_at: 30,
+        };
+        let info = JobInfo::from(record.clone());
+        assert_eq!(info.category, PrintCategory::Reward);
+        assert_eq!(info.retry_at.as_deref(), Some("1970-01-01T00:01:00+00:00"));
+
+        let failed = JobInfo::from(PrintJobRecord {
+            status: STATUS_FAILED.into(),
+            category: "unknown".into(),
+            ..record
+        });
+        assert_eq!(failed.category, PrintCategory::Other);
+        assert!(failed.retry_at.is_none());
+    }
+
+    #[test]
+    fn test_parse_printers() {
+        let printers = parse_printers(
+            r#"[{ "name": "rewards", "type": "bluetooth", "address": "AA:BB:CC:DD:EE:FF" },
+                { "name": "receipt", "type": "escpos", "device": "/dev/usb/lp0" }]"#,
+        )
+        .unwrap();
+        assert_eq!(printers.len(), 2);
+        assert_eq!(printers[0].target.kind(), "bluetooth");
+        assert_eq!(
+            printers[1].target,
+            PrinterTarget::Escpos {
+                device: "/dev/usb/lp0".into(),
+                cut: true
+            }
+        );
+        assert!(parse_printers("").unwrap().is_empty());
+
+        let dup = r#"[{ "name": "a", "type": "usb", "printer_name": "x" },
+                      { "name": "a", "type": "usb", "printer_name": "y" }]"#;
+        assert!(parse_printers(dup).is_err());
+        assert!(
+            parse_printers(r#"[{ "name": "default", "type": "usb", "printer_name": "x" }]"#)
+                .is_err()
+        );
+        assert!(
+            parse_printers(r#"[{ "name": "a", "type": "bluetooth", "address": "" }]"#).is_err()
+        );
+        assert!(parse_printers(r#"[{ "name": "a", "type": "fax" }]"#).is_err());
+    }
+
+    #[test]
+    fn test_registry_routes() {
+        let routes =
+            parse_routes(r#"{ "chat": "receipt", "reward": "gone", "clock": "" }"#).unwrap();
+        assert_eq!(routes.len(), 2);
+        assert!(parse_routes(r#"{ "other": "receipt" }"#).is_err());
+
+        let printer = |name: &str| NamedPrinter {
+            name: name.into(),
+            target: PrinterTarget::Usb {
+                printer_name: name.into(),
+            },
+        };
+        let registry = PrinterRegistry {
+            printers: vec![printer(DEFAULT_PRINTER), printer("receipt")],
+            routes,
+        };
+        assert_eq!(
+            registry.resolve(PrintCategory::Chat).unwrap().name,
+            "receipt"
+        );
+        // Unknown printer and unrouted categories fall back to the default.
+        assert_eq!(
+            registry.resolve(PrintCategory::Reward).unwrap().name,
+            DEFAULT_PRINTER
+        );
+        assert_eq!(
+            registry.resolve(PrintCategory::Clock).unwrap().name,
+            DEFAULT_PRINTER
+        );
+        assert_eq!(
+            registry.resolve(PrintCategory::Other).unwrap().name,
+            DEFAULT_PRINTER
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Rendering helpers that turn images into print-queue jobs.
+
+use ab_glyph::FontRef;
+use image::DynamicImage;
+
+use crate::app::SharedState;
+use crate::services::font::FontService;
+use crate::services::print_queue::{self, PrintCategory, PrintJob};
+use crate::services::print_templates;
+
+/// Load the custom font bytes used for printed text.
+pub fn load_font(state: &SharedState) -> Result<Vec<u8>, String> {
+    FontService::new(state.data_dir().clone())
+        .get_font_data()
+        .map_err(|e| format!("Failed to load custom font: {e}"))
+}
+
+/// Convert an image into a 0/1-per-pixel bitmap at printer width.
+pub fn to_mono_bitmap(img: &DynamicImage, dither: bool, black_point: f32) -> (Vec<u8>, u16) {
+    let width = catprinter::PRINT_WIDTH;
+    let resized = if img.width() == u32::from(width) {
+        img.clone()
+    } else {
+        image_processor::resize_to_width(img, u32::from(width))
+    };
+    let gray = resized.to_luma8();
+    let bw = if dither {
+        image_processor::floyd_steinberg_dither(&gray)
+    } else {
+        let threshold = (black_point.clamp(0.0, 1.0) * 255.0) as u8;
+        image_processor::threshold_convert(&gray, threshold)
+    };
+    let bitmap = bw.pixels().map(|p| u8::from(p.0[0] < 128)).collect();
+    (bitmap, width)
+}
+
+/// Encode an image as PNG bytes.
+pub fn encode_png(img: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut buf = std::io::Cursor::new(Vec::<u8>::new());
+    img.write_to(&mut buf, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {e}"))?;
+    Ok(buf.into_inner())
+}
+
+/// Queue an already-rendered image for printing.
+pub async fn enqueue_image(
+    state: &SharedState,
+    img: &DynamicImage,
+    description: &str,
+    category: PrintCategory,
+) -> Result<(), String> {
+    let (dither, black_point) = {
+        let config = state.config().await;
+        (config.dither, config.black_point)
+    };
+    let (mono_image, mono_width) = to_mono_bitmap(img, dither, black_point);
+    print_queue::enqueue(
+        state,
+        PrintJob {
+            mono_image,
+            mono_width,
+            color_image: encode_png(img).ok(),
+            description: description.to_string(),
+            force: false,
+            category,
+        },
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Largest accepted upload for [`print_uploaded_image`].
+pub const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+/// Largest accepted source dimension (guards against decompression bombs).
+const MAX_SOURCE_DIMENSION: u32 = 8000;
+/// Longest print allowed after resizing to printer width.
+const MAX_PRINT_HEIGHT: u32 = 4000;
+
+/// Options for printing an uploaded image.
+#[derive(Debug, Clone, Copy)]
+pub struct ImagePrintOptions {
+    pub dither: bool,
+    pub black_point: f32,
+    /// Rotate landscape images to portrait so they print larger.
+    pub auto_rotate: bool,
+}
+
+/// Decode a PNG/JPEG upload, apply `auto_rotate` and check that it fits
+/// within the print length limit.
+pub fn decode_upload(data: &[u8], options: ImagePrintOptions) -> Result<DynamicImage, String> {
+    if data.len() > MAX_UPLOAD_BYTES {
+        return Err(format!(
+            "Image is too large (max {} MB)",
+            MAX_UPLOAD_BYTES / 1024 / 1024
+        ));
+    }
+    let format = image::guess_format(data).map_err(|_| "Unrecognized image format")?;
+    if !matches!(format, image::ImageFormat::Png | image::ImageFormat::Jpeg) {
+        return Err("Only PNG and JPEG images are supported".into());
+    }
+    let mut reader = image::ImageReader::with_format(std::io::Cursor::new(data), format);
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    reader.limits(limits);
+    let img = reader
+        .decode()
+        .map_err(|e| format!("Failed to decode image: {e}"))?;
+
+    let img = if options.auto_rotate {
+        image_processor::auto_rotate_portrait(&img)
+    } else {
+        img
+    };
+    let printed_height = u64::from(img.height()) * u64::from(catprinter::PRINT_WIDTH)
+        / u64::from(img.width().max(1));
+    if printed_height > u64::from(MAX_PRINT_HEIGHT) {
+        return Err(format!(
+            "Image would print {printed_height}px long (max {MAX_PRINT_HEIGHT}px)"
+        ));
+    }
+    Ok(img)
+}
+
+/// Decode a PNG/JPEG upload, check its size and queue it. Returns the
+/// printed height in pixels.
+pub async fn print_uploaded_image(
+    state: &SharedState,
+    data: &[u8],
+    options: ImagePrintOptions,
+    description: &str,
+) -> Result<u32, String> {
+    let img = decode_upload(data, options)?;
+    let (mono_image, mono_width) = to_mono_bitmap(&img, options.dither, options.black_point);
+    let printed_height = (mono_image.len() / usize::from(mono_width)) as u32;
+    print_queue::enqueue(
+        state,
+        PrintJob {
+            mono_image,
+            mono_width,
+            color_image: encode_png(&img).ok(),
+            description: description.to_string(),
+            force: false,
+            category: PrintCategory::Other,
+        },
+    )
+    .await?;
+    Ok(printed_height)
+}
+
+/// A rendered print preview.
+#[derive(Debug, Clone)]
+pub struct Preview {
+    pub png: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Render a monochrome bitmap as the printer would output it (including
+/// `rotate_print`) and encode it as PNG.
+pub fn preview_bitmap(bitmap: &[u8], width: u16, rotate_print: bool) -> Result<Preview, String> {
+    let img = image_processor::preview::render_preview(bitmap, u32::from(width), rotate_print)
+        .ok_or("bitmap dimensions are invalid")?;
+    Ok(Preview {
+        png: encode_png(&img)?,
+        width: img.width(),
+        height: img.height(),
+    })
+}
+
+/// Run an image through the print pipeline (dither → rotate) without
+/// queueing it.
+pub fn preview_image(
+    img: &DynamicImage,
+    dither: bool,
+    black_point: f32,
+    rotate_print: bool,
+) -> Result<Preview, String> {
+    let (bitmap, width) = to_mono_bitmap(img, dither, black_point);
+    preview_bitmap(&bitmap, width, rotate_print)
+}
+
+/// Render a titled card (title / username / details) and queue it. The
+/// footer timestamp follows `PRINT_LOCALE`.
+pub async fn print_titled(
+    state: &SharedState,
+    title: &str,
+    username: &str,
+    details: &str,
+    category: PrintCategory,
+) -> Result<(), String> {
+    let font_data = load_font(state)?;
+    let font = FontRef::try_from_slice(&font_data).map_err(|_| "Invalid font data")?;
+    let img = image_processor::message::message_to_image_with_title(
+        title,
+        username,
+        details,
+        None,
+        &font,
+        false,
+        print_templates::locale(state),
+    );
+    enqueue_image(state, &img, title, category).await
+}
@@ -0,0 +1,141 @@
+//! Print rules engine: per-user quota and cooldown for redemption prints.
+//!
+//! Usage is tracked in memory and reset when the stream goes online, so
+//! the quota applies per stream. The broadcaster is never limited.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+
+#[derive(Debug, Default)]
+struct UserUsage {
+    count: u32,
+    last_print: Option<Instant>,
+}
+
+static PRINT_USAGE: LazyLock<RwLock<HashMap<String, UserUsage>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Why a print was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrintRejection {
+    QuotaExceeded { limit: u32 },
+    Cooldown { remaining: Duration },
+}
+
+struct Limits {
+    quota: u32,
+    cooldown: Duration,
+}
+
+fn load_limits(state: &SharedState) -> Limits {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    Limits {
+        quota: get("PRINT_QUOTA_PER_STREAM").parse().unwrap_or(0),
+        cooldown: Duration::from_secs(
+            get("PRINT_COOLDOWN_MINUTES").parse::<u64>().unwrap_or(0) * 60,
+        ),
+    }
+}
+
+/// Check the limits for `user_id` and, if allowed, record the print.
+pub async fn check_and_record(state: &SharedState, user_id: &str) -> Result<(), PrintRejection> {
+    if user_id.is_empty() || user_id == state.config().await.twitch_user_id {
+        return Ok(());
+    }
+    let limits = load_limits(state);
+    let mut usage = PRINT_USAGE.write().await;
+    let entry = usage.entry(user_id.to_string()).or_default();
+    evaluate(entry, &limits, Instant::now())
+}
+
+fn evaluate(entry: &mut UserUsage, limits: &Limits, now: Instant) -> Result<(), PrintRejection> {
+    if limits.quota > 0 && entry.count >= limits.quota {
+        return Err(PrintRejection::QuotaExceeded {
+            limit: limits.quota,
+        });
+    }
+    if let Some(last) = entry.last_print {
+        let elapsed = now.saturating_duration_since(last);
+        if elapsed < limits.cooldown {
+            return Err(PrintRejection::Cooldown {
+                remaining: limits.cooldown - elapsed,
+            });
+        }
+    }
+    entry.count += 1;
+    entry.last_print = Some(now);
+    Ok(())
+}
+
+/// Forget all usage (called when a new stream starts).
+pub async fn reset_usage() {
+    PRINT_USAGE.write().await.clear();
+}
+
+/// Polite chat reply for a refused print, or `None` if replies are off.
+pub fn rejection_message(
+    state: &SharedState,
+    username: &str,
+    rejection: &PrintRejection,
+) -> Option<String> {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    if get("PRINT_LIMIT_REPLY_ENABLED") != "true" {
+        return None;
+    }
+    let text = match rejection {
+        PrintRejection::QuotaExceeded { limit } => get("PRINT_QUOTA_MESSAGE")
+            .replace("{user}", username)
+            .replace("{limit}", &limit.to_string()),
+        PrintRejection::Cooldown { remaining } => get("PRINT_COOLDOWN_MESSAGE")
+            .replace("{user}", username)
+            .replace("{remaining}", &remaining.as_secs().div_ceil(60).to_string()),
+    };
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_and_cooldown() {
+        let limits = Limits {
+            quota: 2,
+            cooldown: Duration::from_secs(60),
+        };
+        let mut usage = UserUsage::default();
+        let t0 = Instant::now();
+
+        assert!(evaluate(&mut usage, &limits, t0).is_ok());
+        assert!(matches!(
+            evaluate(&mut usage, &limits, t0 + Duration::from_secs(30)),
+            Err(PrintRejection::Cooldown { .. })
+        ));
+        assert!(evaluate(&mut usage, &limits, t0 + Duration::from_secs(61)).is_ok());
+        assert_eq!(
+            evaluate(&mut usage, &limits, t0 + Duration::from_secs(200)),
+            Err(PrintRejection::QuotaExceeded { limit: 2 })
+        );
+    }
+
+    #[test]
+    fn test_unlimited_by_default() {
+        let limits = Limits {
+            quota: 0,
+            cooldown: Duration::ZERO,
+        };
+        let mut usage = UserUsage::default();
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert!(evaluate(&mut usage, &limits, now).is_ok());
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Locale variants of printed text.
+//!
+//! Each [`Template`] has one string per language; `{name}` placeholders are
+//! filled by [`render`]. The locale comes from `PRINT_LOCALE`, which also
+//! decides how dates, times and numbers are formatted on prints.
+
+use image_processor::PrintLocale;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    MilestoneTitle,
+    /// `{label}`, `{threshold}`, `{value}`
+    MilestoneDetails,
+    MilestoneFollowers,
+    MilestoneViewers,
+    MilestoneOther,
+    QuoteTitle,
+    RundownTitle,
+    HypeTrainTitle,
+    /// `{level}`, `{total}`
+    HypeTrainDetails,
+    LotteryWinnerTitle,
+    /// `{prize}`
+    LotteryWinnerDetails,
+}
+
+/// The configured print locale; Japanese when unset or unknown.
+pub fn locale(state: &SharedState) -> PrintLocale {
+    SettingsManager::new(state.db().clone())
+        .get_setting("PRINT_LOCALE")
+        .ok()
+        .and_then(|code| PrintLocale::parse(&code))
+        .unwrap_or_default()
+}
+
+/// Template text for `locale`.
+pub fn text(locale: PrintLocale, template: Template) -> &'static str {
+    use Template::*;
+    match (template, locale.lang()) {
+        (MilestoneTitle, "en") => "Milestone reached",
+        (MilestoneTitle, "de") => "Meilenstein erreicht",
+        (MilestoneTitle, "fr") => "Objectif atteint",
+        (MilestoneTitle, "es") => "Meta alcanzada",
+        (MilestoneTitle, _) => "マイルストーン達成",
+
+        (MilestoneDetails, "en") => "{label}: {threshold} reached! (now {value})",
+        (MilestoneDetails, "de") => "{label}: {threshold} erreicht! (aktuell {value})",
+        (MilestoneDetails, "fr") => "{label} : {threshold} atteint ! (actuellement {value})",
+        (MilestoneDetails, "es") => "{label}: ¡{threshold} alcanzado! (ahora {value})",
+        (MilestoneDetails, _) => "{label} {threshold} 達成！（現在 {value}）",
+
+        (MilestoneFollowers, "en") => "Followers",
+        (MilestoneFollowers, "de") => "Follower",
+        (MilestoneFollowers, "fr") => "Abonnés",
+        (MilestoneFollowers, "es") => "Seguidores",
+        (MilestoneFollowers, _) => "フォロワー",
+
+        (MilestoneViewers, "en") => "Viewers",
+        (MilestoneViewers, "de") => "Zuschauer",
+        (MilestoneViewers, "fr") => "Spectateurs",
+        (MilestoneViewers, "es") => "Espectadores",
+        (MilestoneViewers, _) => "同時視聴者",
+
+        (MilestoneOther, "en") => "Milestone",
+        (MilestoneOther, "de") => "Meilenstein",
+        (MilestoneOther, "fr") => "Objectif",
+        (MilestoneOther, "es") => "Meta",
+        (MilestoneOther, _) => "マイルストーン",
+
+        (QuoteTitle, "ja") => "今日の名言",
+        (QuoteTitle, "de") => "Zitat des Tages",
+        (QuoteTitle, "fr") => "Citation du jour",
+        (QuoteTitle, "es") => "Cita del día",
+        (QuoteTitle, _) => "Quote of the Day",
+
+        (RundownTitle, "en") => "Stream rundown",
+        (RundownTitle, "de") => "Ablaufplan",
+        (RundownTitle, "fr") => "Déroulé du live",
+        (RundownTitle, "es") => "Guion del directo",
+        (RundownTitle, _) => "本日の進行表",
+
+        (HypeTrainTitle, "en") => "Hype Train complete!",
+        (HypeTrainTitle, "de") => "Hype-Train abgeschlossen!",
+        (HypeTrainTitle, "fr") => "Hype Train terminé !",
+        (HypeTrainTitle, "es") => "¡Hype Train completado!",
+        (HypeTrainTitle, _) => "ハイプトレイン達成！",
+
+        (HypeTrainDetails, "en") => "Reached level {level} ({total} points). Thank you!",
+        (HypeTrainDetails, "de") => "Level {level} erreicht ({total} Punkte). Danke!",
+        (HypeTrainDetails, "fr") => "Niveau {level} atteint ({total} points). Merci !",
+        (HypeTrainDetails, "es") => "¡Nivel {level} alcanzado ({total} puntos)! ¡Gracias!",
+        (HypeTrainDetails, _) => "レベル {level} 到達！（合計 {total} ポイント）感謝！",
+
+        (LotteryWinnerTitle, "en") => "Congratulations!",
+        (LotteryWinnerTitle, "de") => "Herzlichen Glückwunsch!",
+        (LotteryWinnerTitle, "fr") => "Félicitations !",
+        (LotteryWinnerTitle, "es") => "¡Felicidades!",
+        (LotteryWinnerTitle, _) => "当選おめでとう！",
+
+        (LotteryWinnerDetails, "en") => "Prize: {prize}",
+        (LotteryWinnerDetails, "de") => "Gewinn: {prize}",
+        (LotteryWinnerDetails, "fr") => "Lot : {prize}",
+        (LotteryWinnerDetails, "es") => "Premio: {prize}",
+        (LotteryWinnerDetails, _) => "景品: {prize}",
+    }
+}
+
+/// Template text with `{name}` placeholders replaced.
+pub fn render(locale: PrintLocale, template: Template, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(text(locale, template).to_string(), |out, (name, value)| {
+            out.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let vars = [
+            ("label", "Followers"),
+            ("threshold", "1,000"),
+            ("value", "1,002"),
+        ];
+        assert_eq!(
+            render(PrintLocale::EnUs, Template::MilestoneDetails, &vars),
+            "Followers: 1,000 reached! (now 1,002)"
+        );
+        assert_eq!(
+            render(PrintLocale::JaEra, Template::MilestoneTitle, &[]),
+            "マイルストーン達成"
+        );
+        assert_eq!(
+            text(PrintLocale::De, Template::QuoteTitle),
+            "Zitat des Tages"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Chat-to-print voting.
+//!
+//! Viewers nominate a chat message by replying to it with
+//! `PRINT_VOTE_KEYWORD` (`!print` by default), or by redeeming
+//! `PRINT_VOTE_REWARD_ID` with a viewer's name as input, which nominates
+//! that viewer's latest message. The `print_vote` schedule
+//! (`PRINT_VOTE_CRON`, run by [`super::scheduler`]) prints the most-voted
+//! message with its vote count once it has `PRINT_VOTE_MIN_VOTES` votes and
+//! starts the next round. Authors who opted out of printing are passed over.
+
+use overlay_db::chat::ChatSearch;
+use overlay_db::consents::ConsentScope;
+use overlay_db::print_votes::{PrintVoteTally, SOURCE_REPLY, SOURCE_REWARD};
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::{send_ws, str_field};
+use crate::services::print_queue::PrintCategory;
+use crate::services::{chat_print, consent, scheduler};
+
+const DEFAULT_KEYWORD: &str = "!print";
+const TALLY_LIMIT: i64 = 20;
+/// How far back a redemption looks for the named viewer's latest message.
+const REWARD_LOOKBACK_SECS: i64 = 30 * 60;
+
+struct VoteSettings {
+    enabled: bool,
+    keyword: String,
+    min_votes: i64,
+    reward_id: String,
+}
+
+fn load_settings(state: &SharedState) -> VoteSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let keyword = get("PRINT_VOTE_KEYWORD");
+    VoteSettings {
+        enabled: get("PRINT_VOTE_ENABLED") == "true",
+        keyword: if keyword.is_empty() {
+            DEFAULT_KEYWORD.to_string()
+        } else {
+            keyword
+        },
+        min_votes: get("PRINT_VOTE_MIN_VOTES").parse().unwrap_or(2),
+        reward_id: get("PRINT_VOTE_REWARD_ID"),
+    }
+}
+
+/// The configured vote keyword (`PRINT_VOTE_KEYWORD`, default `!print`).
+pub fn keyword(state: &SharedState) -> String {
+    load_settings(state).keyword
+}
+
+/// A reply's text without the `@parent` mention Twitch puts in front.
+fn reply_body(text: &str) -> &str {
+    let text = text.trim_start();
+    match text.strip_prefix('@') {
+        Some(rest) => rest
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, body)| body.trim()),
+        None => text.trim(),
+    }
+}
+
+/// Whether a reply body starts with the vote keyword.
+fn is_vote(body: &str, keyword: &str) -> bool {
+    body.split_whitespace()
+        .next()
+        .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+}
+
+fn tally(state: &SharedState) -> Vec<PrintVoteTally> {
+    state
+        .db()
+        .get_print_vote_tally(TALLY_LIMIT)
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load print votes: {e}");
+            Vec::new()
+        })
+}
+
+fn vote(state: &SharedState, message_id: &str, voter_id: &str, source: &str) {
+    if voter_id.is_empty() {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp();
+    match state.db().add_print_vote(message_id, voter_id, source, now) {
+        Ok(true) => {
+            tracing::debug!(message_id, source, "Print vote counted");
+            send_ws(state, "print_vote", json!({ "tally": tally(state) }));
+        }
+        Ok(false) => {}
+        Err(e) => tracing::warn!("Failed to record print vote: {e}"),
+    }
+}
+
+/// Count a keyword reply as a vote for the message it replies to.
+pub fn handle_chat_message(state: &SharedState, payload: &Value) {
+    let parent_id = str_field(payload, &["reply", "parent_message_id"]);
+    if parent_id.is_empty() {
+        return;
+    }
+    let s = load_settings(state);
+    let text = str_field(payload, &["message", "text"]);
+    if !s.enabled || !is_vote(reply_body(&text), &s.keyword) {
+        return;
+    }
+    let voter_id = str_field(payload, &["chatter_user_id"]);
+    vote(state, &parent_id, &voter_id, SOURCE_REPLY);
+}
+
+/// Count a vote redemption for the latest message of the viewer named in
+/// its input.
+pub fn handle_redemption(state: &SharedState, payload: &Value) {
+    let s = load_settings(state);
+    let reward_id = str_field(payload, &["reward", "id"]);
+    if !s.enabled || s.reward_id.is_empty() || reward_id != s.reward_id {
+        return;
+    }
+    let input = str_field(payload, &["user_input"]);
+    let Some(author) = input
+        .split_whitespace()
+        .next()
+        .map(|w| w.trim_start_matches('@'))
+    else {
+        tracing::debug!("Print vote redemption without a viewer name");
+        return;
+    };
+    let search = ChatSearch {
+        user: Some(author.to_string()),
+        from: Some(chrono::Utc::now().timestamp() - REWARD_LOOKBACK_SECS),
+        limit: 1,
+        ..Default::default()
+    };
+    match state.db().full_text_search_chat(&search) {
+        Ok(page) => match page.hits.first() {
+            Some(hit) => vote(
+                state,
+                &hit.message.message_id,
+                &str_field(payload, &["user_id"]),
+                SOURCE_REWARD,
+            ),
+            None => tracing::debug!(author, "No recent message to vote for"),
+        },
+        Err(e) => tracing::warn!("Failed to look up message for print vote: {e}"),
+    }
+}
+
+/// Print the round's winner and start the next round. `Ok(None)` when no
+/// printable message has enough votes; the votes then carry over.
+pub async fn print_winner(state: &SharedState) -> Result<Option<PrintVoteTally>, String> {
+    let min_votes = load_settings(state).min_votes;
+    let Some(winner) = tally(state)
+        .into_iter()
+        .filter(|t| t.votes >= min_votes)
+        .find(|t| consent::allows(state, &t.message.user_id, ConsentScope::Print))
+    else {
+        return Ok(None);
+    };
+
+    let msg = &winner.message;
+    let fragments = serde_json::from_str::<Value>(&msg.fragments_json)
+        .ok()
+        .filter(Value::is_array)
+        .unwrap_or_else(|| json!([{ "type": "text", "text": msg.message }]));
+    let badges = serde_json::from_str::<Value>(&msg.badges_json).unwrap_or(Value::Null);
+    let label = format!("{} ({}票)", msg.username, winner.votes);
+    chat_print::print_chat_message(state, &label, &badges, &fragments, PrintCategory::Chat).await?;
+
+    state
+        .db()
+        .record_print_vote_winner(msg, winner.votes, chrono::Utc::now().timestamp())
+        .map_err(|e| format!("Failed to record print vote winner: {e}"))?;
+    tracing::info!(
+        message_id = %msg.message_id,
+        votes = winner.votes,
+        "Printed voted chat message"
+    );
+    send_ws(
+        state,
+        "print_vote",
+        json!({ "tally": tally(state), "winner": winner }),
+    );
+    Ok(Some(winner))
+}
+
+/// Settings, the current tally, recent winners and the next round.
+pub fn status(state: &SharedState) -> Value {
+    let s = load_settings(state);
+    let next_round = scheduler::list(state)
+        .1
+        .into_iter()
+        .find(|schedule| schedule.id == scheduler::SCHEDULE_PRINT_VOTE)
+        .and_then(|schedule| schedule.next_run);
+    let winners = state.db().get_print_vote_winners(10).unwrap_or_else(|e| {
+        tracing::warn!("Failed to load print vote winners: {e}");
+        Vec::new()
+    });
+    json!({
+        "enabled": s.enabled,
+        "keyword": s.keyword,
+        "min_votes": s.min_votes,
+        "next_round": next_round,
+        "tally": tally(state),
+        "winners": winners,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_vote_keyword() {
+        assert!(is_vote(reply_body("@alice !print"), "!print"));
+        assert!(is_vote(reply_body("@alice  !PRINT これ最高"), "!print"));
+        assert!(!is_vote(reply_body("@alice わかる !print"), "!print"));
+        assert!(!is_vote(reply_body("@alice"), "!print"));
+        assert!(is_vote(reply_body("!print"), "!print"));
+    }
+}
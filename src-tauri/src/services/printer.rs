@@ -1,5 +1,6 @@
 //! Printer service helpers (BLE scan/test/reconnect + CUPS listing).
 
+use std::collections::HashMap;
 use std::sync::LazyLock;
 use std::time::Duration;
 
@@ -12,6 +13,8 @@ use tokio::process::Command;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+use crate::services::print_queue;
+
 const GB_SERVICE_UUID_STANDARD: Uuid = Uuid::from_u128(0x0000_ae30_0000_1000_8000_0080_5f9b_34fb);
 
 #[derive(Debug, Clone, Serialize)]
@@ -27,7 +30,7 @@ pub struct SystemPrinter {
     pub status: String,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct PrinterRuntimeState {
     pub connected: bool,
     pub connected_type: Option<String>,
@@ -35,23 +38,44 @@ pub struct PrinterRuntimeState {
     pub last_error: Option<String>,
 }
 
-static PRINTER_RUNTIME: LazyLock<RwLock<PrinterRuntimeState>> =
-    LazyLock::new(|| RwLock::new(PrinterRuntimeState::default()));
+/// Runtime state per printer name (see [`print_queue::DEFAULT_PRINTER`]).
+static PRINTER_RUNTIME: LazyLock<RwLock<HashMap<String, PrinterRuntimeState>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
 
+/// State of the default printer.
 pub async fn get_runtime_state() -> PrinterRuntimeState {
-    PRINTER_RUNTIME.read().await.clone()
+    get_printer_state(print_queue::DEFAULT_PRINTER).await
+}
+
+pub async fn get_printer_state(name: &str) -> PrinterRuntimeState {
+    PRINTER_RUNTIME
+        .read()
+        .await
+        .get(name)
+        .cloned()
+        .unwrap_or_default()
 }
 
 pub async fn mark_connected(printer_type: &str, target: &str) {
-    let mut rt = PRINTER_RUNTIME.write().await;
+    mark_printer_connected(print_queue::DEFAULT_PRINTER, printer_type, target).await;
+}
+
+pub async fn mark_error(err: impl Into<String>) {
+    mark_printer_error(print_queue::DEFAULT_PRINTER, err).await;
+}
+
+pub async fn mark_printer_connected(name: &str, printer_type: &str, target: &str) {
+    let mut runtime = PRINTER_RUNTIME.write().await;
+    let rt = runtime.entry(name.to_string()).or_default();
     rt.connected = true;
     rt.connected_type = Some(printer_type.to_string());
     rt.connected_target = Some(target.to_string());
     rt.last_error = None;
 }
 
-pub async fn mark_error(err: impl Into<String>) {
-    let mut rt = PRINTER_RUNTIME.write().await;
+pub async fn mark_printer_error(name: &str, err: impl Into<String>) {
+    let mut runtime = PRINTER_RUNTIME.write().await;
+    let rt = runtime.entry(name.to_string()).or_default();
     rt.connected = false;
     rt.last_error = Some(err.into());
 }
@@ -168,6 +192,13 @@ pub async fn is_usb_printer_available(printer_name: &str) -> Result<bool, String
     Ok(printers.iter().any(|p| p.name == printer_name))
 }
 
+/// Whether the ESC/POS device path exists (`COM` ports are not files and
+/// are assumed present).
+pub fn is_escpos_device_available(device: &str) -> bool {
+    !device.is_empty()
+        && (device.to_ascii_uppercase().starts_with("COM") || std::path::Path::new(device).exists())
+}
+
 async fn connect_target(
     conn: &mut BleConnection,
     address: &str,
@@ -1,17 +1,21 @@
 //! Printer data pipeline helpers.
 //!
 //! Converts monochrome bitmaps (0/1 per pixel) into printer commands and
-//! sends them over BLE or USB paths.
+//! sends them over BLE, USB (CUPS) or ESC/POS device paths.
 
 use std::time::Duration;
 
 use catprinter::ble::{BleConnection, DiscoveredDevice};
+use catprinter::protocol::EscPosProtocol;
 use catprinter::protocol::gb::{GbProtocol, SERVICE_UUID_MACOS};
+use catprinter::transport::{self, DeviceTransport};
 use catprinter::{CatPrinterError, PrinterProtocol};
 use uuid::Uuid;
 
 const GB_SERVICE_UUID_STANDARD: Uuid = Uuid::from_u128(0x0000_ae30_0000_1000_8000_0080_5f9b_34fb);
 const TEST_IMAGE_HEIGHT: usize = 144;
+/// Lines fed after the image.
+const FEED_LINES: u16 = 4;
 
 /// Build a simple monochrome test bitmap (384px width).
 pub fn generate_test_bitmap(width: u16) -> Vec<u8> {
@@ -70,9 +74,10 @@ pub async fn print_bitmap_bluetooth(
         .map_err(cat_error)?;
     tokio::time::sleep(Duration::from_millis(500)).await;
 
-    let send_result = send_bitmap_over_ble(&conn, &protocol, &print_bitmap, width)
-        .await
-        .map_err(cat_error);
+    let send_result =
+        transport::send_bitmap(&mut conn, &protocol, &print_bitmap, width, FEED_LINES)
+            .await
+            .map_err(cat_error);
     let disconnect_result = conn.disconnect().await.map_err(cat_error);
     finalize_ble_results(send_result, disconnect_result)
 }
@@ -94,7 +99,8 @@ pub async fn print_bitmap_usb(
     } else {
         normalized
     };
-    let payload = build_gb_payload(&print_bitmap, width)?;
+    let payload = transport::build_payload(&GbProtocol::new(), &print_bitmap, width, FEED_LINES)
+        .map_err(cat_error)?;
     let rows = print_bitmap.len() / (width as usize);
 
     let width_mm = 53.0f32;
@@ -103,48 +109,30 @@ pub async fn print_bitmap_usb(
     crate::services::printer::print_via_usb(printer_name, &payload, width_mm, height_mm).await
 }
 
-fn build_gb_payload(bitmap: &[u8], width: u16) -> Result<Vec<u8>, String> {
-    let protocol = GbProtocol::new();
-    let row_width = width as usize;
-    if row_width == 0 || !bitmap.len().is_multiple_of(row_width) {
-        return Err("bitmap dimensions are invalid".to_string());
-    }
-
-    let mut payload = Vec::new();
-    for cmd in protocol.build_init_sequence() {
-        payload.extend_from_slice(&cmd);
-    }
-    for row in bitmap.chunks(row_width) {
-        let cmd = protocol.encode_row(row, width);
-        payload.extend_from_slice(&cmd);
-    }
-    payload.extend_from_slice(&protocol.build_feed_command(4));
-    for cmd in protocol.build_finish_sequence() {
-        payload.extend_from_slice(&cmd);
-    }
-
-    Ok(payload)
-}
-
-async fn send_bitmap_over_ble(
-    conn: &BleConnection,
-    protocol: &GbProtocol,
+/// Send a bitmap to an ESC/POS printer device (USB printer class or serial).
+pub async fn print_bitmap_escpos(
+    device: &str,
     bitmap: &[u8],
     width: u16,
-) -> Result<(), CatPrinterError> {
-    let row_width = width as usize;
-    for cmd in protocol.build_init_sequence() {
-        conn.write_data(&cmd).await?;
-    }
-    for row in bitmap.chunks(row_width) {
-        let cmd = protocol.encode_row(row, width);
-        conn.write_data(&cmd).await?;
-    }
-    conn.write_data(&protocol.build_feed_command(4)).await?;
-    for cmd in protocol.build_finish_sequence() {
-        conn.write_data(&cmd).await?;
+    rotate_print: bool,
+    cut: bool,
+) -> Result<(), String> {
+    if device.trim().is_empty() {
+        return Err("ESC/POS printer device is not configured".to_string());
     }
-    Ok(())
+
+    let normalized = normalize_bitmap(bitmap, width)?;
+    let print_bitmap = if rotate_print {
+        rotate_bitmap_180(&normalized, width)
+    } else {
+        normalized
+    };
+
+    let protocol = EscPosProtocol::new().with_cut(cut);
+    let mut conn = DeviceTransport::open(device).await.map_err(cat_error)?;
+    transport::send_bitmap(&mut conn, &protocol, &print_bitmap, width, FEED_LINES)
+        .await
+        .map_err(cat_error)
 }
 
 fn normalize_bitmap(bitmap: &[u8], width: u16) -> Result<Vec<u8>, String> {
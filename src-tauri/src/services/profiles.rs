@@ -0,0 +1,375 @@
+//! Viewer profile details for the chat UI.
+//!
+//! Profiles come from a chain of [`ProfileProvider`]s tried in the order set
+//! by `PROFILE_PROVIDERS` (`helix,ivr,decapi` by default); the first one that
+//! answers wins. Each provider has its own circuit breaker: after
+//! [`FAILURE_THRESHOLD`] failures in a row it is skipped for [`COOLDOWN`],
+//! then given a single trial request. Results are cached in the database for
+//! `PROFILE_CACHE_HOURS`, and a stale entry is served when every provider
+//! fails. With `PROFILE_THIRD_PARTY_ENABLED` off only Helix is asked and
+//! cached third-party entries are ignored.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use overlay_db::profiles::ViewerProfile;
+use serde::Deserialize;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::helix;
+
+pub const PROVIDER_HELIX: &str = "helix";
+pub const PROVIDER_IVR: &str = "ivr";
+pub const PROVIDER_DECAPI: &str = "decapi";
+pub const PROVIDERS: [&str; 3] = [PROVIDER_HELIX, PROVIDER_IVR, PROVIDER_DECAPI];
+
+const FAILURE_THRESHOLD: u32 = 3;
+const COOLDOWN: Duration = Duration::from_secs(300);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_CACHE_HOURS: i64 = 24;
+/// Stale entries older than this are dropped rather than served.
+const STALE_LIMIT_SECS: i64 = 30 * 24 * 3600;
+
+/// A source of viewer profile details.
+pub trait ProfileProvider {
+    fn name(&self) -> &'static str;
+
+    /// Whether lookups leave Twitch for another service.
+    fn third_party(&self) -> bool;
+
+    fn fetch(
+        &self,
+        state: &SharedState,
+        user_id: &str,
+    ) -> impl Future<Output = Result<ViewerProfile, String>> + Send;
+}
+
+/// The Twitch API, using the broadcaster's token.
+pub struct HelixProvider;
+
+impl ProfileProvider for HelixProvider {
+    fn name(&self) -> &'static str {
+        PROVIDER_HELIX
+    }
+
+    fn third_party(&self) -> bool {
+        false
+    }
+
+    async fn fetch(&self, state: &SharedState, user_id: &str) -> Result<ViewerProfile, String> {
+        let ctx = helix::context(state).await?;
+        let user = ctx
+            .api
+            .get_user_by_id(&ctx.token, user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("user not found")?;
+        Ok(ViewerProfile {
+            user_id: user.id,
+            login: user.login,
+            display_name: user.display_name,
+            avatar_url: user.profile_image_url,
+            description: user.description,
+            created_at: user.created_at,
+            ..Default::default()
+        })
+    }
+}
+
+/// api.ivr.fi, which needs no token.
+pub struct IvrProvider;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IvrUser {
+    id: String,
+    login: String,
+    #[serde(default)]
+    display_name: String,
+    #[serde(default)]
+    logo: String,
+    #[serde(default)]
+    bio: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+}
+
+impl ProfileProvider for IvrProvider {
+    fn name(&self) -> &'static str {
+        PROVIDER_IVR
+    }
+
+    fn third_party(&self) -> bool {
+        true
+    }
+
+    async fn fetch(&self, state: &SharedState, user_id: &str) -> Result<ViewerProfile, String> {
+        let users: Vec<IvrUser> = state
+            .http()
+            .get("https://api.ivr.fi/v2/twitch/user")
+            .query(&[("id", user_id)])
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+        let user = users.into_iter().next().ok_or("user not found")?;
+        Ok(ViewerProfile {
+            user_id: user.id,
+            login: user.login,
+            display_name: user.display_name,
+            avatar_url: user.logo,
+            description: user.bio.unwrap_or_default(),
+            created_at: user.created_at.unwrap_or_default(),
+            ..Default::default()
+        })
+    }
+}
+
+/// decapi.me, which answers in plain text and only knows the avatar and
+/// account creation time.
+pub struct DecApiProvider;
+
+impl DecApiProvider {
+    async fn get_text(state: &SharedState, path: &str, user_id: &str) -> Result<String, String> {
+        let text = state
+            .http()
+            .get(format!("https://decapi.me/twitch/{path}/{user_id}"))
+            .query(&[("id", "true")])
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(text.trim().to_string())
+    }
+}
+
+impl ProfileProvider for DecApiProvider {
+    fn name(&self) -> &'static str {
+        PROVIDER_DECAPI
+    }
+
+    fn third_party(&self) -> bool {
+        true
+    }
+
+    async fn fetch(&self, state: &SharedState, user_id: &str) -> Result<ViewerProfile, String> {
+        // Errors come back as 200 with a message in place of the URL.
+        let avatar_url = Self::get_text(state, "avatar", user_id).await?;
+        if !avatar_url.starts_with("https://") {
+            return Err(avatar_url);
+        }
+        let created_at = Self::get_text(state, "creation", user_id)
+            .await
+            .unwrap_or_default();
+        Ok(ViewerProfile {
+            user_id: user_id.to_string(),
+            avatar_url,
+            created_at,
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Breaker {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl Breaker {
+    /// Whether a request may go out now. Once the cooldown has passed one
+    /// trial request is let through and the breaker stays open until it
+    /// reports back.
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        match self.open_until {
+            Some(until) if now < until => false,
+            Some(_) => {
+                self.open_until = Some(now + COOLDOWN);
+                true
+            }
+            None => true,
+        }
+    }
+
+    fn record(&mut self, ok: bool, now: Instant) {
+        if ok {
+            *self = Self::default();
+        } else {
+            self.failures += 1;
+            if self.failures >= FAILURE_THRESHOLD {
+                self.open_until = Some(now + COOLDOWN);
+            }
+        }
+    }
+}
+
+static BREAKERS: LazyLock<Mutex<HashMap<&'static str, Breaker>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn with_breaker<T>(name: &'static str, f: impl FnOnce(&mut Breaker) -> T) -> T {
+    let mut breakers = BREAKERS.lock().unwrap_or_else(|e| e.into_inner());
+    f(breakers.entry(name).or_default())
+}
+
+/// Ask `provider` through its circuit breaker; `None` when it is a
+/// third-party service and those are turned off.
+async fn fetch_guarded(
+    provider: &impl ProfileProvider,
+    state: &SharedState,
+    user_id: &str,
+    third_party: bool,
+) -> Option<Result<ViewerProfile, String>> {
+    if provider.third_party() && !third_party {
+        return None;
+    }
+    let name = provider.name();
+    if !with_breaker(name, |b| b.try_acquire(Instant::now())) {
+        return Some(Err("circuit open".into()));
+    }
+    let result = provider.fetch(state, user_id).await;
+    with_breaker(name, |b| b.record(result.is_ok(), Instant::now()));
+    Some(result.map(|profile| ViewerProfile {
+        source: name.to_string(),
+        ..profile
+    }))
+}
+
+struct ProfileSettings {
+    providers: Vec<String>,
+    third_party: bool,
+    cache_secs: i64,
+}
+
+fn load_settings(state: &SharedState) -> ProfileSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let hours = get("PROFILE_CACHE_HOURS")
+        .parse()
+        .unwrap_or(DEFAULT_CACHE_HOURS);
+    ProfileSettings {
+        providers: parse_providers(&get("PROFILE_PROVIDERS")),
+        third_party: get("PROFILE_THIRD_PARTY_ENABLED") == "true",
+        cache_secs: hours * 3600,
+    }
+}
+
+/// Provider names from a comma-separated list, lowercased and without
+/// duplicates. Unknown names are kept so validation can report them.
+pub fn parse_providers(value: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in value.split(',').map(|n| n.trim().to_lowercase()) {
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Profile of `user_id`, from the cache unless it has expired or `refresh`
+/// is set.
+pub async fn lookup(
+    state: &SharedState,
+    user_id: &str,
+    refresh: bool,
+) -> Result<ViewerProfile, String> {
+    let s = load_settings(state);
+    let now = chrono::Utc::now().timestamp();
+    let db_err = |e: overlay_db::DbError| format!("Profile cache error: {e}");
+    state
+        .db()
+        .prune_viewer_profiles(now - STALE_LIMIT_SECS)
+        .map_err(db_err)?;
+    let cached = state
+        .db()
+        .get_viewer_profile(user_id)
+        .map_err(db_err)?
+        .filter(|p| s.third_party || p.source == PROVIDER_HELIX);
+    let fresh = cached
+        .as_ref()
+        .filter(|p| !refresh && p.fetched_at > now - s.cache_secs);
+    if let Some(profile) = fresh {
+        return Ok(profile.clone());
+    }
+
+    let mut errors = Vec::new();
+    for name in &s.providers {
+        let tp = s.third_party;
+        let result = match name.as_str() {
+            PROVIDER_HELIX => fetch_guarded(&HelixProvider, state, user_id, tp).await,
+            PROVIDER_IVR => fetch_guarded(&IvrProvider, state, user_id, tp).await,
+            PROVIDER_DECAPI => fetch_guarded(&DecApiProvider, state, user_id, tp).await,
+            _ => None,
+        };
+        let Some(result) = result else {
+            continue;
+        };
+        match result {
+            Ok(profile) => {
+                let profile = ViewerProfile {
+                    fetched_at: now,
+                    ..profile
+                };
+                state.db().save_viewer_profile(&profile).map_err(db_err)?;
+                return Ok(profile);
+            }
+            Err(e) => {
+                tracing::debug!(provider = %name, user_id, "Profile lookup failed: {e}");
+                errors.push(format!("{name}: {e}"));
+            }
+        }
+    }
+    match cached {
+        Some(profile) => Ok(profile),
+        None if errors.is_empty() => Err("No profile provider enabled".into()),
+        None => Err(format!("Profile lookup failed ({})", errors.join("; "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_and_half_opens() {
+        let now = Instant::now();
+        let mut b = Breaker::default();
+        for _ in 0..FAILURE_THRESHOLD {
+            assert!(b.try_acquire(now));
+            b.record(false, now);
+        }
+        assert!(!b.try_acquire(now + Duration::from_secs(1)));
+
+        // One trial after the cooldown; a failure reopens it.
+        let later = now + COOLDOWN;
+        assert!(b.try_acquire(later));
+        assert!(!b.try_acquire(later));
+        b.record(false, later);
+        assert!(!b.try_acquire(later + Duration::from_secs(1)));
+
+        let much_later = later + COOLDOWN;
+        assert!(b.try_acquire(much_later));
+        b.record(true, much_later);
+        assert!(b.try_acquire(much_later));
+        assert_eq!(b.failures, 0);
+    }
+
+    #[test]
+    fn test_parse_providers() {
+        assert_eq!(
+            parse_providers(" IVR, helix,,ivr ,decapi"),
+            vec!["ivr", "helix", "decapi"]
+        );
+        assert!(parse_providers("").is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+//! Rebuilding derived data (`POST /api/system/rebuild-projections`).
+//!
+//! Runs the overlay-db projection rebuilds one after another as a job;
+//! each step reports how many rows it corrected.
+
+use overlay_db::projections::PROJECTIONS;
+use serde_json::{Map, Value, json};
+
+use crate::app::SharedState;
+use crate::services::jobs::{self, JobInfo};
+
+pub const JOB_KIND: &str = "rebuild_projections";
+
+/// Resolve requested projection names (empty = all).
+pub fn resolve_names(requested: &[String]) -> Result<Vec<&'static str>, String> {
+    if requested.is_empty() {
+        return Ok(PROJECTIONS.to_vec());
+    }
+    requested
+        .iter()
+        .map(|name| {
+            PROJECTIONS
+                .iter()
+                .copied()
+                .find(|p| *p == name.as_str())
+                .ok_or_else(|| format!("Unknown projection: {name}"))
+        })
+        .collect()
+}
+
+/// Start the rebuild job.
+pub async fn start(state: &SharedState, names: Vec<&'static str>) -> Result<JobInfo, String> {
+    let db = state.db().clone();
+    jobs::spawn(state, JOB_KIND, move |job| async move {
+        let total = names.len() as u64;
+        let mut corrected = Map::new();
+        for (i, name) in names.into_iter().enumerate() {
+            job.progress(i as u64, total, format!("Rebuilding {name}"))
+                .await;
+            let db = db.clone();
+            let fixed = tokio::task::spawn_blocking(move || db.rebuild_projection(name))
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| format!("{name}: {e}"))?;
+            tracing::info!(projection = name, fixed, "Projection rebuilt");
+            corrected.insert(name.to_string(), json!(fixed));
+        }
+        job.progress(total, total, "Done").await;
+        Ok(json!({ "corrected": Value::Object(corrected) }))
+    })
+    .await
+}
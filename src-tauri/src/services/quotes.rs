@@ -0,0 +1,203 @@
+//! Quote database chat commands.
+//!
+//! `!quote` / `!quote random` replies with a random quote, `!quote <id>`
+//! with a specific one and `!quote add <text>` stores a new quote (mods and
+//! the broadcaster only unless `QUOTES_ADD_MOD_ONLY` is off). Quote of the
+//! day printing happens at stream start.
+
+use overlay_db::quotes::Quote;
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::str_field;
+use crate::services::print_queue::PrintCategory;
+use crate::services::print_templates::{self, Template};
+use crate::services::{print_render, twitch_chat};
+
+pub const COMMAND: &str = "!quote";
+const MAX_QUOTE_LEN: usize = 400;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteCommand {
+    Random,
+    Get(i64),
+    Add(String),
+}
+
+struct QuoteSettings {
+    enabled: bool,
+    add_mod_only: bool,
+    print_daily: bool,
+}
+
+fn load_settings(state: &SharedState) -> QuoteSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    QuoteSettings {
+        enabled: get("QUOTES_ENABLED") == "true",
+        add_mod_only: get("QUOTES_ADD_MOD_ONLY") != "false",
+        print_daily: get("QUOTE_OF_THE_DAY_PRINT") == "true",
+    }
+}
+
+/// Parse a `!quote` command. Returns `None` for other messages.
+pub fn parse_command(text: &str) -> Option<QuoteCommand> {
+    let mut parts = text.trim().splitn(2, char::is_whitespace);
+    if !parts.next()?.eq_ignore_ascii_case(COMMAND) {
+        return None;
+    }
+    let rest = parts.next().unwrap_or_default().trim();
+    let (sub, arg) = rest
+        .split_once(char::is_whitespace)
+        .map_or((rest, ""), |(s, a)| (s, a.trim()));
+    match sub.to_lowercase().as_str() {
+        "" | "random" => Some(QuoteCommand::Random),
+        "add" if !arg.is_empty() => Some(QuoteCommand::Add(arg.to_string())),
+        other => other
+            .trim_start_matches('#')
+            .parse()
+            .ok()
+            .map(QuoteCommand::Get),
+    }
+}
+
+/// Whether the chatter has a broadcaster or moderator badge.
+fn is_moderator(badges: &Value) -> bool {
+    badges.as_array().is_some_and(|badges| {
+        badges
+            .iter()
+            .any(|b| matches!(b["set_id"].as_str(), Some("broadcaster" | "moderator")))
+    })
+}
+
+/// Chat line for a quote, e.g. `#12: "It's fine." — streamer (2026-10-16)`.
+pub fn format_quote(quote: &Quote) -> String {
+    let date = chrono::DateTime::from_timestamp(quote.created_at, 0)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let mut line = format!("#{}: \"{}\"", quote.id, quote.text);
+    if !quote.author.is_empty() {
+        line.push_str(&format!(" — {}", quote.author));
+    }
+    if !date.is_empty() {
+        line.push_str(&format!(" ({date})"));
+    }
+    line
+}
+
+/// Handle a chat message if it is a `!quote` command (replies in the
+/// background).
+pub fn handle_chat_message(state: &SharedState, payload: &Value) {
+    let Some(command) = parse_command(&str_field(payload, &["message", "text"])) else {
+        return;
+    };
+    let settings = load_settings(state);
+    if !settings.enabled {
+        return;
+    }
+    if matches!(command, QuoteCommand::Add(_))
+        && settings.add_mod_only
+        && !is_moderator(&payload["badges"])
+    {
+        return;
+    }
+
+    let s = state.clone();
+    let login = str_field(payload, &["chatter_user_login"]);
+    tokio::spawn(async move {
+        let Some(reply) = run_command(&s, command, &login) else {
+            return;
+        };
+        if let Err(e) = twitch_chat::send_chat(&s, &reply).await {
+            tracing::warn!("Failed to send quote reply: {e}");
+        }
+    });
+}
+
+/// Execute a command and return the chat reply.
+fn run_command(state: &SharedState, command: QuoteCommand, login: &str) -> Option<String> {
+    let result = match command {
+        QuoteCommand::Random => state
+            .db()
+            .get_random_quote()
+            .map(|q| q.map_or_else(|| "No quotes yet.".to_string(), |q| format_quote(&q))),
+        QuoteCommand::Get(id) => state
+            .db()
+            .get_quote(id)
+            .map(|q| q.map_or_else(|| format!("Quote #{id} not found."), |q| format_quote(&q))),
+        QuoteCommand::Add(text) => {
+            let text: String = text.chars().take(MAX_QUOTE_LEN).collect();
+            state
+                .db()
+                .add_quote(&text, "", login, chrono::Utc::now().timestamp())
+                .map(|q| format!("Added quote #{}.", q.id))
+        }
+    };
+    result
+        .map_err(|e| tracing::warn!("Quote command failed: {e}"))
+        .ok()
+}
+
+/// Print a random quote at stream start when enabled.
+pub async fn on_stream_online(state: &SharedState) {
+    if !load_settings(state).print_daily {
+        return;
+    }
+    let quote = match state.db().get_random_quote() {
+        Ok(Some(q)) => q,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Failed to load quote of the day: {e}");
+            return;
+        }
+    };
+    if let Err(e) = print_quote(state, &quote).await {
+        tracing::warn!("Failed to print quote of the day: {e}");
+    }
+}
+
+/// Print a quote as a titled card.
+pub async fn print_quote(state: &SharedState, quote: &Quote) -> Result<(), String> {
+    let title = print_templates::text(print_templates::locale(state), Template::QuoteTitle);
+    print_render::print_titled(
+        state,
+        title,
+        &format!("#{} {}", quote.id, quote.author),
+        &quote.text,
+        PrintCategory::Other,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("!quote"), Some(QuoteCommand::Random));
+        assert_eq!(parse_command("!Quote random"), Some(QuoteCommand::Random));
+        assert_eq!(parse_command("!quote #12"), Some(QuoteCommand::Get(12)));
+        assert_eq!(
+            parse_command("!quote add  It's fine. "),
+            Some(QuoteCommand::Add("It's fine.".into()))
+        );
+        assert_eq!(parse_command("!quote add"), None);
+        assert_eq!(parse_command("!quotes"), None);
+        assert_eq!(parse_command("hello !quote"), None);
+    }
+
+    #[test]
+    fn test_is_moderator() {
+        assert!(is_moderator(&json!([{ "set_id": "moderator" }])));
+        assert!(is_moderator(&json!([{ "set_id": "broadcaster" }])));
+        assert!(!is_moderator(&json!([{ "set_id": "subscriber" }])));
+        assert!(!is_moderator(&Value::Null));
+    }
+}
@@ -0,0 +1,200 @@
+//! Reward count widget feed and goal celebrations.
+//!
+//! Overlays read the feed from `GET /api/reward/counts/stream` and get a
+//! `reward_count_feed` message whenever a count changes (the dashboard keeps
+//! its `reward_counts` message). A reward may have a goal; when a
+//! redemption brings its count to the goal a `reward_count_goal_reached`
+//! message is sent and the goal's overlay effect, if any, is fired.
+
+use overlay_db::rewards::{RewardCount, RewardCountGoal};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::app::SharedState;
+use crate::eventsub_support::send_ws;
+use crate::services::overlay_effects;
+
+/// One reward as shown by the widget.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RewardCountItem {
+    pub reward_id: String,
+    pub display_name: String,
+    pub count: i32,
+    pub goal: Option<i32>,
+    pub label: String,
+    /// `count / goal`, capped at 1; 0 without a goal.
+    pub progress: f64,
+    pub reached: bool,
+}
+
+fn progress(count: i32, goal: i32) -> f64 {
+    if goal > 0 {
+        (count as f64 / goal as f64).min(1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Whether going from `before` to `after` redemptions crosses `goal`.
+pub fn goal_crossed(before: i32, after: i32, goal: i32) -> bool {
+    goal > 0 && before < goal && after >= goal
+}
+
+/// Merge counts with goals. Rewards with a goal but no redemptions yet are
+/// listed with a count of 0.
+fn build_feed(counts: &[RewardCount], goals: &[RewardCountGoal]) -> Vec<RewardCountItem> {
+    let mut items: Vec<RewardCountItem> = counts
+        .iter()
+        .map(|c| {
+            let goal = goals.iter().find(|g| g.reward_id == c.reward_id);
+            RewardCountItem {
+                reward_id: c.reward_id.clone(),
+                display_name: c.display_name.clone(),
+                count: c.count,
+                goal: goal.map(|g| g.goal),
+                label: goal.map(|g| g.label.clone()).unwrap_or_default(),
+                progress: goal.map_or(0.0, |g| progress(c.count, g.goal)),
+                reached: goal.is_some_and(|g| g.goal > 0 && c.count >= g.goal),
+            }
+        })
+        .collect();
+    for goal in goals {
+        if !counts.iter().any(|c| c.reward_id == goal.reward_id) {
+            items.push(RewardCountItem {
+                reward_id: goal.reward_id.clone(),
+                display_name: String::new(),
+                count: 0,
+                goal: Some(goal.goal),
+                label: goal.label.clone(),
+                progress: 0.0,
+                reached: false,
+            });
+        }
+    }
+    items
+}
+
+pub fn feed(state: &SharedState) -> Result<Vec<RewardCountItem>, String> {
+    let counts = state
+        .db()
+        .get_all_reward_counts()
+        .map_err(|e| e.to_string())?;
+    let goals = state
+        .db()
+        .get_reward_count_goals()
+        .map_err(|e| e.to_string())?;
+    Ok(build_feed(&counts, &goals))
+}
+
+/// Push the current counts to the dashboard and the widget.
+pub fn broadcast(state: &SharedState) {
+    let counts = state.db().get_all_reward_counts().unwrap_or_default();
+    send_ws(state, "reward_counts", json!(counts));
+    match feed(state) {
+        Ok(items) => send_ws(state, "reward_count_feed", json!(items)),
+        Err(e) => tracing::warn!("Failed to build reward count feed: {e}"),
+    }
+}
+
+/// Count a redemption, push the update and celebrate a reached goal.
+pub fn on_redemption(state: &SharedState, reward_id: &str, user_name: &str) {
+    let db = state.db();
+    let before = db
+        .get_reward_count(reward_id)
+        .ok()
+        .flatten()
+        .map_or(0, |c| c.count);
+    if let Err(e) = db.increment_reward_count(reward_id, user_name) {
+        tracing::warn!("Failed to increment reward count: {e}");
+        return;
+    }
+    broadcast(state);
+
+    let Ok(Some(goal)) = db.get_reward_count_goal(reward_id) else {
+        return;
+    };
+    let Some(count) = db.get_reward_count(reward_id).ok().flatten() else {
+        return;
+    };
+    if !goal_crossed(before, count.count, goal.goal) {
+        return;
+    }
+    tracing::info!(reward_id, goal = goal.goal, "Reward count goal reached");
+    send_ws(
+        state,
+        "reward_count_goal_reached",
+        json!({
+            "reward_id": reward_id,
+            "display_name": count.display_name,
+            "count": count.count,
+            "goal": goal.goal,
+            "label": goal.label,
+            "user_name": user_name,
+        }),
+    );
+    if let Some(request) = &goal.effect {
+        match overlay_effects::resolve(state, request) {
+            Ok(effect) => {
+                overlay_effects::trigger(
+                    state,
+                    &effect,
+                    json!({ "type": "reward_count_goal", "reward_id": reward_id }),
+                );
+            }
+            Err(e) => tracing::warn!(reward_id, "Invalid reward goal effect: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(reward_id: &str, count: i32) -> RewardCount {
+        RewardCount {
+            reward_id: reward_id.into(),
+            count,
+            user_names: vec![],
+            display_name: reward_id.to_uppercase(),
+            last_reset_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn goal(reward_id: &str, goal: i32) -> RewardCountGoal {
+        RewardCountGoal {
+            reward_id: reward_id.into(),
+            goal,
+            label: "hydrate".into(),
+            effect: None,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_goal_crossed() {
+        assert!(goal_crossed(9, 10, 10));
+        assert!(!goal_crossed(10, 11, 10));
+        assert!(!goal_crossed(3, 4, 10));
+        assert!(!goal_crossed(0, 1, 0));
+    }
+
+    #[test]
+    fn test_build_feed() {
+        let feed = build_feed(
+            &[count("a", 5), count("b", 12)],
+            &[goal("a", 10), goal("b", 10), goal("c", 3)],
+        );
+        assert_eq!(feed.len(), 3);
+        assert_eq!(feed[0].progress, 0.5);
+        assert!(!feed[0].reached);
+        assert_eq!(feed[1].progress, 1.0);
+        assert!(feed[1].reached);
+        assert_eq!(feed[2].reward_id, "c");
+        assert_eq!(feed[2].count, 0);
+
+        let no_goal = build_feed(&[count("a", 5)], &[]);
+        assert_eq!(no_goal[0].goal, None);
+        assert_eq!(no_goal[0].progress, 0.0);
+    }
+}
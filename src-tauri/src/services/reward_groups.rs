@@ -0,0 +1,320 @@
+//! Switching reward groups on Twitch.
+//!
+//! A group's flag is stored locally and every reward in it is enabled or
+//! disabled through Helix. Rewards that fail are reported but do not stop
+//! the others. Groups can also be bound to stream start/end so they flip
+//! automatically; each switch then runs as a `reward_group:<id>` job.
+//!
+//! A group's policy (`RewardGroupPolicy`) instead states when it should be
+//! on — only while live, only while offline, within time windows — and
+//! [`apply_policies`] switches groups that are not, at stream start/end and
+//! on every stream status check. Toggling such a group by hand only lasts
+//! until the next check.
+
+use chrono::{Datelike, Timelike};
+use overlay_db::rewards::{
+    POLICY_LIVE, POLICY_MANUAL, POLICY_OFFLINE, PolicyWindow, RewardGroupPolicy,
+    TRIGGER_STREAM_OFFLINE, TRIGGER_STREAM_ONLINE,
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::app::SharedState;
+use crate::services::helix;
+use crate::services::jobs::{self, JobHandle, JobInfo};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RewardFailure {
+    pub reward_id: String,
+    pub error: String,
+}
+
+/// Outcome of switching one group.
+#[derive(Debug, Clone, Serialize)]
+pub struct SwitchReport {
+    pub group_id: i64,
+    pub enabled: bool,
+    pub updated: Vec<String>,
+    pub failed: Vec<RewardFailure>,
+}
+
+impl SwitchReport {
+    fn error_summary(&self) -> String {
+        let failures: Vec<String> = self
+            .failed
+            .iter()
+            .map(|f| format!("{}: {}", f.reward_id, f.error))
+            .collect();
+        format!(
+            "{} of {} rewards failed ({})",
+            self.failed.len(),
+            self.failed.len() + self.updated.len(),
+            failures.join(", ")
+        )
+    }
+}
+
+/// Switch every reward of a group, reporting progress to `job` when given.
+pub async fn switch(
+    state: &SharedState,
+    group_id: i64,
+    enabled: bool,
+    job: Option<&JobHandle>,
+) -> Result<SwitchReport, String> {
+    let db = state.db();
+    db.get_reward_group(group_id)
+        .map_err(|e| format!("Reward group {group_id}: {e}"))?;
+    let reward_ids = db.get_group_rewards(group_id).map_err(|e| e.to_string())?;
+    db.update_reward_group_enabled(group_id, enabled)
+        .map_err(|e| e.to_string())?;
+    let mut report = SwitchReport {
+        group_id,
+        enabled,
+        updated: Vec::new(),
+        failed: Vec::new(),
+    };
+    if reward_ids.is_empty() {
+        return Ok(report);
+    }
+
+    let ctx = helix::context(state).await?;
+    let total = reward_ids.len() as u64;
+    for (i, reward_id) in reward_ids.iter().enumerate() {
+        if let Some(job) = job {
+            job.progress(i as u64, total, reward_id.as_str()).await;
+        }
+        match ctx
+            .api
+            .update_reward_enabled(&ctx.token, &ctx.broadcaster_id, reward_id, enabled)
+            .await
+        {
+            Ok(_) => {
+                if let Err(e) = db.set_reward_enabled(reward_id, enabled) {
+                    tracing::warn!(reward_id, "Failed to store reward state: {e}");
+                }
+                report.updated.push(reward_id.clone());
+            }
+            Err(e) => report.failed.push(RewardFailure {
+                reward_id: reward_id.clone(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    if let Some(job) = job {
+        job.progress(total, total, "Done").await;
+    }
+    tracing::info!(
+        group_id,
+        enabled,
+        updated = report.updated.len(),
+        failed = report.failed.len(),
+        "Reward group switched"
+    );
+    Ok(report)
+}
+
+/// Enable or disable every reward of a group. Returns the number of
+/// rewards updated on Twitch; any failed reward makes it an error.
+pub async fn set_enabled(
+    state: &SharedState,
+    group_id: i64,
+    enabled: bool,
+) -> Result<usize, String> {
+    let report = switch(state, group_id, enabled, None).await?;
+    if report.failed.is_empty() {
+        Ok(report.updated.len())
+    } else {
+        Err(report.error_summary())
+    }
+}
+
+/// Switch a group as a background job. Partial failures complete the job
+/// with the failed rewards listed in its result.
+pub async fn spawn_switch(
+    state: &SharedState,
+    group_id: i64,
+    enabled: bool,
+) -> Result<JobInfo, String> {
+    let s = state.clone();
+    jobs::spawn(
+        state,
+        &format!("reward_group:{group_id}"),
+        move |job| async move {
+            let report = switch(&s, group_id, enabled, Some(&job)).await?;
+            Ok(json!(report))
+        },
+    )
+    .await
+}
+
+async fn run_schedules(state: &SharedState, trigger: &str) {
+    let schedules = match state.db().get_scheduled_reward_groups(trigger) {
+        Ok(schedules) => schedules,
+        Err(e) => {
+            tracing::warn!(trigger, "Failed to load reward group schedules: {e}");
+            return;
+        }
+    };
+    for schedule in schedules {
+        if let Err(e) = spawn_switch(state, schedule.group_id, schedule.enabled).await {
+            tracing::warn!(
+                group_id = schedule.group_id,
+                trigger,
+                "Scheduled reward group switch failed: {e}"
+            );
+        }
+    }
+}
+
+/// Apply the groups bound to stream start.
+pub async fn on_stream_online(state: &SharedState) {
+    run_schedules(state, TRIGGER_STREAM_ONLINE).await;
+    apply_policies(state, true).await;
+}
+
+/// Apply the groups bound to stream end.
+pub async fn on_stream_offline(state: &SharedState) {
+    run_schedules(state, TRIGGER_STREAM_OFFLINE).await;
+    apply_policies(state, false).await;
+}
+
+/// Minutes after midnight of an `HH:MM` time.
+fn parse_time(value: &str) -> Option<u32> {
+    let (h, m) = value.trim().split_once(':')?;
+    let (h, m): (u32, u32) = (h.parse().ok()?, m.parse().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+pub fn validate_policy(policy: &RewardGroupPolicy) -> Result<(), String> {
+    if ![POLICY_MANUAL, POLICY_LIVE, POLICY_OFFLINE].contains(&policy.mode.as_str()) {
+        return Err("mode must be 'manual', 'live' or 'offline'".into());
+    }
+    for window in &policy.windows {
+        if window.days.iter().any(|d| *d > 6) {
+            return Err("days must be 0 (Sunday) to 6 (Saturday)".into());
+        }
+        if parse_time(&window.start).is_none() || parse_time(&window.end).is_none() {
+            return Err("start and end must be HH:MM".into());
+        }
+    }
+    Ok(())
+}
+
+/// Whether `window` covers `minute` of `weekday` (0 = Sunday).
+fn in_window(window: &PolicyWindow, weekday: u32, minute: u32) -> bool {
+    let (Some(start), Some(end)) = (parse_time(&window.start), parse_time(&window.end)) else {
+        return false;
+    };
+    let starts_on = |day: u32| window.days.is_empty() || window.days.contains(&day);
+    if start <= end {
+        starts_on(weekday) && (start..end).contains(&minute)
+    } else {
+        // Past midnight: the evening part, or the morning after a start day.
+        (starts_on(weekday) && minute >= start) || (starts_on((weekday + 6) % 7) && minute < end)
+    }
+}
+
+/// Whether `policy` wants its group on; `None` when it leaves the group
+/// alone.
+pub fn desired_state(
+    policy: &RewardGroupPolicy,
+    live: bool,
+    weekday: u32,
+    minute: u32,
+) -> Option<bool> {
+    if !policy.is_automatic() {
+        return None;
+    }
+    let by_stream = match policy.mode.as_str() {
+        POLICY_LIVE => live,
+        POLICY_OFFLINE => !live,
+        _ => true,
+    };
+    let by_time =
+        policy.windows.is_empty() || policy.windows.iter().any(|w| in_window(w, weekday, minute));
+    Some(by_stream && by_time)
+}
+
+/// Switch every group whose policy wants it the other way.
+pub async fn apply_policies(state: &SharedState, live: bool) {
+    let groups = match state.db().get_automatic_reward_groups() {
+        Ok(groups) => groups,
+        Err(e) => {
+            tracing::warn!("Failed to load reward group policies: {e}");
+            return;
+        }
+    };
+    let now = chrono::Local::now();
+    let weekday = now.weekday().num_days_from_sunday();
+    let minute = now.hour() * 60 + now.minute();
+    for (group, policy) in groups {
+        let Some(enabled) = desired_state(&policy, live, weekday, minute) else {
+            continue;
+        };
+        if enabled == group.is_enabled {
+            continue;
+        }
+        match switch(state, group.id, enabled, None).await {
+            Ok(report) if !report.failed.is_empty() => tracing::warn!(
+                group_id = group.id,
+                "Reward group policy switch incomplete: {}",
+                report.error_summary()
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(
+                group_id = group.id,
+                "Reward group policy switch failed: {e}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(mode: &str, windows: &[(&[u32], &str, &str)]) -> RewardGroupPolicy {
+        RewardGroupPolicy {
+            mode: mode.to_string(),
+            windows: windows
+                .iter()
+                .map(|(days, start, end)| PolicyWindow {
+                    days: days.to_vec(),
+                    start: start.to_string(),
+                    end: end.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_desired_state() {
+        let at = |h: u32, m: u32| h * 60 + m;
+        let (manual, live) = (policy(POLICY_MANUAL, &[]), policy(POLICY_LIVE, &[]));
+        assert_eq!(desired_state(&manual, true, 1, 0), None);
+        assert_eq!(desired_state(&live, true, 1, 0), Some(true));
+        assert_eq!(desired_state(&live, false, 1, 0), Some(false));
+        let offline = policy(POLICY_OFFLINE, &[]);
+        assert_eq!(desired_state(&offline, false, 1, 0), Some(true));
+
+        // Friday night until 02:00 Saturday.
+        let night = policy(POLICY_MANUAL, &[(&[5], "20:00", "02:00")]);
+        assert_eq!(desired_state(&night, false, 5, at(21, 0)), Some(true));
+        assert_eq!(desired_state(&night, false, 6, at(1, 59)), Some(true));
+        assert_eq!(desired_state(&night, false, 6, at(2, 0)), Some(false));
+        assert_eq!(desired_state(&night, false, 5, at(1, 0)), Some(false));
+
+        let evenings = policy(POLICY_LIVE, &[(&[], "18:00", "23:00")]);
+        assert_eq!(desired_state(&evenings, true, 3, at(19, 0)), Some(true));
+        assert_eq!(desired_state(&evenings, false, 3, at(19, 0)), Some(false));
+        assert_eq!(desired_state(&evenings, true, 3, at(12, 0)), Some(false));
+    }
+
+    #[test]
+    fn test_validate_policy() {
+        assert!(validate_policy(&policy(POLICY_LIVE, &[(&[0, 6], "09:00", "17:30")])).is_ok());
+        assert!(validate_policy(&policy("always", &[])).is_err());
+        assert!(validate_policy(&policy(POLICY_LIVE, &[(&[7], "09:00", "17:00")])).is_err());
+        assert!(validate_policy(&policy(POLICY_LIVE, &[(&[], "24:00", "17:00")])).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! Stream rundown: a checklist per stream day.
+//!
+//! The day is the current date in `TIMEZONE`. Every change is broadcast as
+//! a `rundown_updated` WebSocket message carrying the full list so control
+//! widgets can simply replace what they show. With
+//! `RUNDOWN_PRINT_ON_STREAM_START` on, today's rundown is printed when the
+//! stream goes online.
+
+use overlay_db::rundowns::RundownItem;
+use serde_json::json;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::eventsub_support::send_ws;
+use crate::services::print_queue::PrintCategory;
+use crate::services::print_templates::{self, Template};
+use crate::services::{print_render, scheduler};
+
+/// Today's stream day in the configured zone.
+pub fn today(state: &SharedState) -> String {
+    chrono::Utc::now()
+        .with_timezone(&scheduler::timezone(state))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Whether `date` is a `YYYY-MM-DD` date.
+pub fn is_valid_date(date: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+}
+
+/// Send the day's list to control widgets.
+pub fn broadcast(state: &SharedState, date: &str) {
+    match state.db().get_rundown(date) {
+        Ok(items) => send_ws(
+            state,
+            "rundown_updated",
+            json!({ "date": date, "items": items }),
+        ),
+        Err(e) => tracing::warn!("Failed to load rundown for broadcast: {e}"),
+    }
+}
+
+/// Checklist text for printing.
+pub fn format_for_print(items: &[RundownItem]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            let mark = if item.checked { "☑" } else { "☐" };
+            format!("{mark} {}", item.text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Print the rundown of `date`. Fails when it is empty.
+pub async fn print(state: &SharedState, date: &str) -> Result<(), String> {
+    let items = state.db().get_rundown(date).map_err(|e| e.to_string())?;
+    if items.is_empty() {
+        return Err(format!("No rundown for {date}"));
+    }
+    let locale = print_templates::locale(state);
+    let date_label = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_or_else(|_| date.to_string(), |d| locale.format_date(d));
+    print_render::print_titled(
+        state,
+        print_templates::text(locale, Template::RundownTitle),
+        &date_label,
+        &format_for_print(&items),
+        PrintCategory::Other,
+    )
+    .await
+}
+
+/// Print today's rundown at stream start when enabled.
+pub async fn on_stream_online(state: &SharedState) {
+    let enabled = SettingsManager::new(state.db().clone())
+        .get_setting("RUNDOWN_PRINT_ON_STREAM_START")
+        .unwrap_or_default()
+        == "true";
+    if !enabled {
+        return;
+    }
+    let date = today(state);
+    if let Err(e) = print(state, &date).await {
+        tracing::info!("Rundown not printed: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(text: &str, checked: bool) -> RundownItem {
+        RundownItem {
+            id: 0,
+            stream_date: "2026-10-16".into(),
+            position: 0,
+            text: text.into(),
+            checked,
+            checked_at: None,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_format_for_print() {
+        let items = [item("Intro", true), item("Game", false)];
+        assert_eq!(format_for_print(&items), "☑ Intro\n☐ Game");
+        assert!(is_valid_date("2026-10-16"));
+        assert!(!is_valid_date("16/10/2026"));
+    }
+}
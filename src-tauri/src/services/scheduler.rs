@@ -0,0 +1,168 @@
+//! Cron-scheduled print jobs evaluated in the configured `TIMEZONE`.
+//!
+//! Each schedule has a cron expression ([`CronExpr`]) from settings; the
+//! loop sleeps until the nearest run, re-reading settings at least every
+//! minute so changes to the time zone or expressions apply without a
+//! restart. Started with the printer subsystem.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::cron::{self, CronExpr};
+use crate::services::print_queue::PrintCategory;
+use crate::services::{print_render, print_templates, print_vote};
+
+const FALLBACK_TIMEZONE: Tz = chrono_tz::Asia::Tokyo;
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+pub const SCHEDULE_CLOCK: &str = "clock";
+pub const SCHEDULE_PRINT_VOTE: &str = "print_vote";
+
+/// Schedules: `(id, name, enabled setting, cron setting)`.
+const SCHEDULES: &[(&str, &str, &str, &str)] = &[
+    (SCHEDULE_CLOCK, "時計印刷", "CLOCK_ENABLED", "CLOCK_CRON"),
+    (
+        SCHEDULE_PRINT_VOTE,
+        "投票メッセージ印刷",
+        "PRINT_VOTE_ENABLED",
+        "PRINT_VOTE_CRON",
+    ),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub cron: String,
+    pub enabled: bool,
+    /// Next run in the configured zone (RFC 3339 with offset).
+    pub next_run: Option<String>,
+    pub error: Option<String>,
+}
+
+struct Schedule {
+    info: ScheduleInfo,
+    expr: Option<CronExpr>,
+}
+
+/// The configured zone, or Asia/Tokyo when unset or unknown.
+pub fn timezone(state: &SharedState) -> Tz {
+    let name = SettingsManager::new(state.db().clone())
+        .get_setting("TIMEZONE")
+        .unwrap_or_default();
+    if name.is_empty() {
+        return FALLBACK_TIMEZONE;
+    }
+    cron::parse_timezone(&name).unwrap_or_else(|e| {
+        tracing::warn!("{e}; using {FALLBACK_TIMEZONE}");
+        FALLBACK_TIMEZONE
+    })
+}
+
+fn load(state: &SharedState, now: &DateTime<Tz>) -> Vec<Schedule> {
+    let sm = SettingsManager::new(state.db().clone());
+    SCHEDULES
+        .iter()
+        .map(|&(id, name, enabled_key, cron_key)| {
+            let cron = sm.get_setting(cron_key).unwrap_or_default();
+            let enabled = sm.get_setting(enabled_key).unwrap_or_default() == "true";
+            let (expr, error) = match CronExpr::parse(&cron) {
+                Ok(expr) => (Some(expr), None),
+                Err(e) => (None, Some(e)),
+            };
+            let next_run = expr
+                .as_ref()
+                .filter(|_| enabled)
+                .and_then(|e| e.next_after(now))
+                .map(|t| t.to_rfc3339());
+            Schedule {
+                info: ScheduleInfo {
+                    id,
+                    name,
+                    cron,
+                    enabled,
+                    next_run,
+                    error,
+                },
+                expr,
+            }
+        })
+        .collect()
+}
+
+/// Every schedule with its next run.
+pub fn list(state: &SharedState) -> (Tz, Vec<ScheduleInfo>) {
+    let tz = timezone(state);
+    let now = Utc::now().with_timezone(&tz);
+    let schedules = load(state, &now).into_iter().map(|s| s.info).collect();
+    (tz, schedules)
+}
+
+/// Run due schedules until the process exits.
+pub async fn run(state: SharedState) {
+    // Next run per schedule, keyed with the zone and expression it was
+    // computed from so a settings change recomputes it.
+    let mut pending: HashMap<&'static str, (Tz, String, DateTime<Tz>)> = HashMap::new();
+    loop {
+        let tz = timezone(&state);
+        let now = Utc::now().with_timezone(&tz);
+        let mut wait = MAX_SLEEP;
+
+        for schedule in load(&state, &now) {
+            let id = schedule.info.id;
+            let (true, Some(expr)) = (schedule.info.enabled, schedule.expr) else {
+                pending.remove(id);
+                continue;
+            };
+            let next = match pending.get(id) {
+                Some((ptz, cron, next)) if *ptz == tz && *cron == schedule.info.cron => *next,
+                _ => match expr.next_after(&now) {
+                    Some(next) => next,
+                    None => continue,
+                },
+            };
+            let next = if next <= now {
+                run_job(&state, id, &now).await;
+                match expr.next_after(&now) {
+                    Some(next) => next,
+                    None => continue,
+                }
+            } else {
+                next
+            };
+            pending.insert(id, (tz, schedule.info.cron, next));
+            if let Ok(until) = (next - now).to_std() {
+                wait = wait.min(until);
+            }
+        }
+        tokio::time::sleep(wait.max(Duration::from_millis(200))).await;
+    }
+}
+
+async fn run_job(state: &SharedState, id: &str, now: &DateTime<Tz>) {
+    tracing::info!(schedule = id, at = %now.to_rfc3339(), "Running schedule");
+    let result = match id {
+        SCHEDULE_CLOCK => print_clock(state, now).await,
+        SCHEDULE_PRINT_VOTE => print_vote::print_winner(state).await.map(|_| ()),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        tracing::warn!(schedule = id, "Scheduled job failed: {e}");
+    }
+}
+
+/// Print the wall-clock time of `now` in the print locale's format.
+pub async fn print_clock(state: &SharedState, now: &DateTime<Tz>) -> Result<(), String> {
+    let locale = print_templates::locale(state);
+    let text = locale.format_time(now.naive_local().time());
+    let font_data = print_render::load_font(state)?;
+    let font = ab_glyph::FontRef::try_from_slice(&font_data).map_err(|_| "Invalid font data")?;
+    let img = image_processor::clock::generate_time_image_simple(&text, &font);
+    print_render::enqueue_image(state, &img, &format!("Clock {text}"), PrintCategory::Clock).await
+}
@@ -0,0 +1,339 @@
+//! Global search across chat, archived events, lottery draws and logs.
+//!
+//! Each source is queried on its own (case-insensitive substring match) and
+//! the hits are merged newest first. Every result carries a `link` the
+//! control panel opens when it is clicked:
+//!
+//! | type      | link                                   |
+//! |-----------|----------------------------------------|
+//! | `chat`    | `/chat?message=<message_id>`           |
+//! | `event`   | `/events?id=<archive id>`              |
+//! | `lottery` | `/present?draw=<draw id>`              |
+//! | `log`     | `/settings?tab=logs&at=<unix seconds>` |
+//!
+//! Chat goes through its full-text index (see
+//! `overlay_db::Database::search_chat_messages`); a failing source is
+//! reported in `errors` without hiding the others.
+
+use overlay_db::chat::ChatMessage;
+use overlay_db::event_archive::ArchivedEvent;
+use overlay_db::lottery::LotteryDraw;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::services::log_buffer::{self, LogEntry};
+
+pub const MIN_QUERY_CHARS: usize = 2;
+pub const DEFAULT_LIMIT: usize = 20;
+pub const MAX_LIMIT: usize = 100;
+const SNIPPET_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultType {
+    Chat,
+    Event,
+    Lottery,
+    Log,
+}
+
+impl ResultType {
+    pub const ALL: [Self; 4] = [Self::Chat, Self::Event, Self::Lottery, Self::Log];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::Event => "event",
+            Self::Lottery => "lottery",
+            Self::Log => "log",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchResult {
+    #[serde(rename = "type")]
+    pub kind: ResultType,
+    pub id: String,
+    pub title: String,
+    pub snippet: String,
+    /// Unix seconds.
+    pub timestamp: i64,
+    pub link: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceError {
+    #[serde(rename = "type")]
+    pub kind: ResultType,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub results: Vec<SearchResult>,
+    pub errors: Vec<SourceError>,
+}
+
+/// Parse a comma-separated `types` filter; empty means every source.
+pub fn parse_types(types: Option<&str>) -> Result<Vec<ResultType>, String> {
+    let Some(types) = types.map(str::trim).filter(|t| !t.is_empty()) else {
+        return Ok(ResultType::ALL.to_vec());
+    };
+    let mut parsed = Vec::new();
+    for name in types.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let kind = ResultType::parse(name).ok_or_else(|| format!("Unknown type: {name}"))?;
+        if !parsed.contains(&kind) {
+            parsed.push(kind);
+        }
+    }
+    Ok(parsed)
+}
+
+/// Up to `SNIPPET_CHARS` characters of `text` around the first match.
+fn snippet(text: &str, query: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= SNIPPET_CHARS {
+        return text;
+    }
+    let lower = text.to_lowercase();
+    let hit = lower
+        .find(&query.to_lowercase())
+        .filter(|_| lower.len() == text.len())
+        .map_or(0, |byte| text[..byte].chars().count());
+    let start = hit
+        .saturating_sub(SNIPPET_CHARS / 3)
+        .min(chars.len() - SNIPPET_CHARS);
+    let end = start + SNIPPET_CHARS;
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.extend(&chars[start..end]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+fn contains_ignore_case(haystack: &str, needle_lower: &str) -> bool {
+    haystack.to_lowercase().contains(needle_lower)
+}
+
+fn chat_result(msg: &ChatMessage, query: &str) -> SearchResult {
+    SearchResult {
+        kind: ResultType::Chat,
+        id: msg.message_id.clone(),
+        title: msg.username.clone(),
+        snippet: snippet(&msg.message, query),
+        timestamp: msg.created_at,
+        link: format!("/chat?message={}", msg.message_id),
+    }
+}
+
+/// String values of an event payload, in document order.
+fn payload_text(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => out.push(s.clone()),
+        Value::Number(n) => out.push(n.to_string()),
+        Value::Array(items) => items.iter().for_each(|v| payload_text(v, out)),
+        Value::Object(map) => map.values().for_each(|v| payload_text(v, out)),
+        _ => {}
+    }
+}
+
+fn event_result(event: &ArchivedEvent, query: &str) -> SearchResult {
+    let mut parts = Vec::new();
+    payload_text(&event.payload, &mut parts);
+    SearchResult {
+        kind: ResultType::Event,
+        id: event.id.to_string(),
+        title: event.event_type.clone(),
+        snippet: snippet(&parts.join(" "), query),
+        timestamp: event.received_at,
+        link: format!("/events?id={}", event.id),
+    }
+}
+
+fn lottery_result(draw: &LotteryDraw) -> SearchResult {
+    let name = if draw.display_name.is_empty() {
+        &draw.username
+    } else {
+        &draw.display_name
+    };
+    SearchResult {
+        kind: ResultType::Lottery,
+        id: draw.id.to_string(),
+        title: name.clone(),
+        snippet: format!(
+            "{name} (@{}) won among {} participants",
+            draw.username, draw.participant_count
+        ),
+        timestamp: draw.drawn_at,
+        link: format!("/present?draw={}", draw.id),
+    }
+}
+
+fn log_result(entry: &LogEntry, query: &str) -> SearchResult {
+    let timestamp = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+        .map(|t| t.timestamp())
+        .unwrap_or(0);
+    SearchResult {
+        kind: ResultType::Log,
+        id: entry.timestamp.clone(),
+        title: format!("{} {}", entry.level, entry.target),
+        snippet: snippet(&entry.message, query),
+        timestamp,
+        link: format!("/settings?tab=logs&at={timestamp}"),
+    }
+}
+
+/// Buffered log lines whose message, target or fields contain `query`,
+/// newest first.
+fn search_logs(entries: &[LogEntry], query: &str, limit: usize) -> Vec<SearchResult> {
+    let needle = query.to_lowercase();
+    entries
+        .iter()
+        .rev()
+        .filter(|e| {
+            contains_ignore_case(&e.message, &needle)
+                || contains_ignore_case(&e.target, &needle)
+                || e.fields
+                    .values()
+                    .any(|v| contains_ignore_case(&v.to_string(), &needle))
+        })
+        .take(limit)
+        .map(|e| log_result(e, query))
+        .collect()
+}
+
+fn search_source(
+    state: &SharedState,
+    kind: ResultType,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SearchResult>, String> {
+    let db = state.db();
+    let db_limit = limit as i64;
+    match kind {
+        ResultType::Chat => db
+            .search_chat_messages(query, db_limit)
+            .map(|msgs| msgs.iter().map(|m| chat_result(m, query)).collect())
+            .map_err(|e| e.to_string()),
+        ResultType::Event => db
+            .search_archived_events(query, db_limit)
+            .map(|events| events.iter().map(|e| event_result(e, query)).collect())
+            .map_err(|e| e.to_string()),
+        ResultType::Lottery => db
+            .search_lottery_draws(query, db_limit)
+            .map(|draws| draws.iter().map(lottery_result).collect())
+            .map_err(|e| e.to_string()),
+        ResultType::Log => Ok(search_logs(&log_buffer::all(), query, limit)),
+    }
+}
+
+/// Search the given sources for `query`. `limit` applies to each source
+/// and to the merged list.
+pub fn search(
+    state: &SharedState,
+    query: &str,
+    types: &[ResultType],
+    limit: usize,
+) -> Result<SearchResponse, String> {
+    let query = query.trim();
+    if query.chars().count() < MIN_QUERY_CHARS {
+        return Err(format!(
+            "Query must be at least {MIN_QUERY_CHARS} characters"
+        ));
+    }
+    let limit = limit.clamp(1, MAX_LIMIT);
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    for &kind in types {
+        match search_source(state, kind, query, limit) {
+            Ok(found) => results.extend(found),
+            Err(error) => {
+                tracing::warn!(source = kind.as_str(), "Search failed: {error}");
+                errors.push(SourceError { kind, error });
+            }
+        }
+    }
+    results.sort_by_key(|r| std::cmp::Reverse(r.timestamp));
+    results.truncate(limit);
+    Ok(SearchResponse {
+        query: query.to_string(),
+        results,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!(parse_types(None).unwrap(), ResultType::ALL.to_vec());
+        assert_eq!(parse_types(Some(" ")).unwrap(), ResultType::ALL.to_vec());
+        assert_eq!(
+            parse_types(Some("log, chat,log")).unwrap(),
+            vec![ResultType::Log, ResultType::Chat]
+        );
+        assert!(parse_types(Some("chat,clips")).is_err());
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("short  text\nhere", "text"), "short text here");
+        let long = format!("{}needle{}", "a".repeat(200), "b".repeat(200));
+        let cut = snippet(&long, "NEEDLE");
+        assert!(cut.contains("needle"));
+        assert!(cut.starts_with('…') && cut.ends_with('…'));
+        assert_eq!(cut.chars().count(), SNIPPET_CHARS + 2);
+    }
+
+    #[test]
+    fn test_search_logs() {
+        let entry = |timestamp: &str, message: &str| LogEntry {
+            timestamp: timestamp.into(),
+            level: "INFO".into(),
+            target: "twitch_overlay::printer".into(),
+            message: message.into(),
+            fields: serde_json::from_value(json!({ "reward_id": "abc" })).unwrap(),
+        };
+        let entries = vec![
+            entry("2026-01-01T00:00:00+00:00", "Printer connected"),
+            entry("2026-01-01T00:01:00+00:00", "Print job done"),
+            entry("2026-01-01T00:02:00+00:00", "Token refreshed"),
+        ];
+        let found = search_logs(&entries, "PRINT", 10);
+        assert_eq!(found.len(), 3); // every target contains "printer"
+        assert_eq!(found[0].snippet, "Token refreshed");
+        assert_eq!(found[0].timestamp, 1_767_225_720);
+        assert_eq!(found[0].link, "/settings?tab=logs&at=1767225720");
+        assert_eq!(search_logs(&entries, "job", 10).len(), 1);
+        assert_eq!(search_logs(&entries, "abc", 1).len(), 1);
+    }
+
+    #[test]
+    fn test_event_result_snippet() {
+        let event = ArchivedEvent {
+            id: 7,
+            event_type: "channel.raid".into(),
+            payload: json!({ "from_broadcaster_user_name": "Friend", "viewers": 12 }),
+            received_at: 100,
+        };
+        let result = event_result(&event, "friend");
+        assert_eq!(result.snippet, "Friend 12");
+        assert_eq!(result.link, "/events?id=7");
+        assert_eq!(serde_json::to_value(&result).unwrap()["type"], "event");
+    }
+}
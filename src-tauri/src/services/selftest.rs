@@ -0,0 +1,266 @@
+//! Startup diagnostics: a self-test of everything the app depends on.
+//!
+//! Each check reports `pass`, `fail` or `skip` (not configured, so nothing
+//! to test) with a short message. The report passes when no check failed.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use twitch_client::auth::validate_token;
+use twitch_client::eventsub::EventSubClient;
+
+use crate::app::SharedState;
+use crate::services::font::FontService;
+use crate::services::printer;
+
+const PORT_TIMEOUT: Duration = Duration::from_secs(2);
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+const PRINTER_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+impl CheckStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Fail => "fail",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub checks: Vec<CheckResult>,
+    pub ran_at: i64,
+}
+
+impl SelfTestReport {
+    pub(crate) fn new(checks: Vec<CheckResult>) -> Self {
+        Self {
+            passed: checks.iter().all(|c| c.status != CheckStatus::Fail),
+            checks,
+            ran_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+type Outcome = (CheckStatus, String);
+
+fn pass(message: impl Into<String>) -> Outcome {
+    (CheckStatus::Pass, message.into())
+}
+
+fn fail(message: impl Into<String>) -> Outcome {
+    (CheckStatus::Fail, message.into())
+}
+
+fn skip(message: impl Into<String>) -> Outcome {
+    (CheckStatus::Skip, message.into())
+}
+
+async fn timed(name: &'static str, check: impl Future<Output = Outcome>) -> CheckResult {
+    let start = Instant::now();
+    let (status, message) = check.await;
+    CheckResult {
+        name,
+        status,
+        message,
+        duration_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+/// Run every check in order.
+pub async fn run(state: &SharedState) -> SelfTestReport {
+    run_checks(state, true).await
+}
+
+/// Run the checks before the server has started (`--check`): the port
+/// must be free rather than answering.
+pub async fn run_before_start(state: &SharedState) -> SelfTestReport {
+    run_checks(state, false).await
+}
+
+async fn run_checks(state: &SharedState, server_running: bool) -> SelfTestReport {
+    let port = if server_running {
+        timed("port", check_port(state)).await
+    } else {
+        timed("port", check_port_free(state)).await
+    };
+    let checks = vec![
+        timed("database", check_database(state)).await,
+        port,
+        timed("twitch_token", check_token(state)).await,
+        timed("eventsub", check_eventsub()).await,
+        timed("printer", check_printer(state)).await,
+        timed("fonts", check_fonts(state)).await,
+    ];
+    let report = SelfTestReport::new(checks);
+    tracing::info!(passed = report.passed, "Self-test finished");
+    report
+}
+
+async fn check_database(state: &SharedState) -> Outcome {
+    match state.db().check_writable() {
+        Ok(()) => pass("Database is writable"),
+        Err(e) => fail(format!("Database is not writable: {e}")),
+    }
+}
+
+async fn check_port(state: &SharedState) -> Outcome {
+    let port = state.server_port();
+    let connect = tokio::net::TcpStream::connect(("127.0.0.1", port));
+    match tokio::time::timeout(PORT_TIMEOUT, connect).await {
+        Ok(Ok(_)) => pass(format!("Server is listening on port {port}")),
+        Ok(Err(e)) => fail(format!("Port {port} is not reachable: {e}")),
+        Err(_) => fail(format!("Port {port} timed out")),
+    }
+}
+
+async fn check_port_free(state: &SharedState) -> Outcome {
+    let port = state.server_port();
+    match tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+        Ok(_) => pass(format!("Port {port} is free")),
+        Err(e) => fail(format!("Port {port} is in use: {e}")),
+    }
+}
+
+async fn check_token(state: &SharedState) -> Outcome {
+    let token = match state.db().get_latest_token() {
+        Ok(Some(t)) => t,
+        Ok(None) => return fail("No Twitch token; authenticate with Twitch"),
+        Err(e) => return fail(format!("Failed to load token: {e}")),
+    };
+    let validate = validate_token(&state.http(), &token.access_token);
+    let validation = match tokio::time::timeout(NETWORK_TIMEOUT, validate).await {
+        Ok(Ok(v)) => v,
+        Ok(Err(e)) => return fail(format!("Token is not valid: {e}")),
+        Err(_) => return fail("Token validation timed out"),
+    };
+    let missing = validation.missing_scopes();
+    if !missing.is_empty() {
+        return fail(format!(
+            "Missing scopes: {}; authenticate with Twitch again",
+            missing.join(", ")
+        ));
+    }
+    let configured = state.config().await.client_id.clone();
+    if !configured.is_empty() && validation.client_id != configured {
+        return fail("Token was issued for a different CLIENT_ID");
+    }
+    pass(format!(
+        "Token for {} is valid for {} more minutes",
+        validation.login,
+        validation.expires_in / 60
+    ))
+}
+
+async fn check_eventsub() -> Outcome {
+    match tokio::time::timeout(NETWORK_TIMEOUT, EventSubClient::probe()).await {
+        Ok(Ok(_)) => pass("EventSub WebSocket is reachable"),
+        Ok(Err(e)) => fail(format!("EventSub connection failed: {e}")),
+        Err(_) => fail("EventSub connection timed out"),
+    }
+}
+
+async fn check_printer(state: &SharedState) -> Outcome {
+    let (printer_type, address, usb_name, escpos_device) = {
+        let config = state.config().await;
+        (
+            config.printer_type.clone(),
+            config.printer_address.clone(),
+            config.usb_printer_name.clone(),
+            config.uses_escpos().then(|| config.escpos_device.clone()),
+        )
+    };
+
+    if let Some(device) = escpos_device {
+        if device.is_empty() {
+            return skip("ESCPOS_DEVICE is not set");
+        }
+        return if printer::is_escpos_device_available(&device) {
+            pass(format!("ESC/POS printer {device} is present"))
+        } else {
+            fail(format!("ESC/POS printer device not found: {device}"))
+        };
+    }
+
+    if printer_type == "usb" {
+        if usb_name.is_empty() {
+            return skip("USB_PRINTER_NAME is not set");
+        }
+        return match printer::is_usb_printer_available(&usb_name).await {
+            Ok(true) => pass(format!("USB printer {usb_name} is available")),
+            Ok(false) => fail(format!("USB printer not found: {usb_name}")),
+            Err(e) => fail(format!("Failed to list printers: {e}")),
+        };
+    }
+
+    if address.is_empty() {
+        return skip("PRINTER_ADDRESS is not set");
+    }
+    if printer::get_runtime_state().await.connected {
+        return pass(format!("Bluetooth printer {address} is connected"));
+    }
+    match tokio::time::timeout(
+        PRINTER_TIMEOUT,
+        printer::test_bluetooth_connection(&address),
+    )
+    .await
+    {
+        Ok(Ok(())) => pass(format!("Bluetooth printer {address} is reachable")),
+        Ok(Err(e)) => fail(format!("Bluetooth printer {address} is not reachable: {e}")),
+        Err(_) => fail(format!("Bluetooth printer {address} timed out")),
+    }
+}
+
+async fn check_fonts(state: &SharedState) -> Outcome {
+    match FontService::new(state.data_dir().clone()).get_font_info() {
+        Ok(info) if info.has_custom_font => pass(format!(
+            "Font {} is installed",
+            info.filename.unwrap_or_default()
+        )),
+        Ok(_) => fail("No font installed; upload a font for printing"),
+        Err(e) => fail(format!("Failed to read font: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(status: CheckStatus) -> CheckResult {
+        CheckResult {
+            name: "check",
+            status,
+            message: String::new(),
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_report_passes_without_failures() {
+        let report =
+            SelfTestReport::new(vec![result(CheckStatus::Pass), result(CheckStatus::Skip)]);
+        assert!(report.passed);
+        let report =
+            SelfTestReport::new(vec![result(CheckStatus::Pass), result(CheckStatus::Fail)]);
+        assert!(!report.passed);
+    }
+}
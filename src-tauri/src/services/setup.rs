@@ -0,0 +1,309 @@
+//! First-run setup wizard.
+//!
+//! The wizard walks through [`STEPS`] in order. Twitch steps are complete
+//! when their settings (or the OAuth token) exist; the printer and overlay
+//! steps are recorded in `SETUP_COMPLETED_STEPS` since they have no single
+//! setting that proves them done. A step can only run once every step
+//! before it is complete, so a half-finished setup resumes at `current`.
+
+use serde::Serialize;
+use twitch_client::api::TwitchApiClient;
+use twitch_client::auth::validate_token;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::{overlay_tokens, printer};
+
+pub const STEP_CREDENTIALS: &str = "credentials";
+pub const STEP_OAUTH: &str = "oauth";
+pub const STEP_BROADCASTER: &str = "broadcaster";
+pub const STEP_PRINTER: &str = "printer";
+pub const STEP_OVERLAY: &str = "overlay";
+
+/// Wizard steps in order, with their titles.
+pub const STEPS: &[(&str, &str)] = &[
+    (STEP_CREDENTIALS, "Twitch アプリの認証情報"),
+    (STEP_OAUTH, "Twitch 認証"),
+    (STEP_BROADCASTER, "配信者の選択"),
+    (STEP_PRINTER, "プリンターの接続"),
+    (STEP_OVERLAY, "オーバーレイ URL"),
+];
+
+const COMPLETED_STEPS_KEY: &str = "SETUP_COMPLETED_STEPS";
+const COMPLETED_KEY: &str = "SETUP_COMPLETED";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepStatus {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetupStatus {
+    pub steps: Vec<StepStatus>,
+    /// First incomplete step; `None` once everything is done.
+    pub current: Option<&'static str>,
+    pub completed: bool,
+}
+
+/// Printer choice for the printer step.
+#[derive(Debug, Clone)]
+pub enum PrinterChoice {
+    Bluetooth { address: String },
+    Usb { name: String },
+    Skip,
+}
+
+/// A wizard error: the HTTP status to report and a message.
+pub type SetupError = (u16, String);
+
+fn settings(state: &SharedState) -> SettingsManager {
+    SettingsManager::new(state.db().clone())
+}
+
+fn recorded_steps(sm: &SettingsManager) -> Vec<String> {
+    sm.get_setting(COMPLETED_STEPS_KEY)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn record_step(sm: &SettingsManager, step: &str) -> Result<(), SetupError> {
+    let mut steps = recorded_steps(sm);
+    if !steps.iter().any(|s| s == step) {
+        steps.push(step.to_string());
+    }
+    sm.set_setting(COMPLETED_STEPS_KEY, &steps.join(","))
+        .map_err(|e| (500, e.to_string()))
+}
+
+fn set(sm: &SettingsManager, key: &str, value: &str) -> Result<(), SetupError> {
+    sm.set_setting(key, value)
+        .map_err(|e| (400, format!("{key}: {e}")))
+}
+
+/// Current progress through the wizard.
+pub fn status(state: &SharedState) -> SetupStatus {
+    let sm = settings(state);
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let recorded = recorded_steps(&sm);
+    let has_token = matches!(state.db().get_latest_token(), Ok(Some(_)));
+
+    let steps: Vec<StepStatus> = STEPS
+        .iter()
+        .map(|&(id, title)| {
+            let completed = match id {
+                STEP_CREDENTIALS => {
+                    !get("CLIENT_ID").is_empty() && !get("CLIENT_SECRET").is_empty()
+                }
+                STEP_OAUTH => has_token,
+                STEP_BROADCASTER => !get("TWITCH_USER_ID").is_empty(),
+                _ => recorded.iter().any(|s| s == id),
+            };
+            StepStatus {
+                id,
+                title,
+                completed,
+            }
+        })
+        .collect();
+    let current = steps.iter().find(|s| !s.completed).map(|s| s.id);
+    SetupStatus {
+        completed: current.is_none(),
+        current,
+        steps,
+    }
+}
+
+/// Fail unless every step before `step` is complete.
+fn require_previous(state: &SharedState, step: &str) -> Result<(), SetupError> {
+    let status = status(state);
+    let pending = status
+        .steps
+        .iter()
+        .take_while(|s| s.id != step)
+        .find(|s| !s.completed);
+    match pending {
+        Some(s) => Err((409, format!("Complete the {} step first", s.id))),
+        None => Ok(()),
+    }
+}
+
+/// Step 1: store the Twitch application credentials.
+pub async fn set_credentials(
+    state: &SharedState,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<SetupStatus, SetupError> {
+    let (client_id, client_secret) = (client_id.trim(), client_secret.trim());
+    if client_id.is_empty() || client_secret.is_empty() {
+        return Err((400, "client_id and client_secret are required".into()));
+    }
+    let sm = settings(state);
+    set(&sm, "CLIENT_ID", client_id)?;
+    set(&sm, "CLIENT_SECRET", client_secret)?;
+    state
+        .reload_config()
+        .await
+        .map_err(|e| (500, format!("Failed to reload config: {e}")))?;
+    Ok(status(state))
+}
+
+/// Step 2: the URL to open for OAuth. Completion is detected from the token
+/// saved by the `/callback` handler.
+pub async fn oauth_url(state: &SharedState) -> Result<String, SetupError> {
+    require_previous(state, STEP_OAUTH)?;
+    let config = state.config().await;
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", config.server_port);
+    twitch_client::auth::TwitchAuth::with_http(
+        config.client_id.clone(),
+        config.client_secret.clone(),
+        redirect_uri,
+        state.http(),
+    )
+    .get_auth_url()
+    .map_err(|e| (500, e.to_string()))
+}
+
+/// Step 3: select the broadcaster; the token owner when `login` is empty.
+pub async fn set_broadcaster(
+    state: &SharedState,
+    login: Option<&str>,
+) -> Result<SetupStatus, SetupError> {
+    require_previous(state, STEP_BROADCASTER)?;
+    let token = state
+        .db()
+        .get_latest_token()
+        .map_err(|e| (500, e.to_string()))?
+        .ok_or_else(|| (409, "No Twitch token; complete the oauth step".to_string()))?;
+    let owner = validate_token(&state.http(), &token.access_token)
+        .await
+        .map_err(|e| (401, format!("Token is not valid: {e}")))?;
+
+    let login = login.map(str::trim).filter(|l| !l.is_empty());
+    let user_id = match login {
+        None => owner.user_id.clone(),
+        Some(login) if login.eq_ignore_ascii_case(&owner.login) => owner.user_id.clone(),
+        Some(login) => {
+            if !login.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err((400, format!("Invalid login: {login}")));
+            }
+            let client_id = state.config().await.client_id.clone();
+            let user = TwitchApiClient::with_http(client_id, state.http())
+                .get_user_by_login(
+                    &twitch_client::Token {
+                        access_token: token.access_token.clone(),
+                        refresh_token: token.refresh_token.clone(),
+                        scope: token.scope.clone(),
+                        expires_at: token.expires_at,
+                    },
+                    login,
+                )
+                .await
+                .map_err(|e| (502, e.to_string()))?
+                .ok_or_else(|| (404, format!("User not found: {login}")))?;
+            tracing::warn!(
+                broadcaster = user.login,
+                token_owner = owner.login,
+                "Broadcaster differs from the authenticated user; EventSub needs the broadcaster's token"
+            );
+            user.id
+        }
+    };
+    if user_id.is_empty() {
+        return Err((400, "Could not determine the broadcaster".into()));
+    }
+    set(&settings(state), "TWITCH_USER_ID", &user_id)?;
+    state
+        .reload_config()
+        .await
+        .map_err(|e| (500, format!("Failed to reload config: {e}")))?;
+    Ok(status(state))
+}
+
+/// Step 4: pair a printer (tested before it is saved) or skip printing.
+pub async fn set_printer(
+    state: &SharedState,
+    choice: PrinterChoice,
+) -> Result<SetupStatus, SetupError> {
+    require_previous(state, STEP_PRINTER)?;
+    let sm = settings(state);
+    match choice {
+        PrinterChoice::Bluetooth { address } => {
+            printer::test_bluetooth_connection(&address)
+                .await
+                .map_err(|e| (502, format!("Printer is not reachable: {e}")))?;
+            set(&sm, "PRINTER_TYPE", "bluetooth")?;
+            set(&sm, "PRINTER_ADDRESS", &address)?;
+        }
+        PrinterChoice::Usb { name } => {
+            match printer::is_usb_printer_available(&name).await {
+                Ok(true) => {}
+                Ok(false) => return Err((404, format!("USB printer not found: {name}"))),
+                Err(e) => return Err((502, e)),
+            }
+            set(&sm, "PRINTER_TYPE", "usb")?;
+            set(&sm, "USB_PRINTER_NAME", &name)?;
+        }
+        PrinterChoice::Skip => {}
+    }
+    record_step(&sm, STEP_PRINTER)?;
+    state
+        .reload_config()
+        .await
+        .map_err(|e| (500, format!("Failed to reload config: {e}")))?;
+    crate::services::features::start_enabled_tasks(state).await;
+    Ok(status(state))
+}
+
+/// Signed overlay URLs for OBS browser sources.
+pub fn overlay_urls(state: &SharedState) -> Result<Vec<(&'static str, String)>, SetupError> {
+    let port = state.server_port();
+    ["all", "present"]
+        .into_iter()
+        .map(|widget| {
+            let token = overlay_tokens::issue(state, widget, "default", Vec::new(), false)
+                .map_err(|e| (500, e))?;
+            Ok((widget, overlay_tokens::widget_url(port, widget, &token)))
+        })
+        .collect()
+}
+
+/// Step 5: hand out the overlay URLs and finish the wizard.
+pub fn finish(state: &SharedState) -> Result<SetupStatus, SetupError> {
+    require_previous(state, STEP_OVERLAY)?;
+    let sm = settings(state);
+    record_step(&sm, STEP_OVERLAY)?;
+    set(&sm, COMPLETED_KEY, "true")?;
+    tracing::info!("First-run setup completed");
+    Ok(status(state))
+}
+
+/// Forget recorded progress (settings and the token are kept).
+pub fn reset(state: &SharedState) -> Result<SetupStatus, SetupError> {
+    let sm = settings(state);
+    set(&sm, COMPLETED_STEPS_KEY, "")?;
+    set(&sm, COMPLETED_KEY, "false")?;
+    Ok(status(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_urls() {
+        let db = overlay_db::Database::open_in_memory().unwrap();
+        let config = crate::init_settings(&db).unwrap();
+        let state = SharedState::new(db, config, std::env::temp_dir());
+        let base = format!("http://localhost:{}/overlay", state.server_port());
+        let urls = overlay_urls(&state).unwrap();
+        assert_eq!(urls[0].0, "all");
+        assert!(urls[0].1.starts_with(&format!("{base}/?token=")));
+        assert!(urls[1].1.starts_with(&format!("{base}/present?token=")));
+    }
+}
@@ -0,0 +1,286 @@
+//! Shoutout queue.
+//!
+//! Twitch allows one shoutout every 2 minutes per channel and one per
+//! target every hour; calling `send_shoutout` earlier fails with 429. All
+//! shoutouts go through this queue instead: a worker spaces them out, skips
+//! targets still on their own cooldown, and re-queues after a 429 until the
+//! retry budget is spent.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::{Mutex, Notify};
+use twitch_client::TwitchError;
+
+use crate::app::SharedState;
+use crate::services::helix;
+
+/// Global spacing (2 minutes plus a safety margin).
+const GLOBAL_COOLDOWN: Duration = Duration::from_secs(125);
+/// Per-target spacing (1 hour plus a safety margin).
+const TARGET_COOLDOWN: Duration = Duration::from_secs(3605);
+/// 429 retries before giving up (about an hour at the global spacing).
+const MAX_COOLDOWN_RETRIES: u32 = 30;
+
+static QUEUE: LazyLock<Mutex<ShoutoutQueue>> =
+    LazyLock::new(|| Mutex::new(ShoutoutQueue::default()));
+static WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingShoutout {
+    pub id: u64,
+    pub target_user_id: String,
+    pub target_user_name: String,
+    /// Where the request came from ("raid", "dashboard", ...).
+    pub source: String,
+    pub queued_at: i64,
+    pub retries: u32,
+    pub last_error: Option<String>,
+    /// Estimated seconds until the next attempt.
+    pub eta_seconds: u64,
+}
+
+#[derive(Debug, Default)]
+struct ShoutoutQueue {
+    pending: VecDeque<PendingShoutout>,
+    next_global: Option<Instant>,
+    target_ready: HashMap<String, Instant>,
+    next_id: u64,
+}
+
+impl ShoutoutQueue {
+    /// Queue a target unless it is already pending; returns the entry.
+    fn push(&mut self, target_id: &str, target_name: &str, source: &str, now: i64) -> u64 {
+        if let Some(existing) = self.pending.iter().find(|p| p.target_user_id == target_id) {
+            return existing.id;
+        }
+        self.next_id += 1;
+        self.pending.push_back(PendingShoutout {
+            id: self.next_id,
+            target_user_id: target_id.to_string(),
+            target_user_name: target_name.to_string(),
+            source: source.to_string(),
+            queued_at: now,
+            retries: 0,
+            last_error: None,
+            eta_seconds: 0,
+        });
+        self.next_id
+    }
+
+    fn remove(&mut self, id: u64) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|p| p.id != id);
+        self.pending.len() != before
+    }
+
+    fn ready_at(&self, target_id: &str) -> Option<Instant> {
+        let target = self.target_ready.get(target_id).copied();
+        match (self.next_global, target) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Take the first entry that may be sent at `now`.
+    fn take_ready(&mut self, now: Instant) -> Option<PendingShoutout> {
+        let index = self
+            .pending
+            .iter()
+            .position(|p| self.ready_at(&p.target_user_id).is_none_or(|t| t <= now))?;
+        self.pending.remove(index)
+    }
+
+    /// Earliest instant any pending entry may be sent.
+    fn next_wake(&self) -> Option<Instant> {
+        let now = Instant::now();
+        self.pending
+            .iter()
+            .map(|p| self.ready_at(&p.target_user_id).unwrap_or(now))
+            .min()
+    }
+
+    fn mark_sent(&mut self, target_id: &str, now: Instant) {
+        self.next_global = Some(now + GLOBAL_COOLDOWN);
+        self.target_ready
+            .insert(target_id.to_string(), now + TARGET_COOLDOWN);
+        self.target_ready.retain(|_, t| *t > now);
+    }
+
+    /// Put an entry that hit a cooldown back at the front.
+    fn requeue(&mut self, mut item: PendingShoutout, error: String, now: Instant) -> bool {
+        item.retries += 1;
+        item.last_error = Some(error);
+        if item.retries > MAX_COOLDOWN_RETRIES {
+            return false;
+        }
+        self.next_global = Some(now + GLOBAL_COOLDOWN);
+        self.pending.push_front(item);
+        true
+    }
+
+    /// Pending entries with estimated wait times, in send order.
+    fn snapshot(&self, now: Instant) -> Vec<PendingShoutout> {
+        let mut slot = self.next_global.unwrap_or(now).max(now);
+        self.pending
+            .iter()
+            .map(|p| {
+                let at = self
+                    .target_ready
+                    .get(&p.target_user_id)
+                    .map_or(slot, |t| slot.max(*t));
+                slot = at + GLOBAL_COOLDOWN;
+                PendingShoutout {
+                    eta_seconds: at.saturating_duration_since(now).as_secs(),
+                    ..p.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Queue a shoutout. Returns the queue entry ID (existing one if the target
+/// is already queued).
+pub async fn enqueue(target_user_id: &str, target_user_name: &str, source: &str) -> u64 {
+    let id = QUEUE.lock().await.push(
+        target_user_id,
+        target_user_name,
+        source,
+        chrono::Utc::now().timestamp(),
+    );
+    WAKE.notify_one();
+    id
+}
+
+/// Pending shoutouts with estimated wait times.
+pub async fn pending() -> Vec<PendingShoutout> {
+    QUEUE.lock().await.snapshot(Instant::now())
+}
+
+/// Drop a pending shoutout.
+pub async fn cancel(id: u64) -> bool {
+    QUEUE.lock().await.remove(id)
+}
+
+/// Background worker sending queued shoutouts.
+pub async fn run_worker(state: SharedState) {
+    loop {
+        let item = {
+            let mut queue = QUEUE.lock().await;
+            queue.take_ready(Instant::now())
+        };
+        let Some(item) = item else {
+            let wake = QUEUE.lock().await.next_wake();
+            match wake {
+                Some(at) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep_until(at.into()) => {}
+                        _ = WAKE.notified() => {}
+                    }
+                }
+                None => WAKE.notified().await,
+            }
+            continue;
+        };
+
+        let result = send(&state, &item.target_user_id).await;
+        let now = Instant::now();
+        let mut queue = QUEUE.lock().await;
+        match result {
+            Ok(()) => {
+                tracing::info!(
+                    target = item.target_user_id,
+                    source = item.source,
+                    "Shoutout sent"
+                );
+                queue.mark_sent(&item.target_user_id, now);
+            }
+            Err(SendError::Cooldown(e)) => {
+                let target = item.target_user_id.clone();
+                if !queue.requeue(item, e, now) {
+                    tracing::warn!(target, "Shoutout dropped after repeated cooldown errors");
+                }
+            }
+            Err(SendError::Failed(e)) => {
+                tracing::warn!(target = item.target_user_id, "Shoutout failed: {e}");
+                // Failed requests may still count against the global cooldown.
+                queue.next_global = Some(now + GLOBAL_COOLDOWN);
+            }
+        }
+    }
+}
+
+enum SendError {
+    Cooldown(String),
+    Failed(String),
+}
+
+async fn send(state: &SharedState, target_user_id: &str) -> Result<(), SendError> {
+    let ctx = helix::context(state).await.map_err(SendError::Failed)?;
+    ctx.api
+        .send_shoutout(
+            &ctx.token,
+            &ctx.broadcaster_id,
+            target_user_id,
+            &ctx.broadcaster_id,
+        )
+        .await
+        .map_err(|e| match e {
+            TwitchError::ApiError { status: 429, .. } => SendError::Cooldown(e.to_string()),
+            other => SendError::Failed(other.to_string()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_dedupes_targets() {
+        let mut q = ShoutoutQueue::default();
+        let a = q.push("1", "alice", "raid", 0);
+        assert_eq!(q.push("1", "alice", "dashboard", 0), a);
+        q.push("2", "bob", "raid", 0);
+        assert_eq!(q.pending.len(), 2);
+        assert!(q.remove(a));
+        assert!(!q.remove(a));
+    }
+
+    #[test]
+    fn test_spacing_and_target_cooldown() {
+        let mut q = ShoutoutQueue::default();
+        let t0 = Instant::now();
+        q.push("1", "alice", "raid", 0);
+        q.push("2", "bob", "raid", 0);
+
+        let first = q.take_ready(t0).unwrap();
+        assert_eq!(first.target_user_id, "1");
+        q.mark_sent("1", t0);
+        assert!(q.take_ready(t0 + Duration::from_secs(60)).is_none());
+
+        // Alice again: still on her per-target cooldown, so Bob goes first.
+        q.push("1", "alice", "raid", 0);
+        q.pending.rotate_left(1);
+        let next = q.take_ready(t0 + GLOBAL_COOLDOWN).unwrap();
+        assert_eq!(next.target_user_id, "2");
+
+        let etas = q.snapshot(t0 + GLOBAL_COOLDOWN);
+        assert_eq!(
+            etas[0].eta_seconds,
+            (TARGET_COOLDOWN - GLOBAL_COOLDOWN).as_secs()
+        );
+    }
+
+    #[test]
+    fn test_requeue_gives_up() {
+        let mut q = ShoutoutQueue::default();
+        let now = Instant::now();
+        q.push("1", "alice", "raid", 0);
+        let mut item = q.take_ready(now).unwrap();
+        item.retries = MAX_COOLDOWN_RETRIES;
+        assert!(!q.requeue(item, "429".into(), now));
+        assert!(q.pending.is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+//! Smart-plug control for printer power (Tasmota / Home Assistant HTTP).
+//!
+//! The plug is switched on when the stream goes online and off when it
+//! ends, and print jobs make sure it is on before printing.
+
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::print_queue;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest time to wait for queued jobs before powering off.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+struct PlugSettings {
+    enabled: bool,
+    kind: String,
+    url: String,
+    token: String,
+    entity_id: String,
+    warmup: Duration,
+}
+
+fn load_settings(state: &SharedState) -> PlugSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    PlugSettings {
+        enabled: get("SMART_PLUG_ENABLED") == "true",
+        kind: get("SMART_PLUG_TYPE"),
+        url: get("SMART_PLUG_URL").trim_end_matches('/').to_string(),
+        token: get("SMART_PLUG_TOKEN"),
+        entity_id: get("SMART_PLUG_ENTITY_ID"),
+        warmup: Duration::from_secs(get("SMART_PLUG_WARMUP_SECONDS").parse().unwrap_or(10)),
+    }
+}
+
+/// Whether smart-plug control is enabled and configured.
+pub fn is_enabled(state: &SharedState) -> bool {
+    let s = load_settings(state);
+    s.enabled && !s.url.is_empty()
+}
+
+/// Query the plug. Returns `Ok(None)` when the integration is disabled.
+pub async fn status(state: &SharedState) -> Result<Option<bool>, String> {
+    let s = load_settings(state);
+    if !s.enabled || s.url.is_empty() {
+        return Ok(None);
+    }
+    query_power(&state.http(), &s).await.map(Some)
+}
+
+/// Switch the plug on or off.
+pub async fn set_power(state: &SharedState, on: bool) -> Result<(), String> {
+    let s = load_settings(state);
+    if !s.enabled || s.url.is_empty() {
+        return Err("Smart plug is not configured".into());
+    }
+    send_power(&state.http(), &s, on).await?;
+    tracing::info!(on, "Smart plug switched");
+    broadcast(state, on);
+    Ok(())
+}
+
+/// Make sure the printer has power before a job; waits for warm-up if it
+/// had to be switched on. No-op when the integration is disabled.
+pub async fn ensure_powered(state: &SharedState) -> Result<(), String> {
+    let s = load_settings(state);
+    if !s.enabled || s.url.is_empty() {
+        return Ok(());
+    }
+    if query_power(&state.http(), &s).await? {
+        return Ok(());
+    }
+    send_power(&state.http(), &s, true).await?;
+    broadcast(state, true);
+    tracing::info!(
+        warmup_secs = s.warmup.as_secs(),
+        "Printer plug was off; powered on"
+    );
+    tokio::time::sleep(s.warmup).await;
+    Ok(())
+}
+
+/// Stream went online: power the printer on.
+pub async fn on_stream_online(state: &SharedState) {
+    if !is_enabled(state) {
+        return;
+    }
+    if let Err(e) = set_power(state, true).await {
+        tracing::warn!("Failed to power on printer plug: {e}");
+    }
+}
+
+/// Stream went offline: wait for queued jobs, then power the printer off.
+pub async fn on_stream_offline(state: &SharedState) {
+    if !is_enabled(state) {
+        return;
+    }
+    let started = std::time::Instant::now();
+    while print_queue::queue_status(state).await.0 > 0 && started.elapsed() < DRAIN_TIMEOUT {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+    if let Err(e) = set_power(state, false).await {
+        tracing::warn!("Failed to power off printer plug: {e}");
+    }
+}
+
+async fn query_power(http: &reqwest::Client, s: &PlugSettings) -> Result<bool, String> {
+    match s.kind.as_str() {
+        "homeassistant" => {
+            let url = format!("{}/api/states/{}", s.url, s.entity_id);
+            let body: Value = http
+                .get(&url)
+                .timeout(HTTP_TIMEOUT)
+                .bearer_auth(&s.token)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| e.to_string())?
+                .json()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(body["state"].as_str() == Some("on"))
+        }
+        _ => {
+            let url = format!("{}/cm?cmnd=Power", s.url);
+            let body: Value = http
+                .get(&url)
+                .timeout(HTTP_TIMEOUT)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| e.to_string())?
+                .json()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(tasmota_is_on(&body))
+        }
+    }
+}
+
+async fn send_power(http: &reqwest::Client, s: &PlugSettings, on: bool) -> Result<(), String> {
+    let resp = match s.kind.as_str() {
+        "homeassistant" => {
+            let service = if on { "turn_on" } else { "turn_off" };
+            let url = format!("{}/api/services/switch/{service}", s.url);
+            http.post(&url)
+                .timeout(HTTP_TIMEOUT)
+                .bearer_auth(&s.token)
+                .json(&json!({ "entity_id": s.entity_id }))
+                .send()
+                .await
+        }
+        _ => {
+            let cmd = if on { "On" } else { "Off" };
+            http.get(format!("{}/cm?cmnd=Power%20{cmd}", s.url))
+                .timeout(HTTP_TIMEOUT)
+                .send()
+                .await
+        }
+    };
+    resp.and_then(|r| r.error_for_status())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Tasmota answers `{"POWER":"ON"}` (or `POWER1` on multi-relay devices).
+fn tasmota_is_on(body: &Value) -> bool {
+    ["POWER", "POWER1"]
+        .iter()
+        .filter_map(|k| body[*k].as_str())
+        .any(|v| v.eq_ignore_ascii_case("on"))
+}
+
+fn broadcast(state: &SharedState, on: bool) {
+    let msg = json!({ "type": "printer_power", "data": { "on": on } });
+    let _ = state.ws_sender().send(msg.to_string());
+}
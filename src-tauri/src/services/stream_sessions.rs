@@ -0,0 +1,151 @@
+//! Per-broadcast session records (see `overlay_db::stream_sessions`).
+//!
+//! `stream.online` opens a session and `stream.offline` closes it. While a
+//! session is open the [`run`] worker samples viewers, followers and
+//! subscribers every [`SAMPLE_INTERVAL`], so the recap has the peak viewer
+//! count, a viewer graph and the follower/sub change over the stream. Subscriber counts
+//! need an affiliate or partner channel; elsewhere they stay empty. Each
+//! check also brings reward groups in line with their policies.
+
+use std::time::Duration;
+
+use overlay_db::stream_sessions::{StreamSample, StreamSession};
+use serde_json::Value;
+
+use crate::app::SharedState;
+use crate::eventsub_support::{send_ws, str_field};
+use crate::services::{helix, reward_groups};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+
+fn db_err(e: overlay_db::DbError) -> String {
+    format!("Stream session database error: {e}")
+}
+
+/// Current counts from Helix, with the stream ID while live. Counts that
+/// fail are left out.
+async fn fetch_sample(state: &SharedState) -> Result<(Option<String>, StreamSample), String> {
+    let ctx = helix::context(state).await?;
+    let stream = ctx
+        .api
+        .get_stream_info(&ctx.token, &ctx.broadcaster_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let followers = ctx
+        .api
+        .get_follower_count(&ctx.token, &ctx.broadcaster_id)
+        .await
+        .inspect_err(|e| tracing::debug!("Follower count unavailable: {e}"))
+        .ok();
+    let subs = ctx
+        .api
+        .get_subscriber_count(&ctx.token, &ctx.broadcaster_id)
+        .await
+        .inspect_err(|e| tracing::debug!("Subscriber count unavailable: {e}"))
+        .ok();
+    let info = stream.info.as_ref();
+    Ok((
+        info.map(|i| i.id.clone()),
+        StreamSample {
+            title: info.map(|i| i.title.clone()),
+            category: info.map(|i| i.game_name.clone()),
+            viewers: stream.is_live.then_some(stream.viewer_count as i64),
+            followers: followers.map(|n| n as i64),
+            subs: subs.map(|n| n as i64),
+        },
+    ))
+}
+
+/// Sample the open session, if any.
+async fn sample_open(state: &SharedState) -> Result<(), String> {
+    let Some(session) = state.db().get_open_stream_session().map_err(db_err)? else {
+        return Ok(());
+    };
+    let (_, sample) = fetch_sample(state).await?;
+    state
+        .db()
+        .record_stream_sample(session.id, &sample, chrono::Utc::now().timestamp())
+        .map_err(db_err)
+}
+
+fn notify(state: &SharedState, session: &StreamSession) {
+    send_ws(state, "stream_session", session);
+}
+
+/// Open a session for the stream in a `stream.online` payload and take the
+/// first sample.
+pub async fn on_stream_online(state: &SharedState, payload: &Value, started_at: i64) {
+    let stream_id = str_field(payload, &["id"]);
+    match state.db().start_stream_session(&stream_id, started_at) {
+        Ok(session) => {
+            tracing::info!(id = session.id, stream_id, "Stream session started");
+            notify(state, &session);
+        }
+        Err(e) => {
+            tracing::warn!("{}", db_err(e));
+            return;
+        }
+    }
+    if let Err(e) = sample_open(state).await {
+        tracing::debug!("Stream session sample skipped: {e}");
+    }
+}
+
+/// Take a last sample and close the open session.
+pub async fn on_stream_offline(state: &SharedState) {
+    if let Err(e) = sample_open(state).await {
+        tracing::debug!("Stream session sample skipped: {e}");
+    }
+    match state
+        .db()
+        .end_stream_session(chrono::Utc::now().timestamp())
+    {
+        Ok(Some(session)) => {
+            tracing::info!(id = session.id, "Stream session ended");
+            notify(state, &session);
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("{}", db_err(e)),
+    }
+}
+
+/// Reconcile with Helix at startup: a stream that went live while the app
+/// was closed gets a session from now, and a session whose offline event
+/// was missed is closed at its last sample.
+async fn reconcile(state: &SharedState) -> Result<(), String> {
+    let (live_stream, _) = fetch_sample(state).await?;
+    let open = state.db().get_open_stream_session().map_err(db_err)?;
+    match (live_stream, open) {
+        (Some(stream_id), open) if open.is_none_or(|s| s.stream_id != stream_id) => {
+            let now = chrono::Utc::now().timestamp();
+            state
+                .db()
+                .start_stream_session(&stream_id, now)
+                .map_err(db_err)?;
+        }
+        (None, Some(session)) => {
+            let ended_at = session.last_sampled_at.unwrap_or(session.started_at);
+            state.db().end_stream_session(ended_at).map_err(db_err)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Background worker sampling the open session and applying reward group
+/// policies for the live state it implies.
+pub async fn run(state: SharedState) {
+    if let Err(e) = reconcile(&state).await {
+        tracing::debug!("Stream session state unknown at startup: {e}");
+    }
+    loop {
+        if let Err(e) = sample_open(&state).await {
+            tracing::debug!("Stream session sample skipped: {e}");
+        }
+        match state.db().get_open_stream_session() {
+            Ok(open) => reward_groups::apply_policies(&state, open.is_some()).await,
+            Err(e) => tracing::warn!("{}", db_err(e)),
+        }
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+    }
+}
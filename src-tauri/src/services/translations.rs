@@ -0,0 +1,49 @@
+//! Chat translations.
+//!
+//! Translation runs in the browser: the client that translates a chat
+//! message broadcasts a `chat-translation` message over the WebSocket
+//! (`{ messageId, translation, translationStatus, translationLang }`). The
+//! worker stores it with the chat message so that the chat history shows it
+//! too.
+
+use serde_json::Value;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::app::SharedState;
+
+/// Store the translations broadcast on the WebSocket.
+pub async fn run(state: SharedState) {
+    let mut rx = state.subscribe_ws();
+    loop {
+        let text = match rx.recv().await {
+            Ok(text) => text,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::debug!(skipped, "Chat translation store lagged");
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        let Ok(msg) = serde_json::from_str::<Value>(&text) else {
+            continue;
+        };
+        if msg["type"] == "chat-translation" {
+            store(&state, &msg["data"]);
+        }
+    }
+}
+
+fn store(state: &SharedState, data: &Value) {
+    let field = |key: &str| data[key].as_str().unwrap_or_default();
+    let message_id = field("messageId");
+    if message_id.is_empty() {
+        return;
+    }
+    if let Err(e) = state.db().update_chat_translation(
+        message_id,
+        field("translation"),
+        field("translationStatus"),
+        field("translationLang"),
+    ) {
+        tracing::warn!("Failed to store chat translation: {e}");
+    }
+}
@@ -0,0 +1,384 @@
+//! Text-to-speech for chat messages and reward redemptions.
+//!
+//! Utterances wait in an in-memory queue and are spoken one at a time by
+//! the [`run`] worker. `TTS_ENGINE` picks the synthesizer:
+//!
+//! - `system` – the OS voice: `say` on macOS, SAPI through PowerShell on
+//!   Windows and `espeak-ng` elsewhere.
+//! - `command` – the external program in `TTS_COMMAND`. The command line is
+//!   split on whitespace and run without a shell; `{text}`, `{voice}` and
+//!   `{rate}` in an argument are replaced.
+//!
+//! Chat and redemptions each have their own voice and speed (`rate`, 1.0 is
+//! the engine's normal speed). Messages hit by the word filter and those of
+//! viewers who opted out of TTS are skipped.
+//! Pausing holds the queue after the current utterance; skipping stops the
+//! current one.
+
+use std::collections::VecDeque;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+use overlay_db::consents::ConsentScope;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::process::Command;
+use tokio::sync::Notify;
+use word_filter::WordMatcher;
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::services::consent;
+
+pub const ENGINE_SYSTEM: &str = "system";
+pub const ENGINE_COMMAND: &str = "command";
+
+pub const MIN_RATE: f64 = 0.5;
+pub const MAX_RATE: f64 = 3.0;
+
+const QUEUE_CAPACITY: usize = 50;
+/// Words per minute of the OS voices at rate 1.0.
+const BASE_WPM: f64 = 175.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    Chat,
+    Reward,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Utterance {
+    pub source: Source,
+    pub text: String,
+    pub voice: String,
+    pub rate: f64,
+}
+
+static QUEUE: LazyLock<Mutex<VecDeque<Utterance>>> = LazyLock::new(Mutex::default);
+static SPEAKING: LazyLock<Mutex<Option<Utterance>>> = LazyLock::new(Mutex::default);
+static PAUSED: AtomicBool = AtomicBool::new(false);
+static WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+static SKIP: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+fn queue() -> MutexGuard<'static, VecDeque<Utterance>> {
+    QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn speaking() -> MutexGuard<'static, Option<Utterance>> {
+    SPEAKING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+struct TtsSettings {
+    enabled: bool,
+    engine: String,
+    command: String,
+    chat: bool,
+    rewards: bool,
+    read_username: bool,
+    max_chars: usize,
+    filter_languages: String,
+    chat_voice: String,
+    chat_rate: f64,
+    reward_voice: String,
+    reward_rate: f64,
+}
+
+fn load_settings(state: &SharedState) -> TtsSettings {
+    let sm = SettingsManager::new(state.db().clone());
+    let get = |key: &str| sm.get_setting(key).unwrap_or_default();
+    let rate = |key: &str| get(key).parse().unwrap_or(1.0);
+    TtsSettings {
+        enabled: get("TTS_ENABLED") == "true",
+        engine: get("TTS_ENGINE"),
+        command: get("TTS_COMMAND"),
+        chat: get("TTS_CHAT_ENABLED") == "true",
+        rewards: get("TTS_REWARDS_ENABLED") == "true",
+        read_username: get("TTS_READ_USERNAME") == "true",
+        max_chars: get("TTS_MAX_CHARS").parse().unwrap_or(140),
+        filter_languages: get("TTS_FILTER_LANGUAGES"),
+        chat_voice: get("TTS_CHAT_VOICE"),
+        chat_rate: rate("TTS_CHAT_RATE"),
+        reward_voice: get("TTS_REWARD_VOICE"),
+        reward_rate: rate("TTS_REWARD_RATE"),
+    }
+}
+
+/// Whether `text` contains a word-filtered term.
+fn is_filtered(state: &SharedState, languages: &str, text: &str) -> bool {
+    let languages: Vec<&str> = languages
+        .split(',')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    match WordMatcher::cached(state.db(), &languages) {
+        Ok(matcher) => text.split_whitespace().any(|term| matcher.is_blocked(term)),
+        Err(e) => {
+            tracing::warn!("Failed to load word filter for TTS: {e}");
+            false
+        }
+    }
+}
+
+/// `text` cut to `max_chars` characters.
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text.to_string(),
+    }
+}
+
+fn enqueue(utterance: Utterance) {
+    {
+        let mut queue = queue();
+        if queue.len() >= QUEUE_CAPACITY {
+            tracing::warn!("TTS queue full ({QUEUE_CAPACITY}); utterance dropped");
+            return;
+        }
+        queue.push_back(utterance);
+    }
+    WAKE.notify_one();
+}
+
+/// Queue `text` from `source` when TTS is on for it and the word filter
+/// lets it through.
+fn speak(state: &SharedState, source: Source, text: &str) {
+    let s = load_settings(state);
+    let wanted = match source {
+        Source::Chat => s.chat,
+        Source::Reward => s.rewards,
+        Source::Manual => true,
+    };
+    let text = text.trim();
+    if !s.enabled || !wanted || text.is_empty() {
+        return;
+    }
+    if is_filtered(state, &s.filter_languages, text) {
+        tracing::debug!(?source, "TTS skipped a filtered message");
+        return;
+    }
+    let (voice, rate) = match source {
+        Source::Reward => (s.reward_voice, s.reward_rate),
+        Source::Chat | Source::Manual => (s.chat_voice, s.chat_rate),
+    };
+    enqueue(Utterance {
+        source,
+        text: truncate(text, s.max_chars),
+        voice,
+        rate: rate.clamp(MIN_RATE, MAX_RATE),
+    });
+}
+
+/// Read a chat message. Commands (`!...`) are not read.
+pub fn on_chat_message(state: &SharedState, user_id: &str, username: &str, message: &str) {
+    if message.trim_start().starts_with('!') || !consent::allows(state, user_id, ConsentScope::Tts)
+    {
+        return;
+    }
+    let text = if load_settings(state).read_username {
+        format!("{username}、{message}")
+    } else {
+        message.to_string()
+    };
+    speak(state, Source::Chat, &text);
+}
+
+/// Read a redemption and the viewer's input, if any.
+pub fn on_redemption(
+    state: &SharedState,
+    user_id: &str,
+    username: &str,
+    reward: &str,
+    input: &str,
+) {
+    if !consent::allows(state, user_id, ConsentScope::Tts) {
+        return;
+    }
+    let text = if input.trim().is_empty() {
+        format!("{username}さんが{reward}を交換しました")
+    } else {
+        format!("{username}さんが{reward}を交換しました。{input}")
+    };
+    speak(state, Source::Reward, &text);
+}
+
+/// Queue arbitrary text with the chat voice (for testing the setup).
+pub fn say(state: &SharedState, text: &str) {
+    speak(state, Source::Manual, text);
+}
+
+pub fn pause() {
+    PAUSED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+pub fn resume() {
+    PAUSED.store(false, Ordering::Relaxed);
+    WAKE.notify_one();
+}
+
+/// Stop the current utterance.
+pub fn skip() {
+    SKIP.notify_waiters();
+}
+
+/// Drop every queued utterance; returns how many were dropped.
+pub fn clear() -> usize {
+    let mut queue = queue();
+    let dropped = queue.len();
+    queue.clear();
+    dropped
+}
+
+/// Settings, queue and the utterance being spoken (for the status API).
+pub fn status(state: &SharedState) -> Value {
+    let s = load_settings(state);
+    json!({
+        "enabled": s.enabled,
+        "engine": s.engine,
+        "paused": PAUSED.load(Ordering::Relaxed),
+        "speaking": speaking().clone(),
+        "queue": queue().iter().cloned().collect::<Vec<_>>(),
+    })
+}
+
+/// Replace `{text}`, `{voice}` and `{rate}` in each argument of `template`.
+fn command_args(template: &str, u: &Utterance) -> Vec<String> {
+    let rate = format!("{:.2}", u.rate);
+    template
+        .split_whitespace()
+        .map(|arg| {
+            arg.replace("{voice}", &u.voice)
+                .replace("{rate}", &rate)
+                .replace("{text}", &u.text)
+        })
+        .collect()
+}
+
+/// Command speaking `u` with the OS voice.
+fn system_command(u: &Utterance) -> Command {
+    let wpm = ((BASE_WPM * u.rate).round() as i64).to_string();
+    if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("say");
+        if !u.voice.is_empty() {
+            cmd.args(["-v", &u.voice]);
+        }
+        cmd.args(["-r", &wpm, "--", &u.text]);
+        cmd
+    } else if cfg!(target_os = "windows") {
+        // The text goes through the environment so it is never parsed as
+        // PowerShell.
+        let sapi_rate = (((u.rate - 1.0) * 10.0).round() as i64).clamp(-10, 10);
+        let mut cmd = Command::new("powershell");
+        cmd.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+             if ($env:TTS_VOICE) { $s.SelectVoice($env:TTS_VOICE) }; \
+             $s.Rate = [int]$env:TTS_RATE; $s.Speak($env:TTS_TEXT)",
+        ])
+        .env("TTS_TEXT", &u.text)
+        .env("TTS_VOICE", &u.voice)
+        .env("TTS_RATE", sapi_rate.to_string());
+        cmd
+    } else {
+        let mut cmd = Command::new("espeak-ng");
+        if !u.voice.is_empty() {
+            cmd.args(["-v", &u.voice]);
+        }
+        cmd.args(["-s", &wpm, "--", &u.text]);
+        cmd
+    }
+}
+
+fn build_command(s: &TtsSettings, u: &Utterance) -> Result<Command, String> {
+    if s.engine != ENGINE_COMMAND {
+        return Ok(system_command(u));
+    }
+    let mut args = command_args(&s.command, u).into_iter();
+    let program = args.next().ok_or("TTS_COMMAND is not set")?;
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    Ok(cmd)
+}
+
+/// Speak `u` and wait until it finishes or is skipped.
+async fn run_utterance(s: &TtsSettings, u: &Utterance) -> Result<(), String> {
+    let mut child = build_command(s, u)?
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start TTS engine: {e}"))?;
+    tokio::select! {
+        status = child.wait() => {
+            let status = status.map_err(|e| e.to_string())?;
+            if !status.success() {
+                return Err(format!("TTS engine exited with {status}"));
+            }
+        }
+        _ = SKIP.notified() => {
+            let _ = child.kill().await;
+            tracing::debug!("TTS utterance skipped");
+        }
+    }
+    Ok(())
+}
+
+/// Background worker speaking queued utterances in order.
+pub async fn run(state: SharedState) {
+    loop {
+        let next = if PAUSED.load(Ordering::Relaxed) {
+            None
+        } else {
+            queue().pop_front()
+        };
+        let Some(utterance) = next else {
+            WAKE.notified().await;
+            continue;
+        };
+        let settings = load_settings(&state);
+        if !settings.enabled {
+            // Turned off since the utterance was queued.
+            continue;
+        }
+        *speaking() = Some(utterance.clone());
+        if let Err(e) = run_utterance(&settings, &utterance).await {
+            tracing::warn!("{e}");
+        }
+        *speaking() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args_replace_placeholders() {
+        let u = Utterance {
+            source: Source::Chat,
+            text: "こんにちは 世界".into(),
+            voice: "alice".into(),
+            rate: 1.25,
+        };
+        assert_eq!(
+            command_args("tts-cli --voice={voice} -r {rate} {text}", &u),
+            vec!["tts-cli", "--voice=alice", "-r", "1.25", "こんにちは 世界"]
+        );
+    }
+
+    #[test]
+    fn test_truncate_counts_characters() {
+        assert_eq!(truncate("あいうえお", 3), "あいう");
+        assert_eq!(truncate("abc", 10), "abc");
+    }
+}
@@ -0,0 +1,89 @@
+//! Sending chat messages to the broadcaster's own channel, and whispers.
+//!
+//! Plain messages and announcements go through Helix. Action (`/me`)
+//! messages have no Helix endpoint and are sent over the IRC relay.
+
+use twitch_client::api::ANNOUNCEMENT_COLORS;
+
+use crate::app::SharedState;
+use crate::services::{helix, irc_relay};
+
+/// Send `message` to the configured channel as the broadcaster.
+pub async fn send_chat(state: &SharedState, message: &str) -> Result<(), String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("message is empty".into());
+    }
+
+    let ctx = helix::context(state).await?;
+    let sent = ctx
+        .api
+        .send_chat_message(
+            &ctx.token,
+            &ctx.broadcaster_id,
+            &ctx.broadcaster_id,
+            message,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !sent.is_sent {
+        return Err(format!("message dropped: {:?}", sent.drop_reason));
+    }
+    Ok(())
+}
+
+/// Post `message` as an announcement in the configured channel. `color` is
+/// one of [`ANNOUNCEMENT_COLORS`]; `None` uses the channel's accent color.
+pub async fn send_announcement(
+    state: &SharedState,
+    message: &str,
+    color: Option<&str>,
+) -> Result<(), String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("message is empty".into());
+    }
+    if let Some(color) = color.filter(|c| !ANNOUNCEMENT_COLORS.contains(c)) {
+        return Err(format!(
+            "unknown announcement color '{color}' ({})",
+            ANNOUNCEMENT_COLORS.join(", ")
+        ));
+    }
+
+    let ctx = helix::context(state).await?;
+    ctx.api
+        .send_announcement(
+            &ctx.token,
+            &ctx.broadcaster_id,
+            &ctx.broadcaster_id,
+            message,
+            color,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Send `message` as an action (`/me`) in the configured channel. Needs the
+/// IRC relay to be connected.
+pub fn send_action(state: &SharedState, message: &str) -> Result<(), String> {
+    let login = irc_relay::status(state).login;
+    if login.is_empty() {
+        return Err("IRC relay is not connected".into());
+    }
+    irc_relay::send_action(&login, message)
+}
+
+/// Whisper `message` to `user_id` as the broadcaster.
+pub async fn send_whisper(state: &SharedState, user_id: &str, message: &str) -> Result<(), String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("message is empty".into());
+    }
+
+    let ctx = helix::context(state).await?;
+    ctx.api
+        .send_whisper(&ctx.token, &ctx.broadcaster_id, user_id, message)
+        .await
+        .map_err(|e| e.to_string())
+}
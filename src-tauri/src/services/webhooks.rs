@@ -0,0 +1,268 @@
+//! Outbound webhooks: forward every EventSub notification to user URLs.
+//!
+//! Each enabled webhook whose `event_types` filter matches (an empty filter
+//! matches everything) gets a delivery queued in `webhook_deliveries`. The
+//! worker POSTs the JSON body
+//! `{ "event_type", "payload", "received_at" }` with the headers
+//!
+//! - `X-Overlay-Event`: the EventSub type
+//! - `X-Overlay-Delivery`: the delivery ID, stable across retries
+//! - `X-Overlay-Signature`: `sha256=<hex HMAC-SHA256 of the body>`, only
+//!   when the webhook has a secret
+//!
+//! A failed delivery is retried with exponential backoff (10 s, 20 s, ...
+//! up to 10 min); after `MAX_ATTEMPTS` it is kept as a failed delivery until
+//! it is retried or cleared.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use overlay_db::webhooks::{STATUS_PENDING, Webhook, WebhookDelivery};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use tokio::sync::Notify;
+
+use crate::app::SharedState;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Pending deliveries kept at most; events beyond it are dropped.
+const QUEUE_CAPACITY: i64 = 1000;
+/// Attempts before a delivery is moved to the failed deliveries.
+const MAX_ATTEMPTS: i64 = 8;
+const RETRY_BASE_SECS: i64 = 10;
+const RETRY_MAX_SECS: i64 = 600;
+/// Deliveries loaded per worker pass.
+const BATCH_SIZE: i64 = 20;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Event type of the request sent by [`send_test`].
+pub const TEST_EVENT: &str = "webhook.test";
+
+/// Wakes the worker when a delivery is queued.
+static WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Seconds to wait before attempt `attempts + 1`, doubling per failure.
+fn retry_delay_secs(attempts: i64) -> i64 {
+    let doublings = attempts.saturating_sub(1).clamp(0, 10) as u32;
+    (RETRY_BASE_SECS << doublings).min(RETRY_MAX_SECS)
+}
+
+fn db_err(e: overlay_db::DbError) -> String {
+    format!("Webhook database error: {e}")
+}
+
+/// `sha256=<hex>` signature of `body`, or `None` without a secret.
+pub fn signature(secret: &str, body: &str) -> Option<String> {
+    if secret.is_empty() {
+        return None;
+    }
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    Some(format!(
+        "sha256={}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+fn request_body(event_type: &str, payload: &Value) -> String {
+    json!({
+        "event_type": event_type,
+        "payload": payload,
+        "received_at": chrono::Utc::now().to_rfc3339(),
+    })
+    .to_string()
+}
+
+/// Queue a delivery of one notification to every matching webhook.
+pub fn forward(state: &SharedState, event_type: &str, payload: &Value) {
+    let db = state.db();
+    let hooks = match db.get_webhooks() {
+        Ok(hooks) => hooks,
+        Err(e) => {
+            tracing::warn!("{}", db_err(e));
+            return;
+        }
+    };
+    let targets: Vec<&Webhook> = hooks.iter().filter(|h| h.accepts(event_type)).collect();
+    if targets.is_empty() {
+        return;
+    }
+    match db.count_webhook_deliveries(STATUS_PENDING) {
+        Ok(pending) if pending + targets.len() as i64 > QUEUE_CAPACITY => {
+            tracing::warn!(
+                event_type,
+                "Webhook queue full ({QUEUE_CAPACITY}); event dropped"
+            );
+            return;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!("{}", db_err(e));
+            return;
+        }
+    }
+    let body = request_body(event_type, payload);
+    let now = chrono::Utc::now().timestamp();
+    for hook in targets {
+        if let Err(e) = db.add_webhook_delivery(hook.id, event_type, &body, now) {
+            tracing::warn!(webhook = %hook.name, "{}", db_err(e));
+        }
+    }
+    WAKE.notify_one();
+}
+
+async fn post(
+    http: &reqwest::Client,
+    hook: &Webhook,
+    event_type: &str,
+    delivery_id: &str,
+    body: &str,
+) -> Result<(), String> {
+    let mut request = http
+        .post(&hook.url)
+        .timeout(HTTP_TIMEOUT)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Overlay-Event", event_type)
+        .header("X-Overlay-Delivery", delivery_id)
+        .body(body.to_string());
+    if let Some(sig) = signature(&hook.secret, body) {
+        request = request.header("X-Overlay-Signature", sig);
+    }
+    request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// POST a test event to a webhook right away, bypassing the queue.
+pub async fn send_test(state: &SharedState, hook: &Webhook) -> Result<(), String> {
+    let body = request_body(TEST_EVENT, &json!({ "webhook_id": hook.id }));
+    let delivery_id = format!("test-{}", chrono::Utc::now().timestamp_millis());
+    post(&state.http(), hook, TEST_EVENT, &delivery_id, &body).await
+}
+
+/// Wait until a delivery may be due: woken by a new one, or when the
+/// earliest backed-off delivery is due.
+async fn wait_for_work(state: &SharedState) {
+    let now = chrono::Utc::now().timestamp();
+    let wait = match state.db().next_webhook_delivery_due() {
+        Ok(Some(due)) => (due - now).clamp(1, RETRY_MAX_SECS),
+        Ok(None) => RETRY_MAX_SECS,
+        Err(e) => {
+            tracing::error!("{}", db_err(e));
+            5
+        }
+    };
+    let _ = tokio::time::timeout(Duration::from_secs(wait as u64), WAKE.notified()).await;
+}
+
+/// Attempt one delivery and record the outcome.
+async fn deliver(
+    state: &SharedState,
+    http: &reqwest::Client,
+    hooks: &HashMap<i64, Webhook>,
+    delivery: &WebhookDelivery,
+) -> Result<(), overlay_db::DbError> {
+    let db = state.db();
+    let Some(hook) = hooks.get(&delivery.webhook_id).filter(|h| h.enabled) else {
+        // Disabled since the event was queued.
+        db.delete_webhook_delivery(delivery.id)?;
+        return Ok(());
+    };
+    let result = post(
+        http,
+        hook,
+        &delivery.event_type,
+        &delivery.id.to_string(),
+        &delivery.body,
+    )
+    .await;
+    let now = chrono::Utc::now().timestamp();
+    match result {
+        Ok(()) => db.delete_webhook_delivery(delivery.id).map(|_| ()),
+        Err(e) => {
+            let attempts = delivery.attempts + 1;
+            let retry_at = (attempts < MAX_ATTEMPTS).then(|| now + retry_delay_secs(attempts));
+            match retry_at {
+                Some(at) => tracing::warn!(
+                    webhook = %hook.name, error = %e, attempts,
+                    "Webhook delivery failed; retrying in {}s", at - now
+                ),
+                None => tracing::error!(
+                    webhook = %hook.name, error = %e, attempts,
+                    "Webhook delivery failed; moved to failed deliveries"
+                ),
+            }
+            db.fail_webhook_delivery(delivery.id, &e, retry_at, now)
+        }
+    }
+}
+
+/// Background worker delivering queued events. Deliveries left by a
+/// previous run are sent first.
+pub async fn run(state: SharedState) {
+    loop {
+        let due = match state
+            .db()
+            .due_webhook_deliveries(chrono::Utc::now().timestamp(), BATCH_SIZE)
+        {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::error!("{}", db_err(e));
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+        if due.is_empty() {
+            wait_for_work(&state).await;
+            continue;
+        }
+        let hooks: HashMap<i64, Webhook> = match state.db().get_webhooks() {
+            Ok(hooks) => hooks.into_iter().map(|h| (h.id, h)).collect(),
+            Err(e) => {
+                tracing::error!("{}", db_err(e));
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+        let http = state.http();
+        for delivery in &due {
+            if let Err(e) = deliver(&state, &http, &hooks, delivery).await {
+                tracing::error!("{}", db_err(e));
+            }
+        }
+    }
+}
+
+/// Let the worker pick up a delivery queued again by the API.
+pub fn wake() {
+    WAKE.notify_one();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        assert_eq!(signature("", "{}"), None);
+        // RFC 4231 test case 2.
+        assert_eq!(
+            signature("Jefe", "what do ya want for nothing?").as_deref(),
+            Some("sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay_secs(1), RETRY_BASE_SECS);
+        assert_eq!(retry_delay_secs(3), RETRY_BASE_SECS * 4);
+        assert_eq!(retry_delay_secs(20), RETRY_MAX_SECS);
+    }
+}
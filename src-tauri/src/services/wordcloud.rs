@@ -0,0 +1,191 @@
+//! Chat word cloud: tokenize stored chat and weight the terms.
+//!
+//! Only text fragments count (emotes, mentions and cheermotes are skipped),
+//! and a term counts once per message so a single spammer can't dominate.
+//! Japanese is split on script boundaries (kanji / katakana runs); hiragana
+//! runs are mostly particles and are dropped.
+
+use std::collections::{HashMap, HashSet};
+
+use overlay_db::chat::ChatMessage;
+use serde::Serialize;
+use serde_json::Value;
+use word_filter::WordMatcher;
+use word_filter::stopwords::is_stopword;
+
+pub const DEFAULT_LIMIT: usize = 50;
+pub const MAX_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WordCloudTerm {
+    pub text: String,
+    pub count: u32,
+    /// `count` relative to the most frequent term (0.0-1.0].
+    pub weight: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Script {
+    Word,
+    Han,
+    Katakana,
+    Hiragana,
+}
+
+fn script_of(c: char) -> Option<Script> {
+    match c {
+        '\u{3041}'..='\u{309F}' => Some(Script::Hiragana),
+        '\u{30A0}'..='\u{30FF}' | '\u{FF66}'..='\u{FF9F}' => Some(Script::Katakana),
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '々' => Some(Script::Han),
+        c if c.is_alphanumeric() || c == '\'' => Some(Script::Word),
+        _ => None,
+    }
+}
+
+/// Split chat text into lowercase candidate terms.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for chunk in text.split_whitespace() {
+        if chunk.starts_with("http://") || chunk.starts_with("https://") {
+            continue;
+        }
+        let mut current = String::new();
+        let mut current_script = None;
+        for c in chunk.chars() {
+            let script = script_of(c);
+            if script != current_script {
+                push_token(&mut tokens, &current, current_script);
+                current.clear();
+                current_script = script;
+            }
+            if script.is_some() {
+                current.push(c);
+            }
+        }
+        push_token(&mut tokens, &current, current_script);
+    }
+    tokens
+}
+
+fn push_token(tokens: &mut Vec<String>, raw: &str, script: Option<Script>) {
+    if matches!(script, None | Some(Script::Hiragana)) {
+        return;
+    }
+    let token = raw.trim_matches('\'').to_lowercase();
+    let len = token.chars().count();
+    // Too short, a bare number, or one repeated character ("wwww", "888").
+    if len < 2
+        || token.chars().all(|c| c.is_numeric())
+        || (len > 2 && token.chars().all(|c| token.starts_with(c)))
+    {
+        return;
+    }
+    tokens.push(token);
+}
+
+/// Text of a stored message without emotes, mentions and cheermotes.
+fn message_text(msg: &ChatMessage) -> String {
+    let Ok(Value::Array(fragments)) = serde_json::from_str::<Value>(&msg.fragments_json) else {
+        return msg.message.clone();
+    };
+    fragments
+        .iter()
+        .filter(|f| f["type"].as_str().unwrap_or("text") == "text")
+        .filter_map(|f| f["text"].as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Count terms over `messages`, most frequent first (ties alphabetical).
+pub fn build(
+    messages: &[ChatMessage],
+    languages: &[&str],
+    matcher: &WordMatcher,
+    limit: usize,
+) -> Vec<WordCloudTerm> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for msg in messages {
+        let unique: HashSet<String> = tokenize(&message_text(msg)).into_iter().collect();
+        for term in unique {
+            *counts.entry(term).or_default() += 1;
+        }
+    }
+
+    let mut terms: Vec<(String, u32)> = counts
+        .into_iter()
+        .filter(|(term, _)| !is_stopword(languages, term) && !matcher.is_blocked(term))
+        .collect();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    terms.truncate(limit);
+
+    let max = terms.first().map_or(1, |(_, c)| *c).max(1) as f32;
+    terms
+        .into_iter()
+        .map(|(text, count)| WordCloudTerm {
+            text,
+            count,
+            weight: count as f32 / max,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(text: &str, fragments: &str) -> ChatMessage {
+        ChatMessage {
+            id: 0,
+            message_id: String::new(),
+            user_id: String::new(),
+            username: "viewer".into(),
+            message: text.into(),
+            fragments_json: fragments.into(),
+            avatar_url: String::new(),
+            translation_text: String::new(),
+            translation_status: String::new(),
+            translation_lang: String::new(),
+            created_at: 0,
+            badges_json: String::new(),
+            color: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("GG! That's a nice clutch https://clips.twitch.tv/x 1234 wwww"),
+            vec!["gg", "that's", "nice", "clutch"]
+        );
+        assert_eq!(tokenize("今日のボス戦すごかった"), vec!["今日", "ボス"]);
+    }
+
+    #[test]
+    fn test_build_weights_and_filters() {
+        let matcher = WordMatcher::new(vec!["badword".into()], vec![]);
+        let messages = vec![
+            msg("clutch clutch the badword", ""),
+            msg(
+                "clutch Kappa",
+                r#"[{"type":"text","text":"clutch "},{"type":"emote","text":"Kappa"}]"#,
+            ),
+            msg("nice", ""),
+        ];
+        let terms = build(&messages, &["en"], &matcher, 10);
+        assert_eq!(
+            terms,
+            vec![
+                WordCloudTerm {
+                    text: "clutch".into(),
+                    count: 2,
+                    weight: 1.0,
+                },
+                WordCloudTerm {
+                    text: "nice".into(),
+                    count: 1,
+                    weight: 0.5,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Remote window control.
+//!
+//! Shared implementation behind the `/api/window/*` endpoints and the
+//! matching Tauri commands, so a Stream Deck or another device on the LAN
+//! can drive the desktop app's windows.
+
+use serde::Serialize;
+use tauri::{Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+
+use crate::app::SharedState;
+use crate::events;
+use crate::notification::window as notification_window;
+use crate::services::overlay_preview;
+use crate::window::stream_monitor;
+
+pub const MAIN_WINDOW_LABEL: &str = "main";
+pub const PREVIEW_WINDOW_LABEL: &str = "overlay-preview";
+
+/// Settings tabs that `open_settings_tab` accepts (mirrors SettingsPage.tsx).
+pub const SETTINGS_TABS: &[&str] = &[
+    "general", "mic", "twitch", "printer", "music", "overlay", "logs", "cache", "api",
+];
+
+const PREVIEW_WIDTH: f64 = 1280.0;
+const PREVIEW_HEIGHT: f64 = 720.0;
+
+/// Visibility snapshot of the windows this module controls.
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowStatus {
+    pub main_visible: bool,
+    pub preview_open: bool,
+    pub notification_visible: bool,
+    pub stream_monitor_visible: bool,
+    /// Notification time-to-display measurements.
+    pub notification_display: notification_window::DisplayStats,
+}
+
+/// Current visibility of the windows this module and the tray control.
+pub fn status(state: &SharedState) -> WindowStatus {
+    let Some(app) = state.app_handle() else {
+        return WindowStatus {
+            main_visible: false,
+            preview_open: false,
+            notification_visible: false,
+            stream_monitor_visible: false,
+            notification_display: notification_window::display_stats(),
+        };
+    };
+    let visible = |label: &str| {
+        app.get_webview_window(label)
+            .and_then(|w| w.is_visible().ok())
+            .unwrap_or(false)
+    };
+    WindowStatus {
+        main_visible: visible(MAIN_WINDOW_LABEL),
+        preview_open: app.get_webview_window(PREVIEW_WINDOW_LABEL).is_some(),
+        notification_visible: notification_window::is_visible(state),
+        stream_monitor_visible: visible(stream_monitor::STREAM_MONITOR_WINDOW_LABEL),
+        notification_display: notification_window::display_stats(),
+    }
+}
+
+/// Ensure the main (settings) window exists, creating it when missing.
+fn ensure_main_window(state: &SharedState) -> Result<WebviewWindow, String> {
+    let Some(app) = state.app_handle() else {
+        return Err("Tauri AppHandle is not initialized".to_string());
+    };
+    if let Some(existing) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        return Ok(existing);
+    }
+
+    let window = WebviewWindowBuilder::new(&app, MAIN_WINDOW_LABEL, WebviewUrl::App("/".into()))
+        .title("Cairo Overlay")
+        .build()
+        .map_err(|e| format!("Failed to create main window: {e}"))?;
+    crate::window::position::restore_window_state(&window, state.db());
+    Ok(window)
+}
+
+/// Show and focus the main window.
+pub fn show_main(state: &SharedState) -> Result<(), String> {
+    let window = ensure_main_window(state)?;
+    window
+        .show()
+        .map_err(|e| format!("Failed to show main window: {e}"))?;
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+    Ok(())
+}
+
+/// Hide the main window (the app keeps running in the background).
+pub fn hide_main(state: &SharedState) -> Result<(), String> {
+    let Some(app) = state.app_handle() else {
+        return Err("Tauri AppHandle is not initialized".to_string());
+    };
+    let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return Ok(());
+    };
+    window
+        .hide()
+        .map_err(|e| format!("Failed to hide main window: {e}"))
+}
+
+/// Show the main window and ask the frontend to switch to `tab`.
+pub fn open_settings_tab(state: &SharedState, tab: &str) -> Result<(), String> {
+    if !SETTINGS_TABS.contains(&tab) {
+        return Err(format!("Unknown settings tab: {tab}"));
+    }
+    show_main(state)?;
+    state.emit_event(
+        events::OPEN_SETTINGS_TAB,
+        events::SettingsTabPayload {
+            tab: tab.to_string(),
+        },
+    );
+    Ok(())
+}
+
+/// Open (or focus) a window rendering the local overlay in preview mode,
+/// with simulated test events.
+pub fn open_overlay_preview(state: &SharedState) -> Result<(), String> {
+    let Some(app) = state.app_handle() else {
+        return Err("Tauri AppHandle is not initialized".to_string());
+    };
+    if let Some(existing) = app.get_webview_window(PREVIEW_WINDOW_LABEL) {
+        existing
+            .show()
+            .map_err(|e| format!("Failed to show overlay preview: {e}"))?;
+        let _ = existing.set_focus();
+        return Ok(());
+    }
+
+    let url = format!(
+        "http://127.0.0.1:{}/overlay/?preview=1",
+        state.server_port()
+    );
+    let url = url
+        .parse()
+        .map_err(|e| format!("Invalid overlay URL {url}: {e}"))?;
+    let window = WebviewWindowBuilder::new(&app, PREVIEW_WINDOW_LABEL, WebviewUrl::External(url))
+        .title("Overlay Preview")
+        .inner_size(PREVIEW_WIDTH, PREVIEW_HEIGHT)
+        .build()
+        .map_err(|e| format!("Failed to create overlay preview: {e}"))?;
+
+    // Simulated events run only while the preview window is open.
+    tauri::async_runtime::spawn(overlay_preview::start());
+    window.on_window_event(|event| {
+        if matches!(event, WindowEvent::Destroyed) {
+            tauri::async_runtime::spawn(overlay_preview::stop());
+        }
+    });
+    Ok(())
+}
+
+/// Close the overlay preview window if it is open.
+pub fn close_overlay_preview(state: &SharedState) -> Result<(), String> {
+    let Some(app) = state.app_handle() else {
+        return Err("Tauri AppHandle is not initialized".to_string());
+    };
+    match app.get_webview_window(PREVIEW_WINDOW_LABEL) {
+        Some(window) => window
+            .close()
+            .map_err(|e| format!("Failed to close overlay preview: {e}")),
+        None => Ok(()),
+    }
+}
+
+/// Move the notification window.
+///
+/// With `screen_index` set, `x`/`y` are relative to that screen's origin;
+/// otherwise they are absolute desktop coordinates. The new position is
+/// persisted like a manual drag.
+pub fn move_notification(
+    state: &SharedState,
+    x: i32,
+    y: i32,
+    screen_index: Option<usize>,
+) -> Result<notification_window::NotificationPosition, String> {
+    notification_window::move_to(state, x, y, screen_index)
+}
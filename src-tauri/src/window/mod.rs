@@ -1,4 +1,8 @@
-//! Window management: monitor detection, position persistence, events.
+//! Window management: monitor detection, position persistence, events,
+//! remote control, and the tray menu.
 
+pub mod control;
 pub mod monitor;
 pub mod position;
+pub mod stream_monitor;
+pub mod tray;
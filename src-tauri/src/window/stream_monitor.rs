@@ -0,0 +1,151 @@
+//! Stream monitor window.
+//!
+//! A compact always-on-top mini dashboard (`/monitor` route) for streamers
+//! with a single screen. It reads the same WebSocket topics as the overlay;
+//! this module only owns the window itself and its saved geometry.
+
+use overlay_db::Database;
+use tauri::{
+    Manager, PhysicalPosition, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent,
+};
+
+use crate::app::SharedState;
+use crate::config::SettingsManager;
+use crate::window::monitor;
+
+pub const STREAM_MONITOR_WINDOW_LABEL: &str = "stream-monitor";
+
+const SETTING_X: &str = "STREAM_MONITOR_WINDOW_X";
+const SETTING_Y: &str = "STREAM_MONITOR_WINDOW_Y";
+const SETTING_WIDTH: &str = "STREAM_MONITOR_WINDOW_WIDTH";
+const SETTING_HEIGHT: &str = "STREAM_MONITOR_WINDOW_HEIGHT";
+const SETTING_OPEN_ON_STARTUP: &str = "STREAM_MONITOR_OPEN_ON_STARTUP";
+
+const DEFAULT_WIDTH: u32 = 320;
+const DEFAULT_HEIGHT: u32 = 480;
+
+/// Ensure the stream monitor window exists. Creates it in hidden state when missing.
+fn ensure_window(state: &SharedState) -> Result<WebviewWindow, String> {
+    let Some(app) = state.app_handle() else {
+        return Err("Tauri AppHandle is not initialized".to_string());
+    };
+
+    if let Some(existing) = app.get_webview_window(STREAM_MONITOR_WINDOW_LABEL) {
+        return Ok(existing);
+    }
+
+    let sm = SettingsManager::new(state.db().clone());
+    let width = read_u32(&sm, SETTING_WIDTH).unwrap_or(DEFAULT_WIDTH);
+    let height = read_u32(&sm, SETTING_HEIGHT).unwrap_or(DEFAULT_HEIGHT);
+    let window = WebviewWindowBuilder::new(
+        &app,
+        STREAM_MONITOR_WINDOW_LABEL,
+        WebviewUrl::App("/monitor".into()),
+    )
+    .title("Stream Monitor")
+    .visible(false)
+    .always_on_top(true)
+    .resizable(true)
+    .inner_size(width as f64, height as f64)
+    .build()
+    .map_err(|e| format!("Failed to create stream monitor window: {e}"))?;
+
+    restore_position(&window, &sm, width, height);
+    install_event_handlers(&window, state.db().clone());
+
+    tracing::info!("Stream monitor window created");
+    Ok(window)
+}
+
+/// Whether the stream monitor window is currently shown.
+pub fn is_visible(state: &SharedState) -> bool {
+    state
+        .app_handle()
+        .and_then(|app| app.get_webview_window(STREAM_MONITOR_WINDOW_LABEL))
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false)
+}
+
+/// Show the stream monitor window (creates it if needed).
+pub fn show(state: &SharedState) -> Result<(), String> {
+    let window = ensure_window(state)?;
+    window
+        .show()
+        .map_err(|e| format!("Failed to show stream monitor window: {e}"))
+}
+
+/// Hide the stream monitor window if it exists.
+pub fn hide(state: &SharedState) -> Result<(), String> {
+    let Some(app) = state.app_handle() else {
+        return Ok(());
+    };
+    let Some(window) = app.get_webview_window(STREAM_MONITOR_WINDOW_LABEL) else {
+        return Ok(());
+    };
+    window
+        .hide()
+        .map_err(|e| format!("Failed to hide stream monitor window: {e}"))
+}
+
+/// Toggle visibility; returns the new visibility.
+pub fn toggle(state: &SharedState) -> Result<bool, String> {
+    if is_visible(state) {
+        hide(state)?;
+        Ok(false)
+    } else {
+        show(state)?;
+        Ok(true)
+    }
+}
+
+/// Open the window at startup when `STREAM_MONITOR_OPEN_ON_STARTUP` is set.
+pub fn restore_on_startup(state: &SharedState) {
+    let sm = SettingsManager::new(state.db().clone());
+    if sm.get_setting(SETTING_OPEN_ON_STARTUP).unwrap_or_default() != "true" {
+        return;
+    }
+    if let Err(e) = show(state) {
+        tracing::warn!("{e}");
+    }
+}
+
+fn install_event_handlers(window: &WebviewWindow, db: Database) {
+    let tracked = window.clone();
+    window.on_window_event(move |event| {
+        if matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
+            persist_layout(&tracked, &db);
+        }
+    });
+}
+
+fn persist_layout(window: &WebviewWindow, db: &Database) {
+    let (Ok(pos), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    let sm = SettingsManager::new(db.clone());
+    let _ = sm.set_setting(SETTING_X, &pos.x.to_string());
+    let _ = sm.set_setting(SETTING_Y, &pos.y.to_string());
+    let _ = sm.set_setting(SETTING_WIDTH, &size.width.to_string());
+    let _ = sm.set_setting(SETTING_HEIGHT, &size.height.to_string());
+}
+
+/// Restore the saved position if it is still on a connected screen.
+fn restore_position(window: &WebviewWindow, sm: &SettingsManager, width: u32, height: u32) {
+    let x = sm.get_setting(SETTING_X).unwrap_or_default().parse::<i32>();
+    let y = sm.get_setting(SETTING_Y).unwrap_or_default().parse::<i32>();
+    let (Ok(x), Ok(y)) = (x, y) else {
+        return;
+    };
+    let screens = monitor::get_all_screens(&window.app_handle());
+    if monitor::find_screen_containing(&screens, x, y, width, height).is_some() {
+        let _ = window.set_position(PhysicalPosition::new(x, y));
+    }
+}
+
+fn read_u32(sm: &SettingsManager, key: &str) -> Option<u32> {
+    sm.get_setting(key)
+        .unwrap_or_default()
+        .parse::<u32>()
+        .ok()
+        .filter(|v| *v > 0)
+}
@@ -0,0 +1,48 @@
+//! System tray icon and menu.
+
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::TrayIconBuilder;
+
+use crate::app::SharedState;
+use crate::window::{control, stream_monitor};
+
+const MENU_TOGGLE_MONITOR: &str = "toggle_stream_monitor";
+const MENU_SHOW_MAIN: &str = "show_main";
+const MENU_QUIT: &str = "quit";
+
+/// Install the tray icon with its menu.
+pub fn install(app: &tauri::App, state: SharedState) -> tauri::Result<()> {
+    let toggle_monitor = MenuItem::with_id(
+        app,
+        MENU_TOGGLE_MONITOR,
+        "ストリームモニター",
+        true,
+        None::<&str>,
+    )?;
+    let show_main = MenuItem::with_id(app, MENU_SHOW_MAIN, "設定を表示", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, MENU_QUIT, "終了", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&toggle_monitor, &show_main, &quit])?;
+
+    let mut builder = TrayIconBuilder::with_id("main")
+        .tooltip("Cairo Overlay")
+        .menu(&menu)
+        .on_menu_event(move |app, event| {
+            let result = match event.id.as_ref() {
+                MENU_TOGGLE_MONITOR => stream_monitor::toggle(&state).map(|_| ()),
+                MENU_SHOW_MAIN => control::show_main(&state),
+                MENU_QUIT => {
+                    app.exit(0);
+                    Ok(())
+                }
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                tracing::warn!("Tray action failed: {e}");
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
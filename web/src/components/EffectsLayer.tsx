@@ -0,0 +1,140 @@
+import React, { useEffect, useState } from 'react';
+import Confetti from 'react-confetti';
+import { getWebSocketClient } from '../utils/websocket';
+
+type EffectName = 'confetti' | 'fireworks' | 'screen_shake';
+
+interface OverlayEffect {
+  id: string;
+  effect: EffectName;
+  params: Record<string, any>;
+  duration_ms: number;
+}
+
+const DEFAULT_COLORS = ['#9147ff', '#ff4f9a', '#ffd23f', '#3ddc97', '#4fc3f7'];
+const FADE_OUT_MS = 3000; // 放出停止後、落下中の粒子が消えるまでの猶予
+const SHAKE_CLASS = 'screen-shake';
+
+const colorsOf = (params: Record<string, any>): string[] =>
+  Array.isArray(params.colors) && params.colors.length > 0 ? params.colors : DEFAULT_COLORS;
+
+/**
+ * 花火の1発分（delay 後に打ち上げ）
+ */
+const FireworkBurst: React.FC<{ delay: number; colors: string[]; particles: number }> = ({
+  delay,
+  colors,
+  particles,
+}) => {
+  const [visible, setVisible] = useState(false);
+  const [origin] = useState(() => ({
+    x: window.innerWidth * (0.15 + Math.random() * 0.7),
+    y: window.innerHeight * (0.15 + Math.random() * 0.35),
+  }));
+
+  useEffect(() => {
+    const timer = setTimeout(() => setVisible(true), delay);
+    return () => clearTimeout(timer);
+  }, [delay]);
+
+  if (!visible) return null;
+  return (
+    <Confetti
+      width={window.innerWidth}
+      height={window.innerHeight}
+      recycle={false}
+      numberOfPieces={particles}
+      confettiSource={{ x: origin.x, y: origin.y, w: 10, h: 10 }}
+      initialVelocityX={{ min: -12, max: 12 }}
+      initialVelocityY={{ min: -14, max: 8 }}
+      gravity={0.15}
+      colors={colors}
+      tweenDuration={100}
+    />
+  );
+};
+
+/**
+ * EffectsLayer component
+ * overlay_effect イベントで紙吹雪・花火・画面揺れを表示する
+ */
+export const EffectsLayer: React.FC = () => {
+  const [effects, setEffects] = useState<OverlayEffect[]>([]);
+
+  useEffect(() => {
+    const wsClient = getWebSocketClient();
+    const timers = new Set<ReturnType<typeof setTimeout>>();
+    const schedule = (fn: () => void, ms: number) => {
+      const timer = setTimeout(() => {
+        timers.delete(timer);
+        fn();
+      }, ms);
+      timers.add(timer);
+    };
+
+    const unsubEffect = wsClient.on('overlay_effect', (data: OverlayEffect) => {
+      console.log('[EffectsLayer] Effect received:', data);
+
+      if (data.effect === 'screen_shake') {
+        const root = document.getElementById('root');
+        if (!root) return;
+        const intensity = Number(data.params?.intensity) || 8;
+        root.style.setProperty('--shake-intensity', `${intensity}px`);
+        root.classList.add(SHAKE_CLASS);
+        schedule(() => root.classList.remove(SHAKE_CLASS), data.duration_ms);
+        return;
+      }
+
+      setEffects((prev) => [...prev, data]);
+      schedule(
+        () => setEffects((prev) => prev.filter((e) => e.id !== data.id)),
+        data.duration_ms + FADE_OUT_MS,
+      );
+    });
+
+    return () => {
+      unsubEffect();
+      timers.forEach(clearTimeout);
+      document.getElementById('root')?.classList.remove(SHAKE_CLASS);
+    };
+  }, []);
+
+  return (
+    <div className="fixed inset-0 pointer-events-none z-50">
+      {effects.map((effect) => {
+        const params = effect.params ?? {};
+        const colors = colorsOf(params);
+
+        if (effect.effect === 'fireworks') {
+          const bursts = Math.max(1, Math.min(20, Number(params.bursts) || 5));
+          const interval = effect.duration_ms / bursts;
+          return (
+            <React.Fragment key={effect.id}>
+              {Array.from({ length: bursts }, (_, i) => (
+                <FireworkBurst
+                  key={i}
+                  delay={i * interval}
+                  colors={colors}
+                  particles={Number(params.particles) || 120}
+                />
+              ))}
+            </React.Fragment>
+          );
+        }
+
+        return (
+          <Confetti
+            key={effect.id}
+            width={window.innerWidth}
+            height={window.innerHeight}
+            recycle={false}
+            numberOfPieces={Number(params.particles) || 400}
+            gravity={Number(params.gravity) || 0.2}
+            colors={colors}
+            tweenDuration={effect.duration_ms}
+          />
+        );
+      })}
+    </div>
+  );
+};
@@ -0,0 +1,88 @@
+import React, { useEffect, useState } from 'react';
+import { getWebSocketClient } from '../utils/websocket';
+
+interface EmoteRainEvent {
+  emote_id: string;
+  emote_name: string;
+  url: string;
+  count: number;
+  intensity: number;
+}
+
+interface Drop {
+  key: string;
+  url: string;
+  left: number;
+  size: number;
+  delay: number;
+  duration: number;
+}
+
+const DROPS_PER_INTENSITY = 12;
+const MAX_DELAY_MS = 2500;
+const MAX_FALL_MS = 4500;
+
+const makeDrops = (data: EmoteRainEvent, rainId: number): Drop[] => {
+  const intensity = Math.max(1, Math.min(5, Number(data.intensity) || 1));
+  return Array.from({ length: intensity * DROPS_PER_INTENSITY }, (_, i) => ({
+    key: `${rainId}-${i}`,
+    url: data.url,
+    left: Math.random() * 100,
+    size: 48 + Math.random() * 40,
+    delay: Math.random() * MAX_DELAY_MS,
+    duration: 2500 + Math.random() * (MAX_FALL_MS - 2500),
+  }));
+};
+
+/**
+ * EmoteRain component
+ * emote_rain イベントでエモートを画面上から降らせる
+ */
+export const EmoteRain: React.FC = () => {
+  const [drops, setDrops] = useState<Drop[]>([]);
+
+  useEffect(() => {
+    const wsClient = getWebSocketClient();
+    const timers = new Set<ReturnType<typeof setTimeout>>();
+    let rainId = 0;
+
+    const unsubRain = wsClient.on('emote_rain', (data: EmoteRainEvent) => {
+      console.log('[EmoteRain] Rain received:', data);
+      rainId += 1;
+      const batch = makeDrops(data, rainId);
+      const prefix = `${rainId}-`;
+      setDrops((prev) => [...prev, ...batch]);
+
+      const timer = setTimeout(() => {
+        timers.delete(timer);
+        setDrops((prev) => prev.filter((d) => !d.key.startsWith(prefix)));
+      }, MAX_DELAY_MS + MAX_FALL_MS);
+      timers.add(timer);
+    });
+
+    return () => {
+      unsubRain();
+      timers.forEach(clearTimeout);
+    };
+  }, []);
+
+  return (
+    <div className="fixed inset-0 pointer-events-none overflow-hidden z-40">
+      {drops.map((drop) => (
+        <img
+          key={drop.key}
+          src={drop.url}
+          alt=""
+          className="emote-rain-drop"
+          style={{
+            left: `${drop.left}%`,
+            width: drop.size,
+            height: drop.size,
+            animationDelay: `${drop.delay}ms`,
+            animationDuration: `${drop.duration}ms`,
+          }}
+        />
+      ))}
+    </div>
+  );
+};
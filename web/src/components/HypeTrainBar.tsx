@@ -0,0 +1,84 @@
+import React, { useEffect, useRef, useState } from 'react';
+import { getWebSocketClient } from '../utils/websocket';
+
+interface TopContributor {
+  user_login: string;
+  user_name: string;
+  type: string;
+  total: number;
+}
+
+interface HypeTrainUpdate {
+  phase: 'begin' | 'progress' | 'end';
+  id: string;
+  level: number;
+  total: number;
+  progress: number;
+  goal: number;
+  percent: number;
+  type: string;
+  top_contributors: TopContributor[];
+  expires_at: string | null;
+  ended_at: string | null;
+}
+
+// 終了後に最終レベルを表示し続ける時間
+const HIDE_AFTER_END_MS = 10000;
+
+/**
+ * HypeTrainBar component
+ * hype_train イベントでハイプトレインのレベルと進捗をバーで表示する
+ */
+export const HypeTrainBar: React.FC = () => {
+  const [train, setTrain] = useState<HypeTrainUpdate | null>(null);
+  const hideTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+
+  useEffect(() => {
+    const wsClient = getWebSocketClient();
+
+    const unsub = wsClient.on('hype_train', (data: HypeTrainUpdate) => {
+      if (hideTimer.current) {
+        clearTimeout(hideTimer.current);
+        hideTimer.current = null;
+      }
+      setTrain(data);
+      if (data.phase === 'end') {
+        hideTimer.current = setTimeout(() => setTrain(null), HIDE_AFTER_END_MS);
+      }
+    });
+
+    return () => {
+      unsub();
+      if (hideTimer.current) clearTimeout(hideTimer.current);
+    };
+  }, []);
+
+  if (!train) return null;
+
+  const ended = train.phase === 'end';
+
+  return (
+    <div className="fixed top-8 left-1/2 -translate-x-1/2 w-[32rem] pointer-events-none z-30 rounded-lg bg-black/70 p-4 text-white">
+      <div className="flex items-baseline justify-between mb-2">
+        <p className="font-bold text-lg">
+          ハイプトレイン レベル {train.level}
+          {ended && ' 達成！'}
+        </p>
+        {!ended && <span className="text-sm">{train.percent}%</span>}
+      </div>
+      {!ended && (
+        <div className="h-3 rounded bg-white/20 overflow-hidden">
+          <div
+            className="h-full rounded bg-purple-500 transition-all duration-500"
+            style={{ width: `${train.percent}%` }}
+          />
+        </div>
+      )}
+      {train.top_contributors.length > 0 && (
+        <p className="mt-2 text-xs opacity-80">
+          {train.top_contributors.map((c) => c.user_name).join(' / ')}
+        </p>
+      )}
+    </div>
+  );
+};
@@ -0,0 +1,165 @@
+import React, { useEffect, useRef, useState } from 'react';
+import { getWebSocketClient } from '../utils/websocket';
+
+interface PollChoice {
+  id: string;
+  title: string;
+  votes: number;
+  channel_points_votes: number;
+}
+
+interface PollUpdate {
+  phase: 'begin' | 'progress' | 'end';
+  poll: { id: string; title: string; status: string; choices: PollChoice[]; ends_at: number | null };
+  total_votes: number;
+}
+
+interface PredictionOutcome {
+  id: string;
+  title: string;
+  color: string;
+  users: number;
+  channel_points: number;
+}
+
+interface PredictionUpdate {
+  phase: 'begin' | 'progress' | 'lock' | 'end';
+  prediction: {
+    id: string;
+    title: string;
+    status: string;
+    outcomes: PredictionOutcome[];
+    winning_outcome_id: string | null;
+  };
+  total_users: number;
+  total_points: number;
+}
+
+interface Bar {
+  id: string;
+  label: string;
+  value: number;
+  detail: string;
+  color: string;
+  highlight: boolean;
+}
+
+interface Panel {
+  title: string;
+  status: string;
+  bars: Bar[];
+  total: number;
+}
+
+// 終了後に結果を表示し続ける時間
+const HIDE_AFTER_END_MS = 15000;
+
+const STATUS_LABELS: Record<string, string> = {
+  active: '受付中',
+  locked: '締め切り',
+  completed: '終了',
+  resolved: '結果確定',
+  canceled: 'キャンセル',
+  terminated: '終了',
+  archived: '終了',
+};
+
+const pollPanel = ({ poll, total_votes }: PollUpdate): Panel => {
+  const top = Math.max(0, ...poll.choices.map((c) => c.votes));
+  return {
+    title: poll.title,
+    status: poll.status,
+    total: total_votes,
+    bars: poll.choices.map((c) => ({
+      id: c.id,
+      label: c.title,
+      value: c.votes,
+      detail: `${c.votes}票`,
+      color: '#9147ff',
+      highlight: poll.status !== 'active' && top > 0 && c.votes === top,
+    })),
+  };
+};
+
+const predictionPanel = ({ prediction, total_points }: PredictionUpdate): Panel => ({
+  title: prediction.title,
+  status: prediction.status,
+  total: total_points,
+  bars: prediction.outcomes.map((o) => ({
+    id: o.id,
+    label: o.title,
+    value: o.channel_points,
+    detail: `${o.users}人 / ${o.channel_points.toLocaleString()}pt`,
+    color: o.color === 'pink' ? '#f5009b' : '#387aff',
+    highlight: prediction.winning_outcome_id === o.id,
+  })),
+});
+
+/**
+ * PollBars component
+ * poll_update / prediction_update イベントで投票・予想の途中経過をバーで表示する
+ */
+export const PollBars: React.FC = () => {
+  const [panel, setPanel] = useState<Panel | null>(null);
+  const hideTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+
+  useEffect(() => {
+    const wsClient = getWebSocketClient();
+
+    const show = (next: Panel, ended: boolean) => {
+      if (hideTimer.current) {
+        clearTimeout(hideTimer.current);
+        hideTimer.current = null;
+      }
+      setPanel(next);
+      if (ended) {
+        hideTimer.current = setTimeout(() => setPanel(null), HIDE_AFTER_END_MS);
+      }
+    };
+
+    const unsubPoll = wsClient.on('poll_update', (data: PollUpdate) => {
+      show(pollPanel(data), data.phase === 'end');
+    });
+    const unsubPrediction = wsClient.on('prediction_update', (data: PredictionUpdate) => {
+      show(predictionPanel(data), data.phase === 'end');
+    });
+
+    return () => {
+      unsubPoll();
+      unsubPrediction();
+      if (hideTimer.current) clearTimeout(hideTimer.current);
+    };
+  }, []);
+
+  if (!panel) return null;
+
+  return (
+    <div className="fixed bottom-8 left-8 w-96 pointer-events-none z-30 rounded-lg bg-black/70 p-4 text-white">
+      <div className="flex items-baseline justify-between mb-3">
+        <p className="font-bold text-lg">{panel.title}</p>
+        <span className="text-xs opacity-80">{STATUS_LABELS[panel.status] ?? panel.status}</span>
+      </div>
+      <div className="space-y-2">
+        {panel.bars.map((bar) => {
+          const percent = panel.total > 0 ? Math.round((bar.value / panel.total) * 100) : 0;
+          return (
+            <div key={bar.id}>
+              <div className="flex justify-between text-sm">
+                <span className={bar.highlight ? 'font-bold' : undefined}>{bar.label}</span>
+                <span>
+                  {percent}% <span className="opacity-70">({bar.detail})</span>
+                </span>
+              </div>
+              <div className="h-3 rounded bg-white/20 overflow-hidden">
+                <div
+                  className="h-full rounded transition-all duration-500"
+                  style={{ width: `${percent}%`, backgroundColor: bar.color }}
+                />
+              </div>
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+};
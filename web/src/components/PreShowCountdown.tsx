@@ -0,0 +1,93 @@
+import React, { useEffect, useRef, useState } from 'react';
+import { buildApiUrl } from '../utils/api';
+import { getWebSocketClient } from '../utils/websocket';
+
+interface PreShowState {
+  phase: 'idle' | 'pre_show' | 'live';
+  title: string;
+  ends_at: number | null;
+  started_at: number | null;
+  live_at: number | null;
+  playlist: string | null;
+  suppressed_alerts: number;
+}
+
+// 配信開始後に「配信開始！」を表示し続ける時間
+const HIDE_AFTER_LIVE_MS = 5000;
+
+const formatRemaining = (seconds: number) => {
+  const m = Math.floor(seconds / 60);
+  const s = seconds % 60;
+  return `${m}:${s.toString().padStart(2, '0')}`;
+};
+
+/**
+ * PreShowCountdown component
+ * pre_show イベントで配信開始前のカウントダウンを表示し、配信開始で切り替える
+ */
+export const PreShowCountdown: React.FC = () => {
+  const [preShow, setPreShow] = useState<PreShowState | null>(null);
+  const [now, setNow] = useState(() => Date.now());
+  const hideTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+
+  useEffect(() => {
+    const apply = (data: PreShowState) => {
+      if (hideTimer.current) {
+        clearTimeout(hideTimer.current);
+        hideTimer.current = null;
+      }
+      if (data.phase === 'idle') {
+        setPreShow(null);
+        return;
+      }
+      setPreShow(data);
+      if (data.phase === 'live') {
+        hideTimer.current = setTimeout(() => setPreShow(null), HIDE_AFTER_LIVE_MS);
+      }
+    };
+
+    // オーバーレイ再読み込み時に進行中のプレショーを復元
+    fetch(buildApiUrl('/api/stream/pre-show'))
+      .then((res) => (res.ok ? res.json() : null))
+      .then((data) => {
+        if (data?.pre_show?.phase === 'pre_show') apply(data.pre_show);
+      })
+      .catch((err) => console.error('Failed to load pre-show state:', err));
+
+    const unsub = getWebSocketClient().on('pre_show', apply);
+
+    return () => {
+      unsub();
+      if (hideTimer.current) clearTimeout(hideTimer.current);
+    };
+  }, []);
+
+  const counting = preShow?.phase === 'pre_show';
+
+  useEffect(() => {
+    if (!counting) return;
+    const timer = setInterval(() => setNow(Date.now()), 1000);
+    return () => clearInterval(timer);
+  }, [counting]);
+
+  if (!preShow) return null;
+
+  const remaining = preShow.ends_at ? Math.max(0, preShow.ends_at - Math.floor(now / 1000)) : 0;
+
+  return (
+    <div className="fixed inset-x-0 top-1/3 flex justify-center pointer-events-none z-30">
+      <div className="rounded-lg bg-black/70 px-10 py-6 text-center text-white">
+        {counting ? (
+          <>
+            <p className="text-2xl font-bold">{preShow.title}</p>
+            <p className="mt-2 text-6xl font-bold tabular-nums">
+              {remaining > 0 ? formatRemaining(remaining) : 'まもなく'}
+            </p>
+          </>
+        ) : (
+          <p className="text-4xl font-bold">配信開始！</p>
+        )}
+      </div>
+    </div>
+  );
+};
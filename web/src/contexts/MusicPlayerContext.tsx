@@ -1,4 +1,4 @@
-import React, { createContext, useContext, useEffect } from 'react';
+import React, { createContext, useContext, useEffect, useRef } from 'react';
 import { useMusicPlayer } from '../hooks/useMusicPlayer';
 import { getWebSocketClient } from '../utils/websocket';
 import { useSettings } from './SettingsContext';
@@ -23,6 +23,8 @@ const MusicPlayerContext = createContext<MusicPlayerContextValue | null>(null);
 export const MusicPlayerProvider = ({ children }: { children: React.ReactNode }) => {
   const { settings } = useSettings();
   const player = useMusicPlayer(settings?.music_volume);
+  // load_playlist の autoplay 指定で、読み込み完了後に再生する
+  const autoplayRef = useRef(false);
 
   // APIからの制御を受け付ける
   useEffect(() => {
@@ -55,6 +57,7 @@ export const MusicPlayerProvider = ({ children }: { children: React.ReactNode })
           break;
         case 'load_playlist':
           if (command.playlist) {
+            autoplayRef.current = command.autoplay === true;
             player.loadPlaylist(command.playlist);
           }
           break;
@@ -71,6 +74,13 @@ export const MusicPlayerProvider = ({ children }: { children: React.ReactNode })
     };
   }, [player.play, player.pause, player.stop, player.next, player.previous, player.setVolume, player.loadPlaylist, player.seek]);
 
+  useEffect(() => {
+    if (autoplayRef.current && !player.isLoading && player.playlist.length > 0) {
+      autoplayRef.current = false;
+      player.play();
+    }
+  }, [player.isLoading, player.playlist, player.play]);
+
   return (
     <MusicPlayerContext.Provider value={player}>
       {children}
@@ -0,0 +1,41 @@
+import { useEffect, useState } from 'react';
+import { buildApiUrl } from '../utils/api';
+
+export interface OverlayClaims {
+  widget: string;
+  layout: string;
+  topics?: string[];
+  issued_at: number;
+  /** セッショントークンのみ（配信終了で失効） */
+  session?: string;
+  expires_at?: number;
+}
+
+/**
+ * URL の ?token= （署名付きオーバーレイ URL）を取得
+ */
+export const getOverlayToken = (): string | null =>
+  new URLSearchParams(window.location.search).get('token');
+
+/**
+ * 署名付きトークンのクレーム（表示するウィジェットとレイアウト）を取得する
+ * トークンがない場合は null（すべてのウィジェットを表示）
+ */
+export const useOverlayClaims = (): OverlayClaims | null => {
+  const [claims, setClaims] = useState<OverlayClaims | null>(null);
+
+  useEffect(() => {
+    const token = getOverlayToken();
+    if (!token) return;
+
+    fetch(buildApiUrl(`/api/overlay/token?token=${encodeURIComponent(token)}`))
+      .then((response) => {
+        if (!response.ok) throw new Error(`HTTP ${response.status}`);
+        return response.json();
+      })
+      .then((data: OverlayClaims) => setClaims(data))
+      .catch((error) => console.error('[useOverlayClaims] Invalid overlay token:', error));
+  }, []);
+
+  return claims;
+};
@@ -1,17 +1,27 @@
 import React, { useEffect, useState } from 'react';
 import { CustomFontLoader } from '../components/CustomFontLoader';
+import { EffectsLayer } from '../components/EffectsLayer';
+import { EmoteRain } from '../components/EmoteRain';
 import FaxReceiver from '../components/FaxReceiver';
+import { HypeTrainBar } from '../components/HypeTrainBar';
 import { MicTranscriptOverlay } from '../components/MicTranscriptOverlay';
 import { Toaster } from 'sonner';
 import { ParticipantTicker } from '../components/ParticipantTicker';
+import { PollBars } from '../components/PollBars';
+import { PreShowCountdown } from '../components/PreShowCountdown';
 import { useSettings } from '../contexts/SettingsContext';
+import { useOverlayClaims } from '../hooks/useOverlayClaims';
 import { useWebSocket } from '../hooks/useWebSocket';
 import { buildApiUrl } from '../utils/api';
+import { getWebSocketClient } from '../utils/websocket';
 import type { PresentParticipant } from './present/PresentPage';
 
 export const MainOverlay: React.FC = () => {
   const { settings } = useSettings();
+  const claims = useOverlayClaims();
   const [participants, setParticipants] = useState<PresentParticipant[]>([]);
+  // パニックボタンで非表示にされたウィジェット
+  const [panicHidden, setPanicHidden] = useState<string[]>([]);
 
   // WebSocket接続を確立してプレゼント参加者の更新を監視
   const { isConnected } = useWebSocket({
@@ -63,6 +73,18 @@ export const MainOverlay: React.FC = () => {
     fetchParticipants();
   }, []);
 
+  // パニックボタンの状態を取得・監視
+  useEffect(() => {
+    const apply = (data: { hidden_widgets?: string[] }) => setPanicHidden(data?.hidden_widgets ?? []);
+
+    fetch(buildApiUrl('/api/system/panic'))
+      .then((res) => (res.ok ? res.json() : null))
+      .then((data) => data && apply(data))
+      .catch((error) => console.error('[MainOverlay] Failed to fetch panic state:', error));
+
+    return getWebSocketClient().on('panic', apply);
+  }, []);
+
   // 参加者stateの変更を監視
   useEffect(() => {
     console.log('[MainOverlay] Participants state updated:', {
@@ -83,18 +105,30 @@ export const MainOverlay: React.FC = () => {
     });
   }, [settings?.lottery_ticker_enabled, participants]);
 
+  // 署名付き URL ではトークンで指定されたウィジェットのみ表示
+  // パニック中はチャット由来のウィジェットを隠す
+  const shows = (widget: string) =>
+    !panicHidden.includes(widget) && (!claims || claims.widget === 'all' || claims.widget === widget);
+
   return (
-    <>
+    <div data-layout={claims?.layout ?? 'default'}>
       <CustomFontLoader />
-      <FaxReceiver />
-      <MicTranscriptOverlay />
+      {shows('fax') && <FaxReceiver />}
+      {shows('transcript') && <MicTranscriptOverlay />}
+      {shows('effects') && <EffectsLayer />}
+      {shows('emote_rain') && <EmoteRain />}
+      {shows('polls') && <PollBars />}
+      {shows('hype_train') && <HypeTrainBar />}
+      {shows('pre_show') && <PreShowCountdown />}
       <Toaster position="top-right" richColors expand={true} duration={3000} />
 
       {/* 参加者ティッカー */}
-      <ParticipantTicker
-        participants={Array.isArray(participants) ? participants : []}
-        enabled={settings?.lottery_ticker_enabled || false}
-      />
-    </>
+      {shows('ticker') && (
+        <ParticipantTicker
+          participants={Array.isArray(participants) ? participants : []}
+          enabled={settings?.lottery_ticker_enabled || false}
+        />
+      )}
+    </div>
   );
 };
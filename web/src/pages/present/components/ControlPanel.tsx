@@ -1,6 +1,12 @@
 import React, { useState } from 'react';
 import { buildApiUrl } from '../../../utils/api';
 
+interface WinnerReceipt {
+  winner: string;
+  prize: string;
+  preview_url: string;
+}
+
 interface ControlPanelProps {
   participantCount: number;
 }
@@ -9,6 +15,9 @@ export const ControlPanel: React.FC<ControlPanelProps> = ({
   participantCount,
 }) => {
   const [isLoading, setIsLoading] = useState(false);
+  // 当選レシート（確認後に印刷）
+  const [receipt, setReceipt] = useState<WinnerReceipt | null>(null);
+  const [prize, setPrize] = useState('');
 
   const handleTestParticipants = async () => {
     setIsLoading(true);
@@ -44,6 +53,8 @@ export const ControlPanel: React.FC<ControlPanelProps> = ({
 
       const data = await response.json();
       console.log('Winner drawn:', data);
+      setReceipt(data.receipt ?? null);
+      setPrize(data.receipt?.prize ?? '');
     } catch (error) {
       console.error('Error drawing winner:', error);
       alert('抽選の実行に失敗しました');
@@ -52,6 +63,47 @@ export const ControlPanel: React.FC<ControlPanelProps> = ({
     }
   };
 
+  const handleUpdatePreview = async () => {
+    setIsLoading(true);
+    try {
+      const response = await fetch(buildApiUrl('/api/present/winner/preview'), {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ prize }),
+      });
+      if (!response.ok) {
+        throw new Error('Failed to update receipt preview');
+      }
+      const data = await response.json();
+      setReceipt(data.receipt);
+    } catch (error) {
+      console.error('Error updating receipt preview:', error);
+      alert('プレビューの更新に失敗しました');
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  const handlePrintReceipt = async () => {
+    setIsLoading(true);
+    try {
+      const response = await fetch(buildApiUrl('/api/present/winner/print'), {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ prize }),
+      });
+      if (!response.ok) {
+        throw new Error('Failed to print receipt');
+      }
+      setReceipt(null);
+    } catch (error) {
+      console.error('Error printing receipt:', error);
+      alert('レシートの印刷に失敗しました');
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
   return (
     <div className="bg-yellow-500/20 backdrop-blur-md rounded-2xl p-6 shadow-2xl border-2 border-yellow-400">
       <h2 className="text-2xl font-bold mb-4 flex items-center gap-2">
@@ -79,6 +131,40 @@ export const ControlPanel: React.FC<ControlPanelProps> = ({
         </button>
       </div>
 
+      {receipt && (
+        <div className="mt-4 flex flex-col gap-3">
+          <h3 className="font-semibold">当選レシート: {receipt.winner}</h3>
+          <img
+            src={buildApiUrl(receipt.preview_url)}
+            alt="当選レシートのプレビュー"
+            className="max-w-xs bg-white rounded"
+          />
+          <input
+            type="text"
+            value={prize}
+            onChange={(e) => setPrize(e.target.value)}
+            placeholder="景品"
+            className="px-3 py-2 rounded bg-black/30 border border-yellow-400"
+          />
+          <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+            <button
+              onClick={handleUpdatePreview}
+              disabled={isLoading}
+              className="px-6 py-3 bg-gray-600 hover:bg-gray-700 rounded-lg font-semibold transition-colors disabled:cursor-not-allowed"
+            >
+              プレビュー更新
+            </button>
+            <button
+              onClick={handlePrintReceipt}
+              disabled={isLoading}
+              className="px-6 py-3 bg-green-600 hover:bg-green-700 disabled:bg-gray-600 rounded-lg font-semibold transition-colors disabled:cursor-not-allowed"
+            >
+              レシート印刷
+            </button>
+          </div>
+        </div>
+      )}
+
       <div className="mt-4 text-sm text-yellow-200">
         <p>💡 テスト用の機能です。本番環境では使用しないでください。</p>
       </div>
@@ -8,6 +8,15 @@ interface WSMessage {
   data: any;
 }
 
+interface PendingCommand {
+  resolve: (result: any) => void;
+  reject: (error: Error) => void;
+  timer: ReturnType<typeof setTimeout>;
+}
+
+// command() の応答待ちを打ち切るまでの時間
+const COMMAND_TIMEOUT_MS = 10000;
+
 /**
  * 統合WebSocketクライアント
  * すべてのリアルタイム通信を1つの接続で管理
@@ -24,6 +33,8 @@ class WebSocketClient {
   private disconnectionHandlers: Set<ConnectionHandler> = new Set();
   private isIntentionallyClosed = false;
   private clientId: string;
+  private pendingCommands: Map<string, PendingCommand> = new Map();
+  private commandSeq = 0;
 
   constructor() {
     // クライアントIDを生成（タブごとに一意）
@@ -32,6 +43,27 @@ class WebSocketClient {
     // WebSocket URLを構築
     const wsUrl = buildApiUrl('/ws').replace(/^http/, 'ws');
     this.url = `${wsUrl}?clientId=${this.clientId}`;
+
+    // プレビューウィンドウ（?preview=1）ではシミュレーションイベントも受信
+    const params = new URLSearchParams(window.location.search);
+    if (params.get('preview') === '1') {
+      this.url += '&preview=1';
+    }
+
+    // 署名付きオーバーレイ URL（?token=）では許可されたトピックのみ受信
+    const token = params.get('token');
+    if (token) {
+      this.url += `&token=${encodeURIComponent(token)}`;
+    }
+  }
+
+  /**
+   * セッショントークンの更新（overlay_token）を再接続用 URL に反映
+   */
+  private updateToken(token: string): void {
+    const url = new URL(this.url);
+    url.searchParams.set('token', token);
+    this.url = url.toString();
   }
 
   /**
@@ -82,6 +114,13 @@ class WebSocketClient {
     this.ws.onmessage = (event: MessageEvent) => {
       try {
         const message: WSMessage = JSON.parse(event.data);
+        if (message.type === 'overlay_token' && message.data?.token) {
+          this.updateToken(message.data.token);
+        }
+        if (message.type === 'command_result') {
+          this.settleCommand(message.data);
+          return;
+        }
         // music_statusは頻繁なのでdebugレベル
         if (message.type === 'music_status') {
           console.debug('WebSocket message received:', message.type);
@@ -114,10 +153,19 @@ class WebSocketClient {
       
       // ハートビートを停止
       this.stopHeartbeat();
+
+      // 応答待ちのコマンドは結果が届かないので失敗させる
+      this.rejectPendingCommands('WebSocket disconnected');
       
       // 切断ハンドラーを呼び出し
       this.disconnectionHandlers.forEach(handler => handler());
       
+      // トークン失効（配信終了でセッション切れ）は再接続しても通らない
+      if (event.code === 1008) {
+        console.warn('Overlay token is no longer valid; not reconnecting');
+        this.isIntentionallyClosed = true;
+      }
+
       // 意図的な切断でなければ再接続
       if (!this.isIntentionallyClosed) {
         this.scheduleReconnect();
@@ -236,6 +284,53 @@ class WebSocketClient {
     this.ws.send(JSON.stringify(message));
   }
 
+  /**
+   * サーバーにコマンドを送り、結果（command_result）を待つ
+   * REST API と同じ処理が実行される。許可されていないコマンドは reject される
+   */
+  command(name: string, args: Record<string, unknown> = {}): Promise<any> {
+    if (this.ws?.readyState !== WebSocket.OPEN) {
+      return Promise.reject(new Error('WebSocket not connected'));
+    }
+
+    const id = `${this.clientId}-${++this.commandSeq}`;
+    return new Promise((resolve, reject) => {
+      const timer = setTimeout(() => {
+        this.pendingCommands.delete(id);
+        reject(new Error(`Command timed out: ${name}`));
+      }, COMMAND_TIMEOUT_MS);
+      this.pendingCommands.set(id, { resolve, reject, timer });
+      this.send('command', { id, command: name, args });
+    });
+  }
+
+  /**
+   * command_result を対応するコマンドに返す
+   */
+  private settleCommand(data: { id?: string; ok?: boolean; result?: any; error?: string }): void {
+    const pending = data?.id ? this.pendingCommands.get(data.id) : undefined;
+    if (!pending) return;
+
+    clearTimeout(pending.timer);
+    this.pendingCommands.delete(data.id!);
+    if (data.ok) {
+      pending.resolve(data.result);
+    } else {
+      pending.reject(new Error(data.error || 'Command failed'));
+    }
+  }
+
+  /**
+   * 応答待ちのコマンドをすべて失敗させる
+   */
+  private rejectPendingCommands(reason: string): void {
+    this.pendingCommands.forEach(({ reject, timer }) => {
+      clearTimeout(timer);
+      reject(new Error(reason));
+    });
+    this.pendingCommands.clear();
+  }
+
   /**
    * 接続を切断
    */